@@ -135,7 +135,7 @@ impl messages::ThisChainWithMessages for Millau {
 		}
 	}
 
-	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
+	fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
 		MessageNonce::MAX
 	}
 
@@ -152,6 +152,7 @@ impl messages::ThisChainWithMessages for Millau {
 			size: inbound_data_size
 				.saturating_add(bp_rialto::EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(bp_millau::TX_EXTRA_BYTES),
+			proof_size: inbound_data_size.saturating_add(bp_rialto::EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
@@ -166,6 +167,7 @@ impl messages::ThisChainWithMessages for Millau {
 			1,
 			multiplier,
 			|weight| weight as _,
+			|proof_size| proof_size as _,
 			transaction,
 		)
 	}
@@ -226,6 +228,7 @@ impl messages::BridgedChainWithMessages for Rialto {
 			size: message_payload_len
 				.saturating_add(bp_millau::EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(bp_rialto::TX_EXTRA_BYTES),
+			proof_size: message_payload_len.saturating_add(bp_millau::EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
@@ -239,6 +242,7 @@ impl messages::BridgedChainWithMessages for Rialto {
 			1,
 			multiplier,
 			|weight| weight as _,
+			|proof_size| proof_size as _,
 			transaction,
 		)
 	}
@@ -261,8 +265,7 @@ impl TargetHeaderChain<ToRialtoMessagePayload, bp_rialto::AccountId> for Rialto
 	) -> Result<(LaneId, InboundLaneData<bp_millau::AccountId>), Self::Error> {
 		messages::source::verify_messages_delivery_proof::<
 			WithRialtoMessageBridge,
-			Runtime,
-			crate::RialtoGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::RialtoGrandpaInstance>,
 		>(proof)
 	}
 }
@@ -282,8 +285,7 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 	) -> Result<ProvedMessages<Message<bp_rialto::Balance>>, Self::Error> {
 		messages::target::verify_messages_proof::<
 			WithRialtoMessageBridge,
-			Runtime,
-			crate::RialtoGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::RialtoGrandpaInstance>,
 		>(proof, messages_count)
 	}
 }
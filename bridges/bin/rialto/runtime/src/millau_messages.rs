@@ -123,7 +123,7 @@ impl messages::ThisChainWithMessages for Rialto {
 		send_origin.linked_account().is_some() && (*lane == [0, 0, 0, 0] || *lane == [0, 0, 0, 1])
 	}
 
-	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
+	fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
 		MessageNonce::MAX
 	}
 
@@ -140,6 +140,7 @@ impl messages::ThisChainWithMessages for Rialto {
 			size: inbound_data_size
 				.saturating_add(bp_millau::EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(bp_rialto::TX_EXTRA_BYTES),
+			proof_size: inbound_data_size.saturating_add(bp_millau::EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
@@ -154,6 +155,7 @@ impl messages::ThisChainWithMessages for Rialto {
 			1,
 			multiplier,
 			|weight| weight as _,
+			|proof_size| proof_size as _,
 			transaction,
 		)
 	}
@@ -214,6 +216,7 @@ impl messages::BridgedChainWithMessages for Millau {
 			size: message_payload_len
 				.saturating_add(bp_rialto::EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(bp_millau::TX_EXTRA_BYTES),
+			proof_size: message_payload_len.saturating_add(bp_rialto::EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
@@ -227,6 +230,7 @@ impl messages::BridgedChainWithMessages for Millau {
 			1,
 			multiplier,
 			|weight| weight as _,
+			|proof_size| proof_size as _,
 			transaction,
 		)
 	}
@@ -249,8 +253,7 @@ impl TargetHeaderChain<ToMillauMessagePayload, bp_millau::AccountId> for Millau
 	) -> Result<(LaneId, InboundLaneData<bp_rialto::AccountId>), Self::Error> {
 		messages::source::verify_messages_delivery_proof::<
 			WithMillauMessageBridge,
-			Runtime,
-			crate::MillauGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::MillauGrandpaInstance>,
 		>(proof)
 	}
 }
@@ -270,8 +273,7 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 	) -> Result<ProvedMessages<Message<bp_millau::Balance>>, Self::Error> {
 		messages::target::verify_messages_proof::<
 			WithMillauMessageBridge,
-			Runtime,
-			crate::MillauGrandpaInstance,
+			pallet_bridge_grandpa::Pallet<Runtime, crate::MillauGrandpaInstance>,
 		>(proof, messages_count)
 	}
 }
@@ -380,6 +382,7 @@ mod tests {
 							weight: dispatch_weight,
 							origin: CallOrigin::SourceRoot,
 							dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+							expires_at: None,
 							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
 						}),
 						fee: 1,
@@ -547,6 +550,7 @@ mod tests {
 							weight: dispatch_weight,
 							origin: CallOrigin::SourceRoot,
 							dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+							expires_at: None,
 							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
 						}),
 						fee: 1,
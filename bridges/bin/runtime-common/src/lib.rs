@@ -21,6 +21,8 @@
 pub mod messages;
 pub mod messages_api;
 pub mod messages_benchmarking;
+pub mod messages_fuzzing;
+pub mod mock_bridged_chain;
 
 #[cfg(feature = "integrity-test")]
 pub mod integrity;
@@ -20,6 +20,7 @@
 //! pallet is used to dispatch incoming messages. Message identified by a tuple
 //! of to elements - message lane id and message nonce.
 
+use bp_header_chain::FinalityBackend;
 use bp_message_dispatch::MessageDispatch as _;
 use bp_messages::{
 	source_chain::LaneMessageVerifier,
@@ -27,8 +28,9 @@ use bp_messages::{
 	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
 };
 use bp_runtime::{
+	decode_compact_storage_proof,
 	messages::{DispatchFeePayment, MessageDispatchResult},
-	ChainId, Size, StorageProofChecker,
+	ChainId, HasherOf, Size, StorageProofChecker,
 };
 use codec::{Decode, DecodeLimit, Encode};
 use frame_support::{
@@ -69,6 +71,30 @@ pub trait MessageBridge {
 		bridged_balance: BalanceOf<BridgedChain<Self>>,
 		bridged_to_this_conversion_rate_override: Option<FixedU128>,
 	) -> BalanceOf<ThisChain<Self>>;
+
+	/// Relayer interest (in percents) for messages sent over the given lane.
+	///
+	/// Bridges may override it to let governance tune the interest. Defaults to
+	/// `RELAYER_FEE_PERCENT`.
+	fn relayer_fee_percent(_lane: &LaneId) -> u32 {
+		Self::RELAYER_FEE_PERCENT
+	}
+
+	/// The lowest fee that relayers are bidding to deliver messages over the given lane.
+	///
+	/// If there's a bid, it is used as the minimal message fee instead of the estimated one.
+	/// Defaults to `None`.
+	fn lowest_relayer_bid(_lane: &LaneId) -> Option<BalanceOf<ThisChain<Self>>> {
+		None
+	}
+
+	/// Returns true if the delivery of messages sent over the given lane is subsidized.
+	///
+	/// Messages of subsidized lanes may be sent without a fee, because relayers are rewarded by
+	/// other means. Defaults to `false`.
+	fn is_lane_subsidized(_lane: &LaneId) -> bool {
+		false
+	}
 }
 
 /// Chain that has `pallet-bridge-messages` and `dispatch` modules.
@@ -104,6 +130,11 @@ pub struct MessageTransaction<Weight> {
 	pub dispatch_weight: Weight,
 	/// The estimated size of the encoded transaction.
 	pub size: u32,
+	/// The estimated size of the storage proof that is verified by the transaction.
+	///
+	/// The proof is a part of the encoded transaction, but it also contributes to the proof of
+	/// validity (PoV) of the block, which is metered separately from the dispatch weight.
+	pub proof_size: u32,
 }
 
 /// This chain that has `pallet-bridge-messages` and `dispatch` modules.
@@ -116,10 +147,10 @@ pub trait ThisChainWithMessages: ChainWithMessages {
 	/// Do we accept message sent by given origin to given lane?
 	fn is_message_accepted(origin: &Self::Origin, lane: &LaneId) -> bool;
 
-	/// Maximal number of pending (not yet delivered) messages at This chain.
+	/// Maximal number of pending (not yet delivered) messages at the outbound lane of This chain.
 	///
 	/// Any messages over this limit, will be rejected.
-	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce;
+	fn maximal_pending_messages_at_outbound_lane(lane: &LaneId) -> MessageNonce;
 
 	/// Estimate size and weight of single message delivery confirmation transaction at This chain.
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>>;
@@ -177,9 +208,135 @@ pub type OriginOf<C> = <C as ThisChainWithMessages>::Origin;
 /// Type of call that is used on this chain.
 pub type CallOf<C> = <C as ThisChainWithMessages>::Call;
 
+/// Macro that declares the message bridge between this and bridged chains, along with the
+/// payload and verifier types of the messages that are sent over it.
+///
+/// The conversion rate, relayer fee percent, lowest relayer bid and subsidized lanes of the bridge
+/// are read using given functions, so that the runtime may store them wherever it wants. Both
+/// chains are expected to use the same balance type.
+///
+/// ```ignore
+/// impl_message_bridge! {
+/// 	/// Message bridge that is "deployed" at Kusama chain and connecting it to Polkadot chain.
+/// 	#[derive(RuntimeDebug, Clone, Copy)]
+/// 	pub struct AtKusamaWithPolkadotMessageBridge {
+/// 		this_chain_id: KUSAMA_CHAIN_ID,
+/// 		bridged_chain_id: POLKADOT_CHAIN_ID,
+/// 		bridged_messages_pallet_name: bp_kusama::WITH_KUSAMA_MESSAGES_PALLET_NAME,
+/// 		this_chain: Kusama,
+/// 		bridged_chain: Polkadot,
+/// 		relayer_fee_percent: 10,
+/// 		bridged_to_this_conversion_rate: BridgeConfig::bridged_to_this_conversion_rate,
+/// 		lane_relayer_fee_percent: BridgeConfig::relayer_fee_percent,
+/// 		lowest_relayer_bid: |lane: &LaneId| BridgeRelayers::lowest_bid(*lane),
+/// 		is_lane_subsidized: BridgeConfig::is_lane_subsidized,
+/// 	}
+///
+/// 	/// Message payload for Kusama -> Polkadot messages as it is seen at Kusama.
+/// 	pub type ToPolkadotMessagePayload = outbound_payload;
+/// 	/// Message verifier for Kusama -> Polkadot messages at Kusama.
+/// 	pub type ToPolkadotMessageVerifier = message_verifier;
+/// 	/// Message payload for Polkadot -> Kusama messages as it is seen at Kusama.
+/// 	pub type FromPolkadotMessagePayload = inbound_payload;
+/// }
+/// ```
+#[macro_export]
+macro_rules! impl_message_bridge(
+	(
+		$(#[$bridge_attr:meta])*
+		$bridge_vis:vis struct $bridge:ident {
+			this_chain_id: $this_chain_id:expr,
+			bridged_chain_id: $bridged_chain_id:expr,
+			bridged_messages_pallet_name: $bridged_messages_pallet_name:expr,
+			this_chain: $this_chain:ty,
+			bridged_chain: $bridged_chain:ty,
+			relayer_fee_percent: $relayer_fee_percent:expr,
+			bridged_to_this_conversion_rate: $bridged_to_this_conversion_rate:expr,
+			lane_relayer_fee_percent: $lane_relayer_fee_percent:expr,
+			lowest_relayer_bid: $lowest_relayer_bid:expr,
+			is_lane_subsidized: $is_lane_subsidized:expr $(,)?
+		}
+
+		$(#[$outbound_payload_attr:meta])*
+		$outbound_payload_vis:vis type $outbound_payload:ident = outbound_payload;
+		$(#[$message_verifier_attr:meta])*
+		$message_verifier_vis:vis type $message_verifier:ident = message_verifier;
+		$(#[$inbound_payload_attr:meta])*
+		$inbound_payload_vis:vis type $inbound_payload:ident = inbound_payload;
+	) => {
+		$(#[$bridge_attr])*
+		$bridge_vis struct $bridge;
+
+		impl $crate::messages::MessageBridge for $bridge {
+			const THIS_CHAIN_ID: bp_runtime::ChainId = $this_chain_id;
+			const BRIDGED_CHAIN_ID: bp_runtime::ChainId = $bridged_chain_id;
+			const RELAYER_FEE_PERCENT: u32 = $relayer_fee_percent;
+			const BRIDGED_MESSAGES_PALLET_NAME: &'static str = $bridged_messages_pallet_name;
+
+			type ThisChain = $this_chain;
+			type BridgedChain = $bridged_chain;
+
+			fn bridged_balance_to_this_balance(
+				bridged_balance: $crate::messages::BalanceOf<$crate::messages::BridgedChain<Self>>,
+				bridged_to_this_conversion_rate_override: Option<sp_runtime::FixedU128>,
+			) -> $crate::messages::BalanceOf<$crate::messages::ThisChain<Self>> {
+				sp_runtime::FixedPointNumber::saturating_mul_int(
+					bridged_to_this_conversion_rate_override
+						.unwrap_or_else($bridged_to_this_conversion_rate),
+					bridged_balance,
+				)
+			}
+
+			fn relayer_fee_percent(lane: &bp_messages::LaneId) -> u32 {
+				($lane_relayer_fee_percent)(lane).unwrap_or(Self::RELAYER_FEE_PERCENT)
+			}
+
+			fn lowest_relayer_bid(
+				lane: &bp_messages::LaneId,
+			) -> Option<$crate::messages::BalanceOf<$crate::messages::ThisChain<Self>>> {
+				($lowest_relayer_bid)(lane)
+			}
+
+			fn is_lane_subsidized(lane: &bp_messages::LaneId) -> bool {
+				($is_lane_subsidized)(lane)
+			}
+		}
+
+		$(#[$outbound_payload_attr])*
+		$outbound_payload_vis type $outbound_payload =
+			$crate::messages::source::FromThisChainMessagePayload<$bridge>;
+		$(#[$message_verifier_attr])*
+		$message_verifier_vis type $message_verifier =
+			$crate::messages::source::FromThisChainMessageVerifier<$bridge>;
+		$(#[$inbound_payload_attr])*
+		$inbound_payload_vis type $inbound_payload =
+			$crate::messages::target::FromBridgedChainMessagePayload<$bridge>;
+	}
+);
+
 /// Raw storage proof type (just raw trie nodes).
 pub type RawStorageProof = Vec<Vec<u8>>;
 
+/// Version (encoding) of the raw storage proof.
+///
+/// It is encoded as a single byte, so new versions may be added without breaking existing ones.
+#[derive(Clone, Copy, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub enum StorageProofVersion {
+	/// Trie nodes, as they're returned by the `state_getReadProof` RPC.
+	Plain,
+	/// Compact trie nodes (`sp_trie::CompactProof`), encoded by the
+	/// `bp_runtime::compact_storage_proof`. Nodes of the compact proof are deduplicated and hashes
+	/// of child nodes that are included in the proof are omitted, so it is significantly smaller
+	/// if the proof includes multiple messages.
+	Compact,
+}
+
+impl Default for StorageProofVersion {
+	fn default() -> Self {
+		StorageProofVersion::Plain
+	}
+}
+
 /// Compute fee of transaction at runtime where regular transaction payment pallet is being used.
 ///
 /// The value of `multiplier` parameter is the expected value of
@@ -187,11 +344,16 @@ pub type RawStorageProof = Vec<Vec<u8>>;
 /// you're charging this payment in advance (and that's what happens with delivery and confirmation
 /// transaction in this crate), then there's a chance that the actual fee will be larger than what
 /// is paid in advance. So the value must be chosen carefully.
+///
+/// The `proof_size_to_fee` converts the proof size of the transaction to fee, the same way the
+/// `weight_to_fee` converts the dispatch weight. Both parts of the fee are adjusted by the
+/// `multiplier`.
 pub fn transaction_payment<Balance: AtLeast32BitUnsigned + FixedPointOperand>(
 	base_extrinsic_weight: Weight,
 	per_byte_fee: Balance,
 	multiplier: FixedU128,
 	weight_to_fee: impl Fn(Weight) -> Balance,
+	proof_size_to_fee: impl Fn(u32) -> Balance,
 	transaction: MessageTransaction<Weight>,
 ) -> Balance {
 	// base fee is charged for every tx
@@ -201,7 +363,8 @@ pub fn transaction_payment<Balance: AtLeast32BitUnsigned + FixedPointOperand>(
 	let len_fee = per_byte_fee.saturating_mul(Balance::from(transaction.size));
 
 	// the adjustable part of the fee
-	let unadjusted_weight_fee = weight_to_fee(transaction.dispatch_weight);
+	let unadjusted_weight_fee = weight_to_fee(transaction.dispatch_weight)
+		.saturating_add(proof_size_to_fee(transaction.proof_size));
 	let adjusted_weight_fee = multiplier.saturating_mul_int(unadjusted_weight_fee);
 
 	base_fee.saturating_add(len_fee).saturating_add(adjusted_weight_fee)
@@ -266,7 +429,8 @@ pub mod source {
 	///   lane;
 	/// - check that the sender has rights to dispatch the call on target chain using provided
 	///   dispatch origin;
-	/// - check that the sender has paid enough funds for both message delivery and dispatch.
+	/// - check that the sender has paid enough funds for both message delivery and dispatch,
+	///   unless the lane is subsidized.
 	#[derive(RuntimeDebug)]
 	pub struct FromThisChainMessageVerifier<B>(PhantomData<B>);
 
@@ -310,7 +474,8 @@ pub mod source {
 			}
 
 			// reject message if there are too many pending messages at this lane
-			let max_pending_messages = ThisChain::<B>::maximal_pending_messages_at_outbound_lane();
+			let max_pending_messages =
+				ThisChain::<B>::maximal_pending_messages_at_outbound_lane(lane);
 			let pending_messages = lane_outbound_data
 				.latest_generated_nonce
 				.saturating_sub(lane_outbound_data.latest_received_nonce);
@@ -338,11 +503,12 @@ pub mod source {
 				},
 			};
 
-			let minimal_fee_in_this_tokens = estimate_message_dispatch_and_delivery_fee::<B>(
-				payload,
-				B::RELAYER_FEE_PERCENT,
-				None,
-			)?;
+			// the delivery of messages of subsidized lanes is paid by the bridge
+			if B::is_lane_subsidized(lane) {
+				return Ok(())
+			}
+
+			let minimal_fee_in_this_tokens = minimal_message_fee::<B>(lane, payload, None)?;
 
 			// compare with actual fee paid
 			if *delivery_and_dispatch_fee < minimal_fee_in_this_tokens {
@@ -388,6 +554,25 @@ pub mod source {
 		Ok(())
 	}
 
+	/// Return the minimal fee that must be paid for delivering a message over the lane.
+	///
+	/// It is the lowest relayer bid for the lane, if there's one. Otherwise the fee is estimated
+	/// with [`estimate_message_dispatch_and_delivery_fee`].
+	pub fn minimal_message_fee<B: MessageBridge>(
+		lane: &LaneId,
+		payload: &FromThisChainMessagePayload<B>,
+		bridged_to_this_conversion_rate: Option<FixedU128>,
+	) -> Result<BalanceOf<ThisChain<B>>, &'static str> {
+		match B::lowest_relayer_bid(lane) {
+			Some(bid) => Ok(bid),
+			None => estimate_message_dispatch_and_delivery_fee::<B>(
+				payload,
+				B::relayer_fee_percent(lane),
+				bridged_to_this_conversion_rate,
+			),
+		}
+	}
+
 	/// Estimate delivery and dispatch fee that must be paid for delivering a message to the Bridged
 	/// chain.
 	///
@@ -437,20 +622,18 @@ pub mod source {
 	}
 
 	/// Verify proof of This -> Bridged chain messages delivery.
-	pub fn verify_messages_delivery_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
+	///
+	/// The proof is verified using finalized headers of the given finality backend (e.g. the
+	/// GRANDPA pallet).
+	pub fn verify_messages_delivery_proof<B: MessageBridge, Finality: FinalityBackend>(
 		proof: FromBridgedChainMessagesDeliveryProof<HashOf<BridgedChain<B>>>,
 	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str>
 	where
-		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
-		HashOf<BridgedChain<B>>: Into<
-			bp_runtime::HashOf<
-				<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain,
-			>,
-		>,
+		HashOf<BridgedChain<B>>: Into<bp_runtime::HashOf<Finality::Chain>>,
 	{
 		let FromBridgedChainMessagesDeliveryProof { bridged_header_hash, storage_proof, lane } =
 			proof;
-		pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
+		Finality::parse_finalized_storage_proof(
 			bridged_header_hash.into(),
 			StorageProof::new(storage_proof),
 			|storage| {
@@ -495,6 +678,7 @@ pub mod target {
 	///
 	/// - hash of finalized header;
 	/// - storage proof of messages and (optionally) outbound lane state;
+	/// - version of the storage proof;
 	/// - lane id;
 	/// - nonces (inclusive range) of messages which are included in this proof.
 	#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
@@ -503,6 +687,8 @@ pub mod target {
 		pub bridged_header_hash: BridgedHeaderHash,
 		/// A storage trie proof of messages being delivered.
 		pub storage_proof: RawStorageProof,
+		/// Version of the [`Self::storage_proof`].
+		pub storage_proof_version: StorageProofVersion,
 		pub lane: LaneId,
 		/// Nonce of the first message being delivered.
 		pub nonces_start: MessageNonce,
@@ -634,25 +820,27 @@ pub mod target {
 	/// The `messages_count` argument verification (sane limits) is supposed to be made
 	/// outside of this function. This function only verifies that the proof declares exactly
 	/// `messages_count` messages.
-	pub fn verify_messages_proof<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
+	///
+	/// The proof is verified using finalized headers of the given finality backend (e.g. the
+	/// GRANDPA pallet).
+	pub fn verify_messages_proof<B: MessageBridge, Finality: FinalityBackend>(
 		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
 		messages_count: u32,
 	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
 	where
-		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
-		HashOf<BridgedChain<B>>: Into<
-			bp_runtime::HashOf<
-				<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain,
-			>,
-		>,
+		HashOf<BridgedChain<B>>: Into<bp_runtime::HashOf<Finality::Chain>>,
 	{
 		verify_messages_proof_with_parser::<B, _, _>(
 			proof,
 			messages_count,
-			|bridged_header_hash, bridged_storage_proof| {
-				pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
+			|bridged_header_hash, bridged_storage_proof_version, bridged_storage_proof| {
+				let bridged_storage_proof = decode_storage_proof::<HasherOf<Finality::Chain>>(
+					bridged_storage_proof_version,
+					bridged_storage_proof,
+				)?;
+				Finality::parse_finalized_storage_proof(
 					bridged_header_hash.into(),
-					StorageProof::new(bridged_storage_proof),
+					bridged_storage_proof,
 					|storage_adapter| storage_adapter,
 				)
 				.map(|storage| StorageProofCheckerAdapter::<_, B> {
@@ -665,9 +853,22 @@ pub mod target {
 		.map_err(Into::into)
 	}
 
+	/// Decode raw storage proof of given version.
+	pub(crate) fn decode_storage_proof<H: Hasher>(
+		version: StorageProofVersion,
+		storage_proof: RawStorageProof,
+	) -> Result<StorageProof, MessageProofError> {
+		match version {
+			StorageProofVersion::Plain => Ok(StorageProof::new(storage_proof)),
+			StorageProofVersion::Compact => decode_compact_storage_proof::<H>(storage_proof)
+				.map_err(|_| MessageProofError::InvalidCompactProof),
+		}
+	}
+
 	#[derive(Debug, PartialEq)]
 	pub(crate) enum MessageProofError {
 		Empty,
+		InvalidCompactProof,
 		MessagesCountMismatch,
 		MissingRequiredMessage,
 		FailedToDecodeMessage,
@@ -679,6 +880,7 @@ pub mod target {
 		fn from(err: MessageProofError) -> &'static str {
 			match err {
 				MessageProofError::Empty => "Messages proof is empty",
+				MessageProofError::InvalidCompactProof => "Failed to decode compact storage proof",
 				MessageProofError::MessagesCountMismatch =>
 					"Declared messages count doesn't match actual value",
 				MessageProofError::MissingRequiredMessage => "Message is missing from the proof",
@@ -731,13 +933,17 @@ pub mod target {
 		build_parser: BuildParser,
 	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, MessageProofError>
 	where
-		BuildParser:
-			FnOnce(HashOf<BridgedChain<B>>, RawStorageProof) -> Result<Parser, MessageProofError>,
+		BuildParser: FnOnce(
+			HashOf<BridgedChain<B>>,
+			StorageProofVersion,
+			RawStorageProof,
+		) -> Result<Parser, MessageProofError>,
 		Parser: MessageProofParser,
 	{
 		let FromBridgedChainMessagesProof {
 			bridged_header_hash,
 			storage_proof,
+			storage_proof_version,
 			lane,
 			nonces_start,
 			nonces_end,
@@ -758,7 +964,7 @@ pub mod target {
 				0
 			};
 
-		let parser = build_parser(bridged_header_hash, storage_proof)?;
+		let parser = build_parser(bridged_header_hash, storage_proof_version, storage_proof)?;
 
 		// Read messages first. All messages that are claimed to be in the proof must
 		// be in the proof. So any error in `read_value`, or even missing value is fatal.
@@ -992,7 +1198,7 @@ mod tests {
 			lane == TEST_LANE_ID
 		}
 
-		fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
+		fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
 			MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE
 		}
 
@@ -1000,6 +1206,7 @@ mod tests {
 			MessageTransaction {
 				dispatch_weight: DELIVERY_CONFIRMATION_TRANSACTION_WEIGHT,
 				size: 0,
+				proof_size: 0,
 			}
 		}
 
@@ -1053,7 +1260,7 @@ mod tests {
 			unreachable!()
 		}
 
-		fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
+		fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
 			unreachable!()
 		}
 
@@ -1087,6 +1294,7 @@ mod tests {
 			MessageTransaction {
 				dispatch_weight: DELIVERY_TRANSACTION_WEIGHT + message_dispatch_weight,
 				size: 0,
+				proof_size: 0,
 			}
 		}
 
@@ -1110,6 +1318,7 @@ mod tests {
 				weight: 100,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				expires_at: None,
 				call: ThisChainCall::Transfer.encode(),
 			}
 			.encode();
@@ -1127,6 +1336,7 @@ mod tests {
 				weight: 100,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				expires_at: None,
 				call: target::FromBridgedChainEncodedMessageCall::<ThisChainCall>::new(
 					ThisChainCall::Transfer.encode(),
 				),
@@ -1145,6 +1355,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceRoot,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
 			call: vec![42],
 		}
 	}
@@ -1214,6 +1425,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceRoot,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
 			call: vec![42],
 		};
 
@@ -1256,6 +1468,7 @@ mod tests {
 			weight: 100,
 			origin: bp_message_dispatch::CallOrigin::SourceAccount(ThisChainAccountId(1)),
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
 			call: vec![42],
 		};
 
@@ -1320,6 +1533,7 @@ mod tests {
 				weight: 5,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
 				call: vec![1, 2, 3, 4, 5, 6],
 			},
 		)
@@ -1334,6 +1548,7 @@ mod tests {
 				weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT + 1,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
 				call: vec![1, 2, 3, 4, 5, 6],
 			},
 		)
@@ -1348,6 +1563,7 @@ mod tests {
 				weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
 				call: vec![0; source::maximal_message_size::<OnThisChainBridge>() as usize + 1],
 			},
 		)
@@ -1363,6 +1579,7 @@ mod tests {
 					weight: BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT,
 					origin: bp_message_dispatch::CallOrigin::SourceRoot,
 					dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+					expires_at: None,
 					call: vec![0; source::maximal_message_size::<OnThisChainBridge>() as _],
 				},
 			),
@@ -1412,6 +1629,7 @@ mod tests {
 		target::FromBridgedChainMessagesProof {
 			bridged_header_hash: (),
 			storage_proof: vec![],
+			storage_proof_version: StorageProofVersion::Plain,
 			lane: Default::default(),
 			nonces_start: 1,
 			nonces_end,
@@ -1424,7 +1642,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
 				messages_proof(10),
 				5,
-				|_, _| unreachable!(),
+				|_, _, _| unreachable!(),
 			),
 			Err(target::MessageProofError::MessagesCountMismatch),
 		);
@@ -1436,7 +1654,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
 				messages_proof(10),
 				15,
-				|_, _| unreachable!(),
+				|_, _, _| unreachable!(),
 			),
 			Err(target::MessageProofError::MessagesCountMismatch),
 		);
@@ -1448,7 +1666,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
 				messages_proof(10),
 				10,
-				|_, _| Err(target::MessageProofError::Custom("test")),
+				|_, _, _| Err(target::MessageProofError::Custom("test")),
 			),
 			Err(target::MessageProofError::Custom("test")),
 		);
@@ -1460,7 +1678,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(10),
 				10,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: 1..=5,
 					outbound_lane_data: None,
@@ -1476,7 +1694,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(10),
 				10,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: true,
 					messages: 1..=10,
 					outbound_lane_data: None,
@@ -1492,7 +1710,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(0),
 				0,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: true,
 					messages: no_messages_range(),
 					outbound_lane_data: Some(OutboundLaneData {
@@ -1512,7 +1730,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(0),
 				0,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: no_messages_range(),
 					outbound_lane_data: None,
@@ -1528,7 +1746,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(0),
 				0,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: no_messages_range(),
 					outbound_lane_data: Some(OutboundLaneData {
@@ -1560,7 +1778,7 @@ mod tests {
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(1),
 				1,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: 1..=1,
 					outbound_lane_data: Some(OutboundLaneData {
@@ -1580,7 +1798,10 @@ mod tests {
 					}),
 					messages: vec![Message {
 						key: MessageKey { lane_id: Default::default(), nonce: 1 },
-						data: MessageData { payload: 1u64.encode(), fee: BridgedChainBalance(0) },
+						data: MessageData {
+							payload: 1u64.encode(),
+							fee: BridgedChainBalance(0),
+						},
 					}],
 				},
 			)]
@@ -1589,13 +1810,24 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn message_proof_is_rejected_if_compact_storage_proof_is_invalid() {
+		assert_eq!(
+			target::decode_storage_proof::<sp_core::Blake2Hasher>(
+				StorageProofVersion::Compact,
+				vec![vec![42; 32]],
+			),
+			Err(target::MessageProofError::InvalidCompactProof),
+		);
+	}
+
 	#[test]
 	fn verify_messages_proof_with_parser_does_not_panic_if_messages_count_mismatches() {
 		assert_eq!(
 			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
 				messages_proof(u64::MAX),
 				0,
-				|_, _| Ok(TestMessageProofParser {
+				|_, _, _| Ok(TestMessageProofParser {
 					failing: false,
 					messages: 0..=u64::MAX,
 					outbound_lane_data: Some(OutboundLaneData {
@@ -1619,7 +1851,8 @@ mod tests {
 				10,
 				FixedU128::zero(),
 				|weight| weight,
-				MessageTransaction { size: 50, dispatch_weight: 777 },
+				|proof_size| proof_size as _,
+				MessageTransaction { size: 50, dispatch_weight: 777, proof_size: 20 },
 			),
 			100 + 50 * 10,
 		);
@@ -1635,12 +1868,54 @@ mod tests {
 				10,
 				FixedU128::one(),
 				|weight| weight,
-				MessageTransaction { size: 50, dispatch_weight: 777 },
+				|proof_size| proof_size as _,
+				MessageTransaction { size: 50, dispatch_weight: 777, proof_size: 20 },
 			),
-			100 + 50 * 10 + 777,
+			100 + 50 * 10 + 777 + 20,
 		);
 	}
 
+	proptest::proptest! {
+		#[test]
+		fn transaction_payment_is_monotonic_and_never_overflows(
+			base_extrinsic_weight in proptest::prelude::any::<Weight>(),
+			per_byte_fee in proptest::prelude::any::<u128>(),
+			size in proptest::prelude::any::<u32>(),
+			dispatch_weight in proptest::prelude::any::<Weight>(),
+			proof_size in proptest::prelude::any::<u32>(),
+			multiplier in proptest::prelude::any::<u128>(),
+			extra_size in proptest::prelude::any::<u32>(),
+			extra_dispatch_weight in proptest::prelude::any::<Weight>(),
+			extra_multiplier in proptest::prelude::any::<u128>(),
+		) {
+			let payment = |multiplier, size, dispatch_weight| {
+				transaction_payment(
+					base_extrinsic_weight,
+					per_byte_fee,
+					FixedU128::from_inner(multiplier),
+					|weight| weight as u128 * 1_000_000_000,
+					|proof_size| proof_size as u128 * 1_000_000_000,
+					MessageTransaction { size, dispatch_weight, proof_size },
+				)
+			};
+
+			let fee = payment(multiplier, size, dispatch_weight);
+			proptest::prop_assert!(
+				fee <= payment(multiplier, size.saturating_add(extra_size), dispatch_weight)
+			);
+			proptest::prop_assert!(
+				fee <= payment(
+					multiplier,
+					size,
+					dispatch_weight.saturating_add(extra_dispatch_weight),
+				)
+			);
+			proptest::prop_assert!(
+				fee <= payment(multiplier.saturating_add(extra_multiplier), size, dispatch_weight)
+			);
+		}
+	}
+
 	#[test]
 	fn conversion_rate_override_works() {
 		let payload = regular_outbound_message_payload();
@@ -18,11 +18,14 @@
 
 use crate::messages::{source::FromThisChainMessagePayload, MessageBridge};
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce};
+use bp_messages::{InboundLaneData, LaneId, MessageDetails, MessageNonce};
 use codec::Decode;
 use sp_std::vec::Vec;
 
 /// Implementation of the `To*OutboundLaneApi::message_details`.
+///
+/// Only undelivered messages are returned, i.e. the `begin..=end` range is clamped to the
+/// messages that have been generated, but not yet received by the bridged chain.
 pub fn outbound_message_details<Runtime, MessagesPalletInstance, BridgeConfig>(
 	lane: LaneId,
 	begin: MessageNonce,
@@ -33,6 +36,11 @@ where
 	MessagesPalletInstance: 'static,
 	BridgeConfig: MessageBridge,
 {
+	let lane_data =
+		pallet_bridge_messages::OutboundLanes::<Runtime, MessagesPalletInstance>::get(&lane);
+	let begin = sp_std::cmp::max(begin, lane_data.latest_received_nonce.saturating_add(1));
+	let end = sp_std::cmp::min(end, lane_data.latest_generated_nonce);
+
 	(begin..=end)
 		.filter_map(|nonce| {
 			let message_data =
@@ -49,3 +57,14 @@ where
 		})
 		.collect()
 }
+
+/// Implementation of the `From*InboundLaneApi::inbound_lane_data`.
+pub fn inbound_lane_data<Runtime, MessagesPalletInstance>(
+	lane: LaneId,
+) -> InboundLaneData<Runtime::InboundRelayer>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: 'static,
+{
+	pallet_bridge_messages::InboundLanes::<Runtime, MessagesPalletInstance>::get(&lane)
+}
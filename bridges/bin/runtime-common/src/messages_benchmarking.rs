@@ -23,7 +23,7 @@ use crate::messages::{
 	source::{FromBridgedChainMessagesDeliveryProof, FromThisChainMessagePayload},
 	target::FromBridgedChainMessagesProof,
 	AccountIdOf, BalanceOf, BridgedChain, CallOf, HashOf, MessageBridge, RawStorageProof,
-	SignatureOf, SignerOf, ThisChain,
+	SignatureOf, SignerOf, StorageProofVersion, ThisChain,
 };
 
 use bp_messages::{storage_keys, MessageData, MessageKey, MessagePayload};
@@ -157,6 +157,7 @@ where
 			SignatureOf<ThisChain<B>>,
 		>::TargetAccount(bridged_account_id, this_public, this_signature),
 		dispatch_fee_payment: params.dispatch_fee_payment.clone(),
+		expires_at: None,
 		call: call.encode(),
 	}
 	.encode();
@@ -170,6 +171,7 @@ where
 		FromBridgedChainMessagesProof {
 			bridged_header_hash,
 			storage_proof,
+			storage_proof_version: StorageProofVersion::Plain,
 			lane: params.lane,
 			nonces_start: *params.message_nonces.start(),
 			nonces_end: *params.message_nonces.end(),
@@ -195,16 +197,19 @@ where
 	BHH: Hasher<Out = HashOf<BridgedChain<B>>>,
 	HashOf<BridgedChain<B>>: Copy + Default,
 {
-	// prepare Bridged chain storage with inbound lane state
-	let storage_key =
-		storage_keys::inbound_lane_data_key(B::BRIDGED_MESSAGES_PALLET_NAME, &params.lane).0;
+	// prepare Bridged chain storage with inbound lane state (and the same state of other lanes)
+	let encoded_inbound_lane_data = params.inbound_lane_data.encode();
 	let mut root = Default::default();
 	let mut mdb = MemoryDB::default();
 	{
 		let mut trie = TrieDBMutV1::<BHH>::new(&mut mdb, &mut root);
-		trie.insert(&storage_key, &params.inbound_lane_data.encode())
-			.map_err(|_| "TrieMut::insert has failed")
-			.expect("TrieMut::insert should not fail in benchmarks");
+		for lane in sp_std::iter::once(&params.lane).chain(params.other_lanes.iter()) {
+			let storage_key =
+				storage_keys::inbound_lane_data_key(B::BRIDGED_MESSAGES_PALLET_NAME, lane).0;
+			trie.insert(&storage_key, &encoded_inbound_lane_data)
+				.map_err(|_| "TrieMut::insert has failed")
+				.expect("TrieMut::insert should not fail in benchmarks");
+		}
 	}
 	root = grow_trie(root, &mut mdb, params.size);
 
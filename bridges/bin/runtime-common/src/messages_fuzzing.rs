@@ -0,0 +1,508 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Everything required to fuzz verification of messages and messages delivery proofs, based on
+//! `bridge_runtime_common::messages` implementation.
+//!
+//! The fuzz case describes the storage of the bridged chain (messages, lane states and unrelated
+//! entries with arbitrary values) and the mutation that is applied to the honest storage proof.
+//! The proof is then verified using the finalized header with the state root of that storage.
+//! Verification must never panic and it must never yield values that are not in the storage.
+
+#![cfg(feature = "fuzzing")]
+
+use crate::messages::{
+	source::{verify_messages_delivery_proof, FromBridgedChainMessagesDeliveryProof},
+	target::{verify_messages_proof, FromBridgedChainMessagesProof},
+	BridgedChainWithMessages, ChainWithMessages, MessageBridge, MessageTransaction,
+	RawStorageProof, StorageProofVersion, ThisChainWithMessages, WeightOf,
+};
+
+use bp_header_chain::FinalityBackend;
+use bp_messages::{
+	storage_keys, InboundLaneData, LaneId, MessageData, MessageNonce, OutboundLaneData,
+};
+use bp_polkadot_core::{AccountId, Balance, BlockNumber, Hash, Hasher, Header, PolkadotLike};
+use bp_runtime::StorageProofChecker;
+use codec::{Decode, Encode};
+use frame_support::weights::Weight;
+use sp_runtime::{traits::Header as HeaderT, DispatchError, FixedU128, StateVersion};
+use sp_state_machine::{backend::Backend, prove_read, InMemoryBackend};
+use sp_trie::StorageProof;
+use std::{cell::RefCell, collections::BTreeMap, ops::RangeInclusive};
+
+/// Name of the messages pallet at the fuzzed bridged chain.
+pub const FUZZ_MESSAGES_PALLET_NAME: &str = "BridgeMessages";
+
+/// Maximal number of messages that the fuzzed messages proof may declare.
+///
+/// The messages pallet never verifies proofs with more messages than it is able to accept, so
+/// there's no point in fuzzing larger proofs.
+pub const MAX_FUZZ_MESSAGES_IN_PROOF: u32 = 1024;
+
+/// Mutation that is applied to the honest storage proof before verification.
+#[derive(Debug, Decode, Encode)]
+pub enum ProofMutation {
+	/// Honest proof is verified.
+	None,
+	/// Node with given index (modulo number of nodes) is removed from the proof.
+	RemoveNode(u32),
+	/// Node with given index (modulo number of nodes) is replaced with given node.
+	ReplaceNode(u32, Vec<u8>),
+	/// Given node is appended to the proof.
+	AppendNode(Vec<u8>),
+	/// Proof is replaced with given nodes.
+	Replace(RawStorageProof),
+}
+
+/// Fuzz case for the messages and messages delivery proofs verification.
+#[derive(Debug, Decode, Encode)]
+pub struct MessagesProofFuzzCase {
+	/// Lane of the proofs.
+	pub lane: LaneId,
+	/// Version of the messages storage proof.
+	pub storage_proof_version: StorageProofVersion,
+	/// Nonce of the first message that is declared by the messages proof.
+	pub nonces_start: MessageNonce,
+	/// Nonce of the last message that is declared by the messages proof.
+	pub nonces_end: MessageNonce,
+	/// Number of messages that is declared by the messages delivery transaction.
+	pub messages_count: u32,
+	/// Raw messages that are stored at the bridged chain, mapped by their nonces.
+	pub messages: Vec<(MessageNonce, Vec<u8>)>,
+	/// Raw outbound lane data that is stored at the bridged chain.
+	pub outbound_lane_data: Option<Vec<u8>>,
+	/// Raw inbound lane data that is stored at the bridged chain.
+	pub inbound_lane_data: Option<Vec<u8>>,
+	/// Unrelated entries that are stored at the bridged chain.
+	pub extra_entries: Vec<(Vec<u8>, Vec<u8>)>,
+	/// Mutation of the honest messages storage proof.
+	pub messages_proof_mutation: ProofMutation,
+	/// Mutation of the honest messages delivery storage proof.
+	pub delivery_proof_mutation: ProofMutation,
+}
+
+/// Bridge with the fuzzed chain.
+#[derive(Debug)]
+pub struct FuzzBridge;
+
+impl MessageBridge for FuzzBridge {
+	const RELAYER_FEE_PERCENT: u32 = 0;
+	const THIS_CHAIN_ID: bp_runtime::ChainId = *b"this";
+	const BRIDGED_CHAIN_ID: bp_runtime::ChainId = *b"fuzz";
+	const BRIDGED_MESSAGES_PALLET_NAME: &'static str = FUZZ_MESSAGES_PALLET_NAME;
+
+	type ThisChain = FuzzChain;
+	type BridgedChain = FuzzChain;
+
+	fn bridged_balance_to_this_balance(
+		_bridged_balance: Balance,
+		_bridged_to_this_conversion_rate_override: Option<FixedU128>,
+	) -> Balance {
+		unreachable!("only proofs verification is fuzzed")
+	}
+}
+
+/// Fuzzed chain. It is used both as This and Bridged chain of the `FuzzBridge`.
+#[derive(Debug)]
+pub struct FuzzChain;
+
+impl ChainWithMessages for FuzzChain {
+	type Hash = Hash;
+	type AccountId = AccountId;
+	type Signer = AccountId;
+	type Signature = ();
+	type Weight = Weight;
+	type Balance = Balance;
+}
+
+impl ThisChainWithMessages for FuzzChain {
+	type Origin = ();
+	type Call = ();
+
+	fn is_message_accepted(_origin: &Self::Origin, _lane: &LaneId) -> bool {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<WeightOf<Self>> {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn transaction_payment(_transaction: MessageTransaction<WeightOf<Self>>) -> Balance {
+		unreachable!("only proofs verification is fuzzed")
+	}
+}
+
+impl BridgedChainWithMessages for FuzzChain {
+	fn maximal_extrinsic_size() -> u32 {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn message_weight_limits(_message_payload: &[u8]) -> RangeInclusive<Self::Weight> {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn estimate_delivery_transaction(
+		_message_payload: &[u8],
+		_include_pay_dispatch_fee_cost: bool,
+		_message_dispatch_weight: WeightOf<Self>,
+	) -> MessageTransaction<WeightOf<Self>> {
+		unreachable!("only proofs verification is fuzzed")
+	}
+
+	fn transaction_payment(_transaction: MessageTransaction<WeightOf<Self>>) -> Balance {
+		unreachable!("only proofs verification is fuzzed")
+	}
+}
+
+thread_local! {
+	static FINALIZED_HEADER: RefCell<Option<Header>> = RefCell::new(None);
+}
+
+/// Finality of the fuzzed chain: the only finalized header is the header, inserted by the
+/// `insert_finalized_header`.
+pub struct FuzzFinality;
+
+impl FuzzFinality {
+	/// Insert finalized header with given state root. Returns hash of the header.
+	pub fn insert_finalized_header(state_root: Hash) -> Hash {
+		let header =
+			Header::new(1, Default::default(), state_root, Default::default(), Default::default());
+		let hash = header.hash();
+		FINALIZED_HEADER.with(|finalized_header| *finalized_header.borrow_mut() = Some(header));
+		hash
+	}
+}
+
+impl FinalityBackend for FuzzFinality {
+	type Chain = PolkadotLike;
+
+	fn best_finalized_number() -> BlockNumber {
+		FINALIZED_HEADER.with(|header| {
+			header.borrow().as_ref().map(|header| *header.number()).unwrap_or_default()
+		})
+	}
+
+	fn finalized_header(hash: Hash) -> Option<Header> {
+		FINALIZED_HEADER
+			.with(|header| header.borrow().clone().filter(|header| header.hash() == hash))
+	}
+
+	fn parse_finalized_storage_proof<R>(
+		hash: Hash,
+		storage_proof: StorageProof,
+		parse: impl FnOnce(StorageProofChecker<Hasher>) -> R,
+	) -> Result<R, DispatchError> {
+		let header = Self::finalized_header(hash).ok_or("Unknown fuzzed header")?;
+		let storage_proof_checker = StorageProofChecker::new(*header.state_root(), storage_proof)
+			.map_err(|_| "Storage root mismatch")?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+/// Run single fuzz case.
+///
+/// Panics if verification of the proof has panicked or if it has yielded values that are not in
+/// the storage of the fuzzed chain. Also panics if the honest proof is rejected while all values
+/// that it is proving are valid.
+pub fn run_fuzz_case(case: MessagesProofFuzzCase) {
+	if case.messages_count > MAX_FUZZ_MESSAGES_IN_PROOF {
+		return
+	}
+
+	// prepare storage of the bridged chain
+	let lane = case.lane;
+	let mut storage = BTreeMap::new();
+	for (key, value) in case.extra_entries {
+		storage.insert(key, value);
+	}
+	for (nonce, message) in case.messages {
+		storage
+			.insert(storage_keys::message_key(FUZZ_MESSAGES_PALLET_NAME, &lane, nonce).0, message);
+	}
+	let outbound_lane_data_key =
+		storage_keys::outbound_lane_data_key(FUZZ_MESSAGES_PALLET_NAME, &lane).0;
+	if let Some(outbound_lane_data) = case.outbound_lane_data {
+		storage.insert(outbound_lane_data_key.clone(), outbound_lane_data);
+	}
+	let inbound_lane_data_key =
+		storage_keys::inbound_lane_data_key(FUZZ_MESSAGES_PALLET_NAME, &lane).0;
+	if let Some(inbound_lane_data) = case.inbound_lane_data {
+		storage.insert(inbound_lane_data_key.clone(), inbound_lane_data);
+	}
+
+	// prepare honest proofs of all keys that are read by the verifier
+	let declares_messages = case.nonces_end >= case.nonces_start &&
+		(case.nonces_end - case.nonces_start).saturating_add(1) ==
+			case.messages_count as MessageNonce;
+	let mut messages_proof_keys = if declares_messages {
+		(case.nonces_start..=case.nonces_end)
+			.map(|nonce| storage_keys::message_key(FUZZ_MESSAGES_PALLET_NAME, &lane, nonce).0)
+			.collect::<Vec<_>>()
+	} else {
+		Vec::new()
+	};
+	messages_proof_keys.push(outbound_lane_data_key);
+	let (state_root, messages_proof) = prove_storage(&storage, &messages_proof_keys);
+	let (_, delivery_proof) = prove_storage(&storage, &[inbound_lane_data_key.clone()]);
+	let bridged_header_hash = FuzzFinality::insert_finalized_header(state_root);
+
+	// verify messages proof
+	let is_honest_messages_proof = matches!(case.messages_proof_mutation, ProofMutation::None);
+	let messages_proof = match case.storage_proof_version {
+		StorageProofVersion::Plain => messages_proof.iter_nodes().collect(),
+		StorageProofVersion::Compact =>
+			bp_runtime::compact_storage_proof::<Hasher>(state_root, messages_proof)
+				.expect("proof is generated for the same root; qed"),
+	};
+	let messages_proof = FromBridgedChainMessagesProof {
+		bridged_header_hash,
+		storage_proof: mutate_proof(messages_proof, case.messages_proof_mutation),
+		storage_proof_version: case.storage_proof_version,
+		lane,
+		nonces_start: case.nonces_start,
+		nonces_end: case.nonces_end,
+	};
+	let verification_result =
+		verify_messages_proof::<FuzzBridge, FuzzFinality>(messages_proof, case.messages_count);
+	let expected_messages = (case.nonces_start..=case.nonces_end)
+		.map(|nonce| {
+			storage
+				.get(&storage_keys::message_key(FUZZ_MESSAGES_PALLET_NAME, &lane, nonce).0)
+				.and_then(|raw_message| MessageData::<Balance>::decode(&mut &raw_message[..]).ok())
+		})
+		.take(MAX_FUZZ_MESSAGES_IN_PROOF as usize)
+		.collect::<Vec<_>>();
+	let expected_lane_state = storage
+		.get(&storage_keys::outbound_lane_data_key(FUZZ_MESSAGES_PALLET_NAME, &lane).0)
+		.map(|raw_lane_state| OutboundLaneData::decode(&mut &raw_lane_state[..]).ok());
+	match verification_result {
+		Ok(mut proved_messages) => {
+			let proved_lane_messages = proved_messages
+				.remove(&lane)
+				.expect("verified proof always has messages of the declared lane");
+			assert!(proved_messages.is_empty(), "verified proof has messages of other lanes");
+			assert_eq!(
+				proved_lane_messages.messages.len(),
+				expected_messages.len(),
+				"verified proof has unexpected number of messages",
+			);
+			for (message, expected_message) in
+				proved_lane_messages.messages.into_iter().zip(expected_messages)
+			{
+				assert_eq!(message.key.lane_id, lane);
+				assert_eq!(
+					Some(message.data),
+					expected_message,
+					"verified proof has forged message",
+				);
+			}
+			if let Some(lane_state) = proved_lane_messages.lane_state {
+				assert_eq!(
+					Some(Some(lane_state)),
+					expected_lane_state,
+					"verified proof has forged outbound lane state",
+				);
+			}
+		},
+		Err(_) if is_honest_messages_proof => {
+			let is_valid_storage = (declares_messages || case.nonces_end < case.nonces_start) &&
+				expected_messages.iter().all(Option::is_some) &&
+				expected_lane_state != Some(None) &&
+				!(expected_messages.is_empty() && expected_lane_state.is_none());
+			assert!(!is_valid_storage, "honest messages proof is rejected");
+		},
+		Err(_) => (),
+	}
+
+	// verify messages delivery proof
+	let is_honest_delivery_proof = matches!(case.delivery_proof_mutation, ProofMutation::None);
+	let delivery_proof = FromBridgedChainMessagesDeliveryProof {
+		bridged_header_hash,
+		storage_proof: mutate_proof(
+			delivery_proof.iter_nodes().collect(),
+			case.delivery_proof_mutation,
+		),
+		lane,
+	};
+	let verification_result =
+		verify_messages_delivery_proof::<FuzzBridge, FuzzFinality>(delivery_proof);
+	let expected_inbound_lane_data = storage
+		.get(&inbound_lane_data_key)
+		.and_then(|raw_data| InboundLaneData::<AccountId>::decode(&mut &raw_data[..]).ok());
+	match verification_result {
+		Ok((proved_lane, proved_inbound_lane_data)) => {
+			assert_eq!(proved_lane, lane);
+			assert_eq!(
+				Some(proved_inbound_lane_data),
+				expected_inbound_lane_data,
+				"verified proof has forged inbound lane state",
+			);
+		},
+		Err(_) if is_honest_delivery_proof => assert!(
+			expected_inbound_lane_data.is_none(),
+			"honest messages delivery proof is rejected",
+		),
+		Err(_) => (),
+	}
+}
+
+/// Generate state root and storage proof of given keys.
+fn prove_storage(storage: &BTreeMap<Vec<u8>, Vec<u8>>, keys: &[Vec<u8>]) -> (Hash, StorageProof) {
+	let state_version = StateVersion::default();
+	let entries = storage
+		.iter()
+		.map(|(key, value)| (key.clone(), Some(value.clone())))
+		.collect::<Vec<_>>();
+	let backend = <InMemoryBackend<Hasher>>::from((vec![(None, entries)], state_version));
+	let state_root = backend.storage_root(std::iter::empty(), state_version).0;
+	let proof = prove_read(backend, keys.iter().map(|key| key.as_slice()))
+		.expect("proving reads from in-memory backend never fails; qed");
+	(state_root, proof)
+}
+
+/// Apply mutation to the raw storage proof.
+fn mutate_proof(mut proof: RawStorageProof, mutation: ProofMutation) -> RawStorageProof {
+	match mutation {
+		ProofMutation::None => (),
+		ProofMutation::RemoveNode(index) if !proof.is_empty() => {
+			let index = index as usize % proof.len();
+			proof.remove(index);
+		},
+		ProofMutation::ReplaceNode(index, node) if !proof.is_empty() => {
+			let index = index as usize % proof.len();
+			proof[index] = node;
+		},
+		ProofMutation::RemoveNode(_) | ProofMutation::ReplaceNode(_, _) => (),
+		ProofMutation::AppendNode(node) => proof.push(node),
+		ProofMutation::Replace(nodes) => proof = nodes,
+	}
+	proof
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bp_messages::{DeliveredMessages, UnrewardedRelayer};
+
+	const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+
+	fn honest_case() -> MessagesProofFuzzCase {
+		MessagesProofFuzzCase {
+			lane: TEST_LANE_ID,
+			storage_proof_version: StorageProofVersion::Plain,
+			nonces_start: 1,
+			nonces_end: 3,
+			messages_count: 3,
+			messages: (1..=3)
+				.map(|nonce| {
+					(nonce, MessageData { payload: vec![nonce as u8; 64], fee: 0 }.encode())
+				})
+				.collect(),
+			outbound_lane_data: Some(
+				OutboundLaneData {
+					oldest_unpruned_nonce: 1,
+					latest_received_nonce: 0,
+					latest_generated_nonce: 3,
+				}
+				.encode(),
+			),
+			inbound_lane_data: Some(
+				InboundLaneData::<AccountId> {
+					relayers: vec![UnrewardedRelayer {
+						relayer: [42u8; 32].into(),
+						messages: DeliveredMessages::new(1, true),
+					}]
+					.into_iter()
+					.collect(),
+					last_confirmed_nonce: 0,
+				}
+				.encode(),
+			),
+			extra_entries: vec![(b"key".to_vec(), b"value".to_vec())],
+			messages_proof_mutation: ProofMutation::None,
+			delivery_proof_mutation: ProofMutation::None,
+		}
+	}
+
+	#[test]
+	fn honest_proofs_are_fuzzed() {
+		run_fuzz_case(honest_case());
+		run_fuzz_case(MessagesProofFuzzCase {
+			storage_proof_version: StorageProofVersion::Compact,
+			..honest_case()
+		});
+	}
+
+	#[test]
+	fn mutated_proofs_are_fuzzed() {
+		let mutations = || {
+			vec![
+				ProofMutation::RemoveNode(0),
+				ProofMutation::RemoveNode(u32::MAX),
+				ProofMutation::ReplaceNode(1, vec![0; 32]),
+				ProofMutation::AppendNode(vec![42; 128]),
+				ProofMutation::Replace(vec![]),
+				ProofMutation::Replace(vec![vec![]]),
+			]
+		};
+		for storage_proof_version in [StorageProofVersion::Plain, StorageProofVersion::Compact] {
+			for (messages_proof_mutation, delivery_proof_mutation) in
+				mutations().into_iter().zip(mutations())
+			{
+				run_fuzz_case(MessagesProofFuzzCase {
+					storage_proof_version,
+					messages_proof_mutation,
+					delivery_proof_mutation,
+					..honest_case()
+				});
+			}
+		}
+	}
+
+	#[test]
+	fn invalid_storage_is_fuzzed() {
+		run_fuzz_case(MessagesProofFuzzCase {
+			messages: vec![(1, vec![42]), (3, vec![])],
+			outbound_lane_data: Some(vec![1, 2, 3]),
+			inbound_lane_data: Some(vec![]),
+			..honest_case()
+		});
+		run_fuzz_case(MessagesProofFuzzCase {
+			nonces_start: MessageNonce::MAX,
+			nonces_end: 0,
+			messages_count: u32::MAX,
+			outbound_lane_data: None,
+			inbound_lane_data: None,
+			..honest_case()
+		});
+	}
+
+	#[test]
+	fn arbitrary_bytes_are_fuzzed() {
+		let encoded_case = honest_case().encode();
+		for len in 0..encoded_case.len() {
+			if let Ok(case) = MessagesProofFuzzCase::decode(&mut &encoded_case[..len]) {
+				run_fuzz_case(case);
+			}
+		}
+	}
+}
@@ -0,0 +1,267 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! In-memory Polkadot-like chain, that may be used in runtime tests to produce valid bridged
+//! headers, GRANDPA justifications and messages storage proofs.
+//!
+//! The chain starts at the genesis header, which is used to initialize the GRANDPA pallet. Every
+//! `finalize_header` call produces a child of the best header, which commits to the current state
+//! of the chain storage and is finalized by the test GRANDPA authorities from `bp_test_utils`.
+//! Storage proofs are always generated for the current storage, so they are only valid at the best
+//! header if storage hasn't been changed after it has been produced.
+
+#![cfg(feature = "test-helpers")]
+
+use crate::messages::{
+	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
+	RawStorageProof, StorageProofVersion,
+};
+
+use bp_header_chain::{justification::GrandpaJustification, InitializationData};
+use bp_messages::{
+	storage_keys, InboundLaneData, LaneId, MessageData, MessageNonce, OutboundLaneData,
+};
+use bp_polkadot_core::{Hash, Hasher, Header};
+use codec::Encode;
+use sp_runtime::{traits::Header as HeaderT, StateVersion};
+use sp_state_machine::{backend::Backend, prove_read, InMemoryBackend};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+/// In-memory Polkadot-like bridged chain.
+#[derive(Debug)]
+pub struct MockBridgedChain {
+	/// Storage of the chain.
+	storage: BTreeMap<Vec<u8>, Vec<u8>>,
+	/// Genesis header of the chain.
+	genesis_header: Header,
+	/// Best (and finalized) header of the chain.
+	best_header: Header,
+}
+
+impl Default for MockBridgedChain {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl MockBridgedChain {
+	/// Create chain with empty storage.
+	pub fn new() -> Self {
+		let storage = BTreeMap::new();
+		let genesis_header = Header::new(
+			0,
+			Default::default(),
+			state_root(&storage),
+			Default::default(),
+			Default::default(),
+		);
+		MockBridgedChain {
+			storage,
+			genesis_header: genesis_header.clone(),
+			best_header: genesis_header,
+		}
+	}
+
+	/// Return data that is used to initialize the GRANDPA pallet with the genesis header of the
+	/// chain.
+	pub fn initialization_data(&self) -> InitializationData<Header> {
+		InitializationData {
+			header: Box::new(self.genesis_header.clone()),
+			authority_list: bp_test_utils::authority_list(),
+			set_id: bp_test_utils::TEST_GRANDPA_SET_ID,
+			is_halted: false,
+		}
+	}
+
+	/// Return best header of the chain.
+	pub fn best_header(&self) -> &Header {
+		&self.best_header
+	}
+
+	/// Return hash of the best header of the chain.
+	pub fn best_header_hash(&self) -> Hash {
+		self.best_header.hash()
+	}
+
+	/// Insert raw value into the chain storage.
+	pub fn insert_raw(&mut self, key: Vec<u8>, value: Vec<u8>) {
+		self.storage.insert(key, value);
+	}
+
+	/// Replace the whole chain storage with given key-value pairs.
+	///
+	/// This may be used to produce headers and proofs for the storage of a runtime that is running
+	/// in test externalities.
+	pub fn set_storage(&mut self, storage: impl IntoIterator<Item = (Vec<u8>, Vec<u8>)>) {
+		self.storage = storage.into_iter().collect();
+	}
+
+	/// Insert message into the storage of the messages pallet with given name.
+	pub fn insert_message<Fee: Encode>(
+		&mut self,
+		pallet_name: &str,
+		lane: &LaneId,
+		nonce: MessageNonce,
+		message: &MessageData<Fee>,
+	) {
+		self.insert_raw(storage_keys::message_key(pallet_name, lane, nonce).0, message.encode());
+	}
+
+	/// Insert outbound lane data into the storage of the messages pallet with given name.
+	pub fn insert_outbound_lane_data(
+		&mut self,
+		pallet_name: &str,
+		lane: &LaneId,
+		data: &OutboundLaneData,
+	) {
+		self.insert_raw(storage_keys::outbound_lane_data_key(pallet_name, lane).0, data.encode());
+	}
+
+	/// Insert inbound lane data into the storage of the messages pallet with given name.
+	pub fn insert_inbound_lane_data<RelayerId: Encode>(
+		&mut self,
+		pallet_name: &str,
+		lane: &LaneId,
+		data: &InboundLaneData<RelayerId>,
+	) {
+		self.insert_raw(storage_keys::inbound_lane_data_key(pallet_name, lane).0, data.encode());
+	}
+
+	/// Produce child of the best header, that commits to the current storage, and its GRANDPA
+	/// justification. The produced header becomes the best header of the chain.
+	pub fn finalize_header(&mut self) -> (Header, GrandpaJustification<Header>) {
+		let header = Header::new(
+			*self.best_header.number() + 1,
+			Default::default(),
+			state_root(&self.storage),
+			self.best_header.hash(),
+			Default::default(),
+		);
+		let justification = bp_test_utils::make_default_justification(&header);
+		self.best_header = header.clone();
+		(header, justification)
+	}
+
+	/// Prove values of given keys in the current storage.
+	pub fn prove_storage(&self, keys: &[Vec<u8>]) -> RawStorageProof {
+		prove_read(backend(&self.storage), keys.iter().map(|key| key.as_slice()))
+			.expect("proving reads from in-memory backend never fails; qed")
+			.iter_nodes()
+			.collect()
+	}
+
+	/// Prove messages (and, optionally, outbound lane data) of the messages pallet with given
+	/// name at the best header.
+	pub fn messages_proof(
+		&self,
+		pallet_name: &str,
+		lane: LaneId,
+		nonces: RangeInclusive<MessageNonce>,
+		include_outbound_lane_data: bool,
+	) -> FromBridgedChainMessagesProof<Hash> {
+		let mut keys = nonces
+			.clone()
+			.map(|nonce| storage_keys::message_key(pallet_name, &lane, nonce).0)
+			.collect::<Vec<_>>();
+		if include_outbound_lane_data {
+			keys.push(storage_keys::outbound_lane_data_key(pallet_name, &lane).0);
+		}
+
+		FromBridgedChainMessagesProof {
+			bridged_header_hash: self.best_header_hash(),
+			storage_proof: self.prove_storage(&keys),
+			storage_proof_version: StorageProofVersion::Plain,
+			lane,
+			nonces_start: *nonces.start(),
+			nonces_end: *nonces.end(),
+		}
+	}
+
+	/// Prove inbound lane data of the messages pallet with given name at the best header.
+	pub fn messages_delivery_proof(
+		&self,
+		pallet_name: &str,
+		lane: LaneId,
+	) -> FromBridgedChainMessagesDeliveryProof<Hash> {
+		let key = storage_keys::inbound_lane_data_key(pallet_name, &lane).0;
+		FromBridgedChainMessagesDeliveryProof {
+			bridged_header_hash: self.best_header_hash(),
+			storage_proof: self.prove_storage(&[key]),
+			lane,
+		}
+	}
+}
+
+/// Return root of given storage.
+fn state_root(storage: &BTreeMap<Vec<u8>, Vec<u8>>) -> Hash {
+	backend(storage).storage_root(std::iter::empty(), StateVersion::default()).0
+}
+
+/// Return in-memory backend with given storage.
+fn backend(storage: &BTreeMap<Vec<u8>, Vec<u8>>) -> InMemoryBackend<Hasher> {
+	let entries = storage
+		.iter()
+		.map(|(key, value)| (key.clone(), Some(value.clone())))
+		.collect::<Vec<_>>();
+	<InMemoryBackend<Hasher>>::from((vec![(None, entries)], StateVersion::default()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bp_runtime::StorageProofChecker;
+
+	const PALLET_NAME: &str = "BridgeMessages";
+	const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+
+	#[test]
+	fn finalized_headers_are_chained() {
+		let mut chain = MockBridgedChain::new();
+		let genesis_hash = chain.best_header_hash();
+		assert_eq!(chain.initialization_data().header.hash(), genesis_hash);
+
+		let (header, justification) = chain.finalize_header();
+		assert_eq!(*header.number(), 1);
+		assert_eq!(*header.parent_hash(), genesis_hash);
+		assert_eq!(justification.commit.target_hash, header.hash());
+		assert_eq!(chain.best_header_hash(), header.hash());
+	}
+
+	#[test]
+	fn messages_proof_is_valid_at_best_header() {
+		let mut chain = MockBridgedChain::new();
+		let message = MessageData { payload: vec![42], fee: 0u64 };
+		chain.insert_message(PALLET_NAME, &TEST_LANE_ID, 1, &message);
+		let (header, _) = chain.finalize_header();
+
+		let proof = chain.messages_proof(PALLET_NAME, TEST_LANE_ID, 1..=1, true);
+		assert_eq!(proof.bridged_header_hash, header.hash());
+
+		let checker = StorageProofChecker::<Hasher>::new(
+			*header.state_root(),
+			sp_trie::StorageProof::new(proof.storage_proof),
+		)
+		.unwrap();
+		assert_eq!(
+			checker.read_value(&storage_keys::message_key(PALLET_NAME, &TEST_LANE_ID, 1).0),
+			Ok(Some(message.encode())),
+		);
+		assert_eq!(
+			checker.read_value(&storage_keys::outbound_lane_data_key(PALLET_NAME, &TEST_LANE_ID).0),
+			Ok(None),
+		);
+	}
+}
@@ -0,0 +1,42 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Messages proof and messages delivery proof verification fuzzer.
+
+#![warn(missing_docs)]
+
+use bridge_runtime_common::messages_fuzzing::{run_fuzz_case, MessagesProofFuzzCase};
+use codec::Decode;
+use honggfuzz::fuzz;
+
+fn run_fuzzer() {
+	fuzz!(|data: &[u8]| {
+		let case = match MessagesProofFuzzCase::decode(&mut &data[..]) {
+			Ok(case) => case,
+			Err(_) => return,
+		};
+		log::info!("Verifying proofs of fuzz case {:?}", case);
+		run_fuzz_case(case);
+	})
+}
+
+fn main() {
+	env_logger::init();
+
+	loop {
+		run_fuzzer();
+	}
+}
@@ -0,0 +1,832 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate BEEFY Pallet
+//!
+//! This pallet is an on-chain BEEFY light client for Substrate based chains. It is an alternative
+//! to the GRANDPA pallet: both pallets are finality backends of the bridge, but this pallet
+//! verifies BEEFY commitments instead of GRANDPA justifications. BEEFY commitments are signed by
+//! a (2/3 + 1) of the BEEFY validators and they are much cheaper to verify.
+//!
+//! Every commitment contains the root of the MMR (Merkle Mountain Range) of the bridged chain. The
+//! relayer submits the commitment along with the MMR leaf, the proof of the leaf inclusion into
+//! the MMR and the header, referenced by the leaf. Once all of them are verified, the header is
+//! stored in the pallet, so its state root may be used to verify storage proofs (e.g. proofs of
+//! bridged messages).
+//!
+//! The latest MMR leaf of the commitment contains the next BEEFY validator set (its id, length and
+//! merkle root of validators addresses). When the bridged chain enacts the set, the relayer must
+//! provide the validators of the set along with the first commitment signed by them.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Runtime-generated enums
+#![allow(clippy::large_enum_variant)]
+
+use beefy_primitives::{
+	crypto::{AuthorityId as BeefyId, Signature as BeefySignature},
+	known_payload_ids::MMR_ROOT_ID,
+	mmr::{BeefyNextAuthoritySet, MmrLeaf},
+	SignedCommitment, ValidatorSetId,
+};
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
+use codec::{Decode, Encode};
+use frame_support::{ensure, weights::Weight};
+use frame_system::{ensure_signed, RawOrigin};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BadOrigin, Convert, Header as HeaderT},
+	RuntimeDebug,
+};
+use sp_std::{boxed::Box, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Block number of the bridged chain.
+pub type BridgedBlockNumber<T, I> = BlockNumberOf<<T as Config<I>>::BridgedChain>;
+/// Block hash of the bridged chain.
+pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
+/// Hasher of the bridged chain.
+pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
+/// Header of the bridged chain.
+pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
+/// Signed BEEFY commitment of the bridged chain.
+pub type BridgedBeefySignedCommitment<T, I> =
+	SignedCommitment<BridgedBlockNumber<T, I>, BeefySignature>;
+/// MMR leaf of the bridged chain.
+pub type BridgedMmrLeaf<T, I> =
+	MmrLeaf<BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>, MmrHash, <T as Config<I>>::LeafExtra>;
+/// Proof of the MMR leaf inclusion into the MMR of the bridged chain.
+pub type BridgedMmrProof = pallet_mmr_primitives::Proof<MmrHash>;
+
+/// Hash of the MMR nodes (and of the BEEFY merkle trees).
+pub type MmrHash = H256;
+/// Hashing of the MMR nodes.
+pub type MmrHashing = sp_runtime::traits::Keccak256;
+
+/// Weight of the single BEEFY signature verification.
+///
+/// There are no benchmarks for the pallet yet, so it is a conservative estimate of the
+/// `secp256k1_ecdsa_recover_compressed` host function cost.
+pub const SIGNATURE_VERIFICATION_WEIGHT: Weight = 100_000_000;
+
+/// BEEFY validator set.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct AuthoritySet {
+	/// Id of the validator set.
+	pub id: ValidatorSetId,
+	/// Validators of the set.
+	pub authorities: Vec<BeefyId>,
+}
+
+/// Data required for initializing the BEEFY bridge pallet.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct InitializationData<H: HeaderT> {
+	/// The header from which we should start syncing.
+	pub header: Box<H>,
+	/// The current BEEFY validator set.
+	pub authority_set: AuthoritySet,
+	/// The next BEEFY validator set, as it is announced in the MMR leaves.
+	pub next_authority_set: BeefyNextAuthoritySet<MmrHash>,
+	/// Should the pallet block transaction immediately after initialization.
+	pub is_halted: bool,
+}
+
+/// Keccak256 hasher of the BEEFY merkle tree.
+pub struct BeefyKeccak256;
+
+impl beefy_merkle_tree::Hasher for BeefyKeccak256 {
+	fn hash(data: &[u8]) -> beefy_merkle_tree::Hash {
+		sp_io::hashing::keccak_256(data)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The chain we are bridging to here.
+		type BridgedChain: Chain;
+
+		/// Extra data of the MMR leaf of the bridged chain.
+		type LeafExtra: Parameter;
+
+		/// The upper bound on the number of requests allowed by the pallet.
+		///
+		/// A request refers to an action which writes a header to storage.
+		///
+		/// Once this bound is reached the pallet will not allow any dispatchables to be called
+		/// until the request count has decreased.
+		#[pallet::constant]
+		type MaxRequests: Get<u32>;
+
+		/// Maximal number of finalized headers to keep in the storage.
+		#[pallet::constant]
+		type HeadersToKeep: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
+			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+
+			(0_u64)
+				.saturating_add(T::DbWeight::get().reads(1))
+				.saturating_add(T::DbWeight::get().writes(1))
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Import the header of the bridged chain, finalized by the BEEFY commitment.
+		///
+		/// The `mmr_leaf` must reference the `header` and it must be a part of the MMR, whose root
+		/// is signed by the commitment. If the commitment is signed by the next validator set,
+		/// the `authorities` of this set must be provided.
+		#[pallet::weight(T::DbWeight::get().reads_writes(6, 7).saturating_add(
+			SIGNATURE_VERIFICATION_WEIGHT.saturating_mul(commitment.signatures.len() as Weight)
+		))]
+		pub fn submit_commitment(
+			origin: OriginFor<T>,
+			commitment: BridgedBeefySignedCommitment<T, I>,
+			authorities: Option<Vec<BeefyId>>,
+			mmr_leaf: Box<BridgedMmrLeaf<T, I>>,
+			mmr_proof: BridgedMmrProof,
+			header: Box<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(Self::request_count() < T::MaxRequests::get(), <Error<T, I>>::TooManyRequests);
+
+			let best_finalized = <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get())
+				.ok_or(<Error<T, I>>::NotInitialized)?;
+			ensure!(best_finalized.number() < header.number(), <Error<T, I>>::OldHeader);
+
+			let (authority_set, is_authorities_change_enacted) =
+				select_authority_set::<T, I>(commitment.commitment.validator_set_id, authorities)?;
+			verify_commitment::<T, I>(&commitment, &authority_set)?;
+
+			let mmr_root = commitment
+				.commitment
+				.payload
+				.get_decoded::<MmrHash>(&MMR_ROOT_ID)
+				.ok_or(<Error<T, I>>::MissingMmrRoot)?;
+			let is_latest_leaf = mmr_proof.leaf_index.saturating_add(1) == mmr_proof.leaf_count;
+			pallet_mmr::verify_leaf_proof::<MmrHashing, _>(
+				mmr_root,
+				pallet_mmr_primitives::DataOrHash::Data(*mmr_leaf.clone()),
+				mmr_proof,
+			)
+			.map_err(|_| <Error<T, I>>::InvalidMmrProof)?;
+
+			let hash = header.hash();
+			ensure!(
+				mmr_leaf.parent_number_and_hash == (*header.number(), hash),
+				<Error<T, I>>::InvalidHeader
+			);
+
+			if is_authorities_change_enacted {
+				log::info!(
+					target: "runtime::bridge-beefy",
+					"Transitioned to authority set {}! New authorities are: {:?}",
+					authority_set.id,
+					authority_set.authorities,
+				);
+				<CurrentAuthoritySet<T, I>>::put(authority_set);
+			}
+			// only the latest leaf of the MMR announces the actual next validator set
+			if is_latest_leaf {
+				<NextAuthoritySet<T, I>>::put(mmr_leaf.beefy_next_authority_set);
+			}
+
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+			insert_header::<T, I>(*header, hash);
+			log::info!(target: "runtime::bridge-beefy", "Succesfully imported finalized header with hash {:?}!", hash);
+
+			Ok(().into())
+		}
+
+		/// Bootstrap the bridge pallet with an initial header and validator sets from which to
+		/// sync.
+		///
+		/// This function is only allowed to be called from a trusted origin and writes to storage
+		/// with practically no checks in terms of the validity of the data. It is important that
+		/// you ensure that valid data is being passed in.
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 6), DispatchClass::Operational))]
+		pub fn initialize(
+			origin: OriginFor<T>,
+			init_data: super::InitializationData<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			let init_allowed = !<BestFinalized<T, I>>::exists();
+			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
+			initialize_bridge::<T, I>(init_data.clone());
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Pallet has been initialized with the following parameters: {:?}",
+				init_data
+			);
+
+			Ok(().into())
+		}
+
+		/// Change `PalletOwner`.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_owner(
+			origin: OriginFor<T>,
+			new_owner: Option<T::AccountId>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-beefy", "Setting pallet Owner to: {:?}", new_owner);
+				},
+				None => {
+					PalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-beefy", "Removed Owner of pallet.");
+				},
+			}
+
+			Ok(().into())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(
+			origin: OriginFor<T>,
+			operational: bool,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<IsHalted<T, I>>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-beefy", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-beefy", "Stopping pallet operations.");
+			}
+
+			Ok(().into())
+		}
+	}
+
+	/// The current number of requests which have written to storage.
+	///
+	/// If the `RequestCount` hits `MaxRequests`, no more calls will be allowed to the pallet until
+	/// the request capacity is increased.
+	///
+	/// The `RequestCount` is decreased by one at the beginning of every block. This is to ensure
+	/// that the pallet can always make progress.
+	#[pallet::storage]
+	#[pallet::getter(fn request_count)]
+	pub(super) type RequestCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Hash of the best finalized header.
+	#[pallet::storage]
+	pub(super) type BestFinalized<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockHash<T, I>, ValueQuery>;
+
+	/// A ring buffer of imported hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, BridgedBlockHash<T, I>>;
+
+	/// Current ring buffer position.
+	#[pallet::storage]
+	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery>;
+
+	/// Headers which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockHash<T, I>, BridgedHeader<T, I>>;
+
+	/// The current BEEFY validator set.
+	#[pallet::storage]
+	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, AuthoritySet, OptionQuery>;
+
+	/// The next BEEFY validator set, announced by the latest imported MMR leaf.
+	#[pallet::storage]
+	pub(super) type NextAuthoritySet<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BeefyNextAuthoritySet<MmrHash>, OptionQuery>;
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
+	/// `None`, then there are no direct ways to halt/resume pallet operations, but other
+	/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
+	/// flag directly or call the `halt_operations`).
+	#[pallet::storage]
+	pub type PalletOwner<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The commitment isn't signed by the required number of validators.
+		InvalidCommitment,
+		/// The commitment is signed by the unknown validator set, or the provided validators
+		/// don't match the next validator set.
+		InvalidAuthoritySet,
+		/// The commitment has no MMR root.
+		MissingMmrRoot,
+		/// The MMR leaf isn't a part of the MMR, signed by the commitment.
+		InvalidMmrProof,
+		/// The header isn't referenced by the MMR leaf.
+		InvalidHeader,
+		/// There are too many requests for the current window to handle.
+		TooManyRequests,
+		/// The header being imported is older than the best finalized header known to the pallet.
+		OldHeader,
+		/// The header is unknown to the pallet.
+		UnknownHeader,
+		/// The pallet is not yet initialized.
+		NotInitialized,
+		/// The pallet has already been initialized.
+		AlreadyInitialized,
+		/// All pallet operations are halted.
+		Halted,
+		/// The storage proof doesn't contains storage root. So it is invalid for given header.
+		StorageRootMismatch,
+	}
+
+	/// Select the validator set that has signed the commitment.
+	///
+	/// It is either the current validator set, or the next validator set. In the latter case,
+	/// the `authorities` must match the next validator set, announced by the MMR leaf.
+	///
+	/// Returns the validator set and the flag, telling whether the set change is enacted.
+	pub(crate) fn select_authority_set<T: Config<I>, I: 'static>(
+		validator_set_id: ValidatorSetId,
+		authorities: Option<Vec<BeefyId>>,
+	) -> Result<(AuthoritySet, bool), Error<T, I>> {
+		let current_authority_set =
+			<CurrentAuthoritySet<T, I>>::get().ok_or(<Error<T, I>>::NotInitialized)?;
+		if validator_set_id == current_authority_set.id {
+			return Ok((current_authority_set, false))
+		}
+
+		let next_authority_set =
+			<NextAuthoritySet<T, I>>::get().ok_or(<Error<T, I>>::NotInitialized)?;
+		let authorities = authorities.ok_or(<Error<T, I>>::InvalidAuthoritySet)?;
+		ensure!(
+			validator_set_id == next_authority_set.id &&
+				authorities.len() as u32 == next_authority_set.len &&
+				authorities_root(&authorities) == next_authority_set.root,
+			<Error<T, I>>::InvalidAuthoritySet
+		);
+
+		Ok((AuthoritySet { id: validator_set_id, authorities }, true))
+	}
+
+	/// Verify that the commitment is signed by the required number of validators of the set.
+	pub(crate) fn verify_commitment<T: Config<I>, I: 'static>(
+		commitment: &BridgedBeefySignedCommitment<T, I>,
+		authority_set: &AuthoritySet,
+	) -> Result<(), Error<T, I>> {
+		ensure!(
+			commitment.signatures.len() == authority_set.authorities.len(),
+			<Error<T, I>>::InvalidCommitment
+		);
+
+		let commitment_hash = sp_io::hashing::keccak_256(&commitment.commitment.encode());
+		let valid_signatures = commitment
+			.signatures
+			.iter()
+			.zip(authority_set.authorities.iter())
+			.filter(|(signature, authority)| match signature {
+				Some(signature) => verify_signature(signature, authority, &commitment_hash),
+				None => false,
+			})
+			.count();
+		ensure!(
+			valid_signatures >= signatures_required(authority_set.authorities.len()),
+			<Error<T, I>>::InvalidCommitment
+		);
+
+		Ok(())
+	}
+
+	/// Import a previously verified header to the storage.
+	///
+	/// Note this function solely takes care of updating the storage and pruning old entries,
+	/// but does not verify the validity of such import.
+	pub(crate) fn insert_header<T: Config<I>, I: 'static>(
+		header: BridgedHeader<T, I>,
+		hash: BridgedBlockHash<T, I>,
+	) {
+		let index = <ImportedHashesPointer<T, I>>::get();
+		let pruning = <ImportedHashes<T, I>>::try_get(index);
+		<BestFinalized<T, I>>::put(hash);
+		<ImportedHeaders<T, I>>::insert(hash, header);
+		<ImportedHashes<T, I>>::insert(index, hash);
+
+		// Update ring buffer pointer and remove old header.
+		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
+		if let Ok(hash) = pruning {
+			log::debug!(target: "runtime::bridge-beefy", "Pruning old header: {:?}.", hash);
+			<ImportedHeaders<T, I>>::remove(hash);
+		}
+	}
+
+	/// Since this writes to storage with no real checks this should only be used in functions that
+	/// were called by a trusted origin.
+	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(
+		init_params: super::InitializationData<BridgedHeader<T, I>>,
+	) {
+		let super::InitializationData { header, authority_set, next_authority_set, is_halted } =
+			init_params;
+
+		let initial_hash = header.hash();
+		<ImportedHashesPointer<T, I>>::put(0);
+		insert_header::<T, I>(*header, initial_hash);
+
+		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<NextAuthoritySet<T, I>>::put(next_authority_set);
+
+		<IsHalted<T, I>>::put(is_halted);
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer))
+				if Some(signer) == <PalletOwner<T, I>>::get().as_ref() =>
+				Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if <IsHalted<T, I>>::get() {
+			Err(<Error<T, I>>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get the best finalized header the pallet knows of.
+	///
+	/// Returns a dummy header if there is no best header. This can only happen
+	/// if the pallet has not been initialized yet.
+	pub fn best_finalized() -> BridgedHeader<T, I> {
+		let hash = <BestFinalized<T, I>>::get();
+		<ImportedHeaders<T, I>>::get(hash).unwrap_or_else(|| {
+			<BridgedHeader<T, I>>::new(
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			)
+		})
+	}
+
+	/// Check if a particular header is known to the bridge pallet.
+	pub fn is_known_header(hash: BridgedBlockHash<T, I>) -> bool {
+		<ImportedHeaders<T, I>>::contains_key(hash)
+	}
+
+	/// Verify that the passed storage proof is valid, given it is crafted using
+	/// known finalized header. If the proof is valid, then the `parse` callback
+	/// is called and the function returns its result.
+	pub fn parse_finalized_storage_proof<R>(
+		hash: BridgedBlockHash<T, I>,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		let header = <ImportedHeaders<T, I>>::get(hash).ok_or(Error::<T, I>::UnknownHeader)?;
+		let storage_proof_checker =
+			bp_runtime::StorageProofChecker::new(*header.state_root(), storage_proof)
+				.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+impl<T: Config<I>, I: 'static> bp_header_chain::FinalityBackend for Pallet<T, I> {
+	type Chain = T::BridgedChain;
+
+	fn best_finalized_number() -> BridgedBlockNumber<T, I> {
+		*Self::best_finalized().number()
+	}
+
+	fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	fn parse_finalized_storage_proof<R>(
+		hash: BridgedBlockHash<T, I>,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		Pallet::<T, I>::parse_finalized_storage_proof(hash, storage_proof, parse)
+	}
+}
+
+/// Returns merkle root of the BEEFY validators addresses, as it is computed by the bridged chain.
+pub fn authorities_root(authorities: &[BeefyId]) -> MmrHash {
+	beefy_merkle_tree::merkle_root::<BeefyKeccak256, _, _>(
+		authorities.iter().cloned().map(pallet_beefy_mmr::BeefyEcdsaToEthereum::convert),
+	)
+	.into()
+}
+
+/// Returns number of valid signatures, required to accept the commitment of the validator set of
+/// given size.
+fn signatures_required(authorities_len: usize) -> usize {
+	authorities_len - authorities_len.saturating_sub(1) / 3
+}
+
+/// Verify the signature of the commitment hash.
+fn verify_signature(
+	signature: &BeefySignature,
+	authority: &BeefyId,
+	commitment_hash: &[u8; 32],
+) -> bool {
+	let signature: &[u8] = signature.as_ref();
+	let signature = match <[u8; 65]>::try_from(signature) {
+		Ok(signature) => signature,
+		Err(_) => return false,
+	};
+	let authority: &[u8] = authority.as_ref();
+	sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature, commitment_hash)
+		.map(|recovered| recovered[..] == *authority)
+		.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		commitment, mmr_leaf, next_block, run_test, test_header, validators, HeadersToKeep, Origin,
+		TestHeader, TestRuntime,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use sp_runtime::DispatchError;
+
+	fn init_data(header: TestHeader) -> super::InitializationData<TestHeader> {
+		super::InitializationData {
+			header: Box::new(header),
+			authority_set: AuthoritySet { id: 0, authorities: validators(0) },
+			next_authority_set: next_authority_set(1),
+			is_halted: false,
+		}
+	}
+
+	fn next_authority_set(id: ValidatorSetId) -> BeefyNextAuthoritySet<MmrHash> {
+		let authorities = validators(id);
+		BeefyNextAuthoritySet {
+			id,
+			len: authorities.len() as u32,
+			root: authorities_root(&authorities),
+		}
+	}
+
+	fn initialize_substrate_bridge() {
+		assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data(test_header(0))));
+	}
+
+	fn submit_commitment(
+		header_number: u64,
+		validator_set_id: ValidatorSetId,
+		signed_by: usize,
+		authorities: Option<Vec<BeefyId>>,
+	) -> frame_support::dispatch::DispatchResultWithPostInfo {
+		let header = test_header(header_number);
+		let leaf = mmr_leaf(&header, next_authority_set(validator_set_id + 1));
+		let (commitment, mmr_proof) =
+			commitment(header_number + 1, validator_set_id, signed_by, &leaf);
+		Pallet::<TestRuntime>::submit_commitment(
+			Origin::signed(1),
+			commitment,
+			authorities,
+			Box::new(leaf),
+			mmr_proof,
+			Box::new(header),
+		)
+	}
+
+	#[test]
+	fn init_root_or_owner_origin_can_initialize_pallet() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::signed(1), init_data(test_header(0))),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				init_data(test_header(0))
+			));
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::root(), init_data(test_header(0))),
+				<Error<TestRuntime>>::AlreadyInitialized,
+			);
+		})
+	}
+
+	#[test]
+	fn succesfully_imports_header_with_valid_commitment() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(submit_commitment(1, 0, 3, None));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), test_header(1).hash());
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_without_enough_signatures() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(submit_commitment(1, 0, 2, None), <Error<TestRuntime>>::InvalidCommitment);
+		})
+	}
+
+	#[test]
+	fn rejects_commitment_of_unknown_validator_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				submit_commitment(1, 2, 3, Some(validators(2))),
+				<Error<TestRuntime>>::InvalidAuthoritySet,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_header_that_is_not_referenced_by_mmr_leaf() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let leaf = mmr_leaf(&test_header(1), next_authority_set(1));
+			let (commitment, mmr_proof) = commitment(2, 0, 3, &leaf);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(
+					Origin::signed(1),
+					commitment,
+					None,
+					Box::new(leaf),
+					mmr_proof,
+					Box::new(test_header(2)),
+				),
+				<Error<TestRuntime>>::InvalidHeader,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_mmr_leaf_that_is_not_a_part_of_signed_mmr() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let leaf = mmr_leaf(&test_header(1), next_authority_set(1));
+			let (commitment, mmr_proof) =
+				commitment(2, 0, 3, &mmr_leaf(&test_header(1), next_authority_set(2)));
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(
+					Origin::signed(1),
+					commitment,
+					None,
+					Box::new(leaf),
+					mmr_proof,
+					Box::new(test_header(1)),
+				),
+				<Error<TestRuntime>>::InvalidMmrProof,
+			);
+		})
+	}
+
+	#[test]
+	fn enacts_next_authority_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// authorities of the next set must be provided
+			assert_noop!(
+				submit_commitment(1, 1, 3, None),
+				<Error<TestRuntime>>::InvalidAuthoritySet
+			);
+			assert_noop!(
+				submit_commitment(1, 1, 3, Some(validators(0))),
+				<Error<TestRuntime>>::InvalidAuthoritySet,
+			);
+
+			assert_ok!(submit_commitment(1, 1, 3, Some(validators(1))));
+			assert_eq!(
+				<CurrentAuthoritySet<TestRuntime>>::get(),
+				Some(AuthoritySet { id: 1, authorities: validators(1) }),
+			);
+			assert_eq!(<NextAuthoritySet<TestRuntime>>::get(), Some(next_authority_set(2)));
+
+			// the previous set can't sign commitments anymore
+			next_block();
+			assert_noop!(
+				submit_commitment(2, 0, 3, None),
+				<Error<TestRuntime>>::InvalidAuthoritySet
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_old_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(submit_commitment(2, 0, 3, None));
+			next_block();
+			assert_noop!(submit_commitment(1, 0, 3, None), <Error<TestRuntime>>::OldHeader);
+		})
+	}
+
+	#[test]
+	fn rejects_commitments_if_halted() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert_noop!(submit_commitment(1, 0, 3, None), <Error<TestRuntime>>::Halted);
+		})
+	}
+
+	#[test]
+	fn should_prune_headers_over_headers_to_keep_parameter() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			for number in 1..=HeadersToKeep::get() as u64 {
+				assert_ok!(submit_commitment(number, 0, 3, None));
+				next_block();
+			}
+
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+		})
+	}
+
+	#[test]
+	fn parse_finalized_storage_accepts_valid_proof() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+
+			let mut header = test_header(0);
+			header.set_state_root(state_root);
+			assert_ok!(Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				init_data(header.clone())
+			));
+
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					header.hash(),
+					storage_proof,
+					|_| (),
+				),
+				(),
+			);
+		})
+	}
+}
@@ -0,0 +1,207 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate::{BridgedBeefySignedCommitment, BridgedMmrLeaf, BridgedMmrProof, MmrHash, MmrHashing};
+use beefy_primitives::{
+	crypto::AuthorityId as BeefyId,
+	known_payload_ids::MMR_ROOT_ID,
+	mmr::{BeefyNextAuthoritySet, MmrLeaf, MmrLeafVersion},
+	Commitment, Payload, SignedCommitment, ValidatorSetId,
+};
+use bp_runtime::Chain;
+use codec::Encode;
+use frame_support::{construct_runtime, parameter_types, traits::OnInitialize, weights::Weight};
+use pallet_mmr_primitives::{DataOrHash, Proof};
+use sp_core::{ecdsa, sr25519::Signature, Pair};
+use sp_runtime::{
+	testing::{Header, H256},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type TestHeader = crate::BridgedHeader<TestRuntime, ()>;
+pub type TestNumber = crate::BridgedBlockNumber<TestRuntime, ()>;
+pub type TestMmrLeaf = BridgedMmrLeaf<TestRuntime, ()>;
+pub type TestSignedCommitment = BridgedBeefySignedCommitment<TestRuntime, ()>;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+use crate as beefy;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Beefy: beefy::{Pallet},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+	pub const MaxRequests: u32 = 2;
+	pub const HeadersToKeep: u32 = 5;
+	pub const NumValidators: u8 = 4;
+}
+
+impl beefy::Config for TestRuntime {
+	type BridgedChain = TestBridgedChain;
+	type LeafExtra = ();
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+
+	type AccountId = AccountId;
+	type Balance = u64;
+	type Index = u64;
+	type Signature = Signature;
+
+	fn max_extrinsic_size() -> u32 {
+		unreachable!()
+	}
+	fn max_extrinsic_weight() -> Weight {
+		unreachable!()
+	}
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(test)
+}
+
+pub fn next_block() {
+	let current_number = frame_system::Pallet::<TestRuntime>::block_number();
+	frame_system::Pallet::<TestRuntime>::set_block_number(current_number + 1);
+	let _ = Beefy::on_initialize(current_number);
+}
+
+pub fn test_header(num: TestNumber) -> TestHeader {
+	// We wrap the call to avoid explicit type annotations in our tests
+	bp_test_utils::test_header(num)
+}
+
+/// Key pairs of the validators of the given BEEFY validator set.
+pub fn validator_pairs(validator_set_id: ValidatorSetId) -> Vec<ecdsa::Pair> {
+	(0..NumValidators::get())
+		.map(|index| {
+			let seed = (validator_set_id as u8).wrapping_mul(NumValidators::get()) + index + 1;
+			ecdsa::Pair::from_seed(&[seed; 32])
+		})
+		.collect()
+}
+
+/// Validators of the given BEEFY validator set.
+pub fn validators(validator_set_id: ValidatorSetId) -> Vec<BeefyId> {
+	validator_pairs(validator_set_id)
+		.iter()
+		.map(|pair| pair.public().into())
+		.collect()
+}
+
+/// MMR leaf, referencing given header.
+pub fn mmr_leaf(
+	header: &TestHeader,
+	next_authority_set: BeefyNextAuthoritySet<MmrHash>,
+) -> TestMmrLeaf {
+	MmrLeaf {
+		version: MmrLeafVersion::new(0, 0),
+		parent_number_and_hash: (*header.number(), header.hash()),
+		beefy_next_authority_set: next_authority_set,
+		leaf_extra: (),
+	}
+}
+
+/// Commitment to the MMR with the single `leaf`, signed by the first `signed_by` validators of
+/// the set. Returns the commitment and the proof of `leaf` inclusion into the MMR.
+pub fn commitment(
+	block_number: TestNumber,
+	validator_set_id: ValidatorSetId,
+	signed_by: usize,
+	leaf: &TestMmrLeaf,
+) -> (TestSignedCommitment, BridgedMmrProof) {
+	let mmr_root = DataOrHash::<MmrHashing, _>::Data(leaf.clone()).hash();
+	let commitment = Commitment {
+		payload: Payload::new(MMR_ROOT_ID, mmr_root.encode()),
+		block_number,
+		validator_set_id,
+	};
+
+	let commitment_hash = sp_io::hashing::keccak_256(&commitment.encode());
+	let signatures = validator_pairs(validator_set_id)
+		.into_iter()
+		.enumerate()
+		.map(|(index, pair)| {
+			if index < signed_by {
+				Some(pair.sign_prehashed(&commitment_hash).into())
+			} else {
+				None
+			}
+		})
+		.collect();
+
+	(
+		SignedCommitment { commitment, signatures },
+		Proof { leaf_index: 0, leaf_count: 1, items: vec![] },
+	)
+}
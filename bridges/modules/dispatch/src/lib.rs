@@ -18,8 +18,10 @@
 //!
 //! The messages are interpreted directly as runtime `Call`. We attempt to decode
 //! them and then dispatch as usual. To prevent compatibility issues, the Calls have
-//! to include a `spec_version`. This will be checked before dispatch. In the case of
-//! a successful dispatch an event is emitted.
+//! to include a `spec_version`. This will be checked before dispatch. Messages with a
+//! different `spec_version` are only dispatched if the runtime declares it compatible (e.g.
+//! for a while after runtime upgrades that haven't changed the `Call` encoding). In the case
+//! of a successful dispatch an event is emitted.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 // Generated by `decl_event!`
@@ -27,7 +29,7 @@
 
 use bp_message_dispatch::{CallOrigin, MessageDispatch, MessagePayload, SpecVersion};
 use bp_runtime::{
-	derive_account_id,
+	derive_account_id, derive_account_id_with_prefix,
 	messages::{DispatchFeePayment, MessageDispatchResult},
 	ChainId, SourceAccount,
 };
@@ -89,10 +91,32 @@ pub mod pallet {
 		/// `Call` which has been encoded using previous `spec_version`, then we might end
 		/// up with decoding error, instead of `MessageVersionSpecMismatch`.
 		type EncodedCall: Decode + Encode + Into<Result<<Self as Config<I>>::Call, ()>>;
+		/// Spec versions, other than the current `spec_version` of the runtime, that messages
+		/// may be dispatched with.
+		///
+		/// Only spec versions whose `Call` encoding is compatible with the current one may be
+		/// accepted. Otherwise, calls of messages may be decoded improperly. Messages with
+		/// `CallOrigin::TargetAccount` origin are only dispatched with the current spec version.
+		type CompatibleSpecVersions: Contains<SpecVersion>;
 		/// A type which can be turned into an AccountId from a 256-bit hash.
 		///
 		/// Used when deriving target chain AccountIds from source chain AccountIds.
 		type AccountIdConverter: sp_runtime::traits::Convert<sp_core::hash::H256, Self::AccountId>;
+		/// Prefix that is used when deriving target chain accounts for messages with
+		/// `CallOrigin::SourceAccount` origin.
+		///
+		/// The message is dispatched by the account, derived from this prefix, source chain id
+		/// and the source chain account id. Use `bp_runtime::ACCOUNT_DERIVATION_PREFIX` to keep
+		/// accounts that are compatible with `bp_runtime::derive_account_id`.
+		type AccountDerivationPrefix: Get<&'static [u8]>;
+		/// Domain of signatures that prove ownership of target chain accounts for messages with
+		/// `CallOrigin::TargetAccount` origin.
+		///
+		/// If the domain is set, signatures are verified over the
+		/// [`message_account_ownership_digest`], that includes the domain and the id of the
+		/// message, so that signed messages can't be replayed at other lanes or with other nonces.
+		/// Otherwise, signatures are verified over the [`account_ownership_digest`].
+		type AccountOwnershipDomain: Get<Option<&'static [u8]>>;
 	}
 
 	type BridgeMessageIdOf<T, I> = <T as Config<I>>::BridgeMessageId;
@@ -138,6 +162,21 @@ pub mod pallet {
 	}
 }
 
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns target chain account that is used to dispatch messages with
+	/// `CallOrigin::SourceAccount(source_account_id)` origin, sent from the given source chain.
+	pub fn bridged_account_id(
+		source_chain: ChainId,
+		source_account_id: T::SourceChainAccountId,
+	) -> T::AccountId {
+		T::AccountIdConverter::convert(derive_account_id_with_prefix(
+			T::AccountDerivationPrefix::get(),
+			source_chain,
+			source_account_id,
+		))
+	}
+}
+
 impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId> for Pallet<T, I> {
 	type Message = MessagePayload<
 		T::SourceChainAccountId,
@@ -184,7 +223,9 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 			dispatch_fee_paid_during_dispatch: false,
 		};
 		let expected_version = <T as frame_system::Config>::Version::get().spec_version;
-		if message.spec_version != expected_version {
+		if message.spec_version != expected_version &&
+			!T::CompatibleSpecVersions::contains(&message.spec_version)
+		{
 			log::trace!(
 				"Message {:?}/{:?}: spec_version mismatch. Expected {:?}, got {:?}",
 				source_chain,
@@ -226,13 +267,44 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 				target_id
 			},
 			CallOrigin::TargetAccount(source_account_id, target_public, target_signature) => {
-				let digest = account_ownership_digest(
-					&call,
-					source_account_id,
-					message.spec_version,
-					source_chain,
-					target_chain,
-				);
+				// signatures are bound to the spec version, so that signed messages can't be
+				// replayed after the bridge is redeployed by the runtime upgrade
+				if message.spec_version != expected_version {
+					log::trace!(
+						target: "runtime::bridge-dispatch",
+						"Message {:?}/{:?}: signed with spec_version {:?}, expected {:?}",
+						source_chain,
+						id,
+						message.spec_version,
+						expected_version,
+					);
+					Self::deposit_event(Event::MessageVersionSpecMismatch(
+						source_chain,
+						id,
+						expected_version,
+						message.spec_version,
+					));
+					return dispatch_result
+				}
+
+				let digest = match T::AccountOwnershipDomain::get() {
+					Some(domain) => message_account_ownership_digest(
+						domain,
+						&id,
+						&call,
+						source_account_id,
+						message.spec_version,
+						source_chain,
+						target_chain,
+					),
+					None => account_ownership_digest(
+						&call,
+						source_account_id,
+						message.spec_version,
+						source_chain,
+						target_chain,
+					),
+				};
 
 				let target_account = target_public.into_account();
 				if !target_signature.verify(&digest[..], &target_account) {
@@ -252,9 +324,7 @@ impl<T: Config<I>, I: 'static> MessageDispatch<T::AccountId, T::BridgeMessageId>
 				target_account
 			},
 			CallOrigin::SourceAccount(source_account_id) => {
-				let hex_id =
-					derive_account_id(source_chain, SourceAccount::Account(source_account_id));
-				let target_id = T::AccountIdConverter::convert(hex_id);
+				let target_id = Self::bridged_account_id(source_chain, source_account_id);
 				log::trace!(target: "runtime::bridge-dispatch", "Source Account: {:?}", &target_id);
 				target_id
 			},
@@ -419,6 +489,40 @@ where
 	proof
 }
 
+/// Target account ownership digest from the source chain, bound to the message with given id.
+///
+/// Unlike the [`account_ownership_digest`], the signature of this digest is only valid for the
+/// single message, identified by `message_id` (e.g. `(LaneId, MessageNonce)`), and can't be reused
+/// by signatures of other domains.
+pub fn message_account_ownership_digest<Call, AccountId, SpecVersion, MessageId>(
+	domain: &[u8],
+	message_id: &MessageId,
+	call: &Call,
+	source_account_id: AccountId,
+	target_spec_version: SpecVersion,
+	source_chain_id: ChainId,
+	target_chain_id: ChainId,
+) -> Vec<u8>
+where
+	Call: Encode,
+	AccountId: Encode,
+	SpecVersion: Encode,
+	MessageId: Encode,
+{
+	let mut proof = Vec::new();
+	domain.encode_to(&mut proof);
+	message_id.encode_to(&mut proof);
+	proof.extend(account_ownership_digest(
+		call,
+		source_account_id,
+		target_spec_version,
+		source_chain_id,
+		target_chain_id,
+	));
+
+	proof
+}
+
 #[cfg(test)]
 mod tests {
 	// From construct_runtime macro
@@ -493,6 +597,7 @@ mod tests {
 		pub const MaximumBlockWeight: Weight = 1024;
 		pub const MaximumBlockLength: u32 = 2 * 1024;
 		pub const AvailableBlockRatio: Perbill = Perbill::one();
+		pub const AccountDerivationPrefix: &'static [u8] = b"test-account-derivation";
 	}
 
 	impl frame_system::Config for TestRuntime {
@@ -531,7 +636,10 @@ mod tests {
 		type Call = Call;
 		type CallFilter = TestCallFilter;
 		type EncodedCall = EncodedCall;
+		type CompatibleSpecVersions = TestCompatibleSpecVersions;
 		type AccountIdConverter = AccountIdConverter;
+		type AccountDerivationPrefix = AccountDerivationPrefix;
+		type AccountOwnershipDomain = ();
 	}
 
 	#[derive(Decode, Encode)]
@@ -543,6 +651,14 @@ mod tests {
 		}
 	}
 
+	pub struct TestCompatibleSpecVersions;
+
+	impl Contains<SpecVersion> for TestCompatibleSpecVersions {
+		fn contains(spec_version: &SpecVersion) -> bool {
+			*spec_version == COMPATIBLE_SPEC_VERSION
+		}
+	}
+
 	pub struct TestCallFilter;
 
 	impl Contains<Call> for TestCallFilter {
@@ -552,6 +668,7 @@ mod tests {
 	}
 
 	const TEST_SPEC_VERSION: SpecVersion = 0;
+	const COMPATIBLE_SPEC_VERSION: SpecVersion = 1;
 	const TEST_WEIGHT: Weight = 1_000_000_000;
 
 	fn new_test_ext() -> sp_io::TestExternalities {
@@ -571,6 +688,7 @@ mod tests {
 			weight: TEST_WEIGHT,
 			origin,
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
 			call: EncodedCall(call.encode()),
 		}
 	}
@@ -645,6 +763,80 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn should_dispatch_message_with_compatible_spec_version() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let mut message = prepare_root_message(Call::System(frame_system::Call::remark {
+				remark: vec![1, 2, 3],
+			}));
+			message.spec_version = COMPATIBLE_SPEC_VERSION;
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message),
+				|_, _| unreachable!(),
+			);
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+						SOURCE_CHAIN_ID,
+						id,
+						Ok(())
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn should_fail_on_compatible_spec_version_of_target_message() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let mut message = prepare_target_message(Call::System(frame_system::Call::remark {
+				remark: vec![1, 2, 3],
+			}));
+			let weight = message.weight;
+			message.spec_version = COMPATIBLE_SPEC_VERSION;
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message),
+				|_, _| unreachable!(),
+			);
+			assert_eq!(result.unspent_weight, weight);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(
+						call_dispatch::Event::<TestRuntime>::MessageVersionSpecMismatch(
+							SOURCE_CHAIN_ID,
+							id,
+							TEST_SPEC_VERSION,
+							COMPATIBLE_SPEC_VERSION
+						)
+					),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
 	#[test]
 	fn should_fail_on_weight_mismatch() {
 		new_test_ext().execute_with(|| {
@@ -1039,6 +1231,72 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn source_origin_message_is_dispatched_by_derived_account() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(frame_system::Call::remark { remark: vec![] });
+			let mut message = prepare_source_message(call);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+
+			let expected_account = AccountIdConverter::convert(derive_account_id_with_prefix(
+				AccountDerivationPrefix::get(),
+				SOURCE_CHAIN_ID,
+				1u64,
+			));
+			assert_eq!(Dispatch::bridged_account_id(SOURCE_CHAIN_ID, 1), expected_account);
+			assert_ne!(
+				expected_account,
+				AccountIdConverter::convert(derive_account_id(
+					SOURCE_CHAIN_ID,
+					SourceAccount::Account(1u64)
+				)),
+			);
+
+			System::set_block_number(1);
+			let mut fee_payer = None;
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				[0; 4],
+				Ok(message),
+				|a, _| {
+					fee_payer = Some(*a);
+					Ok(())
+				},
+			);
+			assert!(result.dispatch_result);
+			assert_eq!(fee_payer, Some(expected_account));
+		})
+	}
+
+	#[test]
+	fn message_account_ownership_digest_is_bound_to_domain_and_message() {
+		let call = Call::System(frame_system::Call::remark { remark: vec![] });
+		let digest = |domain: &[u8], id: BridgeMessageId| {
+			message_account_ownership_digest(
+				domain,
+				&id,
+				&call,
+				1u64,
+				TEST_SPEC_VERSION,
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+			)
+		};
+
+		let legacy_digest = account_ownership_digest(
+			&call,
+			1u64,
+			TEST_SPEC_VERSION,
+			SOURCE_CHAIN_ID,
+			TARGET_CHAIN_ID,
+		);
+		assert!(digest(b"domain", [0; 4]).ends_with(&legacy_digest));
+		assert_ne!(digest(b"domain", [0; 4]), legacy_digest);
+		assert_ne!(digest(b"domain", [0; 4]), digest(b"domain", [0, 0, 0, 1]));
+		assert_ne!(digest(b"domain", [0; 4]), digest(b"other-domain", [0; 4]));
+	}
+
 	#[test]
 	fn origin_is_checked_when_verifying_sending_message_using_source_root_account() {
 		let call = Call::System(frame_system::Call::remark { remark: vec![] });
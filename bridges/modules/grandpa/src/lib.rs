@@ -28,7 +28,8 @@
 //!
 //! Since this pallet only tracks finalized headers it does not deal with forks. Forks can only
 //! occur if the GRANDPA validator set on the bridged chain is either colluding or there is a severe
-//! bug causing resulting in an equivocation. Such events are outside the scope of this pallet.
+//! bug causing resulting in an equivocation. Anyone may report an equivocation of the current
+//! validator set, which halts the pallet, so that no more headers are finalized by that set.
 //! Shall the fork occur on the bridged chain governance intervention will be required to
 //! re-initialize the bridge and track the right fork.
 
@@ -43,7 +44,7 @@ use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
 use finality_grandpa::voter_set::VoterSet;
 use frame_support::{ensure, fail};
 use frame_system::{ensure_signed, RawOrigin};
-use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
+use sp_finality_grandpa::{AuthorityId, ConsensusLog, EquivocationProof, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::traits::{BadOrigin, Header as HeaderT, Zero};
 use sp_std::boxed::Box;
 
@@ -76,6 +77,9 @@ pub mod pallet {
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
 		/// The chain we are bridging to here.
 		type BridgedChain: Chain;
 
@@ -93,6 +97,9 @@ pub mod pallet {
 		/// The setting is there to prevent growing the on-chain state indefinitely. Note
 		/// the setting does not relate to block numbers - we will simply keep as much items
 		/// in the storage, so it doesn't guarantee any fixed timeframe for finality headers.
+		///
+		/// The root or the `PalletOwner` may lower the actual number of stored headers with the
+		/// `set_headers_to_keep` call.
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
@@ -124,10 +131,12 @@ pub mod pallet {
 		///
 		/// If successful in verification, it will write the target header to the underlying storage
 		/// pallet.
+		// pruning of stale headers after `set_headers_to_keep` isn't covered by the benchmark
 		#[pallet::weight(T::WeightInfo::submit_finality_proof(
 			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
 			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
-		))]
+		)
+		.saturating_add(T::DbWeight::get().reads_writes(4, 5)))]
 		pub fn submit_finality_proof(
 			origin: OriginFor<T>,
 			finality_target: Box<BridgedHeader<T, I>>,
@@ -172,7 +181,8 @@ pub mod pallet {
 			// further without importing this header. So every bridge MUST import mandatory headers.
 			//
 			// We don't want to charge extra costs for mandatory operations. So relayer is not
-			// paying fee for mandatory headers import transactions.
+			// paying fee for mandatory headers import transactions. Free imports are still counted
+			// by the rate limiter above, so they can't be used to spam the chain.
 			let is_mandatory_header = is_authorities_change_enacted;
 			let pays_fee = if is_mandatory_header { Pays::No } else { Pays::Yes };
 
@@ -208,6 +218,46 @@ pub mod pallet {
 			Ok(().into())
 		}
 
+		/// Re-initialize the bridge with a new trusted header and authority set.
+		///
+		/// This recovers the pallet if the tracked finality state has become unusable, e.g. after a
+		/// forced authority set change at the bridged chain or its long stall. The header becomes
+		/// the best finalized header and the authority set becomes the current set. The set may
+		/// not be older than the current set. Previously imported headers are kept and pruned as
+		/// usual. State of message lanes is kept by other pallets, so their nonces are preserved.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(8, 14), DispatchClass::Operational))]
+		pub fn reinitialize(
+			origin: OriginFor<T>,
+			init_data: super::InitializationData<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			ensure!(<BestFinalized<T, I>>::exists(), <Error<T, I>>::NotInitialized);
+			ensure!(
+				init_data.set_id >= <CurrentAuthoritySet<T, I>>::get().set_id,
+				<Error<T, I>>::OldAuthoritySet
+			);
+			ensure!(
+				VoterSet::new(init_data.authority_list.iter().cloned()).is_some(),
+				<Error<T, I>>::InvalidAuthoritySet
+			);
+
+			let previous_best_finalized = <BestFinalized<T, I>>::get();
+			let (hash, set_id) = (init_data.header.hash(), init_data.set_id);
+			reinitialize_bridge::<T, I>(init_data.clone());
+
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Pallet has been re-initialized with the following parameters: {:?}",
+				init_data
+			);
+
+			Self::deposit_event(Event::Reinitialized(previous_best_finalized, hash, set_id));
+			Ok(().into())
+		}
+
 		/// Change `PalletOwner`.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
@@ -250,6 +300,109 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Change the number of finalized headers to keep in the storage.
+		///
+		/// Storage proofs are only accepted against stored headers, so the value is also the
+		/// window of recent ancestors of the best finalized header, that proofs may be crafted
+		/// at. It must be in the `1..=HeadersToKeep` range. If the number is lowered, headers that
+		/// no longer fit into the window are pruned gradually, by two with every imported header.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(3, 2), DispatchClass::Operational))]
+		pub fn set_headers_to_keep(
+			origin: OriginFor<T>,
+			headers_to_keep: u32,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			ensure!(
+				headers_to_keep != 0 && headers_to_keep <= T::HeadersToKeep::get(),
+				<Error<T, I>>::InvalidHeadersToKeep
+			);
+
+			// headers that are out of the new window are pruned on next imports, and headers that
+			// are still waiting for pruning are back in the window if it has grown
+			let current_headers_to_keep = headers_to_keep_limit::<T, I>();
+			let stale_headers = <StaleHeadersCount<T, I>>::get();
+			let stale_headers = if headers_to_keep < current_headers_to_keep {
+				stale_headers + (current_headers_to_keep - headers_to_keep)
+			} else {
+				stale_headers.saturating_sub(headers_to_keep - current_headers_to_keep)
+			};
+			<HeadersToKeepLimit<T, I>>::put(headers_to_keep);
+			<StaleHeadersCount<T, I>>::put(stale_headers);
+			log::info!(
+				target: "runtime::bridge-grandpa",
+				"Keeping {} finalized headers. {} headers are waiting for pruning.",
+				headers_to_keep,
+				stale_headers,
+			);
+
+			Self::deposit_event(Event::HeadersToKeepChanged(headers_to_keep, stale_headers));
+			Ok(().into())
+		}
+
+		/// Report an equivocation of an authority of the current GRANDPA authority set.
+		///
+		/// Headers, finalized by the set with an equivocating authority, can't be trusted, so all
+		/// pallet operations are halted if the report is valid. They may be resumed by root or by
+		/// the `PalletOwner` once the equivocation is reviewed. The transaction is free if the
+		/// report is valid.
+		// verifying the equivocation is cheaper than verifying a justification with two precommits
+		#[pallet::weight(T::WeightInfo::submit_finality_proof(2, 0))]
+		pub fn report_equivocation(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<
+				EquivocationProof<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>,
+			>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			let authority_set = <CurrentAuthoritySet<T, I>>::get();
+			ensure!(
+				equivocation_proof.set_id() == authority_set.set_id,
+				<Error<T, I>>::UnknownAuthoritySet
+			);
+			let offender = equivocation_proof.offender().clone();
+			ensure!(
+				authority_set.authorities.iter().any(|(authority, _)| *authority == offender),
+				<Error<T, I>>::InvalidEquivocationProof
+			);
+			ensure!(
+				sp_finality_grandpa::check_equivocation_proof(*equivocation_proof),
+				<Error<T, I>>::InvalidEquivocationProof
+			);
+
+			<IsHalted<T, I>>::put(true);
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Stopping pallet operations: authority {:?} of set {} has equivocated.",
+				offender,
+				authority_set.set_id,
+			);
+
+			Self::deposit_event(Event::EquivocationReported(authority_set.set_id, offender));
+			Ok(Pays::No.into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// The authority of the current authority set has equivocated. All pallet operations have
+		/// been halted.
+		EquivocationReported(SetId, AuthorityId),
+		/// The number of finalized headers to keep in the storage has been changed. The second
+		/// value is the maximal number of stored headers that are out of the new window and are
+		/// waiting for pruning.
+		HeadersToKeepChanged(u32, u32),
+		/// All headers that have been out of the window of kept headers are pruned.
+		StaleHeadersPruned,
+		/// The pallet has been re-initialized. The best finalized header has been changed from
+		/// the first header to the second one, and the authority set with given id has become
+		/// the current set.
+		Reinitialized(BridgedBlockHash<T, I>, BridgedBlockHash<T, I>, SetId),
 	}
 
 	/// The current number of requests which have written to storage.
@@ -283,6 +436,18 @@ pub mod pallet {
 	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, u32, ValueQuery>;
 
+	/// Number of finalized headers to keep in the storage, if it is lower than the
+	/// `HeadersToKeep` of the pallet configuration.
+	#[pallet::storage]
+	pub(super) type HeadersToKeepLimit<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, OptionQuery>;
+
+	/// Number of ring buffer positions before the last `HeadersToKeepLimit` headers, that may
+	/// still hold headers waiting for pruning.
+	#[pallet::storage]
+	pub(super) type StaleHeadersCount<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, u32, ValueQuery>;
+
 	/// Headers which have been imported into the pallet.
 	#[pallet::storage]
 	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
@@ -293,6 +458,13 @@ pub mod pallet {
 	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, bp_header_chain::AuthoritySet, ValueQuery>;
 
+	/// Number of the block (of this chain), at which the current GRANDPA authority set has been
+	/// imported. It is `None` if the pallet is not yet initialized.
+	#[pallet::storage]
+	#[pallet::getter(fn authority_set_imported_at)]
+	pub(super) type AuthoritySetImportedAt<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::BlockNumber, OptionQuery>;
+
 	/// Optional pallet owner.
 	///
 	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
@@ -363,6 +535,15 @@ pub mod pallet {
 		Halted,
 		/// The storage proof doesn't contains storage root. So it is invalid for given header.
 		StorageRootMismatch,
+		/// The equivocation proof is for an authority set other than the current one.
+		UnknownAuthoritySet,
+		/// The equivocation proof is invalid, or the offender is not an authority.
+		InvalidEquivocationProof,
+		/// The number of headers to keep is zero or exceeds the `HeadersToKeep` of the pallet
+		/// configuration.
+		InvalidHeadersToKeep,
+		/// The authority set of the re-initialization data is older than the current set.
+		OldAuthoritySet,
 	}
 
 	/// Check the given header for a GRANDPA scheduled authority set change. If a change
@@ -397,6 +578,7 @@ pub mod pallet {
 			// Since our header schedules a change and we know the delay is 0, it must also enact
 			// the change.
 			<CurrentAuthoritySet<T, I>>::put(&next_authorities);
+			<AuthoritySetImportedAt<T, I>>::put(frame_system::Pallet::<T>::block_number());
 			change_enacted = true;
 
 			log::info!(
@@ -461,27 +643,73 @@ pub mod pallet {
 		<ImportedHashes<T, I>>::insert(index, hash);
 
 		// Update ring buffer pointer and remove old header.
-		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
+		let capacity = T::HeadersToKeep::get();
+		<ImportedHashesPointer<T, I>>::put((index + 1) % capacity);
 		if let Ok(hash) = pruning {
 			log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", hash);
 			<ImportedHeaders<T, I>>::remove(hash);
 		}
+
+		// The ring buffer holds `headers_to_keep` recent headers, preceded by `stale_headers`
+		// headers that are waiting for pruning. The oldest of them is out of the window now, unless
+		// it has just been overwritten. If there are stale headers, the next one is also pruned,
+		// so that the backlog shrinks by one with every import.
+		let headers_to_keep = headers_to_keep_limit::<T, I>();
+		let stale_headers = <StaleHeadersCount<T, I>>::get();
+		let stored_headers = headers_to_keep.saturating_add(stale_headers).min(capacity);
+		let oldest = (index + capacity - stored_headers) % capacity;
+		if oldest != index {
+			prune_header::<T, I>(oldest);
+		}
+		if stale_headers != 0 {
+			prune_header::<T, I>((oldest + 1) % capacity);
+			<StaleHeadersCount<T, I>>::put(stale_headers - 1);
+			if stale_headers == 1 {
+				Pallet::<T, I>::deposit_event(Event::StaleHeadersPruned);
+			}
+		}
+	}
+
+	/// Remove the header at given position of the ring buffer from the storage.
+	fn prune_header<T: Config<I>, I: 'static>(index: u32) {
+		if let Some(hash) = <ImportedHashes<T, I>>::take(index) {
+			log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", hash);
+			<ImportedHeaders<T, I>>::remove(hash);
+		}
+	}
+
+	/// Returns the number of finalized headers to keep in the storage.
+	pub(crate) fn headers_to_keep_limit<T: Config<I>, I: 'static>() -> u32 {
+		let capacity = T::HeadersToKeep::get();
+		<HeadersToKeepLimit<T, I>>::get().map_or(capacity, |limit| limit.min(capacity))
 	}
 
 	/// Since this writes to storage with no real checks this should only be used in functions that
 	/// were called by a trusted origin.
 	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(
 		init_params: super::InitializationData<BridgedHeader<T, I>>,
+	) {
+		<ImportedHashesPointer<T, I>>::put(0);
+		reinitialize_bridge::<T, I>(init_params);
+	}
+
+	/// Make the given header the best finalized header and the given authority set the current
+	/// set. Previously imported headers are kept in the storage.
+	///
+	/// Since this writes to storage with no real checks this should only be used in functions that
+	/// were called by a trusted origin.
+	pub(crate) fn reinitialize_bridge<T: Config<I>, I: 'static>(
+		init_params: super::InitializationData<BridgedHeader<T, I>>,
 	) {
 		let super::InitializationData { header, authority_list, set_id, is_halted } = init_params;
 
 		let initial_hash = header.hash();
 		<InitialHash<T, I>>::put(initial_hash);
-		<ImportedHashesPointer<T, I>>::put(0);
 		insert_header::<T, I>(*header, initial_hash);
 
 		let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
 		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<AuthoritySetImportedAt<T, I>>::put(frame_system::Pallet::<T>::block_number());
 
 		<IsHalted<T, I>>::put(is_halted);
 	}
@@ -548,6 +776,11 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		})
 	}
 
+	/// Get the id of the current GRANDPA authority set, that finalizes next headers.
+	pub fn current_authority_set_id() -> SetId {
+		<CurrentAuthoritySet<T, I>>::get().set_id
+	}
+
 	/// Check if a particular header is known to the bridge pallet.
 	pub fn is_known_header(hash: BridgedBlockHash<T, I>) -> bool {
 		<ImportedHeaders<T, I>>::contains_key(hash)
@@ -570,6 +803,26 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	}
 }
 
+impl<T: Config<I>, I: 'static> bp_header_chain::FinalityBackend for Pallet<T, I> {
+	type Chain = T::BridgedChain;
+
+	fn best_finalized_number() -> BridgedBlockNumber<T, I> {
+		*Self::best_finalized().number()
+	}
+
+	fn finalized_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
+	fn parse_finalized_storage_proof<R>(
+		hash: BridgedBlockHash<T, I>,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		Pallet::<T, I>::parse_finalized_storage_proof(hash, storage_proof, parse)
+	}
+}
+
 pub(crate) fn find_scheduled_change<H: HeaderT>(
 	header: &H,
 ) -> Option<sp_finality_grandpa::ScheduledChange<H::Number>> {
@@ -621,10 +874,13 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::mock::{run_test, test_header, Origin, TestHeader, TestNumber, TestRuntime};
+	use crate::mock::{
+		run_test, test_header, HeadersToKeep, Origin, TestHeader, TestNumber, TestRuntime,
+	};
 	use bp_test_utils::{
-		authority_list, make_default_justification, make_justification_for_header,
-		JustificationGeneratorParams, ALICE, BOB,
+		authority_list, header_id, make_default_justification, make_justification_for_header,
+		signed_precommit, Account, JustificationGeneratorParams, ALICE, BOB, DAVE,
+		TEST_GRANDPA_ROUND,
 	};
 	use codec::Encode;
 	use frame_support::{
@@ -674,6 +930,33 @@ mod tests {
 		let _ = Pallet::<TestRuntime>::on_initialize(current_number);
 	}
 
+	fn precommit_equivocation(
+		offender: Account,
+		signer: Account,
+		set_id: SetId,
+		second_target: u8,
+	) -> Box<EquivocationProof<BridgedBlockHash<TestRuntime, ()>, TestNumber>> {
+		let precommit = |target| {
+			let signed = signed_precommit::<TestHeader>(
+				&signer,
+				header_id::<TestHeader>(target),
+				TEST_GRANDPA_ROUND,
+				set_id,
+			);
+			(signed.precommit, signed.signature)
+		};
+
+		Box::new(EquivocationProof::new(
+			set_id,
+			sp_finality_grandpa::Equivocation::Precommit(finality_grandpa::Equivocation {
+				round_number: TEST_GRANDPA_ROUND,
+				identity: offender.into(),
+				first: precommit(1),
+				second: precommit(second_target),
+			}),
+		))
+	}
+
 	fn change_log(delay: u64) -> Digest {
 		let consensus_log =
 			ConsensusLog::<TestNumber>::ScheduledChange(sp_finality_grandpa::ScheduledChange {
@@ -923,6 +1206,8 @@ mod tests {
 	fn importing_header_enacts_new_authority_set() {
 		run_test(|| {
 			initialize_substrate_bridge();
+			assert_eq!(Pallet::<TestRuntime>::authority_set_imported_at(), Some(0));
+			frame_system::Pallet::<TestRuntime>::set_block_number(5);
 
 			let next_set_id = 2;
 			let next_authorities = vec![(ALICE.into(), 1), (BOB.into(), 1)];
@@ -957,6 +1242,7 @@ mod tests {
 				<CurrentAuthoritySet<TestRuntime>>::get(),
 				bp_header_chain::AuthoritySet::new(next_authorities, next_set_id),
 			);
+			assert_eq!(Pallet::<TestRuntime>::authority_set_imported_at(), Some(5));
 		})
 	}
 
@@ -1010,6 +1296,72 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn equivocation_of_current_authority_halts_pallet() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			assert_ok!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					precommit_equivocation(ALICE, ALICE, 1, 2),
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+			assert!(IsHalted::<TestRuntime>::get());
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events().pop().map(|record| record.event),
+				Some(crate::mock::Event::Grandpa(Event::EquivocationReported(1, ALICE.into()))),
+			);
+
+			assert_noop!(submit_finality_proof(1), Error::<TestRuntime>::Halted);
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					precommit_equivocation(BOB, BOB, 1, 2),
+				),
+				Error::<TestRuntime>::Halted,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_invalid_equivocation_proofs() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let report =
+				|proof| Pallet::<TestRuntime>::report_equivocation(Origin::signed(1), proof);
+
+			// the offender must be an authority of the current set
+			assert_noop!(
+				report(precommit_equivocation(ALICE, ALICE, 2, 2)),
+				Error::<TestRuntime>::UnknownAuthoritySet,
+			);
+			assert_noop!(
+				report(precommit_equivocation(DAVE, DAVE, 1, 2)),
+				Error::<TestRuntime>::InvalidEquivocationProof,
+			);
+
+			// votes for the same target are not an equivocation
+			assert_noop!(
+				report(precommit_equivocation(ALICE, ALICE, 1, 1)),
+				Error::<TestRuntime>::InvalidEquivocationProof,
+			);
+
+			// votes must be signed by the offender
+			assert_noop!(
+				report(precommit_equivocation(ALICE, BOB, 1, 2)),
+				Error::<TestRuntime>::InvalidEquivocationProof,
+			);
+
+			assert!(!IsHalted::<TestRuntime>::get());
+		})
+	}
+
 	#[test]
 	fn parse_finalized_storage_proof_rejects_proof_on_unknown_header() {
 		run_test(|| {
@@ -1043,6 +1395,45 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn parse_finalized_storage_accepts_proof_on_ancestor_of_best_finalized_header() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+
+			let mut ancestor = test_header(2);
+			ancestor.set_state_root(state_root);
+			let ancestor_hash = ancestor.hash();
+			insert_header::<TestRuntime, ()>(ancestor, ancestor_hash);
+
+			// proof at the ancestor is accepted while it is one of `HeadersToKeep` last headers
+			for number in 3..2 + HeadersToKeep::get() as u8 {
+				let header = test_header(number.into());
+				insert_header::<TestRuntime, ()>(header.clone(), header.hash());
+			}
+			assert_ne!(<BestFinalized<TestRuntime>>::get(), ancestor_hash);
+			assert_ok!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					ancestor_hash,
+					storage_proof.clone(),
+					|_| (),
+				),
+				(),
+			);
+
+			// and is rejected once the ancestor is pruned
+			let header = test_header((2 + HeadersToKeep::get()).into());
+			insert_header::<TestRuntime, ()>(header.clone(), header.hash());
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					ancestor_hash,
+					storage_proof,
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownHeader,
+			);
+		});
+	}
+
 	#[test]
 	fn rate_limiter_disallows_imports_once_limit_is_hit_in_single_block() {
 		run_test(|| {
@@ -1054,6 +1445,34 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn rate_limiter_counts_free_mandatory_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+
+			// header that enacts new authority set is imported for free
+			let mut header = test_header(2);
+			header.digest = change_log(0);
+			let justification = make_default_justification(&header);
+			assert_ok!(
+				Pallet::<TestRuntime>::submit_finality_proof(
+					Origin::signed(1),
+					Box::new(header),
+					justification
+				),
+				PostDispatchInfo {
+					actual_weight: None,
+					pays_fee: frame_support::weights::Pays::No,
+				},
+			);
+
+			// but it still counts towards the request limit
+			assert_eq!(<RequestCount<TestRuntime>>::get(), 2);
+			assert_err!(submit_finality_proof(3), <Error<TestRuntime>>::TooManyRequests);
+		})
+	}
+
 	#[test]
 	fn rate_limiter_invalid_requests_do_not_count_towards_request_count() {
 		run_test(|| {
@@ -1150,6 +1569,194 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn lowered_headers_to_keep_prunes_stale_headers_gradually() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=4 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 2));
+			assert_eq!(<StaleHeadersCount<TestRuntime>>::get(), 3);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events().pop().map(|record| record.event),
+				Some(crate::mock::Event::Grandpa(Event::HeadersToKeepChanged(2, 3))),
+			);
+
+			let known_headers = |best: u8| {
+				(0..=best)
+					.map(|number| {
+						Pallet::<TestRuntime>::is_known_header(test_header(number.into()).hash())
+					})
+					.collect::<Vec<_>>()
+			};
+
+			// two headers are pruned with every import until there are no stale headers
+			assert_ok!(submit_finality_proof(5));
+			next_block();
+			assert_eq!(known_headers(5), vec![false, false, true, true, true, true]);
+
+			assert_ok!(submit_finality_proof(6));
+			next_block();
+			assert_eq!(known_headers(6), vec![false, false, false, false, true, true, true]);
+
+			assert_ok!(submit_finality_proof(7));
+			assert_eq!(
+				known_headers(7),
+				vec![false, false, false, false, false, false, true, true],
+			);
+			assert_eq!(<StaleHeadersCount<TestRuntime>>::get(), 0);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events().pop().map(|record| record.event),
+				Some(crate::mock::Event::Grandpa(Event::StaleHeadersPruned)),
+			);
+
+			// and then the oldest header is pruned with every import
+			next_block();
+			assert_ok!(submit_finality_proof(8));
+			assert_eq!(
+				known_headers(8),
+				vec![false, false, false, false, false, false, false, true, true],
+			);
+		})
+	}
+
+	#[test]
+	fn raised_headers_to_keep_stops_pruning_of_headers_in_new_window() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for header in 1..=4 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 2));
+			assert_ok!(submit_finality_proof(5));
+			next_block();
+			assert_eq!(<StaleHeadersCount<TestRuntime>>::get(), 2);
+
+			// headers 2 and 3 are stale, but they're back in the window now
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 4));
+			assert_eq!(<StaleHeadersCount<TestRuntime>>::get(), 0);
+
+			assert_ok!(submit_finality_proof(6));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			for header in 3..=6 {
+				assert!(Pallet::<TestRuntime>::is_known_header(test_header(header).hash()));
+			}
+		})
+	}
+
+	#[test]
+	fn set_headers_to_keep_rejects_invalid_values_and_origins() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(Origin::signed(1), 2),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(Origin::root(), 0),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_headers_to_keep(
+					Origin::root(),
+					HeadersToKeep::get() + 1,
+				),
+				Error::<TestRuntime>::InvalidHeadersToKeep,
+			);
+
+			PalletOwner::<TestRuntime>::put(1);
+			assert_ok!(Pallet::<TestRuntime>::set_headers_to_keep(
+				Origin::signed(1),
+				HeadersToKeep::get(),
+			));
+		})
+	}
+
+	#[test]
+	fn reinitialize_resets_finality_state_and_keeps_imported_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+			let previous_best_finalized = test_header(1).hash();
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			next_block();
+
+			let header = test_header(10);
+			assert_ok!(Pallet::<TestRuntime>::reinitialize(
+				Origin::root(),
+				InitializationData {
+					header: Box::new(header.clone()),
+					authority_list: authority_list(),
+					set_id: 2,
+					is_halted: false,
+				},
+			));
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events().pop().map(|record| record.event),
+				Some(crate::mock::Event::Grandpa(Event::Reinitialized(
+					previous_best_finalized,
+					header.hash(),
+					2,
+				))),
+			);
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), header);
+			assert_eq!(Pallet::<TestRuntime>::current_authority_set_id(), 2);
+			assert!(!IsHalted::<TestRuntime>::get());
+			assert!(Pallet::<TestRuntime>::is_known_header(previous_best_finalized));
+
+			// headers must be finalized by the new authority set now
+			assert_noop!(submit_finality_proof(11), Error::<TestRuntime>::InvalidJustification);
+			let header = test_header(11);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				set_id: 2,
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				Box::new(header),
+				justification,
+			));
+		})
+	}
+
+	#[test]
+	fn reinitialize_rejects_invalid_data_and_origins() {
+		run_test(|| {
+			let init_data = |set_id, authority_list| InitializationData {
+				header: Box::new(test_header(10)),
+				authority_list,
+				set_id,
+				is_halted: false,
+			};
+
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), init_data(1, authority_list())),
+				Error::<TestRuntime>::NotInitialized,
+			);
+
+			initialize_substrate_bridge();
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(
+					Origin::signed(1),
+					init_data(1, authority_list()),
+				),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), init_data(0, authority_list())),
+				Error::<TestRuntime>::OldAuthoritySet,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::reinitialize(Origin::root(), init_data(1, vec![])),
+				Error::<TestRuntime>::InvalidAuthoritySet,
+			);
+		})
+	}
+
 	#[test]
 	fn storage_keys_computed_properly() {
 		assert_eq!(
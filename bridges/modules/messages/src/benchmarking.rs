@@ -34,6 +34,10 @@ use sp_std::{collections::vec_deque::VecDeque, ops::RangeInclusive, prelude::*};
 
 const SEED: u32 = 0;
 
+/// Number of lanes, other than the `Config::bench_lane_id()`, that have their state included in
+/// the worst-case delivery proof.
+const BENCH_OTHER_LANES: u32 = 16;
+
 /// Pallet we're benchmarking here.
 pub struct Pallet<T: Config<I>, I: 'static>(crate::Pallet<T, I>);
 
@@ -82,6 +86,8 @@ pub struct MessageDeliveryProofParams<ThisChainAccountId> {
 	pub lane: LaneId,
 	/// The proof needs to include this inbound lane data.
 	pub inbound_lane_data: InboundLaneData<ThisChainAccountId>,
+	/// The proof needs to include the same inbound lane data for all of these lanes.
+	pub other_lanes: Vec<LaneId>,
 	/// Proof size requirements.
 	pub size: ProofSize,
 }
@@ -92,6 +98,14 @@ pub trait Config<I: 'static>: crate::Config<I> {
 	fn bench_lane_id() -> LaneId {
 		Default::default()
 	}
+	/// Lanes, other than the `bench_lane_id()`, that have state at the bridged chain in the worst
+	/// case.
+	fn bench_other_lanes() -> Vec<LaneId> {
+		(1..=BENCH_OTHER_LANES)
+			.map(|lane| lane.to_be_bytes())
+			.filter(|lane| *lane != Self::bench_lane_id())
+			.collect()
+	}
 	/// Get maximal size of the message payload.
 	fn maximal_message_size() -> u32;
 	/// Return id of relayer account at the bridged chain.
@@ -517,6 +531,7 @@ benchmarks_instance_pallet! {
 				}].into_iter().collect(),
 				last_confirmed_nonce: 0,
 			},
+			other_lanes: Vec::new(),
 			size: ProofSize::Minimal(0),
 		});
 	}: receive_messages_delivery_proof(RawOrigin::Signed(relayer_id.clone()), proof, relayers_state)
@@ -560,6 +575,7 @@ benchmarks_instance_pallet! {
 				}].into_iter().collect(),
 				last_confirmed_nonce: 0,
 			},
+			other_lanes: Vec::new(),
 			size: ProofSize::Minimal(0),
 		});
 	}: receive_messages_delivery_proof(RawOrigin::Signed(relayer_id.clone()), proof, relayers_state)
@@ -606,6 +622,7 @@ benchmarks_instance_pallet! {
 				].into_iter().collect(),
 				last_confirmed_nonce: 0,
 			},
+			other_lanes: Vec::new(),
 			size: ProofSize::Minimal(0),
 		});
 	}: receive_messages_delivery_proof(RawOrigin::Signed(relayer1_id.clone()), proof, relayers_state)
@@ -613,6 +630,53 @@ benchmarks_instance_pallet! {
 		ensure_relayer_rewarded::<T, I>(&relayer1_id, &relayer1_balance);
 		ensure_relayer_rewarded::<T, I>(&relayer2_id, &relayer2_balance);
 	}
+
+	// Benchmark `receive_messages_delivery_proof` extrinsic with following conditions:
+	// * `i` relayers are rewarded for relaying single message each, up to the maximal number of
+	//   unrewarded relayer entries at the inbound lane;
+	// * the proof also includes inbound lane states of other lanes, with the same number of
+	//   relayer entries;
+	// * relayer accounts do not exist (in practice they need to exist in production environment).
+	//
+	// This is the worst case of the delivery confirmation transaction. Once its weights are
+	// generated, they may be used to verify the `receive_messages_delivery_proof` weight, computed
+	// from the benchmarks above.
+	receive_delivery_proof_for_max_relayers_at_multiple_lanes {
+		let i in 1 .. T::MaxUnrewardedRelayerEntriesAtInboundLane::get() as u32;
+
+		let relayers_fund_id = crate::relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>();
+		let relayers: Vec<T::AccountId> = (0..i).map(|j| account("relayer", j, SEED)).collect();
+		let relayer_balances: Vec<_> = relayers.iter().map(T::account_balance).collect();
+		T::endow_account(&relayers_fund_id);
+
+		// send messages that we're going to confirm
+		for _ in 0..i {
+			send_regular_message::<T, I>();
+		}
+
+		let relayers_state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: i as MessageNonce,
+			messages_in_oldest_entry: 1,
+			total_messages: i as MessageNonce,
+		};
+		let proof = T::prepare_message_delivery_proof(MessageDeliveryProofParams {
+			lane: T::bench_lane_id(),
+			inbound_lane_data: InboundLaneData {
+				relayers: relayers.iter().zip(1..).map(|(relayer, nonce)| UnrewardedRelayer {
+					relayer: relayer.clone(),
+					messages: DeliveredMessages::new(nonce, true),
+				}).collect(),
+				last_confirmed_nonce: 0,
+			},
+			other_lanes: T::bench_other_lanes(),
+			size: ProofSize::Minimal(0),
+		});
+	}: receive_messages_delivery_proof(RawOrigin::Signed(relayers[0].clone()), proof, relayers_state)
+	verify {
+		for (relayer, relayer_balance) in relayers.iter().zip(relayer_balances.iter()) {
+			ensure_relayer_rewarded::<T, I>(relayer, relayer_balance);
+		}
+	}
 }
 
 fn send_regular_message<T: Config<I>, I: 'static>() {
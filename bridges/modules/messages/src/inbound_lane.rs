@@ -177,8 +177,8 @@ mod tests {
 		inbound_lane,
 		mock::{
 			dispatch_result, message_data, run_test, unrewarded_relayer, TestMessageDispatch,
-			TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
-			TEST_RELAYER_C,
+			TestRuntime, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_LIMITED_LANE_ID, TEST_RELAYER_A,
+			TEST_RELAYER_B, TEST_RELAYER_C,
 		},
 		RuntimeInboundLaneStorage,
 	};
@@ -408,6 +408,53 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn fails_to_receive_messages_above_unrewarded_relayer_entries_limit_of_limited_lane() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LIMITED_LANE_ID);
+			let mut receive_message = |relayer, nonce| {
+				lane.receive_message::<TestMessageDispatch, _>(
+					&relayer,
+					&relayer,
+					nonce,
+					message_data(REGULAR_PAYLOAD).into(),
+				)
+			};
+
+			let dispatched = ReceivalResult::Dispatched(dispatch_result(0));
+			assert_eq!(receive_message(TEST_RELAYER_A, 1), dispatched);
+			assert_eq!(receive_message(TEST_RELAYER_B, 2), dispatched);
+			assert_eq!(
+				receive_message(TEST_RELAYER_C, 3),
+				ReceivalResult::TooManyUnrewardedRelayers,
+			);
+		});
+	}
+
+	#[test]
+	fn fails_to_receive_messages_above_unconfirmed_messages_limit_of_limited_lane() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LIMITED_LANE_ID);
+			let mut receive_message = |relayer, nonce| {
+				lane.receive_message::<TestMessageDispatch, _>(
+					&relayer,
+					&relayer,
+					nonce,
+					message_data(REGULAR_PAYLOAD).into(),
+				)
+			};
+
+			let dispatched = ReceivalResult::Dispatched(dispatch_result(0));
+			assert_eq!(receive_message(TEST_RELAYER_A, 1), dispatched);
+			assert_eq!(receive_message(TEST_RELAYER_A, 2), dispatched);
+			assert_eq!(receive_message(TEST_RELAYER_A, 3), dispatched);
+			assert_eq!(
+				receive_message(TEST_RELAYER_A, 4),
+				ReceivalResult::TooManyUnconfirmedMessages,
+			);
+		});
+	}
+
 	#[test]
 	fn correctly_receives_following_messages_from_two_relayers_alternately() {
 		run_test(|| {
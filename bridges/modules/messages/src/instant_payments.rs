@@ -19,7 +19,7 @@
 //! The payment is first transferred to a special `relayers-fund` account and only transferred
 //! to the actual relayer in case confirmation is received.
 
-use crate::OutboundMessages;
+use crate::{CancelledOutboundMessages, OutboundMessages};
 
 use bp_messages::{
 	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, SenderOrigin},
@@ -100,6 +100,21 @@ where
 		.map_err(Into::into)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &T::AccountId,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		Currency::transfer(
+			relayer_fund_account,
+			submitter,
+			*fee,
+			// the relayer fund account must stay above ED (needs to be pre-funded)
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(Into::into)
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: LaneId,
 		messages_relayers: VecDeque<UnrewardedRelayer<T::AccountId>>,
@@ -141,6 +156,11 @@ where
 		// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
 		let mut relayer_reward = relayers_rewards.entry(entry.relayer).or_default();
 		for nonce in nonce_begin..nonce_end + 1 {
+			// cancelled messages are not rewarded
+			if CancelledOutboundMessages::<T, I>::contains_key(MessageKey { lane_id, nonce }) {
+				continue
+			}
+
 			let message_data = OutboundMessages::<T, I>::get(MessageKey { lane_id, nonce })
 				.expect("message was just confirmed; we never prune unconfirmed messages; qed");
 			relayer_reward.reward = relayer_reward.reward.saturating_add(&message_data.fee);
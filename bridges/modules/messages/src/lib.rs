@@ -50,21 +50,22 @@ use crate::{
 
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed,
-		OnMessageAccepted, SendMessageArtifacts, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, MessageExpiration,
+		OnDeliveryConfirmed, OnMessageAccepted, SendMessageArtifacts, TargetHeaderChain,
 	},
 	target_chain::{
-		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain,
+		DispatchMessage, InboundLaneLimits, MessageDispatch, ProvedLaneMessages, ProvedMessages,
+		SourceHeaderChain,
 	},
 	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageKey,
 	MessageNonce, OperatingMode, OutboundLaneData, Parameter as MessagesParameter,
-	UnrewardedRelayersState,
+	UnrewardedRelayersState, VerificationError,
 };
 use bp_runtime::{ChainId, Size};
 use codec::{Decode, Encode};
 use frame_support::{
 	fail,
-	traits::Get,
+	traits::{EnsureOrigin, Get},
 	weights::{Pays, PostDispatchInfo},
 };
 use frame_system::RawOrigin;
@@ -109,8 +110,12 @@ pub mod pallet {
 		/// Pallet parameter that is opaque to the pallet itself, but may be used by the runtime
 		/// for integrating the pallet.
 		///
-		/// All pallet parameters may only be updated either by the root, or by the pallet owner.
+		/// All pallet parameters may only be updated by the `AdminOrigin`.
 		type Parameter: MessagesParameter;
+		/// Origin that may update pallet parameters and change the operating mode of the pallet.
+		///
+		/// Use `EnsureOwnerOrRoot` to allow it to the root and the `PalletOwner`.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
 
 		/// Maximal number of messages that may be pruned during maintenance. Maintenance occurs
 		/// whenever new message is sent. The reason is that if you want to use lane, you should
@@ -139,6 +144,9 @@ pub mod pallet {
 		/// Transaction that is declaring more messages than this value, will be rejected. Even if
 		/// these messages are from different lanes.
 		type MaxUnconfirmedMessagesAtInboundLane: Get<MessageNonce>;
+		/// Limits of individual inbound lanes, that may be lower than
+		/// `MaxUnrewardedRelayerEntriesAtInboundLane` and `MaxUnconfirmedMessagesAtInboundLane`.
+		type InboundLaneLimits: InboundLaneLimits;
 
 		/// Payload type of outbound messages. This payload is dispatched on the bridged chain.
 		type OutboundPayload: Parameter + Size;
@@ -181,6 +189,8 @@ pub mod pallet {
 			Self::AccountId,
 			Self::OutboundMessageFee,
 		>;
+		/// Expiration of outbound messages.
+		type MessageExpiration: MessageExpiration<Self::OutboundPayload>;
 		/// Handler for accepted messages.
 		type OnMessageAccepted: OnMessageAccepted;
 		/// Handler for delivered messages.
@@ -237,13 +247,13 @@ pub mod pallet {
 
 		/// Halt or resume all/some pallet operations.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called by the `AdminOrigin`.
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
 		pub fn set_operating_mode(
 			origin: OriginFor<T>,
 			operating_mode: OperatingMode,
 		) -> DispatchResult {
-			ensure_owner_or_root::<T, I>(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			PalletOperatingMode::<T, I>::put(operating_mode);
 			log::info!(
 				target: "runtime::bridge-messages",
@@ -255,7 +265,7 @@ pub mod pallet {
 
 		/// Update pallet parameter.
 		///
-		/// May only be called either by root, or by `PalletOwner`.
+		/// May only be called by the `AdminOrigin`.
 		///
 		/// The weight is: single read for permissions check + 2 writes for parameter value and
 		/// event.
@@ -264,7 +274,7 @@ pub mod pallet {
 			origin: OriginFor<T>,
 			parameter: T::Parameter,
 		) -> DispatchResult {
-			ensure_owner_or_root::<T, I>(origin)?;
+			T::AdminOrigin::ensure_origin(origin)?;
 			parameter.save();
 			Self::deposit_event(Event::ParameterUpdated(parameter));
 			Ok(())
@@ -278,12 +288,17 @@ pub mod pallet {
 			payload: T::OutboundPayload,
 			delivery_and_dispatch_fee: T::OutboundMessageFee,
 		) -> DispatchResultWithPostInfo {
-			crate::send_message::<T, I>(origin, lane_id, payload, delivery_and_dispatch_fee).map(
-				|sent_message| PostDispatchInfo {
-					actual_weight: Some(sent_message.weight),
-					pays_fee: Pays::Yes,
-				},
+			crate::send_message::<T, I>(
+				origin,
+				lane_id,
+				payload,
+				delivery_and_dispatch_fee,
+				Zero::zero(),
 			)
+			.map(|sent_message| PostDispatchInfo {
+				actual_weight: Some(sent_message.weight),
+				pays_fee: Pays::Yes,
+			})
 		}
 
 		/// Pay additional fee for the message.
@@ -309,6 +324,11 @@ pub mod pallet {
 				nonce <= lane.data().latest_generated_nonce,
 				Error::<T, I>::MessageIsNotYetSent
 			);
+			// the fee of the cancelled message may be refunded, so we're not accepting more funds
+			ensure!(
+				!CancelledOutboundMessages::<T, I>::contains_key(MessageKey { lane_id, nonce }),
+				Error::<T, I>::MessageIsAlreadyCancelled
+			);
 
 			// withdraw additional fee from submitter
 			T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
@@ -342,6 +362,9 @@ pub mod pallet {
 				message_data.fee = message_data.fee.saturating_add(&additional_fee);
 				message_data.payload.len()
 			});
+			OutboundMessageTips::<T, I>::mutate(message_key, |relayer_tip| {
+				*relayer_tip = relayer_tip.saturating_add(&additional_fee)
+			});
 
 			// compute actual dispatch weight that depends on the stored message size
 			let actual_weight = sp_std::cmp::min(
@@ -352,6 +375,70 @@ pub mod pallet {
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
 
+		/// Cancel the message whose delivery is not yet confirmed.
+		///
+		/// May only be called either by root, or by the message submitter. Expired messages may
+		/// be cancelled by anyone.
+		///
+		/// The message may already be delivered (and even dispatched) at the bridged chain, so
+		/// the cancellation is only settled when the delivery of the message is confirmed. If the
+		/// bridged chain reports that it has dispatched the message successfully, the cancellation
+		/// is rejected and relayers are rewarded as usual. Otherwise, the message fee (including
+		/// any additional fee) is refunded to the submitter and relayers are not rewarded for
+		/// delivering the message. The payload of the cancelled message is cleared, so it fails
+		/// to dispatch if it is delivered using the proof of later state of this chain.
+		///
+		/// The weight of the call includes the cost of refunding the fee during settlement.
+		#[pallet::weight(
+			T::WeightInfo::maximal_increase_message_fee()
+				.saturating_add(T::DbWeight::get().reads_writes(2, 1))
+		)]
+		pub fn cancel_message(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+		) -> DispatchResultWithPostInfo {
+			ensure_not_halted::<T, I>()?;
+			let lane = outbound_lane::<T, I>(lane_id);
+			ensure!(
+				nonce > lane.data().latest_received_nonce,
+				Error::<T, I>::MessageIsAlreadyDelivered
+			);
+			ensure!(
+				nonce <= lane.data().latest_generated_nonce,
+				Error::<T, I>::MessageIsNotYetSent
+			);
+
+			let message_key = MessageKey { lane_id, nonce };
+			ensure!(
+				!CancelledOutboundMessages::<T, I>::contains_key(&message_key),
+				Error::<T, I>::MessageIsAlreadyCancelled
+			);
+			let submitter = OutboundMessageSubmitters::<T, I>::get(&message_key);
+			match origin.into() {
+				Ok(RawOrigin::Root) => (),
+				Ok(RawOrigin::Signed(ref signer)) if Some(signer) == submitter.as_ref() => (),
+				Ok(RawOrigin::Signed(_)) if is_message_expired::<T, I>(&message_key) => (),
+				_ => return Err(BadOrigin.into()),
+			}
+
+			// clear the payload, so that the message isn't dispatched at the bridged chain if it
+			// is not yet delivered. The fee stays with the message until the cancellation is
+			// settled
+			OutboundMessages::<T, I>::mutate(&message_key, |message_data| {
+				let message_data = message_data.as_mut().expect(
+					"the message is sent and its delivery is not yet confirmed; \
+					so it is in the storage; qed",
+				);
+				message_data.payload = Vec::new();
+			});
+
+			CancelledOutboundMessages::<T, I>::insert(&message_key, ());
+			Self::deposit_event(Event::MessageCancellationRequested(lane_id, nonce));
+
+			Ok(().into())
+		}
+
 		/// Receive messages proof from bridged chain.
 		///
 		/// The weight of the call assumes that the transaction always brings outbound lane
@@ -404,7 +491,7 @@ pub mod pallet {
 					err,
 				);
 
-				Error::<T, I>::InvalidMessagesProof
+				verification_error::<T, I>(err.into(), Error::<T, I>::InvalidMessagesProof)
 			})?;
 
 			// dispatch messages and (optionally) update lane(s) state(s)
@@ -538,7 +625,10 @@ pub mod pallet {
 						err,
 					);
 
-					Error::<T, I>::InvalidMessagesDeliveryProof
+					verification_error::<T, I>(
+						err.into(),
+						Error::<T, I>::InvalidMessagesDeliveryProof,
+					)
 				})?;
 
 			// verify that the relayer has declared correct `lane_data::relayers` state
@@ -620,6 +710,9 @@ pub mod pallet {
 					},
 				}
 
+				// settle cancellations of confirmed messages before relayers are rewarded
+				settle_cancelled_messages::<T, I>(lane_id, &confirmed_messages);
+
 				// emit 'delivered' event
 				let received_range = confirmed_messages.begin..=confirmed_messages.end;
 				Self::deposit_event(Event::MessagesDelivered(lane_id, confirmed_messages));
@@ -645,6 +738,32 @@ pub mod pallet {
 
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
+
+		/// Send message over lane, paying the relayer tip on top of the delivery and dispatch fee.
+		///
+		/// The delivery and dispatch fee must cover the message costs on its own. The tip is
+		/// stored with the message and paid to the relayer together with the fee, when the
+		/// delivery is confirmed. So urgent messages may attract relayers faster.
+		#[pallet::weight(T::WeightInfo::send_message_weight(payload, T::DbWeight::get()))]
+		pub fn send_message_with_tip(
+			origin: OriginFor<T>,
+			lane_id: LaneId,
+			payload: T::OutboundPayload,
+			delivery_and_dispatch_fee: T::OutboundMessageFee,
+			relayer_tip: T::OutboundMessageFee,
+		) -> DispatchResultWithPostInfo {
+			crate::send_message::<T, I>(
+				origin,
+				lane_id,
+				payload,
+				delivery_and_dispatch_fee,
+				relayer_tip,
+			)
+			.map(|sent_message| PostDispatchInfo {
+				actual_weight: Some(sent_message.weight),
+				pays_fee: Pays::Yes,
+			})
+		}
 	}
 
 	#[pallet::event]
@@ -656,6 +775,13 @@ pub mod pallet {
 		MessageAccepted(LaneId, MessageNonce),
 		/// Messages in the inclusive range have been delivered to the bridged chain.
 		MessagesDelivered(LaneId, DeliveredMessages),
+		/// Cancellation of the message has been requested. It is settled when the delivery of
+		/// the message is confirmed.
+		MessageCancellationRequested(LaneId, MessageNonce),
+		/// Message has been cancelled and its fee has been refunded to the submitter.
+		MessageCancelled(LaneId, MessageNonce),
+		/// Message has been dispatched at the bridged chain, so its cancellation is rejected.
+		MessageCancellationRejected(LaneId, MessageNonce),
 	}
 
 	#[pallet::error]
@@ -686,6 +812,17 @@ pub mod pallet {
 		/// The number of actually confirmed messages is going to be larger than the number of
 		/// messages in the proof. This may mean that this or bridged chain storage is corrupted.
 		TryingToConfirmMoreMessagesThanExpected,
+		/// The message someone is trying to work with (i.e. cancel) is already cancelled.
+		MessageIsAlreadyCancelled,
+		/// The bridge rejects outbound messages.
+		OutboundMessagesRejected,
+		/// The conversion rate of the bridged tokens is stale, so the message fee can't be
+		/// verified.
+		StaleConversionRate,
+		/// The bridge rejects inbound messages.
+		InboundMessagesRejected,
+		/// The inbound message lane is disabled.
+		InboundLaneDisabled,
 	}
 
 	/// Optional pallet owner.
@@ -721,6 +858,30 @@ pub mod pallet {
 	pub type OutboundMessages<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, MessageKey, MessageData<T::OutboundMessageFee>>;
 
+	/// Accounts that have submitted queued outbound messages.
+	///
+	/// Messages that have been sent by unsigned origins have no entries here.
+	#[pallet::storage]
+	pub type OutboundMessageSubmitters<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, T::AccountId>;
+
+	/// Relayer tips, paid by submitters of queued outbound messages.
+	///
+	/// The tip is a part of the message fee, that has been paid on top of the delivery and
+	/// dispatch fee. It is kept apart from the `OutboundMessages` entry, so that the layout of
+	/// stored messages and of their storage proofs stays the same. Messages without tip have
+	/// no entries here.
+	#[pallet::storage]
+	pub type OutboundMessageTips<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, T::OutboundMessageFee, ValueQuery>;
+
+	/// Queued outbound messages, whose cancellation has been requested and is not yet settled.
+	///
+	/// Entries of messages that have been cancelled are kept until the messages are pruned.
+	#[pallet::storage]
+	pub type CancelledOutboundMessages<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, MessageKey, ()>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Initial pallet operating mode.
@@ -793,7 +954,7 @@ where
 		message: T::OutboundPayload,
 		delivery_and_dispatch_fee: T::OutboundMessageFee,
 	) -> Result<SendMessageArtifacts, Self::Error> {
-		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee)
+		crate::send_message::<T, I>(sender, lane, message, delivery_and_dispatch_fee, Zero::zero())
 	}
 }
 
@@ -803,6 +964,7 @@ fn send_message<T: Config<I>, I: 'static>(
 	lane_id: LaneId,
 	payload: T::OutboundPayload,
 	delivery_and_dispatch_fee: T::OutboundMessageFee,
+	relayer_tip: T::OutboundMessageFee,
 ) -> sp_std::result::Result<
 	SendMessageArtifacts,
 	sp_runtime::DispatchErrorWithPostInfo<PostDispatchInfo>,
@@ -821,7 +983,7 @@ fn send_message<T: Config<I>, I: 'static>(
 			err,
 		);
 
-		Error::<T, I>::MessageRejectedByChainVerifier
+		verification_error::<T, I>(err.into(), Error::<T, I>::MessageRejectedByChainVerifier)
 	})?;
 
 	// now let's enforce any additional lane rules
@@ -841,13 +1003,14 @@ fn send_message<T: Config<I>, I: 'static>(
 			err,
 		);
 
-		Error::<T, I>::MessageRejectedByLaneVerifier
+		verification_error::<T, I>(err.into(), Error::<T, I>::MessageRejectedByLaneVerifier)
 	})?;
 
-	// let's withdraw delivery and dispatch fee from submitter
+	// let's withdraw delivery and dispatch fee (and the relayer tip) from submitter
+	let fee = delivery_and_dispatch_fee.saturating_add(&relayer_tip);
 	T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
 		&submitter,
-		&delivery_and_dispatch_fee,
+		&fee,
 		&relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>(),
 	)
 	.map_err(|err| {
@@ -855,7 +1018,7 @@ fn send_message<T: Config<I>, I: 'static>(
 			target: "runtime::bridge-messages",
 			"Message to lane {:?} is rejected because submitter is unable to pay fee {:?}: {:?}",
 			lane_id,
-			delivery_and_dispatch_fee,
+			fee,
 			err,
 		);
 
@@ -865,8 +1028,14 @@ fn send_message<T: Config<I>, I: 'static>(
 	// finally, save message in outbound storage and emit event
 	let encoded_payload = payload.encode();
 	let encoded_payload_len = encoded_payload.len();
-	let nonce =
-		lane.send_message(MessageData { payload: encoded_payload, fee: delivery_and_dispatch_fee });
+	let nonce = lane.send_message(MessageData { payload: encoded_payload, fee });
+	if !relayer_tip.is_zero() {
+		OutboundMessageTips::<T, I>::insert(MessageKey { lane_id, nonce }, relayer_tip);
+	}
+	// remember the submitter, so that it may cancel the message
+	if let Ok(RawOrigin::Signed(submitter_account)) = submitter.into() {
+		OutboundMessageSubmitters::<T, I>::insert(MessageKey { lane_id, nonce }, submitter_account);
+	}
 	// Guaranteed to be called outside only when the message is accepted.
 	// We assume that the maximum weight call back used is `single_message_callback_overhead`, so do
 	// not perform complex db operation in callback. If you want to, put these magic logic in
@@ -932,6 +1101,104 @@ fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(
 	}
 }
 
+/// Origin check that passes for root and `PalletOwner`.
+pub struct EnsureOwnerOrRoot<T, I = ()>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> EnsureOrigin<T::Origin> for EnsureOwnerOrRoot<T, I> {
+	type Success = ();
+
+	fn try_origin(origin: T::Origin) -> Result<(), T::Origin> {
+		ensure_owner_or_root::<T, I>(origin.clone()).map_err(|_| origin)
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> T::Origin {
+		RawOrigin::Root.into()
+	}
+}
+
+/// Returns true if the queued outbound message has expired.
+fn is_message_expired<T: Config<I>, I: 'static>(message_key: &MessageKey) -> bool {
+	OutboundMessages::<T, I>::get(message_key)
+		.and_then(|message_data| T::OutboundPayload::decode(&mut &message_data.payload[..]).ok())
+		.map(|payload| T::MessageExpiration::is_expired(&payload))
+		.unwrap_or(false)
+}
+
+/// Settle cancellations of messages, whose delivery has just been confirmed.
+///
+/// The bridged chain reports the dispatch result of every delivered message. If the cancelled
+/// message has been dispatched successfully, the cancellation has come too late, so it is rejected
+/// and relayers are rewarded for delivering the message. Otherwise, the fee of the message is
+/// refunded to its submitter.
+fn settle_cancelled_messages<T: Config<I>, I: 'static>(
+	lane_id: LaneId,
+	confirmed_messages: &DeliveredMessages,
+) {
+	// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
+	for nonce in confirmed_messages.begin..=confirmed_messages.end {
+		let message_key = MessageKey { lane_id, nonce };
+		if !CancelledOutboundMessages::<T, I>::contains_key(&message_key) {
+			continue
+		}
+
+		if confirmed_messages.message_dispatch_result(nonce) {
+			CancelledOutboundMessages::<T, I>::remove(&message_key);
+			Pallet::<T, I>::deposit_event(Event::MessageCancellationRejected(lane_id, nonce));
+			continue
+		}
+
+		let fee = OutboundMessages::<T, I>::mutate(&message_key, |message_data| {
+			message_data
+				.as_mut()
+				.map(|message_data| sp_std::mem::take(&mut message_data.fee))
+				.unwrap_or_else(Zero::zero)
+		});
+		OutboundMessageTips::<T, I>::remove(&message_key);
+
+		// messages, sent by unsigned origins, are refunded to nobody
+		let submitter = OutboundMessageSubmitters::<T, I>::get(&message_key);
+		if let Some(submitter) = submitter.filter(|_| !fee.is_zero()) {
+			let refund_result =
+				T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+					&submitter,
+					&fee,
+					&relayer_fund_account_id::<T::AccountId, T::AccountIdConverter>(),
+				);
+			if let Err(err) = refund_result {
+				// the delivery is confirmed anyway, so the fee stays in the relayers fund
+				log::error!(
+					target: "runtime::bridge-messages",
+					"Failed to refund fee {:?} of the cancelled message {:?}/{:?} to {:?}: {:?}",
+					fee,
+					lane_id,
+					nonce,
+					submitter,
+					err,
+				);
+			}
+		}
+
+		Pallet::<T, I>::deposit_event(Event::MessageCancelled(lane_id, nonce));
+	}
+}
+
+/// Returns the pallet error for the rejection reason, reported by the chain-specific verifier.
+///
+/// If the verifier hasn't reported a specific reason, the `unspecified` error is returned.
+fn verification_error<T: Config<I>, I: 'static>(
+	reason: VerificationError,
+	unspecified: Error<T, I>,
+) -> Error<T, I> {
+	match reason {
+		VerificationError::Unspecified => unspecified,
+		VerificationError::OutboundMessagesRejected => Error::<T, I>::OutboundMessagesRejected,
+		VerificationError::StaleConversionRate => Error::<T, I>::StaleConversionRate,
+		VerificationError::InboundMessagesRejected => Error::<T, I>::InboundMessagesRejected,
+		VerificationError::InboundLaneDisabled => Error::<T, I>::InboundLaneDisabled,
+	}
+}
+
 /// Ensure that the pallet is in normal operational mode.
 fn ensure_normal_operating_mode<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
 	if PalletOperatingMode::<T, I>::get() != OperatingMode::Normal {
@@ -991,11 +1258,15 @@ impl<T: Config<I>, I: 'static> InboundLaneStorage for RuntimeInboundLaneStorage<
 	}
 
 	fn max_unrewarded_relayer_entries(&self) -> MessageNonce {
-		T::MaxUnrewardedRelayerEntriesAtInboundLane::get()
+		let max_entries = T::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+		T::InboundLaneLimits::max_unrewarded_relayer_entries(&self.lane_id)
+			.map_or(max_entries, |lane_max_entries| lane_max_entries.min(max_entries))
 	}
 
 	fn max_unconfirmed_messages(&self) -> MessageNonce {
-		T::MaxUnconfirmedMessagesAtInboundLane::get()
+		let max_messages = T::MaxUnconfirmedMessagesAtInboundLane::get();
+		T::InboundLaneLimits::max_unconfirmed_messages(&self.lane_id)
+			.map_or(max_messages, |lane_max_messages| lane_max_messages.min(max_messages))
 	}
 
 	fn data(&self) -> InboundLaneData<T::InboundRelayer> {
@@ -1056,7 +1327,11 @@ impl<T: Config<I>, I: 'static> OutboundLaneStorage for RuntimeOutboundLaneStorag
 	}
 
 	fn remove_message(&mut self, nonce: &MessageNonce) {
-		OutboundMessages::<T, I>::remove(MessageKey { lane_id: self.lane_id, nonce: *nonce });
+		let message_key = MessageKey { lane_id: self.lane_id, nonce: *nonce };
+		OutboundMessages::<T, I>::remove(&message_key);
+		OutboundMessageSubmitters::<T, I>::remove(&message_key);
+		OutboundMessageTips::<T, I>::remove(&message_key);
+		CancelledOutboundMessages::<T, I>::remove(&message_key);
 	}
 }
 
@@ -1089,10 +1364,11 @@ mod tests {
 	use super::*;
 	use crate::mock::{
 		message, message_payload, run_test, unrewarded_relayer, Event as TestEvent, Origin,
-		TestMessageDeliveryAndDispatchPayment, TestMessagesDeliveryProof, TestMessagesParameter,
-		TestMessagesProof, TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2,
-		TestOnMessageAccepted, TestRuntime, TokenConversionRate, PAYLOAD_REJECTED_BY_TARGET_CHAIN,
-		REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
+		TestMessageDeliveryAndDispatchPayment, TestMessageExpiration, TestMessagesDeliveryProof,
+		TestMessagesParameter, TestMessagesProof, TestOnDeliveryConfirmed1,
+		TestOnDeliveryConfirmed2, TestOnMessageAccepted, TestRuntime, TokenConversionRate,
+		PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A,
+		TEST_RELAYER_B,
 	};
 	use bp_messages::{UnrewardedRelayer, UnrewardedRelayersState};
 	use frame_support::{
@@ -1486,6 +1762,79 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn send_message_with_tip_works() {
+		run_test(|| {
+			// the tip doesn't count towards the delivery and dispatch fee
+			assert_noop!(
+				Pallet::<TestRuntime>::send_message_with_tip(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					0,
+					100,
+				),
+				Error::<TestRuntime, ()>::MessageRejectedByLaneVerifier,
+			);
+
+			// the submitter pays both the fee and the tip
+			assert_ok!(Pallet::<TestRuntime>::send_message_with_tip(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+				100,
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 1100));
+			let message_key = MessageKey { lane_id: TEST_LANE_ID, nonce: 1 };
+			assert_eq!(
+				OutboundMessages::<TestRuntime>::get(message_key)
+					.map(|message_data| message_data.fee),
+				Some(1100),
+			);
+			assert_eq!(OutboundMessageTips::<TestRuntime>::get(message_key), 100);
+
+			// the tip is paid to the relayer together with the fee
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)]
+							.into_iter()
+							.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(TEST_RELAYER_A, 1100));
+		});
+	}
+
+	#[test]
+	fn messages_stored_before_relayer_tips_are_decoded() {
+		run_test(|| {
+			// messages that are already queued have been stored as the `(payload, fee)` pair
+			let message_key = MessageKey { lane_id: TEST_LANE_ID, nonce: 1 };
+			let payload = REGULAR_PAYLOAD.encode();
+			frame_support::storage::unhashed::put_raw(
+				&OutboundMessages::<TestRuntime>::storage_map_final_key(message_key),
+				&(payload.clone(), 100u64).encode(),
+			);
+
+			assert_eq!(
+				OutboundMessages::<TestRuntime>::get(message_key),
+				Some(MessageData { payload, fee: 100 }),
+			);
+			assert_eq!(OutboundMessageTips::<TestRuntime>::get(message_key), 0);
+		});
+	}
+
 	#[test]
 	fn chain_verifier_rejects_invalid_message_in_send_message() {
 		run_test(|| {
@@ -1928,6 +2277,189 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn cancel_message_fails_if_origin_is_not_submitter() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::cancel_message(Origin::signed(2), TEST_LANE_ID, 1),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn expired_message_may_be_cancelled_by_anyone() {
+		run_test(|| {
+			send_regular_message();
+
+			TestMessageExpiration::expire_messages();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				1,
+			));
+			assert!(CancelledOutboundMessages::<TestRuntime, ()>::contains_key(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			}));
+		});
+	}
+
+	#[test]
+	fn cancel_message_fails_if_message_is_already_cancelled() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(Origin::root(), TEST_LANE_ID, 1));
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::cancel_message(Origin::signed(1), TEST_LANE_ID, 1),
+				Error::<TestRuntime, ()>::MessageIsAlreadyCancelled,
+			);
+		});
+	}
+
+	#[test]
+	fn cancel_message_clears_payload_and_keeps_fee_until_delivery_is_confirmed() {
+		run_test(|| {
+			send_regular_message();
+			get_ready_for_events();
+
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert_eq!(
+				Pallet::<TestRuntime, ()>::outbound_message_data(TEST_LANE_ID, 1),
+				Some(MessageData { payload: Vec::new(), fee: REGULAR_PAYLOAD.declared_weight }),
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(Event::MessageCancellationRequested(
+						TEST_LANE_ID,
+						1
+					)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_cancelled() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(Origin::root(), TEST_LANE_ID, 1));
+
+			assert_noop!(
+				Pallet::<TestRuntime, ()>::increase_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					100,
+				),
+				Error::<TestRuntime, ()>::MessageIsAlreadyCancelled,
+			);
+		});
+	}
+
+	#[test]
+	fn cancelled_message_is_refunded_if_it_is_not_dispatched_at_bridged_chain() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+			));
+			get_ready_for_events();
+
+			// the cancelled message has been delivered after its payload has been cleared, so
+			// it has failed to dispatch
+			let mut relayer = unrewarded_relayer(1, 2, TEST_RELAYER_A);
+			relayer.messages.dispatch_results.set(0, false);
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![relayer].into_iter().collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(System::<TestRuntime>::events().iter().any(|record| record.event ==
+				TestEvent::Messages(Event::MessageCancelled(TEST_LANE_ID, 1))));
+		});
+	}
+
+	#[test]
+	fn cancellation_is_rejected_if_message_is_dispatched_at_bridged_chain() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime, ()>::cancel_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+			));
+			get_ready_for_events();
+
+			// the message has been delivered and dispatched before it has been cancelled
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)]
+							.into_iter()
+							.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
+
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				1,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				REGULAR_PAYLOAD.declared_weight
+			));
+			assert!(System::<TestRuntime>::events().iter().any(|record| record.event ==
+				TestEvent::Messages(Event::MessageCancellationRejected(TEST_LANE_ID, 1))));
+		});
+	}
+
 	#[test]
 	fn weight_refund_from_receive_messages_proof_works() {
 		run_test(|| {
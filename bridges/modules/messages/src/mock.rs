@@ -22,11 +22,12 @@ use crate::{instant_payments::cal_relayers_rewards, Config};
 use bitvec::prelude::*;
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed,
-		OnMessageAccepted, SenderOrigin, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, MessageExpiration,
+		OnDeliveryConfirmed, OnMessageAccepted, SenderOrigin, TargetHeaderChain,
 	},
 	target_chain::{
-		DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain,
+		DispatchMessage, InboundLaneLimits, MessageDispatch, ProvedLaneMessages, ProvedMessages,
+		SourceHeaderChain,
 	},
 	DeliveredMessages, InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce,
 	OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayer,
@@ -170,9 +171,11 @@ impl Config for TestRuntime {
 	type Event = Event;
 	type WeightInfo = ();
 	type Parameter = TestMessagesParameter;
+	type AdminOrigin = crate::EnsureOwnerOrRoot<TestRuntime>;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type InboundLaneLimits = TestInboundLaneLimits;
 
 	type OutboundPayload = TestPayload;
 	type OutboundMessageFee = TestMessageFee;
@@ -186,6 +189,7 @@ impl Config for TestRuntime {
 	type TargetHeaderChain = TestTargetHeaderChain;
 	type LaneMessageVerifier = TestLaneMessageVerifier;
 	type MessageDeliveryAndDispatchPayment = TestMessageDeliveryAndDispatchPayment;
+	type MessageExpiration = TestMessageExpiration;
 	type OnMessageAccepted = TestOnMessageAccepted;
 	type OnDeliveryConfirmed = (TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2);
 
@@ -228,6 +232,9 @@ pub const TEST_ERROR: &str = "Test error";
 /// Lane that we're using in tests.
 pub const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
 
+/// Lane with lower limits than other lanes.
+pub const TEST_LIMITED_LANE_ID: LaneId = [0, 0, 0, 2];
+
 /// Regular message payload.
 pub const REGULAR_PAYLOAD: TestPayload = message_payload(0, 50);
 
@@ -340,6 +347,11 @@ impl TestMessageDeliveryAndDispatchPayment {
 		frame_support::storage::unhashed::get(b":message-fee:") == Some((raw_origin.unwrap(), fee))
 	}
 
+	/// Returns true if given fee has been refunded to given submitter.
+	pub fn is_fee_refunded(submitter: AccountId, fee: TestMessageFee) -> bool {
+		frame_support::storage::unhashed::get(b":refunded-message-fee:") == Some((submitter, fee))
+	}
+
 	/// Returns true if given relayer has been rewarded with given balance. The reward-paid flag is
 	/// cleared after the call.
 	pub fn is_reward_paid(relayer: AccountId, fee: TestMessageFee) -> bool {
@@ -367,6 +379,15 @@ impl MessageDeliveryAndDispatchPayment<Origin, AccountId, TestMessageFee>
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &AccountId,
+		fee: &TestMessageFee,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		frame_support::storage::unhashed::put(b":refunded-message-fee:", &(submitter, fee));
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		lane_id: LaneId,
 		message_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
@@ -383,6 +404,23 @@ impl MessageDeliveryAndDispatchPayment<Origin, AccountId, TestMessageFee>
 	}
 }
 
+/// Message expiration that is used in tests.
+#[derive(Debug, Default)]
+pub struct TestMessageExpiration;
+
+impl TestMessageExpiration {
+	/// Expire all messages.
+	pub fn expire_messages() {
+		frame_support::storage::unhashed::put(b":expire-messages:", &true);
+	}
+}
+
+impl MessageExpiration<TestPayload> for TestMessageExpiration {
+	fn is_expired(_payload: &TestPayload) -> bool {
+		frame_support::storage::unhashed::get(b":expire-messages:") == Some(true)
+	}
+}
+
 #[derive(Debug)]
 pub struct TestOnMessageAccepted;
 
@@ -465,6 +503,20 @@ impl OnDeliveryConfirmed for TestOnDeliveryConfirmed2 {
 	}
 }
 
+/// Inbound lane limits that are used in tests.
+#[derive(Debug)]
+pub struct TestInboundLaneLimits;
+
+impl InboundLaneLimits for TestInboundLaneLimits {
+	fn max_unrewarded_relayer_entries(lane: &LaneId) -> Option<MessageNonce> {
+		(*lane == TEST_LIMITED_LANE_ID).then(|| 2)
+	}
+
+	fn max_unconfirmed_messages(lane: &LaneId) -> Option<MessageNonce> {
+		(*lane == TEST_LIMITED_LANE_ID).then(|| 3)
+	}
+}
+
 /// Source header chain that is used in tests.
 #[derive(Debug)]
 pub struct TestSourceHeaderChain;
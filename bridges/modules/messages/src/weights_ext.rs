@@ -264,11 +264,15 @@ pub trait WeightInfoExt: WeightInfo {
 			.total_messages
 			.saturating_mul(Self::single_message_callback_overhead(db_weight));
 
+		// and cost of checking whether every confirmed message has been cancelled
+		let cancellation_overhead = db_weight.reads(relayers_state.total_messages);
+
 		transaction_overhead
 			.saturating_add(messages_overhead)
 			.saturating_add(relayers_overhead)
 			.saturating_add(proof_size_overhead)
 			.saturating_add(callback_overhead)
+			.saturating_add(cancellation_overhead)
 	}
 
 	// Functions that are used by extrinsics weights formulas.
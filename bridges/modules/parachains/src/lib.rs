@@ -0,0 +1,527 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate Parachains Pallet
+//!
+//! This pallet tracks heads of the parachains of the bridged relay chain. The relay chain
+//! finality is provided by some other pallet (e.g. the GRANDPA or the BEEFY pallet), which is
+//! used to verify storage proofs of the `Heads` map of the relay chain `paras` pallet.
+//!
+//! Tracked heads may be used to verify storage proofs of the bridged parachains (e.g. proofs of
+//! messages, sent by the parachain) - see [`ParachainFinality`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// Runtime-generated enums
+#![allow(clippy::large_enum_variant)]
+
+use bp_header_chain::FinalityBackend;
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf, StorageProofChecker};
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, weights::Weight, Twox64Concat};
+use scale_info::TypeInfo;
+use sp_core::{storage::StorageKey, H256};
+use sp_runtime::{
+	traits::{BlakeTwo256, Hash as HashT, Header as HeaderT, Zero},
+	DispatchError, RuntimeDebug,
+};
+use sp_std::{marker::PhantomData, vec::Vec};
+use sp_trie::StorageProof;
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// The bridged relay chain.
+pub type RelayChain<T, I> = <<T as Config<I>>::RelayFinality as FinalityBackend>::Chain;
+/// Block number of the bridged relay chain.
+pub type RelayBlockNumber<T, I> = BlockNumberOf<RelayChain<T, I>>;
+/// Block hash of the bridged relay chain.
+pub type RelayBlockHash<T, I> = HashOf<RelayChain<T, I>>;
+/// Hasher of the bridged relay chain.
+pub type RelayBlockHasher<T, I> = HasherOf<RelayChain<T, I>>;
+
+/// Name of the `Heads` map of the relay chain `paras` pallet.
+pub const PARAS_HEADS_MAP_NAME: &str = "Heads";
+
+/// Parachain id.
+#[derive(Clone, Copy, Decode, Encode, Eq, Ord, PartialEq, PartialOrd, RuntimeDebug, TypeInfo)]
+pub struct ParaId(pub u32);
+
+/// Hash of the parachain head.
+pub type ParaHash = H256;
+
+/// Parachain head, as it is stored in the relay chain storage (encoded parachain header).
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ParaHead(pub Vec<u8>);
+
+impl ParaHead {
+	/// Returns the hash of the parachain head.
+	///
+	/// This is the hash of the parachain header, if the parachain is using `BlakeTwo256` hashing.
+	pub fn hash(&self) -> ParaHash {
+		BlakeTwo256::hash(&self.0)
+	}
+}
+
+/// Storage proof of the parachain heads, crafted at the relay chain block.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ParaHeadsProof(pub Vec<Vec<u8>>);
+
+/// Best known head of the parachain.
+#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct BestParaHead<RelayBlockNumber> {
+	/// Number of the relay chain block, where the head has been read.
+	pub at_relay_block_number: RelayBlockNumber,
+	/// Hash of the parachain head.
+	pub head_hash: ParaHash,
+	/// Position of the next head hash in the `ImportedParaHashes` ring buffer.
+	pub next_imported_hash_position: u32,
+}
+
+/// Weight of single parachain head import.
+///
+/// There are no benchmarks for the pallet yet, so it only accounts for storage accesses.
+fn parachain_head_import_weight<T: frame_system::Config>() -> Weight {
+	T::DbWeight::get().reads_writes(2, 4)
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// Finality of the bridged relay chain.
+		type RelayFinality: FinalityBackend;
+
+		/// Name of the `paras` pallet in the `construct_runtime!` of the bridged relay chain.
+		#[pallet::constant]
+		type ParasPalletName: Get<&'static str>;
+
+		/// Maximal number of heads of every parachain to keep in the storage.
+		#[pallet::constant]
+		type HeadsToKeep: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Import heads of the parachains from the storage proof, crafted at the finalized relay
+		/// chain block.
+		///
+		/// Heads that are missing from the proof, or are read at the relay chain block that is
+		/// not newer than the block of the best known head, are ignored.
+		#[pallet::weight(T::DbWeight::get().reads(1).saturating_add(
+			parachain_head_import_weight::<T>().saturating_mul(parachains.len() as Weight)
+		))]
+		pub fn submit_parachain_heads(
+			origin: OriginFor<T>,
+			relay_block_hash: RelayBlockHash<T, I>,
+			parachains: Vec<ParaId>,
+			parachain_heads_proof: ParaHeadsProof,
+		) -> DispatchResult {
+			let _ = ensure_signed(origin)?;
+
+			let relay_block_number = *T::RelayFinality::finalized_header(relay_block_hash)
+				.ok_or(Error::<T, I>::UnknownRelayChainBlock)?
+				.number();
+
+			T::RelayFinality::parse_finalized_storage_proof(
+				relay_block_hash,
+				StorageProof::new(parachain_heads_proof.0),
+				move |storage| {
+					for parachain in parachains {
+						let parachain_head = match read_parachain_head::<T, I>(&storage, parachain)
+						{
+							Some(parachain_head) => parachain_head,
+							None => {
+								log::trace!(
+									target: "runtime::bridge-parachains",
+									"The head of parachain {:?} is missing from the proof",
+									parachain,
+								);
+								continue
+							},
+						};
+
+						update_parachain_head::<T, I>(
+							parachain,
+							relay_block_number,
+							parachain_head,
+						);
+					}
+				},
+			)?;
+
+			Ok(())
+		}
+	}
+
+	/// Best known heads of the parachains.
+	#[pallet::storage]
+	pub(super) type BestParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, ParaId, BestParaHead<RelayBlockNumber<T, I>>>;
+
+	/// Parachain heads which have been imported into the pallet.
+	#[pallet::storage]
+	pub(super) type ImportedParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Blake2_128Concat, ParaHash, ParaHead>;
+
+	/// A ring buffer of imported parachain head hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedParaHashes<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ParaId, Twox64Concat, u32, ParaHash>;
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Relay chain block is unknown to the relay chain finality pallet.
+		UnknownRelayChainBlock,
+		/// Parachain head is unknown to the pallet.
+		UnknownParaHead,
+		/// The storage proof doesn't contains storage root. So it is invalid for given header.
+		StorageRootMismatch,
+	}
+
+	/// Read the parachain head from the storage proof of the relay chain `paras` pallet.
+	pub(crate) fn read_parachain_head<T: Config<I>, I: 'static>(
+		storage: &StorageProofChecker<RelayBlockHasher<T, I>>,
+		parachain: ParaId,
+	) -> Option<ParaHead> {
+		let key = parachain_head_storage_key(T::ParasPalletName::get(), parachain);
+		let encoded_head = storage.read_value(&key.0).ok()??;
+		ParaHead::decode(&mut &encoded_head[..]).ok()
+	}
+
+	/// Import the parachain head, read at the given relay chain block, to the storage.
+	///
+	/// Note this function solely takes care of updating the storage and pruning old entries,
+	/// but does not verify the validity of such import.
+	pub(crate) fn update_parachain_head<T: Config<I>, I: 'static>(
+		parachain: ParaId,
+		relay_block_number: RelayBlockNumber<T, I>,
+		parachain_head: ParaHead,
+	) {
+		let head_hash = parachain_head.hash();
+		let next_imported_hash_position = match <BestParaHeads<T, I>>::get(parachain) {
+			Some(best) if best.at_relay_block_number >= relay_block_number => {
+				log::trace!(
+					target: "runtime::bridge-parachains",
+					"The head of parachain {:?} at relay block {:?} is not newer than the best head \
+					at relay block {:?}",
+					parachain,
+					relay_block_number,
+					best.at_relay_block_number,
+				);
+				return
+			},
+			Some(best) if best.head_hash == head_hash => {
+				<BestParaHeads<T, I>>::insert(
+					parachain,
+					BestParaHead { at_relay_block_number: relay_block_number, ..best },
+				);
+				return
+			},
+			Some(best) => best.next_imported_hash_position,
+			None => 0,
+		};
+
+		let pruning = <ImportedParaHashes<T, I>>::try_get(parachain, next_imported_hash_position);
+		<ImportedParaHeads<T, I>>::insert(parachain, head_hash, parachain_head);
+		<ImportedParaHashes<T, I>>::insert(parachain, next_imported_hash_position, head_hash);
+		<BestParaHeads<T, I>>::insert(
+			parachain,
+			BestParaHead {
+				at_relay_block_number: relay_block_number,
+				head_hash,
+				next_imported_hash_position: (next_imported_hash_position + 1) %
+					T::HeadsToKeep::get(),
+			},
+		);
+		if let Ok(head_hash) = pruning {
+			log::debug!(
+				target: "runtime::bridge-parachains",
+				"Pruning old head of parachain {:?}: {:?}.",
+				parachain,
+				head_hash,
+			);
+			<ImportedParaHeads<T, I>>::remove(parachain, head_hash);
+		}
+
+		log::trace!(
+			target: "runtime::bridge-parachains",
+			"Updated head of parachain {:?} to {:?} at relay block {:?}",
+			parachain,
+			head_hash,
+			relay_block_number,
+		);
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Get the best known head of the parachain.
+	pub fn best_parachain_head(parachain: ParaId) -> Option<ParaHead> {
+		let best = <BestParaHeads<T, I>>::get(parachain)?;
+		<ImportedParaHeads<T, I>>::get(parachain, best.head_hash)
+	}
+
+	/// Get the imported head of the parachain with given hash.
+	pub fn parachain_head(parachain: ParaId, hash: ParaHash) -> Option<ParaHead> {
+		<ImportedParaHeads<T, I>>::get(parachain, hash)
+	}
+}
+
+/// Finality of the bridged parachain `P` headers, tracked by the pallet.
+///
+/// The parachain `C` must use `BlakeTwo256` to hash its headers. It allows verifying storage
+/// proofs (e.g. message proofs) of the parachain, the same way it is done for the bridged chains
+/// with their own finality.
+pub struct ParachainFinality<T, I, P, C>(PhantomData<(T, I, P, C)>);
+
+impl<T, I, P, C> FinalityBackend for ParachainFinality<T, I, P, C>
+where
+	T: Config<I>,
+	I: 'static,
+	P: Get<ParaId>,
+	C: Chain<Hash = ParaHash>,
+{
+	type Chain = C;
+
+	fn best_finalized_number() -> BlockNumberOf<C> {
+		<BestParaHeads<T, I>>::get(P::get())
+			.and_then(|best| Self::finalized_header(best.head_hash))
+			.map(|header| *header.number())
+			.unwrap_or_else(Zero::zero)
+	}
+
+	fn finalized_header(hash: ParaHash) -> Option<HeaderOf<C>> {
+		let head = Pallet::<T, I>::parachain_head(P::get(), hash)?;
+		HeaderOf::<C>::decode(&mut &head.0[..]).ok()
+	}
+
+	fn parse_finalized_storage_proof<R>(
+		hash: ParaHash,
+		storage_proof: StorageProof,
+		parse: impl FnOnce(StorageProofChecker<HasherOf<C>>) -> R,
+	) -> Result<R, DispatchError> {
+		let header = Self::finalized_header(hash).ok_or(Error::<T, I>::UnknownParaHead)?;
+		let storage_proof_checker = StorageProofChecker::new(*header.state_root(), storage_proof)
+			.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+/// Returns the storage key of the parachain head in the `Heads` map of the relay chain `paras`
+/// pallet.
+pub fn parachain_head_storage_key(paras_pallet_name: &str, parachain: ParaId) -> StorageKey {
+	bp_runtime::storage_map_final_key::<Twox64Concat>(
+		paras_pallet_name,
+		PARAS_HEADS_MAP_NAME,
+		&parachain.encode(),
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		insert_relay_header, prepare_parachain_heads_proof, run_test, test_header, Origin,
+		TestHeader, TestParachain, TestRuntime,
+	};
+	use frame_support::{assert_noop, assert_ok, parameter_types};
+
+	parameter_types! {
+		pub const TestParaId: ParaId = ParaId(1);
+	}
+
+	type TestParachainFinality = ParachainFinality<TestRuntime, (), TestParaId, TestParachain>;
+
+	fn head_data(parachain: u32, head_number: u64) -> ParaHead {
+		ParaHead((parachain, head_number).encode())
+	}
+
+	fn submit_parachain_heads(
+		relay_block_number: u64,
+		heads: Vec<(u32, ParaHead)>,
+		parachains: Vec<u32>,
+	) -> frame_support::dispatch::DispatchResult {
+		let (state_root, proof) = prepare_parachain_heads_proof(heads);
+		let relay_block_hash = insert_relay_header(relay_block_number, state_root);
+		Pallet::<TestRuntime>::submit_parachain_heads(
+			Origin::signed(1),
+			relay_block_hash,
+			parachains.into_iter().map(ParaId).collect(),
+			proof,
+		)
+	}
+
+	#[test]
+	fn imports_parachain_heads() {
+		run_test(|| {
+			assert_ok!(submit_parachain_heads(
+				0,
+				vec![(1, head_data(1, 0)), (2, head_data(2, 0))],
+				vec![1, 2],
+			));
+
+			assert_eq!(
+				<BestParaHeads<TestRuntime>>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 0,
+					head_hash: head_data(1, 0).hash(),
+					next_imported_hash_position: 1,
+				}),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 0))
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(2)),
+				Some(head_data(2, 0))
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_heads_at_unknown_relay_block() {
+		run_test(|| {
+			let (_, proof) = prepare_parachain_heads_proof(vec![(1, head_data(1, 0))]);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					Default::default(),
+					vec![ParaId(1)],
+					proof,
+				),
+				Error::<TestRuntime>::UnknownRelayChainBlock,
+			);
+		})
+	}
+
+	#[test]
+	fn ignores_heads_missing_from_proof() {
+		run_test(|| {
+			assert_ok!(submit_parachain_heads(0, vec![(1, head_data(1, 0))], vec![1, 2]));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 0))
+			);
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(ParaId(2)), None);
+		})
+	}
+
+	#[test]
+	fn ignores_heads_at_older_relay_block() {
+		run_test(|| {
+			assert_ok!(submit_parachain_heads(10, vec![(1, head_data(1, 10))], vec![1]));
+			assert_ok!(submit_parachain_heads(5, vec![(1, head_data(1, 5))], vec![1]));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::best_parachain_head(ParaId(1)),
+				Some(head_data(1, 10)),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(ParaId(1), head_data(1, 5).hash()),
+				None
+			);
+		})
+	}
+
+	#[test]
+	fn updates_relay_block_of_unchanged_head() {
+		run_test(|| {
+			assert_ok!(submit_parachain_heads(0, vec![(1, head_data(1, 0))], vec![1]));
+			assert_ok!(submit_parachain_heads(1, vec![(1, head_data(1, 0))], vec![1]));
+
+			assert_eq!(
+				<BestParaHeads<TestRuntime>>::get(ParaId(1)),
+				Some(BestParaHead {
+					at_relay_block_number: 1,
+					head_hash: head_data(1, 0).hash(),
+					next_imported_hash_position: 1,
+				}),
+			);
+		})
+	}
+
+	#[test]
+	fn prunes_heads_over_heads_to_keep_parameter() {
+		run_test(|| {
+			let heads_to_keep = <TestRuntime as Config>::HeadsToKeep::get() as u64;
+			for number in 0..=heads_to_keep {
+				assert_ok!(submit_parachain_heads(
+					number,
+					vec![(1, head_data(1, number))],
+					vec![1]
+				));
+			}
+
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(ParaId(1), head_data(1, 0).hash()),
+				None
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(ParaId(1), head_data(1, 1).hash()),
+				Some(head_data(1, 1)),
+			);
+		})
+	}
+
+	#[test]
+	fn parachain_finality_verifies_storage_proofs_at_parachain_heads() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let mut parachain_header: TestHeader = test_header(7);
+			parachain_header.set_state_root(state_root);
+			let parachain_head = ParaHead(parachain_header.encode());
+			assert_ok!(submit_parachain_heads(0, vec![(1, parachain_head)], vec![1]));
+
+			assert_eq!(TestParachainFinality::best_finalized_number(), 7);
+			assert_ok!(
+				TestParachainFinality::parse_finalized_storage_proof(
+					parachain_header.hash(),
+					storage_proof,
+					|_| (),
+				),
+				(),
+			);
+		})
+	}
+
+	#[test]
+	fn parachain_finality_rejects_storage_proofs_at_unknown_parachain_heads() {
+		run_test(|| {
+			let (_, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			assert_noop!(
+				TestParachainFinality::parse_finalized_storage_proof(
+					test_header(7).hash(),
+					storage_proof,
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownParaHead,
+			);
+		})
+	}
+}
@@ -0,0 +1,201 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate::{parachain_head_storage_key, ParaHead, ParaHeadsProof, ParaId};
+use bp_header_chain::FinalityBackend;
+use bp_runtime::{Chain, StorageProofChecker};
+use codec::Encode;
+use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use sp_core::sr25519::Signature;
+use sp_runtime::{
+	testing::{Header, H256},
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	DispatchError, Perbill,
+};
+use sp_trie::StorageProof;
+use std::cell::RefCell;
+
+pub type AccountId = u64;
+pub type TestHeader = Header;
+pub type TestNumber = u64;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+use crate as parachains;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Parachains: parachains::{Pallet},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+parameter_types! {
+	pub const ParasPalletName: &'static str = PARAS_PALLET_NAME;
+	pub const HeadsToKeep: u32 = 5;
+}
+
+impl parachains::Config for TestRuntime {
+	type RelayFinality = TestRelayFinality;
+	type ParasPalletName = ParasPalletName;
+	type HeadsToKeep = HeadsToKeep;
+}
+
+/// Name of the `paras` pallet at the test relay chain.
+pub const PARAS_PALLET_NAME: &str = "Paras";
+
+#[derive(Debug)]
+pub struct TestRelayChain;
+
+impl Chain for TestRelayChain {
+	type BlockNumber = TestNumber;
+	type Hash = H256;
+	type Hasher = BlakeTwo256;
+	type Header = TestHeader;
+
+	type AccountId = AccountId;
+	type Balance = u64;
+	type Index = u64;
+	type Signature = Signature;
+
+	fn max_extrinsic_size() -> u32 {
+		unreachable!()
+	}
+	fn max_extrinsic_weight() -> Weight {
+		unreachable!()
+	}
+}
+
+/// Parachain of the test relay chain. It uses the same header type as the relay chain.
+pub type TestParachain = TestRelayChain;
+
+thread_local! {
+	static RELAY_HEADERS: RefCell<Vec<TestHeader>> = RefCell::new(Vec::new());
+}
+
+/// Finality of the test relay chain: all headers, inserted by the `insert_relay_header` are
+/// considered finalized.
+pub struct TestRelayFinality;
+
+impl FinalityBackend for TestRelayFinality {
+	type Chain = TestRelayChain;
+
+	fn best_finalized_number() -> TestNumber {
+		RELAY_HEADERS.with(|headers| {
+			headers.borrow().iter().map(|header| *header.number()).max().unwrap_or_default()
+		})
+	}
+
+	fn finalized_header(hash: H256) -> Option<TestHeader> {
+		RELAY_HEADERS
+			.with(|headers| headers.borrow().iter().find(|header| header.hash() == hash).cloned())
+	}
+
+	fn parse_finalized_storage_proof<R>(
+		hash: H256,
+		storage_proof: StorageProof,
+		parse: impl FnOnce(StorageProofChecker<BlakeTwo256>) -> R,
+	) -> Result<R, DispatchError> {
+		let header = Self::finalized_header(hash).ok_or("Unknown relay header")?;
+		let storage_proof_checker = StorageProofChecker::new(*header.state_root(), storage_proof)
+			.map_err(|_| "Storage root mismatch")?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(test)
+}
+
+pub fn test_header(num: TestNumber) -> TestHeader {
+	// We wrap the call to avoid explicit type annotations in our tests
+	bp_test_utils::test_header(num)
+}
+
+/// Insert finalized relay chain header with given number and state root. Returns hash of the
+/// header.
+pub fn insert_relay_header(number: TestNumber, state_root: H256) -> H256 {
+	let mut header = test_header(number);
+	header.set_state_root(state_root);
+	let hash = header.hash();
+	RELAY_HEADERS.with(|headers| headers.borrow_mut().push(header));
+	hash
+}
+
+/// Prepare storage proof of the given parachain heads at the test relay chain. Returns state root
+/// and the proof.
+pub fn prepare_parachain_heads_proof(heads: Vec<(u32, ParaHead)>) -> (H256, ParaHeadsProof) {
+	use sp_state_machine::{backend::Backend, prove_read, InMemoryBackend};
+
+	let state_version = sp_runtime::StateVersion::default();
+	let storage = heads
+		.into_iter()
+		.map(|(parachain, head)| {
+			let key = parachain_head_storage_key(PARAS_PALLET_NAME, ParaId(parachain));
+			(key.0, Some(head.encode()))
+		})
+		.collect::<Vec<_>>();
+	let keys = storage.iter().map(|(key, _)| key.clone()).collect::<Vec<_>>();
+
+	let backend = <InMemoryBackend<BlakeTwo256>>::from((vec![(None, storage)], state_version));
+	let root = backend.storage_root(std::iter::empty(), state_version).0;
+	let proof = prove_read(backend, &keys).unwrap().iter_nodes().collect();
+
+	(root, ParaHeadsProof(proof))
+}
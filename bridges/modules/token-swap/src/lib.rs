@@ -347,6 +347,7 @@ pub mod pallet {
 							bridged_currency_transfer_signature,
 						),
 						dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+						expires_at: None,
 						call: bridged_currency_transfer,
 					},
 					swap_delivery_and_dispatch_fee,
@@ -18,7 +18,10 @@
 // RuntimeApi generated functions
 #![allow(clippy::too_many_arguments)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce};
+use bp_messages::{
+	InboundLaneData, LaneId, MessageDetails, MessageDispatchDryRun, MessageNonce,
+	MessagePayloadVerdict, OutboundLaneStats,
+};
 use frame_support::weights::{
 	Weight, WeightToFeeCoefficient, WeightToFeeCoefficients, WeightToFeePolynomial,
 };
@@ -81,6 +84,9 @@ pub const WITH_ROCOCO_MESSAGES_PALLET_NAME: &str = "BridgeRococoMessages";
 
 /// Name of the `RococoFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_best_finalized";
+/// Name of the `RococoFinalityApi::best_finalized_with_set_id` runtime method.
+pub const BEST_FINALIZED_ROCOCO_HEADER_WITH_SET_ID_METHOD: &str =
+	"RococoFinalityApi_best_finalized_with_set_id";
 
 /// Name of the `ToRococoOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime
 /// method.
@@ -88,10 +94,33 @@ pub const TO_ROCOCO_ESTIMATE_MESSAGE_FEE_METHOD: &str =
 	"ToRococoOutboundLaneApi_estimate_message_delivery_and_dispatch_fee";
 /// Name of the `ToRococoOutboundLaneApi::message_details` runtime method.
 pub const TO_ROCOCO_MESSAGE_DETAILS_METHOD: &str = "ToRococoOutboundLaneApi_message_details";
+/// Name of the `ToRococoOutboundLaneApi::is_lane_congested` runtime method.
+pub const TO_ROCOCO_IS_LANE_CONGESTED_METHOD: &str = "ToRococoOutboundLaneApi_is_lane_congested";
+/// Name of the `ToRococoOutboundLaneApi::lanes` runtime method.
+pub const TO_ROCOCO_LANES_METHOD: &str = "ToRococoOutboundLaneApi_lanes";
+/// Name of the `ToRococoOutboundLaneApi::outbound_lane_stats` runtime method.
+pub const TO_ROCOCO_OUTBOUND_LANE_STATS_METHOD: &str =
+	"ToRococoOutboundLaneApi_outbound_lane_stats";
+/// Name of the `FromRococoInboundLaneApi::inbound_lane_data` runtime method.
+pub const FROM_ROCOCO_INBOUND_LANE_DATA_METHOD: &str =
+	"FromRococoInboundLaneApi_inbound_lane_data";
+/// Name of the `FromRococoInboundLaneApi::bridged_account_id` runtime method.
+pub const FROM_ROCOCO_BRIDGED_ACCOUNT_ID_METHOD: &str =
+	"FromRococoInboundLaneApi_bridged_account_id";
+/// Name of the `FromRococoInboundLaneApi::check_message_payload` runtime method.
+pub const FROM_ROCOCO_CHECK_MESSAGE_PAYLOAD_METHOD: &str =
+	"FromRococoInboundLaneApi_check_message_payload";
+/// Name of the `FromRococoInboundLaneApi::dry_run_message_dispatch` runtime method.
+pub const FROM_ROCOCO_DRY_RUN_MESSAGE_DISPATCH_METHOD: &str =
+	"FromRococoInboundLaneApi_dry_run_message_dispatch";
 
 /// Existential deposit on Rococo.
 pub const EXISTENTIAL_DEPOSIT: Balance = 1_000_000_000_000 / 100;
 
+// NOTE: This needs to be kept up to date with the Rococo runtime found in the Polkadot repo.
+/// Fee that is paid for every byte of the Rococo transaction.
+pub const TRANSACTION_BYTE_FEE: Balance = 10 * (1_000_000_000_000 / 100 / 1_000);
+
 /// Weight of pay-dispatch-fee operation for inbound messages at Rococo chain.
 ///
 /// This value corresponds to the result of
@@ -109,6 +138,13 @@ sp_api::decl_runtime_apis! {
 	pub trait RococoFinalityApi {
 		/// Returns number and hash of the best finalized header known to the bridge module.
 		fn best_finalized() -> (BlockNumber, Hash);
+		/// Returns number and hash of the best finalized header known to the bridge module and
+		/// the id of the Rococo GRANDPA authority set, that the module expects to finalize next
+		/// headers.
+		///
+		/// If the set id is behind the set id of Rococo itself, the header that enacts the next
+		/// set must be relayed before headers (and messages, proven by them) of later sets.
+		fn best_finalized_with_set_id() -> (BlockNumber, Hash, u64);
 	}
 
 	/// Outbound message lane API for messages that are sent to Rococo chain.
@@ -140,5 +176,39 @@ sp_api::decl_runtime_apis! {
 			begin: MessageNonce,
 			end: MessageNonce,
 		) -> Vec<MessageDetails<OutboundMessageFee>>;
+		/// Returns true if the lane has too many messages that haven't been delivered to
+		/// Rococo yet, so senders should hold back new messages.
+		fn is_lane_congested(lane: LaneId) -> bool;
+		/// Returns statistics of the lane: the number of sent, delivered and undispatched
+		/// messages, the total fee paid by their submitters and the latest relayer.
+		fn outbound_lane_stats(lane: LaneId) -> OutboundLaneStats<AccountId, OutboundMessageFee>;
+		/// Returns all lanes that are registered for messages to Rococo, including disabled
+		/// lanes.
+		fn lanes() -> Vec<LaneId>;
+	}
+
+	/// Inbound message lane API for messages that are sent by Rococo chain.
+	///
+	/// This API is implemented by runtimes that are receiving messages from Rococo chain, not the
+	/// Rococo runtime itself.
+	pub trait FromRococoInboundLaneApi {
+		/// Returns the state of the inbound lane: relayers that have delivered messages and are
+		/// not yet rewarded, and the nonce of the latest message whose delivery the source chain
+		/// has confirmed.
+		fn inbound_lane_data(lane: LaneId) -> InboundLaneData<AccountId>;
+		/// Returns the account of this chain that dispatches messages, sent by the given
+		/// Rococo account with the `CallOrigin::SourceAccount` origin.
+		fn bridged_account_id(source_account: AccountId) -> AccountId;
+		/// Checks the encoded payload of a message, sent over the given lane, against the
+		/// current bridge configuration of this chain.
+		fn check_message_payload(lane: LaneId, payload: Vec<u8>) -> MessagePayloadVerdict;
+		/// Dispatches a message with the given nonce and encoded payload, delivered over the
+		/// given lane by the given relayer, and rolls back all changes made by the dispatch.
+		fn dry_run_message_dispatch(
+			relayer: AccountId,
+			lane: LaneId,
+			nonce: MessageNonce,
+			payload: Vec<u8>,
+		) -> MessageDispatchDryRun;
 	}
 }
@@ -18,7 +18,10 @@
 // RuntimeApi generated functions
 #![allow(clippy::too_many_arguments)]
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce};
+use bp_messages::{
+	InboundLaneData, LaneId, MessageDetails, MessageDispatchDryRun, MessageNonce,
+	MessagePayloadVerdict, OutboundLaneStats,
+};
 use sp_runtime::FixedU128;
 use sp_std::prelude::*;
 
@@ -50,6 +53,9 @@ pub const WITH_WOCOCO_MESSAGES_PALLET_NAME: &str = "BridgeWococoMessages";
 
 /// Name of the `WococoFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_WOCOCO_HEADER_METHOD: &str = "WococoFinalityApi_best_finalized";
+/// Name of the `WococoFinalityApi::best_finalized_with_set_id` runtime method.
+pub const BEST_FINALIZED_WOCOCO_HEADER_WITH_SET_ID_METHOD: &str =
+	"WococoFinalityApi_best_finalized_with_set_id";
 
 /// Name of the `ToWococoOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime
 /// method.
@@ -57,6 +63,25 @@ pub const TO_WOCOCO_ESTIMATE_MESSAGE_FEE_METHOD: &str =
 	"ToWococoOutboundLaneApi_estimate_message_delivery_and_dispatch_fee";
 /// Name of the `ToWococoOutboundLaneApi::message_details` runtime method.
 pub const TO_WOCOCO_MESSAGE_DETAILS_METHOD: &str = "ToWococoOutboundLaneApi_message_details";
+/// Name of the `ToWococoOutboundLaneApi::is_lane_congested` runtime method.
+pub const TO_WOCOCO_IS_LANE_CONGESTED_METHOD: &str = "ToWococoOutboundLaneApi_is_lane_congested";
+/// Name of the `ToWococoOutboundLaneApi::lanes` runtime method.
+pub const TO_WOCOCO_LANES_METHOD: &str = "ToWococoOutboundLaneApi_lanes";
+/// Name of the `ToWococoOutboundLaneApi::outbound_lane_stats` runtime method.
+pub const TO_WOCOCO_OUTBOUND_LANE_STATS_METHOD: &str =
+	"ToWococoOutboundLaneApi_outbound_lane_stats";
+/// Name of the `FromWococoInboundLaneApi::inbound_lane_data` runtime method.
+pub const FROM_WOCOCO_INBOUND_LANE_DATA_METHOD: &str =
+	"FromWococoInboundLaneApi_inbound_lane_data";
+/// Name of the `FromWococoInboundLaneApi::bridged_account_id` runtime method.
+pub const FROM_WOCOCO_BRIDGED_ACCOUNT_ID_METHOD: &str =
+	"FromWococoInboundLaneApi_bridged_account_id";
+/// Name of the `FromWococoInboundLaneApi::check_message_payload` runtime method.
+pub const FROM_WOCOCO_CHECK_MESSAGE_PAYLOAD_METHOD: &str =
+	"FromWococoInboundLaneApi_check_message_payload";
+/// Name of the `FromWococoInboundLaneApi::dry_run_message_dispatch` runtime method.
+pub const FROM_WOCOCO_DRY_RUN_MESSAGE_DISPATCH_METHOD: &str =
+	"FromWococoInboundLaneApi_dry_run_message_dispatch";
 
 sp_api::decl_runtime_apis! {
 	/// API for querying information about the finalized Wococo headers.
@@ -66,6 +91,13 @@ sp_api::decl_runtime_apis! {
 	pub trait WococoFinalityApi {
 		/// Returns number and hash of the best finalized header known to the bridge module.
 		fn best_finalized() -> (BlockNumber, Hash);
+		/// Returns number and hash of the best finalized header known to the bridge module and
+		/// the id of the Wococo GRANDPA authority set, that the module expects to finalize next
+		/// headers.
+		///
+		/// If the set id is behind the set id of Wococo itself, the header that enacts the next
+		/// set must be relayed before headers (and messages, proven by them) of later sets.
+		fn best_finalized_with_set_id() -> (BlockNumber, Hash, u64);
 	}
 
 	/// Outbound message lane API for messages that are sent to Wococo chain.
@@ -97,5 +129,39 @@ sp_api::decl_runtime_apis! {
 			begin: MessageNonce,
 			end: MessageNonce,
 		) -> Vec<MessageDetails<OutboundMessageFee>>;
+		/// Returns true if the lane has too many messages that haven't been delivered to
+		/// Wococo yet, so senders should hold back new messages.
+		fn is_lane_congested(lane: LaneId) -> bool;
+		/// Returns statistics of the lane: the number of sent, delivered and undispatched
+		/// messages, the total fee paid by their submitters and the latest relayer.
+		fn outbound_lane_stats(lane: LaneId) -> OutboundLaneStats<AccountId, OutboundMessageFee>;
+		/// Returns all lanes that are registered for messages to Wococo, including disabled
+		/// lanes.
+		fn lanes() -> Vec<LaneId>;
+	}
+
+	/// Inbound message lane API for messages that are sent by Wococo chain.
+	///
+	/// This API is implemented by runtimes that are receiving messages from Wococo chain, not the
+	/// Wococo runtime itself.
+	pub trait FromWococoInboundLaneApi {
+		/// Returns the state of the inbound lane: relayers that have delivered messages and are
+		/// not yet rewarded, and the nonce of the latest message whose delivery the source chain
+		/// has confirmed.
+		fn inbound_lane_data(lane: LaneId) -> InboundLaneData<AccountId>;
+		/// Returns the account of this chain that dispatches messages, sent by the given
+		/// Wococo account with the `CallOrigin::SourceAccount` origin.
+		fn bridged_account_id(source_account: AccountId) -> AccountId;
+		/// Checks the encoded payload of a message, sent over the given lane, against the
+		/// current bridge configuration of this chain.
+		fn check_message_payload(lane: LaneId, payload: Vec<u8>) -> MessagePayloadVerdict;
+		/// Dispatches a message with the given nonce and encoded payload, delivered over the
+		/// given lane by the given relayer, and rolls back all changes made by the dispatch.
+		fn dry_run_message_dispatch(
+			relayer: AccountId,
+			lane: LaneId,
+			nonce: MessageNonce,
+			payload: Vec<u8>,
+		) -> MessageDispatchDryRun;
 	}
 }
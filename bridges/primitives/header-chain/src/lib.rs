@@ -19,14 +19,18 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf, StorageProofChecker};
 use codec::{Codec, Decode, Encode, EncodeLike};
 use core::{clone::Clone, cmp::Eq, default::Default, fmt::Debug};
 use scale_info::TypeInfo;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sp_finality_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
-use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT, RuntimeDebug};
+use sp_runtime::{
+	generic::OpaqueDigestItemId, traits::Header as HeaderT, DispatchError, RuntimeDebug,
+};
 use sp_std::boxed::Box;
+use sp_trie::StorageProof;
 
 pub mod justification;
 pub mod storage_keys;
@@ -111,6 +115,32 @@ impl<H: Default, E> HeaderChain<H, E> for () {
 	}
 }
 
+/// A trait for pallets that keep track of finalized headers of the bridged chain and verify
+/// storage proofs at these headers (finality backends of the bridge).
+///
+/// The pallet may use any finality proofs to import headers (e.g. GRANDPA justifications or BEEFY
+/// commitments). Bridge instances select the backend by using its pallet to verify storage proofs.
+pub trait FinalityBackend {
+	/// The bridged chain.
+	type Chain: Chain;
+
+	/// Get number of the best finalized header of the bridged chain, known to the backend.
+	fn best_finalized_number() -> BlockNumberOf<Self::Chain>;
+
+	/// Get the finalized header of the bridged chain with given hash, if it is known to the
+	/// backend.
+	fn finalized_header(hash: HashOf<Self::Chain>) -> Option<HeaderOf<Self::Chain>>;
+
+	/// Verify that the passed storage proof is valid, given it is crafted using known finalized
+	/// header. If the proof is valid, then the `parse` callback is called and the function returns
+	/// its result.
+	fn parse_finalized_storage_proof<R>(
+		hash: HashOf<Self::Chain>,
+		storage_proof: StorageProof,
+		parse: impl FnOnce(StorageProofChecker<HasherOf<Self::Chain>>) -> R,
+	) -> Result<R, DispatchError>;
+}
+
 /// Abstract finality proof that is justifying block finality.
 pub trait FinalityProof<Number>: Clone + Send + Sync + Debug {
 	/// Return number of header that this proof is generated for.
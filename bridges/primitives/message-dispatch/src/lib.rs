@@ -23,9 +23,9 @@ use bp_runtime::{
 	messages::{DispatchFeePayment, MessageDispatchResult},
 	ChainId, Size,
 };
-use codec::{Decode, Encode};
+use codec::{Decode, Encode, EncodeLike, Input, Output};
 use frame_support::RuntimeDebug;
-use scale_info::TypeInfo;
+use scale_info::{build::Fields, meta_type, Path, Type, TypeInfo, TypeParameter};
 use sp_std::prelude::*;
 
 /// Message dispatch weight.
@@ -34,6 +34,32 @@ pub type Weight = u64;
 /// Spec version type.
 pub type SpecVersion = u32;
 
+/// Version of the message payload encoding.
+///
+/// Versioned payloads start with the `VERSIONED_MESSAGE_PAYLOAD_MARKER`, followed by the version
+/// byte. The version is bumped whenever the layout of the payload changes, so that messages which
+/// have been encoded with the previous layout (e.g. messages that have been sent before the
+/// runtime upgrade) are still decoded.
+pub type MessagePayloadVersion = u8;
+
+/// Marker that versioned payloads start with.
+///
+/// Payloads have been encoded without the version before, and they start with the
+/// `spec_version`. The marker takes its place in versioned payloads. No runtime has the maximal
+/// spec version, so a legacy payload that starts with the marker could never be dispatched anyway.
+pub const VERSIONED_MESSAGE_PAYLOAD_MARKER: SpecVersion = SpecVersion::MAX;
+
+/// Version of the legacy payload layout, that is encoded without the marker and the version.
+///
+/// Legacy payloads don't have the `expires_at` field, so they never expire.
+pub const MESSAGE_PAYLOAD_V0: MessagePayloadVersion = 0;
+
+/// Version of the first versioned payload layout. It adds the `expires_at` field.
+pub const MESSAGE_PAYLOAD_V1: MessagePayloadVersion = 1;
+
+/// Version that message payloads are encoded with.
+pub const MESSAGE_PAYLOAD_VERSION: MessagePayloadVersion = MESSAGE_PAYLOAD_V1;
+
 /// A generic trait to dispatch arbitrary messages delivered over the bridge.
 pub trait MessageDispatch<AccountId, BridgeMessageId> {
 	/// A type of the message to be dispatched.
@@ -112,7 +138,10 @@ pub enum CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainS
 }
 
 /// Message payload type used by dispatch module.
-#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+///
+/// The payload is encoded with the `MESSAGE_PAYLOAD_VERSION` byte in front of its fields.
+/// Payloads of the previous versions are decoded as well.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq)]
 pub struct MessagePayload<
 	SourceChainAccountId,
 	TargetChainAccountPublic,
@@ -129,10 +158,151 @@ pub struct MessagePayload<
 	pub origin: CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature>,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Source chain block number, starting from which the message is expired. Expired messages
+	/// are not dispatched and their fee may be refunded at the source chain.
+	pub expires_at: Option<u64>,
 	/// The call itself.
 	pub call: Call,
 }
 
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> Encode
+	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: Encode,
+	TargetChainAccountPublic: Encode,
+	TargetChainSignature: Encode,
+	Call: Encode,
+{
+	fn size_hint(&self) -> usize {
+		VERSIONED_MESSAGE_PAYLOAD_MARKER.size_hint() +
+			MESSAGE_PAYLOAD_VERSION.size_hint() +
+			self.spec_version.size_hint() +
+			self.weight.size_hint() +
+			self.origin.size_hint() +
+			self.dispatch_fee_payment.size_hint() +
+			self.expires_at.size_hint() +
+			self.call.size_hint()
+	}
+
+	fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+		VERSIONED_MESSAGE_PAYLOAD_MARKER.encode_to(dest);
+		MESSAGE_PAYLOAD_VERSION.encode_to(dest);
+		self.spec_version.encode_to(dest);
+		self.weight.encode_to(dest);
+		self.origin.encode_to(dest);
+		self.dispatch_fee_payment.encode_to(dest);
+		self.expires_at.encode_to(dest);
+		self.call.encode_to(dest);
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> EncodeLike
+	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: Encode,
+	TargetChainAccountPublic: Encode,
+	TargetChainSignature: Encode,
+	Call: Encode,
+{
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> Decode
+	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: Decode,
+	TargetChainAccountPublic: Decode,
+	TargetChainSignature: Decode,
+	Call: Decode,
+{
+	fn decode<I: Input>(input: &mut I) -> Result<Self, codec::Error> {
+		// legacy (`MESSAGE_PAYLOAD_V0`) payloads start with the spec version
+		let (version, spec_version) = match SpecVersion::decode(input)? {
+			VERSIONED_MESSAGE_PAYLOAD_MARKER => {
+				let version = MessagePayloadVersion::decode(input)?;
+				if version != MESSAGE_PAYLOAD_V1 {
+					return Err("Unknown version of the message payload".into())
+				}
+				(version, SpecVersion::decode(input)?)
+			},
+			spec_version => (MESSAGE_PAYLOAD_V0, spec_version),
+		};
+
+		Ok(MessagePayload {
+			spec_version,
+			weight: Decode::decode(input)?,
+			origin: Decode::decode(input)?,
+			dispatch_fee_payment: Decode::decode(input)?,
+			// legacy messages never expire
+			expires_at: match version {
+				MESSAGE_PAYLOAD_V0 => None,
+				_ => Decode::decode(input)?,
+			},
+			call: Decode::decode(input)?,
+		})
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> TypeInfo
+	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: TypeInfo + 'static,
+	TargetChainAccountPublic: TypeInfo + 'static,
+	TargetChainSignature: TypeInfo + 'static,
+	Call: TypeInfo + 'static,
+{
+	type Identity = Self;
+
+	fn type_info() -> Type {
+		Type::builder()
+			.path(Path::new("MessagePayload", module_path!()))
+			.type_params(vec![
+				TypeParameter::new(
+					"SourceChainAccountId",
+					Some(meta_type::<SourceChainAccountId>()),
+				),
+				TypeParameter::new(
+					"TargetChainAccountPublic",
+					Some(meta_type::<TargetChainAccountPublic>()),
+				),
+				TypeParameter::new(
+					"TargetChainSignature",
+					Some(meta_type::<TargetChainSignature>()),
+				),
+				TypeParameter::new("Call", Some(meta_type::<Call>())),
+			])
+			.composite(
+				Fields::named()
+					.field(|f| f.ty::<SpecVersion>().name("marker").type_name("SpecVersion"))
+					.field(|f| {
+						f.ty::<MessagePayloadVersion>()
+							.name("version")
+							.type_name("MessagePayloadVersion")
+					})
+					.field(|f| f.ty::<SpecVersion>().name("spec_version").type_name("SpecVersion"))
+					.field(|f| f.ty::<Weight>().name("weight").type_name("Weight"))
+					.field(|f| {
+						f.ty::<CallOrigin<
+							SourceChainAccountId,
+							TargetChainAccountPublic,
+							TargetChainSignature,
+						>>()
+						.name("origin")
+						.type_name(
+							"CallOrigin<SourceChainAccountId, TargetChainAccountPublic, \
+							TargetChainSignature>",
+						)
+					})
+					.field(|f| {
+						f.ty::<DispatchFeePayment>()
+							.name("dispatch_fee_payment")
+							.type_name("DispatchFeePayment")
+					})
+					.field(|f| f.ty::<Option<u64>>().name("expires_at").type_name("Option<u64>"))
+					.field(|f| f.ty::<Call>().name("call").type_name("Call")),
+			)
+	}
+}
+
 impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Vec<u8>>
 {
@@ -140,3 +310,67 @@ impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 		self.call.len() as _
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type TestPayload = MessagePayload<u64, u64, u64, Vec<u8>>;
+
+	fn test_payload(expires_at: Option<u64>) -> TestPayload {
+		MessagePayload {
+			spec_version: 1,
+			weight: 100,
+			origin: CallOrigin::SourceAccount(42),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			expires_at,
+			call: vec![1, 2, 3],
+		}
+	}
+
+	#[test]
+	fn message_payload_is_encoded_with_version() {
+		let payload = test_payload(Some(10));
+		let encoded = payload.encode();
+
+		assert_eq!(encoded[..4], VERSIONED_MESSAGE_PAYLOAD_MARKER.encode()[..]);
+		assert_eq!(encoded[4], MESSAGE_PAYLOAD_VERSION);
+		assert_eq!(encoded.len(), payload.size_hint());
+		assert_eq!(TestPayload::decode(&mut &encoded[..]).ok(), Some(payload));
+	}
+
+	#[test]
+	fn message_payload_of_legacy_layout_is_decoded() {
+		// the payload has been encoded with the derived `Encode` before payloads were versioned
+		#[derive(Encode)]
+		struct LegacyPayload {
+			spec_version: SpecVersion,
+			weight: Weight,
+			origin: CallOrigin<u64, u64, u64>,
+			dispatch_fee_payment: DispatchFeePayment,
+			call: Vec<u8>,
+		}
+
+		for spec_version in [0, 1, 2, 255, 9_999] {
+			let payload = TestPayload { spec_version, ..test_payload(None) };
+			let encoded = LegacyPayload {
+				spec_version,
+				weight: payload.weight,
+				origin: payload.origin.clone(),
+				dispatch_fee_payment: payload.dispatch_fee_payment,
+				call: payload.call.clone(),
+			}
+			.encode();
+
+			assert_eq!(TestPayload::decode(&mut &encoded[..]).ok(), Some(payload));
+		}
+	}
+
+	#[test]
+	fn message_payload_of_unknown_version_is_rejected() {
+		let mut encoded = test_payload(None).encode();
+		encoded[4] = MESSAGE_PAYLOAD_VERSION + 1;
+
+		assert!(TestPayload::decode(&mut &encoded[..]).is_err());
+	}
+}
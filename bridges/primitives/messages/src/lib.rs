@@ -58,6 +58,30 @@ impl Default for OperatingMode {
 	}
 }
 
+/// Reason of message or proof rejection by the chain-specific verifier.
+///
+/// The messages pallet reports it to the transaction submitter as one of its errors, so wallets
+/// and relayers may distinguish rejection reasons.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum VerificationError {
+	/// The verifier hasn't reported a specific reason. The reason is only logged.
+	Unspecified,
+	/// The bridge rejects outbound messages.
+	OutboundMessagesRejected,
+	/// The conversion rate of the bridged tokens is stale.
+	StaleConversionRate,
+	/// The bridge rejects inbound messages.
+	InboundMessagesRejected,
+	/// The inbound message lane is disabled.
+	InboundLaneDisabled,
+}
+
+impl From<&'static str> for VerificationError {
+	fn from(_: &'static str) -> Self {
+		VerificationError::Unspecified
+	}
+}
+
 /// Messages pallet parameter.
 pub trait Parameter: frame_support::Parameter {
 	/// Save parameter value in the runtime storage.
@@ -191,6 +215,75 @@ pub struct MessageDetails<OutboundMessageFee> {
 	pub dispatch_fee_payment: DispatchFeePayment,
 }
 
+/// Statistics of the outbound lane, returned by runtime APIs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct OutboundLaneStats<AccountId, Balance> {
+	/// Number of messages that have been sent over the lane.
+	pub messages_sent: MessageNonce,
+	/// Number of messages whose delivery has been confirmed.
+	pub messages_delivered: MessageNonce,
+	/// Number of delivered messages that haven't been dispatched at the bridged chain.
+	pub failed_dispatches: MessageNonce,
+	/// Total fee paid by submitters of messages whose delivery has been confirmed.
+	pub fees_collected: Balance,
+	/// The relayer that has delivered the latest confirmed message.
+	pub last_relayer: Option<AccountId>,
+}
+
+impl<AccountId, Balance: Default> Default for OutboundLaneStats<AccountId, Balance> {
+	fn default() -> Self {
+		OutboundLaneStats {
+			messages_sent: 0,
+			messages_delivered: 0,
+			failed_dispatches: 0,
+			fees_collected: Default::default(),
+			last_relayer: None,
+		}
+	}
+}
+
+/// Verdict on the payload of an inbound message, returned by runtime APIs.
+///
+/// It tells whether the message would be dispatched if it was delivered now.
+#[derive(Clone, Copy, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum MessagePayloadVerdict {
+	/// The message would be dispatched.
+	Accepted,
+	/// The message payload can't be decoded.
+	UndecodablePayload,
+	/// The lane is not registered or it is disabled.
+	LaneNotOpen,
+	/// The message has been built for an incompatible runtime version.
+	IncompatibleSpecVersion,
+	/// The message call can't be decoded.
+	UndecodableCall,
+	/// The encoded message call is too large.
+	CallTooLarge,
+	/// Calls are nested too deep in the message call.
+	CallTooDeep,
+	/// The message call is not allowed at the lane.
+	CallNotAllowed,
+	/// The declared dispatch weight is lower than the weight of the message call.
+	InsufficientDispatchWeight,
+	/// The declared dispatch weight exceeds the weight limit of the message call.
+	DispatchWeightLimitExceeded,
+}
+
+/// Result of the inbound message dispatch that has been simulated without committing any changes.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct MessageDispatchDryRun {
+	/// Verdict of checking the message payload against the current bridge configuration.
+	pub verdict: MessagePayloadVerdict,
+	/// Dispatch result flag, that would be relayed back to the source chain.
+	pub dispatch_result: bool,
+	/// Weight, declared by the message sender.
+	pub declared_weight: Weight,
+	/// Weight that would actually be spent by the dispatch.
+	pub actual_weight: Weight,
+	/// Whether the dispatch fee would be paid during dispatch.
+	pub dispatch_fee_paid_during_dispatch: bool,
+}
+
 /// Bit vector of message dispatch results.
 pub type DispatchResultsBitVec = BitVec<u8, Msb0>;
 
@@ -340,6 +433,37 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn dispatch_results_are_forwarded_for_every_message() {
+		use source_chain::{ForwardDispatchResults, OnDeliveryConfirmed, OnDispatchResult};
+
+		struct NonceOfDispatchedMessage;
+		impl OnDispatchResult for NonceOfDispatchedMessage {
+			fn on_dispatch_result(
+				_lane: &LaneId,
+				nonce: MessageNonce,
+				dispatch_result: bool,
+			) -> frame_support::weights::Weight {
+				if dispatch_result {
+					nonce
+				} else {
+					0
+				}
+			}
+		}
+
+		let mut messages = DeliveredMessages::new(5, true);
+		messages.note_dispatched_message(false);
+		messages.note_dispatched_message(true);
+		assert_eq!(
+			ForwardDispatchResults::<NonceOfDispatchedMessage>::on_messages_delivered(
+				&[0, 0, 0, 0],
+				&messages,
+			),
+			12,
+		);
+	}
+
 	#[test]
 	fn inbound_lane_data_returns_correct_hint() {
 		let test_cases = vec![
@@ -16,7 +16,9 @@
 
 //! Primitives of messages module, that are used on the source chain.
 
-use crate::{DeliveredMessages, InboundLaneData, LaneId, MessageNonce, OutboundLaneData};
+use crate::{
+	DeliveredMessages, InboundLaneData, LaneId, MessageNonce, OutboundLaneData, VerificationError,
+};
 
 use crate::UnrewardedRelayer;
 use bp_runtime::Size;
@@ -24,6 +26,7 @@ use frame_support::{weights::Weight, Parameter, RuntimeDebug};
 use sp_std::{
 	collections::{btree_map::BTreeMap, vec_deque::VecDeque},
 	fmt::Debug,
+	marker::PhantomData,
 	ops::RangeInclusive,
 };
 
@@ -63,7 +66,7 @@ pub struct RelayerRewards<Balance> {
 /// that's stuck) and/or processing messages without paying fees.
 pub trait TargetHeaderChain<Payload, AccountId> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<&'static str> + Into<VerificationError>;
 
 	/// Proof that messages have been received by target chain.
 	type MessagesDeliveryProof: Parameter + Size;
@@ -98,7 +101,7 @@ pub trait TargetHeaderChain<Payload, AccountId> {
 /// Any fee requirements should also be enforced here.
 pub trait LaneMessageVerifier<SenderOrigin, Submitter, Payload, Fee> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<&'static str> + Into<VerificationError>;
 
 	/// Verify message payload and return Ok(()) if message is valid and allowed to be sent over the
 	/// lane.
@@ -136,6 +139,14 @@ pub trait MessageDeliveryAndDispatchPayment<SenderOrigin, AccountId, Balance> {
 		relayer_fund_account: &AccountId,
 	) -> Result<(), Self::Error>;
 
+	/// Return delivery_and_dispatch_fee of the cancelled message from some relayers-fund account
+	/// to the submitter account.
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &AccountId,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error>;
+
 	/// Pay rewards for delivering messages to the given relayers.
 	///
 	/// The implementation may also choose to pay reward to the `confirmation_relayer`, which is
@@ -223,15 +234,80 @@ impl OnDeliveryConfirmed for Tuple {
 	}
 }
 
+/// Handler for dispatch results of outbound messages, acknowledged by the target chain.
+///
+/// Dispatch results are delivered back to the source chain together with the delivery
+/// confirmation, so they are only known once the delivery is confirmed.
+pub trait OnDispatchResult {
+	/// Called for every confirmed message with the result of its dispatch at the target chain.
+	///
+	/// Should return weight consumed by the call. The same limit as for
+	/// `OnDeliveryConfirmed::on_messages_delivered` applies to every message.
+	fn on_dispatch_result(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl OnDispatchResult for Tuple {
+	fn on_dispatch_result(lane: &LaneId, nonce: MessageNonce, dispatch_result: bool) -> Weight {
+		let mut total_weight: Weight = 0;
+		for_tuples!(
+			#(
+				total_weight = total_weight.saturating_add(Tuple::on_dispatch_result(lane, nonce, dispatch_result));
+			)*
+		);
+		total_weight
+	}
+}
+
+/// Delivery confirmation handler that passes the dispatch result of every confirmed message to
+/// the `OnDispatchResult` handler.
+pub struct ForwardDispatchResults<Handler>(PhantomData<Handler>);
+
+impl<Handler: OnDispatchResult> OnDeliveryConfirmed for ForwardDispatchResults<Handler> {
+	fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+		let dispatch_results = messages.dispatch_results.iter();
+		let mut total_weight: Weight = 0;
+		for (nonce, dispatch_result) in (messages.begin..=messages.end).zip(dispatch_results) {
+			total_weight = total_weight.saturating_add(Handler::on_dispatch_result(
+				lane,
+				nonce,
+				*dispatch_result,
+			));
+		}
+		total_weight
+	}
+}
+
 /// Handler for messages have been accepted
 pub trait OnMessageAccepted {
 	/// Called when a message has been accepted by message pallet.
 	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce) -> Weight;
 }
 
-impl OnMessageAccepted for () {
-	fn on_messages_accepted(_lane: &LaneId, _message: &MessageNonce) -> Weight {
-		0
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl OnMessageAccepted for Tuple {
+	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce) -> Weight {
+		let mut total_weight: Weight = 0;
+		for_tuples!(
+			#(
+				total_weight = total_weight.saturating_add(Tuple::on_messages_accepted(lane, message));
+			)*
+		);
+		total_weight
+	}
+}
+
+/// Expiration of outbound messages.
+///
+/// Fee of expired messages that are not yet delivered, may be refunded by anyone.
+pub trait MessageExpiration<Payload> {
+	/// Returns true if the message with given payload has expired.
+	fn is_expired(payload: &Payload) -> bool;
+}
+
+impl<Payload> MessageExpiration<Payload> for () {
+	fn is_expired(_payload: &Payload) -> bool {
+		false
 	}
 }
 
@@ -288,6 +364,14 @@ impl<SenderOrigin, AccountId, Balance>
 		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_submitter: &AccountId,
+		_fee: &Balance,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+	}
+
 	fn pay_relayers_rewards(
 		_lane_id: LaneId,
 		_messages_relayers: VecDeque<UnrewardedRelayer<AccountId>>,
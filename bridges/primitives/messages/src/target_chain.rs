@@ -16,7 +16,9 @@
 
 //! Primitives of messages module, that are used on the target chain.
 
-use crate::{LaneId, Message, MessageData, MessageKey, OutboundLaneData};
+use crate::{
+	LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData, VerificationError,
+};
 
 use bp_runtime::{messages::MessageDispatchResult, Size};
 use codec::{Decode, Encode, Error as CodecError};
@@ -61,7 +63,7 @@ pub struct DispatchMessage<DispatchPayload, Fee> {
 /// that's stuck) and/or processing messages without paying fees.
 pub trait SourceHeaderChain<Fee> {
 	/// Error type.
-	type Error: Debug + Into<&'static str>;
+	type Error: Debug + Into<&'static str> + Into<VerificationError>;
 
 	/// Proof that messages are sent from source chain. This may also include proof
 	/// of corresponding outbound lane states.
@@ -110,6 +112,30 @@ pub trait MessageDispatch<AccountId, Fee> {
 	) -> MessageDispatchResult;
 }
 
+/// Limits of inbound lanes that may be tuned per lane.
+///
+/// The limits of the lane are never larger than the limits, configured for all lanes of the
+/// messages pallet.
+pub trait InboundLaneLimits {
+	/// Maximal number of unrewarded relayer entries at the lane, or `None` if the lane uses the
+	/// limit of the pallet.
+	fn max_unrewarded_relayer_entries(lane: &LaneId) -> Option<MessageNonce>;
+
+	/// Maximal number of unconfirmed messages at the lane, or `None` if the lane uses the limit of
+	/// the pallet.
+	fn max_unconfirmed_messages(lane: &LaneId) -> Option<MessageNonce>;
+}
+
+impl InboundLaneLimits for () {
+	fn max_unrewarded_relayer_entries(_lane: &LaneId) -> Option<MessageNonce> {
+		None
+	}
+
+	fn max_unconfirmed_messages(_lane: &LaneId) -> Option<MessageNonce> {
+		None
+	}
+}
+
 impl<Message> Default for ProvedLaneMessages<Message> {
 	fn default() -> Self {
 		ProvedLaneMessages { lane_state: None, messages: Vec::new() }
@@ -29,7 +29,10 @@ pub use chain::{
 	HasherOf, HeaderOf, IndexOf, SignatureOf, TransactionEraOf,
 };
 pub use frame_support::storage::storage_prefix as storage_value_final_key;
-pub use storage_proof::{Error as StorageProofError, StorageProofChecker};
+pub use storage_proof::{
+	compact_storage_proof, decode_compact_storage_proof, Error as StorageProofError,
+	StorageProofChecker,
+};
 
 #[cfg(feature = "std")]
 pub use storage_proof::craft_valid_storage_proof;
@@ -60,6 +63,9 @@ pub const ROCOCO_CHAIN_ID: ChainId = *b"roco";
 /// Bridge-with-Wococo instance id.
 pub const WOCOCO_CHAIN_ID: ChainId = *b"woco";
 
+/// Bridge-with-Westend instance id.
+pub const WESTEND_CHAIN_ID: ChainId = *b"wend";
+
 /// Call-dispatch module prefix.
 pub const CALL_DISPATCH_MODULE_PREFIX: &[u8] = b"pallet-bridge/dispatch";
 
@@ -69,6 +75,10 @@ pub const ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/
 /// A unique prefix for entropy when generating a cross-chain account ID for the Root account.
 pub const ROOT_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/root";
 
+/// A unique domain of signatures that prove ownership of target chain accounts by the senders of
+/// the messages.
+pub const ACCOUNT_OWNERSHIP_DOMAIN: &[u8] = b"pallet-bridge/account-ownership";
+
 /// Generic header Id.
 #[derive(RuntimeDebug, Default, Clone, Copy, Eq, Hash, PartialEq)]
 pub struct HeaderId<Hash, Number>(pub Number, pub Hash);
@@ -110,11 +120,26 @@ where
 {
 	match id {
 		SourceAccount::Root =>
-			(ROOT_ACCOUNT_DERIVATION_PREFIX, bridge_id).using_encoded(blake2_256),
+			(ROOT_ACCOUNT_DERIVATION_PREFIX, bridge_id).using_encoded(blake2_256).into(),
 		SourceAccount::Account(id) =>
-			(ACCOUNT_DERIVATION_PREFIX, bridge_id, id).using_encoded(blake2_256),
+			derive_account_id_with_prefix(ACCOUNT_DERIVATION_PREFIX, bridge_id, id),
 	}
-	.into()
+}
+
+/// Derive an account ID from a foreign (non-root) account ID, using custom derivation prefix.
+///
+/// This works exactly like `derive_account_id`, but allows bridges to use their own entropy
+/// prefix instead of the `ACCOUNT_DERIVATION_PREFIX`. Deriving with `ACCOUNT_DERIVATION_PREFIX`
+/// gives the same result as `derive_account_id(bridge_id, SourceAccount::Account(id))`.
+pub fn derive_account_id_with_prefix<AccountId>(
+	prefix: &[u8],
+	bridge_id: ChainId,
+	id: AccountId,
+) -> H256
+where
+	AccountId: Encode,
+{
+	(prefix, bridge_id, id).using_encoded(blake2_256).into()
 }
 
 /// Derive the account ID of the shared relayer fund account.
@@ -276,4 +301,16 @@ mod tests {
 			),
 		);
 	}
+
+	#[test]
+	fn derive_account_id_with_default_prefix_matches_derive_account_id() {
+		assert_eq!(
+			derive_account_id_with_prefix(ACCOUNT_DERIVATION_PREFIX, ROCOCO_CHAIN_ID, 42u64),
+			derive_account_id(ROCOCO_CHAIN_ID, SourceAccount::Account(42u64)),
+		);
+		assert_ne!(
+			derive_account_id_with_prefix(b"custom-prefix", ROCOCO_CHAIN_ID, 42u64),
+			derive_account_id(ROCOCO_CHAIN_ID, SourceAccount::Account(42u64)),
+		);
+	}
 }
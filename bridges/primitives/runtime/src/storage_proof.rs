@@ -19,7 +19,7 @@
 use hash_db::{HashDB, Hasher, EMPTY_PREFIX};
 use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
-use sp_trie::{read_trie_value, LayoutV1, MemoryDB, StorageProof};
+use sp_trie::{read_trie_value, CompactProof, LayoutV1, MemoryDB, StorageProof};
 
 /// This struct is used to read storage values from a subset of a Merklized database. The "proof"
 /// is a subset of the nodes in the Merkle structure of the database, so that it provides
@@ -62,6 +62,34 @@ where
 pub enum Error {
 	StorageRootMismatch,
 	StorageValueUnavailable,
+	InvalidCompactProof,
+}
+
+/// Encodes the storage proof, generated for the given root, in the compact form.
+///
+/// Nodes of the compact proof are deduplicated and hashes of child nodes that are included in the
+/// proof are omitted, because they may be recomputed by the verifier.
+pub fn compact_storage_proof<H: Hasher>(
+	root: H::Out,
+	proof: StorageProof,
+) -> Result<Vec<Vec<u8>>, Error> {
+	proof
+		.into_compact_proof::<H>(root)
+		.map(|proof| proof.encoded_nodes)
+		.map_err(|_| Error::InvalidCompactProof)
+}
+
+/// Decodes the compact storage proof, encoded by [`compact_storage_proof`].
+///
+/// The root of the decoded proof is not checked here - it is checked by the
+/// [`StorageProofChecker`].
+pub fn decode_compact_storage_proof<H: Hasher>(
+	encoded_nodes: Vec<Vec<u8>>,
+) -> Result<StorageProof, Error> {
+	CompactProof { encoded_nodes }
+		.to_storage_proof::<H>(None)
+		.map(|(proof, _)| proof)
+		.map_err(|_| Error::InvalidCompactProof)
 }
 
 /// Return valid storage proof and state root.
@@ -116,4 +144,27 @@ pub mod tests {
 			Some(Error::StorageRootMismatch)
 		);
 	}
+
+	#[test]
+	fn compact_storage_proof_check() {
+		let (root, proof) = craft_valid_storage_proof();
+		let proof_size = proof.clone().iter_nodes().map(|node| node.len()).sum::<usize>();
+
+		// compact proof is smaller than the regular proof
+		let compact_proof = compact_storage_proof::<sp_core::Blake2Hasher>(root, proof).unwrap();
+		assert!(compact_proof.iter().map(|node| node.len()).sum::<usize>() < proof_size);
+
+		// check decoded proof in runtime
+		let proof = decode_compact_storage_proof::<sp_core::Blake2Hasher>(compact_proof).unwrap();
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(root, proof).unwrap();
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+		assert_eq!(checker.read_value(b"key2"), Ok(Some(b"value2".to_vec())));
+		assert_eq!(checker.read_value(b"key22"), Ok(None));
+
+		// garbage can't be decoded
+		assert_eq!(
+			decode_compact_storage_proof::<sp_core::Blake2Hasher>(vec![vec![42; 32]]).err(),
+			Some(Error::InvalidCompactProof)
+		);
+	}
 }
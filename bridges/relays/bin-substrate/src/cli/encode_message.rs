@@ -83,7 +83,7 @@ mod tests {
 	#[test]
 	fn should_encode_raw_message() {
 		// given
-		let msg = "01000000e88514000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d003c040130000000000000000000000000";
+		let msg = "ffffffff0101000000e88514000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00003c040130000000000000000000000000";
 		let encode_message =
 			EncodeMessage::from_iter(vec!["encode-message", "rialto-to-millau", "raw", msg]);
 
@@ -94,6 +94,20 @@ mod tests {
 		assert_eq!(format!("{:?}", hex), format!("0x{}", msg));
 	}
 
+	#[test]
+	fn should_encode_raw_message_of_legacy_layout() {
+		// given: the message, encoded before payloads were versioned
+		let msg = "01000000e88514000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d003c040130000000000000000000000000";
+		let encode_message =
+			EncodeMessage::from_iter(vec!["encode-message", "rialto-to-millau", "raw", msg]);
+
+		// when
+		let hex = encode_message.encode().unwrap();
+
+		// then: it is re-encoded with the current version and without expiration
+		assert_eq!(format!("{:?}", hex), "0xffffffff0101000000e88514000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00003c040130000000000000000000000000");
+	}
+
 	#[test]
 	fn should_encode_remark_with_size() {
 		// given
@@ -115,6 +129,6 @@ mod tests {
 		let hex = encode_message.encode().unwrap();
 
 		// then
-		assert_eq!(format!("{:?}", hex), "0x010000002a0000000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d003c000130000000000000000000000000");
+		assert_eq!(format!("{:?}", hex), "0xffffffff01010000002a0000000000000002d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00003c000130000000000000000000000000");
 	}
 }
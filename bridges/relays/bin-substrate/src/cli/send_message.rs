@@ -293,6 +293,7 @@ where
 		weight,
 		origin,
 		dispatch_fee_payment: dispatch_fee_payment.into(),
+		expires_at: None,
 		call: HexBytes::encode(call),
 	};
 
@@ -300,8 +301,9 @@ where
 	log::info!(target: "bridge", "Encoded Message Payload: {:?}", HexBytes::encode(&payload));
 
 	// re-pack to return `Vec<u8>`
-	let MessagePayload { spec_version, weight, origin, dispatch_fee_payment, call } = payload;
-	MessagePayload { spec_version, weight, origin, dispatch_fee_payment, call: call.0 }
+	let MessagePayload { spec_version, weight, origin, dispatch_fee_payment, expires_at, call } =
+		payload;
+	MessagePayload { spec_version, weight, origin, dispatch_fee_payment, expires_at, call: call.0 }
 }
 
 pub(crate) fn compute_maximal_message_dispatch_weight(maximal_extrinsic_weight: Weight) -> Weight {
@@ -346,6 +348,7 @@ mod tests {
 					sp_keyring::AccountKeyring::Alice.to_account_id()
 				),
 				dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+				expires_at: None,
 				call: hex!("0001081234").to_vec(),
 			}
 		);
@@ -392,6 +395,7 @@ mod tests {
 					signature,
 				),
 				dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+				expires_at: None,
 				call: hex!("0001081234").to_vec(),
 			}
 		);
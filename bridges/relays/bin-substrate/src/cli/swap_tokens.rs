@@ -234,6 +234,7 @@ impl SwapTokens {
 						),
 						dispatch_fee_payment:
 							bp_runtime::messages::DispatchFeePayment::AtTargetChain,
+						expires_at: None,
 						call: bridged_currency_transfer.encode(),
 					},
 				)
@@ -375,6 +376,7 @@ impl SwapTokens {
 						accounts.target_account_at_bridged_chain.clone(),
 					),
 					dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+					expires_at: None,
 					call: claim_swap_call.encode(),
 				};
 				let claim_swap_delivery_and_dispatch_fee =
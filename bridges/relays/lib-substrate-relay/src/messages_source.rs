@@ -32,8 +32,10 @@ use bp_messages::{
 	storage_keys::{operating_mode_key, outbound_lane_data_key},
 	LaneId, MessageNonce, OperatingMode, OutboundLaneData, UnrewardedRelayersState,
 };
+use bp_runtime::{compact_storage_proof, HasherOf};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
+	StorageProofVersion,
 };
 use codec::{Decode, Encode};
 use frame_support::weights::Weight;
@@ -235,15 +237,16 @@ where
 			));
 		}
 
-		let proof = self
-			.source_client
-			.prove_storage(storage_keys, id.1)
-			.await?
-			.iter_nodes()
-			.collect();
+		// compact proof is significantly smaller than the regular proof when multiple messages are
+		// delivered, because all messages share the same trie path
+		let proof = self.source_client.prove_storage(storage_keys, id.1).await?;
+		let state_root = *self.source_client.header_by_hash(id.1).await?.state_root();
+		let proof = compact_storage_proof::<HasherOf<P::SourceChain>>(state_root, proof)
+			.map_err(SubstrateError::StorageProofError)?;
 		let proof = FromBridgedChainMessagesProof {
 			bridged_header_hash: id.1,
 			storage_proof: proof,
+			storage_proof_version: StorageProofVersion::Compact,
 			lane: self.lane_id,
 			nonces_start: *nonces.start(),
 			nonces_end: *nonces.end(),
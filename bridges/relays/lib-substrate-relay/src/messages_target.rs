@@ -454,6 +454,7 @@ fn prepare_dummy_messages_proof<SC: Chain>(
 				0;
 				SC::STORAGE_PROOF_OVERHEAD.saturating_add(total_size) as usize
 			]],
+			storage_proof_version: Default::default(),
 			lane: Default::default(),
 			nonces_start: *nonces.start(),
 			nonces_end: *nonces.end(),
@@ -0,0 +1,151 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding and validation of the bridged messages payloads.
+//!
+//! The `bridge-decode-message` subcommand decodes the payload of a message that is sent to the
+//! local chain using the runtime the binary has been built with. Then it asks the local node
+//! whether the message would be dispatched, given the current call filter of the lane and the
+//! weight limits of the call, so message authors may validate payloads before paying fees.
+
+use crate::bridge_relay::{parse_lane_id, LocalChain};
+use bp_message_dispatch::MessagePayload;
+use bp_messages::{LaneId, MessagePayloadVerdict};
+use codec::{Decode, Encode};
+use relay_rococo_client::Rococo;
+use relay_substrate_client::{ChainRuntimeVersion, Client, ConnectionParams};
+use relay_wococo_client::Wococo;
+use service::rococo_runtime::{
+	decode_inbound_bridge_call, FromRococoMessagePayload, FromWococoMessagePayload,
+};
+use sp_core::Bytes;
+use std::fmt::Debug;
+
+/// Decode the payload of a message, sent to the local chain, and check whether it would be
+/// dispatched.
+#[derive(Debug, clap::Parser)]
+pub struct BridgeDecodeMessageCmd {
+	/// Hex-encoded message payload.
+	pub payload: String,
+
+	/// Chain of the local node: `rococo` or `wococo`.
+	#[clap(long, default_value = "rococo")]
+	pub chain: LocalChain,
+
+	/// Hex-encoded identifier of the lane the message is sent over.
+	#[clap(long, default_value = "00000000", parse(try_from_str = parse_lane_id))]
+	pub lane: LaneId,
+
+	/// Host name of the local node websocket RPC server.
+	#[clap(long, default_value = "127.0.0.1")]
+	pub rpc_host: String,
+
+	/// TCP port of the local node websocket RPC server.
+	#[clap(long, default_value = "9944")]
+	pub rpc_port: u16,
+
+	/// Use secure websocket connection to the local node.
+	#[clap(long)]
+	pub rpc_secure: bool,
+}
+
+impl BridgeDecodeMessageCmd {
+	/// Print the decoded payload and the verdict of the local node.
+	///
+	/// Fails if the message wouldn't be dispatched.
+	pub async fn run(&self) -> anyhow::Result<()> {
+		let connection_params = ConnectionParams {
+			host: self.rpc_host.clone(),
+			port: self.rpc_port,
+			secure: self.rpc_secure,
+			chain_runtime_version: ChainRuntimeVersion::Auto,
+		};
+		let payload = hex::decode(self.payload.trim_start_matches("0x"))
+			.map_err(|e| anyhow::format_err!("Invalid payload: {}", e))?;
+		let verdict = match self.chain {
+			LocalChain::Rococo => {
+				if let Some(payload) = decode_payload::<FromWococoMessagePayload>(&payload) {
+					print_payload(&payload);
+				}
+				let client = Client::<Rococo>::try_connect(connection_params).await?;
+				let method = bp_wococo::FROM_WOCOCO_CHECK_MESSAGE_PAYLOAD_METHOD;
+				check_payload(&client, method, self.lane, payload).await?
+			},
+			LocalChain::Wococo => {
+				if let Some(payload) = decode_payload::<FromRococoMessagePayload>(&payload) {
+					print_payload(&payload);
+				}
+				let client = Client::<Wococo>::try_connect(connection_params).await?;
+				let method = bp_rococo::FROM_ROCOCO_CHECK_MESSAGE_PAYLOAD_METHOD;
+				check_payload(&client, method, self.lane, payload).await?
+			},
+		};
+
+		println!("Verdict: {:?}", verdict);
+		if verdict != MessagePayloadVerdict::Accepted {
+			anyhow::bail!("The message wouldn't be dispatched at lane {}", hex::encode(self.lane));
+		}
+
+		Ok(())
+	}
+}
+
+/// Decode the payload with the types of the bundled runtime.
+fn decode_payload<Payload: Decode>(encoded_payload: &[u8]) -> Option<Payload> {
+	match Payload::decode(&mut &encoded_payload[..]) {
+		Ok(payload) => Some(payload),
+		Err(error) => {
+			println!("The payload can't be decoded: {}", error);
+			None
+		},
+	}
+}
+
+fn print_payload<SourceAccountId, TargetAccountPublic, TargetSignature, Call>(
+	payload: &MessagePayload<SourceAccountId, TargetAccountPublic, TargetSignature, Call>,
+) where
+	SourceAccountId: Debug,
+	TargetAccountPublic: Debug,
+	TargetSignature: Debug,
+	Call: Encode,
+{
+	println!("Spec version: {}", payload.spec_version);
+	println!("Dispatch weight: {}", payload.weight);
+	println!("Origin: {:?}", payload.origin);
+	println!("Dispatch fee payment: {:?}", payload.dispatch_fee_payment);
+	println!("Expires at: {:?}", payload.expires_at);
+
+	// the encoded call is private to the payload, so we decode a copy of it
+	match Vec::<u8>::decode(&mut &payload.call.encode()[..]) {
+		Ok(encoded_call) => match decode_inbound_bridge_call(&encoded_call) {
+			Ok(call) => println!("Call: {:?}", call),
+			Err(error) => println!("Call: {:?} ({})", error, hex::encode(&encoded_call)),
+		},
+		Err(error) => println!("Call can't be decoded: {}", error),
+	}
+}
+
+/// Ask the local node to check the payload against the current bridge configuration.
+async fn check_payload<C: relay_substrate_client::Chain>(
+	client: &Client<C>,
+	method: &str,
+	lane: LaneId,
+	payload: Vec<u8>,
+) -> anyhow::Result<MessagePayloadVerdict> {
+	let encoded_verdict =
+		client.state_call(method.into(), Bytes((lane, payload).encode()), None).await?;
+	Ok(Decode::decode(&mut &encoded_verdict.0[..])?)
+}
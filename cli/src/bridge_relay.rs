@@ -0,0 +1,420 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Headers and messages relay between Rococo and Wococo, embedded into the node binary.
+//!
+//! The `bridge-relay` subcommand connects to the RPC endpoint of the local node and to the RPC
+//! endpoint of the bridged chain and runs the same relay loops as the standalone
+//! `substrate-relay` binary: on-demand GRANDPA finality relays in both directions and a messages
+//! relay in both directions for every served lane.
+//!
+//! The relay is configured from the runtime the binary has been built with. Both chains are
+//! expected to run the runtime version that is bundled into the binary, and the relay stops once
+//! either chain upgrades to another version. Unless lanes are given explicitly, the relay serves
+//! all lanes registered at the local chain.
+
+use async_trait::async_trait;
+use bp_messages::LaneId;
+use codec::Decode;
+use frame_support::weights::Weight;
+use futures::{FutureExt, TryFutureExt};
+use messages_relay::{message_lane_loop::RelayerMode, relay_strategy::MixStrategy};
+use relay_rococo_client::Rococo;
+use relay_substrate_client::{ChainRuntimeVersion, Client, ConnectionParams};
+use relay_utils::metrics::MetricsParams;
+use relay_wococo_client::Wococo;
+use sp_core::{sr25519, Bytes, Pair};
+use std::str::FromStr;
+use substrate_relay_helper::{
+	finality_pipeline::SubstrateFinalitySyncPipeline,
+	messages_lane::{MessagesRelayParams, SubstrateMessageLane},
+	on_demand_headers::OnDemandHeadersRelay,
+	TransactionParams,
+};
+
+/// Maximal saturating difference between `balance(now)` and `balance(now-24h)` to treat
+/// relay as gone wild.
+///
+/// This is the same limit that the standalone relayer uses for both bridge directions. Note that
+/// this is in plancks, so this corresponds to `1500 UNITS`.
+const MAXIMAL_BALANCE_DECREASE_PER_DAY: bp_rococo::Balance = 1_500_000_000_000_000;
+
+/// Chain of the local node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalChain {
+	/// The local node is a Rococo node and the bridged chain is Wococo.
+	Rococo,
+	/// The local node is a Wococo node and the bridged chain is Rococo.
+	Wococo,
+}
+
+impl FromStr for LocalChain {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"rococo" => Ok(LocalChain::Rococo),
+			"wococo" => Ok(LocalChain::Wococo),
+			_ => Err(format!("Bridge relay is not supported for chain {}", s)),
+		}
+	}
+}
+
+/// Relay headers and messages between the local chain and the bridged chain.
+#[derive(Debug, clap::Parser)]
+pub struct BridgeRelayCmd {
+	/// Chain of the local node: `rococo` or `wococo`.
+	#[clap(long, default_value = "rococo")]
+	pub chain: LocalChain,
+
+	/// Host name of the local node websocket RPC server.
+	#[clap(long, default_value = "127.0.0.1")]
+	pub local_rpc_host: String,
+
+	/// TCP port of the local node websocket RPC server.
+	#[clap(long, default_value = "9944")]
+	pub local_rpc_port: u16,
+
+	/// Use secure websocket connection to the local node.
+	#[clap(long)]
+	pub local_rpc_secure: bool,
+
+	/// Host name of the bridged chain websocket RPC server.
+	#[clap(long)]
+	pub bridged_rpc_host: String,
+
+	/// TCP port of the bridged chain websocket RPC server.
+	#[clap(long, default_value = "9944")]
+	pub bridged_rpc_port: u16,
+
+	/// Use secure websocket connection to the bridged chain.
+	#[clap(long)]
+	pub bridged_rpc_secure: bool,
+
+	/// Secret URI of the relayer account. It signs transactions at both chains.
+	#[clap(long)]
+	pub relayer_suri: String,
+
+	/// Password of the relayer account secret URI.
+	#[clap(long)]
+	pub relayer_password: Option<String>,
+
+	/// Hex-encoded identifiers of lanes that should be served by the relay.
+	///
+	/// If no lanes are given, the relay serves all lanes registered at the local chain.
+	#[clap(long, parse(try_from_str = parse_lane_id))]
+	pub lane: Vec<LaneId>,
+
+	/// Relay only mandatory headers (headers that are changing the GRANDPA authorities set).
+	#[clap(long)]
+	pub only_mandatory_headers: bool,
+
+	/// Deliver messages and confirmations even if the relayer is not rewarded for that.
+	#[clap(long)]
+	pub altruistic: bool,
+}
+
+impl BridgeRelayCmd {
+	/// Run the relay until any of its loops fails.
+	pub async fn run(&self) -> anyhow::Result<()> {
+		let local = self.local_connection_params();
+		let bridged = self.bridged_connection_params();
+		let (rococo, wococo) = match self.chain {
+			LocalChain::Rococo => (local, bridged),
+			LocalChain::Wococo => (bridged, local),
+		};
+		let rococo_client = Client::<Rococo>::try_connect(rococo).await?;
+		let wococo_client = Client::<Wococo>::try_connect(wococo).await?;
+
+		let lanes = if self.lane.is_empty() {
+			self.registered_lanes(&rococo_client, &wococo_client).await?
+		} else {
+			self.lane.clone()
+		};
+		if lanes.is_empty() {
+			anyhow::bail!("There are no lanes to serve at the local chain");
+		}
+
+		let signer =
+			sr25519::Pair::from_string(&self.relayer_suri, self.relayer_password.as_deref())
+				.map_err(|e| anyhow::format_err!("Invalid relayer secret URI: {:?}", e))?;
+		let transaction_params = TransactionParams { signer, mortality: None };
+		let relayer_mode =
+			if self.altruistic { RelayerMode::Altruistic } else { RelayerMode::Rational };
+		let relay_strategy = MixStrategy::new(relayer_mode);
+
+		// version guards are stopping the relay once the chain is upgraded to another runtime
+		RococoFinalityToWococo::start_relay_guards(&wococo_client, &transaction_params, true)
+			.await?;
+		WococoFinalityToRococo::start_relay_guards(&rococo_client, &transaction_params, true)
+			.await?;
+		let rococo_to_wococo_headers = OnDemandHeadersRelay::new::<RococoFinalityToWococo>(
+			rococo_client.clone(),
+			wococo_client.clone(),
+			transaction_params.clone(),
+			self.only_mandatory_headers,
+		);
+		let wococo_to_rococo_headers = OnDemandHeadersRelay::new::<WococoFinalityToRococo>(
+			wococo_client.clone(),
+			rococo_client.clone(),
+			transaction_params.clone(),
+			self.only_mandatory_headers,
+		);
+
+		let mut message_relays = Vec::with_capacity(lanes.len() * 2);
+		for lane in lanes {
+			log::info!(target: "bridge", "Serving lane {}", hex::encode(lane));
+
+			let rococo_to_wococo_messages = substrate_relay_helper::messages_lane::run::<
+				RococoMessagesToWococo,
+			>(MessagesRelayParams {
+				source_client: rococo_client.clone(),
+				source_transaction_params: transaction_params.clone(),
+				target_client: wococo_client.clone(),
+				target_transaction_params: transaction_params.clone(),
+				source_to_target_headers_relay: Some(rococo_to_wococo_headers.clone()),
+				target_to_source_headers_relay: Some(wococo_to_rococo_headers.clone()),
+				lane_id: lane,
+				metrics_params: MetricsParams::disabled(),
+				standalone_metrics: None,
+				relay_strategy: relay_strategy.clone(),
+			})
+			.map_err(|e| anyhow::format_err!("{}", e))
+			.boxed();
+			let wococo_to_rococo_messages = substrate_relay_helper::messages_lane::run::<
+				WococoMessagesToRococo,
+			>(MessagesRelayParams {
+				source_client: wococo_client.clone(),
+				source_transaction_params: transaction_params.clone(),
+				target_client: rococo_client.clone(),
+				target_transaction_params: transaction_params.clone(),
+				source_to_target_headers_relay: Some(wococo_to_rococo_headers.clone()),
+				target_to_source_headers_relay: Some(rococo_to_wococo_headers.clone()),
+				lane_id: lane,
+				metrics_params: MetricsParams::disabled(),
+				standalone_metrics: None,
+				relay_strategy: relay_strategy.clone(),
+			})
+			.map_err(|e| anyhow::format_err!("{}", e))
+			.boxed();
+
+			message_relays.push(rococo_to_wococo_messages);
+			message_relays.push(wococo_to_rococo_messages);
+		}
+
+		futures::future::select_all(message_relays).await.0
+	}
+
+	fn local_connection_params(&self) -> ConnectionParams {
+		ConnectionParams {
+			host: self.local_rpc_host.clone(),
+			port: self.local_rpc_port,
+			secure: self.local_rpc_secure,
+			chain_runtime_version: bundled_runtime_version(),
+		}
+	}
+
+	fn bridged_connection_params(&self) -> ConnectionParams {
+		ConnectionParams {
+			host: self.bridged_rpc_host.clone(),
+			port: self.bridged_rpc_port,
+			secure: self.bridged_rpc_secure,
+			chain_runtime_version: bundled_runtime_version(),
+		}
+	}
+
+	/// Reads identifiers of lanes that are registered at the local chain.
+	async fn registered_lanes(
+		&self,
+		rococo_client: &Client<Rococo>,
+		wococo_client: &Client<Wococo>,
+	) -> anyhow::Result<Vec<LaneId>> {
+		let encoded_lanes = match self.chain {
+			LocalChain::Rococo => {
+				rococo_client
+					.state_call(bp_wococo::TO_WOCOCO_LANES_METHOD.into(), Bytes(Vec::new()), None)
+					.await?
+			},
+			LocalChain::Wococo => {
+				wococo_client
+					.state_call(bp_rococo::TO_ROCOCO_LANES_METHOD.into(), Bytes(Vec::new()), None)
+					.await?
+			},
+		};
+
+		Ok(Decode::decode(&mut &encoded_lanes.0[..])?)
+	}
+}
+
+/// Returns version of the runtime that is bundled into the binary.
+///
+/// Rococo and Wococo are running the same runtime, so its version is used for both chains.
+fn bundled_runtime_version() -> ChainRuntimeVersion {
+	let version = &service::rococo_runtime::VERSION;
+	ChainRuntimeVersion::Custom(version.spec_version, version.transaction_version)
+}
+
+pub(crate) fn parse_lane_id(s: &str) -> Result<LaneId, String> {
+	let mut lane = LaneId::default();
+	hex::decode_to_slice(s, &mut lane).map_err(|e| format!("Invalid lane {}: {}", s, e))?;
+	Ok(lane)
+}
+
+/// Description of Rococo -> Wococo finalized headers bridge.
+#[derive(Clone, Debug)]
+pub struct RococoFinalityToWococo;
+substrate_relay_helper::generate_mocked_submit_finality_proof_call_builder!(
+	RococoFinalityToWococo,
+	RococoFinalityToWococoCallBuilder,
+	relay_wococo_client::runtime::Call::BridgeGrandpaRococo,
+	relay_wococo_client::runtime::BridgeGrandpaRococoCall::submit_finality_proof
+);
+
+#[async_trait]
+impl SubstrateFinalitySyncPipeline for RococoFinalityToWococo {
+	type SourceChain = Rococo;
+	type TargetChain = Wococo;
+
+	type SubmitFinalityProofCallBuilder = RococoFinalityToWococoCallBuilder;
+	type TransactionSignScheme = Wococo;
+
+	async fn start_relay_guards(
+		target_client: &Client<Wococo>,
+		transaction_params: &TransactionParams<sr25519::Pair>,
+		enable_version_guard: bool,
+	) -> relay_substrate_client::Result<()> {
+		substrate_relay_helper::finality_guards::start::<Wococo, Wococo>(
+			target_client,
+			transaction_params,
+			enable_version_guard,
+			MAXIMAL_BALANCE_DECREASE_PER_DAY,
+		)
+		.await
+	}
+}
+
+/// Description of Wococo -> Rococo finalized headers bridge.
+#[derive(Clone, Debug)]
+pub struct WococoFinalityToRococo;
+substrate_relay_helper::generate_mocked_submit_finality_proof_call_builder!(
+	WococoFinalityToRococo,
+	WococoFinalityToRococoCallBuilder,
+	relay_rococo_client::runtime::Call::BridgeGrandpaWococo,
+	relay_rococo_client::runtime::BridgeGrandpaWococoCall::submit_finality_proof
+);
+
+#[async_trait]
+impl SubstrateFinalitySyncPipeline for WococoFinalityToRococo {
+	type SourceChain = Wococo;
+	type TargetChain = Rococo;
+
+	type SubmitFinalityProofCallBuilder = WococoFinalityToRococoCallBuilder;
+	type TransactionSignScheme = Rococo;
+
+	async fn start_relay_guards(
+		target_client: &Client<Rococo>,
+		transaction_params: &TransactionParams<sr25519::Pair>,
+		enable_version_guard: bool,
+	) -> relay_substrate_client::Result<()> {
+		substrate_relay_helper::finality_guards::start::<Rococo, Rococo>(
+			target_client,
+			transaction_params,
+			enable_version_guard,
+			MAXIMAL_BALANCE_DECREASE_PER_DAY,
+		)
+		.await
+	}
+}
+
+/// Description of Rococo -> Wococo messages bridge.
+#[derive(Clone, Debug)]
+pub struct RococoMessagesToWococo;
+substrate_relay_helper::generate_mocked_receive_message_proof_call_builder!(
+	RococoMessagesToWococo,
+	RococoMessagesToWococoReceiveMessagesProofCallBuilder,
+	relay_wococo_client::runtime::Call::BridgeRococoMessages,
+	relay_wococo_client::runtime::BridgeRococoMessagesCall::receive_messages_proof
+);
+substrate_relay_helper::generate_mocked_receive_message_delivery_proof_call_builder!(
+	RococoMessagesToWococo,
+	RococoMessagesToWococoReceiveMessagesDeliveryProofCallBuilder,
+	relay_rococo_client::runtime::Call::BridgeWococoMessages,
+	relay_rococo_client::runtime::BridgeWococoMessagesCall::receive_messages_delivery_proof
+);
+
+impl SubstrateMessageLane for RococoMessagesToWococo {
+	const SOURCE_TO_TARGET_CONVERSION_RATE_PARAMETER_NAME: Option<&'static str> = None;
+	const TARGET_TO_SOURCE_CONVERSION_RATE_PARAMETER_NAME: Option<&'static str> = None;
+
+	const SOURCE_FEE_MULTIPLIER_PARAMETER_NAME: Option<&'static str> = None;
+	const TARGET_FEE_MULTIPLIER_PARAMETER_NAME: Option<&'static str> = None;
+	const AT_SOURCE_TRANSACTION_PAYMENT_PALLET_NAME: Option<&'static str> = None;
+	const AT_TARGET_TRANSACTION_PAYMENT_PALLET_NAME: Option<&'static str> = None;
+
+	type SourceChain = Rococo;
+	type TargetChain = Wococo;
+
+	type SourceTransactionSignScheme = Rococo;
+	type TargetTransactionSignScheme = Wococo;
+
+	type ReceiveMessagesProofCallBuilder = RococoMessagesToWococoReceiveMessagesProofCallBuilder;
+	type ReceiveMessagesDeliveryProofCallBuilder =
+		RococoMessagesToWococoReceiveMessagesDeliveryProofCallBuilder;
+
+	type TargetToSourceChainConversionRateUpdateBuilder = ();
+
+	type RelayStrategy = MixStrategy;
+}
+
+/// Description of Wococo -> Rococo messages bridge.
+#[derive(Clone, Debug)]
+pub struct WococoMessagesToRococo;
+substrate_relay_helper::generate_mocked_receive_message_proof_call_builder!(
+	WococoMessagesToRococo,
+	WococoMessagesToRococoReceiveMessagesProofCallBuilder,
+	relay_rococo_client::runtime::Call::BridgeWococoMessages,
+	relay_rococo_client::runtime::BridgeWococoMessagesCall::receive_messages_proof
+);
+substrate_relay_helper::generate_mocked_receive_message_delivery_proof_call_builder!(
+	WococoMessagesToRococo,
+	WococoMessagesToRococoReceiveMessagesDeliveryProofCallBuilder,
+	relay_wococo_client::runtime::Call::BridgeRococoMessages,
+	relay_wococo_client::runtime::BridgeRococoMessagesCall::receive_messages_delivery_proof
+);
+
+impl SubstrateMessageLane for WococoMessagesToRococo {
+	const SOURCE_TO_TARGET_CONVERSION_RATE_PARAMETER_NAME: Option<&'static str> = None;
+	const TARGET_TO_SOURCE_CONVERSION_RATE_PARAMETER_NAME: Option<&'static str> = None;
+
+	const SOURCE_FEE_MULTIPLIER_PARAMETER_NAME: Option<&'static str> = None;
+	const TARGET_FEE_MULTIPLIER_PARAMETER_NAME: Option<&'static str> = None;
+	const AT_SOURCE_TRANSACTION_PAYMENT_PALLET_NAME: Option<&'static str> = None;
+	const AT_TARGET_TRANSACTION_PAYMENT_PALLET_NAME: Option<&'static str> = None;
+
+	type SourceChain = Wococo;
+	type TargetChain = Rococo;
+
+	type SourceTransactionSignScheme = Wococo;
+	type TargetTransactionSignScheme = Rococo;
+
+	type ReceiveMessagesProofCallBuilder = WococoMessagesToRococoReceiveMessagesProofCallBuilder;
+	type ReceiveMessagesDeliveryProofCallBuilder =
+		WococoMessagesToRococoReceiveMessagesDeliveryProofCallBuilder;
+
+	type TargetToSourceChainConversionRateUpdateBuilder = ();
+
+	type RelayStrategy = MixStrategy;
+}
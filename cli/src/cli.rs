@@ -70,6 +70,25 @@ pub enum Subcommand {
 	/// Key management CLI utilities
 	#[clap(subcommand)]
 	Key(sc_cli::KeySubcommand),
+
+	/// Relay headers and messages between the local chain and the bridged chain.
+	#[cfg(feature = "bridge-relay")]
+	BridgeRelay(crate::bridge_relay::BridgeRelayCmd),
+
+	/// Relay headers and messages between the local chain and the bridged chain. Note:
+	/// `bridge-relay` feature must be enabled.
+	#[cfg(not(feature = "bridge-relay"))]
+	BridgeRelay,
+
+	/// Decode the payload of a message, sent to the local chain, and check whether it would be
+	/// dispatched.
+	#[cfg(feature = "bridge-relay")]
+	BridgeDecodeMessage(crate::bridge_decode_message::BridgeDecodeMessageCmd),
+
+	/// Decode the payload of a message, sent to the local chain, and check whether it would be
+	/// dispatched. Note: `bridge-relay` feature must be enabled.
+	#[cfg(not(feature = "bridge-relay"))]
+	BridgeDecodeMessage,
 }
 
 #[allow(missing_docs)]
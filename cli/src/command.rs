@@ -580,6 +580,19 @@ pub fn run() -> Result<()> {
 					))
 				})
 			}
+
+			#[cfg(feature = "rococo-native")]
+			if chain_spec.is_rococo() || chain_spec.is_wococo() || chain_spec.is_versi() {
+				return runner.async_run(|config| {
+					Ok((
+						cmd.run::<service::rococo_runtime::Block, service::RococoExecutorDispatch>(
+							config,
+						)
+						.map_err(Error::SubstrateCli),
+						task_manager,
+					))
+				})
+			}
 			// else we assume it is polkadot.
 			#[cfg(feature = "polkadot-native")]
 			{
@@ -603,6 +616,34 @@ pub fn run() -> Result<()> {
 				.into(),
 		)
 		.into()),
+		#[cfg(feature = "bridge-relay")]
+		Some(Subcommand::BridgeRelay(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("bridge=info");
+			builder.with_colors(true);
+			builder.init()?;
+
+			Ok(async_std::task::block_on(cmd.run())?)
+		},
+		#[cfg(not(feature = "bridge-relay"))]
+		Some(Subcommand::BridgeRelay) => Err(Error::Other(
+			"BridgeRelay wasn't enabled when building the node. \
+				You can enable it with `--features bridge-relay`."
+				.into(),
+		)),
+		#[cfg(feature = "bridge-relay")]
+		Some(Subcommand::BridgeDecodeMessage(cmd)) => {
+			let mut builder = sc_cli::LoggerBuilder::new("");
+			builder.with_colors(true);
+			builder.init()?;
+
+			Ok(async_std::task::block_on(cmd.run())?)
+		},
+		#[cfg(not(feature = "bridge-relay"))]
+		Some(Subcommand::BridgeDecodeMessage) => Err(Error::Other(
+			"BridgeDecodeMessage wasn't enabled when building the node. \
+				You can enable it with `--features bridge-relay`."
+				.into(),
+		)),
 	}?;
 
 	#[cfg(feature = "pyroscope")]
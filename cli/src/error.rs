@@ -39,6 +39,10 @@ pub enum Error {
 	#[error("Failed to connect to pyroscope agent")]
 	PyroscopeError(#[from] pyro::error::PyroscopeError),
 
+	#[cfg(feature = "bridge-relay")]
+	#[error(transparent)]
+	BridgeRelay(#[from] anyhow::Error),
+
 	#[error("Failed to resolve provided URL")]
 	AddressResolutionFailure(#[from] std::io::Error),
 
@@ -18,6 +18,10 @@
 
 #![warn(missing_docs)]
 
+#[cfg(feature = "bridge-relay")]
+mod bridge_decode_message;
+#[cfg(feature = "bridge-relay")]
+mod bridge_relay;
 #[cfg(feature = "cli")]
 mod cli;
 #[cfg(feature = "cli")]
@@ -20,7 +20,7 @@
 //! There is also the [`Client`] enum that combines all the different clients into one common structure.
 
 use polkadot_primitives::v2::{
-	AccountId, Balance, Block, BlockNumber, Hash, Header, Nonce, ParachainHost,
+	AccountId, AuditApi, Balance, Block, BlockNumber, Hash, Header, Nonce, ParachainHost,
 };
 use sc_client_api::{AuxStore, Backend as BackendT, BlockchainEvents, KeyIterator, UsageProvider};
 use sc_executor::NativeElseWasmExecutor;
@@ -125,6 +125,7 @@ pub trait RuntimeApiCollection:
 	+ sp_consensus_babe::BabeApi<Block>
 	+ sp_finality_grandpa::GrandpaApi<Block>
 	+ ParachainHost<Block>
+	+ AuditApi<Block>
 	+ sp_block_builder::BlockBuilder<Block>
 	+ frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce>
 	+ pallet_mmr_primitives::MmrApi<Block, <Block as BlockT>::Hash>
@@ -146,6 +147,7 @@ where
 		+ sp_consensus_babe::BabeApi<Block>
 		+ sp_finality_grandpa::GrandpaApi<Block>
 		+ ParachainHost<Block>
+		+ AuditApi<Block>
 		+ sp_block_builder::BlockBuilder<Block>
 		+ frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce>
 		+ pallet_mmr_primitives::MmrApi<Block, <Block as BlockT>::Hash>
@@ -670,8 +672,42 @@ macro_rules! with_signed_payload {
 
 				$( $setup )*
 
-				signed_payload!($extra, $raw_payload,
-					($period, $current_block, $nonce, $tip, $call, $genesis));
+				let $extra: runtime::SignedExtra = (
+					frame_system::CheckNonZeroSender::<runtime::Runtime>::new(),
+					frame_system::CheckSpecVersion::<runtime::Runtime>::new(),
+					frame_system::CheckTxVersion::<runtime::Runtime>::new(),
+					frame_system::CheckGenesis::<runtime::Runtime>::new(),
+					frame_system::CheckMortality::<runtime::Runtime>::from(sp_runtime::generic::Era::mortal(
+						$period,
+						$current_block,
+					)),
+					frame_system::CheckNonce::<runtime::Runtime>::from($nonce),
+					frame_system::CheckWeight::<runtime::Runtime>::new(),
+					pallet_transaction_payment::ChargeTransactionPayment::<runtime::Runtime>::from($tip),
+					runtime::PrioritizeRegisteredRelayers,
+					runtime::BridgeRejectObsoleteHeadersAndMessages,
+					runtime::RefundRelayerForMessagesDelivery,
+					polkadot_runtime_common::reserved_space::ReserveBlockSpace::<runtime::Runtime>::new(),
+				);
+
+				let $raw_payload = runtime::SignedPayload::from_raw(
+					$call.clone(),
+					$extra.clone(),
+					(
+						(),
+						runtime::VERSION.spec_version,
+						runtime::VERSION.transaction_version,
+						$genesis.clone(),
+						$genesis,
+						(),
+						(),
+						(),
+						(),
+						(),
+						(),
+						(),
+					),
+				);
 
 				$( $usage )*
 			},
@@ -679,7 +715,7 @@ macro_rules! with_signed_payload {
 	}
 }
 
-/// Generates a `SignedPayload` for the Kusama, Westend and Rococo runtime.
+/// Generates a `SignedPayload` for the Kusama and Westend runtime.
 ///
 /// Should only be used for benchmarking as it is not tested for regular usage.
 #[allow(unused_macros)]
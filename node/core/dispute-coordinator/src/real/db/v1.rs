@@ -15,6 +15,10 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! `V1` database for the dispute coordinator.
+//!
+//! Recent disputes are stored per session, with an index of the stored sessions. The legacy
+//! single-value entry of recent disputes is read until the first write replaces it. The layout of
+//! all other entries hasn't changed.
 
 use polkadot_node_subsystem::{SubsystemError, SubsystemResult};
 use polkadot_node_subsystem_util::database::{DBTransaction, Database};
@@ -36,7 +40,12 @@ use crate::{
 	status::DisputeStatus,
 };
 
+/// Legacy key, under which all recent disputes used to be stored as a single value.
+///
+/// It is still read if no per-session entries exist and is removed on the next write.
 const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
+const RECENT_DISPUTES_SESSIONS_KEY: &[u8; 24] = b"recent-disputes-sessions";
+const RECENT_DISPUTES_SUBKEY: &[u8; 15] = b"recent-disputes";
 const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
 const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
 
@@ -84,7 +93,13 @@ impl Backend for DbBackend {
 					tx.put_vec(self.config.col_data, EARLIEST_SESSION_KEY, session.encode());
 				},
 				BackendWriteOp::WriteRecentDisputes(recent_disputes) => {
-					tx.put_vec(self.config.col_data, RECENT_DISPUTES_KEY, recent_disputes.encode());
+					write_recent_disputes(&*self.inner, &self.config, &mut tx, recent_disputes)
+						.map_err(|e| {
+							FatalError::DbWriteFailed(std::io::Error::new(
+								std::io::ErrorKind::Other,
+								e,
+							))
+						})?;
 				},
 				BackendWriteOp::WriteCandidateVotes(session, candidate_hash, votes) => {
 					tx.put_vec(
@@ -114,6 +129,16 @@ fn candidate_votes_key(session: SessionIndex, candidate_hash: &CandidateHash) ->
 	buf
 }
 
+fn recent_disputes_key(session: SessionIndex) -> [u8; 15 + 4] {
+	let mut buf = [0u8; 15 + 4];
+	buf[..15].copy_from_slice(RECENT_DISPUTES_SUBKEY);
+
+	// big-endian encoding is used to ensure lexicographic ordering.
+	buf[15..].copy_from_slice(&session.to_be_bytes());
+
+	buf
+}
+
 /// Column configuration information for the DB.
 #[derive(Debug, Clone)]
 pub struct ColumnConfiguration {
@@ -155,6 +180,12 @@ impl From<polkadot_node_primitives::CandidateVotes> for CandidateVotes {
 /// The mapping for recent disputes; any which have not yet been pruned for being ancient.
 pub type RecentDisputes = std::collections::BTreeMap<(SessionIndex, CandidateHash), DisputeStatus>;
 
+/// Recent disputes of a single session, as they are stored in the DB.
+///
+/// Recent disputes are split by session, so that a new vote only rewrites the (small) entry
+/// of the session it belongs to, and pruning an ancient session is a single deletion.
+type SessionRecentDisputes = std::collections::BTreeMap<CandidateHash, DisputeStatus>;
+
 /// Errors while accessing things from the DB.
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -208,10 +239,76 @@ pub(crate) fn load_recent_disputes(
 	db: &dyn Database,
 	config: &ColumnConfiguration,
 ) -> SubsystemResult<Option<RecentDisputes>> {
-	load_decode(db, config.col_data, RECENT_DISPUTES_KEY)
+	load_recent_disputes_inner(db, config)
 		.map_err(|e| SubsystemError::with_origin("dispute-coordinator", e))
 }
 
+fn load_recent_disputes_inner(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+) -> Result<Option<RecentDisputes>> {
+	let sessions: Vec<SessionIndex> =
+		match load_decode(db, config.col_data, RECENT_DISPUTES_SESSIONS_KEY)? {
+			Some(sessions) => sessions,
+			// Nothing has been written in the per-session format yet.
+			None => return load_decode(db, config.col_data, RECENT_DISPUTES_KEY),
+		};
+
+	let mut recent_disputes = RecentDisputes::new();
+	for session in sessions {
+		let session_disputes: SessionRecentDisputes =
+			load_decode(db, config.col_data, &recent_disputes_key(session))?.unwrap_or_default();
+		recent_disputes.extend(
+			session_disputes
+				.into_iter()
+				.map(|(candidate_hash, status)| ((session, candidate_hash), status)),
+		);
+	}
+
+	Ok(Some(recent_disputes))
+}
+
+/// Add operations, required to replace the recent disputes with the given value, to the transaction.
+///
+/// Only entries of sessions that have actually changed are rewritten. The legacy single-value
+/// entry is removed, so the migration to the per-session format happens on the first write.
+fn write_recent_disputes(
+	db: &dyn Database,
+	config: &ColumnConfiguration,
+	tx: &mut DBTransaction,
+	recent_disputes: RecentDisputes,
+) -> Result<()> {
+	let mut by_session = std::collections::BTreeMap::<SessionIndex, SessionRecentDisputes>::new();
+	for ((session, candidate_hash), status) in recent_disputes {
+		by_session.entry(session).or_default().insert(candidate_hash, status);
+	}
+
+	let prev_sessions: Option<Vec<SessionIndex>> =
+		load_decode(db, config.col_data, RECENT_DISPUTES_SESSIONS_KEY)?;
+	match prev_sessions {
+		Some(ref prev_sessions) =>
+			for session in prev_sessions.iter().filter(|s| !by_session.contains_key(s)) {
+				tx.delete(config.col_data, &recent_disputes_key(*session));
+			},
+		None => tx.delete(config.col_data, RECENT_DISPUTES_KEY),
+	}
+
+	for (session, session_disputes) in &by_session {
+		let key = recent_disputes_key(*session);
+		let encoded = session_disputes.encode();
+		if db.get(config.col_data, &key)?.as_deref() != Some(&encoded[..]) {
+			tx.put_vec(config.col_data, &key, encoded);
+		}
+	}
+
+	let sessions = by_session.into_keys().collect::<Vec<_>>();
+	if prev_sessions.as_ref() != Some(&sessions) {
+		tx.put_vec(config.col_data, RECENT_DISPUTES_SESSIONS_KEY, sessions.encode());
+	}
+
+	Ok(())
+}
+
 /// Maybe prune data in the DB based on the provided session index.
 ///
 /// This is intended to be called on every block, and as such will be used to populate the DB on
@@ -423,6 +520,79 @@ mod tests {
 			.is_none());
 	}
 
+	#[test]
+	fn legacy_recent_disputes_are_migrated_on_write() {
+		let mut backend = make_db();
+
+		let legacy: RecentDisputes = vec![
+			((1, CandidateHash(Hash::repeat_byte(1))), DisputeStatus::Active),
+			((2, CandidateHash(Hash::repeat_byte(2))), DisputeStatus::Active),
+		]
+		.into_iter()
+		.collect();
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(backend.config.col_data, RECENT_DISPUTES_KEY, legacy.encode());
+		backend.inner.write(tx).unwrap();
+
+		// Legacy value is still readable.
+		assert_eq!(backend.load_recent_disputes().unwrap(), Some(legacy.clone()));
+
+		let mut updated = legacy.clone();
+		updated.insert((3, CandidateHash(Hash::repeat_byte(3))), DisputeStatus::Active);
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(updated.clone());
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(backend.load_recent_disputes().unwrap(), Some(updated));
+		assert!(backend.inner.get(backend.config.col_data, RECENT_DISPUTES_KEY).unwrap().is_none());
+		assert!(backend
+			.inner
+			.get(backend.config.col_data, &recent_disputes_key(3))
+			.unwrap()
+			.is_some());
+	}
+
+	#[test]
+	fn recent_disputes_of_removed_sessions_are_deleted() {
+		let mut backend = make_db();
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(
+			vec![
+				((1, CandidateHash(Hash::repeat_byte(1))), DisputeStatus::Active),
+				((2, CandidateHash(Hash::repeat_byte(2))), DisputeStatus::Active),
+			]
+			.into_iter()
+			.collect(),
+		);
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_recent_disputes(
+			vec![((2, CandidateHash(Hash::repeat_byte(2))), DisputeStatus::Active)]
+				.into_iter()
+				.collect(),
+		);
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		assert_eq!(
+			backend.load_recent_disputes().unwrap().unwrap(),
+			vec![((2, CandidateHash(Hash::repeat_byte(2))), DisputeStatus::Active)]
+				.into_iter()
+				.collect(),
+		);
+		assert!(backend
+			.inner
+			.get(backend.config.col_data, &recent_disputes_key(1))
+			.unwrap()
+			.is_none());
+	}
+
 	#[test]
 	fn note_current_session_prunes_old() {
 		let mut backend = make_db();
@@ -22,7 +22,7 @@ use sp_consensus_babe::Epoch;
 
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
-	CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
+	CommittedCandidateReceipt, CoreState, FeatureBits, GroupRotationInfo, Hash, Id as ParaId,
 	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
 	PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode,
 	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
@@ -47,6 +47,7 @@ const ON_CHAIN_VOTES_CACHE_SIZE: usize = 3 * 1024;
 const PVFS_REQUIRE_PRECHECK_SIZE: usize = 1024;
 const VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
 const VERSION_CACHE_SIZE: usize = 4 * 1024;
+const FEATURE_BITS_CACHE_SIZE: usize = 4 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -115,6 +116,7 @@ pub(crate) struct RequestResultCache {
 		ResidentSizeOf<Option<ValidationCodeHash>>,
 	>,
 	version: MemoryLruCache<Hash, ResidentSizeOf<u32>>,
+	feature_bits: MemoryLruCache<Hash, ResidentSizeOf<FeatureBits>>,
 }
 
 impl Default for RequestResultCache {
@@ -142,6 +144,7 @@ impl Default for RequestResultCache {
 			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_SIZE),
 			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
 			version: MemoryLruCache::new(VERSION_CACHE_SIZE),
+			feature_bits: MemoryLruCache::new(FEATURE_BITS_CACHE_SIZE),
 		}
 	}
 }
@@ -407,6 +410,14 @@ impl RequestResultCache {
 	pub(crate) fn cache_version(&mut self, key: Hash, value: u32) {
 		self.version.insert(key, ResidentSizeOf(value));
 	}
+
+	pub(crate) fn feature_bits(&mut self, relay_parent: &Hash) -> Option<&FeatureBits> {
+		self.feature_bits.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_feature_bits(&mut self, relay_parent: Hash, bits: FeatureBits) {
+		self.feature_bits.insert(relay_parent, ResidentSizeOf(bits));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -442,4 +453,5 @@ pub(crate) enum RequestResult {
 	SubmitPvfCheckStatement(Hash, PvfCheckStatement, ValidatorSignature, ()),
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
 	Version(Hash, u32),
+	FeatureBits(Hash, FeatureBits),
 }
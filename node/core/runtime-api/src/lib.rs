@@ -164,6 +164,8 @@ where
 				.cache_validation_code_hash((relay_parent, para_id, assumption), hash),
 			Version(relay_parent, version) =>
 				self.requests_cache.cache_version(relay_parent, version),
+			FeatureBits(relay_parent, bits) =>
+				self.requests_cache.cache_feature_bits(relay_parent, bits),
 		}
 	}
 
@@ -265,6 +267,8 @@ where
 			Request::ValidationCodeHash(para, assumption, sender) =>
 				query!(validation_code_hash(para, assumption), sender)
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
+			Request::FeatureBits(sender) =>
+				query!(feature_bits(), sender).map(|sender| Request::FeatureBits(sender)),
 		}
 	}
 
@@ -521,6 +525,7 @@ where
 		},
 		Request::ValidationCodeHash(para, assumption, sender) =>
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
+		Request::FeatureBits(sender) => query!(FeatureBits, feature_bits(), ver = 3, sender),
 	}
 }
 
@@ -87,6 +87,14 @@ impl Meter {
 		}
 	}
 
+	/// Count the number of items queued up inside the channel, without consuming the time of
+	/// flight samples.
+	pub fn pending(&self) -> usize {
+		self.sent
+			.load(Ordering::Relaxed)
+			.saturating_sub(self.received.load(Ordering::Relaxed))
+	}
+
 	fn note_sent(&self) -> usize {
 		self.sent.fetch_add(1, Ordering::Relaxed)
 	}
@@ -127,3 +127,20 @@ fn failed_send_does_not_inc_sent() {
 		assert_matches!(unbounded.meter().read(), Readout { sent: 0, received: 0, .. });
 	});
 }
+
+#[test]
+fn pending_does_not_consume_tof() {
+	block_on(async move {
+		let (mut tx, mut rx) = channel::<Msg>(5);
+		tx.try_send(Msg::default()).unwrap();
+		tx.try_send(Msg::default()).unwrap();
+		tx.try_send(Msg::default()).unwrap();
+		rx.try_next().unwrap();
+		rx.try_next().unwrap();
+		assert_eq!(tx.meter().pending(), 1);
+		assert_eq!(rx.meter().pending(), 1);
+		assert_matches!(tx.meter().read(), Readout { sent: 3, received: 2, tof } => {
+			assert_eq!(tof.len(), 1);
+		});
+	});
+}
@@ -14,11 +14,15 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use futures::{future::Either, FutureExt, StreamExt, TryFutureExt};
+use fatality::Nested;
+use futures::{FutureExt, StreamExt, TryFutureExt};
 
 use sp_keystore::SyncCryptoStorePtr;
 
-use polkadot_node_network_protocol::request_response::{v1, IncomingRequestReceiver};
+use polkadot_node_network_protocol::{
+	request_response::{incoming::OutgoingResponse, v1, IncomingRequest, IncomingRequestReceiver},
+	UnifiedReputationChange as Rep,
+};
 use polkadot_subsystem::{
 	messages::AvailabilityDistributionMessage, overseer, FromOverseer, OverseerSignal,
 	SpawnedSubsystem, SubsystemContext, SubsystemError,
@@ -32,7 +36,7 @@ use polkadot_node_subsystem_util::runtime::RuntimeInfo;
 
 /// `Requester` taking care of requesting chunks for candidates pending availability.
 mod requester;
-use requester::Requester;
+use requester::{PushedChunk, Requester};
 
 /// Handing requests for PoVs during backing.
 mod pov_requester;
@@ -50,6 +54,9 @@ mod tests;
 
 const LOG_TARGET: &'static str = "parachain::availability-distribution";
 
+const COST_INVALID_PUSH_REQUEST: Rep = Rep::CostMajor("Pushed chunk could not be decoded.");
+const COST_INVALID_PUSHED_CHUNK: Rep = Rep::CostMajor("Pushed chunk is invalid.");
+
 /// The availability distribution subsystem.
 pub struct AvailabilityDistributionSubsystem {
 	/// Easy and efficient runtime access for this subsystem.
@@ -58,6 +65,8 @@ pub struct AvailabilityDistributionSubsystem {
 	recvs: IncomingRequestReceivers,
 	/// Prometheus metrics.
 	metrics: Metrics,
+	/// Whether to push chunks of candidates we backed, instead of waiting for them to be fetched.
+	push_chunks: bool,
 }
 
 /// Receivers to be passed into availability distribution.
@@ -66,6 +75,8 @@ pub struct IncomingRequestReceivers {
 	pub pov_req_receiver: IncomingRequestReceiver<v1::PoVFetchingRequest>,
 	/// Receiver for incoming availability chunk requests.
 	pub chunk_req_receiver: IncomingRequestReceiver<v1::ChunkFetchingRequest>,
+	/// Receiver for availability chunks pushed to us by backers.
+	pub chunk_push_receiver: IncomingRequestReceiver<v1::ChunkPushingRequest>,
 }
 
 impl<Context> overseer::Subsystem<Context, SubsystemError> for AvailabilityDistributionSubsystem
@@ -91,7 +102,16 @@ impl AvailabilityDistributionSubsystem {
 		metrics: Metrics,
 	) -> Self {
 		let runtime = RuntimeInfo::new(Some(keystore));
-		Self { runtime, recvs, metrics }
+		Self { runtime, recvs, metrics, push_chunks: false }
+	}
+
+	/// Enable or disable pushing of chunks to their validators, after a candidate we backed got
+	/// included.
+	///
+	/// Receiving pushed chunks is always supported.
+	pub fn with_chunk_pushing(mut self, enabled: bool) -> Self {
+		self.push_chunks = enabled;
+		self
 	}
 
 	/// Start processing work as passed on from the Overseer.
@@ -100,10 +120,12 @@ impl AvailabilityDistributionSubsystem {
 		Context: SubsystemContext<Message = AvailabilityDistributionMessage>,
 		Context: overseer::SubsystemContext<Message = AvailabilityDistributionMessage>,
 	{
-		let Self { mut runtime, recvs, metrics } = self;
+		let Self { mut runtime, recvs, metrics, push_chunks } = self;
 
-		let IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver } = recvs;
-		let mut requester = Requester::new(metrics.clone()).fuse();
+		let IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver, chunk_push_receiver } =
+			recvs;
+		let mut chunk_push_receiver = Some(chunk_push_receiver);
+		let mut requester = Requester::new(metrics.clone(), push_chunks).fuse();
 
 		{
 			let sender = ctx.sender().clone();
@@ -123,21 +145,47 @@ impl AvailabilityDistributionSubsystem {
 		loop {
 			let action = {
 				let mut subsystem_next = ctx.recv().fuse();
+				let mut pushed_chunk_next = async {
+					match chunk_push_receiver.as_mut() {
+						Some(receiver) => receiver.recv(|| vec![COST_INVALID_PUSH_REQUEST]).await,
+						None => futures::future::pending().await,
+					}
+				}
+				.fuse();
 				futures::select! {
-					subsystem_msg = subsystem_next => Either::Left(subsystem_msg),
-					from_task = requester.next() => Either::Right(from_task),
+					subsystem_msg = subsystem_next => Action::Message(subsystem_msg),
+					from_task = requester.next() => Action::FromTask(from_task),
+					pushed_chunk = pushed_chunk_next => Action::PushedChunk(pushed_chunk),
 				}
 			};
 
 			// Handle task messages sending:
 			let message = match action {
-				Either::Left(subsystem_msg) =>
+				Action::Message(subsystem_msg) =>
 					subsystem_msg.map_err(|e| FatalError::IncomingMessageChannel(e))?,
-				Either::Right(from_task) => {
+				Action::FromTask(from_task) => {
 					let from_task = from_task.ok_or(FatalError::RequesterExhausted)?;
 					ctx.send_message(from_task).await;
 					continue
 				},
+				Action::PushedChunk(pushed_chunk) => {
+					match pushed_chunk.into_nested() {
+						Ok(Ok(req)) =>
+							answer_pushed_chunk(&mut ctx, requester.get_mut(), req, &metrics).await,
+						Ok(Err(jfyi)) => {
+							gum::debug!(target: LOG_TARGET, error = ?jfyi, "Error decoding pushed chunk.");
+						},
+						Err(fatal) => {
+							gum::debug!(
+								target: LOG_TARGET,
+								error = ?fatal,
+								"Shutting down pushed chunk receiver."
+							);
+							chunk_push_receiver = None;
+						},
+					}
+					continue
+				},
 			};
 			match message {
 				FromOverseer::Signal(OverseerSignal::ActiveLeaves(update)) => {
@@ -180,3 +228,41 @@ impl AvailabilityDistributionSubsystem {
 		}
 	}
 }
+
+/// Next thing to handle in the main loop of the subsystem.
+enum Action<M, T, P> {
+	/// Message from the overseer.
+	Message(M),
+	/// Message from a fetch or push task.
+	FromTask(T),
+	/// A chunk pushed to us by a backer.
+	PushedChunk(P),
+}
+
+/// Hand a pushed chunk to the `Requester` and respond accordingly.
+async fn answer_pushed_chunk<Context>(
+	ctx: &mut Context,
+	requester: &mut Requester,
+	req: IncomingRequest<v1::ChunkPushingRequest>,
+	metrics: &Metrics,
+) where
+	Context: SubsystemContext,
+{
+	let IncomingRequest { peer, payload, pending_response } = req;
+	let (result, reputation_changes, label) = match requester.on_pushed_chunk(ctx, payload).await {
+		PushedChunk::Accepted(_) =>
+			(v1::ChunkPushingResponse::Stored, Vec::new(), metrics::SUCCEEDED),
+		PushedChunk::NotNeeded =>
+			(v1::ChunkPushingResponse::NotNeeded, Vec::new(), metrics::NOT_NEEDED),
+		PushedChunk::Invalid => {
+			gum::debug!(target: LOG_TARGET, ?peer, "Peer pushed an invalid chunk to us");
+			(v1::ChunkPushingResponse::NotNeeded, vec![COST_INVALID_PUSHED_CHUNK], metrics::FAILED)
+		},
+	};
+	metrics.on_received_pushed_chunk(label);
+
+	let response = OutgoingResponse { result: Ok(result), reputation_changes, sent_feedback: None };
+	if let Err(()) = pending_response.send_outgoing_response(response) {
+		gum::debug!(target: LOG_TARGET, ?peer, "Sending response to pushed chunk failed");
+	}
+}
@@ -31,6 +31,9 @@ pub const FAILED: &'static str = "failed";
 /// Label for chunks/PoVs that could not be served, because they were not available.
 pub const NOT_FOUND: &'static str = "not-found";
 
+/// Label for pushed chunks we did not need, e.g. because we had fetched them already.
+pub const NOT_NEEDED: &'static str = "not-needed";
+
 /// Availability Distribution metrics.
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
@@ -56,6 +59,12 @@ struct MetricsInner {
 	/// Number of times our first set of validators did not provide the needed chunk and we had to
 	/// query further validators.
 	retries: Counter<U64>,
+
+	/// Number of chunks we pushed to other validators as a backer.
+	pushed_chunks: CounterVec<U64>,
+
+	/// Number of chunks pushed to us by backers.
+	received_pushed_chunks: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -98,6 +107,20 @@ impl Metrics {
 			metrics.retries.inc()
 		}
 	}
+
+	/// Increment counter on chunks pushed to other validators.
+	pub fn on_pushed_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.pushed_chunks.with_label_values(&[label]).inc()
+		}
+	}
+
+	/// Increment counter on chunks pushed to us.
+	pub fn on_received_pushed_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.received_pushed_chunks.with_label_values(&[label]).inc()
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -150,6 +173,26 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			pushed_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_pushed_chunks_total",
+						"Total number of chunks pushed by this backer.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
+			received_pushed_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_received_pushed_chunks_total",
+						"Total number of chunks pushed to us by backers.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
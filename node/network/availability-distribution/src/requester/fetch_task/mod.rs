@@ -30,7 +30,7 @@ use polkadot_node_network_protocol::request_response::{
 use polkadot_node_primitives::ErasureChunk;
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, BlakeTwo256, CandidateHash, GroupIndex, Hash, HashT, OccupiedCore,
-	SessionIndex,
+	SessionIndex, ValidatorIndex,
 };
 use polkadot_subsystem::{
 	jaeger,
@@ -69,6 +69,31 @@ pub struct FetchTask {
 	/// We keep the task around in until `live_in` becomes empty, to make
 	/// sure we won't re-fetch an already fetched candidate.
 	state: FetchedState,
+
+	/// What a chunk pushed to us by a backer needs to look like, so it can replace the fetch.
+	///
+	/// `None` if we are not fetching anything for this candidate (e.g. we are a backer).
+	expected_chunk: Option<ExpectedChunk>,
+}
+
+/// Information needed for validating a pushed chunk.
+#[derive(Clone, Copy)]
+struct ExpectedChunk {
+	/// The chunk index we are fetching.
+	index: ValidatorIndex,
+	/// Root hash, for verifying the chunks validity.
+	erasure_root: Hash,
+}
+
+/// Outcome of handing a pushed chunk to a `FetchTask`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PushedChunk {
+	/// The chunk is valid and the fetch got canceled, the chunk should be stored.
+	Accepted(ErasureChunk),
+	/// We are not fetching that chunk (anymore).
+	NotNeeded,
+	/// The chunk is not ours or does not match the erasure root.
+	Invalid,
 }
 
 /// State of a particular candidate chunk fetching process.
@@ -183,13 +208,19 @@ impl FetchTask {
 
 		if let Some(running) = prepared_running {
 			let (handle, kill) = oneshot::channel();
+			let expected_chunk =
+				ExpectedChunk { index: running.request.index, erasure_root: running.erasure_root };
 
 			ctx.spawn("chunk-fetcher", running.run(kill).boxed())
 				.map_err(|e| FatalError::SpawnTask(e))?;
 
-			Ok(FetchTask { live_in, state: FetchedState::Started(handle) })
+			Ok(FetchTask {
+				live_in,
+				state: FetchedState::Started(handle),
+				expected_chunk: Some(expected_chunk),
+			})
 		} else {
-			Ok(FetchTask { live_in, state: FetchedState::Canceled })
+			Ok(FetchTask { live_in, state: FetchedState::Canceled, expected_chunk: None })
 		}
 	}
 
@@ -217,6 +248,27 @@ impl FetchTask {
 		!self.live_in.is_empty()
 	}
 
+	/// Take a chunk pushed to us by a backer instead of fetching it.
+	///
+	/// If the chunk is valid, the running fetch gets canceled and the chunk is returned for
+	/// storing.
+	pub fn accept_pushed_chunk(&mut self, chunk: ErasureChunk) -> PushedChunk {
+		let expected = match self.expected_chunk {
+			Some(expected) if !self.is_finished() => expected,
+			_ => return PushedChunk::NotNeeded,
+		};
+		if chunk.index != expected.index {
+			return PushedChunk::Invalid
+		}
+		match branch_hash(&expected.erasure_root, chunk.proof(), chunk.index.0 as usize) {
+			Ok(hash) if hash == BlakeTwo256::hash(&chunk.chunk) => {},
+			_ => return PushedChunk::Invalid,
+		}
+		// Dropping the sender kills the running fetch.
+		self.state = FetchedState::Canceled;
+		PushedChunk::Accepted(chunk)
+	}
+
 	/// Whether this task can be considered finished.
 	///
 	/// That is, it is either canceled, succeeded or failed.
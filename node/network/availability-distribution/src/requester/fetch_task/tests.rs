@@ -102,6 +102,32 @@ fn task_stores_valid_chunk() {
 	test.run(task, rx);
 }
 
+#[test]
+fn valid_pushed_chunk_cancels_fetch() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (erasure_root, chunk) = get_valid_chunk_data(pov);
+	let (handle, kill) = oneshot::channel();
+	let mut task = FetchTask {
+		live_in: HashSet::new(),
+		state: FetchedState::Started(handle),
+		expected_chunk: Some(ExpectedChunk { index: chunk.index, erasure_root }),
+	};
+
+	let mut wrongly_indexed = chunk.clone();
+	wrongly_indexed.index = ValidatorIndex(chunk.index.0 + 1);
+	assert_eq!(task.accept_pushed_chunk(wrongly_indexed), PushedChunk::Invalid);
+	let mut bogus = chunk.clone();
+	bogus.chunk = vec![1, 2, 3];
+	assert_eq!(task.accept_pushed_chunk(bogus), PushedChunk::Invalid);
+	assert!(!task.is_finished());
+
+	assert_eq!(task.accept_pushed_chunk(chunk.clone()), PushedChunk::Accepted(chunk.clone()));
+	assert!(task.is_finished());
+	assert_eq!(executor::block_on(kill), Err(oneshot::Canceled));
+	// Already got it, no need for another one:
+	assert_eq!(task.accept_pushed_chunk(chunk), PushedChunk::NotNeeded);
+}
+
 #[test]
 fn task_does_not_accept_wrongly_indexed_chunk() {
 	let (mut task, rx) = get_test_running_task();
@@ -29,13 +29,14 @@ use std::{
 use futures::{
 	channel::{mpsc, oneshot},
 	task::{Context, Poll},
-	Stream,
+	FutureExt, Stream,
 };
 
+use polkadot_node_network_protocol::request_response::v1::ChunkPushingRequest;
 use polkadot_node_subsystem_util::runtime::{get_occupied_cores, RuntimeInfo};
 use polkadot_primitives::v2::{CandidateHash, Hash, OccupiedCore, SessionIndex};
 use polkadot_subsystem::{
-	messages::{AllMessages, ChainApiMessage},
+	messages::{AllMessages, AvailabilityStoreMessage, ChainApiMessage},
 	ActivatedLeaf, ActiveLeavesUpdate, LeafStatus, SubsystemContext,
 };
 
@@ -50,8 +51,13 @@ use session_cache::SessionCache;
 
 /// A task fetching a particular chunk.
 mod fetch_task;
+pub use fetch_task::PushedChunk;
 use fetch_task::{FetchTask, FetchTaskConfig, FromFetchTask};
 
+/// A task pushing chunks of a candidate we backed.
+mod push_task;
+use push_task::PushTask;
+
 /// Requester takes care of requesting erasure chunks from backing groups and stores them in the
 /// av store.
 ///
@@ -76,6 +82,9 @@ pub struct Requester {
 
 	/// Prometheus Metrics
 	metrics: Metrics,
+
+	/// Whether to push chunks of candidates we backed to their validators.
+	push_chunks: bool,
 }
 
 impl Requester {
@@ -86,9 +95,16 @@ impl Requester {
 	///
 	/// You must feed it with `ActiveLeavesUpdate` via `update_fetching_heads` and make it progress
 	/// by advancing the stream.
-	pub fn new(metrics: Metrics) -> Self {
+	pub fn new(metrics: Metrics, push_chunks: bool) -> Self {
 		let (tx, rx) = mpsc::channel(1);
-		Requester { fetches: HashMap::new(), session_cache: SessionCache::new(), tx, rx, metrics }
+		Requester {
+			fetches: HashMap::new(),
+			session_cache: SessionCache::new(),
+			tx,
+			rx,
+			metrics,
+			push_chunks,
+		}
 	}
 
 	/// Update heads that need availability distribution.
@@ -158,6 +174,43 @@ impl Requester {
 		Ok(())
 	}
 
+	/// Handle a chunk pushed to us by a backer.
+	///
+	/// If we are currently fetching that chunk, the fetch gets canceled and the pushed chunk is
+	/// stored instead.
+	pub async fn on_pushed_chunk<Context>(
+		&mut self,
+		ctx: &mut Context,
+		request: ChunkPushingRequest,
+	) -> PushedChunk
+	where
+		Context: SubsystemContext,
+	{
+		let ChunkPushingRequest { candidate_hash, chunk } = request;
+		let outcome = match self.fetches.get_mut(&candidate_hash) {
+			Some(task) => task.accept_pushed_chunk(chunk),
+			None => PushedChunk::NotNeeded,
+		};
+		if let PushedChunk::Accepted(chunk) = &outcome {
+			let (tx, rx) = oneshot::channel();
+			ctx.send_message(AvailabilityStoreMessage::StoreChunk {
+				candidate_hash,
+				chunk: chunk.clone(),
+				tx,
+			})
+			.await;
+			if let Ok(Ok(())) = rx.await {
+				return outcome
+			}
+			gum::error!(target: LOG_TARGET, ?candidate_hash, "Storing pushed erasure chunk failed");
+			// The fetch got canceled already, make sure we retry on next block still pending
+			// availability.
+			self.fetches.remove(&candidate_hash);
+			return PushedChunk::NotNeeded
+		}
+		outcome
+	}
+
 	/// Stop requesting chunks for obsolete heads.
 	///
 	fn stop_requesting_chunks(&mut self, obsolete_leaves: impl Iterator<Item = Hash>) {
@@ -196,6 +249,7 @@ impl Requester {
 				Entry::Vacant(e) => {
 					let tx = self.tx.clone();
 					let metrics = self.metrics.clone();
+					let push_chunks = self.push_chunks;
 
 					let task_cfg = self
 						.session_cache
@@ -208,7 +262,14 @@ impl Requester {
 							// be fetchable by the state trie.
 							leaf,
 							leaf_session_index,
-							|info| FetchTaskConfig::new(leaf, &core, tx, metrics, info),
+							|info| {
+								let push_task = if push_chunks {
+									PushTask::new(&core, tx.clone(), metrics.clone(), info)
+								} else {
+									None
+								};
+								(FetchTaskConfig::new(leaf, &core, tx, metrics, info), push_task)
+							},
 						)
 						.await
 						.map_err(|err| {
@@ -220,8 +281,12 @@ impl Requester {
 							err
 						});
 
-					if let Ok(Some(task_cfg)) = task_cfg {
+					if let Ok(Some((task_cfg, push_task))) = task_cfg {
 						e.insert(FetchTask::start(task_cfg, ctx).await?);
+						if let Some(push_task) = push_task {
+							ctx.spawn("chunk-pusher", push_task.run().boxed())
+								.map_err(|e| FatalError::SpawnTask(e))?;
+						}
 					}
 					// Not a validator, nothing to do.
 				},
@@ -0,0 +1,217 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pushing of erasure chunks by backers to the validators they belong to.
+
+use futures::{
+	channel::{mpsc, oneshot},
+	future::join_all,
+	SinkExt,
+};
+
+use polkadot_node_network_protocol::request_response::{
+	outgoing::{OutgoingRequest, Recipient, Requests},
+	v1::{ChunkPushingRequest, ChunkPushingResponse},
+};
+use polkadot_node_primitives::ErasureChunk;
+use polkadot_primitives::v2::{AuthorityDiscoveryId, CandidateHash, OccupiedCore, ValidatorIndex};
+use polkadot_subsystem::messages::{
+	AllMessages, AvailabilityStoreMessage, IfDisconnected, NetworkBridgeMessage,
+};
+
+use crate::{
+	metrics::{Metrics, FAILED, NOT_NEEDED, SUCCEEDED},
+	requester::{fetch_task::FromFetchTask, session_cache::SessionInfo},
+	LOG_TARGET,
+};
+
+/// Task pushing chunks of a candidate we backed to their validators.
+///
+/// The chunks are split among the backers, so that every chunk is pushed once. Recipients which
+/// are already fetching the chunk will cancel their fetch, recipients which did not yet learn about
+/// the candidate (or did not get their chunk pushed, because their backer is offline) will fetch
+/// as usual.
+pub struct PushTask {
+	/// The candidate whose chunks are to be pushed.
+	candidate_hash: CandidateHash,
+
+	/// Validators to push chunks to, together with their chunk index.
+	///
+	/// See `push_recipients` for which validators get their chunk pushed by us.
+	recipients: Vec<(ValidatorIndex, AuthorityDiscoveryId)>,
+
+	/// Sender for communicating with other subsystems.
+	sender: mpsc::Sender<FromFetchTask>,
+
+	/// Prometheus metrics for reporting results.
+	metrics: Metrics,
+}
+
+impl PushTask {
+	/// Prepare pushing of chunks for the given core.
+	///
+	/// Returns `None` if we are not part of the backing group responsible for the core.
+	pub fn new(
+		core: &OccupiedCore,
+		sender: mpsc::Sender<FromFetchTask>,
+		metrics: Metrics,
+		session_info: &SessionInfo,
+	) -> Option<Self> {
+		if session_info.our_group != Some(core.group_responsible) {
+			return None
+		}
+		let recipients = push_recipients(
+			session_info.discovery_keys.len(),
+			&session_info.our_group_members,
+			session_info.our_index,
+		)
+		.into_iter()
+		.filter_map(|index| {
+			session_info
+				.discovery_keys
+				.get(index.0 as usize)
+				.map(|key| (index, key.clone()))
+		})
+		.collect();
+
+		Some(PushTask { candidate_hash: core.candidate_hash, recipients, sender, metrics })
+	}
+
+	/// Push our share of the chunks, stops early if the node seems to be shutting down.
+	pub async fn run(mut self) {
+		let mut requests = Vec::with_capacity(self.recipients.len());
+		let mut responses = Vec::with_capacity(self.recipients.len());
+
+		for (index, validator) in std::mem::take(&mut self.recipients) {
+			let chunk = match self.query_chunk(index).await {
+				Some(chunk) => chunk,
+				None => {
+					gum::debug!(
+						target: LOG_TARGET,
+						candidate_hash = ?self.candidate_hash,
+						chunk_index = ?index,
+						"Chunk to push not found in availability store"
+					);
+					self.metrics.on_pushed_chunk(FAILED);
+					continue
+				},
+			};
+			let (req, response_recv) = OutgoingRequest::new(
+				Recipient::Authority(validator),
+				ChunkPushingRequest { candidate_hash: self.candidate_hash, chunk },
+			);
+			requests.push(Requests::ChunkPushing(req));
+			responses.push(response_recv);
+		}
+
+		if requests.is_empty() {
+			return
+		}
+
+		if let Err(err) = self
+			.sender
+			.send(FromFetchTask::Message(AllMessages::NetworkBridge(
+				NetworkBridgeMessage::SendRequests(requests, IfDisconnected::ImmediateError),
+			)))
+			.await
+		{
+			gum::info!(
+				target: LOG_TARGET,
+				?err,
+				"Node seems to be shutting down, canceling push task"
+			);
+			return
+		}
+
+		for response in join_all(responses).await {
+			let label = match response {
+				Ok(ChunkPushingResponse::Stored) => SUCCEEDED,
+				Ok(ChunkPushingResponse::NotNeeded) => NOT_NEEDED,
+				Err(err) => {
+					gum::trace!(
+						target: LOG_TARGET,
+						candidate_hash = ?self.candidate_hash,
+						?err,
+						"Pushing chunk failed"
+					);
+					FAILED
+				},
+			};
+			self.metrics.on_pushed_chunk(label);
+		}
+	}
+
+	/// Retrieve the chunk with the given index from the availability store.
+	async fn query_chunk(&mut self, index: ValidatorIndex) -> Option<ErasureChunk> {
+		let (tx, rx) = oneshot::channel();
+		self.sender
+			.send(FromFetchTask::Message(AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::QueryChunk(self.candidate_hash, index, tx),
+			)))
+			.await
+			.ok()?;
+		rx.await.ok().flatten()
+	}
+}
+
+/// Validators whose chunks we push, out of the given number of validators.
+///
+/// Every chunk is pushed by a single backer: the chunk of validator `i` is pushed by the member at
+/// position `i % backers.len()` of the backing group. Members of the backing group are skipped,
+/// they have the full data already.
+fn push_recipients(
+	n_validators: usize,
+	backers: &[ValidatorIndex],
+	our_index: ValidatorIndex,
+) -> Vec<ValidatorIndex> {
+	let our_position = match backers.iter().position(|backer| *backer == our_index) {
+		Some(position) => position,
+		None => return Vec::new(),
+	};
+	(0..n_validators)
+		.filter(|index| index % backers.len() == our_position)
+		.map(|index| ValidatorIndex(index as _))
+		.filter(|index| !backers.contains(index))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn every_chunk_is_pushed_by_one_backer() {
+		let backers = vec![ValidatorIndex(4), ValidatorIndex(1), ValidatorIndex(7)];
+
+		let mut pushed = Vec::new();
+		for backer in &backers {
+			let recipients = push_recipients(10, &backers, *backer);
+			assert!(recipients.len() <= 3);
+			pushed.extend(recipients);
+		}
+		pushed.sort();
+
+		let expected: Vec<_> =
+			(0..10).map(ValidatorIndex).filter(|v| !backers.contains(v)).collect();
+		assert_eq!(pushed, expected);
+	}
+
+	#[test]
+	fn nothing_is_pushed_by_non_backers() {
+		let backers = vec![ValidatorIndex(4), ValidatorIndex(1), ValidatorIndex(7)];
+		assert!(push_recipients(10, &backers, ValidatorIndex(0)).is_empty());
+	}
+}
@@ -56,6 +56,11 @@ pub struct SessionInfo {
 	/// validators.
 	pub validator_groups: Vec<Vec<AuthorityDiscoveryId>>,
 
+	/// Discovery keys of all validators of the session, by `ValidatorIndex`.
+	///
+	/// Used for pushing chunks to the validators they belong to.
+	pub discovery_keys: Vec<AuthorityDiscoveryId>,
+
 	/// Information about ourselves:
 	pub our_index: ValidatorIndex,
 
@@ -64,6 +69,12 @@ pub struct SessionInfo {
 	///
 	/// `None`, if we are not in fact part of any group.
 	pub our_group: Option<GroupIndex>,
+
+	/// Members of our group, in the order of the session info, which is the same on all nodes.
+	///
+	/// Used for splitting the chunks to push among the backers of a candidate. Empty, if we are
+	/// not part of any group.
+	pub our_group_members: Vec<ValidatorIndex>,
 }
 
 /// Report of bad validators.
@@ -192,6 +203,10 @@ impl SessionCache {
 		if let Some(our_index) = info.validator_info.our_index {
 			// Get our group index:
 			let our_group = info.validator_info.our_group;
+			let our_group_members = our_group
+				.and_then(|g| info.session_info.validator_groups.get(g.0 as usize))
+				.cloned()
+				.unwrap_or_default();
 
 			// Shuffle validators in groups:
 			let mut rng = thread_rng();
@@ -213,7 +228,14 @@ impl SessionCache {
 				})
 				.collect();
 
-			let info = SessionInfo { validator_groups, our_index, session_index, our_group };
+			let info = SessionInfo {
+				validator_groups,
+				discovery_keys,
+				our_index,
+				session_index,
+				our_group,
+				our_group_members,
+			};
 			return Ok(Some(info))
 		}
 		return Ok(None)
@@ -44,9 +44,11 @@ fn test_harness<T: Future<Output = ()>>(
 
 	let (pov_req_receiver, pov_req_cfg) = IncomingRequest::get_config_receiver();
 	let (chunk_req_receiver, chunk_req_cfg) = IncomingRequest::get_config_receiver();
+	// Kept alive for the duration of the test, so the pushed chunk receiver stays open:
+	let (chunk_push_receiver, _chunk_push_cfg) = IncomingRequest::get_config_receiver();
 	let subsystem = AvailabilityDistributionSubsystem::new(
 		keystore,
-		IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
+		IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver, chunk_push_receiver },
 		Default::default(),
 	);
 	let subsystem = subsystem.run(context);
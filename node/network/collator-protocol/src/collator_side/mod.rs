@@ -23,14 +23,18 @@ use std::{
 use futures::{
 	channel::oneshot, pin_mut, select, stream::FuturesUnordered, Future, FutureExt, StreamExt,
 };
+use parity_scale_codec::Encode;
 use sp_core::Pair;
 
 use polkadot_node_network_protocol::{
 	peer_set::PeerSet,
 	request_response::{
 		incoming::{self, OutgoingResponse},
-		v1::{self as request_v1, CollationFetchingRequest, CollationFetchingResponse},
-		IncomingRequest, IncomingRequestReceiver,
+		v1::{
+			self as request_v1, CollationFetchingRequest, CollationFetchingResponse,
+			CollationRangeFetchingRequest, CollationRangeFetchingResponse,
+		},
+		IncomingRequest, IncomingRequestReceiver, COLLATION_RANGE_SIZE,
 	},
 	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
 };
@@ -250,6 +254,24 @@ struct Collation {
 	receipt: CandidateReceipt,
 	pov: PoV,
 	status: CollationStatus,
+	/// The encoded `(receipt, pov)`, once requested in ranges.
+	encoded: Option<Vec<u8>>,
+}
+
+impl Collation {
+	/// The encoded `(receipt, pov)`, encoding it on first use.
+	fn encoded(&mut self) -> &[u8] {
+		let Collation { receipt, pov, encoded, .. } = self;
+		encoded.get_or_insert_with(|| (&*receipt, &*pov).encode())
+	}
+}
+
+/// A collation fetch, waiting to be fulfilled.
+enum WaitingCollationFetch {
+	/// Request of the whole collation.
+	Collation(IncomingRequest<CollationFetchingRequest>),
+	/// Request of a range of the encoded collation.
+	Range(IncomingRequest<CollationRangeFetchingRequest>),
 }
 
 /// Stores the state for waiting collation fetches.
@@ -258,7 +280,7 @@ struct WaitingCollationFetches {
 	/// Is there currently a collation getting fetched?
 	collation_fetch_active: bool,
 	/// The collation fetches waiting to be fulfilled.
-	waiting: VecDeque<IncomingRequest<CollationFetchingRequest>>,
+	waiting: VecDeque<WaitingCollationFetch>,
 	/// All peers that are waiting or actively uploading.
 	///
 	/// We will not accept multiple requests from the same peer, otherwise our DoS protection of
@@ -445,9 +467,10 @@ where
 		state.collation_result_senders.insert(receipt.hash(), result_sender);
 	}
 
-	state
-		.collations
-		.insert(relay_parent, Collation { receipt, pov, status: CollationStatus::Created });
+	state.collations.insert(
+		relay_parent,
+		Collation { receipt, pov, status: CollationStatus::Created, encoded: None },
+	);
 
 	let interested = state.peers_interested_in_leaf(&relay_parent);
 	// Make sure already connected peers get collations:
@@ -745,6 +768,55 @@ async fn send_collation(
 	state.metrics.on_collation_sent();
 }
 
+/// Issue a response to a previously requested range of a collation.
+async fn send_collation_range(
+	state: &mut State,
+	request: IncomingRequest<CollationRangeFetchingRequest>,
+) {
+	let CollationRangeFetchingRequest { relay_parent, offset, length, .. } = request.payload;
+	let peer_id = request.peer;
+
+	let encoded = match state.collations.get_mut(&relay_parent) {
+		Some(collation) => collation.encoded(),
+		None => return,
+	};
+	let total_len = encoded.len() as u32;
+	let end = total_len.min(offset.saturating_add(length.min(COLLATION_RANGE_SIZE)));
+	let data = encoded[offset as usize..end as usize].to_vec();
+
+	let (tx, rx) = oneshot::channel();
+
+	let response = OutgoingResponse {
+		result: Ok(CollationRangeFetchingResponse::Range { total_len, data }),
+		reputation_changes: Vec::new(),
+		sent_feedback: Some(tx),
+	};
+
+	if let Err(_) = request.send_outgoing_response(response) {
+		gum::warn!(target: LOG_TARGET, "Sending collation range response failed");
+	}
+
+	state.active_collation_fetches.push(
+		async move {
+			let r = rx.timeout(MAX_UNSHARED_UPLOAD_TIME).await;
+			if r.is_none() {
+				gum::debug!(
+					target: LOG_TARGET,
+					?relay_parent,
+					?peer_id,
+					"Sending collation range timed out, carrying on with next validator."
+				);
+			}
+			(relay_parent, peer_id)
+		}
+		.boxed(),
+	);
+
+	if end == total_len {
+		state.metrics.on_collation_sent();
+	}
+}
+
 /// A networking messages switch.
 async fn handle_incoming_peer_message<Context>(
 	ctx: &mut Context,
@@ -877,7 +949,7 @@ where
 			}
 
 			if waiting.collation_fetch_active {
-				waiting.waiting.push_back(req);
+				waiting.waiting.push_back(WaitingCollationFetch::Collation(req));
 			} else {
 				waiting.collation_fetch_active = true;
 				// Obtain a timer for sending collation
@@ -904,6 +976,75 @@ where
 	Ok(())
 }
 
+/// Process an incoming network request for a range of a collation.
+///
+/// Range requests share the queue of full collation fetches, so each peer gets at most one upload
+/// in flight, be it a whole collation or a range of it.
+async fn handle_incoming_range_request<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	req: IncomingRequest<request_v1::CollationRangeFetchingRequest>,
+) -> Result<()>
+where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let CollationRangeFetchingRequest { relay_parent, para_id, offset, length } = req.payload;
+
+	if state.collating_on != Some(para_id) {
+		gum::warn!(
+			target: LOG_TARGET,
+			for_para_id = %para_id,
+			our_para_id = ?state.collating_on,
+			"received a `CollationRangeFetchingRequest` for unexpected para_id",
+		);
+		return Ok(())
+	}
+
+	let collation = match state.collations.get_mut(&relay_parent) {
+		Some(collation) => collation,
+		None => {
+			gum::warn!(
+				target: LOG_TARGET,
+				%relay_parent,
+				"received a `CollationRangeFetchingRequest` for a relay parent we don't have collation stored.",
+			);
+			return Ok(())
+		},
+	};
+	collation.status.advance_to_requested();
+
+	if offset > collation.encoded().len() as u32 {
+		ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer, COST_INVALID_REQUEST))
+			.await;
+		return Ok(())
+	}
+
+	if offset == 0 {
+		state.metrics.on_collation_sent_requested();
+	}
+
+	let waiting = state.waiting_collation_fetches.entry(relay_parent).or_default();
+
+	if !waiting.waiting_peers.insert(req.peer) {
+		gum::debug!(
+			target: LOG_TARGET,
+			"Dropping incoming range request as peer has a request in flight already."
+		);
+		ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer, COST_APPARENT_FLOOD))
+			.await;
+		return Ok(())
+	}
+
+	if waiting.collation_fetch_active {
+		waiting.waiting.push_back(WaitingCollationFetch::Range(req));
+	} else {
+		waiting.collation_fetch_active = true;
+		send_collation_range(state, req).await;
+	}
+	Ok(())
+}
+
 /// Our view has changed.
 async fn handle_peer_view_change<Context>(
 	ctx: &mut Context,
@@ -1024,6 +1165,7 @@ pub(crate) async fn run<Context>(
 	local_peer_id: PeerId,
 	collator_pair: CollatorPair,
 	mut req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	mut range_req_receiver: IncomingRequestReceiver<request_v1::CollationRangeFetchingRequest>,
 	metrics: Metrics,
 ) -> std::result::Result<(), FatalError>
 where
@@ -1038,6 +1180,8 @@ where
 	loop {
 		let recv_req = req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
 		pin_mut!(recv_req);
+		let recv_range_req = range_req_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
+		pin_mut!(recv_range_req);
 		select! {
 			msg = ctx.recv().fuse() => match msg.map_err(FatalError::SubsystemReceive)? {
 				FromOverseer::Communication { msg } => {
@@ -1064,11 +1208,18 @@ where
 					continue
 				};
 
-				if let Some(collation) = state.collations.get(&relay_parent) {
-					let receipt = collation.receipt.clone();
-					let pov = collation.pov.clone();
-
-					send_collation(&mut state, next, receipt, pov).await;
+				match next {
+					WaitingCollationFetch::Collation(next) => {
+						if let Some(collation) = state.collations.get(&relay_parent) {
+							let receipt = collation.receipt.clone();
+							let pov = collation.pov.clone();
+
+							send_collation(&mut state, next, receipt, pov).await;
+						}
+					},
+					WaitingCollationFetch::Range(next) => {
+						send_collation_range(&mut state, next).await;
+					},
 				}
 			}
 			in_req = recv_req => {
@@ -1090,6 +1241,25 @@ where
 					}
 				}
 			}
+			in_req = recv_range_req => {
+				match in_req {
+					Ok(req) => {
+						log_error(
+							handle_incoming_range_request(&mut ctx, &mut state, req).await,
+							"Handling incoming range request"
+						)?;
+					}
+					Err(error) => {
+						let jfyi = error.split().map_err(incoming::Error::from)?;
+						gum::debug!(
+							target: LOG_TARGET,
+							error = ?jfyi,
+							"Decoding incoming range request failed"
+						);
+						continue
+					}
+				}
+			}
 		}
 	}
 }
@@ -184,6 +184,7 @@ type VirtualOverseer = test_helpers::TestSubsystemContextHandle<CollatorProtocol
 struct TestHarness {
 	virtual_overseer: VirtualOverseer,
 	req_cfg: sc_network::config::RequestResponseConfig,
+	range_req_cfg: sc_network::config::RequestResponseConfig,
 }
 
 fn test_harness<T: Future<Output = TestHarness>>(
@@ -202,13 +203,21 @@ fn test_harness<T: Future<Output = TestHarness>>(
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
 	let (collation_req_receiver, req_cfg) = IncomingRequest::get_config_receiver();
+	let (collation_range_req_receiver, range_req_cfg) = IncomingRequest::get_config_receiver();
 	let subsystem = async {
-		run(context, local_peer_id, collator_pair, collation_req_receiver, Default::default())
-			.await
-			.unwrap();
+		run(
+			context,
+			local_peer_id,
+			collator_pair,
+			collation_req_receiver,
+			collation_range_req_receiver,
+			Default::default(),
+		)
+		.await
+		.unwrap();
 	};
 
-	let test_fut = test(TestHarness { virtual_overseer, req_cfg });
+	let test_fut = test(TestHarness { virtual_overseer, req_cfg, range_req_cfg });
 
 	futures::pin_mut!(test_fut);
 	futures::pin_mut!(subsystem);
@@ -635,7 +644,162 @@ fn advertise_and_send_collation() {
 		.await;
 
 		expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent).await;
-		TestHarness { virtual_overseer, req_cfg }
+		TestHarness { virtual_overseer, req_cfg, range_req_cfg: test_harness.range_req_cfg }
+	});
+}
+
+#[test]
+fn serve_collation_in_ranges() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let range_req_cfg = &mut test_harness.range_req_cfg;
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		let DistributeCollation { candidate, pov_block } =
+			distribute_collation(virtual_overseer, &test_state, true).await;
+		let encoded = (candidate, pov_block).encode();
+
+		let peer = test_state.current_group_validator_peer_ids()[0].clone();
+		let request_range = |offset| {
+			let (pending_response, rx) = oneshot::channel();
+			let request = RawIncomingRequest {
+				peer,
+				payload: CollationRangeFetchingRequest {
+					relay_parent: test_state.relay_parent,
+					para_id: test_state.para_id,
+					offset,
+					length: 2,
+				}
+				.encode(),
+				pending_response,
+			};
+			(request, rx)
+		};
+
+		let (request, rx) = request_range(1);
+		range_req_cfg.inbound_queue.as_mut().unwrap().send(request).await.unwrap();
+		assert_matches!(
+			rx.await,
+			Ok(full_response) => {
+				let CollationRangeFetchingResponse::Range { total_len, data } =
+					CollationRangeFetchingResponse::decode(
+						&mut full_response.result
+						.expect("We should have a proper answer").as_ref()
+				)
+				.expect("Decoding should work");
+				assert_eq!(total_len as usize, encoded.len());
+				assert_eq!(data, encoded[1..3].to_vec());
+			}
+		);
+
+		// Ranges beyond the end of the collation are bogus:
+		let (request, rx) = request_range(encoded.len() as u32 + 1);
+		range_req_cfg.inbound_queue.as_mut().unwrap().send(request).await.unwrap();
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(bad_peer, _)) => {
+				assert_eq!(bad_peer, peer);
+			}
+		);
+		assert_matches!(rx.await, Err(_));
+
+		test_harness
+	});
+}
+
+#[test]
+fn range_requests_wait_for_active_uploads() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let range_req_cfg = &mut test_harness.range_req_cfg;
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		let DistributeCollation { candidate, pov_block } =
+			distribute_collation(virtual_overseer, &test_state, true).await;
+		let encoded = (candidate, pov_block).encode();
+
+		let validator_0 = test_state.current_group_validator_peer_ids()[0].clone();
+		let validator_1 = test_state.current_group_validator_peer_ids()[1].clone();
+		let request_range = |peer, offset| {
+			let (pending_response, rx) = oneshot::channel();
+			let request = RawIncomingRequest {
+				peer,
+				payload: CollationRangeFetchingRequest {
+					relay_parent: test_state.relay_parent,
+					para_id: test_state.para_id,
+					offset,
+					length: 2,
+				}
+				.encode(),
+				pending_response,
+			};
+			(request, rx)
+		};
+
+		// Keep the feedback channel alive, so the upload to the first validator stays active.
+		let (request, rx) = request_range(validator_0, 0);
+		range_req_cfg.inbound_queue.as_mut().unwrap().send(request).await.unwrap();
+		let feedback_tx = assert_matches!(
+			rx.await,
+			Ok(full_response) => {
+				let CollationRangeFetchingResponse::Range { data, .. } =
+					CollationRangeFetchingResponse::decode(
+						&mut full_response.result
+						.expect("We should have a proper answer").as_ref()
+				)
+				.expect("Decoding should work");
+				assert_eq!(data, encoded[0..2].to_vec());
+
+				full_response.sent_feedback.expect("Feedback channel is always set")
+			}
+		);
+
+		// A second request of the same validator is a flood:
+		let (request, rx) = request_range(validator_0, 2);
+		range_req_cfg.inbound_queue.as_mut().unwrap().send(request).await.unwrap();
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(bad_peer, _)) => {
+				assert_eq!(bad_peer, validator_0);
+			}
+		);
+		assert_matches!(rx.await, Err(_));
+
+		// Another validator has to wait for the active upload to finish:
+		let (request, mut rx) = request_range(validator_1, 0);
+		range_req_cfg.inbound_queue.as_mut().unwrap().send(request).await.unwrap();
+		Delay::new(Duration::from_millis(100)).await;
+		assert!(
+			rx.try_recv().unwrap().is_none(),
+			"We should not have sent the range to the second validator yet",
+		);
+
+		feedback_tx.send(()).expect("Sending collation fetch finished");
+		assert_matches!(
+			rx.await,
+			Ok(full_response) => {
+				let CollationRangeFetchingResponse::Range { total_len, data } =
+					CollationRangeFetchingResponse::decode(
+						&mut full_response.result
+						.expect("We should have a proper answer").as_ref()
+				)
+				.expect("Decoding should work");
+				assert_eq!(total_len as usize, encoded.len());
+				assert_eq!(data, encoded[0..2].to_vec());
+			}
+		);
+
+		test_harness
 	});
 }
 
@@ -74,12 +74,15 @@ pub enum ProtocolSide {
 		eviction_policy: CollatorEvictionPolicy,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
+		/// Fetch collations in ranges, so interrupted transfers can be resumed.
+		fetch_in_ranges: bool,
 	},
 	/// Collators operate on a parachain.
 	Collator(
 		PeerId,
 		CollatorPair,
 		IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+		IncomingRequestReceiver<request_v1::CollationRangeFetchingRequest>,
 		collator_side::Metrics,
 	),
 }
@@ -104,10 +107,24 @@ impl CollatorProtocolSubsystem {
 		Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	{
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } =>
-				validator_side::run(ctx, keystore, eviction_policy, metrics).await,
-			ProtocolSide::Collator(local_peer_id, collator_pair, req_receiver, metrics) =>
-				collator_side::run(ctx, local_peer_id, collator_pair, req_receiver, metrics).await,
+			ProtocolSide::Validator { keystore, eviction_policy, metrics, fetch_in_ranges } =>
+				validator_side::run(ctx, keystore, eviction_policy, metrics, fetch_in_ranges).await,
+			ProtocolSide::Collator(
+				local_peer_id,
+				collator_pair,
+				req_receiver,
+				range_req_receiver,
+				metrics,
+			) =>
+				collator_side::run(
+					ctx,
+					local_peer_id,
+					collator_pair,
+					req_receiver,
+					range_req_receiver,
+					metrics,
+				)
+				.await,
 		}
 	}
 }
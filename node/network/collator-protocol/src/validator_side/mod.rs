@@ -58,6 +58,9 @@ use crate::error::Result;
 
 use super::{modify_reputation, LOG_TARGET};
 
+/// Fetching of collations in ranges.
+mod range_fetching;
+
 #[cfg(test)]
 mod tests;
 
@@ -616,6 +619,9 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Whether to fetch collations in ranges, instead of as a whole.
+	fetch_in_ranges: bool,
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -786,14 +792,20 @@ async fn request_collation<Context>(
 		return
 	}
 
-	let (full_request, response_recv) = OutgoingRequest::new(
-		Recipient::Peer(peer_id),
-		CollationFetchingRequest { relay_parent, para_id },
-	);
-	let requests = Requests::CollationFetching(full_request);
+	let (from_collator, requests) = if state.fetch_in_ranges {
+		let fetch =
+			range_fetching::fetch_collation(ctx.sender().clone(), peer_id, relay_parent, para_id);
+		(fetch.boxed(), None)
+	} else {
+		let (full_request, response_recv) = OutgoingRequest::new(
+			Recipient::Peer(peer_id),
+			CollationFetchingRequest { relay_parent, para_id },
+		);
+		(response_recv.boxed(), Some(Requests::CollationFetching(full_request)))
+	};
 
 	let per_request = PerRequest {
-		from_collator: response_recv.boxed().fuse(),
+		from_collator: from_collator.fuse(),
 		to_requester: result,
 		span: state
 			.span_per_relay_parent
@@ -814,11 +826,14 @@ async fn request_collation<Context>(
 		"Requesting collation",
 	);
 
-	ctx.send_message(NetworkBridgeMessage::SendRequests(
-		vec![requests],
-		IfDisconnected::ImmediateError,
-	))
-	.await;
+	// Range fetches send their requests themselves:
+	if let Some(requests) = requests {
+		ctx.send_message(NetworkBridgeMessage::SendRequests(
+			vec![requests],
+			IfDisconnected::ImmediateError,
+		))
+		.await;
+	}
 }
 
 /// Networking message has been received.
@@ -1215,12 +1230,13 @@ pub(crate) async fn run<Context>(
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
 	metrics: Metrics,
+	fetch_in_ranges: bool,
 ) -> std::result::Result<(), crate::error::FatalError>
 where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	let mut state = State { metrics, ..Default::default() };
+	let mut state = State { metrics, fetch_in_ranges, ..Default::default() };
 
 	let next_inactivity_stream = infinite_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -0,0 +1,212 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fetching of collations in ranges.
+//!
+//! The encoded collation is requested in ranges of `COLLATION_RANGE_SIZE`, with up to
+//! `MAX_PARALLEL_COLLATION_RANGES` requests in flight. A range failing because of a network
+//! error or timeout is requested again, so a transient disconnect only costs us that range instead
+//! of the whole transfer.
+
+use std::time::Duration;
+
+use futures::{stream, StreamExt, TryStreamExt};
+use futures_timer::Delay;
+use parity_scale_codec::Decode;
+
+use polkadot_node_network_protocol::{
+	request_response::{
+		network,
+		outgoing::{OutgoingResult, Recipient, RequestError},
+		v1::{
+			CollationFetchingRequest, CollationFetchingResponse, CollationRangeFetchingRequest,
+			CollationRangeFetchingResponse,
+		},
+		OutgoingRequest, Requests, COLLATION_RANGE_SIZE, MAX_PARALLEL_COLLATION_RANGES,
+	},
+	PeerId,
+};
+use polkadot_node_primitives::PoV;
+use polkadot_primitives::v2::{CandidateReceipt, Hash, Id as ParaId, MAX_POV_SIZE};
+use polkadot_subsystem::{
+	messages::{IfDisconnected, NetworkBridgeMessage},
+	SubsystemSender,
+};
+
+use crate::LOG_TARGET;
+
+/// How often we try to fetch a single range, before giving up on the collation.
+const MAX_RANGE_ATTEMPTS: usize = 3;
+
+/// Time to wait before retrying a failed range, giving the collator a chance to reconnect.
+const RANGE_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// Upper bound of the encoded collation size we are willing to download.
+///
+/// Dominated by the PoV, leaving some space for the candidate receipt.
+const MAX_COLLATION_SIZE: u32 = MAX_POV_SIZE + 10_000;
+
+/// Where to fetch a collation from.
+#[derive(Clone)]
+struct Source {
+	peer_id: PeerId,
+	relay_parent: Hash,
+	para_id: ParaId,
+}
+
+/// Fetch the collation advertised by `peer_id` in ranges.
+///
+/// Falls back to fetching the collation as a whole, if the collator does not support range
+/// requests.
+pub async fn fetch_collation<Sender>(
+	sender: Sender,
+	peer_id: PeerId,
+	relay_parent: Hash,
+	para_id: ParaId,
+) -> OutgoingResult<CollationFetchingResponse>
+where
+	Sender: SubsystemSender,
+{
+	let source = Source { peer_id, relay_parent, para_id };
+
+	let (total_len, first) = match fetch_range(sender.clone(), source.clone(), 0).await {
+		Err(RequestError::NetworkError(network::RequestFailure::Network(
+			network::OutboundFailure::UnsupportedProtocols,
+		))) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				?peer_id,
+				"Collator does not support range requests, fetching whole collation"
+			);
+			return fetch_whole(sender, source).await
+		},
+		r => r?,
+	};
+	if total_len > MAX_COLLATION_SIZE {
+		return Err(RequestError::InvalidResponse("Collation exceeds maximum size".into()))
+	}
+
+	let mut encoded = Vec::with_capacity(total_len as usize);
+	encoded.extend(first);
+
+	let offsets = (encoded.len() as u32..total_len).step_by(COLLATION_RANGE_SIZE as usize);
+	let mut ranges = stream::iter(offsets)
+		.map(|offset| fetch_range(sender.clone(), source.clone(), offset))
+		.buffered(MAX_PARALLEL_COLLATION_RANGES);
+
+	while let Some((len, data)) = ranges.try_next().await? {
+		if len != total_len {
+			return Err(RequestError::InvalidResponse("Collation length changed".into()))
+		}
+		encoded.extend(data);
+	}
+	if encoded.len() != total_len as usize {
+		return Err(RequestError::InvalidResponse("Collation length mismatch".into()))
+	}
+
+	let (receipt, pov) = <(CandidateReceipt, PoV)>::decode(&mut encoded.as_slice())?;
+	Ok(CollationFetchingResponse::Collation(receipt, pov))
+}
+
+/// Fetch a single range, retrying on network errors.
+///
+/// Returns the total length of the encoded collation and the range. The range is checked to be
+/// of the expected length.
+async fn fetch_range<Sender>(
+	mut sender: Sender,
+	source: Source,
+	offset: u32,
+) -> OutgoingResult<(u32, Vec<u8>)>
+where
+	Sender: SubsystemSender,
+{
+	let mut attempt = 0;
+	loop {
+		attempt += 1;
+		let (req, response_recv) = OutgoingRequest::new(
+			Recipient::Peer(source.peer_id),
+			CollationRangeFetchingRequest {
+				relay_parent: source.relay_parent,
+				para_id: source.para_id,
+				offset,
+				length: COLLATION_RANGE_SIZE,
+			},
+		);
+		sender
+			.send_message(
+				NetworkBridgeMessage::SendRequests(
+					vec![Requests::CollationRangeFetching(req)],
+					IfDisconnected::ImmediateError,
+				)
+				.into(),
+			)
+			.await;
+
+		match response_recv.await {
+			Ok(CollationRangeFetchingResponse::Range { total_len, data }) => {
+				let expected = total_len.saturating_sub(offset).min(COLLATION_RANGE_SIZE);
+				if data.len() != expected as usize {
+					return Err(RequestError::InvalidResponse("Unexpected range length".into()))
+				}
+				return Ok((total_len, data))
+			},
+			// Retrying won't help with those:
+			Err(err @ RequestError::InvalidResponse(_)) |
+			Err(
+				err @ RequestError::NetworkError(network::RequestFailure::Network(
+					network::OutboundFailure::UnsupportedProtocols,
+				)),
+			) => return Err(err),
+			Err(err) if attempt >= MAX_RANGE_ATTEMPTS => return Err(err),
+			Err(err) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					peer_id = ?source.peer_id,
+					relay_parent = ?source.relay_parent,
+					offset,
+					attempt,
+					?err,
+					"Fetching collation range failed, retrying"
+				);
+				Delay::new(RANGE_RETRY_DELAY).await;
+			},
+		}
+	}
+}
+
+/// Fetch the collation with a single `CollationFetchingRequest`.
+async fn fetch_whole<Sender>(
+	mut sender: Sender,
+	source: Source,
+) -> OutgoingResult<CollationFetchingResponse>
+where
+	Sender: SubsystemSender,
+{
+	let (req, response_recv) = OutgoingRequest::new(
+		Recipient::Peer(source.peer_id),
+		CollationFetchingRequest { relay_parent: source.relay_parent, para_id: source.para_id },
+	);
+	sender
+		.send_message(
+			NetworkBridgeMessage::SendRequests(
+				vec![Requests::CollationFetching(req)],
+				IfDisconnected::ImmediateError,
+			)
+			.into(),
+		)
+		.await;
+	response_recv.await
+}
@@ -24,7 +24,9 @@ use std::{iter, sync::Arc, time::Duration};
 
 use polkadot_node_network_protocol::{
 	our_view,
-	request_response::{Requests, ResponseSender},
+	request_response::{
+		network, v1::CollationRangeFetchingResponse, Requests, ResponseSender, COLLATION_RANGE_SIZE,
+	},
 	ObservedRole,
 };
 use polkadot_node_primitives::BlockData;
@@ -119,6 +121,13 @@ struct TestHarness {
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+	test_harness_inner(false, test)
+}
+
+fn test_harness_inner<T: Future<Output = VirtualOverseer>>(
+	fetch_in_ranges: bool,
+	test: impl FnOnce(TestHarness) -> T,
+) {
 	let _ = env_logger::builder()
 		.is_test(true)
 		.filter(Some("polkadot_collator_protocol"), log::LevelFilter::Trace)
@@ -145,6 +154,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 			undeclared: DECLARE_TIMEOUT,
 		},
 		Metrics::default(),
+		fetch_in_ranges,
 	);
 
 	let test_fut = test(TestHarness { virtual_overseer });
@@ -294,6 +304,32 @@ async fn assert_fetch_collation_request(
 	})
 }
 
+/// Assert that a collation range request was send.
+async fn assert_fetch_collation_range_request(
+	virtual_overseer: &mut VirtualOverseer,
+	relay_parent: Hash,
+	para_id: ParaId,
+	offset: u32,
+) -> ResponseSender {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::NetworkBridge(NetworkBridgeMessage::SendRequests(reqs, IfDisconnected::ImmediateError)
+	) => {
+		let req = reqs.into_iter().next()
+			.expect("There should be exactly one request");
+		match req {
+			Requests::CollationRangeFetching(req) => {
+				let payload = req.payload;
+				assert_eq!(payload.relay_parent, relay_parent);
+				assert_eq!(payload.para_id, para_id);
+				assert_eq!(payload.offset, offset);
+				req.pending_response
+			}
+			_ => panic!("Unexpected request"),
+		}
+	})
+}
+
 /// Connect and declare a collator
 async fn connect_and_declare_collator(
 	virtual_overseer: &mut VirtualOverseer,
@@ -666,6 +702,94 @@ fn fetch_collations_works() {
 	});
 }
 
+#[test]
+fn fetch_collation_in_ranges_resumes_after_network_error() {
+	let test_state = TestState::default();
+
+	test_harness_inner(true, |test_harness| async move {
+		let TestHarness { mut virtual_overseer } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0].clone(),
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		// Large enough for two ranges:
+		let pov = PoV { block_data: BlockData(vec![42; COLLATION_RANGE_SIZE as usize + 10]) };
+		let mut candidate_a =
+			dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+		candidate_a.descriptor.para_id = test_state.chain_ids[0];
+		candidate_a.descriptor.relay_parent = test_state.relay_parent;
+		let encoded = (candidate_a, pov.clone()).encode();
+		let total_len = encoded.len() as u32;
+		let range = |offset: usize| {
+			let end = encoded.len().min(offset + COLLATION_RANGE_SIZE as usize);
+			CollationRangeFetchingResponse::Range { total_len, data: encoded[offset..end].to_vec() }
+				.encode()
+		};
+
+		let response_channel = assert_fetch_collation_range_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			0,
+		)
+		.await;
+		response_channel.send(Ok(range(0))).expect("Sending response should succeed");
+
+		// The collator goes away in the middle of the transfer ...
+		let response_channel = assert_fetch_collation_range_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			COLLATION_RANGE_SIZE,
+		)
+		.await;
+		response_channel
+			.send(Err(network::RequestFailure::Network(network::OutboundFailure::ConnectionClosed)))
+			.expect("Sending response should succeed");
+
+		// ... and we only re-request the missing range:
+		let response_channel = assert_fetch_collation_range_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			COLLATION_RANGE_SIZE,
+		)
+		.await;
+		response_channel
+			.send(Ok(range(COLLATION_RANGE_SIZE as usize)))
+			.expect("Sending response should succeed");
+
+		assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov,
+		)
+		.await;
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn reject_connection_to_next_group() {
 	let test_state = TestState::default();
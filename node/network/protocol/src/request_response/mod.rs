@@ -71,6 +71,12 @@ pub enum Protocol {
 	StatementFetching,
 	/// Sending of dispute statements with application level confirmations.
 	DisputeSending,
+	/// Protocol for pushing chunks to their designated validators, used by availability
+	/// distribution.
+	ChunkPushing,
+	/// Protocol for fetching collations from collators in ranges, so interrupted transfers can be
+	/// resumed.
+	CollationRangeFetching,
 }
 
 /// Minimum bandwidth we expect for validators - 500Mbit/s is the recommendation, so approximately
@@ -108,6 +114,15 @@ const STATEMENTS_TIMEOUT: Duration = Duration::from_secs(1);
 /// to have 3 slow nodes connected, to delay transfer for others by `STATEMENTS_TIMEOUT`.
 pub const MAX_PARALLEL_STATEMENT_REQUESTS: u32 = 3;
 
+/// Size of the ranges requested via `CollationRangeFetching`.
+///
+/// Small enough to be transferred well within `POV_REQUEST_TIMEOUT_CONNECTED`, even on a slow
+/// link, so a failing range only costs us a fraction of the whole collation.
+pub const COLLATION_RANGE_SIZE: u32 = 1024 * 1024;
+
+/// How many ranges of a collation we fetch from a collator in parallel.
+pub const MAX_PARALLEL_COLLATION_RANGES: usize = 4;
+
 /// Response size limit for responses of POV like data.
 ///
 /// This is larger than `MAX_POV_SIZE` to account for protocol overhead and for additional data in
@@ -189,6 +204,23 @@ impl Protocol {
 				request_timeout: Duration::from_secs(12),
 				inbound_queue: Some(tx),
 			},
+			Protocol::ChunkPushing => RequestResponseConfig {
+				name: p_name,
+				// The request carries the chunk itself:
+				max_request_size: POV_RESPONSE_SIZE as u64 * 3,
+				// Responses are just confirmations.
+				max_response_size: 100,
+				// We are connected to all validators:
+				request_timeout: CHUNK_REQUEST_TIMEOUT,
+				inbound_queue: Some(tx),
+			},
+			Protocol::CollationRangeFetching => RequestResponseConfig {
+				name: p_name,
+				max_request_size: 1_000,
+				max_response_size: COLLATION_RANGE_SIZE as u64 + 1_000,
+				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
+				inbound_queue: Some(tx),
+			},
 		};
 		(rx, cfg)
 	}
@@ -234,6 +266,11 @@ impl Protocol {
 			// average, so something in the ballpark of 100 should be fine. Nodes will retry on
 			// failure, so having a good value here is mostly about performance tuning.
 			Protocol::DisputeSending => 100,
+			// Each backer pushes at most one chunk per candidate to us, so bursts are bounded by
+			// the number of candidates becoming available at the same time.
+			Protocol::ChunkPushing => 100,
+			// Group sizes of max 10 validators, each fetching a couple of ranges in parallel.
+			Protocol::CollationRangeFetching => 10 * MAX_PARALLEL_COLLATION_RANGES,
 		}
 	}
 
@@ -251,6 +288,8 @@ impl Protocol {
 			Protocol::AvailableDataFetching => "/polkadot/req_available_data/1",
 			Protocol::StatementFetching => "/polkadot/req_statement/1",
 			Protocol::DisputeSending => "/polkadot/send_dispute/1",
+			Protocol::ChunkPushing => "/polkadot/push_chunk/1",
+			Protocol::CollationRangeFetching => "/polkadot/req_collation_range/1",
 		}
 	}
 }
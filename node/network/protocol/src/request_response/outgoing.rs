@@ -40,6 +40,10 @@ pub enum Requests {
 	StatementFetching(OutgoingRequest<v1::StatementFetchingRequest>),
 	/// Requests for notifying about an ongoing dispute.
 	DisputeSending(OutgoingRequest<v1::DisputeRequest>),
+	/// Push an availability chunk to the validator it belongs to.
+	ChunkPushing(OutgoingRequest<v1::ChunkPushingRequest>),
+	/// Fetch a range of a collation from a collator.
+	CollationRangeFetching(OutgoingRequest<v1::CollationRangeFetchingRequest>),
 }
 
 impl Requests {
@@ -52,6 +56,8 @@ impl Requests {
 			Self::AvailableDataFetching(_) => Protocol::AvailableDataFetching,
 			Self::StatementFetching(_) => Protocol::StatementFetching,
 			Self::DisputeSending(_) => Protocol::DisputeSending,
+			Self::ChunkPushing(_) => Protocol::ChunkPushing,
+			Self::CollationRangeFetching(_) => Protocol::CollationRangeFetching,
 		}
 	}
 
@@ -70,6 +76,8 @@ impl Requests {
 			Self::AvailableDataFetching(r) => r.encode_request(),
 			Self::StatementFetching(r) => r.encode_request(),
 			Self::DisputeSending(r) => r.encode_request(),
+			Self::ChunkPushing(r) => r.encode_request(),
+			Self::CollationRangeFetching(r) => r.encode_request(),
 		}
 	}
 }
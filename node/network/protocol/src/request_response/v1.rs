@@ -88,6 +88,34 @@ impl IsRequest for ChunkFetchingRequest {
 	const PROTOCOL: Protocol = Protocol::ChunkFetching;
 }
 
+/// Push an availability chunk to the validator it belongs to.
+///
+/// Sent by backers right after the candidate got occupied, so the recipient does not need to
+/// fetch its chunk.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ChunkPushingRequest {
+	/// Hash of the candidate the chunk belongs to.
+	pub candidate_hash: CandidateHash,
+	/// The chunk, including its index and proof.
+	pub chunk: ErasureChunk,
+}
+
+/// Response to a `ChunkPushingRequest`.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum ChunkPushingResponse {
+	/// The chunk has been verified and stored.
+	#[codec(index = 0)]
+	Stored,
+	/// The chunk was not needed (already fetched, unknown candidate, not our chunk) or invalid.
+	#[codec(index = 1)]
+	NotNeeded,
+}
+
+impl IsRequest for ChunkPushingRequest {
+	type Response = ChunkPushingResponse;
+	const PROTOCOL: Protocol = Protocol::ChunkPushing;
+}
+
 /// Request the advertised collation at that relay-parent.
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct CollationFetchingRequest {
@@ -110,6 +138,40 @@ impl IsRequest for CollationFetchingRequest {
 	const PROTOCOL: Protocol = Protocol::CollationFetching;
 }
 
+/// Request a range of the advertised collation at that relay-parent.
+///
+/// Ranges are taken from the SCALE encoded `(CandidateReceipt, PoV)`, as also sent in
+/// `CollationFetchingResponse::Collation`.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CollationRangeFetchingRequest {
+	/// Relay parent we want a collation for.
+	pub relay_parent: Hash,
+	/// The `ParaId` of the collation.
+	pub para_id: ParaId,
+	/// Offset of the requested range into the encoded collation.
+	pub offset: u32,
+	/// Length of the requested range, capped at `COLLATION_RANGE_SIZE` by the collator.
+	pub length: u32,
+}
+
+/// Responses to `CollationRangeFetchingRequest`s as sent by collators.
+#[derive(Debug, Clone, Encode, Decode)]
+pub enum CollationRangeFetchingResponse {
+	/// Deliver the requested range.
+	#[codec(index = 0)]
+	Range {
+		/// Length of the whole encoded collation.
+		total_len: u32,
+		/// The requested range, shorter if it exceeds `total_len`.
+		data: Vec<u8>,
+	},
+}
+
+impl IsRequest for CollationRangeFetchingRequest {
+	type Response = CollationRangeFetchingResponse;
+	const PROTOCOL: Protocol = Protocol::CollationRangeFetching;
+}
+
 /// Request the advertised collation at that relay-parent.
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct PoVFetchingRequest {
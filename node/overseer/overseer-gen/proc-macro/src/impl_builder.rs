@@ -344,6 +344,8 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			handle: #handle,
 			/// The side consumed by the `spawned` side of the overseer pattern.
 			consumer: #support_crate ::metered::MeteredReceiver < #event >,
+			/// Introspection of the messages exchanged between the subsystems.
+			introspection: #support_crate ::MessageIntrospection,
 		}
 
 		impl #connector {
@@ -359,6 +361,18 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			pub fn handle(&self) -> #handle {
 				self.handle.clone()
 			}
+			/// Obtain a clone of the message introspection handle.
+			pub fn introspection(&self) -> #support_crate ::MessageIntrospection {
+				self.introspection.clone()
+			}
+			/// Use the given message introspection handle instead of a new one.
+			pub fn with_introspection(
+				mut self,
+				introspection: #support_crate ::MessageIntrospection,
+			) -> Self {
+				self.introspection = introspection;
+				self
+			}
 		}
 
 		impl ::std::default::Default for #connector {
@@ -370,6 +384,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				Self {
 					handle: events_tx,
 					consumer: events_rx,
+					introspection: ::std::default::Default::default(),
 				}
 			}
 		}
@@ -457,6 +472,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				let #connector {
 					handle: events_tx,
 					consumer: events_rx,
+					introspection,
 				} = connector;
 
 				let handle = events_tx.clone();
@@ -522,7 +538,8 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						message_rx,
 						channels_out.clone(),
 						to_overseer_tx.clone(),
-						subsystem_static_str
+						subsystem_static_str,
+						introspection.clone(),
 					);
 
 					let #subsystem_name: OverseenSubsystem< #consumes > =
@@ -536,6 +553,10 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 							subsystem_static_str,
 							&mut running_subsystems,
 						)?;
+
+					if let Some(instance) = #subsystem_name.instance.as_ref() {
+						introspection.register(subsystem_static_str, instance.meters.clone());
+					}
 				)*
 
 				use #support_crate ::StreamExt;
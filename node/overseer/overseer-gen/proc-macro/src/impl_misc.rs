@@ -112,7 +112,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				>,
 			signals_received: SignalsReceived,
 			pending_incoming: Option<(usize, M)>,
-			name: &'static str
+			name: &'static str,
+			introspection: #support_crate ::MessageIntrospection,
 		}
 
 		impl<M> #subsystem_ctx_name<M> {
@@ -122,7 +123,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				messages: SubsystemIncomingMessages<M>,
 				to_subsystems: ChannelsOut,
 				to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOverseer>,
-				name: &'static str
+				name: &'static str,
+				introspection: #support_crate ::MessageIntrospection,
 			) -> Self {
 				let signals_received = SignalsReceived::default();
 				#subsystem_ctx_name {
@@ -135,7 +137,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					to_overseer,
 					signals_received,
 					pending_incoming: None,
-					name
+					name,
+					introspection,
 				}
 			}
 
@@ -189,6 +192,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					let mut await_signal = self.signals.next().fuse();
 					let signals_received = self.signals_received.load();
 					let pending_incoming = &mut self.pending_incoming;
+					let introspection = &self.introspection;
+					let name = self.name;
 
 					// Otherwise, wait for the next signal or incoming message.
 					let from_overseer = #support_crate ::futures::select_biased! {
@@ -208,6 +213,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 									.to_owned()
 								))?;
 
+							introspection.on_message_received(name, &packet.message);
+
 							if packet.signals_received > signals_received {
 								// wait until we've received enough signals to return this message.
 								*pending_incoming = Some((packet.signals_received, packet.message));
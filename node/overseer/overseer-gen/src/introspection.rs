@@ -0,0 +1,375 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Introspection of the messages exchanged between subsystems.
+//!
+//! Meant for debugging a running node, e.g. subsystems waiting on each other. The number of
+//! pending messages of every subsystem is always available. The types of the messages a
+//! subsystem received recently are only recorded while recording is enabled, and the messages
+//! themselves only while they are captured, since both require formatting every message.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt::{self, Debug, Write},
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc, Mutex, MutexGuard, PoisonError,
+	},
+};
+
+use crate::SubsystemMeters;
+
+/// Number of most recently received messages of a subsystem the message type histogram covers.
+pub const HISTOGRAM_WINDOW: usize = 1000;
+
+/// Maximum number of messages that can be captured at once.
+pub const MAX_CAPTURED_MESSAGES: usize = 1000;
+
+/// Maximum length of a captured message, longer messages are truncated.
+pub const MAX_CAPTURED_MESSAGE_LEN: usize = 4096;
+
+/// Maximum length of a message type.
+const MAX_MESSAGE_TYPE_LEN: usize = 64;
+
+/// Error returned when capturing the messages of a subsystem that doesn't exist.
+#[derive(thiserror::Error, Debug)]
+#[error("Unknown subsystem: {0}")]
+pub struct UnknownSubsystem(pub String);
+
+/// Pending messages and recently received message types of a subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubsystemSnapshot {
+	/// Name of the subsystem.
+	pub name: &'static str,
+	/// Number of messages sent to the subsystem, but not received by it yet.
+	pub pending_messages: usize,
+	/// Number of signals sent to the subsystem, but not received by it yet.
+	pub pending_signals: usize,
+	/// Number of messages per type among the last [`HISTOGRAM_WINDOW`] messages received while
+	/// recording, most frequent first. Empty if recording is disabled.
+	pub recent_message_types: Vec<(String, usize)>,
+}
+
+/// Messages captured for a subsystem.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapturedMessages {
+	/// Name of the subsystem.
+	pub subsystem: &'static str,
+	/// Formatted messages, oldest first.
+	pub messages: Vec<String>,
+}
+
+/// Snapshot of the messages exchanged between subsystems.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageBusSnapshot {
+	/// Whether the types of the received messages are recorded.
+	pub recording: bool,
+	/// All subsystems, in the order they have been spawned.
+	pub subsystems: Vec<SubsystemSnapshot>,
+	/// Messages of the subsystem that is being captured, if any.
+	pub captured: Option<CapturedMessages>,
+}
+
+/// Shared handle to inspect the messages exchanged between the subsystems of an overseer.
+///
+/// The overseer registers its subsystems on the handle of its connector when it is built.
+#[derive(Clone, Default)]
+pub struct MessageIntrospection(Arc<Inner>);
+
+impl Debug for MessageIntrospection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("MessageIntrospection")
+			.field("active", &self.0.active.load(Ordering::Relaxed))
+			.finish()
+	}
+}
+
+#[derive(Default)]
+struct Inner {
+	// Whether recording or capturing, so that subsystems don't need to lock otherwise.
+	active: AtomicBool,
+	state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+	subsystems: Vec<(&'static str, SubsystemMeters)>,
+	recording: bool,
+	recent: HashMap<&'static str, VecDeque<String>>,
+	capture: Option<Capture>,
+}
+
+struct Capture {
+	subsystem: &'static str,
+	count: usize,
+	messages: VecDeque<String>,
+}
+
+impl MessageIntrospection {
+	/// Register a spawned subsystem.
+	pub fn register(&self, name: &'static str, meters: SubsystemMeters) {
+		self.state().subsystems.push((name, meters));
+	}
+
+	/// Names of all registered subsystems.
+	pub fn subsystems(&self) -> Vec<&'static str> {
+		self.state().subsystems.iter().map(|(name, _)| *name).collect()
+	}
+
+	/// Enable or disable recording the types of the received messages.
+	///
+	/// Disabling recording discards the recorded types.
+	pub fn set_recording(&self, recording: bool) {
+		let mut state = self.state();
+		state.recording = recording;
+		if !recording {
+			state.recent.clear();
+		}
+		self.update_active(&state);
+	}
+
+	/// Capture the last `count` messages received by `subsystem`, replacing any previous capture.
+	///
+	/// `count` is limited to [`MAX_CAPTURED_MESSAGES`], a `count` of zero stops capturing.
+	pub fn capture(&self, subsystem: &str, count: usize) -> Result<(), UnknownSubsystem> {
+		let mut state = self.state();
+		state.capture = if count == 0 {
+			None
+		} else {
+			let subsystem = state
+				.subsystems
+				.iter()
+				.map(|(name, _)| *name)
+				.find(|name| *name == subsystem)
+				.ok_or_else(|| UnknownSubsystem(subsystem.into()))?;
+			let count = count.min(MAX_CAPTURED_MESSAGES);
+			Some(Capture { subsystem, count, messages: VecDeque::with_capacity(count) })
+		};
+		self.update_active(&state);
+		Ok(())
+	}
+
+	/// Take a snapshot of the pending messages of all subsystems along with the recorded
+	/// message types and captured messages.
+	pub fn snapshot(&self) -> MessageBusSnapshot {
+		let state = self.state();
+		let subsystems = state
+			.subsystems
+			.iter()
+			.map(|(name, meters)| {
+				let mut recent_message_types = HashMap::<&str, usize>::new();
+				for message_type in state.recent.get(name).into_iter().flatten() {
+					*recent_message_types.entry(message_type).or_default() += 1;
+				}
+				let mut recent_message_types = recent_message_types
+					.into_iter()
+					.map(|(message_type, count)| (message_type.to_owned(), count))
+					.collect::<Vec<_>>();
+				recent_message_types.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+				SubsystemSnapshot {
+					name,
+					pending_messages: meters.bounded.pending() + meters.unbounded.pending(),
+					pending_signals: meters.signals.pending(),
+					recent_message_types,
+				}
+			})
+			.collect();
+
+		MessageBusSnapshot {
+			recording: state.recording,
+			subsystems,
+			captured: state.capture.as_ref().map(|capture| CapturedMessages {
+				subsystem: capture.subsystem,
+				messages: capture.messages.iter().cloned().collect(),
+			}),
+		}
+	}
+
+	/// Note that `subsystem` received `message`.
+	pub fn on_message_received<M: Debug>(&self, subsystem: &'static str, message: &M) {
+		if !self.0.active.load(Ordering::Relaxed) {
+			return
+		}
+
+		let mut state = self.state();
+		if state.recording {
+			let recent = state.recent.entry(subsystem).or_default();
+			if recent.len() == HISTOGRAM_WINDOW {
+				recent.pop_front();
+			}
+			recent.push_back(message_type(message));
+		}
+		if let Some(capture) = state.capture.as_mut().filter(|c| c.subsystem == subsystem) {
+			if capture.messages.len() == capture.count {
+				capture.messages.pop_front();
+			}
+			capture.messages.push_back(truncated(message, MAX_CAPTURED_MESSAGE_LEN, false));
+		}
+	}
+
+	fn state(&self) -> MutexGuard<'_, State> {
+		self.0.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn update_active(&self, state: &State) {
+		self.0
+			.active
+			.store(state.recording || state.capture.is_some(), Ordering::Relaxed);
+	}
+}
+
+/// The type of a message, i.e. the name of its variant.
+fn message_type(message: &impl Debug) -> String {
+	truncated(message, MAX_MESSAGE_TYPE_LEN, true)
+}
+
+/// Format `value` up to `limit` bytes and, if `name_only`, up to the end of its name.
+///
+/// Formatting stops as soon as the limit is reached, so only the formatted part is paid for.
+fn truncated(value: &impl Debug, limit: usize, name_only: bool) -> String {
+	struct Truncated {
+		buf: String,
+		limit: usize,
+		name_only: bool,
+	}
+
+	impl Write for Truncated {
+		fn write_str(&mut self, s: &str) -> fmt::Result {
+			let mut end = s.len();
+			if self.name_only {
+				end = s.find(|c: char| c == '(' || c == '{' || c.is_whitespace()).unwrap_or(end);
+			}
+			let remaining = self.limit - self.buf.len();
+			if end > remaining {
+				end = remaining;
+				while !s.is_char_boundary(end) {
+					end -= 1;
+				}
+			}
+
+			self.buf.push_str(&s[..end]);
+			if end < s.len() {
+				// Abort formatting the rest of the value.
+				Err(fmt::Error)
+			} else {
+				Ok(())
+			}
+		}
+	}
+
+	let mut out = Truncated { buf: String::new(), limit, name_only };
+	let _ = write!(out, "{:?}", value);
+	out.buf
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[derive(Debug)]
+	enum TestMessage {
+		Unit,
+		Tuple(u32),
+		Struct { value: String },
+	}
+
+	fn meters() -> SubsystemMeters {
+		SubsystemMeters {
+			bounded: Default::default(),
+			unbounded: Default::default(),
+			signals: Default::default(),
+		}
+	}
+
+	#[test]
+	fn message_types_are_variant_names() {
+		assert_eq!(message_type(&TestMessage::Unit), "Unit");
+		assert_eq!(message_type(&TestMessage::Tuple(1)), "Tuple");
+		assert_eq!(message_type(&TestMessage::Struct { value: "a".into() }), "Struct");
+	}
+
+	#[test]
+	fn captured_messages_are_truncated() {
+		let message = TestMessage::Struct { value: "ä".repeat(10) };
+		assert_eq!(truncated(&message, 19, false), "Struct { value: \"ä");
+		assert_eq!(truncated(&message, 18, false), "Struct { value: \"");
+	}
+
+	#[test]
+	fn nothing_is_recorded_by_default() {
+		let introspection = MessageIntrospection::default();
+		introspection.register("a", meters());
+		introspection.on_message_received("a", &TestMessage::Unit);
+
+		let snapshot = introspection.snapshot();
+		assert!(!snapshot.recording);
+		assert!(snapshot.subsystems[0].recent_message_types.is_empty());
+		assert_eq!(snapshot.captured, None);
+	}
+
+	#[test]
+	fn recording_builds_histograms() {
+		let introspection = MessageIntrospection::default();
+		introspection.register("a", meters());
+		introspection.register("b", meters());
+		introspection.set_recording(true);
+
+		introspection.on_message_received("a", &TestMessage::Unit);
+		introspection.on_message_received("a", &TestMessage::Tuple(1));
+		introspection.on_message_received("a", &TestMessage::Tuple(2));
+		for _ in 0..HISTOGRAM_WINDOW {
+			introspection.on_message_received("b", &TestMessage::Tuple(1));
+		}
+		introspection.on_message_received("b", &TestMessage::Unit);
+
+		let snapshot = introspection.snapshot();
+		assert_eq!(
+			snapshot.subsystems[0].recent_message_types,
+			vec![("Tuple".into(), 2), ("Unit".into(), 1)],
+		);
+		assert_eq!(
+			snapshot.subsystems[1].recent_message_types,
+			vec![("Tuple".into(), HISTOGRAM_WINDOW - 1), ("Unit".into(), 1)],
+		);
+
+		introspection.set_recording(false);
+		assert!(introspection.snapshot().subsystems[0].recent_message_types.is_empty());
+	}
+
+	#[test]
+	fn capture_keeps_last_messages() {
+		let introspection = MessageIntrospection::default();
+		introspection.register("a", meters());
+		assert!(introspection.capture("c", 2).is_err());
+		introspection.capture("a", 2).unwrap();
+
+		introspection.on_message_received("a", &TestMessage::Tuple(1));
+		introspection.on_message_received("a", &TestMessage::Tuple(2));
+		introspection.on_message_received("a", &TestMessage::Tuple(3));
+
+		assert_eq!(
+			introspection.snapshot().captured,
+			Some(CapturedMessages {
+				subsystem: "a",
+				messages: vec!["Tuple(2)".into(), "Tuple(3)".into()],
+			}),
+		);
+
+		introspection.capture("a", 0).unwrap();
+		assert_eq!(introspection.snapshot().captured, None);
+	}
+}
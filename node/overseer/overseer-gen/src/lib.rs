@@ -98,6 +98,12 @@ pub use polkadot_node_network_protocol::WrongVariant;
 
 use std::fmt;
 
+mod introspection;
+pub use self::introspection::{
+	CapturedMessages, MessageBusSnapshot, MessageIntrospection, SubsystemSnapshot,
+	UnknownSubsystem, HISTOGRAM_WINDOW, MAX_CAPTURED_MESSAGES, MAX_CAPTURED_MESSAGE_LEN,
+};
+
 #[cfg(test)]
 mod tests;
 
@@ -105,9 +105,10 @@ use parity_util_mem::MemoryAllocationTracker;
 
 pub use polkadot_overseer_gen as gen;
 pub use polkadot_overseer_gen::{
-	overlord, FromOverseer, MapSubsystem, MessagePacket, SignalsReceived, SpawnNamed, Subsystem,
-	SubsystemContext, SubsystemIncomingMessages, SubsystemInstance, SubsystemMeterReadouts,
-	SubsystemMeters, SubsystemSender, TimeoutExt, ToOverseer,
+	overlord, CapturedMessages, FromOverseer, MapSubsystem, MessageBusSnapshot,
+	MessageIntrospection, MessagePacket, SignalsReceived, SpawnNamed, Subsystem, SubsystemContext,
+	SubsystemIncomingMessages, SubsystemInstance, SubsystemMeterReadouts, SubsystemMeters,
+	SubsystemSender, SubsystemSnapshot, TimeoutExt, ToOverseer, UnknownSubsystem,
 };
 
 /// Store 2 days worth of blocks, not accounting for forks,
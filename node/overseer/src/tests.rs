@@ -216,6 +216,72 @@ fn overseer_works() {
 	});
 }
 
+// Checks that the messages received by subsystems can be inspected.
+#[test]
+fn overseer_introspection_works() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let (s1_tx, s1_rx) = metered::channel::<usize>(64);
+		let (s2_tx, _s2_rx) = metered::channel::<usize>(64);
+
+		let mut s1_rx = s1_rx.fuse();
+		let connector = OverseerConnector::default();
+		let introspection = connector.introspection();
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_candidate_validation(move |_| TestSubsystem1(s1_tx))
+			.replace_candidate_backing(move |_| TestSubsystem2(s2_tx))
+			.build_with_connector(connector)
+			.unwrap();
+
+		assert!(introspection.subsystems().contains(&"candidate-validation"));
+		assert!(introspection.capture("candidate-validation-subsystem", 3).is_err());
+		introspection.set_recording(true);
+		introspection.capture("candidate-validation", 3).unwrap();
+
+		let mut handle = Handle::new(handle);
+		let overseer_fut = overseer.run().fuse();
+
+		pin_mut!(overseer_fut);
+
+		let mut s1_results = Vec::new();
+		loop {
+			select! {
+				_ = overseer_fut => break,
+				s1_next = s1_rx.next() => {
+					match s1_next {
+						Some(msg) => {
+							s1_results.push(msg);
+							if s1_results.len() == 10 {
+								handle.stop().await;
+							}
+						}
+						None => break,
+					}
+				},
+				complete => break,
+			}
+		}
+
+		let snapshot = introspection.snapshot();
+		let candidate_validation = snapshot
+			.subsystems
+			.iter()
+			.find(|subsystem| subsystem.name == "candidate-validation")
+			.unwrap();
+		assert_eq!(
+			candidate_validation.recent_message_types,
+			vec![("ValidateFromChainState".to_owned(), 10)],
+		);
+		assert_matches!(snapshot.captured, Some(captured) => {
+			assert_eq!(captured.subsystem, "candidate-validation");
+			assert_eq!(captured.messages.len(), 3);
+			assert!(captured.messages[0].starts_with("ValidateFromChainState("));
+		});
+	});
+}
+
 // Checks activated/deactivated metrics are updated properly.
 #[test]
 fn overseer_metrics_work() {
@@ -1139,6 +1205,7 @@ fn context_holds_onto_message_until_enough_signals_received() {
 		channels_out,
 		to_overseer_tx,
 		"test",
+		MessageIntrospection::default(),
 	);
 
 	assert_eq!(ctx.signals_received.load(), 0);
@@ -0,0 +1,244 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the Rococo <> Wococo bridge.
+//!
+//! The metrics are derived from the on-chain state of the bridge pallets, which is read
+//! through the bridge runtime APIs at every new best block. A Rococo node reports the state
+//! of its bridge with Wococo and vice versa:
+//!
+//! - the best finalized header of the bridged chain that is known to the runtime;
+//! - the number of blocks imported since that header has last changed;
+//! - the number of undelivered outbound messages at every registered lane;
+//! - the number of unrewarded relayer entries at every registered lane.
+
+#![cfg(all(feature = "full-node", feature = "rococo-native"))]
+
+use bp_messages::LaneId;
+use futures::StreamExt;
+use polkadot_client::{FullClient, RococoExecutorDispatch};
+use polkadot_node_subsystem_util::metrics::{self, prometheus};
+use polkadot_primitives::v2::{Block, BlockNumber};
+use sc_client_api::BlockchainEvents;
+use sp_api::ProvideRuntimeApi;
+use sp_runtime::{generic::BlockId, traits::Header as HeaderT};
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "bridge-metrics";
+
+/// Prometheus metrics of the bridge state.
+#[derive(Debug, Default, Clone)]
+pub struct Metrics(Option<MetricsInner>);
+
+#[derive(Debug, Clone)]
+struct MetricsInner {
+	best_finalized_bridged_header: prometheus::Gauge<prometheus::U64>,
+	bridged_finality_lag: prometheus::Gauge<prometheus::U64>,
+	undelivered_messages: prometheus::GaugeVec<prometheus::U64>,
+	unrewarded_relayer_entries: prometheus::GaugeVec<prometheus::U64>,
+}
+
+impl metrics::Metrics for Metrics {
+	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
+		let metrics = MetricsInner {
+			best_finalized_bridged_header: prometheus::register(
+				prometheus::Gauge::with_opts(prometheus::Opts::new(
+					"polkadot_bridge_best_finalized_bridged_header",
+					"Number of the best finalized header of the bridged chain known to the runtime",
+				))?,
+				registry,
+			)?,
+			bridged_finality_lag: prometheus::register(
+				prometheus::Gauge::with_opts(prometheus::Opts::new(
+					"polkadot_bridge_bridged_finality_lag",
+					"Number of blocks imported since the best finalized bridged header has changed",
+				))?,
+				registry,
+			)?,
+			undelivered_messages: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_bridge_undelivered_messages",
+						"Number of outbound messages not yet delivered to the bridged chain",
+					),
+					&["lane"],
+				)?,
+				registry,
+			)?,
+			unrewarded_relayer_entries: prometheus::register(
+				prometheus::GaugeVec::new(
+					prometheus::Opts::new(
+						"polkadot_bridge_unrewarded_relayer_entries",
+						"Number of relayer entries at the inbound lane that are not yet rewarded",
+					),
+					&["lane"],
+				)?,
+				registry,
+			)?,
+		};
+
+		Ok(Metrics(Some(metrics)))
+	}
+}
+
+impl Metrics {
+	fn note_best_finalized_bridged_header(&self, number: BlockNumber, lag: BlockNumber) {
+		if let Some(ref metrics) = self.0 {
+			metrics.best_finalized_bridged_header.set(number as _);
+			metrics.bridged_finality_lag.set(lag as _);
+		}
+	}
+
+	fn note_lanes(&self, lanes: &[LaneState]) {
+		if let Some(ref metrics) = self.0 {
+			// lanes may be deregistered, so drop values of lanes that are no longer reported
+			metrics.undelivered_messages.reset();
+			metrics.unrewarded_relayer_entries.reset();
+			for lane in lanes {
+				let label = lane_label(&lane.id);
+				metrics
+					.undelivered_messages
+					.with_label_values(&[&label])
+					.set(lane.undelivered_messages);
+				metrics
+					.unrewarded_relayer_entries
+					.with_label_values(&[&label])
+					.set(lane.unrewarded_relayer_entries);
+			}
+		}
+	}
+}
+
+/// Bridge state of a single lane.
+struct LaneState {
+	id: LaneId,
+	undelivered_messages: u64,
+	unrewarded_relayer_entries: u64,
+}
+
+/// Bridge state at some block.
+struct BridgeState {
+	best_finalized_bridged_header: BlockNumber,
+	lanes: Vec<LaneState>,
+}
+
+/// Reads the state of the bridge with the given chain through its runtime APIs.
+macro_rules! read_bridge_state {
+	(
+		$api:expr,
+		$at:expr,
+		$chain:ident,
+		$finality_api:ident,
+		$outbound_api:ident,
+		$inbound_api:ident
+	) => {{
+		let (best_finalized_bridged_header, _) =
+			$chain::$finality_api::best_finalized(&*$api, $at)?;
+		let mut lanes = Vec::new();
+		for id in $chain::$outbound_api::lanes(&*$api, $at)? {
+			let stats = $chain::$outbound_api::outbound_lane_stats(&*$api, $at, id)?;
+			let inbound_lane = $chain::$inbound_api::inbound_lane_data(&*$api, $at, id)?;
+			lanes.push(LaneState {
+				id,
+				undelivered_messages: stats.messages_sent.saturating_sub(stats.messages_delivered),
+				unrewarded_relayer_entries: inbound_lane.relayers.len() as u64,
+			});
+		}
+
+		Ok(BridgeState { best_finalized_bridged_header, lanes })
+	}};
+}
+
+fn read_bridge_state(
+	client: &FullClient<rococo_runtime::RuntimeApi, RococoExecutorDispatch>,
+	at: &BlockId<Block>,
+	is_wococo: bool,
+) -> Result<BridgeState, sp_api::ApiError> {
+	let api = client.runtime_api();
+	if is_wococo {
+		read_bridge_state!(
+			api,
+			at,
+			bp_rococo,
+			RococoFinalityApi,
+			ToRococoOutboundLaneApi,
+			FromRococoInboundLaneApi
+		)
+	} else {
+		read_bridge_state!(
+			api,
+			at,
+			bp_wococo,
+			WococoFinalityApi,
+			ToWococoOutboundLaneApi,
+			FromWococoInboundLaneApi
+		)
+	}
+}
+
+fn lane_label(lane: &LaneId) -> String {
+	lane.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Updates the bridge metrics at every new best block until the import notification stream
+/// is closed.
+///
+/// `is_wococo` selects the bridged chain: a Wococo node tracks its bridge with Rococo and
+/// any other node of the Rococo runtime tracks its bridge with Wococo.
+pub(crate) async fn run(
+	client: Arc<FullClient<rococo_runtime::RuntimeApi, RococoExecutorDispatch>>,
+	is_wococo: bool,
+	metrics: Metrics,
+) {
+	let mut imports = client.import_notification_stream();
+	// best finalized bridged header and the local block at which it has been first seen
+	let mut last_bridged_header: Option<(BlockNumber, BlockNumber)> = None;
+
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best {
+			continue
+		}
+
+		let hash = notification.hash;
+		let number = *notification.header.number();
+		let state = match read_bridge_state(&client, &BlockId::Hash(hash), is_wococo) {
+			Ok(state) => state,
+			Err(error) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					?hash,
+					?error,
+					"Failed to read the bridge state",
+				);
+				continue
+			},
+		};
+
+		let seen_at = match last_bridged_header {
+			Some((bridged, seen_at)) if bridged == state.best_finalized_bridged_header => seen_at,
+			_ => {
+				last_bridged_header = Some((state.best_finalized_bridged_header, number));
+				number
+			},
+		};
+
+		metrics.note_best_finalized_bridged_header(
+			state.best_finalized_bridged_header,
+			number.saturating_sub(seen_at),
+		);
+		metrics.note_lanes(&state.lanes);
+	}
+}
@@ -126,15 +126,35 @@ pub struct RococoGenesisExt {
 	///
 	/// If `None` is supplied, the default value is used.
 	session_length_in_blocks: Option<u32>,
+	/// The slot duration, and thus the target block time, in milliseconds.
+	///
+	/// If `None` is supplied, the default value is used.
+	slot_duration_in_millis: Option<u64>,
 }
 
 #[cfg(feature = "rococo-native")]
 impl sp_runtime::BuildStorage for RococoGenesisExt {
 	fn assimilate_storage(&self, storage: &mut sp_core::storage::Storage) -> Result<(), String> {
+		if self.session_length_in_blocks == Some(0) {
+			return Err("The session length must be at least one block".into())
+		}
+		if let Some(duration) = self.slot_duration_in_millis {
+			if !rococo_runtime_constants::time::is_valid_slot_duration(duration) {
+				return Err(format!(
+					"The slot duration must be between 1 and {} milliseconds, got {}",
+					rococo_runtime_constants::time::MAX_SLOT_DURATION,
+					duration,
+				))
+			}
+		}
+
 		sp_state_machine::BasicExternalities::execute_with_storage(storage, || {
 			if let Some(length) = self.session_length_in_blocks.as_ref() {
 				rococo_runtime_constants::time::EpochDurationInBlocks::set(length);
 			}
+			if let Some(duration) = self.slot_duration_in_millis.as_ref() {
+				rococo_runtime_constants::time::SlotDuration::set(duration);
+			}
 		});
 		self.runtime_genesis_config.assimilate_storage(storage)
 	}
@@ -572,6 +592,14 @@ fn westend_staging_testnet_config_genesis(wasm_binary: &[u8]) -> westend::Genesi
 			next_free_para_id: polkadot_primitives::v2::LOWEST_PUBLIC_ID,
 		},
 		xcm_pallet: Default::default(),
+		bridge_rococo_grandpa: westend::BridgeRococoGrandpaConfig {
+			owner: Some(endowed_accounts[0].clone()),
+			..Default::default()
+		},
+		bridge_rococo_messages: westend::BridgeRococoMessagesConfig {
+			owner: Some(endowed_accounts[0].clone()),
+			..Default::default()
+		},
 	}
 }
 
@@ -1074,6 +1102,10 @@ fn rococo_staging_testnet_config_genesis(wasm_binary: &[u8]) -> rococo_runtime::
 			owner: Some(endowed_accounts[0].clone()),
 			..Default::default()
 		},
+		bridge_westend_grandpa: rococo_runtime::BridgeWestendGrandpaConfig {
+			owner: Some(endowed_accounts[0].clone()),
+			..Default::default()
+		},
 		bridge_rococo_messages: rococo_runtime::BridgeRococoMessagesConfig {
 			owner: Some(endowed_accounts[0].clone()),
 			..Default::default()
@@ -1082,6 +1114,14 @@ fn rococo_staging_testnet_config_genesis(wasm_binary: &[u8]) -> rococo_runtime::
 			owner: Some(endowed_accounts[0].clone()),
 			..Default::default()
 		},
+		bridge_westend_messages: rococo_runtime::BridgeWestendMessagesConfig {
+			owner: Some(endowed_accounts[0].clone()),
+			..Default::default()
+		},
+		bridge_config: rococo_runtime::BridgeConfigConfig {
+			lanes: vec![([0, 0, 0, 0], rococo_runtime::BRIDGE_CALL_FILTER_ALL)],
+			source_root_call_filter: None,
+		},
 	}
 }
 
@@ -1167,6 +1207,7 @@ pub fn rococo_staging_testnet_config() -> Result<RococoChainSpec, String> {
 		move || RococoGenesisExt {
 			runtime_genesis_config: rococo_staging_testnet_config_genesis(wasm_binary),
 			session_length_in_blocks: None,
+			slot_duration_in_millis: None,
 		},
 		boot_nodes,
 		Some(
@@ -1495,7 +1536,7 @@ pub fn westend_testnet_genesis(
 		im_online: Default::default(),
 		authority_discovery: westend::AuthorityDiscoveryConfig { keys: vec![] },
 		vesting: westend::VestingConfig { vesting: vec![] },
-		sudo: westend::SudoConfig { key: Some(root_key) },
+		sudo: westend::SudoConfig { key: Some(root_key.clone()) },
 		hrmp: Default::default(),
 		configuration: westend::ConfigurationConfig {
 			config: default_parachains_host_configuration(),
@@ -1505,6 +1546,14 @@ pub fn westend_testnet_genesis(
 			next_free_para_id: polkadot_primitives::v2::LOWEST_PUBLIC_ID,
 		},
 		xcm_pallet: Default::default(),
+		bridge_rococo_grandpa: westend::BridgeRococoGrandpaConfig {
+			owner: Some(root_key.clone()),
+			..Default::default()
+		},
+		bridge_rococo_messages: westend::BridgeRococoMessagesConfig {
+			owner: Some(root_key.clone()),
+			..Default::default()
+		},
 	}
 }
 
@@ -1588,6 +1637,10 @@ pub fn rococo_testnet_genesis(
 			owner: Some(root_key.clone()),
 			..Default::default()
 		},
+		bridge_westend_grandpa: rococo_runtime::BridgeWestendGrandpaConfig {
+			owner: Some(root_key.clone()),
+			..Default::default()
+		},
 		bridge_rococo_messages: rococo_runtime::BridgeRococoMessagesConfig {
 			owner: Some(root_key.clone()),
 			..Default::default()
@@ -1596,6 +1649,14 @@ pub fn rococo_testnet_genesis(
 			owner: Some(root_key.clone()),
 			..Default::default()
 		},
+		bridge_westend_messages: rococo_runtime::BridgeWestendMessagesConfig {
+			owner: Some(root_key.clone()),
+			..Default::default()
+		},
+		bridge_config: rococo_runtime::BridgeConfigConfig {
+			lanes: vec![([0, 0, 0, 0], rococo_runtime::BRIDGE_CALL_FILTER_ALL)],
+			source_root_call_filter: None,
+		},
 	}
 }
 
@@ -1709,6 +1770,7 @@ pub fn rococo_development_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: rococo_development_config_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -1732,6 +1794,7 @@ pub fn versi_development_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: rococo_development_config_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -1756,6 +1819,7 @@ pub fn wococo_development_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: rococo_development_config_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -1862,14 +1926,58 @@ pub fn westend_local_testnet_config() -> Result<WestendChainSpec, String> {
 	))
 }
 
+/// Seeds of the authorities of the Rococo local testnet.
+#[cfg(feature = "rococo-native")]
+const ROCOCO_LOCAL_TESTNET_AUTHORITIES: [&str; 2] = ["Alice", "Bob"];
+
+/// Seeds of the authorities of the Wococo local testnet.
+#[cfg(feature = "rococo-native")]
+const WOCOCO_LOCAL_TESTNET_AUTHORITIES: [&str; 4] = ["Alice", "Bob", "Charlie", "Dave"];
+
+/// Data that initializes the bridge GRANDPA pallet at genesis of the local testnet, that is
+/// bridged with the local testnet of given authorities.
+///
+/// The genesis header of the bridged testnet can't be used here, because its genesis includes the
+/// same data for this testnet. So the initial header is a stand-in with number of the genesis
+/// header. The pallet never checks parents of imported headers, so the first header, finalized by
+/// the genesis authority set of the bridged testnet, is accepted and replaces the stand-in.
+#[cfg(feature = "rococo-native")]
+fn local_testnet_bridge_init_data(
+	bridged_authorities: &[&str],
+) -> bp_header_chain::InitializationData<bp_rococo::Header> {
+	bp_header_chain::InitializationData {
+		header: Box::new(<bp_rococo::Header as sp_runtime::traits::Header>::new(
+			0,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)),
+		authority_list: bridged_authorities
+			.iter()
+			.map(|seed| (get_authority_keys_from_seed(seed).3, 1))
+			.collect(),
+		set_id: 0,
+		is_halted: false,
+	}
+}
+
+/// Rococo local testnet genesis, that is bridged with the Wococo local testnet from the start.
 #[cfg(feature = "rococo-native")]
 fn rococo_local_testnet_genesis(wasm_binary: &[u8]) -> rococo_runtime::GenesisConfig {
-	rococo_testnet_genesis(
+	let mut genesis = rococo_testnet_genesis(
 		wasm_binary,
-		vec![get_authority_keys_from_seed("Alice"), get_authority_keys_from_seed("Bob")],
+		ROCOCO_LOCAL_TESTNET_AUTHORITIES
+			.iter()
+			.copied()
+			.map(get_authority_keys_from_seed)
+			.collect(),
 		get_account_id_from_seed::<sr25519::Public>("Alice"),
 		None,
-	)
+	);
+	genesis.bridge_wococo_grandpa.init_data =
+		Some(local_testnet_bridge_init_data(&WOCOCO_LOCAL_TESTNET_AUTHORITIES));
+	genesis
 }
 
 /// Rococo local testnet config (multivalidator Alice + Bob)
@@ -1885,6 +1993,7 @@ pub fn rococo_local_testnet_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: rococo_local_testnet_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -1896,19 +2005,23 @@ pub fn rococo_local_testnet_config() -> Result<RococoChainSpec, String> {
 }
 
 /// Wococo is a temporary testnet that uses almost the same runtime as rococo.
+///
+/// The Wococo local testnet is bridged with the Rococo local testnet from the start.
 #[cfg(feature = "rococo-native")]
 fn wococo_local_testnet_genesis(wasm_binary: &[u8]) -> rococo_runtime::GenesisConfig {
-	rococo_testnet_genesis(
+	let mut genesis = rococo_testnet_genesis(
 		wasm_binary,
-		vec![
-			get_authority_keys_from_seed("Alice"),
-			get_authority_keys_from_seed("Bob"),
-			get_authority_keys_from_seed("Charlie"),
-			get_authority_keys_from_seed("Dave"),
-		],
+		WOCOCO_LOCAL_TESTNET_AUTHORITIES
+			.iter()
+			.copied()
+			.map(get_authority_keys_from_seed)
+			.collect(),
 		get_account_id_from_seed::<sr25519::Public>("Alice"),
 		None,
-	)
+	);
+	genesis.bridge_rococo_grandpa.init_data =
+		Some(local_testnet_bridge_init_data(&ROCOCO_LOCAL_TESTNET_AUTHORITIES));
+	genesis
 }
 
 /// `Versi` is a temporary testnet that uses the same runtime as rococo.
@@ -1940,6 +2053,7 @@ pub fn wococo_local_testnet_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: wococo_local_testnet_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -1963,6 +2077,7 @@ pub fn versi_local_testnet_config() -> Result<RococoChainSpec, String> {
 			runtime_genesis_config: versi_local_testnet_genesis(wasm_binary),
 			// Use 1 minute session length.
 			session_length_in_blocks: Some(10),
+			slot_duration_in_millis: None,
 		},
 		vec![],
 		None,
@@ -18,6 +18,7 @@
 
 #![deny(unused_results)]
 
+mod bridge_metrics;
 pub mod chain_spec;
 mod grandpa_support;
 mod parachains_db;
@@ -417,7 +418,11 @@ fn new_partial<RuntimeApi, ExecutorDispatch, ChainSelection>(
 				babe::BabeLink<Block>,
 				(BeefySignedCommitmentSender<Block>, BeefyBestBlockSender<Block>),
 			),
-			grandpa::SharedVoterState,
+			(
+				grandpa::SharedVoterState,
+				polkadot_rpc::AuthoringSwitch,
+				polkadot_rpc::MessageIntrospection,
+			),
 			sp_consensus_babe::SlotDuration,
 			Option<Telemetry>,
 		),
@@ -503,8 +508,13 @@ where
 	let shared_epoch_changes = babe_link.epoch_changes().clone();
 	let slot_duration = babe_config.slot_duration();
 
+	let authoring_switch = polkadot_rpc::AuthoringSwitch::default();
+	let message_introspection = polkadot_rpc::MessageIntrospection::default();
+	let submission_nonces = polkadot_rpc::SubmissionNonces::default();
+
 	let import_setup = (block_import, grandpa_link, babe_link, beefy_links);
-	let rpc_setup = shared_voter_state.clone();
+	let rpc_setup =
+		(shared_voter_state.clone(), authoring_switch.clone(), message_introspection.clone());
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -513,6 +523,7 @@ where
 		let select_chain = select_chain.clone();
 		let chain_spec = config.chain_spec.cloned_box();
 		let backend = backend.clone();
+		let wasm_method = config.wasm_method;
 
 		move |deny_unsafe,
 		      subscription_executor: polkadot_rpc::SubscriptionTaskExecutor|
@@ -540,6 +551,10 @@ where
 					beefy_best_block_stream: beefy_best_block_stream.clone(),
 					subscription_executor,
 				},
+				authoring_switch: authoring_switch.clone(),
+				message_introspection: message_introspection.clone(),
+				wasm_method,
+				submission_nonces: submission_nonces.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -612,6 +627,26 @@ impl IsCollator {
 	}
 }
 
+/// Authoring check that is additionally gated by the operator [`polkadot_rpc::AuthoringSwitch`].
+#[cfg(feature = "full-node")]
+struct SwitchableCanAuthorWith<C> {
+	inner: C,
+	switch: polkadot_rpc::AuthoringSwitch,
+}
+
+#[cfg(feature = "full-node")]
+impl<C: consensus_common::CanAuthorWith<Block>> consensus_common::CanAuthorWith<Block>
+	for SwitchableCanAuthorWith<C>
+{
+	fn can_author_with(&self, at: &BlockId) -> Result<(), String> {
+		if self.switch.is_paused() {
+			return Err("Block authoring has been paused by the node operator".into())
+		}
+
+		self.inner.can_author_with(at)
+	}
+}
+
 /// Returns the active leaves the overseer should start with.
 #[cfg(feature = "full-node")]
 async fn active_leaves<RuntimeApi, ExecutorDispatch>(
@@ -733,6 +768,8 @@ where
 	let requires_overseer_for_chain_sel = local_keystore.is_some() && auth_or_collator;
 
 	let pvf_checker_enabled = !is_collator.is_collator() && chain_spec.is_versi();
+	let chunk_pushing_enabled = role.is_authority() && chain_spec.is_versi();
+	let collation_range_fetching_enabled = chain_spec.is_versi();
 
 	let select_chain = if requires_overseer_for_chain_sel {
 		let metrics =
@@ -762,7 +799,7 @@ where
 		select_chain,
 	)?;
 
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, authoring_switch, message_introspection) = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 
 	// Note: GrandPa is pushed before the Polkadot-specific protocols. This doesn't change
@@ -798,8 +835,12 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (chunk_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
+	let (chunk_push_receiver, cfg) = IncomingRequest::get_config_receiver();
+	config.network.request_response_protocols.push(cfg);
 	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
+	let (collation_range_req_receiver, cfg) = IncomingRequest::get_config_receiver();
+	config.network.request_response_protocols.push(cfg);
 	let (available_data_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
 	let (statement_req_receiver, cfg) = IncomingRequest::get_config_receiver();
@@ -978,7 +1019,7 @@ where
 	let overseer_handle = if let Some((authority_discovery_service, keystore)) = maybe_params {
 		let (overseer, overseer_handle) = overseer_gen
 			.generate::<service::SpawnTaskHandle, FullClient<RuntimeApi, ExecutorDispatch>>(
-				overseer_connector,
+				overseer_connector.with_introspection(message_introspection),
 				OverseerGenArgs {
 					leaves: active_leaves,
 					keystore,
@@ -988,7 +1029,9 @@ where
 					authority_discovery_service,
 					pov_req_receiver,
 					chunk_req_receiver,
+					chunk_push_receiver,
 					collation_req_receiver,
+					collation_range_req_receiver,
 					available_data_req_receiver,
 					statement_req_receiver,
 					dispute_req_receiver,
@@ -1001,6 +1044,8 @@ where
 					chain_selection_config,
 					dispute_coordinator_config,
 					pvf_checker_enabled,
+					chunk_pushing_enabled,
+					collation_range_fetching_enabled,
 				},
 			)
 			.map_err(|e| {
@@ -1043,8 +1088,10 @@ where
 	};
 
 	if role.is_authority() {
-		let can_author_with =
-			consensus_common::CanAuthorWithNativeVersion::new(client.executor().clone());
+		let can_author_with = SwitchableCanAuthorWith {
+			inner: consensus_common::CanAuthorWithNativeVersion::new(client.executor().clone()),
+			switch: authoring_switch,
+		};
 
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -1295,7 +1342,9 @@ pub fn build_full(
 		config.chain_spec.is_wococo() ||
 		config.chain_spec.is_versi()
 	{
-		return new_full::<rococo_runtime::RuntimeApi, RococoExecutorDispatch, _>(
+		let is_wococo = config.chain_spec.is_wococo();
+		let prometheus_registry = config.prometheus_registry().cloned();
+		let full = new_full::<rococo_runtime::RuntimeApi, RococoExecutorDispatch, _>(
 			config,
 			is_collator,
 			grandpa_pause,
@@ -1305,8 +1354,19 @@ pub fn build_full(
 			None,
 			overseer_enable_anyways,
 			overseer_gen,
-		)
-		.map(|full| full.with_client(Client::Rococo))
+		)?;
+
+		// The bridge state is only read when there is a registry to report it to.
+		if let Some(ref registry) = prometheus_registry {
+			let metrics = polkadot_node_subsystem_util::metrics::Metrics::register(Some(registry))?;
+			full.task_manager.spawn_handle().spawn(
+				"bridge-metrics",
+				None,
+				bridge_metrics::run(full.client.clone(), is_wococo, metrics),
+			);
+		}
+
+		return Ok(full.with_client(Client::Rococo))
 	}
 
 	#[cfg(feature = "kusama-native")]
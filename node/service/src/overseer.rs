@@ -88,7 +88,10 @@ where
 	/// POV request receiver
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
+	pub chunk_push_receiver: IncomingRequestReceiver<request_v1::ChunkPushingRequest>,
 	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	pub collation_range_req_receiver:
+		IncomingRequestReceiver<request_v1::CollationRangeFetchingRequest>,
 	pub available_data_req_receiver:
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	pub statement_req_receiver: IncomingRequestReceiver<request_v1::StatementFetchingRequest>,
@@ -111,6 +114,10 @@ where
 	pub dispute_coordinator_config: DisputeCoordinatorConfig,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// Enable pushing of erasure chunks by backers
+	pub chunk_pushing_enabled: bool,
+	/// Enable fetching of collations in ranges
+	pub collation_range_fetching_enabled: bool,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -125,7 +132,9 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		authority_discovery_service,
 		pov_req_receiver,
 		chunk_req_receiver,
+		chunk_push_receiver,
 		collation_req_receiver,
+		collation_range_req_receiver,
 		available_data_req_receiver,
 		statement_req_receiver,
 		dispute_req_receiver,
@@ -138,6 +147,8 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		chain_selection_config,
 		dispute_coordinator_config,
 		pvf_checker_enabled,
+		chunk_pushing_enabled,
+		collation_range_fetching_enabled,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -180,11 +191,18 @@ where
 	let metrics = <OverseerMetrics as MetricsTrait>::register(registry)?;
 
 	let builder = Overseer::builder()
-		.availability_distribution(AvailabilityDistributionSubsystem::new(
-			keystore.clone(),
-			IncomingRequestReceivers { pov_req_receiver, chunk_req_receiver },
-			Metrics::register(registry)?,
-		))
+		.availability_distribution(
+			AvailabilityDistributionSubsystem::new(
+				keystore.clone(),
+				IncomingRequestReceivers {
+					pov_req_receiver,
+					chunk_req_receiver,
+					chunk_push_receiver,
+				},
+				Metrics::register(registry)?,
+			)
+			.with_chunk_pushing(chunk_pushing_enabled),
+		)
 		.availability_recovery(AvailabilityRecoverySubsystem::with_chunks_only(
 			available_data_req_receiver,
 			Metrics::register(registry)?,
@@ -223,12 +241,14 @@ where
 					network_service.local_peer_id().clone(),
 					collator_pair,
 					collation_req_receiver,
+					collation_range_req_receiver,
 					Metrics::register(registry)?,
 				),
 				IsCollator::No => ProtocolSide::Validator {
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
 					metrics: Metrics::register(registry)?,
+					fetch_in_ranges: collation_range_fetching_enabled,
 				},
 			};
 			CollatorProtocolSubsystem::new(side)
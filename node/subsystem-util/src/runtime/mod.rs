@@ -25,16 +25,17 @@ use sp_application_crypto::AppKey;
 use sp_core::crypto::ByteArray;
 use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
-use polkadot_node_subsystem::{SubsystemContext, SubsystemSender};
+use polkadot_node_subsystem::{errors::RuntimeApiError, SubsystemContext, SubsystemSender};
 use polkadot_primitives::v2::{
-	CandidateEvent, CoreState, EncodeAs, GroupIndex, GroupRotationInfo, Hash, OccupiedCore,
-	SessionIndex, SessionInfo, Signed, SigningContext, UncheckedSigned, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex,
+	CandidateEvent, CoreState, EncodeAs, FeatureBits, GroupIndex, GroupRotationInfo, Hash,
+	OccupiedCore, SessionIndex, SessionInfo, Signed, SigningContext, UncheckedSigned,
+	ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
 };
 
 use crate::{
-	request_availability_cores, request_candidate_events, request_session_index_for_child,
-	request_session_info, request_validation_code_by_hash, request_validator_groups,
+	request_availability_cores, request_candidate_events, request_feature_bits,
+	request_session_index_for_child, request_session_info, request_validation_code_by_hash,
+	request_validator_groups,
 };
 
 /// Errors that can happen on runtime fetches.
@@ -326,3 +327,20 @@ where
 	recv_runtime(request_validation_code_by_hash(relay_parent, validation_code_hash, sender).await)
 		.await
 }
+
+/// Get the features activated on the network as of the given `relay_parent`.
+///
+/// Runtimes that predate feature bits don't activate any feature.
+pub async fn get_feature_bits<Sender>(
+	sender: &mut Sender,
+	relay_parent: Hash,
+) -> Result<FeatureBits>
+where
+	Sender: SubsystemSender,
+{
+	match recv_runtime(request_feature_bits(relay_parent, sender).await).await {
+		Err(Error::RuntimeRequest(RuntimeApiError::NotSupported { .. })) =>
+			Ok(FeatureBits::default()),
+		result => result,
+	}
+}
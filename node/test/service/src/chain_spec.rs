@@ -25,6 +25,7 @@ use polkadot_test_runtime::BABE_GENESIS_EPOCH_CONFIG;
 use sc_chain_spec::{ChainSpec, ChainType};
 use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_core::sr25519;
+use sp_keyring::Sr25519Keyring;
 use sp_runtime::Perbill;
 use test_runtime_constants::currency::DOTS;
 
@@ -59,6 +60,35 @@ pub fn polkadot_local_testnet_genesis() -> polkadot_test_runtime::GenesisConfig
 	)
 }
 
+/// Testnet config with the given validators and Alice as root.
+///
+/// Networks using different `id`s are kept apart by their protocol id, even if their nodes end
+/// up connected to each other.
+pub fn polkadot_testnet_config(id: &str, validators: &[Sr25519Keyring]) -> PolkadotChainSpec {
+	let validators = validators.to_vec();
+	PolkadotChainSpec::from_genesis(
+		id,
+		id,
+		ChainType::Local,
+		move || {
+			polkadot_testnet_genesis(
+				validators
+					.iter()
+					.map(|validator| get_authority_keys_from_seed(&format!("{:?}", validator)))
+					.collect(),
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				None,
+			)
+		},
+		vec![],
+		None,
+		Some(id),
+		None,
+		None,
+		Default::default(),
+	)
+}
+
 /// Helper function to generate stash, controller and session key from seed
 fn get_authority_keys_from_seed(
 	seed: &str,
@@ -141,7 +171,7 @@ fn polkadot_testnet_genesis(
 		},
 		staking: runtime::StakingConfig {
 			minimum_validator_count: 1,
-			validator_count: 2,
+			validator_count: initial_authorities.len() as u32,
 			stakers: initial_authorities
 				.iter()
 				.map(|x| (x.0.clone(), x.1.clone(), STASH, runtime::StakerStatus::Validator))
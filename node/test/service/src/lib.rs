@@ -19,24 +19,28 @@
 #![warn(missing_docs)]
 
 pub mod chain_spec;
+pub mod network;
 
 pub use chain_spec::*;
-use futures::future::Future;
+use frame_system::EventRecord;
+use futures::{future::Future, StreamExt};
 use polkadot_node_primitives::{CollationGenerationConfig, CollatorFn};
 use polkadot_node_subsystem::messages::{CollationGenerationMessage, CollatorProtocolMessage};
 use polkadot_overseer::Handle;
-use polkadot_primitives::v2::{Balance, CollatorPair, HeadData, Id as ParaId, ValidationCode};
+use polkadot_primitives::v2::{
+	Balance, CollatorPair, Hash, HeadData, Id as ParaId, ValidationCode,
+};
 use polkadot_runtime_common::BlockHashCount;
 use polkadot_runtime_parachains::paras::ParaGenesisArgs;
 use polkadot_service::{
 	ClientHandle, Error, ExecuteWithClient, FullClient, IsCollator, NewFull, PrometheusConfig,
 };
 use polkadot_test_runtime::{
-	ParasSudoWrapperCall, Runtime, SignedExtra, SignedPayload, SudoCall, UncheckedExtrinsic,
+	Event, ParasSudoWrapperCall, Runtime, SignedExtra, SignedPayload, SudoCall, UncheckedExtrinsic,
 	VERSION,
 };
 use sc_chain_spec::ChainSpec;
-use sc_client_api::execution_extensions::ExecutionStrategies;
+use sc_client_api::{execution_extensions::ExecutionStrategies, BlockchainEvents, StorageProvider};
 use sc_network::{
 	config::{NetworkConfiguration, TransportConfig},
 	multiaddr,
@@ -47,8 +51,14 @@ use sc_service::{
 };
 use sp_arithmetic::traits::SaturatedConversion;
 use sp_blockchain::HeaderBackend;
+use sp_core::{storage::StorageKey, twox_128};
 use sp_keyring::Sr25519Keyring;
-use sp_runtime::{codec::Encode, generic, traits::IdentifyAccount, MultiSigner};
+use sp_runtime::{
+	codec::{Decode, Encode},
+	generic::{self, BlockId},
+	traits::IdentifyAccount,
+	MultiSigner,
+};
 use sp_state_machine::BasicExternalities;
 use std::{
 	net::{Ipv4Addr, SocketAddr},
@@ -130,12 +140,32 @@ pub fn node_config(
 	key: Sr25519Keyring,
 	boot_nodes: Vec<MultiaddrWithPeerId>,
 	is_validator: bool,
+) -> Configuration {
+	node_config_with_chain_spec(
+		polkadot_local_testnet_config(),
+		storage_update_func,
+		tokio_handle,
+		key,
+		boot_nodes,
+		is_validator,
+	)
+}
+
+/// Create a Polkadot `Configuration` for the given chain spec.
+///
+/// See [`node_config`] for details.
+pub fn node_config_with_chain_spec(
+	mut spec: PolkadotChainSpec,
+	storage_update_func: impl Fn(),
+	tokio_handle: tokio::runtime::Handle,
+	key: Sr25519Keyring,
+	boot_nodes: Vec<MultiaddrWithPeerId>,
+	is_validator: bool,
 ) -> Configuration {
 	let base_path = BasePath::new_temp_dir().expect("could not create temporary directory");
 	let root = base_path.path();
 	let role = if is_validator { Role::Authority } else { Role::Full };
 	let key_seed = key.to_seed();
-	let mut spec = polkadot_local_testnet_config();
 	let mut storage = spec.as_storage_builder().build_storage().expect("could not build storage");
 
 	BasicExternalities::execute_with_storage(&mut storage, storage_update_func);
@@ -246,6 +276,16 @@ pub fn run_collator_node(
 	collator_pair: CollatorPair,
 ) -> PolkadotTestNode {
 	let config = node_config(storage_update_func, tokio_handle, key, boot_nodes, false);
+	run_collator_node_with_config(config, collator_pair)
+}
+
+/// Run a test collator node that uses the test runtime and specified `config`.
+///
+/// See [`run_collator_node`] for details.
+pub fn run_collator_node_with_config(
+	config: Configuration,
+	collator_pair: CollatorPair,
+) -> PolkadotTestNode {
 	let multiaddr = config.network.listen_addresses[0].clone();
 	let NewFull { task_manager, client, network, rpc_handlers, overseer_handle, .. } =
 		new_full(config, IsCollator::Yes(collator_pair), None)
@@ -279,30 +319,62 @@ impl PolkadotTestNode {
 		function: impl Into<polkadot_test_runtime::Call>,
 		caller: Sr25519Keyring,
 	) -> Result<RpcTransactionOutput, RpcTransactionError> {
-		let extrinsic = construct_extrinsic(&*self.client, function, caller, 0);
+		self.send_extrinsic_with_nonce(function, caller, 0).await
+	}
+
+	/// Send an extrinsic with the given account `nonce` to this node.
+	pub async fn send_extrinsic_with_nonce(
+		&self,
+		function: impl Into<polkadot_test_runtime::Call>,
+		caller: Sr25519Keyring,
+		nonce: u32,
+	) -> Result<RpcTransactionOutput, RpcTransactionError> {
+		let extrinsic = construct_extrinsic(&*self.client, function, caller, nonce);
 
 		self.rpc_handlers.send_transaction(extrinsic.into()).await
 	}
 
-	/// Register a parachain at this relay chain.
-	pub async fn register_parachain(
+	/// Returns the nonce of the `account` at the best block of this node.
+	pub fn account_nonce(&self, account: Sr25519Keyring) -> u32 {
+		let account_id = MultiSigner::from(account.public()).into_account();
+		let key = frame_system::Account::<Runtime>::hashed_key_for(&account_id);
+		read_storage::<frame_system::AccountInfo<u32, pallet_balances::AccountData<Balance>>>(
+			&self.client,
+			self.client.info().best_hash,
+			key,
+		)
+		.map(|info| info.nonce)
+		.unwrap_or_default()
+	}
+
+	/// Returns the events deposited in the block with the given hash.
+	pub fn block_events(&self, hash: Hash) -> Vec<Event> {
+		block_events(&self.client, hash)
+	}
+
+	/// Wait for a block with an event matching `filter` to be imported in the node and return the
+	/// event and the hash of the block. This function will not return if no such event is ever
+	/// deposited, thus you should restrict the maximum amount of time of the test execution.
+	///
+	/// Only blocks imported after this function has been called are considered, so it can be
+	/// called before sending the extrinsic that deposits the event.
+	pub fn wait_for_event(
 		&self,
-		id: ParaId,
-		validation_code: impl Into<ValidationCode>,
-		genesis_head: impl Into<HeadData>,
-	) -> Result<(), RpcTransactionError> {
-		let call = ParasSudoWrapperCall::sudo_schedule_para_initialize {
-			id,
-			genesis: ParaGenesisArgs {
-				genesis_head: genesis_head.into(),
-				validation_code: validation_code.into(),
-				parachain: true,
-			},
-		};
-
-		self.send_extrinsic(SudoCall::sudo { call: Box::new(call.into()) }, Sr25519Keyring::Alice)
-			.await
-			.map(drop)
+		mut filter: impl FnMut(&Event) -> bool,
+	) -> impl Future<Output = (Hash, Event)> {
+		let client = self.client.clone();
+		let mut import_notifications = client.import_notification_stream();
+		async move {
+			while let Some(notification) = import_notifications.next().await {
+				if let Some(event) =
+					block_events(&client, notification.hash).into_iter().find(|event| filter(event))
+				{
+					return (notification.hash, event)
+				}
+			}
+
+			panic!("import notification stream of the node has ended");
+		}
 	}
 
 	/// Wait for `count` blocks to be imported in the node and then exit. This function will not return if no blocks
@@ -330,6 +402,22 @@ impl PolkadotTestNode {
 	}
 }
 
+fn block_events(client: &Client, hash: Hash) -> Vec<Event> {
+	let key = [twox_128(b"System"), twox_128(b"Events")].concat();
+	read_storage::<Vec<EventRecord<Event, Hash>>>(client, hash, key)
+		.unwrap_or_default()
+		.into_iter()
+		.map(|record| record.event)
+		.collect()
+}
+
+fn read_storage<T: Decode>(client: &Client, at: Hash, key: Vec<u8>) -> Option<T> {
+	client
+		.storage(&BlockId::Hash(at), &StorageKey(key))
+		.expect("storage of imported blocks is available")
+		.and_then(|data| T::decode(&mut &data.0[..]).ok())
+}
+
 /// Construct an extrinsic that can be applied to the test runtime.
 pub fn construct_extrinsic(
 	client: &Client,
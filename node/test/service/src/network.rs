@@ -0,0 +1,299 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test networks made of several validators and collators.
+//!
+//! A [`TestNetworkBuilder`] spawns all nodes of a relay network in-process, registers the
+//! parachains of its collators and optionally spawns a second relay network next to it, e.g. to
+//! test a message bridge between the two. The nodes, their keys and the genesis of the networks
+//! only depend on the builder, so the same builder always produces the same networks.
+
+use crate::{
+	node_config_with_chain_spec, polkadot_testnet_config, run_collator_node_with_config,
+	run_validator_node, PolkadotTestNode,
+};
+use futures::{future, Future};
+use polkadot_node_primitives::CollatorFn;
+use polkadot_primitives::v2::{CollatorPair, Hash, HeadData, Id as ParaId, ValidationCode};
+use polkadot_runtime_parachains::paras::ParaGenesisArgs;
+use polkadot_test_runtime::{Event, ParasSudoWrapperCall, SudoCall};
+use sc_service::SpawnTaskHandle;
+use sp_keyring::Sr25519Keyring;
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+use substrate_test_client::{RpcTransactionError, RpcTransactionOutput};
+
+/// Identifier of the network spawned by default.
+const DEFAULT_NETWORK_ID: &str = "test_network";
+
+/// Creates the collation function of a collator, given the spawn handle of its node.
+pub type CollatorFnFactory = Box<dyn FnOnce(SpawnTaskHandle) -> CollatorFn + Send>;
+
+/// A collator of a test network.
+pub struct TestCollator {
+	/// Key of the collator node.
+	pub key: Sr25519Keyring,
+	/// Collator key the collations are signed with.
+	pub collator_pair: CollatorPair,
+	/// Parachain the collator is collating for.
+	pub para_id: ParaId,
+	/// Validation code the parachain is registered with.
+	pub validation_code: ValidationCode,
+	/// Genesis head the parachain is registered with.
+	pub genesis_head: HeadData,
+	/// Creates the collation function of the collator.
+	pub collator: CollatorFnFactory,
+}
+
+/// Builder of a [`TestNetwork`].
+pub struct TestNetworkBuilder {
+	id: String,
+	validators: Vec<Sr25519Keyring>,
+	collators: Vec<TestCollator>,
+	storage_update_func: Box<dyn Fn() + Send + Sync>,
+	worker_program_path: Option<PathBuf>,
+	bridged: Option<Box<TestNetworkBuilder>>,
+}
+
+impl Default for TestNetworkBuilder {
+	fn default() -> Self {
+		TestNetworkBuilder {
+			id: DEFAULT_NETWORK_ID.into(),
+			validators: vec![Sr25519Keyring::Alice, Sr25519Keyring::Bob],
+			collators: Vec::new(),
+			storage_update_func: Box::new(|| {}),
+			worker_program_path: None,
+			bridged: None,
+		}
+	}
+}
+
+impl TestNetworkBuilder {
+	/// Create a builder of a network with Alice and Bob as validators.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Set the identifier of the network. Networks with different identifiers never talk to each
+	/// other.
+	pub fn with_id(mut self, id: impl Into<String>) -> Self {
+		self.id = id.into();
+		self
+	}
+
+	/// Set the validators of the network.
+	pub fn with_validators(mut self, validators: impl IntoIterator<Item = Sr25519Keyring>) -> Self {
+		self.validators = validators.into_iter().collect();
+		self
+	}
+
+	/// Add a collator to the network. Its parachain is registered once the network is spawned.
+	pub fn with_collator(mut self, collator: TestCollator) -> Self {
+		self.collators.push(collator);
+		self
+	}
+
+	/// Set the function adjusting the genesis storage of the network.
+	///
+	/// The function is executed in an externalities provided environment.
+	pub fn with_storage_update_func(
+		mut self,
+		storage_update_func: impl Fn() + Send + Sync + 'static,
+	) -> Self {
+		self.storage_update_func = Box::new(storage_update_func);
+		self
+	}
+
+	/// Set the path of the PVF worker program used by the validators.
+	pub fn with_worker_program_path(mut self, worker_program_path: impl Into<PathBuf>) -> Self {
+		self.worker_program_path = Some(worker_program_path.into());
+		self
+	}
+
+	/// Spawn a second network, built by `bridged`, along with this one.
+	///
+	/// The networks don't share any nodes, so the second network needs to have a different id.
+	/// The second network can't have a bridged network of its own.
+	pub fn with_bridged_network(mut self, bridged: TestNetworkBuilder) -> Self {
+		assert_ne!(self.id, bridged.id, "bridged network must have a different id");
+		assert!(bridged.bridged.is_none(), "bridged network can't have a bridged network");
+		self.bridged = Some(Box::new(bridged));
+		self
+	}
+
+	/// Spawn all nodes of the network and register the parachains of its collators.
+	///
+	/// The first validator is the boot node of all other nodes of the network.
+	pub async fn build(mut self, tokio_handle: tokio::runtime::Handle) -> TestNetwork {
+		let bridged = self.bridged.take();
+		let mut network = self.spawn(tokio_handle.clone()).await;
+		if let Some(bridged) = bridged {
+			network.bridged = Some(Box::new(bridged.spawn(tokio_handle).await));
+		}
+
+		network
+	}
+
+	async fn spawn(self, tokio_handle: tokio::runtime::Handle) -> TestNetwork {
+		assert!(!self.validators.is_empty(), "test network needs at least one validator");
+
+		let TestNetworkBuilder {
+			id,
+			validators: validator_keys,
+			collators: test_collators,
+			storage_update_func,
+			worker_program_path,
+			..
+		} = self;
+
+		let mut boot_nodes = Vec::new();
+		let mut validators = Vec::with_capacity(validator_keys.len());
+		for key in &validator_keys {
+			let config = node_config_with_chain_spec(
+				polkadot_testnet_config(&id, &validator_keys),
+				&*storage_update_func,
+				tokio_handle.clone(),
+				*key,
+				boot_nodes.clone(),
+				true,
+			);
+			let node = run_validator_node(config, worker_program_path.clone());
+			if boot_nodes.is_empty() {
+				boot_nodes.push(node.addr.clone());
+			}
+			validators.push(node);
+		}
+
+		let mut network = TestNetwork {
+			id,
+			validators,
+			collators: Vec::with_capacity(test_collators.len()),
+			bridged: None,
+			nonces: Default::default(),
+		};
+
+		for collator in test_collators {
+			network
+				.register_parachain(
+					collator.para_id,
+					collator.validation_code,
+					collator.genesis_head,
+				)
+				.await
+				.expect("could not register parachain of the test collator");
+
+			let config = node_config_with_chain_spec(
+				polkadot_testnet_config(&network.id, &validator_keys),
+				&*storage_update_func,
+				tokio_handle.clone(),
+				collator.key,
+				boot_nodes.clone(),
+				false,
+			);
+			let mut node = run_collator_node_with_config(config, collator.collator_pair.clone());
+			let collator_fn = (collator.collator)(node.task_manager.spawn_handle());
+			node.register_collator(collator.collator_pair, collator.para_id, collator_fn)
+				.await;
+			network.collators.push(node);
+		}
+
+		network
+	}
+}
+
+/// A running test network.
+pub struct TestNetwork {
+	/// Identifier of the network.
+	pub id: String,
+	/// Validator nodes, in the order they have been given to the builder.
+	pub validators: Vec<PolkadotTestNode>,
+	/// Collator nodes, in the order they have been given to the builder.
+	pub collators: Vec<PolkadotTestNode>,
+	/// The second network, if one has been spawned.
+	pub bridged: Option<Box<TestNetwork>>,
+	nonces: Mutex<HashMap<Sr25519Keyring, u32>>,
+}
+
+impl TestNetwork {
+	/// The first validator of the network, which all other nodes are connected to.
+	pub fn node(&self) -> &PolkadotTestNode {
+		&self.validators[0]
+	}
+
+	/// The second network.
+	///
+	/// # Panics
+	///
+	/// If no second network has been spawned.
+	pub fn bridged(&self) -> &TestNetwork {
+		self.bridged.as_deref().expect("no bridged network has been spawned")
+	}
+
+	/// Send an extrinsic to the first validator of the network.
+	///
+	/// Nonces are tracked by the network, so several extrinsics of the same `caller` can be sent
+	/// without waiting for the previous ones to be included.
+	pub async fn send_extrinsic(
+		&self,
+		function: impl Into<polkadot_test_runtime::Call>,
+		caller: Sr25519Keyring,
+	) -> Result<RpcTransactionOutput, RpcTransactionError> {
+		let nonce = {
+			let mut nonces = self.nonces.lock().expect("nonces lock is never poisoned");
+			let nonce = nonces.entry(caller).or_insert_with(|| self.node().account_nonce(caller));
+			*nonce += 1;
+			*nonce - 1
+		};
+
+		self.node().send_extrinsic_with_nonce(function, caller, nonce).await
+	}
+
+	/// Register a parachain at the network.
+	pub async fn register_parachain(
+		&self,
+		id: ParaId,
+		validation_code: impl Into<ValidationCode>,
+		genesis_head: impl Into<HeadData>,
+	) -> Result<(), RpcTransactionError> {
+		let call = ParasSudoWrapperCall::sudo_schedule_para_initialize {
+			id,
+			genesis: ParaGenesisArgs {
+				genesis_head: genesis_head.into(),
+				validation_code: validation_code.into(),
+				parachain: true,
+			},
+		};
+
+		self.send_extrinsic(SudoCall::sudo { call: Box::new(call.into()) }, Sr25519Keyring::Alice)
+			.await
+			.map(drop)
+	}
+
+	/// Wait for `count` blocks to be imported by all validators of the network.
+	pub async fn wait_for_blocks(&self, count: usize) {
+		future::join_all(self.validators.iter().map(|node| node.wait_for_blocks(count))).await;
+	}
+
+	/// Wait for an event matching `filter` to be deposited in a block imported by the first
+	/// validator of the network.
+	///
+	/// See [`PolkadotTestNode::wait_for_event`] for details.
+	pub fn wait_for_event(
+		&self,
+		filter: impl FnMut(&Event) -> bool,
+	) -> impl Future<Output = (Hash, Event)> {
+		self.node().wait_for_event(filter)
+	}
+}
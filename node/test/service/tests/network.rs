@@ -0,0 +1,59 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use futures::future;
+use polkadot_test_runtime::{Call, Event};
+use polkadot_test_service::network::TestNetworkBuilder;
+use sp_keyring::Sr25519Keyring::*;
+use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSigner};
+
+fn is_transfer_to(dest: &AccountId32, value: u128) -> impl FnMut(&Event) -> bool + '_ {
+	move |event| {
+		matches!(
+			event,
+			Event::Balances(pallet_balances::Event::Transfer { to, amount, .. })
+				if to == dest && *amount == value
+		)
+	}
+}
+
+#[substrate_test_utils::test]
+async fn test_networks_build_blocks_and_include_transactions() {
+	let mut builder = sc_cli::LoggerBuilder::new("");
+	builder.with_colors(false);
+	builder.init().expect("Sets up logger");
+
+	let network = TestNetworkBuilder::new()
+		.with_validators([Alice, Bob, Charlie])
+		.with_bridged_network(TestNetworkBuilder::new().with_id("bridged_test_network"))
+		.build(tokio::runtime::Handle::current())
+		.await;
+
+	future::join(network.wait_for_blocks(3), network.bridged().wait_for_blocks(3)).await;
+
+	let dave = MultiSigner::from(Dave.public()).into_account();
+	let transfers = future::join(
+		network.wait_for_event(is_transfer_to(&dave, 1_000)),
+		network.wait_for_event(is_transfer_to(&dave, 2_000)),
+	);
+
+	// nonces are tracked by the network, so both transfers are included
+	for value in [1_000, 2_000] {
+		let transfer = pallet_balances::Call::transfer { dest: dave.clone().into(), value };
+		network.send_extrinsic(Call::Balances(transfer), Alice).await.unwrap();
+	}
+	transfers.await;
+}
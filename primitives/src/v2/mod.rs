@@ -1651,9 +1651,46 @@ impl PvfCheckStatement {
 	}
 }
 
+/// Features activated on the network, one bit per feature index.
+///
+/// Features are activated by governance at a chosen block, which lets the runtime and the node
+/// switch to a new behaviour at the same point without a coordinated node release. Indices are
+/// assigned to features as they are introduced and are never reused.
+#[derive(Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct FeatureBits(pub BitVec<u8, bitvec::order::Lsb0>);
+
+#[cfg(feature = "std")]
+impl MallocSizeOf for FeatureBits {
+	fn size_of(&self, _ops: &mut MallocSizeOfOps) -> usize {
+		self.0.as_raw_slice().len()
+	}
+}
+
+impl FeatureBits {
+	/// Returns true if the feature with the given index is activated.
+	pub fn is_enabled(&self, index: FeatureIndex) -> bool {
+		self.0.get(index as usize).map_or(false, |bit| *bit)
+	}
+
+	/// Activate or deactivate the feature with the given index.
+	pub fn set(&mut self, index: FeatureIndex, enabled: bool) {
+		let index = index as usize;
+		if index >= self.0.len() {
+			if !enabled {
+				return
+			}
+			self.0.resize(index + 1, false);
+		}
+		self.0.set(index, enabled);
+	}
+}
+
+/// Index of a feature in the [`FeatureBits`].
+pub type FeatureIndex = u8;
+
 sp_api::decl_runtime_apis! {
 	/// The API for querying the state of parachains on-chain.
-	#[api_version(2)]
+	#[api_version(3)]
 	pub trait ParachainHost<H: Encode + Decode = Hash, N: Encode + Decode = BlockNumber> {
 		/// Get the current validators.
 		fn validators() -> Vec<ValidatorId>;
@@ -1743,6 +1780,11 @@ sp_api::decl_runtime_apis! {
 		fn validation_code_hash(para_id: Id, assumption: OccupiedCoreAssumption)
 			-> Option<ValidationCodeHash>;
 
+		/// Returns the features activated on the network, as of the state this is invoked on.
+		///
+		/// NOTE: This function is only available since parachain host version 3.
+		fn feature_bits() -> FeatureBits;
+
 
 		/***** Replaced in v2 *****/
 
@@ -1750,6 +1792,15 @@ sp_api::decl_runtime_apis! {
 		#[changed_in(2)]
 		fn session_info(index: SessionIndex) -> Option<OldV1SessionInfo>;
 	}
+
+	/// The API for locating storage items that external auditors want proven.
+	pub trait AuditApi {
+		/// Returns the storage keys of all audited items of the given staking era, as present in
+		/// the state this is invoked on.
+		///
+		/// Runtimes without staking ignore the era and return their era-independent items only.
+		fn era_audit_keys(era: u32) -> Vec<Vec<u8>>;
+	}
 }
 
 /// Old, v1-style info about session info. Only needed for limited
@@ -1902,4 +1953,20 @@ mod tests {
 
 		assert!(zero_b.leading_zeros() >= zero_u.leading_zeros());
 	}
+
+	#[test]
+	fn feature_bits_grow_only_when_enabling() {
+		let mut bits = FeatureBits::default();
+		bits.set(7, false);
+		assert_eq!(bits.0.len(), 0);
+
+		bits.set(7, true);
+		assert!(bits.is_enabled(7));
+		assert!(!bits.is_enabled(6));
+		assert!(!bits.is_enabled(200));
+		assert_eq!(bits.0.len(), 8);
+
+		bits.set(7, false);
+		assert!(!bits.is_enabled(7));
+	}
 }
@@ -0,0 +1,135 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Audit RPC.
+//!
+//! Generates storage proofs of the items the runtime marks as audited (staking exposures,
+//! bridge lane states, bridge configuration) at finalized blocks. Third parties can verify
+//! the proven values against the state root of a finalized header they obtained themselves,
+//! without running an archive node.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use polkadot_primitives::v2::{AuditApi, Block, BlockNumber, Hash};
+use sc_client_api::ProofProvider;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::generic::BlockId;
+
+/// Base code for all audit errors.
+const BASE_ERROR: i64 = 9100;
+
+/// Storage proof of the audited items of an era.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EraStorageProof {
+	/// Hash of the finalized block the proof has been generated at.
+	pub at: Hash,
+	/// Number of the finalized block the proof has been generated at.
+	pub number: BlockNumber,
+	/// Storage keys of the audited items.
+	pub keys: Vec<Bytes>,
+	/// Trie nodes proving the values of `keys` against the state root of `at`.
+	pub proof: Vec<Bytes>,
+}
+
+/// Audit RPC methods.
+#[rpc]
+pub trait AuditRpcApi {
+	/// Generate a storage proof of the audited items of the given staking era.
+	///
+	/// The proof is generated at the finalized block `at`, or at the last finalized block if
+	/// not given. Staking keeps the items of past eras for `HistoryDepth` eras, so a proof at a
+	/// recent block also covers those. Proofs can only be generated at blocks whose state has
+	/// not been pruned yet.
+	#[rpc(name = "audit_eraStorageProof")]
+	fn era_storage_proof(&self, era: u32, at: Option<Hash>) -> Result<EraStorageProof>;
+}
+
+/// Implementation of the [`AuditRpcApi`].
+pub struct Audit<C> {
+	client: Arc<C>,
+}
+
+impl<C> Audit<C> {
+	/// Create new audit RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Audit { client }
+	}
+}
+
+impl<C> AuditRpcApi for Audit<C>
+where
+	C: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ ProofProvider<Block>
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: AuditApi<Block>,
+{
+	fn era_storage_proof(&self, era: u32, at: Option<Hash>) -> Result<EraStorageProof> {
+		let info = self.client.info();
+		let (at, number) = match at {
+			None => (info.finalized_hash, info.finalized_number),
+			Some(at) => {
+				let number = self
+					.client
+					.number(at)
+					.map_err(|e| internal_error("Failed to fetch block number", e))?
+					.ok_or_else(|| RpcError::invalid_params(format!("Unknown block: {}", at)))?;
+				let canonical = self
+					.client
+					.hash(number)
+					.map_err(|e| internal_error("Failed to fetch block hash", e))?;
+				if number > info.finalized_number || canonical != Some(at) {
+					return Err(RpcError::invalid_params(format!("Block is not finalized: {}", at)))
+				}
+				(at, number)
+			},
+		};
+
+		let id = BlockId::Hash(at);
+		let keys = self
+			.client
+			.runtime_api()
+			.era_audit_keys(&id, era)
+			.map_err(|e| internal_error("Failed to fetch audited storage keys", e))?;
+		let proof = self
+			.client
+			.read_proof(&id, &mut keys.iter().map(|key| key.as_slice()))
+			.map_err(|e| internal_error("Failed to generate storage proof", e))?;
+
+		Ok(EraStorageProof {
+			at,
+			number,
+			keys: keys.into_iter().map(Into::into).collect(),
+			proof: proof.iter_nodes().map(Into::into).collect(),
+		})
+	}
+}
+
+fn internal_error(message: &str, error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(BASE_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
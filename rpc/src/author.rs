@@ -0,0 +1,405 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction submission with automatic nonce management.
+//!
+//! Signs calls with account keys of the node keystore, assigns them the next available nonce of
+//! the account and resubmits them with the same nonce if they expire before being included.
+//! Relayers and governance bots sharing an account and a node therefore don't race each other
+//! for nonces. All methods are considered unsafe.
+
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+};
+
+use frame_rpc_system::AccountNonceApi;
+use futures::{future, StreamExt};
+use jsonrpc_core::{BoxFuture, Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use polkadot_primitives::v2::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{blake2_256, crypto::key_types::ACCOUNT, sr25519, Bytes};
+use sp_keystore::{SyncCryptoStore, SyncCryptoStorePtr};
+use sp_runtime::{
+	codec::{Compact, Encode},
+	generic::{BlockId, Era},
+	MultiAddress, MultiSignature, OpaqueExtrinsic,
+};
+use txpool_api::{InPoolTransaction, TransactionPool, TransactionSource, TransactionStatus};
+
+/// Base code for all author errors.
+const BASE_ERROR: i64 = 9300;
+
+/// The transaction has been rejected by the transaction pool.
+const TRANSACTION_REJECTED: i64 = BASE_ERROR + 1;
+
+/// The nonce of the transaction has been used by another transaction.
+const NONCE_USED: i64 = BASE_ERROR + 2;
+
+/// The transaction has not been included after all attempts.
+const ATTEMPTS_EXHAUSTED: i64 = BASE_ERROR + 3;
+
+/// Number of blocks a submitted transaction is valid for.
+const MORTALITY_PERIOD: u64 = 64;
+
+/// Number of times a transaction is submitted, unless the caller asks for a different number.
+const DEFAULT_ATTEMPTS: u32 = 3;
+
+/// Maximal number of times a transaction is submitted.
+const MAX_ATTEMPTS: u32 = 10;
+
+/// Signed extrinsics of format version 4.
+const SIGNED_EXTRINSIC_V4: u8 = 0b1000_0100;
+
+/// Next nonces of the accounts that submitted transactions through the author RPC.
+///
+/// Shared by all RPC servers of the node, so that their callers don't get the same nonce.
+#[derive(Clone, Default)]
+pub struct SubmissionNonces(Arc<Mutex<HashMap<AccountId, Nonce>>>);
+
+/// A transaction that has been included in a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Submission {
+	/// Hash of the included transaction.
+	pub extrinsic_hash: Hash,
+	/// Hash of the block the transaction has been included in.
+	pub block_hash: Hash,
+	/// Nonce the transaction has been signed with.
+	pub nonce: Nonce,
+	/// Number of times the transaction has been submitted.
+	pub attempts: u32,
+}
+
+/// Author RPC methods.
+#[rpc]
+pub trait AuthorExtApi {
+	/// Sign the SCALE-encoded `call` with the key of `signer` and submit it with the next
+	/// available nonce of `signer`. The key needs to be in the keystore of the node, with the
+	/// `acco` key type.
+	///
+	/// Returns once the transaction is included in a block. A transaction that expires before
+	/// being included is signed again with the same nonce and resubmitted, up to `attempts`
+	/// submissions in total (3 by default, at most 10).
+	#[rpc(name = "author_submitAndWatchWithRetries")]
+	fn submit_and_watch_with_retries(
+		&self,
+		call: Bytes,
+		signer: AccountId,
+		attempts: Option<u32>,
+	) -> BoxFuture<Result<Submission>>;
+}
+
+/// Implementation of the [`AuthorExtApi`].
+pub struct AuthorExt<C, P> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	keystore: SyncCryptoStorePtr,
+	nonces: SubmissionNonces,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C, P> AuthorExt<C, P> {
+	/// Create new author RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		pool: Arc<P>,
+		keystore: SyncCryptoStorePtr,
+		nonces: SubmissionNonces,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		AuthorExt { client, pool, keystore, nonces, deny_unsafe }
+	}
+}
+
+impl<C, P> AuthorExtApi for AuthorExt<C, P>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AccountNonceApi<Block, AccountId, Nonce> + Core<Block>,
+	P: TransactionPool<Block = Block, Hash = Hash> + 'static,
+{
+	fn submit_and_watch_with_retries(
+		&self,
+		call: Bytes,
+		signer: AccountId,
+		attempts: Option<u32>,
+	) -> BoxFuture<Result<Submission>> {
+		if let Err(e) = self.deny_unsafe.check_if_safe() {
+			return Box::pin(future::err(e.into()))
+		}
+
+		let attempts = attempts.unwrap_or(DEFAULT_ATTEMPTS);
+		if attempts == 0 || attempts > MAX_ATTEMPTS {
+			return Box::pin(future::err(RpcError::invalid_params(format!(
+				"Number of attempts must be between 1 and {}",
+				MAX_ATTEMPTS
+			))))
+		}
+
+		let public: [u8; 32] = signer.clone().into();
+		if !SyncCryptoStore::has_keys(&*self.keystore, &[(public.to_vec(), ACCOUNT)]) {
+			return Box::pin(future::err(RpcError::invalid_params(format!(
+				"No key of {} in the keystore",
+				signer
+			))))
+		}
+
+		let submitter = Submitter {
+			client: self.client.clone(),
+			pool: self.pool.clone(),
+			keystore: self.keystore.clone(),
+			nonces: self.nonces.clone(),
+			call: call.0,
+			signer,
+			public: sr25519::Public::from_raw(public),
+		};
+		Box::pin(async move {
+			let nonce = submitter.reserve_nonce()?;
+			let result = submitter.submit(nonce, attempts).await;
+			if result.is_err() {
+				submitter.release_nonce();
+			}
+			result
+		})
+	}
+}
+
+/// Signs and submits a single call.
+struct Submitter<C, P> {
+	client: Arc<C>,
+	pool: Arc<P>,
+	keystore: SyncCryptoStorePtr,
+	nonces: SubmissionNonces,
+	call: Vec<u8>,
+	signer: AccountId,
+	public: sr25519::Public,
+}
+
+impl<C, P> Submitter<C, P>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: AccountNonceApi<Block, AccountId, Nonce> + Core<Block>,
+	P: TransactionPool<Block = Block, Hash = Hash> + 'static,
+{
+	/// Returns the next nonce of the signer that neither the chain, nor the transaction pool,
+	/// nor a pending submission uses.
+	fn reserve_nonce(&self) -> Result<Nonce> {
+		let mut nonce = self.on_chain_nonce()?;
+		for transaction in self.pool.ready() {
+			if transaction.provides().first() == Some(&(&self.signer, nonce).encode()) {
+				nonce += 1;
+			}
+		}
+
+		let mut nonces = self.nonces.0.lock().expect("nonces lock is never poisoned; qed");
+		let next = nonces.entry(self.signer.clone()).or_default();
+		let nonce = nonce.max(*next);
+		*next = nonce + 1;
+		Ok(nonce)
+	}
+
+	/// Forget the next nonce of the signer, so that it is derived from the chain and the
+	/// transaction pool again. This fills the gap left by a transaction that hasn't been included.
+	fn release_nonce(&self) {
+		self.nonces
+			.0
+			.lock()
+			.expect("nonces lock is never poisoned; qed")
+			.remove(&self.signer);
+	}
+
+	fn on_chain_nonce(&self) -> Result<Nonce> {
+		let best_hash = self.client.info().best_hash;
+		self.client
+			.runtime_api()
+			.account_nonce(&BlockId::Hash(best_hash), self.signer.clone())
+			.map_err(|e| internal_error("Failed to query the account nonce", e))
+	}
+
+	async fn submit(&self, nonce: Nonce, attempts: u32) -> Result<Submission> {
+		for attempt in 1..=attempts {
+			let (extrinsic, at) = self.sign(nonce)?;
+			let extrinsic_hash = self.pool.hash_of(&extrinsic);
+			let mut watcher = self
+				.pool
+				.submit_and_watch(&BlockId::Hash(at), TransactionSource::External, extrinsic)
+				.await
+				.map_err(|e| error(TRANSACTION_REJECTED, "Transaction has been rejected", e))?;
+
+			while let Some(status) = watcher.next().await {
+				match status {
+					TransactionStatus::InBlock(block_hash) |
+					TransactionStatus::Finalized(block_hash) =>
+						return Ok(Submission { extrinsic_hash, block_hash, nonce, attempts: attempt }),
+					TransactionStatus::Usurped(_) |
+					TransactionStatus::Dropped |
+					TransactionStatus::Invalid => break,
+					_ => {},
+				}
+			}
+
+			// the transaction has left the pool without being included, which is expected if it
+			// has expired, but not if another transaction with the same nonce has been included
+			if self.on_chain_nonce()? > nonce {
+				return Err(error(
+					NONCE_USED,
+					"Nonce has been used by another transaction",
+					(&self.signer, nonce),
+				))
+			}
+			log::debug!(
+				target: "rpc",
+				"Transaction {:?} of {} with nonce {} has not been included (attempt {}/{})",
+				extrinsic_hash,
+				self.signer,
+				nonce,
+				attempt,
+				attempts,
+			);
+		}
+
+		Err(error(ATTEMPTS_EXHAUSTED, "Transaction has not been included", attempts))
+	}
+
+	/// Sign the call with the given nonce, valid for `MORTALITY_PERIOD` blocks starting at the best
+	/// block. Returns the signed extrinsic and the hash of the best block.
+	fn sign(&self, nonce: Nonce) -> Result<(OpaqueExtrinsic, Hash)> {
+		let info = self.client.info();
+		let at = BlockId::Hash(info.best_hash);
+		let version = self
+			.client
+			.runtime_api()
+			.version(&at)
+			.map_err(|e| internal_error("Failed to query the runtime version", e))?;
+
+		let era = Era::mortal(MORTALITY_PERIOD, info.best_number.into());
+		let birth = era.birth(info.best_number.into()) as BlockNumber;
+		let birth_hash = self
+			.client
+			.hash(birth)
+			.ok()
+			.flatten()
+			.ok_or_else(|| internal_error("Failed to query the block hash", birth))?;
+
+		let extrinsic = signed_extrinsic(
+			&self.call,
+			&self.signer,
+			nonce,
+			era,
+			(version.spec_version, version.transaction_version, info.genesis_hash, birth_hash),
+			|payload| {
+				SyncCryptoStore::sign_with(&*self.keystore, ACCOUNT, &self.public.into(), payload)
+					.ok()
+					.flatten()
+					.and_then(|signature| sr25519::Signature::try_from(&signature[..]).ok())
+					.ok_or_else(|| internal_error("Failed to sign the transaction", &self.signer))
+			},
+		)?;
+		Ok((extrinsic, info.best_hash))
+	}
+}
+
+/// Encode a signed extrinsic of a relay chain runtime.
+///
+/// All relay chain runtimes share the layout of their signed extensions: only the mortality, the
+/// nonce and the tip are part of the extrinsic, and only the spec and transaction versions, the
+/// genesis hash and the hash of the block the mortality starts at are signed along with the call.
+fn signed_extrinsic(
+	call: &[u8],
+	signer: &AccountId,
+	nonce: Nonce,
+	era: Era,
+	additional_signed: (u32, u32, Hash, Hash),
+	sign: impl FnOnce(&[u8]) -> Result<sr25519::Signature>,
+) -> Result<OpaqueExtrinsic> {
+	let extra = (era, Compact(nonce), Compact::<Balance>(0));
+
+	let mut payload = call.to_vec();
+	extra.encode_to(&mut payload);
+	additional_signed.encode_to(&mut payload);
+	let signature = if payload.len() > 256 { sign(&blake2_256(&payload)) } else { sign(&payload) }?;
+
+	let mut extrinsic = vec![SIGNED_EXTRINSIC_V4];
+	MultiAddress::<AccountId, ()>::Id(signer.clone()).encode_to(&mut extrinsic);
+	MultiSignature::Sr25519(signature).encode_to(&mut extrinsic);
+	extra.encode_to(&mut extrinsic);
+	extrinsic.extend_from_slice(call);
+
+	OpaqueExtrinsic::from_bytes(&extrinsic.encode())
+		.map_err(|e| internal_error("Failed to encode the transaction", e))
+}
+
+fn error(code: i64, message: &str, data: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(code),
+		message: message.into(),
+		data: Some(format!("{:?}", data).into()),
+	}
+}
+
+fn internal_error(message: &str, error: impl std::fmt::Debug) -> RpcError {
+	self::error(BASE_ERROR, message, error)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::Pair;
+	use sp_runtime::{codec::Decode, traits::Verify};
+
+	#[test]
+	fn signed_extrinsic_is_encoded_like_relay_chain_extrinsics() {
+		let pair = sr25519::Pair::from_seed(&[1; 32]);
+		let signer = AccountId::from(pair.public());
+		let era = Era::mortal(MORTALITY_PERIOD, 100);
+		let additional_signed = (9180, 12, Hash::repeat_byte(1), Hash::repeat_byte(2));
+
+		// payloads longer than 256 bytes are hashed before being signed
+		for call in [vec![0, 1, 2], vec![3; 300]] {
+			let extrinsic =
+				signed_extrinsic(&call, &signer, 5, era, additional_signed, |payload| {
+					Ok(pair.sign(payload))
+				})
+				.unwrap()
+				.encode();
+
+			let input = &mut &extrinsic[..];
+			let len = Compact::<u32>::decode(input).unwrap();
+			assert_eq!(len.0 as usize, input.len());
+			assert_eq!(u8::decode(input).unwrap(), SIGNED_EXTRINSIC_V4);
+			assert_eq!(
+				MultiAddress::<AccountId, ()>::decode(input).unwrap(),
+				MultiAddress::Id(signer.clone()),
+			);
+			let signature = MultiSignature::decode(input).unwrap();
+			let extra = <(Era, Compact<Nonce>, Compact<Balance>)>::decode(input).unwrap();
+			assert_eq!(extra, (era, Compact(5), Compact(0)));
+			assert_eq!(*input, &call[..]);
+
+			let mut payload = call.clone();
+			extra.encode_to(&mut payload);
+			additional_signed.encode_to(&mut payload);
+			if payload.len() > 256 {
+				payload = blake2_256(&payload).to_vec();
+			}
+			assert!(signature.verify(&payload[..], &signer));
+		}
+	}
+}
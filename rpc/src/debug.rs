@@ -0,0 +1,653 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Debug RPC.
+//!
+//! Executes a single extrinsic or a runtime upgrade on top of a block and reports what it did.
+//! For an extrinsic, that is the dispatch outcome, every storage access in order and the events
+//! it deposited. For an upgrade, that is the weight and the storage changes of the upgrade and
+//! of the block it happens in. Meant for diagnosing bridged dispatches and governance calls that
+//! behave unexpectedly, and for checking upgrades before they are voted on. All methods are
+//! considered unsafe.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	fmt,
+	sync::{Arc, Mutex},
+};
+
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use futures::future::BoxFuture;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use polkadot_primitives::v2::{
+	Block, Hash, Header, InherentData as ParachainsInherentData, PARACHAINS_INHERENT_IDENTIFIER,
+};
+use sc_client_api::{Backend, StateBackend};
+use sc_executor::{WasmExecutionMethod, WasmExecutor};
+use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
+use serde::{Deserialize, Serialize};
+use sp_api::{Core, ProvideRuntimeApi, RuntimeVersion};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_consensus_babe::{
+	digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest},
+	BabeApi, Slot,
+};
+use sp_core::{
+	blake2_256,
+	bytes::{from_hex, to_hex},
+	storage::well_known_keys,
+	traits::{RuntimeCode, SpawnNamed, WrappedRuntimeCode},
+	twox_128, Bytes, OpaqueMetadata,
+};
+use sp_inherents::InherentData;
+use sp_runtime::{
+	codec::{Decode, Encode},
+	generic::{BlockId, Digest, DigestItem},
+	traits::{Block as BlockT, HashFor, Header as HeaderT},
+	ApplyExtrinsicResult,
+};
+use sp_state_machine::{ExecutionStrategy, OverlayedChanges, StateMachine};
+use tracing::{
+	field::{Field, Visit},
+	span,
+	subscriber::Interest,
+	Event, Metadata, Subscriber,
+};
+
+/// Base code for all debug errors.
+const BASE_ERROR: i64 = 9200;
+
+/// Target of the tracing events the host emits for every storage access.
+const STATE_TARGET: &str = "state";
+
+/// A single storage access of a traced extrinsic.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageAccess {
+	/// Kind of the access, e.g. `Get`, `Put`, `Append` or `ClearPrefix`.
+	pub method: String,
+	/// Storage key of the child trie, if the access is to child storage.
+	pub child: Option<Bytes>,
+	/// Accessed key or prefix, if any.
+	pub key: Option<Bytes>,
+	/// Value that has been read or written, if any.
+	pub value: Option<Bytes>,
+}
+
+/// Trace of an extrinsic executed on top of a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicTrace {
+	/// Hash of the block the extrinsic has been executed on top of.
+	pub at: Hash,
+	/// Outcome of applying the extrinsic.
+	pub result: ApplyExtrinsicResult,
+	/// Storage accesses of the extrinsic, in the order they happened.
+	pub storage: Vec<StorageAccess>,
+	/// SCALE-encoded `EventRecord`s deposited by the extrinsic.
+	pub events: Vec<Bytes>,
+}
+
+/// Storage changes of a single pallet.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PalletStorageDelta {
+	/// Storage prefix of the pallet, or the hex-encoded first 16 bytes of the changed keys if the
+	/// prefix is not declared in the metadata.
+	pub pallet: String,
+	/// Number of keys that have been inserted.
+	pub inserted: u32,
+	/// Number of keys whose value has been changed.
+	pub modified: u32,
+	/// Number of keys that have been removed.
+	pub removed: u32,
+}
+
+/// Outcome of a runtime upgrade executed on top of a block.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpgradeDryRun {
+	/// Hash of the block the upgrade has been executed on top of.
+	pub at: Hash,
+	/// Spec version of the runtime at `at`.
+	pub current_spec_version: u32,
+	/// Spec version of the new runtime.
+	pub spec_version: u32,
+	/// Weight consumed by initializing the block, including the upgrade hooks, if that succeeded.
+	pub initialization_weight: Option<u64>,
+	/// Weight consumed by the block before it is finalized, if its inherents have been applied.
+	pub block_weight: Option<u64>,
+	/// Storage changes of the upgrade and the block, per pallet. The new code is not included.
+	pub storage: Vec<PalletStorageDelta>,
+	/// The panic or failed inherent the execution has been aborted with, if any.
+	pub panic: Option<String>,
+}
+
+/// Debug RPC methods.
+#[rpc]
+pub trait DebugApi {
+	/// Execute the SCALE-encoded `extrinsic` in a new block on top of `at` (the best block if not
+	/// given) and return its trace. Nothing is imported or submitted.
+	///
+	/// The block is initialized, but no inherents are applied before the extrinsic.
+	#[rpc(name = "debug_traceExtrinsic")]
+	fn trace_extrinsic(&self, extrinsic: Bytes, at: Option<Hash>) -> Result<ExtrinsicTrace>;
+
+	/// Replace the runtime with the wasm `code` on top of `at` (the best block if not given),
+	/// execute a new block with the new runtime and report what the upgrade and the block did.
+	/// Nothing is imported or submitted.
+	///
+	/// The block only contains the inherents, without any bitfields, candidates or disputes. The
+	/// upgrade hooks only run if the spec version of `code` is greater than the current one.
+	#[rpc(name = "debug_dryRunUpgrade")]
+	fn dry_run_upgrade(&self, code: Bytes, at: Option<Hash>) -> Result<UpgradeDryRun>;
+}
+
+/// Implementation of the [`DebugApi`].
+pub struct Debug<C, B> {
+	client: Arc<C>,
+	backend: Arc<B>,
+	executor: WasmExecutor<sp_io::SubstrateHostFunctions>,
+	spawner: Spawner,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C, B> Debug<C, B> {
+	/// Create new debug RPC handler.
+	///
+	/// Runtime upgrades are executed with `wasm_method`, their tasks are spawned on `spawner`.
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<B>,
+		wasm_method: WasmExecutionMethod,
+		spawner: SubscriptionTaskExecutor,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		let executor = WasmExecutor::new(wasm_method, None, 1, None, 1);
+		Debug { client, backend, executor, spawner: Spawner(spawner), deny_unsafe }
+	}
+}
+
+impl<C, B> DebugApi for Debug<C, B>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: BlockBuilder<Block> + BabeApi<Block>,
+	B: Backend<Block> + Send + Sync + 'static,
+	B::State: StateBackend<HashFor<Block>>,
+{
+	fn trace_extrinsic(&self, extrinsic: Bytes, at: Option<Hash>) -> Result<ExtrinsicTrace> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let id = BlockId::Hash(at);
+		let parent = self
+			.client
+			.header(id)
+			.map_err(|e| internal_error("Failed to fetch block header", e))?
+			.ok_or_else(|| RpcError::invalid_params(format!("Unknown block: {}", at)))?;
+		let extrinsic = <Block as BlockT>::Extrinsic::decode(&mut &extrinsic[..])
+			.map_err(|e| RpcError::invalid_params(format!("Invalid extrinsic: {}", e)))?;
+
+		let header = Header {
+			parent_hash: at,
+			number: parent.number + 1,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		};
+		let runtime_api = self.client.runtime_api();
+		runtime_api
+			.initialize_block(&id, &header)
+			.map_err(|e| internal_error("Failed to initialize block", e))?;
+
+		// Only the extrinsic itself is traced, not the block initialization.
+		let tracer = StorageTracer::default();
+		let dispatch = tracing::Dispatch::new(tracer.clone());
+		let result = tracing::dispatcher::with_default(&dispatch, || {
+			runtime_api.apply_extrinsic(&id, extrinsic)
+		})
+		.map_err(|e| internal_error("Failed to apply extrinsic", e))?;
+
+		let storage = tracer.take();
+		let events_key = [twox_128(b"System"), twox_128(b"Events")].concat();
+		let events = storage
+			.iter()
+			.filter(|access| {
+				access.method == "Append" && access.key.as_deref() == Some(&events_key[..])
+			})
+			.filter_map(|access| access.value.clone())
+			.collect();
+
+		Ok(ExtrinsicTrace { at, result, storage, events })
+	}
+
+	fn dry_run_upgrade(&self, code: Bytes, at: Option<Hash>) -> Result<UpgradeDryRun> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let id = BlockId::Hash(at);
+		let parent = self
+			.client
+			.header(id)
+			.map_err(|e| internal_error("Failed to fetch block header", e))?
+			.ok_or_else(|| RpcError::invalid_params(format!("Unknown block: {}", at)))?;
+		let runtime_api = self.client.runtime_api();
+		let current_spec_version = runtime_api
+			.version(&id)
+			.map_err(|e| internal_error("Failed to fetch runtime version", e))?
+			.spec_version;
+		let slot_duration = runtime_api
+			.configuration(&id)
+			.map_err(|e| internal_error("Failed to fetch BABE configuration", e))?
+			.slot_duration;
+		let parent_slot = sc_consensus_babe::find_pre_digest::<Block>(&parent)
+			.map_err(|e| internal_error("Failed to find slot of block", e))?
+			.slot();
+		let state = self
+			.backend
+			.state_at(id)
+			.map_err(|e| internal_error("Failed to fetch state", e))?;
+
+		let heap_pages = state
+			.storage(well_known_keys::HEAP_PAGES)
+			.ok()
+			.flatten()
+			.and_then(|pages| u64::decode(&mut &pages[..]).ok());
+		let code_fetcher = WrappedRuntimeCode(code.0.as_slice().into());
+		let runtime_code = RuntimeCode {
+			code_fetcher: &code_fetcher,
+			heap_pages,
+			hash: blake2_256(&code).to_vec(),
+		};
+		let mut dry_run = DryRun {
+			state: &state,
+			overlay: Default::default(),
+			executor: &self.executor,
+			runtime_code,
+			spawner: self.spawner.clone(),
+		};
+		dry_run.overlay.set_storage(well_known_keys::CODE.to_vec(), Some(code.to_vec()));
+
+		let spec_version = dry_run
+			.call_decode::<RuntimeVersion>("Core_version", &[])
+			.map_err(|e| RpcError::invalid_params(format!("Invalid runtime: {}", e)))?
+			.spec_version;
+		let prefixes = dry_run.storage_prefixes();
+
+		let mut report = UpgradeDryRun {
+			at,
+			current_spec_version,
+			spec_version,
+			initialization_weight: None,
+			block_weight: None,
+			storage: Vec::new(),
+			panic: None,
+		};
+		let slot = Slot::from(u64::from(parent_slot) + 1);
+		report.panic = dry_run.execute_block(parent, slot, slot_duration, &mut report).err();
+		report.storage = dry_run.storage_deltas(&prefixes);
+
+		Ok(report)
+	}
+}
+
+/// Executes runtime calls of a runtime upgrade on top of a block, keeping their changes.
+struct DryRun<'a, S> {
+	state: &'a S,
+	overlay: OverlayedChanges,
+	executor: &'a WasmExecutor<sp_io::SubstrateHostFunctions>,
+	runtime_code: RuntimeCode<'a>,
+	spawner: Spawner,
+}
+
+impl<'a, S: StateBackend<HashFor<Block>>> DryRun<'a, S> {
+	fn call(&mut self, method: &str, data: &[u8]) -> std::result::Result<Vec<u8>, String> {
+		StateMachine::new(
+			self.state,
+			&mut self.overlay,
+			self.executor,
+			method,
+			data,
+			Default::default(),
+			&self.runtime_code,
+			self.spawner.clone(),
+		)
+		.execute(ExecutionStrategy::AlwaysWasm)
+		.map_err(|e| format!("{}: {}", method, e))
+	}
+
+	fn call_decode<R: Decode>(
+		&mut self,
+		method: &str,
+		data: &[u8],
+	) -> std::result::Result<R, String> {
+		let result = self.call(method, data)?;
+		R::decode(&mut &result[..]).map_err(|e| format!("{}: invalid result: {}", method, e))
+	}
+
+	fn storage(&self, key: &[u8]) -> Option<Vec<u8>> {
+		match self.overlay.storage(key) {
+			Some(value) => value.map(|value| value.to_vec()),
+			None => self.state.storage(key).ok().flatten(),
+		}
+	}
+
+	/// Storage prefixes of the pallets of the new runtime.
+	fn storage_prefixes(&mut self) -> HashMap<[u8; 16], String> {
+		let metadata = self
+			.call_decode::<OpaqueMetadata>("Metadata_metadata", &[])
+			.ok()
+			.and_then(|metadata| RuntimeMetadataPrefixed::decode(&mut &metadata[..]).ok());
+		match metadata.map(|metadata| metadata.1) {
+			Some(RuntimeMetadata::V14(metadata)) => metadata
+				.pallets
+				.into_iter()
+				.filter_map(|pallet| pallet.storage)
+				.map(|storage| (twox_128(storage.prefix.as_bytes()), storage.prefix))
+				.collect(),
+			_ => HashMap::new(),
+		}
+	}
+
+	/// Execute a block with only the inherents on top of `parent`, authored in `slot`.
+	fn execute_block(
+		&mut self,
+		parent: Header,
+		slot: Slot,
+		slot_duration: u64,
+		report: &mut UpgradeDryRun,
+	) -> std::result::Result<(), String> {
+		let pre_digest =
+			PreDigest::SecondaryPlain(SecondaryPlainPreDigest { authority_index: 0, slot });
+		let header = Header {
+			parent_hash: parent.hash(),
+			number: parent.number + 1,
+			state_root: Default::default(),
+			extrinsics_root: Default::default(),
+			digest: Digest { logs: vec![DigestItem::babe_pre_digest(pre_digest)] },
+		};
+		self.call("Core_initialize_block", &header.encode())?;
+		report.initialization_weight = Some(self.block_weight()?);
+
+		let mut inherent_data = InherentData::new();
+		let parachains_inherent_data = ParachainsInherentData {
+			bitfields: Vec::new(),
+			backed_candidates: Vec::new(),
+			disputes: Vec::new(),
+			parent_header: parent,
+		};
+		inherent_data
+			.put_data(sp_timestamp::INHERENT_IDENTIFIER, &(u64::from(slot) * slot_duration))
+			.and_then(|_| {
+				inherent_data.put_data(PARACHAINS_INHERENT_IDENTIFIER, &parachains_inherent_data)
+			})
+			.map_err(|e| format!("Failed to create inherent data: {}", e))?;
+		let inherents = self.call_decode::<Vec<<Block as BlockT>::Extrinsic>>(
+			"BlockBuilder_inherent_extrinsics",
+			&inherent_data.encode(),
+		)?;
+		for inherent in inherents {
+			self.call_decode::<ApplyExtrinsicResult>(
+				"BlockBuilder_apply_extrinsic",
+				&inherent.encode(),
+			)?
+			.map_err(|e| format!("Invalid inherent: {:?}", e))?
+			.map_err(|e| format!("Inherent failed: {:?}", e))?;
+		}
+		report.block_weight = Some(self.block_weight()?);
+
+		self.call("BlockBuilder_finalize_block", &[])?;
+		Ok(())
+	}
+
+	/// Total weight consumed by the current block.
+	fn block_weight(&self) -> std::result::Result<u64, String> {
+		let key = [twox_128(b"System"), twox_128(b"BlockWeight")].concat();
+		match self.storage(&key) {
+			Some(weight) => ConsumedWeight::decode(&mut &weight[..])
+				.map(|weight| weight.normal + weight.operational + weight.mandatory)
+				.map_err(|e| format!("Invalid block weight: {}", e)),
+			None => Ok(0),
+		}
+	}
+
+	fn storage_deltas(&self, prefixes: &HashMap<[u8; 16], String>) -> Vec<PalletStorageDelta> {
+		let changes = self
+			.overlay
+			.changes()
+			.filter(|(key, _)| key.as_slice() != well_known_keys::CODE)
+			.map(|(key, value)| {
+				let before = self.state.storage(key).ok().flatten();
+				(key.as_slice(), before, value.value().map(|value| value.as_slice()))
+			});
+		storage_deltas(changes, prefixes)
+	}
+}
+
+/// Group storage changes, given as `(key, value before, value after)`, by pallet.
+fn storage_deltas<'a>(
+	changes: impl Iterator<Item = (&'a [u8], Option<Vec<u8>>, Option<&'a [u8]>)>,
+	prefixes: &HashMap<[u8; 16], String>,
+) -> Vec<PalletStorageDelta> {
+	let mut deltas = BTreeMap::<String, PalletStorageDelta>::new();
+	for (key, before, after) in changes {
+		let prefix = &key[..key.len().min(16)];
+		let pallet = prefixes.get(prefix).cloned().unwrap_or_else(|| to_hex(prefix, false));
+		let delta = deltas
+			.entry(pallet.clone())
+			.or_insert_with(|| PalletStorageDelta { pallet, ..Default::default() });
+		match (before, after) {
+			(None, Some(_)) => delta.inserted += 1,
+			(Some(before), Some(after)) if before != after => delta.modified += 1,
+			(Some(_), None) => delta.removed += 1,
+			_ => {},
+		}
+	}
+
+	deltas
+		.into_values()
+		.filter(|delta| delta.inserted + delta.modified + delta.removed > 0)
+		.collect()
+}
+
+/// Weight consumed by a block per dispatch class, as stored by `frame_system`.
+#[derive(Decode)]
+struct ConsumedWeight {
+	normal: u64,
+	operational: u64,
+	mandatory: u64,
+}
+
+/// Spawns the tasks of executed runtimes on the RPC task executor.
+#[derive(Clone)]
+struct Spawner(SubscriptionTaskExecutor);
+
+impl SpawnNamed for Spawner {
+	fn spawn_blocking(
+		&self,
+		name: &'static str,
+		group: Option<&'static str>,
+		future: BoxFuture<'static, ()>,
+	) {
+		self.0.spawn_blocking(name, group, future)
+	}
+
+	fn spawn(
+		&self,
+		name: &'static str,
+		group: Option<&'static str>,
+		future: BoxFuture<'static, ()>,
+	) {
+		self.0.spawn(name, group, future)
+	}
+}
+
+/// Collects the storage accesses reported by the host while it is the default dispatcher.
+#[derive(Clone, Default)]
+struct StorageTracer(Arc<Mutex<Vec<StorageAccess>>>);
+
+impl StorageTracer {
+	fn take(&self) -> Vec<StorageAccess> {
+		self.0
+			.lock()
+			.map(|mut accesses| std::mem::take(&mut *accesses))
+			.unwrap_or_default()
+	}
+}
+
+impl Subscriber for StorageTracer {
+	fn register_callsite(&self, _: &'static Metadata<'static>) -> Interest {
+		// Don't let the callsite cache disable events for the other dispatchers.
+		Interest::sometimes()
+	}
+
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		metadata.target() == STATE_TARGET
+	}
+
+	fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+		span::Id::from_u64(1)
+	}
+
+	fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+	fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+	fn event(&self, event: &Event<'_>) {
+		let mut visitor = StorageAccessVisitor::default();
+		event.record(&mut visitor);
+		if let (Some(method), Ok(mut accesses)) = (visitor.method, self.0.lock()) {
+			accesses.push(StorageAccess {
+				method,
+				child: visitor.child,
+				key: visitor.key,
+				value: visitor.value,
+			});
+		}
+	}
+
+	fn enter(&self, _: &span::Id) {}
+
+	fn exit(&self, _: &span::Id) {}
+}
+
+#[derive(Default)]
+struct StorageAccessVisitor {
+	method: Option<String>,
+	child: Option<Bytes>,
+	key: Option<Bytes>,
+	value: Option<Bytes>,
+}
+
+impl Visit for StorageAccessVisitor {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		match field.name() {
+			"method" => self.method = Some(value.into()),
+			"child_info" => self.child = parse_hex(value),
+			"key" => self.key = parse_hex(value),
+			"value" | "result" => self.value = parse_hex(value),
+			_ => {},
+		}
+	}
+
+	fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+		self.record_str(field, &format!("{:?}", value))
+	}
+}
+
+/// Parse a hex value as the host reports it, either plain or wrapped in an `Option`.
+fn parse_hex(value: &str) -> Option<Bytes> {
+	let value = value.strip_prefix("Some(").and_then(|v| v.strip_suffix(')')).unwrap_or(value);
+	from_hex(value).ok().map(Into::into)
+}
+
+fn internal_error(message: &str, error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(BASE_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_reported_values() {
+		assert_eq!(parse_hex("0a0b"), Some(vec![0x0a, 0x0b].into()));
+		assert_eq!(parse_hex("Some(0a0b)"), Some(vec![0x0a, 0x0b].into()));
+		assert_eq!(parse_hex("Some()"), Some(Vec::new().into()));
+		assert_eq!(parse_hex("None"), None);
+	}
+
+	#[test]
+	fn groups_storage_changes_by_pallet() {
+		let system = twox_128(b"System");
+		let prefixes = [(system, "System".to_owned())].into_iter().collect();
+		let key = |prefix: &[u8], suffix: u8| [prefix, &[suffix]].concat();
+		let (a, b, c, d) = (key(&system, 1), key(&system, 2), key(&system, 3), key(&system, 4));
+		let unknown = key(&[0xff; 16], 1);
+		let changes = vec![
+			(&a[..], None, Some(&[1u8][..])),
+			(&b[..], Some(vec![1]), Some(&[2u8][..])),
+			(&c[..], Some(vec![1]), None),
+			(&d[..], Some(vec![1]), Some(&[1u8][..])),
+			(&unknown[..], None, Some(&[1u8][..])),
+			(&b":short"[..], None, None),
+		];
+
+		assert_eq!(
+			storage_deltas(changes.into_iter(), &prefixes),
+			vec![
+				PalletStorageDelta {
+					pallet: "System".into(),
+					inserted: 1,
+					modified: 1,
+					removed: 1
+				},
+				PalletStorageDelta {
+					pallet: format!("0x{}", "ff".repeat(16)),
+					inserted: 1,
+					modified: 0,
+					removed: 0,
+				},
+			],
+		);
+	}
+
+	#[test]
+	fn collects_state_events_only() {
+		let tracer = StorageTracer::default();
+		let dispatch = tracing::Dispatch::new(tracer.clone());
+		tracing::dispatcher::with_default(&dispatch, || {
+			tracing::trace!(target: "state", method = "Get", key = "0a", result = "Some(0b)");
+			tracing::trace!(target: "runtime", method = "Get", key = "0c");
+		});
+
+		assert_eq!(
+			tracer.take(),
+			vec![StorageAccess {
+				method: "Get".into(),
+				child: None,
+				key: Some(vec![0x0a].into()),
+				value: Some(vec![0x0b].into()),
+			}],
+		);
+	}
+}
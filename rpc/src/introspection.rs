@@ -0,0 +1,144 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Subsystem message bus introspection RPC.
+//!
+//! Reports the messages pending for every subsystem of the overseer, the types of the messages
+//! they received recently and the last messages of a chosen subsystem. Meant for diagnosing
+//! subsystems that wait on each other. All methods are considered unsafe.
+
+use jsonrpc_core::{Error as RpcError, Result};
+use jsonrpc_derive::rpc;
+use polkadot_overseer::{MessageBusSnapshot, MessageIntrospection};
+use sc_rpc::DenyUnsafe;
+use serde::{Deserialize, Serialize};
+
+/// Messages of a single subsystem.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsystemMessages {
+	/// Name of the subsystem.
+	pub name: String,
+	/// Number of messages sent to the subsystem, but not received by it yet.
+	pub pending_messages: usize,
+	/// Number of signals sent to the subsystem, but not received by it yet.
+	pub pending_signals: usize,
+	/// Number of messages per type among the messages the subsystem received most recently,
+	/// most frequent first. Empty unless recording is enabled.
+	pub recent_message_types: Vec<(String, usize)>,
+}
+
+/// Messages captured for a subsystem.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedMessages {
+	/// Name of the subsystem.
+	pub subsystem: String,
+	/// Debug representations of the last messages the subsystem received, oldest first.
+	pub messages: Vec<String>,
+}
+
+/// Snapshot of the messages exchanged between subsystems.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageBus {
+	/// Whether the types of the received messages are recorded.
+	pub recording: bool,
+	/// All subsystems, in the order they have been spawned. Empty if the node doesn't run an
+	/// overseer.
+	pub subsystems: Vec<SubsystemMessages>,
+	/// Messages of the subsystem that is being captured, if any.
+	pub captured: Option<CapturedMessages>,
+}
+
+impl From<MessageBusSnapshot> for MessageBus {
+	fn from(snapshot: MessageBusSnapshot) -> Self {
+		MessageBus {
+			recording: snapshot.recording,
+			subsystems: snapshot
+				.subsystems
+				.into_iter()
+				.map(|subsystem| SubsystemMessages {
+					name: subsystem.name.into(),
+					pending_messages: subsystem.pending_messages,
+					pending_signals: subsystem.pending_signals,
+					recent_message_types: subsystem.recent_message_types,
+				})
+				.collect(),
+			captured: snapshot.captured.map(|captured| CapturedMessages {
+				subsystem: captured.subsystem.into(),
+				messages: captured.messages,
+			}),
+		}
+	}
+}
+
+/// Subsystem message bus introspection RPC methods.
+#[rpc]
+pub trait IntrospectionApi {
+	/// Take a snapshot of the messages exchanged between subsystems.
+	#[rpc(name = "introspection_messageBus")]
+	fn message_bus(&self) -> Result<MessageBus>;
+
+	/// Enable or disable recording the types of the messages received by all subsystems.
+	///
+	/// Disabling recording discards the recorded types.
+	#[rpc(name = "introspection_setRecording")]
+	fn set_recording(&self, enabled: bool) -> Result<()>;
+
+	/// Capture the last `count` messages received by the given subsystem, e.g.
+	/// `("dispute-coordinator", 100)`, replacing any previous capture.
+	///
+	/// `count` is limited to 1000, a `count` of zero stops capturing.
+	#[rpc(name = "introspection_captureMessages")]
+	fn capture_messages(&self, subsystem: String, count: u32) -> Result<()>;
+}
+
+/// Implementation of the [`IntrospectionApi`].
+pub struct Introspection {
+	introspection: MessageIntrospection,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl Introspection {
+	/// Create new introspection RPC handler.
+	pub fn new(introspection: MessageIntrospection, deny_unsafe: DenyUnsafe) -> Self {
+		Introspection { introspection, deny_unsafe }
+	}
+}
+
+impl IntrospectionApi for Introspection {
+	fn message_bus(&self) -> Result<MessageBus> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.introspection.snapshot().into())
+	}
+
+	fn set_recording(&self, enabled: bool) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.introspection.set_recording(enabled);
+		Ok(())
+	}
+
+	fn capture_messages(&self, subsystem: String, count: u32) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		self.introspection
+			.capture(&subsystem, count as usize)
+			.map_err(|e| RpcError::invalid_params(e.to_string()))
+	}
+}
@@ -20,9 +20,10 @@
 
 use std::sync::Arc;
 
-use polkadot_primitives::v2::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
-use sc_client_api::AuxStore;
+use polkadot_primitives::v2::{AccountId, AuditApi, Balance, Block, BlockNumber, Hash, Nonce};
+use sc_client_api::{AuxStore, ProofProvider};
 use sc_consensus_babe::Epoch;
+use sc_executor::WasmExecutionMethod;
 use sc_finality_grandpa::FinalityProofProvider;
 pub use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
 use sc_sync_state_rpc::{SyncStateRpcApi, SyncStateRpcHandler};
@@ -34,6 +35,16 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod audit;
+pub mod author;
+pub mod debug;
+pub mod introspection;
+pub mod operator;
+
+pub use author::SubmissionNonces;
+pub use operator::AuthoringSwitch;
+pub use polkadot_overseer::MessageIntrospection;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -90,6 +101,14 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// Switch that is used by the operator RPC to pause block authoring.
+	pub authoring_switch: AuthoringSwitch,
+	/// Introspection of the messages exchanged between the subsystems of the overseer.
+	pub message_introspection: MessageIntrospection,
+	/// Method the debug RPC executes runtime upgrades with.
+	pub wasm_method: WasmExecutionMethod,
+	/// Next nonces of the accounts that submit transactions through the author RPC.
+	pub submission_nonces: SubmissionNonces,
 }
 
 /// Instantiate all RPC extensions.
@@ -102,6 +121,7 @@ where
 		+ HeaderBackend<Block>
 		+ AuxStore
 		+ HeaderMetadata<Block, Error = BlockChainError>
+		+ ProofProvider<Block>
 		+ Send
 		+ Sync
 		+ 'static,
@@ -110,20 +130,39 @@ where
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
 	C::Api: BlockBuilder<Block>,
-	P: TransactionPool + Sync + Send + 'static,
+	C::Api: sp_session::SessionKeys<Block>,
+	C::Api: AuditApi<Block>,
+	P: TransactionPool<Block = Block, Hash = Hash> + Sync + Send + 'static,
 	SC: SelectChain<Block> + 'static,
 	B: sc_client_api::Backend<Block> + Send + Sync + 'static,
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
+	use audit::{Audit, AuditRpcApi};
+	use author::{AuthorExt, AuthorExtApi};
+	use debug::{Debug, DebugApi};
 	use frame_rpc_system::{FullSystem, SystemApi};
+	use introspection::{Introspection, IntrospectionApi};
+	use operator::{Operator, OperatorApi};
 	use pallet_mmr_rpc::{Mmr, MmrApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use sc_consensus_babe_rpc::BabeRpcHandler;
 	use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
 
 	let mut io = jsonrpc_core::IoHandler::default();
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		authoring_switch,
+		message_introspection,
+		wasm_method,
+		submission_nonces,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -134,12 +173,45 @@ where
 	} = grandpa;
 
 	io.extend_with(substrate_state_trie_migration_rpc::StateMigrationApi::to_delegate(
-		substrate_state_trie_migration_rpc::MigrationRpc::new(client.clone(), backend, deny_unsafe),
+		substrate_state_trie_migration_rpc::MigrationRpc::new(
+			client.clone(),
+			backend.clone(),
+			deny_unsafe,
+		),
 	));
 
-	io.extend_with(SystemApi::to_delegate(FullSystem::new(client.clone(), pool, deny_unsafe)));
+	io.extend_with(SystemApi::to_delegate(FullSystem::new(
+		client.clone(),
+		pool.clone(),
+		deny_unsafe,
+	)));
 	io.extend_with(TransactionPaymentApi::to_delegate(TransactionPayment::new(client.clone())));
 	io.extend_with(MmrApi::to_delegate(Mmr::new(client.clone())));
+	io.extend_with(AuditRpcApi::to_delegate(Audit::new(client.clone())));
+	io.extend_with(DebugApi::to_delegate(Debug::new(
+		client.clone(),
+		backend,
+		wasm_method,
+		subscription_executor.clone(),
+		deny_unsafe,
+	)));
+	io.extend_with(OperatorApi::to_delegate(Operator::new(
+		client.clone(),
+		keystore.clone(),
+		authoring_switch,
+		deny_unsafe,
+	)));
+	io.extend_with(AuthorExtApi::to_delegate(AuthorExt::new(
+		client.clone(),
+		pool,
+		keystore.clone(),
+		submission_nonces,
+		deny_unsafe,
+	)));
+	io.extend_with(IntrospectionApi::to_delegate(Introspection::new(
+		message_introspection,
+		deny_unsafe,
+	)));
 	io.extend_with(sc_consensus_babe_rpc::BabeApi::to_delegate(BabeRpcHandler::new(
 		client.clone(),
 		shared_epoch_changes.clone(),
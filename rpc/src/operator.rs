@@ -0,0 +1,200 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Validator operator RPC.
+//!
+//! Privileged endpoints that allow fleet automation to manage a running validator
+//! (rotate session keys, adjust logging, drain block authoring) without restarting
+//! the node with a new set of CLI flags. All methods are considered unsafe.
+
+use std::sync::{
+	atomic::{AtomicBool, Ordering},
+	Arc,
+};
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use polkadot_primitives::v2::Block;
+use sc_rpc::DenyUnsafe;
+use sc_tracing::logging;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_keystore::{KeystoreExt, SyncCryptoStorePtr};
+use sp_runtime::generic::BlockId;
+use sp_session::SessionKeys;
+
+/// Base code for all operator errors.
+const BASE_ERROR: i64 = 9000;
+
+/// Shared switch that allows pausing block authoring of the node.
+///
+/// The switch is consulted by the block authoring worker before every slot, so pausing
+/// takes effect after the slot that is currently being claimed (if any) ends.
+#[derive(Clone, Default)]
+pub struct AuthoringSwitch(Arc<AtomicBool>);
+
+impl AuthoringSwitch {
+	/// Stop authoring blocks, starting from the next slot.
+	pub fn pause(&self) {
+		self.0.store(true, Ordering::SeqCst);
+	}
+
+	/// Resume authoring blocks.
+	pub fn resume(&self) {
+		self.0.store(false, Ordering::SeqCst);
+	}
+
+	/// Returns true if block authoring is paused.
+	pub fn is_paused(&self) -> bool {
+		self.0.load(Ordering::SeqCst)
+	}
+}
+
+/// Validator operator RPC methods.
+#[rpc]
+pub trait OperatorApi {
+	/// Generate a new set of session keys, store them in the node keystore and return
+	/// the SCALE-encoded public keys.
+	#[rpc(name = "operator_rotateKeys")]
+	fn rotate_keys(&self) -> Result<Bytes>;
+
+	/// Change the log level of a single target, e.g. `("parachain::dispute-coordinator", "trace")`.
+	#[rpc(name = "operator_setLogLevel")]
+	fn set_log_level(&self, target: String, level: String) -> Result<()>;
+
+	/// Reset all log levels to the values the node has been started with.
+	#[rpc(name = "operator_resetLogLevels")]
+	fn reset_log_levels(&self) -> Result<()>;
+
+	/// Stop authoring blocks after the current slot.
+	#[rpc(name = "operator_drain")]
+	fn drain(&self) -> Result<()>;
+
+	/// Resume authoring blocks after `operator_drain`.
+	#[rpc(name = "operator_resume")]
+	fn resume(&self) -> Result<()>;
+
+	/// Returns true if the node is drained (i.e. not authoring blocks).
+	#[rpc(name = "operator_isDrained")]
+	fn is_drained(&self) -> Result<bool>;
+}
+
+/// Implementation of the [`OperatorApi`].
+pub struct Operator<C> {
+	client: Arc<C>,
+	keystore: SyncCryptoStorePtr,
+	authoring_switch: AuthoringSwitch,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<C> Operator<C> {
+	/// Create new operator RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		keystore: SyncCryptoStorePtr,
+		authoring_switch: AuthoringSwitch,
+		deny_unsafe: DenyUnsafe,
+	) -> Self {
+		Operator { client, keystore, authoring_switch, deny_unsafe }
+	}
+}
+
+impl<C> OperatorApi for Operator<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: SessionKeys<Block>,
+{
+	fn rotate_keys(&self) -> Result<Bytes> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let best_block_hash = self.client.info().best_hash;
+		let mut runtime_api = self.client.runtime_api();
+		runtime_api.register_extension(KeystoreExt(self.keystore.clone()));
+		runtime_api
+			.generate_session_keys(&BlockId::Hash(best_block_hash), None)
+			.map(Into::into)
+			.map_err(|e| internal_error("Failed to generate session keys", e))
+	}
+
+	fn set_log_level(&self, target: String, level: String) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		if target.is_empty() || target.contains(|c: char| c == ',' || c == '=') {
+			return Err(RpcError::invalid_params(format!("Invalid log target: {}", target)))
+		}
+		if level.parse::<log::LevelFilter>().is_err() {
+			return Err(RpcError::invalid_params(format!("Invalid log level: {}", level)))
+		}
+
+		logging::add_directives(&format!("{}={}", target, level));
+		logging::reload_filter().map_err(|e| internal_error("Failed to reload log filter", e))
+	}
+
+	fn reset_log_levels(&self) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		logging::reset_log_filter().map_err(|e| internal_error("Failed to reset log filter", e))
+	}
+
+	fn drain(&self) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		log::info!(target: "rpc", "Block authoring has been paused by the node operator");
+		self.authoring_switch.pause();
+		Ok(())
+	}
+
+	fn resume(&self) -> Result<()> {
+		self.deny_unsafe.check_if_safe()?;
+
+		log::info!(target: "rpc", "Block authoring has been resumed by the node operator");
+		self.authoring_switch.resume();
+		Ok(())
+	}
+
+	fn is_drained(&self) -> Result<bool> {
+		self.deny_unsafe.check_if_safe()?;
+
+		Ok(self.authoring_switch.is_paused())
+	}
+}
+
+fn internal_error(message: &str, error: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(BASE_ERROR),
+		message: message.into(),
+		data: Some(format!("{:?}", error).into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn authoring_switch_is_shared_between_clones() {
+		let switch = AuthoringSwitch::default();
+		let rpc_side = switch.clone();
+		assert!(!switch.is_paused());
+
+		rpc_side.pause();
+		assert!(switch.is_paused());
+
+		rpc_side.resume();
+		assert!(!switch.is_paused());
+	}
+}
@@ -0,0 +1,51 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage keys of items that are audited by third parties.
+//!
+//! Used to implement the `AuditApi` runtime API. Staking keeps the per-era items for
+//! `HistoryDepth` eras, so a proof against a recent finalized header covers past eras as well.
+
+use pallet_staking::{
+	ActiveEra, CurrentEra, EraIndex, ErasStakers, ErasStakersClipped, ErasTotalStake,
+	ErasValidatorPrefs, ErasValidatorReward,
+};
+use sp_std::prelude::*;
+
+/// Storage keys of the staking items of the given era.
+///
+/// Covers the active and current era, all exposures and validator preferences of the era, and
+/// the total stake and validator reward of the era.
+pub fn staking_era_keys<T: pallet_staking::Config>(era: EraIndex) -> Vec<Vec<u8>> {
+	let mut keys =
+		vec![ActiveEra::<T>::hashed_key().to_vec(), CurrentEra::<T>::hashed_key().to_vec()];
+
+	keys.extend(
+		ErasStakers::<T>::iter_key_prefix(era).map(|v| ErasStakers::<T>::hashed_key_for(era, v)),
+	);
+	keys.extend(
+		ErasStakersClipped::<T>::iter_key_prefix(era)
+			.map(|v| ErasStakersClipped::<T>::hashed_key_for(era, v)),
+	);
+	keys.extend(
+		ErasValidatorPrefs::<T>::iter_key_prefix(era)
+			.map(|v| ErasValidatorPrefs::<T>::hashed_key_for(era, v)),
+	);
+	keys.push(ErasTotalStake::<T>::hashed_key_for(era));
+	keys.push(ErasValidatorReward::<T>::hashed_key_for(era));
+
+	keys
+}
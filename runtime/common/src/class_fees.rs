@@ -0,0 +1,430 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Independent fee multiplier for operational transactions.
+//!
+//! `pallet_transaction_payment` scales the weight fee of all transactions with a single multiplier,
+//! which follows how full the normal dispatch class of the previous blocks was. Operational
+//! transactions, e.g. bridge message deliveries or dispute votes, are included in the space
+//! reserved for them, so the fullness of the normal class says little about their demand.
+//!
+//! This pallet keeps a second multiplier for the operational class, adjusted towards a target
+//! fullness of that class, and [`ClassFeeAdapter`] charges operational transactions with it. Normal
+//! transactions are still charged with the multiplier of `pallet_transaction_payment`.
+
+use frame_support::{
+	traits::Get,
+	weights::{
+		DispatchClass, DispatchInfo, GetDispatchInfo, Pays, PostDispatchInfo, Weight,
+		WeightToFeePolynomial,
+	},
+};
+pub use pallet::*;
+use pallet_transaction_payment::{
+	BalanceOf, FeeDetails, Multiplier, OnChargeTransaction, RuntimeDispatchInfo,
+};
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Dispatchable, SaturatedConversion, Saturating},
+	transaction_validity::TransactionValidityError,
+	FixedPointNumber, FixedPointOperand, Perquintill,
+};
+use sp_std::marker::PhantomData;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// The portion of the operational class that we adjust the operational fees with. Blocks
+		/// with less operational weight than this decrease the multiplier, and more increase it.
+		#[pallet::constant]
+		type TargetBlockFullness: Get<Perquintill>;
+
+		/// How fast the operational multiplier changes.
+		#[pallet::constant]
+		type AdjustmentVariable: Get<Multiplier>;
+
+		/// Minimum value of the operational multiplier.
+		#[pallet::constant]
+		type MinimumMultiplier: Get<Multiplier>;
+	}
+
+	/// The multiplier of the weight fee of operational transactions in the next block.
+	///
+	/// Follows the multiplier of `pallet_transaction_payment` until it is first adjusted.
+	#[pallet::storage]
+	pub(crate) type NextOperationalFeeMultiplier<T> = StorageValue<_, Multiplier>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_now: T::BlockNumber) -> Weight {
+			// `on_finalize` reads the consumed weight and the multiplier, and writes the latter.
+			T::DbWeight::get().reads_writes(2, 1)
+		}
+
+		fn on_finalize(_now: T::BlockNumber) {
+			let next = Self::next_multiplier(Self::operational_fee_multiplier());
+			NextOperationalFeeMultiplier::<T>::put(next);
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The multiplier of the weight fee of operational transactions in the current block.
+	pub fn operational_fee_multiplier() -> Multiplier {
+		NextOperationalFeeMultiplier::<T>::get()
+			.unwrap_or_else(pallet_transaction_payment::Pallet::<T>::next_fee_multiplier)
+	}
+
+	/// Returns the fee of a transaction, given the `fee` computed for it by
+	/// `pallet_transaction_payment`.
+	///
+	/// The weight fee of operational transactions is scaled by the operational multiplier instead
+	/// of the multiplier of `pallet_transaction_payment`. Other fees are returned as is.
+	pub fn class_fee<B: AtLeast32BitUnsigned>(
+		class: DispatchClass,
+		pays_fee: Pays,
+		weight: Weight,
+		fee: B,
+	) -> B {
+		if class != DispatchClass::Operational || pays_fee != Pays::Yes {
+			return fee
+		}
+
+		// same as `pallet_transaction_payment`, which never charges more than a full block
+		let capped_weight = weight.min(T::BlockWeights::get().max_block);
+		let unadjusted_weight_fee: u128 = T::WeightToFee::calc(&capped_weight).saturated_into();
+		let normal_weight_fee = pallet_transaction_payment::Pallet::<T>::next_fee_multiplier()
+			.saturating_mul_int(unadjusted_weight_fee);
+		let operational_weight_fee =
+			Self::operational_fee_multiplier().saturating_mul_int(unadjusted_weight_fee);
+
+		fee.saturated_into::<u128>()
+			.saturating_sub(normal_weight_fee)
+			.saturating_add(operational_weight_fee)
+			.saturated_into()
+	}
+
+	/// Same as `pallet_transaction_payment::Pallet::query_info`, but with the fee the transaction
+	/// is actually charged.
+	pub fn query_info<Extrinsic: sp_runtime::traits::Extrinsic + GetDispatchInfo>(
+		unchecked_extrinsic: Extrinsic,
+		len: u32,
+	) -> RuntimeDispatchInfo<BalanceOf<T>>
+	where
+		BalanceOf<T>: FixedPointOperand,
+		T::Call: Dispatchable<Info = DispatchInfo>,
+	{
+		let info = unchecked_extrinsic.get_dispatch_info();
+		let mut dispatch_info =
+			pallet_transaction_payment::Pallet::<T>::query_info(unchecked_extrinsic, len);
+		dispatch_info.partial_fee =
+			Self::class_fee(info.class, info.pays_fee, info.weight, dispatch_info.partial_fee);
+		dispatch_info
+	}
+
+	/// Same as `pallet_transaction_payment::Pallet::query_fee_details`, but with the fee the
+	/// transaction is actually charged.
+	pub fn query_fee_details<Extrinsic: sp_runtime::traits::Extrinsic + GetDispatchInfo>(
+		unchecked_extrinsic: Extrinsic,
+		len: u32,
+	) -> FeeDetails<BalanceOf<T>>
+	where
+		BalanceOf<T>: FixedPointOperand,
+		T::Call: Dispatchable<Info = DispatchInfo>,
+	{
+		let info = unchecked_extrinsic.get_dispatch_info();
+		let mut fee_details =
+			pallet_transaction_payment::Pallet::<T>::query_fee_details(unchecked_extrinsic, len);
+		if let Some(ref mut inclusion_fee) = fee_details.inclusion_fee {
+			inclusion_fee.adjusted_weight_fee = Self::class_fee(
+				info.class,
+				info.pays_fee,
+				info.weight,
+				inclusion_fee.adjusted_weight_fee,
+			);
+		}
+		fee_details
+	}
+
+	/// Computes the next operational multiplier, the same way `TargetedFeeAdjustment` computes the
+	/// next multiplier from the fullness of the normal class.
+	fn next_multiplier(previous: Multiplier) -> Multiplier {
+		let weights = T::BlockWeights::get();
+		let max_weight =
+			weights.get(DispatchClass::Operational).max_total.unwrap_or(weights.max_block);
+		let block_weight = frame_system::Pallet::<T>::block_weight();
+		let block_weight = (*block_weight.get(DispatchClass::Operational)).min(max_weight) as u128;
+		let target_weight = (T::TargetBlockFullness::get() * max_weight) as u128;
+		let v = T::AdjustmentVariable::get();
+		let min_multiplier = T::MinimumMultiplier::get();
+
+		let positive = block_weight >= target_weight;
+		let diff_abs = block_weight.max(target_weight) - block_weight.min(target_weight);
+		let diff = Multiplier::saturating_from_rational(diff_abs, max_weight.max(1));
+		let diff_squared = diff.saturating_mul(diff);
+
+		let v_squared_2 = v.saturating_mul(v) / Multiplier::saturating_from_integer(2);
+		let first_term = v.saturating_mul(diff);
+		let second_term = v_squared_2.saturating_mul(diff_squared);
+
+		if positive {
+			let excess = first_term.saturating_add(second_term).saturating_mul(previous);
+			previous.saturating_add(excess).max(min_multiplier)
+		} else {
+			let negative = first_term.saturating_sub(second_term).saturating_mul(previous);
+			previous.saturating_sub(negative).max(min_multiplier)
+		}
+	}
+}
+
+/// Charges operational transactions with the operational multiplier of [`Pallet`] and passes the
+/// fees on to `OCT`, e.g. `CurrencyAdapter`.
+pub struct ClassFeeAdapter<T, OCT>(PhantomData<(T, OCT)>);
+
+impl<T, OCT> OnChargeTransaction<T> for ClassFeeAdapter<T, OCT>
+where
+	T: Config,
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+	OCT: OnChargeTransaction<T>,
+{
+	type Balance = OCT::Balance;
+	type LiquidityInfo = OCT::LiquidityInfo;
+
+	fn withdraw_fee(
+		who: &T::AccountId,
+		call: &T::Call,
+		info: &DispatchInfo,
+		fee: Self::Balance,
+		tip: Self::Balance,
+	) -> Result<Self::LiquidityInfo, TransactionValidityError> {
+		let fee = Pallet::<T>::class_fee(info.class, info.pays_fee, info.weight, fee);
+		OCT::withdraw_fee(who, call, info, fee, tip)
+	}
+
+	fn correct_and_deposit_fee(
+		who: &T::AccountId,
+		info: &DispatchInfo,
+		post_info: &PostDispatchInfo,
+		corrected_fee: Self::Balance,
+		tip: Self::Balance,
+		already_withdrawn: Self::LiquidityInfo,
+	) -> Result<(), TransactionValidityError> {
+		let corrected_fee = Pallet::<T>::class_fee(
+			info.class,
+			post_info.pays_fee(info),
+			post_info.calc_actual_weight(info),
+			corrected_fee,
+		);
+		OCT::correct_and_deposit_fee(who, info, post_info, corrected_fee, tip, already_withdrawn)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::class_fees;
+	use frame_support::{
+		parameter_types,
+		traits::{ConstU8, Hooks},
+		weights::IdentityFee,
+	};
+	use frame_system::limits;
+	use pallet_transaction_payment::CurrencyAdapter;
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		Perbill,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+			ClassFees: class_fees::{Pallet, Storage},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub BlockWeights: limits::BlockWeights =
+			limits::BlockWeights::with_sensible_defaults(1_000_000, Perbill::from_percent(75));
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = BlockWeights;
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	impl pallet_transaction_payment::Config for Test {
+		type OnChargeTransaction = ClassFeeAdapter<Test, CurrencyAdapter<Balances, ()>>;
+		type OperationalFeeMultiplier = ConstU8<5>;
+		type WeightToFee = IdentityFee<u64>;
+		type LengthToFee = IdentityFee<u64>;
+		type FeeMultiplierUpdate = ();
+	}
+
+	parameter_types! {
+		pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(10);
+		pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_integer(1);
+		pub MinimumMultiplier: Multiplier = Multiplier::saturating_from_rational(1, 10);
+	}
+
+	impl Config for Test {
+		type TargetBlockFullness = TargetBlockFullness;
+		type AdjustmentVariable = AdjustmentVariable;
+		type MinimumMultiplier = MinimumMultiplier;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> { balances: vec![(1, 1_000_000)] }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		t.into()
+	}
+
+	fn run_block(now: u64, operational_weight: Weight) {
+		System::initialize(&now, &Default::default(), &Default::default());
+		System::register_extra_weight_unchecked(operational_weight, DispatchClass::Operational);
+		ClassFees::on_finalize(now);
+	}
+
+	fn info(class: DispatchClass, pays_fee: Pays) -> DispatchInfo {
+		DispatchInfo { weight: 1_000, class, pays_fee }
+	}
+
+	#[test]
+	fn operational_multiplier_follows_operational_fullness() {
+		new_test_ext().execute_with(|| {
+			let normal = TransactionPayment::next_fee_multiplier();
+			assert_eq!(ClassFees::operational_fee_multiplier(), normal);
+
+			// the operational class is full, but the normal class is empty
+			run_block(1, 1_000_000);
+			let increased = ClassFees::operational_fee_multiplier();
+			assert!(increased > normal, "{:?} !> {:?}", increased, normal);
+			assert_eq!(TransactionPayment::next_fee_multiplier(), normal);
+
+			run_block(2, 0);
+			let decreased = ClassFees::operational_fee_multiplier();
+			assert!(decreased < increased, "{:?} !< {:?}", decreased, increased);
+
+			// the multiplier never drops below its minimum
+			for now in 3..100 {
+				run_block(now, 0);
+			}
+			assert_eq!(ClassFees::operational_fee_multiplier(), MinimumMultiplier::get());
+		});
+	}
+
+	#[test]
+	fn operational_transactions_are_charged_with_operational_multiplier() {
+		new_test_ext().execute_with(|| {
+			NextOperationalFeeMultiplier::<Test>::put(Multiplier::saturating_from_integer(3));
+			let call = Call::Balances(pallet_balances::Call::transfer { dest: 2, value: 1 });
+			let withdraw_fee = |info: &DispatchInfo, fee: u64| {
+				let before = Balances::free_balance(1);
+				<Test as pallet_transaction_payment::Config>::OnChargeTransaction::withdraw_fee(
+					&1, &call, info, fee, 0,
+				)
+				.unwrap();
+				before - Balances::free_balance(1)
+			};
+
+			// 10 of length fee and a weight fee of 1000, adjusted by a multiplier of 1
+			assert_eq!(withdraw_fee(&info(DispatchClass::Normal, Pays::Yes), 1_010), 1_010);
+			assert_eq!(withdraw_fee(&info(DispatchClass::Operational, Pays::Yes), 1_010), 3_010);
+			assert_eq!(withdraw_fee(&info(DispatchClass::Operational, Pays::No), 0), 0);
+
+			// refunds use the operational multiplier as well
+			let operational = info(DispatchClass::Operational, Pays::Yes);
+			let already_withdrawn =
+				<Test as pallet_transaction_payment::Config>::OnChargeTransaction::withdraw_fee(
+					&1,
+					&call,
+					&operational,
+					1_010,
+					0,
+				)
+				.unwrap();
+			let before = Balances::free_balance(1);
+			<Test as pallet_transaction_payment::Config>::OnChargeTransaction::correct_and_deposit_fee(
+				&1,
+				&operational,
+				&PostDispatchInfo { actual_weight: Some(500), pays_fee: Pays::Yes },
+				510,
+				0,
+				already_withdrawn,
+			)
+			.unwrap();
+			assert_eq!(Balances::free_balance(1) - before, 3_010 - 1_510);
+		});
+	}
+}
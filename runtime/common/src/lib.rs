@@ -20,13 +20,17 @@
 
 pub mod assigned_slots;
 pub mod auctions;
+pub mod audit;
 pub mod claims;
+pub mod class_fees;
 pub mod crowdloan;
 pub mod elections;
 pub mod impls;
 pub mod paras_registrar;
 pub mod paras_sudo_wrapper;
 pub mod purchase;
+pub mod reserved_space;
+pub mod sanity;
 pub mod slot_range;
 pub mod slots;
 pub mod traits;
@@ -79,6 +83,9 @@ parameter_types! {
 	/// The portion of the `NORMAL_DISPATCH_RATIO` that we adjust the fees with. Blocks filled less
 	/// than this will decrease the weight and more will increase.
 	pub const TargetBlockFullness: Perquintill = Perquintill::from_percent(25);
+	/// The portion of the block that we adjust the fees of `Operational` extrinsics with. Most
+	/// blocks don't contain any, so the target is a lot lower than `TargetBlockFullness`.
+	pub const OperationalTargetBlockFullness: Perquintill = Perquintill::from_percent(5);
 	/// The adjustment variable of the runtime. Higher values will cause `TargetBlockFullness` to
 	/// change the fees more rapidly.
 	pub AdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(3, 100_000);
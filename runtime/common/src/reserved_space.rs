@@ -0,0 +1,511 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block space reserved for classes of transactions.
+//!
+//! Some transactions must make it into blocks even when fees spike, e.g. bridge message
+//! deliveries keep the bridge alive. Governance may reserve a share of the block weight and
+//! length available to normal transactions for such a class of transactions. Reservations change
+//! at the start of the next era, which starts every `EraLength` blocks.
+//!
+//! [`ReserveBlockSpace`] boosts the priority of transactions of a class with a reservation by
+//! `PriorityBoost`. The boost is added to the priority other extensions derive from the fees, so
+//! block authors include these transactions before others with comparable fees, and still order
+//! them by their fees. Once the class has used its share of the block, further transactions of the
+//! class are left for the next block.
+//! The share the class leaves unused is released to the transactions that the author includes
+//! after them.
+//!
+//! Inherents, e.g. the parachains inherent carrying dispute statements, are not affected. They
+//! are included before all transactions anyway.
+
+use frame_support::{
+	traits::Get,
+	weights::{DispatchClass, DispatchInfo, Weight},
+};
+pub use pallet::*;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Dispatchable, SignedExtension, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	Perbill,
+};
+use sp_std::{fmt::Debug, marker::PhantomData};
+
+/// Determines the class of transactions that block space may be reserved for.
+pub trait ClassifyCall<Call, Class> {
+	/// Returns the class of the call, if it belongs to any.
+	fn reserved_class(call: &Call) -> Option<Class>;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Classes of transactions that block space may be reserved for.
+		type Class: Parameter + Copy + MaxEncodedLen;
+
+		/// Determines the class of calls.
+		type ClassifyCall: ClassifyCall<<Self as frame_system::Config>::Call, Self::Class>;
+
+		/// The origin that may reserve block space.
+		type ReserveOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Number of blocks in an era. Must not be zero.
+		#[pallet::constant]
+		type EraLength: Get<Self::BlockNumber>;
+
+		/// The maximal share of the normal block space reserved for all classes together.
+		#[pallet::constant]
+		type MaxReservedShare: Get<Perbill>;
+
+		/// The priority added to transactions of classes with reserved block space.
+		#[pallet::constant]
+		type PriorityBoost: Get<TransactionPriority>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The share of the block space reserved for the class changes with the next era.
+		ReservationScheduled(T::Class, Perbill),
+		/// The share of the block space reserved for the class has changed.
+		ReservationChanged(T::Class, Perbill),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The reservations of all classes would exceed `MaxReservedShare`.
+		TooMuchReserved,
+	}
+
+	/// Shares of the normal block space reserved for classes in the current era.
+	#[pallet::storage]
+	#[pallet::getter(fn reservation)]
+	pub(crate) type Reservations<T: Config> = StorageMap<_, Twox64Concat, T::Class, Perbill>;
+
+	/// Reservations that change with the next era. A zero share ends the reservation.
+	#[pallet::storage]
+	pub(crate) type NextReservations<T: Config> = StorageMap<_, Twox64Concat, T::Class, Perbill>;
+
+	/// Weight and length used by classes in the current block.
+	#[pallet::storage]
+	pub(crate) type BlockUsage<T: Config> =
+		StorageMap<_, Twox64Concat, T::Class, (Weight, u32), ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let cleared = match BlockUsage::<T>::remove_all(None) {
+				sp_io::KillStorageResult::AllRemoved(cleared) |
+				sp_io::KillStorageResult::SomeRemaining(cleared) => cleared,
+			};
+			let mut weight = T::DbWeight::get().writes(cleared.into());
+
+			if (now % T::EraLength::get()).is_zero() {
+				for (class, share) in NextReservations::<T>::drain() {
+					if share.is_zero() {
+						Reservations::<T>::remove(class);
+					} else {
+						Reservations::<T>::insert(class, share);
+					}
+					Self::deposit_event(Event::ReservationChanged(class, share));
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+				}
+			}
+			weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Reserve `share` of the block space available to normal transactions for the class,
+		/// starting with the next era. A zero `share` ends the reservation of the class.
+		///
+		/// There are only a few classes, so iterating over their reservations is cheap.
+		#[pallet::weight((T::DbWeight::get().reads_writes(4, 1), DispatchClass::Operational))]
+		pub fn set_reservation(
+			origin: OriginFor<T>,
+			class: T::Class,
+			share: Perbill,
+		) -> DispatchResult {
+			T::ReserveOrigin::ensure_origin(origin)?;
+
+			let reserved = Self::next_era_reservations()
+				.filter(|(other, _)| *other != class)
+				.fold(share.deconstruct() as u64, |reserved, (_, share)| {
+					reserved + share.deconstruct() as u64
+				});
+			ensure!(
+				reserved <= T::MaxReservedShare::get().deconstruct() as u64,
+				Error::<T>::TooMuchReserved,
+			);
+
+			NextReservations::<T>::insert(class, share);
+			Self::deposit_event(Event::ReservationScheduled(class, share));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the weight and length reserved for the class in every block of the current era.
+	pub fn reserved_space(class: T::Class) -> Option<(Weight, u32)> {
+		let share = Reservations::<T>::get(class)?;
+		let weights = T::BlockWeights::get();
+		let max_weight = weights.get(DispatchClass::Normal).max_total.unwrap_or(weights.max_block);
+		let max_length = *T::BlockLength::get().max.get(DispatchClass::Normal);
+		Some((share * max_weight, share * max_length))
+	}
+
+	/// Reservations of the next era, including the reservations that don't change.
+	fn next_era_reservations() -> impl Iterator<Item = (T::Class, Perbill)> {
+		let unchanged = Reservations::<T>::iter()
+			.filter(|(class, _)| !NextReservations::<T>::contains_key(class));
+		NextReservations::<T>::iter().chain(unchanged)
+	}
+
+	/// Returns the class of the transaction and the space reserved for it, if the transaction
+	/// fits into that space.
+	fn reserved_space_of(
+		call: &<T as frame_system::Config>::Call,
+		info: &DispatchInfo,
+		len: usize,
+	) -> Option<(T::Class, Weight, u32)> {
+		if info.class != DispatchClass::Normal {
+			return None
+		}
+
+		let class = T::ClassifyCall::reserved_class(call)?;
+		let (weight, length) = Self::reserved_space(class)?;
+		if info.weight > weight || len > length as usize {
+			return None
+		}
+		Some((class, weight, length))
+	}
+}
+
+/// Prioritizes transactions of classes with reserved block space and keeps every class within
+/// the space reserved for it.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, TypeInfo)]
+#[scale_info(skip_type_params(T))]
+pub struct ReserveBlockSpace<T: Config + Send + Sync>(PhantomData<T>);
+
+impl<T: Config + Send + Sync> Debug for ReserveBlockSpace<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ReserveBlockSpace")
+	}
+
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config + Send + Sync> ReserveBlockSpace<T> {
+	/// Create new `SignedExtension` to reserve block space.
+	pub fn new() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> SignedExtension for ReserveBlockSpace<T>
+where
+	<T as frame_system::Config>::Call: Dispatchable<Info = DispatchInfo>,
+{
+	type AccountId = T::AccountId;
+	type Call = <T as frame_system::Config>::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+	const IDENTIFIER: &'static str = "ReserveBlockSpace";
+
+	fn additional_signed(&self) -> Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		let priority = match Pallet::<T>::reserved_space_of(call, info, len) {
+			Some(_) => T::PriorityBoost::get(),
+			None => 0,
+		};
+		Ok(ValidTransaction { priority, ..Default::default() })
+	}
+
+	fn pre_dispatch(
+		self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		if let Some((class, reserved_weight, reserved_length)) =
+			Pallet::<T>::reserved_space_of(call, info, len)
+		{
+			BlockUsage::<T>::try_mutate(class, |(weight, length)| {
+				let new_weight = weight.saturating_add(info.weight);
+				let new_length = length.saturating_add(len as u32);
+				if new_weight > reserved_weight || new_length > reserved_length {
+					return Err(InvalidTransaction::ExhaustsResources)
+				}
+
+				*weight = new_weight;
+				*length = new_length;
+				Ok(())
+			})?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::reserved_space;
+	use frame_support::{assert_noop, assert_ok, parameter_types, traits::Hooks};
+	use frame_system::{limits, EnsureRoot};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+		DispatchError,
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			ReservedSpace: reserved_space::{Pallet, Call, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+		pub BlockWeights: limits::BlockWeights =
+			limits::BlockWeights::with_sensible_defaults(1_000_000, Perbill::from_percent(75));
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = BlockWeights;
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	/// Remarks are the only calls of class `0`.
+	pub struct ClassifyRemarks;
+
+	impl ClassifyCall<Call, u8> for ClassifyRemarks {
+		fn reserved_class(call: &Call) -> Option<u8> {
+			match call {
+				Call::System(frame_system::Call::remark { .. }) => Some(0),
+				_ => None,
+			}
+		}
+	}
+
+	parameter_types! {
+		pub const EraLength: u64 = 10;
+		pub const MaxReservedShare: Perbill = Perbill::from_percent(50);
+		pub const PriorityBoost: TransactionPriority = 1_000;
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Class = u8;
+		type ClassifyCall = ClassifyRemarks;
+		type ReserveOrigin = EnsureRoot<u64>;
+		type EraLength = EraLength;
+		type MaxReservedShare = MaxReservedShare;
+		type PriorityBoost = PriorityBoost;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		frame_system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	}
+
+	fn run_to_block(now: u64) {
+		System::set_block_number(now);
+		ReservedSpace::on_initialize(now);
+	}
+
+	#[test]
+	fn reservations_change_with_the_next_era() {
+		new_test_ext().execute_with(|| {
+			let reserve = |class, percent| {
+				ReservedSpace::set_reservation(
+					Origin::root(),
+					class,
+					Perbill::from_percent(percent),
+				)
+			};
+
+			assert_noop!(
+				ReservedSpace::set_reservation(Origin::signed(1), 0, Perbill::from_percent(10)),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(reserve(0, 10));
+			assert_ok!(reserve(1, 40));
+			assert_noop!(reserve(2, 1), Error::<Test>::TooMuchReserved);
+			// the scheduled reservation of a class may be replaced
+			assert_ok!(reserve(1, 30));
+			assert_eq!(ReservedSpace::reservation(0), None);
+
+			run_to_block(9);
+			assert_eq!(ReservedSpace::reservation(0), None);
+			run_to_block(10);
+			assert_eq!(ReservedSpace::reservation(0), Some(Perbill::from_percent(10)));
+			assert_eq!(ReservedSpace::reservation(1), Some(Perbill::from_percent(30)));
+
+			// the reservation of class `0` ends with the next era, so its share is available
+			assert_ok!(reserve(0, 0));
+			assert_noop!(reserve(2, 21), Error::<Test>::TooMuchReserved);
+			assert_ok!(reserve(2, 20));
+
+			run_to_block(20);
+			assert_eq!(ReservedSpace::reservation(0), None);
+			assert_eq!(ReservedSpace::reservation(1), Some(Perbill::from_percent(30)));
+			assert_eq!(ReservedSpace::reservation(2), Some(Perbill::from_percent(20)));
+		});
+	}
+
+	#[test]
+	fn reserved_transactions_are_prioritized_within_their_reservation() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(ReservedSpace::set_reservation(
+				Origin::root(),
+				0,
+				Perbill::from_percent(10),
+			));
+			run_to_block(10);
+
+			let (weight, length) = ReservedSpace::reserved_space(0).unwrap();
+			let remark = Call::System(frame_system::Call::remark { remark: vec![] });
+			let other = Call::System(frame_system::Call::remark_with_event { remark: vec![] });
+			let info = |weight| DispatchInfo { weight, ..Default::default() };
+			let priority = |call, weight, len| {
+				ReserveBlockSpace::<Test>::new()
+					.validate(&1, call, &info(weight), len)
+					.unwrap()
+					.priority
+			};
+			let pre_dispatch = |call, weight, len| {
+				ReserveBlockSpace::<Test>::new().pre_dispatch(&1, call, &info(weight), len)
+			};
+
+			assert_eq!(priority(&remark, weight / 2, 10), PriorityBoost::get());
+			assert_eq!(priority(&other, weight / 2, 10), 0);
+			// transactions that don't fit into the reservation are not prioritized
+			assert_eq!(priority(&remark, weight + 1, 10), 0);
+			assert_eq!(priority(&remark, 1, length as usize + 1), 0);
+
+			// the class is kept within its reservation
+			assert_ok!(pre_dispatch(&remark, weight / 2, 10));
+			assert_ok!(pre_dispatch(&remark, weight / 2, 10));
+			assert_eq!(
+				pre_dispatch(&remark, 1, 10),
+				Err(InvalidTransaction::ExhaustsResources.into()),
+			);
+			assert_ok!(pre_dispatch(&other, weight, 10));
+
+			// the reservation is renewed with every block
+			run_to_block(11);
+			assert_ok!(pre_dispatch(&remark, 1, 10));
+		});
+	}
+
+	#[test]
+	fn reserved_transactions_are_still_ordered_by_fees() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(ReservedSpace::set_reservation(
+				Origin::root(),
+				0,
+				Perbill::from_percent(10),
+			));
+			run_to_block(10);
+
+			let remark = Call::System(frame_system::Call::remark { remark: vec![] });
+			let other = Call::System(frame_system::Call::remark_with_event { remark: vec![] });
+			let info = DispatchInfo { weight: 1, ..Default::default() };
+			// the priority of a transaction, given the priority derived from its fee
+			let priority = |call, fee_priority| {
+				ValidTransaction { priority: fee_priority, ..Default::default() }
+					.combine_with(
+						ReserveBlockSpace::<Test>::new().validate(&1, call, &info, 10).unwrap(),
+					)
+					.priority
+			};
+
+			// reserved transactions go before others with comparable fees
+			assert!(priority(&remark, 10) > priority(&other, 500));
+			// ... and are still ordered by their fees
+			assert!(priority(&remark, 20) > priority(&remark, 10));
+			// ... but others paying far more go first
+			assert!(priority(&other, 2_000) > priority(&remark, 10));
+			// the boost saturates
+			assert_eq!(
+				priority(&remark, TransactionPriority::max_value()),
+				TransactionPriority::max_value()
+			);
+		});
+	}
+}
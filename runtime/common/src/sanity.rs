@@ -0,0 +1,272 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Per-block sanity checks of invariants spanning several pallets.
+//!
+//! Meant for test networks. The checks only run in runtimes built with debug assertions, at the
+//! end of every block. A violated invariant is logged and reported with the `SanityCheckFailed`
+//! event, so that the network keeps producing blocks while it is investigated. The checks only
+//! read storage, so the resulting state is the same whether they run or not, but their cost isn't
+//! accounted for in the block weight.
+//!
+//! With the `try-runtime` feature, a violated invariant aborts the block, and the checks also run
+//! against the live state after runtime upgrades, regardless of debug assertions.
+
+use frame_support::{
+	ensure,
+	storage::StoragePrefixedMap,
+	traits::{Currency, Get},
+};
+pub use pallet::*;
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::{marker::PhantomData, prelude::*};
+
+const LOG_TARGET: &str = "runtime::sanity";
+
+/// An invariant that is checked at the end of every block.
+pub trait SanityCheck<BlockNumber> {
+	/// Check the invariant at the end of block `now`.
+	fn check(now: BlockNumber) -> Result<(), &'static str>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(8)]
+impl<BlockNumber: Copy> SanityCheck<BlockNumber> for Tuple {
+	fn check(now: BlockNumber) -> Result<(), &'static str> {
+		for_tuples!( #( Tuple::check(now)?; )* );
+		Ok(())
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Invariants checked at the end of every block.
+		type Checks: SanityCheck<Self::BlockNumber>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An invariant is violated at the end of the block.
+		SanityCheckFailed(Vec<u8>),
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_finalize(now: T::BlockNumber) {
+			if !cfg!(debug_assertions) {
+				return
+			}
+
+			if let Err(e) = T::Checks::check(now) {
+				log::error!(target: LOG_TARGET, "Sanity check failed at block {:?}: {}", now, e);
+				Self::deposit_event(Event::SanityCheckFailed(e.as_bytes().to_vec()));
+
+				#[cfg(feature = "try-runtime")]
+				panic!("Sanity check failed: {}", e);
+			}
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			T::Checks::check(frame_system::Pallet::<T>::block_number())
+		}
+	}
+}
+
+/// Total issuance covers the balances of a sample of accounts.
+///
+/// The sample is made of up to `Sample` consecutive accounts, starting at a position in the
+/// account map derived from the parent hash, so that all accounts are covered over time.
+pub struct TotalIssuanceCoversSample<T, Sample>(PhantomData<(T, Sample)>);
+
+impl<T, Sample> SanityCheck<T::BlockNumber> for TotalIssuanceCoversSample<T, Sample>
+where
+	T: pallet_balances::Config,
+	Sample: Get<u32>,
+{
+	fn check(_now: T::BlockNumber) -> Result<(), &'static str> {
+		let mut start = frame_system::Account::<T>::final_prefix().to_vec();
+		start.extend_from_slice(frame_system::Pallet::<T>::parent_hash().as_ref());
+
+		let sampled = frame_system::Account::<T>::iter_keys_from(start)
+			.take(Sample::get() as usize)
+			.fold(T::Balance::zero(), |sampled, who| {
+				sampled.saturating_add(pallet_balances::Pallet::<T>::total_balance(&who))
+			});
+		ensure!(
+			sampled <= pallet_balances::Pallet::<T>::total_issuance(),
+			"Total issuance is lower than the balance of sampled accounts",
+		);
+		Ok(())
+	}
+}
+
+/// Scheduler agendas only exist for blocks that haven't been executed yet.
+///
+/// The agenda of a block is taken when the block is initialized, so an agenda of the current or
+/// of a past block means that scheduled calls are lost.
+pub struct NoStaleSchedulerAgendas<T>(PhantomData<T>);
+
+impl<T: pallet_scheduler::Config> SanityCheck<T::BlockNumber> for NoStaleSchedulerAgendas<T> {
+	fn check(now: T::BlockNumber) -> Result<(), &'static str> {
+		ensure!(
+			pallet_scheduler::Agenda::<T>::iter_keys().all(|when| when > now),
+			"Scheduler agenda of an executed block has not been serviced",
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::sanity;
+	use frame_support::{
+		parameter_types,
+		traits::{GenesisBuild, Hooks},
+	};
+	use sp_core::H256;
+	use sp_runtime::{
+		testing::Header,
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	frame_support::construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+			Sanity: sanity::{Pallet, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u32 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = frame_support::traits::Everything;
+		type BlockWeights = ();
+		type BlockLength = ();
+		type DbWeight = ();
+		type Origin = Origin;
+		type Call = Call;
+		type Index = u64;
+		type BlockNumber = u64;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<u64>;
+		type Header = Header;
+		type Event = Event;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = pallet_balances::AccountData<u64>;
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = frame_support::traits::ConstU32<16>;
+	}
+
+	parameter_types! {
+		pub const ExistentialDeposit: u64 = 1;
+		pub const Sample: u32 = 16;
+	}
+
+	impl pallet_balances::Config for Test {
+		type Balance = u64;
+		type Event = Event;
+		type DustRemoval = ();
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = System;
+		type MaxLocks = ();
+		type MaxReserves = ();
+		type ReserveIdentifier = [u8; 8];
+		type WeightInfo = ();
+	}
+
+	impl Config for Test {
+		type Event = Event;
+		type Checks = TotalIssuanceCoversSample<Test, Sample>;
+	}
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+		pallet_balances::GenesisConfig::<Test> {
+			balances: (1..=10).map(|who| (who, 100)).collect(),
+		}
+		.assimilate_storage(&mut t)
+		.unwrap();
+		let mut ext = sp_io::TestExternalities::new(t);
+		// start sampling at the first account
+		ext.execute_with(|| System::set_parent_hash(H256::zero()));
+		ext
+	}
+
+	#[test]
+	fn total_issuance_check_works() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(<Test as Config>::Checks::check(1), Ok(()));
+
+			pallet_balances::TotalIssuance::<Test>::put(999);
+			assert!(<Test as Config>::Checks::check(1).is_err());
+
+			// accounts beyond the sample aren't checked
+			System::set_parent_hash(H256::repeat_byte(0xff));
+			assert_eq!(<Test as Config>::Checks::check(1), Ok(()));
+		});
+	}
+
+	#[test]
+	#[cfg(not(feature = "try-runtime"))]
+	fn failed_check_is_reported() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			Sanity::on_finalize(1);
+			assert!(System::events().is_empty());
+
+			pallet_balances::TotalIssuance::<Test>::put(0);
+			Sanity::on_finalize(1);
+			System::assert_last_event(
+				sanity::Event::SanityCheckFailed(
+					b"Total issuance is lower than the balance of sampled accounts".to_vec(),
+				)
+				.into(),
+			);
+		});
+	}
+}
@@ -16,13 +16,17 @@
 
 //! Configuration manager for the Polkadot runtime parachains logic.
 //!
-//! Configuration can change only at session boundaries and is buffered until then.
+//! Configuration can change only at session boundaries and is buffered until then. Feature bits
+//! are the exception: they change at the block governance has scheduled the change for.
 
 use crate::shared;
 use frame_support::{pallet_prelude::*, weights::constants::WEIGHT_PER_MILLIS};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v2::{Balance, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE, MAX_POV_SIZE};
+use primitives::v2::{
+	Balance, FeatureBits, FeatureIndex, SessionIndex, MAX_CODE_SIZE, MAX_HEAD_DATA_SIZE,
+	MAX_POV_SIZE,
+};
 use sp_runtime::traits::Zero;
 use sp_std::prelude::*;
 
@@ -502,6 +506,16 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(crate) type BypassConsistencyCheck<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	/// Features activated on the network.
+	#[pallet::storage]
+	#[pallet::getter(fn feature_bits)]
+	pub(crate) type ActiveFeatureBits<T: Config> = StorageValue<_, FeatureBits, ValueQuery>;
+
+	/// Feature changes scheduled for a block, applied in order at the start of the block.
+	#[pallet::storage]
+	pub(crate) type ScheduledFeatureChanges<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(FeatureIndex, bool)>, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		pub config: HostConfiguration<T::BlockNumber>,
@@ -1116,6 +1130,25 @@ pub mod pallet {
 			<Self as Store>::BypassConsistencyCheck::put(new);
 			Ok(())
 		}
+
+		/// Activate or deactivate the feature with the given index at the start of block `at`.
+		///
+		/// Changes scheduled for the same block are applied in the order they were scheduled in.
+		#[pallet::weight((
+			T::DbWeight::get().reads_writes(1, 1),
+			DispatchClass::Operational,
+		))]
+		pub fn set_feature_bit(
+			origin: OriginFor<T>,
+			index: FeatureIndex,
+			enabled: bool,
+			at: T::BlockNumber,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(at > <frame_system::Pallet<T>>::block_number(), Error::<T>::InvalidNewValue);
+			<Self as Store>::ScheduledFeatureChanges::append(at, (index, enabled));
+			Ok(())
+		}
 	}
 
 	#[pallet::hooks]
@@ -1146,8 +1179,20 @@ pub struct SessionChangeOutcome<BlockNumber> {
 
 impl<T: Config> Pallet<T> {
 	/// Called by the initializer to initialize the configuration pallet.
-	pub(crate) fn initializer_initialize(_now: T::BlockNumber) -> Weight {
-		0
+	///
+	/// Applies the feature changes scheduled for the block.
+	pub(crate) fn initializer_initialize(now: T::BlockNumber) -> Weight {
+		let changes = <Self as Store>::ScheduledFeatureChanges::take(now);
+		if changes.is_empty() {
+			return T::DbWeight::get().reads(1)
+		}
+
+		<Self as Store>::ActiveFeatureBits::mutate(|bits| {
+			for (index, enabled) in changes {
+				bits.set(index, enabled);
+			}
+		});
+		T::DbWeight::get().reads_writes(2, 2)
 	}
 
 	/// Called by the initializer to finalize the configuration pallet.
@@ -1193,6 +1238,11 @@ impl<T: Config> Pallet<T> {
 		SessionChangeOutcome { prev_config, new_config }
 	}
 
+	/// Returns true if the feature with the given index is activated.
+	pub fn is_feature_enabled(index: FeatureIndex) -> bool {
+		Self::feature_bits().is_enabled(index)
+	}
+
 	/// Return the session index that should be used for any future scheduled changes.
 	fn scheduled_session() -> SessionIndex {
 		shared::Pallet::<T>::scheduled_session()
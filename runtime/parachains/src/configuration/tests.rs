@@ -15,7 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use super::*;
-use crate::mock::{new_test_ext, Configuration, Origin, ParasShared, Test};
+use crate::mock::{new_test_ext, Configuration, Origin, ParasShared, System, Test};
 use frame_support::{assert_err, assert_ok};
 
 fn on_new_session(session_index: SessionIndex) -> (HostConfiguration<u32>, HostConfiguration<u32>) {
@@ -515,6 +515,36 @@ fn non_root_cannot_set_config() {
 	});
 }
 
+#[test]
+fn feature_bits_change_at_scheduled_block() {
+	new_test_ext(Default::default()).execute_with(|| {
+		System::set_block_number(1);
+		assert_err!(
+			Configuration::set_feature_bit(Origin::root(), 3, true, 1),
+			Error::<Test>::InvalidNewValue,
+		);
+		assert!(Configuration::set_feature_bit(Origin::signed(1), 3, true, 2).is_err());
+
+		assert_ok!(Configuration::set_feature_bit(Origin::root(), 3, true, 3));
+		assert_ok!(Configuration::set_feature_bit(Origin::root(), 10, true, 3));
+		assert_ok!(Configuration::set_feature_bit(Origin::root(), 10, false, 3));
+		assert_ok!(Configuration::set_feature_bit(Origin::root(), 3, false, 5));
+
+		Configuration::initializer_initialize(2);
+		assert!(!Configuration::is_feature_enabled(3));
+
+		// changes scheduled for the same block are applied in order
+		Configuration::initializer_initialize(3);
+		assert!(Configuration::is_feature_enabled(3));
+		assert!(!Configuration::is_feature_enabled(10));
+		assert_eq!(Configuration::feature_bits().0.len(), 11);
+
+		Configuration::initializer_initialize(5);
+		assert!(!Configuration::is_feature_enabled(3));
+		assert_eq!(<Configuration as Store>::ScheduledFeatureChanges::iter().count(), 0);
+	});
+}
+
 #[test]
 fn verify_externally_accessible() {
 	// This test verifies that the value can be accessed through the well known keys and the
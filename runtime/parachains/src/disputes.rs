@@ -52,6 +52,17 @@ pub enum DisputeLocation {
 	Remote,
 }
 
+/// Record of a dispute amnesty granted by governance.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct AmnestyRecord<BlockNumber> {
+	/// The block in which the amnesty has been granted.
+	pub granted_at: BlockNumber,
+	/// The disputes which have been cleared.
+	pub disputes: Vec<(SessionIndex, CandidateHash)>,
+	/// All candidates included up to and including this block have been approved by force.
+	pub force_approved_up_to: BlockNumber,
+}
+
 /// The result of a dispute, whether the candidate is deemed valid (for) or invalid (against).
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 pub enum DisputeResult {
@@ -273,7 +284,7 @@ pub trait DisputesHandler<BlockNumber: Ord> {
 	fn initializer_finalize();
 
 	/// Called by the initializer to note that a new session has started.
-	fn initializer_on_new_session(notification: &SessionChangeNotification<BlockNumber>);
+	fn initializer_on_new_session(notification: &SessionChangeNotification<BlockNumber>) -> Weight;
 }
 
 impl<BlockNumber: Ord> DisputesHandler<BlockNumber> for () {
@@ -327,7 +338,11 @@ impl<BlockNumber: Ord> DisputesHandler<BlockNumber> for () {
 
 	fn initializer_finalize() {}
 
-	fn initializer_on_new_session(_notification: &SessionChangeNotification<BlockNumber>) {}
+	fn initializer_on_new_session(
+		_notification: &SessionChangeNotification<BlockNumber>,
+	) -> Weight {
+		0
+	}
 }
 
 impl<T: Config> DisputesHandler<T::BlockNumber> for pallet::Pallet<T>
@@ -386,13 +401,31 @@ where
 		pallet::Pallet::<T>::initializer_finalize()
 	}
 
-	fn initializer_on_new_session(notification: &SessionChangeNotification<T::BlockNumber>) {
+	fn initializer_on_new_session(
+		notification: &SessionChangeNotification<T::BlockNumber>,
+	) -> Weight {
 		pallet::Pallet::<T>::initializer_on_new_session(notification)
 	}
 }
 
 pub trait WeightInfo {
 	fn force_unfreeze() -> Weight;
+
+	/// PLACEHOLDER: the `grant_amnesty` benchmark hasn't been run yet, so the generated runtime
+	/// weights don't implement this method. Until they are regenerated, the weight is estimated
+	/// from the storage accesses of the call at `RocksDbWeight` plus one millisecond of
+	/// computation per cleared dispute, which is far more than the call needs.
+	fn grant_amnesty(d: u32) -> Weight {
+		use frame_support::weights::constants::{RocksDbWeight, WEIGHT_PER_MILLIS};
+
+		let d = d as Weight;
+		RocksDbWeight::get()
+			.reads_writes(
+				d.saturating_mul(3).saturating_add(2),
+				d.saturating_mul(3).saturating_add(3),
+			)
+			.saturating_add(d.saturating_add(1).saturating_mul(WEIGHT_PER_MILLIS))
+	}
 }
 
 pub struct TestWeightInfo;
@@ -400,6 +433,9 @@ impl WeightInfo for TestWeightInfo {
 	fn force_unfreeze() -> Weight {
 		0
 	}
+	fn grant_amnesty(_d: u32) -> Weight {
+		0
+	}
 }
 
 pub use pallet::*;
@@ -414,6 +450,9 @@ pub mod pallet {
 		type RewardValidators: RewardValidators;
 		type PunishValidators: PunishValidators;
 
+		/// Origin allowed to grant dispute amnesties.
+		type AmnestyOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
 		/// Weight information for extrinsics in this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -466,6 +505,23 @@ pub mod pallet {
 	#[pallet::getter(fn last_valid_block)]
 	pub(super) type Frozen<T: Config> = StorageValue<_, Option<T::BlockNumber>, ValueQuery>;
 
+	/// All dispute amnesties granted by governance, by amnesty index. These are never pruned.
+	#[pallet::storage]
+	#[pallet::getter(fn amnesties)]
+	pub(super) type Amnesties<T: Config> =
+		StorageMap<_, Twox64Concat, u32, AmnestyRecord<T::BlockNumber>>;
+
+	/// The number of dispute amnesties granted so far, which is also the index of the next one.
+	#[pallet::storage]
+	pub(super) type AmnestyCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	/// Disputes cleared by an amnesty for the last several sessions, mapped to the amnesty index.
+	///
+	/// Further votes on these disputes are ignored.
+	#[pallet::storage]
+	pub(super) type Amnestied<T> =
+		StorageDoubleMap<_, Twox64Concat, SessionIndex, Blake2_128Concat, CandidateHash, u32>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub fn deposit_event)]
 	pub enum Event<T: Config> {
@@ -482,6 +538,8 @@ pub mod pallet {
 		/// instead revert the block at the given height. This should be the
 		/// number of the child of the last known valid block in the chain.
 		Revert(T::BlockNumber),
+		/// Governance cleared a set of disputes. \[amnesty index\]
+		AmnestyGranted(u32),
 	}
 
 	#[pallet::error]
@@ -500,6 +558,12 @@ pub mod pallet {
 		PotentialSpam,
 		/// A dispute where there are only votes on one side.
 		SingleSidedDispute,
+		/// An amnesty has to clear at least one dispute.
+		EmptyAmnesty,
+		/// The dispute to clear is unknown.
+		DisputeNotFound,
+		/// The dispute to clear has already concluded.
+		DisputeNotActive,
 	}
 
 	#[pallet::call]
@@ -510,6 +574,67 @@ pub mod pallet {
 			Frozen::<T>::set(None);
 			Ok(())
 		}
+
+		/// Clear the given active disputes and approve all candidates included up to and
+		/// including block `up_to` by force.
+		///
+		/// This is an emergency measure for spurious disputes, e.g. caused by a bug in the node,
+		/// stalling finality. The amnesty is recorded in `Amnesties` and further votes on the
+		/// cleared disputes are ignored.
+		#[pallet::weight(<T as Config>::WeightInfo::grant_amnesty(disputes.len() as u32))]
+		pub fn grant_amnesty(
+			origin: OriginFor<T>,
+			mut disputes: Vec<(SessionIndex, CandidateHash)>,
+			up_to: T::BlockNumber,
+		) -> DispatchResult {
+			T::AmnestyOrigin::ensure_origin(origin)?;
+
+			disputes.sort();
+			disputes.dedup();
+			ensure!(!disputes.is_empty(), Error::<T>::EmptyAmnesty);
+
+			let mut states = Vec::with_capacity(disputes.len());
+			for (session, candidate_hash) in &disputes {
+				let dispute = <Disputes<T>>::get(session, candidate_hash)
+					.ok_or(Error::<T>::DisputeNotFound)?;
+				ensure!(dispute.concluded_at.is_none(), Error::<T>::DisputeNotActive);
+				states.push(dispute);
+			}
+
+			let index = AmnestyCount::<T>::mutate(|count| {
+				let index = *count;
+				*count = count.saturating_add(1);
+				index
+			});
+
+			for ((session, candidate_hash), dispute) in disputes.iter().zip(states) {
+				<Disputes<T>>::remove(session, candidate_hash);
+				<Amnestied<T>>::insert(session, candidate_hash, index);
+
+				// Local disputes don't count towards spam.
+				if !<Included<T>>::contains_key(session, candidate_hash) {
+					SpamSlots::<T>::mutate(session, |spam_slots| {
+						if let Some(spam_slots) = spam_slots {
+							decrement_spam(spam_slots, &dispute);
+						}
+					});
+				}
+			}
+
+			Amnesties::<T>::insert(
+				index,
+				AmnestyRecord {
+					granted_at: <frame_system::Pallet<T>>::block_number(),
+					disputes,
+					force_approved_up_to: up_to,
+				},
+			);
+			frame_system::Pallet::<T>::deposit_log(
+				ConsensusLog::ForceApprove(up_to.saturated_into()).into(),
+			);
+			Self::deposit_event(Event::AmnestyGranted(index));
+			Ok(())
+		}
 	}
 }
 
@@ -832,13 +957,16 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn initializer_finalize() {}
 
 	/// Called by the initializer to note a new session in the disputes pallet.
+	///
+	/// Returns the weight of pruning the sessions which left the dispute period.
 	pub(crate) fn initializer_on_new_session(
 		notification: &SessionChangeNotification<T::BlockNumber>,
-	) {
+	) -> Weight {
 		let config = <configuration::Pallet<T>>::config();
+		let mut weight = T::DbWeight::get().reads(1);
 
 		if notification.session_index <= config.dispute_period + 1 {
-			return
+			return weight
 		}
 
 		let pruning_target = notification.session_index - config.dispute_period - 1;
@@ -850,18 +978,28 @@ impl<T: Config> Pallet<T> {
 				pruning_target..=pruning_target
 			};
 
+			let removed = |result: sp_io::KillStorageResult| match result {
+				sp_io::KillStorageResult::AllRemoved(removed) |
+				sp_io::KillStorageResult::SomeRemaining(removed) => removed as Weight,
+			};
 			for to_prune in to_prune {
 				// This should be small, as disputes are rare, so `None` is fine.
-				<Disputes<T>>::remove_prefix(to_prune, None);
+				let mut pruned = removed(<Disputes<T>>::remove_prefix(to_prune, None));
 
 				// This is larger, and will be extracted to the `shared` pallet for more proper pruning.
 				// TODO: https://github.com/paritytech/polkadot/issues/3469
-				<Included<T>>::remove_prefix(to_prune, None);
+				pruned += removed(<Included<T>>::remove_prefix(to_prune, None));
+				pruned += removed(<Amnestied<T>>::remove_prefix(to_prune, None));
 				SpamSlots::<T>::remove(to_prune);
+
+				// every removed key has been read by the prefix iteration
+				weight = weight.saturating_add(T::DbWeight::get().reads_writes(pruned, pruned + 1));
 			}
 
 			*last_pruned = Some(pruning_target);
 		});
+
+		weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
 	}
 
 	/// Handle sets of dispute statements corresponding to 0 or more candidates.
@@ -914,6 +1052,11 @@ impl<T: Config> Pallet<T> {
 		let now = <frame_system::Pallet<T>>::block_number();
 		let oldest_accepted = now.saturating_sub(post_conclusion_acceptance_period);
 
+		// Votes on disputes cleared by governance are ignored.
+		if <Amnestied<T>>::contains_key(&set.session, &set.candidate_hash) {
+			return StatementSetFilter::RemoveAll
+		}
+
 		// Load session info to access validators
 		let session_info = match <session_info::Pallet<T>>::session_info(set.session) {
 			Some(s) => s,
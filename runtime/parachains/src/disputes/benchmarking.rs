@@ -18,7 +18,7 @@ use super::*;
 
 use frame_benchmarking::benchmarks;
 use frame_system::RawOrigin;
-use sp_runtime::traits::One;
+use sp_runtime::traits::{One, Zero};
 
 benchmarks! {
 	force_unfreeze {
@@ -28,6 +28,24 @@ benchmarks! {
 		assert!(Frozen::<T>::get().is_none())
 	}
 
+	grant_amnesty {
+		let d in 1 .. 100;
+		let disputes: Vec<_> = (0..d)
+			.map(|i| (0, CandidateHash(sp_core::H256::from_low_u64_be(i as u64))))
+			.collect();
+		for (session, candidate_hash) in &disputes {
+			<Disputes<T>>::insert(session, candidate_hash, DisputeState {
+				validators_for: bitvec![u8, BitOrderLsb0; 0; 1],
+				validators_against: bitvec![u8, BitOrderLsb0; 0; 1],
+				start: Zero::zero(),
+				concluded_at: None,
+			});
+		}
+	}: _(RawOrigin::Root, disputes, One::one())
+	verify {
+		assert!(Amnesties::<T>::get(0).is_some())
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext(Default::default()),
@@ -19,9 +19,9 @@ use crate::{
 	configuration::HostConfiguration,
 	disputes::DisputesHandler,
 	mock::{
-		new_test_ext, AccountId, AllPalletsWithSystem, Initializer, MockGenesisConfig, System,
-		Test, PUNISH_VALIDATORS_AGAINST, PUNISH_VALIDATORS_FOR, PUNISH_VALIDATORS_INCONCLUSIVE,
-		REWARD_VALIDATORS,
+		new_test_ext, AccountId, AllPalletsWithSystem, Initializer, MockGenesisConfig, Origin,
+		System, Test, PUNISH_VALIDATORS_AGAINST, PUNISH_VALIDATORS_FOR,
+		PUNISH_VALIDATORS_INCONCLUSIVE, REWARD_VALIDATORS,
 	},
 };
 use frame_support::{
@@ -1150,6 +1150,88 @@ fn test_revert_and_freeze_merges() {
 	})
 }
 
+#[test]
+fn test_grant_amnesty() {
+	new_test_ext(Default::default()).execute_with(|| {
+		// events are ignored for genesis block
+		System::set_block_number(1);
+
+		let candidate_hash_a = CandidateHash(sp_core::H256::repeat_byte(1));
+		let candidate_hash_b = CandidateHash(sp_core::H256::repeat_byte(2));
+		let candidate_hash_c = CandidateHash(sp_core::H256::repeat_byte(3));
+
+		// 7 validators for the byzantine threshold to be 2, so the dispute is unconfirmed.
+		<Disputes<Test>>::insert(
+			&1,
+			&candidate_hash_a,
+			DisputeState {
+				validators_for: bitvec![u8, BitOrderLsb0; 1, 0, 0, 0, 0, 0, 0],
+				validators_against: bitvec![u8, BitOrderLsb0; 0, 1, 0, 0, 0, 0, 0],
+				start: 0,
+				concluded_at: None,
+			},
+		);
+		<Disputes<Test>>::insert(
+			&1,
+			&candidate_hash_b,
+			DisputeState {
+				validators_for: bitvec![u8, BitOrderLsb0; 1, 0, 0, 0, 0, 0, 0],
+				validators_against: bitvec![u8, BitOrderLsb0; 0, 1, 1, 1, 1, 1, 0],
+				start: 0,
+				concluded_at: Some(1),
+			},
+		);
+		SpamSlots::<Test>::insert(1, vec![1, 1, 0, 0, 0, 0, 0]);
+
+		assert_noop!(
+			Pallet::<Test>::grant_amnesty(Origin::signed(1), vec![(1, candidate_hash_a)], 0),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<Test>::grant_amnesty(Origin::root(), vec![], 0),
+			Error::<Test>::EmptyAmnesty,
+		);
+		assert_noop!(
+			Pallet::<Test>::grant_amnesty(
+				Origin::root(),
+				vec![(1, candidate_hash_a), (1, candidate_hash_c)],
+				0
+			),
+			Error::<Test>::DisputeNotFound,
+		);
+		assert_noop!(
+			Pallet::<Test>::grant_amnesty(
+				Origin::root(),
+				vec![(1, candidate_hash_a), (1, candidate_hash_b)],
+				0
+			),
+			Error::<Test>::DisputeNotActive,
+		);
+
+		assert_ok!(Pallet::<Test>::grant_amnesty(
+			Origin::root(),
+			vec![(1, candidate_hash_a), (1, candidate_hash_a)],
+			0
+		));
+
+		assert!(<Disputes<Test>>::get(1, candidate_hash_a).is_none());
+		assert!(<Disputes<Test>>::get(1, candidate_hash_b).is_some());
+		assert_eq!(<Amnestied<Test>>::get(1, candidate_hash_a), Some(0));
+		assert_eq!(SpamSlots::<Test>::get(1), Some(vec![0; 7]));
+		assert_eq!(
+			Pallet::<Test>::amnesties(0),
+			Some(AmnestyRecord {
+				granted_at: 1,
+				disputes: vec![(1, candidate_hash_a)],
+				force_approved_up_to: 0,
+			})
+		);
+		assert_eq!(AmnestyCount::<Test>::get(), 1);
+		assert_eq!(System::digest().logs[0], ConsensusLog::ForceApprove(0).into());
+		System::assert_has_event(Event::AmnestyGranted(0).into());
+	})
+}
+
 #[test]
 fn test_has_supermajority_against() {
 	assert_eq!(
@@ -2416,6 +2498,60 @@ fn filter_ignores_single_sided() {
 	})
 }
 
+#[test]
+fn filter_removes_amnestied() {
+	new_test_ext(Default::default()).execute_with(|| {
+		let v0 = <ValidatorId as CryptoType>::Pair::generate().0;
+		let v1 = <ValidatorId as CryptoType>::Pair::generate().0;
+
+		run_to_block(3, |b| {
+			// a new session at each block
+			Some((
+				true,
+				b,
+				vec![(&0, v0.public()), (&1, v1.public())],
+				Some(vec![(&0, v0.public()), (&1, v1.public())]),
+			))
+		});
+
+		let candidate_hash_a = CandidateHash(sp_core::H256::repeat_byte(1));
+		let candidate_hash_b = CandidateHash(sp_core::H256::repeat_byte(2));
+
+		<Amnestied<Test>>::insert(&1, &candidate_hash_a, 0);
+
+		let statement_set = |c_hash: &CandidateHash| {
+			let payload = |valid| {
+				ExplicitDisputeStatement { valid, candidate_hash: c_hash.clone(), session: 1 }
+					.signing_payload()
+			};
+			DisputeStatementSet {
+				candidate_hash: c_hash.clone(),
+				session: 1,
+				statements: vec![
+					(
+						DisputeStatement::Valid(ValidDisputeStatementKind::Explicit),
+						ValidatorIndex(0),
+						v0.sign(&payload(true)),
+					),
+					(
+						DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+						ValidatorIndex(1),
+						v1.sign(&payload(false)),
+					),
+				],
+			}
+		};
+
+		let statements = apply_filter_all::<Test, _>(vec![
+			statement_set(&candidate_hash_a),
+			statement_set(&candidate_hash_b),
+		]);
+
+		assert_eq!(statements.len(), 1);
+		assert_eq!(statements[0].as_ref().candidate_hash, candidate_hash_b);
+	})
+}
+
 #[test]
 fn import_ignores_single_sided() {
 	new_test_ext(Default::default()).execute_with(|| {
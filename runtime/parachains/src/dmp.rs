@@ -119,22 +119,28 @@ impl<T: Config> Pallet<T> {
 	pub(crate) fn initializer_on_new_session(
 		_notification: &initializer::SessionChangeNotification<T::BlockNumber>,
 		outgoing_paras: &[ParaId],
-	) {
-		Self::perform_outgoing_para_cleanup(outgoing_paras);
+	) -> Weight {
+		Self::perform_outgoing_para_cleanup(outgoing_paras)
 	}
 
 	/// Iterate over all paras that were noted for offboarding and remove all the data
 	/// associated with them.
-	fn perform_outgoing_para_cleanup(outgoing: &[ParaId]) {
+	fn perform_outgoing_para_cleanup(outgoing: &[ParaId]) -> Weight {
+		let mut weight: Weight = 0;
 		for outgoing_para in outgoing {
-			Self::clean_dmp_after_outgoing(outgoing_para);
+			weight = weight.saturating_add(Self::clean_dmp_after_outgoing(outgoing_para));
 		}
+		weight
 	}
 
 	/// Remove all relevant storage items for an outgoing parachain.
-	fn clean_dmp_after_outgoing(outgoing_para: &ParaId) {
+	fn clean_dmp_after_outgoing(outgoing_para: &ParaId) -> Weight {
 		<Self as Store>::DownwardMessageQueues::remove(outgoing_para);
 		<Self as Store>::DownwardMessageQueueHeads::remove(outgoing_para);
+
+		// The whole queue is a single value, so removing it doesn't depend on the number of
+		// messages in it.
+		T::DbWeight::get().writes(2)
 	}
 
 	/// Enqueue a downward message to a specific recipient para.
@@ -731,6 +731,20 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Returns true if none of the participants of the channel has been offboarded.
+	///
+	/// The HRMP state of paras offboarded at a session change is only removed in a block following
+	/// the session change, see [`initializer::SessionChangeStage`]. Until then, their channels and
+	/// channel requests are treated as if they had already been removed.
+	fn is_live(channel_id: &HrmpChannelId) -> bool {
+		// An offboarded para has no lifecycle, unless it has been registered again.
+		let is_live = |para| match <paras::Pallet<T>>::lifecycle(para) {
+			Some(lifecycle) => !lifecycle.is_onboarding(),
+			None => false,
+		};
+		is_live(channel_id.sender) && is_live(channel_id.recipient)
+	}
+
 	/// Iterate over all open channel requests and:
 	///
 	/// - prune the stale requests
@@ -917,8 +931,8 @@ impl<T: Config> Pallet<T> {
 			let channel_id = HrmpChannelId { sender, recipient: out_msg.recipient };
 
 			let channel = match <Self as Store>::HrmpChannels::get(&channel_id) {
-				Some(channel) => channel,
-				None => return Err(OutboundHrmpAcceptanceErr::NoSuchChannel { channel_id, idx }),
+				Some(channel) if Self::is_live(&channel_id) => channel,
+				_ => return Err(OutboundHrmpAcceptanceErr::NoSuchChannel { channel_id, idx }),
 			};
 
 			let msg_size = out_msg.data.len() as u32;
@@ -1191,6 +1205,7 @@ impl<T: Config> Pallet<T> {
 		let channel_id = HrmpChannelId { sender, recipient: origin };
 		let mut channel_req = <Self as Store>::HrmpOpenChannelRequests::get(&channel_id)
 			.ok_or(Error::<T>::AcceptHrmpChannelDoesntExist)?;
+		ensure!(Self::is_live(&channel_id), Error::<T>::AcceptHrmpChannelDoesntExist);
 		ensure!(!channel_req.confirmed, Error::<T>::AcceptHrmpChannelAlreadyConfirmed);
 
 		// check if by accepting this open channel request, this parachain would exceed the
@@ -1247,6 +1262,7 @@ impl<T: Config> Pallet<T> {
 
 		let open_channel_req = <Self as Store>::HrmpOpenChannelRequests::get(&channel_id)
 			.ok_or(Error::<T>::OpenHrmpChannelDoesntExist)?;
+		ensure!(Self::is_live(&channel_id), Error::<T>::OpenHrmpChannelDoesntExist);
 		ensure!(!open_channel_req.confirmed, Error::<T>::OpenHrmpChannelAlreadyConfirmed);
 
 		// Remove the request by the channel id and sync the accompanying list with the set.
@@ -1277,7 +1293,7 @@ impl<T: Config> Pallet<T> {
 
 		// check if the channel requested to close does exist.
 		ensure!(
-			<Self as Store>::HrmpChannels::get(&channel_id).is_some(),
+			<Self as Store>::HrmpChannels::get(&channel_id).is_some() && Self::is_live(&channel_id),
 			Error::<T>::CloseHrmpChannelDoesntExist,
 		);
 
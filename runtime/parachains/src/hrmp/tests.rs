@@ -326,6 +326,74 @@ fn accept_incoming_request_and_offboard() {
 	});
 }
 
+#[test]
+fn channels_of_offboarded_para_unusable_until_cleaned_up() {
+	let para_a = 32.into();
+	let para_b = 64.into();
+	let para_c = 97.into();
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		register_parachain(para_a);
+		register_parachain(para_b);
+		register_parachain(para_c);
+
+		run_to_block(5, Some(vec![4, 5]));
+		Hrmp::init_open_channel(para_b, para_a, 2, 8).unwrap();
+		Hrmp::accept_open_channel(para_a, para_b).unwrap();
+
+		// On Block 6: session change. The channel b -> a is opened and a requests a -> c.
+		run_to_block(6, Some(vec![6]));
+		assert!(channel_exists(para_b, para_a));
+		Hrmp::init_open_channel(para_a, para_c, 2, 8).unwrap();
+		deregister_parachain(para_a);
+
+		// On Block 7: session change. The channel is still usable while a is offboarding.
+		run_to_block(7, Some(vec![7]));
+		let msgs = vec![OutboundHrmpMessage { recipient: para_a, data: b"knock".to_vec() }];
+		assert!(Hrmp::check_outbound_hrmp(&Configuration::config(), para_b, &msgs).is_ok());
+
+		// The next session change offboards a, but its HRMP state is cleaned up in a later block.
+		let config = Configuration::config();
+		let notification = crate::initializer::SessionChangeNotification {
+			prev_config: config.clone(),
+			new_config: config.clone(),
+			session_index: ParasShared::session_index() + 1,
+			..Default::default()
+		};
+		ParasShared::initializer_on_new_session(
+			notification.session_index,
+			notification.random_seed,
+			&notification.new_config,
+			notification.validators.clone(),
+		);
+		let outgoing_paras = Paras::initializer_on_new_session(&notification);
+		assert_eq!(outgoing_paras, vec![para_a]);
+
+		// Until then, the channel and the request of a are treated as if they were removed.
+		assert!(channel_exists(para_b, para_a));
+		assert!(matches!(
+			Hrmp::check_outbound_hrmp(&config, para_b, &msgs),
+			Err(OutboundHrmpAcceptanceErr::NoSuchChannel { .. })
+		));
+		assert!(matches!(
+			Hrmp::close_channel(para_b, HrmpChannelId { sender: para_b, recipient: para_a }),
+			Err(Error::<Test>::CloseHrmpChannelDoesntExist)
+		));
+		assert_noop!(
+			Hrmp::accept_open_channel(para_c, para_a),
+			Error::<Test>::AcceptHrmpChannelDoesntExist,
+		);
+		assert_noop!(
+			Hrmp::cancel_open_request(para_c, HrmpChannelId { sender: para_a, recipient: para_c }),
+			Error::<Test>::OpenHrmpChannelDoesntExist,
+		);
+
+		Hrmp::initializer_on_new_session(&notification, &outgoing_paras);
+		assert!(!channel_exists(para_b, para_a));
+		Hrmp::assert_storage_consistency_exhaustive();
+	});
+}
+
 #[test]
 fn check_sent_messages() {
 	let para_a = 32.into();
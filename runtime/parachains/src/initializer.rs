@@ -18,6 +18,12 @@
 //! parachains modules. It's also responsible for finalization and session change notifications.
 //!
 //! This module can throw fatal errors if session-change notifications are received after initialization.
+//!
+//! Work which must be observed by the block following a session change (configuration, validator
+//! shuffling, group rotation, para lifecycle transitions, ...) is done in the session change block
+//! itself. The remaining cleanup is split into [`SessionChangeStage`]s, which are processed one per
+//! block in the blocks following the session change, so that the session change block does not
+//! spike in weight.
 
 use crate::{
 	configuration::{self, HostConfiguration},
@@ -25,13 +31,14 @@ use crate::{
 	dmp, hrmp, inclusion, paras, scheduler, session_info, shared, ump,
 };
 use frame_support::{
-	traits::{OneSessionHandler, Randomness},
+	traits::{Get, OneSessionHandler, Randomness},
 	weights::Weight,
 };
 use frame_system::limits::BlockWeights;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v2::{BlockNumber, ConsensusLog, SessionIndex, ValidatorId};
+use primitives::v2::{BlockNumber, ConsensusLog, Id as ParaId, SessionIndex, ValidatorId};
 use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
 use sp_std::prelude::*;
 
 #[cfg(test)]
@@ -79,6 +86,54 @@ struct BufferedSessionChange {
 	session_index: SessionIndex,
 }
 
+/// A stage of the session change handling, deferred to the blocks following the session change.
+///
+/// Deferring the cleanup of paras offboarded at the session change is sound, because their HRMP
+/// and DMP state can't be used in the meantime:
+///
+/// - The paras are no longer scheduled, so their candidates can't be backed or included anymore.
+///   Thus they can't send or receive HRMP messages, and their downward message queues are not
+///   processed.
+/// - HRMP treats channels and channel requests of offboarded paras as already removed, so other
+///   paras can't send messages over them, nor accept, cancel or close them.
+/// - Downward messages which are still sent to the paras are removed along with their queues, as
+///   the DMP cleanup is the last stage.
+/// - All stages still pending are processed before the next session change is applied, which is
+///   the earliest point at which a para with the same id could be onboarded again.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum SessionChangeStage {
+	/// Prune disputes and included candidates of sessions which left the dispute period.
+	DisputesPruning,
+	/// Clean up HRMP channels of outgoing paras and process pending channel requests.
+	HrmpCleanup,
+	/// Clean up downward message queues of outgoing paras.
+	DmpCleanup,
+}
+
+impl SessionChangeStage {
+	/// The stage to process after this one, if any.
+	fn next(self) -> Option<Self> {
+		match self {
+			SessionChangeStage::DisputesPruning => Some(SessionChangeStage::HrmpCleanup),
+			SessionChangeStage::HrmpCleanup => Some(SessionChangeStage::DmpCleanup),
+			SessionChangeStage::DmpCleanup => None,
+		}
+	}
+}
+
+/// Session change handling which has been deferred to the following blocks.
+#[derive(Encode, Decode, TypeInfo)]
+struct PendingSessionChange<BlockNumber> {
+	/// The session which has been started.
+	session_index: SessionIndex,
+	/// The configuration before the session change.
+	prev_config: HostConfiguration<BlockNumber>,
+	/// Paras which have been offboarded at the session change.
+	outgoing_paras: Vec<ParaId>,
+	/// The next stage to process.
+	stage: SessionChangeStage,
+}
+
 pub trait WeightInfo {
 	fn force_approve(d: u32) -> Weight;
 }
@@ -143,9 +198,21 @@ pub mod pallet {
 	pub(super) type BufferedSessionChanges<T: Config> =
 		StorageValue<_, Vec<BufferedSessionChange>, ValueQuery>;
 
+	/// Session change handling of the last session change which has not been processed yet.
+	///
+	/// One stage is processed at the beginning of every block. Any remaining stages are processed
+	/// at once before applying the next session change.
+	#[pallet::storage]
+	pub(super) type PendingSessionChangeWork<T: Config> =
+		StorageValue<_, PendingSessionChange<T::BlockNumber>>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(now: T::BlockNumber) -> Weight {
+			// Continue with the handling of the last session change, before the other modules
+			// observe this block.
+			let stage_weight = Self::process_session_change_stage();
+
 			// The other modules are initialized in this order:
 			// - Configuration
 			// - Paras
@@ -169,7 +236,7 @@ pub mod pallet {
 
 			HasInitialized::<T>::set(Some(()));
 
-			total_weight
+			total_weight.saturating_add(stage_weight)
 		}
 
 		fn on_finalize(now: T::BlockNumber) {
@@ -225,6 +292,10 @@ impl<T: Config> Pallet<T> {
 		all_validators: Vec<ValidatorId>,
 		queued: Vec<ValidatorId>,
 	) {
+		// Stages of the previous session change must have been processed before the next session
+		// change is applied.
+		Self::flush_session_change_stages();
+
 		let random_seed = {
 			let mut buf = [0u8; 32];
 			// TODO: audit usage of randomness API
@@ -259,10 +330,72 @@ impl<T: Config> Pallet<T> {
 		scheduler::Pallet::<T>::initializer_on_new_session(&notification);
 		inclusion::Pallet::<T>::initializer_on_new_session(&notification);
 		session_info::Pallet::<T>::initializer_on_new_session(&notification);
-		T::DisputesHandler::initializer_on_new_session(&notification);
-		dmp::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
+		// Upward messages are dispatched in every block, so the queues of outgoing paras need to
+		// be gone by the next block.
 		ump::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
-		hrmp::Pallet::<T>::initializer_on_new_session(&notification, &outgoing_paras);
+
+		// The rest is done in the following blocks.
+		PendingSessionChangeWork::<T>::put(PendingSessionChange {
+			session_index,
+			prev_config: notification.prev_config,
+			outgoing_paras,
+			stage: SessionChangeStage::DisputesPruning,
+		});
+	}
+
+	/// Process the next stage of the last session change, if any.
+	fn process_session_change_stage() -> Weight {
+		let mut weight = T::DbWeight::get().reads(1);
+		let mut pending = match PendingSessionChangeWork::<T>::get() {
+			Some(pending) => pending,
+			None => return weight,
+		};
+
+		weight = weight.saturating_add(Self::apply_session_change_stage(&pending));
+		match pending.stage.next() {
+			Some(next) => {
+				pending.stage = next;
+				PendingSessionChangeWork::<T>::put(pending);
+			},
+			None => PendingSessionChangeWork::<T>::kill(),
+		}
+
+		weight.saturating_add(T::DbWeight::get().writes(1))
+	}
+
+	/// Process all remaining stages of the last session change.
+	fn flush_session_change_stages() {
+		if let Some(mut pending) = PendingSessionChangeWork::<T>::take() {
+			loop {
+				Self::apply_session_change_stage(&pending);
+				match pending.stage.next() {
+					Some(next) => pending.stage = next,
+					None => break,
+				}
+			}
+		}
+	}
+
+	fn apply_session_change_stage(pending: &PendingSessionChange<T::BlockNumber>) -> Weight {
+		// Only the fields used by the deferred stages are populated.
+		let notification = SessionChangeNotification {
+			prev_config: pending.prev_config.clone(),
+			new_config: configuration::Pallet::<T>::config(),
+			session_index: pending.session_index,
+			..Default::default()
+		};
+		let outgoing_paras = &pending.outgoing_paras;
+
+		// The configuration is read to build the notification.
+		let weight = T::DbWeight::get().reads(1);
+		weight.saturating_add(match pending.stage {
+			SessionChangeStage::DisputesPruning =>
+				T::DisputesHandler::initializer_on_new_session(&notification),
+			SessionChangeStage::HrmpCleanup =>
+				hrmp::Pallet::<T>::initializer_on_new_session(&notification, outgoing_paras),
+			SessionChangeStage::DmpCleanup =>
+				dmp::Pallet::<T>::initializer_on_new_session(&notification, outgoing_paras),
+		})
 	}
 
 	/// Should be called when a new session occurs. Buffers the session notification to be applied
@@ -285,6 +418,7 @@ impl<T: Config> Pallet<T> {
 		if session_index == 0 {
 			// Genesis session should be immediately enacted.
 			Self::apply_new_session(0, validators, queued);
+			Self::flush_session_change_stages();
 		} else {
 			BufferedSessionChanges::<T>::mutate(|v| {
 				v.push(BufferedSessionChange { validators, queued, session_index })
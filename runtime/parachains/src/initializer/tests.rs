@@ -64,6 +64,56 @@ fn session_change_applied_on_finalize() {
 	});
 }
 
+#[test]
+fn session_change_stages_processed_one_per_block() {
+	new_test_ext(Default::default()).execute_with(|| {
+		Initializer::on_initialize(1);
+		Initializer::on_new_session(false, 1, Vec::new().into_iter(), Some(Vec::new().into_iter()));
+		Initializer::on_finalize(1);
+
+		let stage = || <Initializer as Store>::PendingSessionChangeWork::get().map(|p| p.stage);
+		assert_eq!(stage(), Some(SessionChangeStage::DisputesPruning));
+
+		Initializer::on_initialize(2);
+		assert_eq!(stage(), Some(SessionChangeStage::HrmpCleanup));
+		Initializer::on_finalize(2);
+
+		Initializer::on_initialize(3);
+		assert_eq!(stage(), Some(SessionChangeStage::DmpCleanup));
+		Initializer::on_finalize(3);
+
+		Initializer::on_initialize(4);
+		assert_eq!(stage(), None);
+	});
+}
+
+#[test]
+fn pending_stages_flushed_on_next_session_change() {
+	new_test_ext(Default::default()).execute_with(|| {
+		Initializer::on_initialize(1);
+		Initializer::on_new_session(false, 1, Vec::new().into_iter(), Some(Vec::new().into_iter()));
+		Initializer::on_finalize(1);
+
+		Initializer::on_initialize(2);
+		Initializer::on_new_session(false, 2, Vec::new().into_iter(), Some(Vec::new().into_iter()));
+		Initializer::on_finalize(2);
+
+		// Only the stages of session 2 are left.
+		let pending = <Initializer as Store>::PendingSessionChangeWork::get().unwrap();
+		assert_eq!(pending.session_index, 2);
+		assert_eq!(pending.stage, SessionChangeStage::DisputesPruning);
+	});
+}
+
+#[test]
+fn genesis_session_stages_processed_immediately() {
+	new_test_ext(Default::default()).execute_with(|| {
+		Initializer::on_new_session(false, 0, Vec::new().into_iter(), Some(Vec::new().into_iter()));
+
+		assert!(<Initializer as Store>::PendingSessionChangeWork::get().is_none());
+	});
+}
+
 #[test]
 fn sets_flag_on_initialize() {
 	new_test_ext(Default::default()).execute_with(|| {
@@ -124,6 +174,16 @@ fn scheduled_cleanup_performed() {
 		// Apply session 2 in the future
 		Initializer::apply_new_session(2, vec![], vec![]);
 
+		// The DMP cleanup is deferred to the third block after the session change. Messages which
+		// are sent to A in the meantime are removed as well.
+		for b in 1..=2 {
+			Initializer::on_initialize(b);
+			assert_ok!(Dmp::queue_downward_message(&Configuration::config(), a, vec![b as u8]));
+			Initializer::on_finalize(b);
+			assert!(!Dmp::dmq_contents(a).is_empty());
+		}
+		Initializer::on_initialize(3);
+
 		assert!(Dmp::dmq_contents(a).is_empty());
 		assert!(Dmp::dmq_contents(b).is_empty());
 		assert!(!Dmp::dmq_contents(c).is_empty());
@@ -244,6 +244,7 @@ impl crate::disputes::Config for Test {
 	type Event = Event;
 	type RewardValidators = Self;
 	type PunishValidators = Self;
+	type AmnestyOrigin = frame_system::EnsureRoot<AccountId>;
 	type WeightInfo = crate::disputes::TestWeightInfo;
 }
 
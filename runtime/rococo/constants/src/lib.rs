@@ -41,14 +41,44 @@ pub mod time {
 	pub const SLOT_DURATION: Moment = MILLISECS_PER_BLOCK;
 	pub const DEFAULT_EPOCH_DURATION: BlockNumber = prod_or_fast!(1 * HOURS, 1 * MINUTES);
 	frame_support::parameter_types! {
-		pub storage EpochDurationInBlocks: BlockNumber = DEFAULT_EPOCH_DURATION;
+		// Both can be overridden in the genesis of a chain, e.g. to deploy a network with a
+		// different block time. They must not be changed afterwards.
+		pub storage EpochDurationInBlocks: BlockNumber = prod_or_fast!(1 * hours(), 1 * minutes());
+		pub storage SlotDuration: Moment = SLOT_DURATION;
 	}
 
-	// These time units are defined in number of blocks.
+	// These time units are defined in number of blocks, at the default slot duration.
 	pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 	pub const HOURS: BlockNumber = MINUTES * 60;
 	pub const DAYS: BlockNumber = HOURS * 24;
 
+	/// The longest slot duration, at which a minute still lasts at least one block.
+	pub const MAX_SLOT_DURATION: Moment = 60_000;
+
+	/// Returns true if the chain may be deployed with the given slot duration.
+	pub fn is_valid_slot_duration(duration: Moment) -> bool {
+		(1..=MAX_SLOT_DURATION).contains(&duration)
+	}
+
+	/// Number of blocks in a minute, at the slot duration of the chain.
+	pub fn minutes() -> BlockNumber {
+		// the slot duration is validated when the genesis is built, but zero time units would
+		// break every period that is derived from them, so they are guarded here as well
+		let duration = SlotDuration::get();
+		debug_assert!(is_valid_slot_duration(duration), "Invalid slot duration: {}", duration);
+		(60_000 / duration.clamp(1, MAX_SLOT_DURATION)) as BlockNumber
+	}
+
+	/// Number of blocks in an hour, at the slot duration of the chain.
+	pub fn hours() -> BlockNumber {
+		minutes() * 60
+	}
+
+	/// Number of blocks in a day, at the slot duration of the chain.
+	pub fn days() -> BlockNumber {
+		hours() * 24
+	}
+
 	// 1 in 4 blocks (on average, not counting collisions) will be primary babe blocks.
 	pub const PRIMARY_PROBABILITY: (u64, u64) = (1, 4);
 }
@@ -98,6 +128,10 @@ mod tests {
 	use super::{
 		currency::{CENTS, DOLLARS, MILLICENTS},
 		fee::WeightToFee,
+		time::{
+			days, hours, is_valid_slot_duration, minutes, SlotDuration, DAYS, HOURS,
+			MAX_SLOT_DURATION, MINUTES,
+		},
 	};
 	use crate::weights::ExtrinsicBaseWeight;
 	use frame_support::weights::WeightToFeePolynomial;
@@ -121,4 +155,28 @@ mod tests {
 		let y = CENTS / 10;
 		assert!(x.max(y) - x.min(y) < MILLICENTS);
 	}
+
+	#[test]
+	fn time_units_follow_slot_duration() {
+		sp_io::TestExternalities::default().execute_with(|| {
+			assert_eq!((minutes(), hours(), days()), (MINUTES, HOURS, DAYS));
+
+			SlotDuration::set(&3000);
+			assert_eq!((minutes(), hours(), days()), (2 * MINUTES, 2 * HOURS, 2 * DAYS));
+
+			SlotDuration::set(&12000);
+			assert_eq!((minutes(), hours(), days()), (MINUTES / 2, HOURS / 2, DAYS / 2));
+
+			SlotDuration::set(&MAX_SLOT_DURATION);
+			assert_eq!((minutes(), hours(), days()), (1, 60, 60 * 24));
+		});
+	}
+
+	#[test]
+	fn slot_duration_is_validated() {
+		assert!(!is_valid_slot_duration(0));
+		assert!(is_valid_slot_duration(1));
+		assert!(is_valid_slot_duration(MAX_SLOT_DURATION));
+		assert!(!is_valid_slot_duration(MAX_SLOT_DURATION + 1));
+	}
 }
@@ -0,0 +1,361 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet that keeps the wrapped native tokens of the bridged chain.
+//!
+//! Tokens, locked at the bridged chain, are minted at this chain by the `mint` call. The call is
+//! only accepted from the `BridgedAssetsAccount`: the account, that messages of the assets bridge
+//! of the bridged chain are dispatched with.
+//!
+//! Holders burn the wrapped tokens to get the locked tokens back at the bridged chain. The burn
+//! sends the message that unlocks the tokens there. Once the delivery of the message is
+//! confirmed, the burn is settled: the tokens are either counted as unlocked, or minted back to
+//! the holder if the message hasn't been dispatched at the bridged chain.
+//!
+//! Balances below `MinimumBalance` would only bloat the state, so accounts may not be left with
+//! them: mints, transfers and burns that would leave such a balance are rejected. The only
+//! exception are failed burns, whose tokens are always minted back to the holder.
+//!
+//! Every minted token is either issued at this chain, pending to be unlocked, or unlocked at the
+//! bridged chain. [`Pallet::reconcile`] checks that the total issuance matches the burns confirmed
+//! by the bridged chain.
+
+use bp_messages::{source_chain::OnDeliveryConfirmed, DeliveredMessages, LaneId, MessageNonce};
+use frame_support::{pallet_prelude::*, traits::Get, weights::Weight};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, CheckedSub, Saturating, Zero},
+	DispatchError,
+};
+
+pub use pallet::*;
+
+/// Sends the messages that unlock the tokens, locked at the bridged chain.
+pub trait UnlockMessages<AccountId, Balance> {
+	/// Weight of sending the unlock message, including the payment of the message fee.
+	fn send_weight() -> Weight;
+
+	/// Sends the message over the lane, that unlocks `amount` of the locked tokens to the
+	/// `recipient` at the bridged chain. The message is sent by `sender`, and its fee is paid by
+	/// `payer`. Returns the nonce of the message.
+	fn send(
+		lane: LaneId,
+		sender: AccountId,
+		payer: &AccountId,
+		recipient: AccountId,
+		amount: Balance,
+		fee: Balance,
+	) -> Result<MessageNonce, DispatchError>;
+}
+
+/// State of the burn, whose tokens are unlocked at the bridged chain.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum BurnState {
+	/// The delivery of the unlock message hasn't been confirmed yet.
+	Pending,
+	/// The tokens have been unlocked at the bridged chain.
+	Unlocked,
+	/// The unlock message hasn't been dispatched at the bridged chain.
+	Failed,
+}
+
+/// Wrapped tokens burned at this chain.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct Burn<AccountId, Balance> {
+	/// Holder of the burned tokens.
+	pub holder: AccountId,
+	/// Amount of the burned tokens.
+	pub amount: Balance,
+	/// State of the burn.
+	pub state: BurnState,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	/// Configuration for the bridge assets pallet.
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Balance of the wrapped tokens. Message fees are paid in the same units.
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
+
+		/// Account that messages of the assets bridge of the bridged chain are dispatched with.
+		type BridgedAssetsAccount: Get<Self::AccountId>;
+
+		/// Account that sends the unlock messages to the bridged chain.
+		type UnlockAccount: Get<Self::AccountId>;
+
+		/// Lane that the unlock messages are sent over.
+		type UnlockLane: Get<LaneId>;
+
+		/// Sends the unlock messages.
+		type UnlockMessages: UnlockMessages<Self::AccountId, Self::Balance>;
+
+		/// The minimal non-zero balance of the wrapped tokens that an account may hold.
+		#[pallet::constant]
+		type MinimumBalance: Get<Self::Balance>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Tokens have been minted to the account.
+		Minted(T::AccountId, T::Balance),
+		/// Tokens have been transferred between accounts.
+		Transferred(T::AccountId, T::AccountId, T::Balance),
+		/// Tokens of the holder have been burned, to be unlocked by the message with given nonce.
+		Burned(T::AccountId, T::Balance, MessageNonce),
+		/// The burn has been settled with the given state.
+		BurnSettled(MessageNonce, BurnState),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The origin is not the account of the assets bridge of the bridged chain.
+		NotBridgedAssetsAccount,
+		/// The account doesn't have enough wrapped tokens.
+		InsufficientBalance,
+		/// The amount of tokens is zero.
+		ZeroAmount,
+		/// There's no burn with given nonce.
+		UnknownBurn,
+		/// The delivery of the unlock message hasn't been confirmed yet.
+		BurnIsPending,
+		/// The account would be left with a non-zero balance below the minimal balance.
+		BelowMinimumBalance,
+	}
+
+	/// Wrapped token balances of the accounts.
+	#[pallet::storage]
+	#[pallet::getter(fn balance)]
+	pub(crate) type Balances<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, T::Balance, ValueQuery>;
+
+	/// Total amount of the wrapped tokens held by accounts.
+	#[pallet::storage]
+	#[pallet::getter(fn total_issuance)]
+	pub(crate) type TotalIssuance<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Total amount of the tokens ever minted.
+	#[pallet::storage]
+	#[pallet::getter(fn total_minted)]
+	pub(crate) type TotalMinted<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Total amount of the burned tokens that have been unlocked at the bridged chain.
+	#[pallet::storage]
+	#[pallet::getter(fn total_unlocked)]
+	pub(crate) type TotalUnlocked<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, T::Balance, ValueQuery>;
+
+	/// Burns that haven't been settled yet, by the nonce of their unlock message.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_burn)]
+	pub(crate) type Burns<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, MessageNonce, Burn<T::AccountId, T::Balance>>;
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Mint the tokens, locked at the bridged chain, to the recipient.
+		///
+		/// Only dispatched by the messages of the assets bridge of the bridged chain.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+		pub fn mint(
+			origin: OriginFor<T>,
+			recipient: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(
+				sender == T::BridgedAssetsAccount::get(),
+				Error::<T, I>::NotBridgedAssetsAccount,
+			);
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			let balance = Balances::<T, I>::get(&recipient).saturating_add(amount);
+			Self::ensure_minimum_balance(balance)?;
+
+			Self::issue(&recipient, amount);
+			TotalMinted::<T, I>::mutate(|total| *total = total.saturating_add(amount));
+
+			Self::deposit_event(Event::Minted(recipient, amount));
+			Ok(())
+		}
+
+		/// Transfer the wrapped tokens to another account.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn transfer(
+			origin: OriginFor<T>,
+			dest: T::AccountId,
+			amount: T::Balance,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+
+			let remaining = Balances::<T, I>::get(&sender)
+				.checked_sub(&amount)
+				.ok_or(Error::<T, I>::InsufficientBalance)?;
+			// the sender may transfer to itself
+			let dest_balance =
+				if dest == sender { remaining } else { Balances::<T, I>::get(&dest) };
+			let dest_balance = dest_balance.saturating_add(amount);
+			Self::ensure_minimum_balance(remaining)?;
+			Self::ensure_minimum_balance(dest_balance)?;
+
+			Self::set_balance(&sender, remaining);
+			Self::set_balance(&dest, dest_balance);
+
+			Self::deposit_event(Event::Transferred(sender, dest, amount));
+			Ok(())
+		}
+
+		/// Burn the wrapped tokens, sending the message that unlocks them to the recipient at the
+		/// bridged chain.
+		///
+		/// The `delivery_and_dispatch_fee` of the message is paid by the holder.
+		#[pallet::weight(
+			T::UnlockMessages::send_weight().saturating_add(T::DbWeight::get().reads_writes(2, 3))
+		)]
+		pub fn burn(
+			origin: OriginFor<T>,
+			recipient: T::AccountId,
+			amount: T::Balance,
+			delivery_and_dispatch_fee: T::Balance,
+		) -> DispatchResult {
+			let holder = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T, I>::ZeroAmount);
+			let remaining = Balances::<T, I>::get(&holder)
+				.checked_sub(&amount)
+				.ok_or(Error::<T, I>::InsufficientBalance)?;
+			Self::ensure_minimum_balance(remaining)?;
+
+			let nonce = T::UnlockMessages::send(
+				T::UnlockLane::get(),
+				T::UnlockAccount::get(),
+				&holder,
+				recipient,
+				amount,
+				delivery_and_dispatch_fee,
+			)?;
+			Self::set_balance(&holder, remaining);
+			TotalIssuance::<T, I>::mutate(|total| *total = total.saturating_sub(amount));
+			Burns::<T, I>::insert(
+				nonce,
+				Burn { holder: holder.clone(), amount, state: BurnState::Pending },
+			);
+
+			Self::deposit_event(Event::Burned(holder, amount, nonce));
+			Ok(())
+		}
+
+		/// Settle the burn, whose unlock message delivery has been confirmed.
+		///
+		/// Tokens of the failed burn are minted back to the holder.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+		pub fn settle_burn(origin: OriginFor<T>, nonce: MessageNonce) -> DispatchResult {
+			ensure_signed(origin)?;
+			let burn = Burns::<T, I>::get(nonce).ok_or(Error::<T, I>::UnknownBurn)?;
+			match burn.state {
+				BurnState::Pending => return Err(Error::<T, I>::BurnIsPending.into()),
+				BurnState::Unlocked => TotalUnlocked::<T, I>::mutate(|total| {
+					*total = total.saturating_add(burn.amount)
+				}),
+				BurnState::Failed => Self::issue(&burn.holder, burn.amount),
+			}
+			Burns::<T, I>::remove(nonce);
+
+			Self::deposit_event(Event::BurnSettled(nonce, burn.state));
+			Ok(())
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> OnDeliveryConfirmed for Pallet<T, I> {
+		fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+			if *lane != T::UnlockLane::get() {
+				return 0
+			}
+
+			// the lane may be shared with other senders, so not every message is an unlock
+			let mut writes = 0;
+			for nonce in messages.begin..=messages.end {
+				let state = if messages.message_dispatch_result(nonce) {
+					BurnState::Unlocked
+				} else {
+					BurnState::Failed
+				};
+				Burns::<T, I>::mutate(nonce, |burn| {
+					if let Some(burn) = burn {
+						burn.state = state;
+						writes += 1;
+					}
+				});
+			}
+
+			T::DbWeight::get().reads_writes(messages.total_messages(), writes)
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Checks that every minted token is either held by an account, burned and pending to be
+	/// unlocked, or unlocked at the bridged chain.
+	///
+	/// Iterates over all balances and burns, so it must not be used in extrinsics.
+	pub fn reconcile() -> Result<(), &'static str> {
+		let total_issuance = TotalIssuance::<T, I>::get();
+		let held = Balances::<T, I>::iter_values()
+			.fold(T::Balance::zero(), |total, balance| total.saturating_add(balance));
+		ensure!(held == total_issuance, "Wrapped token balances don't match the total issuance");
+
+		let pending = Burns::<T, I>::iter_values()
+			.fold(T::Balance::zero(), |total, burn| total.saturating_add(burn.amount));
+		let accounted = total_issuance.saturating_add(pending);
+		ensure!(
+			TotalMinted::<T, I>::get() == accounted.saturating_add(TotalUnlocked::<T, I>::get()),
+			"Minted wrapped tokens don't match the total issuance and the confirmed burns",
+		);
+		Ok(())
+	}
+
+	fn ensure_minimum_balance(balance: T::Balance) -> DispatchResult {
+		ensure!(
+			balance.is_zero() || balance >= T::MinimumBalance::get(),
+			Error::<T, I>::BelowMinimumBalance,
+		);
+		Ok(())
+	}
+
+	fn issue(account: &T::AccountId, amount: T::Balance) {
+		Balances::<T, I>::mutate(account, |balance| *balance = balance.saturating_add(amount));
+		TotalIssuance::<T, I>::mutate(|total| *total = total.saturating_add(amount));
+	}
+
+	fn set_balance(account: &T::AccountId, balance: T::Balance) {
+		if balance.is_zero() {
+			Balances::<T, I>::remove(account);
+		} else {
+			Balances::<T, I>::insert(account, balance);
+		}
+	}
+}
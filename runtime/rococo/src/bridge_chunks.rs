@@ -0,0 +1,399 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet that dispatches calls, which are too large to fit into a single bridge message.
+//!
+//! The sender splits the encoded call into chunks and sends every chunk in a separate message,
+//! that dispatches the `receive_chunk` call. Messages of the lane are delivered in order, so the
+//! chunks are staged one after another, and the last chunk dispatches the reassembled call with
+//! the origin of the sender. The outcome of the call is the outcome of the last message, so the
+//! sender may track the call by the nonce of this message.
+//!
+//! Alternatively, the message only carries the hash of the call, which dispatches the
+//! `dispatch_preimage` call. The call itself is noted as the preimage at this chain, which keeps
+//! message delivery proofs small. If the preimage has been noted before the message is delivered,
+//! the call is dispatched by the message. Otherwise the preimage is requested, and anyone may
+//! dispatch the pending call with `dispatch_pending_call` once the preimage is noted. Then the
+//! outcome of the message only tells that the call has been accepted.
+//!
+//! Calls, whose chunks or preimage haven't been received within `StagedCallLifetime` blocks, are
+//! discarded.
+//!
+//! Reassembled calls and preimages are never seen by the call filters of lanes, so chunks and
+//! hashes are only accepted from the `ChunksOrigin` and calls are checked by the `CallFilter`.
+
+use frame_support::{
+	dispatch::{DispatchErrorWithPostInfo, Dispatchable, PostDispatchInfo},
+	pallet_prelude::*,
+	traits::{Contains, Get, PreimageProvider},
+	weights::{extract_actual_weight, GetDispatchInfo, Weight},
+};
+use parity_scale_codec::DecodeLimit;
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::traits::Saturating;
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+/// Call, whose chunks are being received.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct StagedCall<BlockNumber> {
+	/// Total number of chunks of the call.
+	pub total: u32,
+	/// Number of chunks that have been received.
+	pub received: u32,
+	/// The call is discarded at this block, unless all chunks are received before.
+	pub expires_at: BlockNumber,
+}
+
+/// Call, whose preimage hasn't been noted when it has been received.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct PendingCall<BlockNumber> {
+	/// Weight that the call is dispatched with.
+	pub call_weight: Weight,
+	/// The call is discarded at this block, unless the preimage is noted before.
+	pub expires_at: BlockNumber,
+}
+
+/// Call is identified by its sender and hash of the encoded call.
+pub type StagedCallKey<AccountId> = (AccountId, H256);
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Configuration for the bridge chunks pallet.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The overreaching call type.
+		type Call: Parameter
+			+ Dispatchable<Origin = Self::Origin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo;
+
+		/// Origin that sends chunks, e.g. the account that messages of the bridged chain
+		/// governance are dispatched with. Reassembled calls are dispatched with this account.
+		type ChunksOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+
+		/// Filter of the reassembled calls and preimages.
+		type CallFilter: Contains<<Self as Config>::Call>;
+
+		/// Preimages of the calls that are dispatched by their hash.
+		type Preimages: PreimageProvider<H256>;
+
+		/// Maximal size of a single chunk.
+		#[pallet::constant]
+		type MaxChunkSize: Get<u32>;
+
+		/// Maximal number of chunks of a single call.
+		#[pallet::constant]
+		type MaxChunks: Get<u32>;
+
+		/// Maximal depth of calls, nested into the reassembled call.
+		#[pallet::constant]
+		type MaxCallDepth: Get<u32>;
+
+		/// Number of blocks, within which all chunks of the call must be received.
+		#[pallet::constant]
+		type StagedCallLifetime: Get<Self::BlockNumber>;
+
+		/// Maximal number of staged calls that expire at the same block.
+		#[pallet::constant]
+		type MaxExpiringCalls: Get<u32>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			for key in ExpiringCalls::<T>::take(now) {
+				// the call may have been dispatched, or received again, since it has been scheduled
+				weight = weight.saturating_add(T::DbWeight::get().reads(2));
+				let is_staged_call_expired =
+					StagedCalls::<T>::get(&key).map_or(false, |staged| staged.expires_at == now);
+				let is_pending_call_expired =
+					PendingCalls::<T>::get(&key).map_or(false, |pending| pending.expires_at == now);
+				if is_staged_call_expired {
+					Self::discard(&key);
+					weight = weight.saturating_add(T::DbWeight::get().writes(2));
+				}
+				if is_pending_call_expired {
+					PendingCalls::<T>::remove(&key);
+					T::Preimages::unrequest_preimage(&key.1);
+					weight = weight.saturating_add(T::DbWeight::get().reads_writes(1, 2));
+				}
+				if is_staged_call_expired || is_pending_call_expired {
+					Self::deposit_event(Event::CallDiscarded(key.0, key.1));
+				}
+			}
+
+			weight
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The chunk with given index of the call with given hash has been received from the
+		/// sender.
+		ChunkReceived(T::AccountId, H256, u32),
+		/// The preimage of the call with given hash, received from the sender, has been
+		/// requested.
+		PreimageRequested(T::AccountId, H256),
+		/// All chunks, or the preimage, of the call have been received and the call has been
+		/// dispatched with given result.
+		CallDispatched(T::AccountId, H256, DispatchResult),
+		/// Not all chunks, or the preimage, of the call have been received in time, so the call
+		/// has been discarded.
+		CallDiscarded(T::AccountId, H256),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The chunk is not the next chunk of the call.
+		InvalidChunk,
+		/// The call is split into too many chunks.
+		TooManyChunks,
+		/// The chunk is larger than `MaxChunkSize`.
+		ChunkTooLarge,
+		/// Too many calls are staged at this block.
+		TooManyStagedCalls,
+		/// The first chunk of the call hasn't been received, the call isn't pending, or the call
+		/// has expired.
+		UnknownCall,
+		/// Hash of the reassembled call doesn't match the hash of the call.
+		CallHashMismatch,
+		/// The call can't be decoded.
+		UndecodableCall,
+		/// The call is not allowed by the `CallFilter`.
+		CallNotAllowed,
+		/// The call requires more weight than has been declared.
+		InsufficientCallWeight,
+		/// The preimage of the pending call hasn't been noted yet.
+		PreimageMissing,
+	}
+
+	/// Calls, whose chunks are being received.
+	#[pallet::storage]
+	#[pallet::getter(fn staged_call)]
+	pub(crate) type StagedCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, StagedCallKey<T::AccountId>, StagedCall<T::BlockNumber>>;
+
+	/// Received chunks of staged calls, by their index.
+	#[pallet::storage]
+	pub(crate) type StagedChunks<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		StagedCallKey<T::AccountId>,
+		Identity,
+		u32,
+		BoundedVec<u8, T::MaxChunkSize>,
+	>;
+
+	/// Calls, whose preimages have been requested.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_call)]
+	pub(crate) type PendingCalls<T: Config> =
+		StorageMap<_, Blake2_128Concat, StagedCallKey<T::AccountId>, PendingCall<T::BlockNumber>>;
+
+	/// Staged and pending calls, by the block at which they expire.
+	#[pallet::storage]
+	pub(crate) type ExpiringCalls<T: Config> = StorageMap<
+		_,
+		Identity,
+		T::BlockNumber,
+		BoundedVec<StagedCallKey<T::AccountId>, T::MaxExpiringCalls>,
+		ValueQuery,
+	>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Receive the chunk of the call with given hash.
+		///
+		/// Chunks must be received in order. The first chunk discards chunks of the same call that
+		/// have been received before, and the last chunk dispatches the reassembled call with
+		/// given `call_weight`.
+		#[pallet::weight(Pallet::<T>::receive_chunk_weight(*index, *total, *call_weight))]
+		pub fn receive_chunk(
+			origin: OriginFor<T>,
+			call_hash: H256,
+			index: u32,
+			total: u32,
+			call_weight: Weight,
+			chunk: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			let sender = T::ChunksOrigin::ensure_origin(origin)?;
+			ensure!(index < total, Error::<T>::InvalidChunk);
+			ensure!(total <= T::MaxChunks::get(), Error::<T>::TooManyChunks);
+			let chunk: BoundedVec<_, T::MaxChunkSize> =
+				chunk.try_into().map_err(|_| Error::<T>::ChunkTooLarge)?;
+
+			let key = (sender, call_hash);
+			let staged = if index == 0 {
+				let expires_at = frame_system::Pallet::<T>::block_number()
+					.saturating_add(T::StagedCallLifetime::get());
+				ExpiringCalls::<T>::try_append(expires_at, key.clone())
+					.map_err(|_| Error::<T>::TooManyStagedCalls)?;
+				Self::discard(&key);
+				StagedCall { total, received: 0, expires_at }
+			} else {
+				StagedCalls::<T>::get(&key).ok_or(Error::<T>::UnknownCall)?
+			};
+			ensure!(staged.total == total && staged.received == index, Error::<T>::InvalidChunk);
+
+			if index + 1 < total {
+				StagedChunks::<T>::insert(&key, index, chunk);
+				StagedCalls::<T>::insert(&key, StagedCall { received: index + 1, ..staged });
+				Self::deposit_event(Event::ChunkReceived(key.0, key.1, index));
+				return Ok(().into())
+			}
+
+			// all chunks have been received, so the call is dispatched and forgotten
+			let mut encoded_call = Vec::new();
+			for chunk_index in 0..index {
+				encoded_call.extend(StagedChunks::<T>::take(&key, chunk_index).unwrap_or_default());
+			}
+			encoded_call.extend(chunk);
+			StagedCalls::<T>::remove(&key);
+
+			let declared_weight = Self::receive_chunk_weight(index, total, call_weight);
+			Self::dispatch_call(key, encoded_call, call_weight, declared_weight)
+		}
+
+		/// Dispatch the call with given hash, whose preimage is noted at this chain.
+		///
+		/// If the preimage has already been noted, the call is dispatched with given `call_weight`.
+		/// Otherwise the preimage is requested and the call becomes pending.
+		#[pallet::weight(Pallet::<T>::dispatch_preimage_weight(*call_weight))]
+		pub fn dispatch_preimage(
+			origin: OriginFor<T>,
+			call_hash: H256,
+			call_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let sender = T::ChunksOrigin::ensure_origin(origin)?;
+			let key = (sender, call_hash);
+			if let Some(encoded_call) = T::Preimages::get_preimage(&call_hash) {
+				let declared_weight = Self::dispatch_preimage_weight(call_weight);
+				return Self::dispatch_call(key, encoded_call, call_weight, declared_weight)
+			}
+
+			let expires_at = frame_system::Pallet::<T>::block_number()
+				.saturating_add(T::StagedCallLifetime::get());
+			ExpiringCalls::<T>::try_append(expires_at, key.clone())
+				.map_err(|_| Error::<T>::TooManyStagedCalls)?;
+			// the preimage is only requested once, because it is only unrequested once
+			if !PendingCalls::<T>::contains_key(&key) {
+				T::Preimages::request_preimage(&call_hash);
+			}
+			PendingCalls::<T>::insert(&key, PendingCall { call_weight, expires_at });
+
+			Self::deposit_event(Event::PreimageRequested(key.0, key.1));
+			Ok(Some(Self::dispatch_preimage_weight(0)).into())
+		}
+
+		/// Dispatch the pending call, whose preimage has been noted after the call has been
+		/// received.
+		///
+		/// May be called by anyone. The `call_weight_limit` must not be lower than the weight that
+		/// the sender has declared for the call.
+		#[pallet::weight(Pallet::<T>::dispatch_preimage_weight(*call_weight_limit))]
+		pub fn dispatch_pending_call(
+			origin: OriginFor<T>,
+			sender: T::AccountId,
+			call_hash: H256,
+			call_weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let key = (sender, call_hash);
+			let pending = PendingCalls::<T>::get(&key).ok_or(Error::<T>::UnknownCall)?;
+			ensure!(pending.call_weight <= call_weight_limit, Error::<T>::InsufficientCallWeight);
+			let encoded_call =
+				T::Preimages::get_preimage(&call_hash).ok_or(Error::<T>::PreimageMissing)?;
+
+			PendingCalls::<T>::remove(&key);
+			T::Preimages::unrequest_preimage(&call_hash);
+
+			let declared_weight = Self::dispatch_preimage_weight(pending.call_weight);
+			Self::dispatch_call(key, encoded_call, pending.call_weight, declared_weight)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Weight of receiving the chunk with given index. The last chunk also dispatches the call.
+	fn receive_chunk_weight(index: u32, total: u32, call_weight: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		if index.saturating_add(1) < total {
+			db_weight.reads_writes(3, 4)
+		} else {
+			db_weight
+				.reads_writes(2 + total as u64, 2 + total as u64)
+				.saturating_add(call_weight)
+		}
+	}
+
+	/// Weight of dispatching the call by its preimage.
+	fn dispatch_preimage_weight(call_weight: Weight) -> Weight {
+		T::DbWeight::get().reads_writes(3, 3).saturating_add(call_weight)
+	}
+
+	/// Dispatch the call, reassembled from chunks or noted as the preimage, with the origin of
+	/// its sender.
+	fn dispatch_call(
+		(sender, call_hash): StagedCallKey<T::AccountId>,
+		encoded_call: Vec<u8>,
+		call_weight: Weight,
+		declared_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		ensure!(
+			H256(sp_io::hashing::blake2_256(&encoded_call)) == call_hash,
+			Error::<T>::CallHashMismatch,
+		);
+		let call = <T as Config>::Call::decode_with_depth_limit(
+			T::MaxCallDepth::get(),
+			&mut &encoded_call[..],
+		)
+		.map_err(|_| Error::<T>::UndecodableCall)?;
+		ensure!(T::CallFilter::contains(&call), Error::<T>::CallNotAllowed);
+		let call_info = call.get_dispatch_info();
+		ensure!(call_info.weight <= call_weight, Error::<T>::InsufficientCallWeight);
+
+		let result = call.dispatch(frame_system::RawOrigin::Signed(sender.clone()).into());
+		let actual_call_weight = extract_actual_weight(&result, &call_info);
+		let result = result.map(drop).map_err(|err| err.error);
+		Self::deposit_event(Event::CallDispatched(sender, call_hash, result));
+
+		let post_info: PostDispatchInfo =
+			Some(declared_weight.saturating_sub(call_weight.saturating_sub(actual_call_weight)))
+				.into();
+		result
+			.map(|_| post_info)
+			.map_err(|error| DispatchErrorWithPostInfo { post_info, error })
+	}
+
+	/// Forget the staged call and its received chunks.
+	fn discard(key: &StagedCallKey<T::AccountId>) {
+		StagedCalls::<T>::remove(key);
+		StagedChunks::<T>::remove_prefix(key, None);
+	}
+}
@@ -0,0 +1,1323 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet for governance-controlled configuration of the Rococo <> Wococo message bridge.
+//!
+//! Keeps a registry of the message lanes that are open. Every lane has its own set of accounts
+//! allowed to send messages over it and a call filter restricting what inbound messages of the
+//! lane may dispatch. Governance opens additional lanes by registering them, and defines the
+//! calls that every call filter allows.
+//!
+//! Privileged origins have no account of their own, so governance may link them to accounts. The
+//! Root origin and the majority of the collective then send messages on behalf of their linked
+//! accounts, which must be allowed senders of the lane, as any other account.
+//!
+//! Inbound messages of a lane are either dispatched as encoded calls, or executed as XCM
+//! messages, depending on the dispatch mode of the lane. Calls of lanes with a dispatch delay are
+//! scheduled for dispatch at a future block instead of being dispatched in the delivery
+//! transaction.
+//!
+//! Messages of subsidized lanes (e.g. the lane of governance messages) are sent without a fee.
+//! Relayers that deliver them are rewarded from the subsidy account instead (see
+//! `crate::bridge_relayers`).
+//!
+//! Calls of inbound messages, sent by the Root origin of the bridged chain, must additionally be
+//! allowed by the source root call filter. Such messages are rejected until governance sets it.
+//!
+//! Call filters match calls by their pallet and call index, so calls that dispatch other calls
+//! (e.g. batches or proxy calls) are rejected by default. Governance may allow them up to a
+//! nesting depth, in which case every inner call must be allowed by the call filter as well.
+//!
+//! Governance may also cap the dispatch weight that messages declare for calls with the given
+//! pallet and call index. Messages declaring a larger weight are neither sent, nor dispatched, so
+//! cheap calls can't be used to waste the block space of relayer transactions.
+//!
+//! Inbound messages are dispatched only if they have been sent with the current spec version of
+//! this chain. After runtime upgrades that keep the encoding of calls, governance may accept
+//! messages sent with older spec versions, so that messages in flight are not stranded. At most
+//! `MaxSpecVersionWindow` older spec versions are accepted.
+//!
+//! The relayer interest that message fees must cover may be tuned per lane, using the
+//! `RelayerFeePercent` parameter of the messages pallets. The same way, the limits of pending,
+//! unrewarded and unconfirmed messages of a lane may be lowered with the `LaneLimits` parameter.
+//! Conversion rates and their limits, set by the other parameters, are kept by this pallet too.
+//! Delivery fees of outbound messages grow linearly with the size of the payload. The
+//! `LargeMessageFee` parameter adds the component that grows quadratically above the threshold
+//! size, so that very large messages pay for their impact on proof sizes and block space.
+//!
+//! Outbound lanes, whose pending messages reach `LaneCongestedThreshold` of the lane limit, are
+//! marked as congested, so that senders may apply backpressure. The lane stays congested until
+//! its pending messages drop to `LaneUncongestedThreshold` of the limit.
+//!
+//! Relayers keep the fee multiplier of the bridged chains in sync, by submitting storage proofs of
+//! the bridged chain state at finalized headers. The synced fee multiplier is used to estimate the
+//! cost of message deliveries at the bridged chain. Proofs are bounded by
+//! `MaxBridgedStateProofSize` and `MaxBridgedStateProofNodes`, and submitters only get their fee
+//! back if the proof actually changes the synced fee multiplier.
+//!
+//! XCM messages, sent to the bridged networks, are exported over the lanes that governance
+//! selects for the networks. New networks are onboarded, and their traffic is moved to fresh
+//! lanes, by changing the lane of the network.
+//!
+//! During incidents, governance may freeze the whole bridge by changing its operating mode,
+//! regardless of the configuration of individual lanes.
+//!
+//! The bridge is halted automatically if the GRANDPA authority set of a bridged chain, tracked by
+//! this chain, hasn't been changed for `MaxAuthoritySetAge` blocks. Changes of the set may have
+//! not been relayed, so proofs may be verified against the set that is no longer secure. Once the
+//! situation is resolved, governance resumes the bridge.
+
+use bp_message_dispatch::SpecVersion;
+use bp_messages::{LaneId, MessageNonce};
+use bp_runtime::ChainId;
+use frame_support::{
+	pallet_prelude::*, traits::Contains, CloneNoBound, PartialEqNoBound, RuntimeDebug,
+	RuntimeDebugNoBound,
+};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{FixedU128, Perbill};
+use sp_std::prelude::*;
+use xcm::latest::NetworkId;
+
+pub use pallet::*;
+
+pub mod migration;
+
+/// Identifier of the set of calls that inbound messages of a lane may dispatch.
+pub type CallFilterId = u32;
+
+/// The call filter that allows all calls. It can't be changed.
+pub const CALL_FILTER_ALL: CallFilterId = 0;
+
+/// Initial value of `BridgedToThisConversionRate` parameter.
+pub const INITIAL_BRIDGED_TO_THIS_CONVERSION_RATE: FixedU128 =
+	FixedU128::from_inner(FixedU128::DIV);
+
+/// A call, or a group of calls, allowed by a call filter.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum AllowedCall {
+	/// The call with the given pallet and call index, with any arguments.
+	Index { pallet: u8, call: u8 },
+	/// The call with the given blake2-256 hash of its encoding, i.e. with exactly these arguments.
+	Hash(H256),
+}
+
+/// How call filters treat calls that dispatch other calls, e.g. batches or proxy calls.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum NestedCallsFilter {
+	/// Calls that dispatch other calls are rejected, unless they are allowed by their hash.
+	Reject,
+	/// Calls that dispatch other calls are allowed if all calls that they dispatch are allowed by
+	/// the same call filter, and calls are nested at most `max_depth` times.
+	Inspect { max_depth: u8 },
+}
+
+impl Default for NestedCallsFilter {
+	fn default() -> Self {
+		NestedCallsFilter::Reject
+	}
+}
+
+/// Inspects calls that dispatch other calls.
+pub trait InnerCalls {
+	/// Returns the encoded calls that the encoded call dispatches, or `None` if it doesn't dispatch
+	/// other calls.
+	fn inner_calls(encoded_call: &[u8]) -> Option<Vec<Vec<u8>>>;
+}
+
+impl InnerCalls for () {
+	fn inner_calls(_encoded_call: &[u8]) -> Option<Vec<Vec<u8>>> {
+		None
+	}
+}
+
+impl AllowedCall {
+	/// Returns true if the encoded call is allowed.
+	fn matches(&self, encoded_call: &[u8], call_hash: &H256) -> bool {
+		match *self {
+			AllowedCall::Index { pallet, call } =>
+				encoded_call.get(..2) == Some(&[pallet, call][..]),
+			AllowedCall::Hash(ref hash) => hash == call_hash,
+		}
+	}
+}
+
+/// How inbound messages of a lane are dispatched.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum DispatchMode {
+	/// The payload is an encoded call, which is filtered by the call filter of the lane.
+	Call,
+	/// The payload is a `VersionedXcm`, which is executed by the XCM executor with the bridged
+	/// chain as origin. The call filter of the lane doesn't apply, the XCM barrier does.
+	Xcm,
+}
+
+/// Who pays for the delivery of outbound messages of a lane.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum LaneFeePayment {
+	/// The submitter pays the message fee, which is then paid to relayers.
+	Submitter,
+	/// Messages may be sent without a fee. Relayers are rewarded from the subsidy account.
+	Subsidized,
+}
+
+/// Privileged origin that may be linked to an account, to send messages on its behalf.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum LinkedOrigin {
+	/// The Root origin.
+	Root,
+	/// The origin of the collective, approved by the majority of its members.
+	Collective,
+}
+
+/// Operating mode of the whole bridge.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum OperatingMode {
+	/// Messages are sent and received over all enabled lanes.
+	Normal,
+	/// New outbound messages are rejected. Inbound messages are still received and dispatched.
+	RejectingOutboundMessages,
+	/// Messages are neither sent nor received.
+	Halted,
+}
+
+impl Default for OperatingMode {
+	fn default() -> Self {
+		OperatingMode::Normal
+	}
+}
+
+/// Limits of the messages of a lane.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct LaneLimits {
+	/// Maximal number of outbound messages that haven't been delivered yet.
+	pub max_pending_messages: MessageNonce,
+	/// Maximal number of unrewarded relayer entries at the inbound lane.
+	pub max_unrewarded_relayer_entries: MessageNonce,
+	/// Maximal number of inbound messages, whose delivery hasn't been confirmed yet.
+	pub max_unconfirmed_messages: MessageNonce,
+}
+
+/// Super-linear component of the delivery fee of large outbound messages.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct LargeMessageFee {
+	/// Size of the payload, above which the component is charged.
+	pub threshold: u32,
+	/// Additional delivery weight of every byte above the threshold, per byte above the
+	/// threshold.
+	pub byte_weight_per_byte: Weight,
+}
+
+impl LargeMessageFee {
+	/// Additional delivery weight of the payload with given size.
+	pub fn delivery_weight(&self, payload_len: u32) -> Weight {
+		let extra_bytes = Weight::from(payload_len.saturating_sub(self.threshold));
+		extra_bytes
+			.saturating_mul(extra_bytes)
+			.saturating_mul(self.byte_weight_per_byte)
+	}
+}
+
+/// Verifies storage proofs of the bridged chain state.
+pub trait BridgedFeeMultiplierProof<BlockNumber> {
+	/// Verifies the storage proof against the finalized header of the bridged chain and returns
+	/// the number of the header and the `NextFeeMultiplier` of the bridged chain at that header.
+	fn verify(
+		chain: ChainId,
+		at: H256,
+		storage_proof: Vec<Vec<u8>>,
+	) -> Result<(BlockNumber, FixedU128), DispatchError>;
+
+	/// Returns the weight of verifying the storage proof of the given size.
+	fn proof_weight(proof_size: u32) -> Weight;
+}
+
+/// Tracks the GRANDPA authority sets of the bridged chains.
+pub trait BridgedAuthoritySets<BlockNumber> {
+	/// Returns the numbers of blocks of this chain, at which the current authority sets of the
+	/// bridged chains have been imported. Chains, whose sets are not tracked yet, are skipped.
+	fn imported_at() -> Vec<(ChainId, BlockNumber)>;
+}
+
+/// Configuration of a registered message lane.
+#[derive(
+	CloneNoBound, PartialEqNoBound, RuntimeDebugNoBound, Encode, Decode, TypeInfo, MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T))]
+#[codec(mel_bound())]
+pub struct LaneConfig<T: Config> {
+	/// Whether messages are sent and received over the lane.
+	pub enabled: bool,
+	/// Accounts which are allowed to send messages over the lane.
+	pub allowed_senders: BoundedVec<T::AccountId, T::MaxAllowedMessageSenders>,
+	/// Calls that inbound messages of the lane may dispatch.
+	pub call_filter: CallFilterId,
+	/// How inbound messages of the lane are dispatched.
+	pub dispatch_mode: DispatchMode,
+	/// Who pays for the delivery of outbound messages of the lane.
+	pub fee_payment: LaneFeePayment,
+	/// Number of blocks that calls of inbound messages of the lane are delayed for. If `None`,
+	/// calls are dispatched in the delivery transaction.
+	pub dispatch_delay: Option<T::BlockNumber>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{
+		dispatch::{DispatchResult, DispatchResultWithPostInfo},
+		traits::EnsureOrigin,
+	};
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	#[pallet::storage_version(migration::STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	/// Configuration for the bridge config pallet.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Privileged origin that can change the bridge configuration.
+		type ConfigOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// Origin that can change the calls allowed by call filters.
+		type CallFilterOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin>;
+
+		/// Maximal number of accounts which are allowed to send messages over a single lane.
+		#[pallet::constant]
+		type MaxAllowedMessageSenders: Get<u32>;
+
+		/// Maximal number of entries of a single call filter.
+		#[pallet::constant]
+		type MaxAllowedCalls: Get<u32>;
+
+		/// Inspects calls that dispatch other calls, so that call filters may check inner calls.
+		type InnerCalls: InnerCalls;
+
+		/// Maximal difference between the current spec version of this chain and the oldest spec
+		/// version that inbound messages are accepted with.
+		#[pallet::constant]
+		type MaxSpecVersionWindow: Get<SpecVersion>;
+
+		/// Verifies storage proofs of the fee multiplier of the bridged chains.
+		type BridgedFeeMultiplierProof: BridgedFeeMultiplierProof<Self::BlockNumber>;
+
+		/// Maximal total size of the nodes of the bridged chain storage proof.
+		#[pallet::constant]
+		type MaxBridgedStateProofSize: Get<u32>;
+
+		/// Maximal number of nodes of the bridged chain storage proof.
+		#[pallet::constant]
+		type MaxBridgedStateProofNodes: Get<u32>;
+
+		/// Tracks the GRANDPA authority sets of the bridged chains.
+		type BridgedAuthoritySets: BridgedAuthoritySets<Self::BlockNumber>;
+
+		/// Number of blocks after which the bridge is halted, if the tracked authority set of a
+		/// bridged chain hasn't been changed.
+		#[pallet::constant]
+		type MaxAuthoritySetAge: Get<Self::BlockNumber>;
+
+		/// Share of the pending messages limit of an outbound lane, at which the lane becomes
+		/// congested.
+		#[pallet::constant]
+		type LaneCongestedThreshold: Get<Perbill>;
+
+		/// Share of the pending messages limit of a congested outbound lane, at which the lane is
+		/// no longer congested. Should be lower than `LaneCongestedThreshold`.
+		#[pallet::constant]
+		type LaneUncongestedThreshold: Get<Perbill>;
+
+		/// Maximal size of the call of outbound messages that may be delivered to the bridged
+		/// chains, derived from their maximal extrinsic size. Governance may only lower it.
+		#[pallet::constant]
+		type MaxOutboundPayloadSize: Get<u32>;
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let mut weight = T::DbWeight::get().reads(1);
+			if BridgeOperatingMode::<T>::get() == OperatingMode::Halted {
+				return weight
+			}
+
+			let resumed_at = ResumedAfterAuthoritySetStallAt::<T>::get();
+			let imported_at = T::BridgedAuthoritySets::imported_at();
+			weight = weight.saturating_add(T::DbWeight::get().reads(1 + imported_at.len() as u64));
+			let stalled_chain = imported_at.into_iter().find(|(_, imported_at)| {
+				now.saturating_sub(resumed_at.max(*imported_at)) > T::MaxAuthoritySetAge::get()
+			});
+			if let Some((chain, _)) = stalled_chain {
+				log::warn!(
+					target: "runtime::bridge",
+					"Halting the bridge: authority set of {:?} hasn't been changed for too long",
+					chain,
+				);
+
+				BridgeOperatingMode::<T>::put(OperatingMode::Halted);
+				Self::deposit_event(Event::AuthoritySetStalled(chain));
+				Self::deposit_event(Event::OperatingModeChanged(OperatingMode::Halted));
+				weight = weight.saturating_add(T::DbWeight::get().writes(1));
+			}
+
+			weight
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migration::migrate_to_latest::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migration::check_latest::<T>()
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The lane has been registered with the given call filter.
+		LaneRegistered(LaneId, CallFilterId),
+		/// The lane has been enabled.
+		LaneEnabled(LaneId),
+		/// The lane has been disabled.
+		LaneDisabled(LaneId),
+		/// The call filter of the lane has been changed.
+		LaneCallFilterChanged(LaneId, CallFilterId),
+		/// The call filter of messages, sent by the bridged chain Root, has been changed.
+		SourceRootCallFilterChanged(Option<CallFilterId>),
+		/// The account is now allowed to send messages over the lane.
+		MessageSenderAdded(LaneId, T::AccountId),
+		/// The account is no longer allowed to send messages over the lane.
+		MessageSenderRemoved(LaneId, T::AccountId),
+		/// The privileged origin has been linked to the account, or unlinked if there's no account.
+		OriginAccountChanged(LinkedOrigin, Option<T::AccountId>),
+		/// The calls allowed by the call filter have been changed.
+		CallFilterChanged(CallFilterId),
+		/// The way call filters treat calls that dispatch other calls has been changed.
+		NestedCallsFilterChanged(NestedCallsFilter),
+		/// The dispatch weight limit of calls with the given pallet and call index has been
+		/// changed, or removed if there's no limit.
+		CallWeightLimitChanged(u8, u8, Option<Weight>),
+		/// The dispatch mode of the lane has been changed.
+		LaneDispatchModeChanged(LaneId, DispatchMode),
+		/// The fee payment of the lane has been changed.
+		LaneFeePaymentChanged(LaneId, LaneFeePayment),
+		/// The dispatch delay of the lane has been changed.
+		LaneDispatchDelayChanged(LaneId, Option<T::BlockNumber>),
+		/// The oldest spec version that inbound messages are accepted with has been changed.
+		OldestCompatibleSpecVersionChanged(Option<SpecVersion>),
+		/// The operating mode of the bridge has been changed.
+		OperatingModeChanged(OperatingMode),
+		/// The fee multiplier of the bridged chain has been synced at the given bridged block.
+		BridgedFeeMultiplierSynced(ChainId, T::BlockNumber, FixedU128),
+		/// The authority set of the bridged chain hasn't been changed for too long, so the bridge
+		/// has been halted.
+		AuthoritySetStalled(ChainId),
+		/// The outbound lane to the bridged chain has too many pending messages.
+		LaneCongested(ChainId, LaneId),
+		/// The outbound lane to the bridged chain is no longer congested.
+		LaneUncongested(ChainId, LaneId),
+		/// The relayer interest of the lane has been changed by the `RelayerFeePercent` parameter.
+		RelayerFeePercentUpdated { lane: LaneId, old: Option<u32>, new: u32 },
+		/// The limits of the lane have been changed by the `LaneLimits` parameter.
+		LaneLimitsUpdated { lane: LaneId, old: Option<LaneLimits>, new: LaneLimits },
+		/// The conversion rate of the bridged chain tokens to the tokens of this chain has been
+		/// changed by the `BridgedToThisConversionRate` parameter.
+		ConversionRateUpdated { old: FixedU128, new: FixedU128 },
+		/// The conversion rate staleness has been changed by the `MaxConversionRateStaleness`
+		/// parameter.
+		MaxConversionRateStalenessUpdated {
+			old: Option<T::BlockNumber>,
+			new: Option<T::BlockNumber>,
+		},
+		/// The conversion rate change limit has been changed by the `ConversionRateChangeLimit`
+		/// parameter.
+		ConversionRateChangeLimitUpdated {
+			old: Option<(Perbill, T::BlockNumber)>,
+			new: Option<(Perbill, T::BlockNumber)>,
+		},
+		/// The conversion rate of the native tokens to the asset that message fees may be paid in
+		/// has been changed by the `FeeAssetConversionRate` parameter.
+		FeeAssetConversionRateUpdated { old: FixedU128, new: FixedU128 },
+		/// The lane that XCM messages are exported over to the network has been changed, or
+		/// removed if there's no lane.
+		XcmExportLaneChanged(NetworkId, Option<LaneId>),
+		/// The maximal size of the call of outbound messages has been changed, or reset to
+		/// the `MaxOutboundPayloadSize` if there's no size.
+		MaximalOutboundPayloadSizeChanged(Option<u32>),
+		/// The super-linear fee component of large outbound messages has been changed by the
+		/// `LargeMessageFee` parameter.
+		LargeMessageFeeUpdated { old: Option<LargeMessageFee>, new: Option<LargeMessageFee> },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The lane is already registered.
+		LaneAlreadyRegistered,
+		/// The lane is not registered.
+		LaneNotRegistered,
+		/// The account is already allowed to send messages.
+		AlreadyAllowed,
+		/// The account is not allowed to send messages.
+		NotAllowed,
+		/// The maximal number of allowed message senders has been reached.
+		TooManyMessageSenders,
+		/// The call filter can't be changed.
+		ReservedCallFilter,
+		/// The call filter has too many entries.
+		TooManyAllowedCalls,
+		/// The fee multiplier has already been synced at the same or a later bridged block.
+		OutdatedFeeMultiplier,
+		/// The spec version is newer than the current spec version, or older than the spec
+		/// version window allows.
+		SpecVersionOutsideWindow,
+		/// The size is larger than the `MaxOutboundPayloadSize`.
+		OutboundPayloadSizeAboveLimit,
+		/// The chain is not bridged.
+		UnknownBridgedChain,
+		/// The storage proof has no valid fee multiplier of the bridged chain.
+		InvalidFeeMultiplierProof,
+		/// The storage proof has more nodes or bytes than allowed.
+		StorageProofTooLarge,
+	}
+
+	/// Configuration of all registered lanes.
+	#[pallet::storage]
+	#[pallet::getter(fn lane_config)]
+	pub(crate) type Lanes<T: Config> = StorageMap<_, Blake2_128Concat, LaneId, LaneConfig<T>>;
+
+	/// Accounts that privileged origins are sending messages on behalf of.
+	#[pallet::storage]
+	#[pallet::getter(fn linked_origin_account)]
+	pub(crate) type LinkedOriginAccounts<T: Config> =
+		StorageMap<_, Twox64Concat, LinkedOrigin, T::AccountId>;
+
+	/// Calls allowed by the call filters. Call filters without an entry don't allow any call.
+	#[pallet::storage]
+	#[pallet::getter(fn allowed_calls)]
+	pub(crate) type CallFilters<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		CallFilterId,
+		BoundedVec<AllowedCall, T::MaxAllowedCalls>,
+		ValueQuery,
+	>;
+
+	/// Call filter of inbound messages, sent by the Root origin of the bridged chain.
+	///
+	/// Applies in addition to the call filter of the lane. If not set, these messages are rejected.
+	#[pallet::storage]
+	#[pallet::getter(fn source_root_call_filter)]
+	pub(crate) type SourceRootCallFilter<T: Config> = StorageValue<_, CallFilterId>;
+
+	/// How call filters treat calls that dispatch other calls.
+	#[pallet::storage]
+	#[pallet::getter(fn nested_calls_filter)]
+	pub(crate) type NestedCalls<T: Config> = StorageValue<_, NestedCallsFilter, ValueQuery>;
+
+	/// The oldest spec version of this chain that inbound messages are accepted with, in addition
+	/// to the current spec version.
+	///
+	/// Messages with spec versions that are older than `MaxSpecVersionWindow` allows are refused,
+	/// so the window closes automatically after further runtime upgrades.
+	#[pallet::storage]
+	#[pallet::getter(fn oldest_compatible_spec_version)]
+	pub(crate) type OldestCompatibleSpecVersion<T: Config> = StorageValue<_, SpecVersion>;
+
+	/// Maximal dispatch weight that messages may declare for calls with the given pallet and call
+	/// index. Calls without an entry are only limited by the maximal dispatch weight of messages.
+	#[pallet::storage]
+	#[pallet::getter(fn call_weight_limit)]
+	pub(crate) type CallWeightLimits<T: Config> = StorageMap<_, Twox64Concat, (u8, u8), Weight>;
+
+	/// Operating mode of the bridge.
+	#[pallet::storage]
+	#[pallet::getter(fn operating_mode)]
+	pub(crate) type BridgeOperatingMode<T: Config> = StorageValue<_, OperatingMode, ValueQuery>;
+
+	/// Relayer interest (in percents) of lanes that don't use the default interest of the bridge.
+	///
+	/// Updated through the `RelayerFeePercent` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_fee_percent)]
+	pub(crate) type RelayerFeePercents<T: Config> = StorageMap<_, Blake2_128Concat, LaneId, u32>;
+
+	/// Limits of lanes that don't use the maximal limits of the bridge.
+	///
+	/// Updated through the `LaneLimits` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn lane_limits)]
+	pub(crate) type CustomLaneLimits<T: Config> =
+		StorageMap<_, Blake2_128Concat, LaneId, LaneLimits>;
+
+	/// Outbound lanes to the bridged chains that are congested, mapped to the number of pending
+	/// messages at which they are no longer congested.
+	#[pallet::storage]
+	pub(crate) type CongestedLanes<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, ChainId, Blake2_128Concat, LaneId, MessageNonce>;
+
+	/// Fee multiplier of the bridged chains, together with the bridged block it has been synced at.
+	#[pallet::storage]
+	#[pallet::getter(fn bridged_fee_multiplier)]
+	pub(crate) type BridgedFeeMultipliers<T: Config> =
+		StorageMap<_, Twox64Concat, ChainId, (T::BlockNumber, FixedU128)>;
+
+	/// Block at which governance has resumed the bridge, halted because of the stalled authority
+	/// set. Authority sets are not considered stalled for `MaxAuthoritySetAge` blocks after that.
+	#[pallet::storage]
+	#[pallet::getter(fn resumed_after_authority_set_stall_at)]
+	pub(crate) type ResumedAfterAuthoritySetStallAt<T: Config> =
+		StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::type_value]
+	pub(crate) fn InitialConversionRate() -> FixedU128 {
+		INITIAL_BRIDGED_TO_THIS_CONVERSION_RATE
+	}
+
+	#[pallet::type_value]
+	pub(crate) fn InitialConversionRateChangePeriod<T: Config>() -> (T::BlockNumber, FixedU128) {
+		(Default::default(), INITIAL_BRIDGED_TO_THIS_CONVERSION_RATE)
+	}
+
+	/// Conversion rate of the bridged chain tokens to the tokens of this chain. The conversion
+	/// formula we use is: `ThisTokens = BridgedTokens * conversion_rate`.
+	///
+	/// Updated through the `BridgedToThisConversionRate` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn bridged_to_this_conversion_rate)]
+	pub(crate) type BridgedToThisConversionRate<T: Config> =
+		StorageValue<_, FixedU128, ValueQuery, InitialConversionRate>;
+
+	/// Block at which `BridgedToThisConversionRate` has been updated last time.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate_updated_at)]
+	pub(crate) type ConversionRateUpdatedAt<T: Config> =
+		StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	/// Number of blocks after the last update of `BridgedToThisConversionRate`, when outbound
+	/// messages start being rejected. If not set, the conversion rate never goes stale.
+	///
+	/// Updated through the `MaxConversionRateStaleness` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn max_conversion_rate_staleness)]
+	pub(crate) type MaxConversionRateStaleness<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// Maximal change of `BridgedToThisConversionRate` within a period of the given number of
+	/// blocks, relative to the rate at the start of the period. If not set, changes are not
+	/// limited.
+	///
+	/// Updated through the `ConversionRateChangeLimit` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate_change_limit)]
+	pub(crate) type ConversionRateChangeLimit<T: Config> =
+		StorageValue<_, (Perbill, T::BlockNumber)>;
+
+	/// Block at which the current conversion rate change period has started, and the conversion
+	/// rate at that block.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate_change_period_start)]
+	pub(crate) type ConversionRateChangePeriodStart<T: Config> = StorageValue<
+		_,
+		(T::BlockNumber, FixedU128),
+		ValueQuery,
+		InitialConversionRateChangePeriod<T>,
+	>;
+
+	/// Conversion rate of the native tokens to the asset that message fees may be paid in. The
+	/// conversion formula we use is: `AssetTokens = NativeTokens * conversion_rate`.
+	///
+	/// Updated through the `FeeAssetConversionRate` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_asset_conversion_rate)]
+	pub(crate) type FeeAssetConversionRate<T: Config> =
+		StorageValue<_, FixedU128, ValueQuery, InitialConversionRate>;
+
+	/// Lanes that XCM messages are exported over to the bridged networks. Messages to networks
+	/// without an entry are not exported.
+	#[pallet::storage]
+	#[pallet::getter(fn xcm_export_lane)]
+	pub(crate) type XcmExportLanes<T: Config> = StorageMap<_, Blake2_128Concat, NetworkId, LaneId>;
+
+	/// Maximal size of the call of outbound messages, set by governance. If there's no value,
+	/// the `MaxOutboundPayloadSize` is used.
+	#[pallet::storage]
+	pub(crate) type MaximalOutboundPayloadSize<T: Config> = StorageValue<_, u32>;
+
+	/// Super-linear component of the delivery fee of large outbound messages. If not set, the
+	/// fee grows linearly with the size of the payload.
+	///
+	/// Updated through the `LargeMessageFee` parameter of the messages pallets.
+	#[pallet::storage]
+	#[pallet::getter(fn large_message_fee)]
+	pub(crate) type LargeMessageFeeCurve<T: Config> = StorageValue<_, LargeMessageFee>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig {
+		/// Lanes which are open at genesis, together with their call filter.
+		pub lanes: Vec<(LaneId, CallFilterId)>,
+		/// Call filter of inbound messages, sent by the Root origin of the bridged chain.
+		pub source_root_call_filter: Option<CallFilterId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl Default for GenesisConfig {
+		fn default() -> Self {
+			GenesisConfig { lanes: Default::default(), source_root_call_filter: None }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			for (lane, call_filter) in &self.lanes {
+				Lanes::<T>::insert(lane, LaneConfig::<T>::new(*call_filter));
+			}
+			SourceRootCallFilter::<T>::set(self.source_root_call_filter);
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a new, enabled lane without any allowed message senders.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn register_lane(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			call_filter: CallFilterId,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+			ensure!(!Lanes::<T>::contains_key(lane), Error::<T>::LaneAlreadyRegistered);
+
+			Lanes::<T>::insert(lane, LaneConfig::<T>::new(call_filter));
+
+			Self::deposit_event(Event::LaneRegistered(lane, call_filter));
+			Ok(())
+		}
+
+		/// Enable or disable sending and receiving messages over the lane.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_lane_enabled(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			enabled: bool,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				config.enabled = enabled;
+				Ok(())
+			})?;
+
+			if enabled {
+				Self::deposit_event(Event::LaneEnabled(lane));
+			} else {
+				Self::deposit_event(Event::LaneDisabled(lane));
+			}
+			Ok(())
+		}
+
+		/// Change the calls that inbound messages of the lane may dispatch.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_lane_call_filter(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			call_filter: CallFilterId,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				config.call_filter = call_filter;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::LaneCallFilterChanged(lane, call_filter));
+			Ok(())
+		}
+
+		/// Change the calls that inbound messages, sent by the bridged chain Root, may dispatch.
+		///
+		/// If the call filter is `None`, these messages are rejected.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_source_root_call_filter(
+			origin: OriginFor<T>,
+			call_filter: Option<CallFilterId>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			SourceRootCallFilter::<T>::set(call_filter);
+
+			Self::deposit_event(Event::SourceRootCallFilterChanged(call_filter));
+			Ok(())
+		}
+
+		/// Change how inbound messages of the lane are dispatched.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_lane_dispatch_mode(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			dispatch_mode: DispatchMode,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				config.dispatch_mode = dispatch_mode;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::LaneDispatchModeChanged(lane, dispatch_mode));
+			Ok(())
+		}
+
+		/// Change who pays for the delivery of outbound messages of the lane.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_lane_fee_payment(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			fee_payment: LaneFeePayment,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				config.fee_payment = fee_payment;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::LaneFeePaymentChanged(lane, fee_payment));
+			Ok(())
+		}
+
+		/// Change the number of blocks that calls of inbound messages of the lane are delayed for.
+		///
+		/// The delay only applies to lanes that dispatch encoded calls. If it is `None`, calls are
+		/// dispatched in the delivery transaction.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_lane_dispatch_delay(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			dispatch_delay: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				config.dispatch_delay = dispatch_delay;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::LaneDispatchDelayChanged(lane, dispatch_delay));
+			Ok(())
+		}
+
+		/// Change the oldest spec version that inbound messages are accepted with.
+		///
+		/// Messages with spec versions between it and the current spec version are dispatched as
+		/// if they have been sent with the current spec version, so it must only be set if calls
+		/// are encoded the same way since that spec version. If it is `None`, only messages with
+		/// the current spec version are accepted.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_oldest_compatible_spec_version(
+			origin: OriginFor<T>,
+			spec_version: Option<SpecVersion>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+			if let Some(spec_version) = spec_version {
+				ensure!(
+					Self::is_within_spec_version_window(spec_version),
+					Error::<T>::SpecVersionOutsideWindow,
+				);
+			}
+
+			OldestCompatibleSpecVersion::<T>::set(spec_version);
+
+			Self::deposit_event(Event::OldestCompatibleSpecVersionChanged(spec_version));
+			Ok(())
+		}
+
+		/// Change the operating mode of the bridge, e.g. to halt it during an incident.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_operating_mode(
+			origin: OriginFor<T>,
+			operating_mode: OperatingMode,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			BridgeOperatingMode::<T>::put(operating_mode);
+
+			Self::deposit_event(Event::OperatingModeChanged(operating_mode));
+			Ok(())
+		}
+
+		/// Resume the bridge, halted because the authority set of a bridged chain hasn't been
+		/// changed for too long.
+		///
+		/// Authority sets are not considered stalled for another `MaxAuthoritySetAge` blocks.
+		#[pallet::weight(T::DbWeight::get().writes(2))]
+		pub fn resume_after_authority_set_stall(origin: OriginFor<T>) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			ResumedAfterAuthoritySetStallAt::<T>::put(frame_system::Pallet::<T>::block_number());
+			BridgeOperatingMode::<T>::put(OperatingMode::Normal);
+
+			Self::deposit_event(Event::OperatingModeChanged(OperatingMode::Normal));
+			Ok(())
+		}
+
+		/// Allow the account to send messages over the lane.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn add_message_sender(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			sender: T::AccountId,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				ensure!(!config.allowed_senders.contains(&sender), Error::<T>::AlreadyAllowed);
+				config
+					.allowed_senders
+					.try_push(sender.clone())
+					.map_err(|_| Error::<T>::TooManyMessageSenders)
+			})?;
+
+			Self::deposit_event(Event::MessageSenderAdded(lane, sender));
+			Ok(())
+		}
+
+		/// Disallow the account to send messages over the lane.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn remove_message_sender(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			sender: T::AccountId,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			Self::mutate_lane(lane, |config| {
+				let position = config
+					.allowed_senders
+					.iter()
+					.position(|s| s == &sender)
+					.ok_or(Error::<T>::NotAllowed)?;
+				config.allowed_senders.remove(position);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::MessageSenderRemoved(lane, sender));
+			Ok(())
+		}
+
+		/// Link the privileged origin to the account, or unlink it if the account is `None`.
+		///
+		/// The account must still be allowed to send messages over the lane.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_origin_account(
+			origin: OriginFor<T>,
+			linked_origin: LinkedOrigin,
+			account: Option<T::AccountId>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+
+			LinkedOriginAccounts::<T>::set(linked_origin, account.clone());
+
+			Self::deposit_event(Event::OriginAccountChanged(linked_origin, account));
+			Ok(())
+		}
+
+		/// Replace the calls allowed by the call filter.
+		///
+		/// An empty list of allowed calls removes the call filter, so it doesn't allow any call.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_call_filter(
+			origin: OriginFor<T>,
+			call_filter: CallFilterId,
+			allowed_calls: Vec<AllowedCall>,
+		) -> DispatchResult {
+			T::CallFilterOrigin::ensure_origin(origin)?;
+			ensure!(call_filter != CALL_FILTER_ALL, Error::<T>::ReservedCallFilter);
+
+			let allowed_calls: BoundedVec<_, T::MaxAllowedCalls> =
+				allowed_calls.try_into().map_err(|_| Error::<T>::TooManyAllowedCalls)?;
+			if allowed_calls.is_empty() {
+				CallFilters::<T>::remove(call_filter);
+			} else {
+				CallFilters::<T>::insert(call_filter, allowed_calls);
+			}
+
+			Self::deposit_event(Event::CallFilterChanged(call_filter));
+			Ok(())
+		}
+
+		/// Change how call filters treat calls that dispatch other calls.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_nested_calls_filter(
+			origin: OriginFor<T>,
+			nested_calls_filter: NestedCallsFilter,
+		) -> DispatchResult {
+			T::CallFilterOrigin::ensure_origin(origin)?;
+
+			NestedCalls::<T>::put(nested_calls_filter);
+
+			Self::deposit_event(Event::NestedCallsFilterChanged(nested_calls_filter));
+			Ok(())
+		}
+
+		/// Change the maximal dispatch weight that messages may declare for calls with the given
+		/// pallet and call index, or remove the limit if it is `None`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_call_weight_limit(
+			origin: OriginFor<T>,
+			pallet: u8,
+			call: u8,
+			weight_limit: Option<Weight>,
+		) -> DispatchResult {
+			T::CallFilterOrigin::ensure_origin(origin)?;
+
+			CallWeightLimits::<T>::set((pallet, call), weight_limit);
+
+			Self::deposit_event(Event::CallWeightLimitChanged(pallet, call, weight_limit));
+			Ok(())
+		}
+
+		/// Sync the fee multiplier of the bridged chain from a storage proof of its state at the
+		/// finalized header.
+		///
+		/// The fee multiplier must be synced at a later bridged block than before. The
+		/// transaction is free if the synced fee multiplier differs from the previous one.
+		#[pallet::weight(
+			T::DbWeight::get()
+				.reads_writes(2, 1)
+				.saturating_add(T::BridgedFeeMultiplierProof::proof_weight(
+					storage_proof_size(storage_proof),
+				))
+		)]
+		pub fn sync_bridged_fee_multiplier(
+			origin: OriginFor<T>,
+			chain: ChainId,
+			at: H256,
+			storage_proof: Vec<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(
+				storage_proof.len() <= T::MaxBridgedStateProofNodes::get() as usize &&
+					storage_proof_size(&storage_proof) <= T::MaxBridgedStateProofSize::get(),
+				Error::<T>::StorageProofTooLarge,
+			);
+			let (block_number, fee_multiplier) =
+				T::BridgedFeeMultiplierProof::verify(chain, at, storage_proof)?;
+			let synced = BridgedFeeMultipliers::<T>::get(chain);
+			ensure!(
+				synced.map_or(true, |(synced_at, _)| block_number > synced_at),
+				Error::<T>::OutdatedFeeMultiplier,
+			);
+
+			BridgedFeeMultipliers::<T>::insert(chain, (block_number, fee_multiplier));
+			Self::deposit_event(Event::BridgedFeeMultiplierSynced(
+				chain,
+				block_number,
+				fee_multiplier,
+			));
+
+			let is_changed = synced.map_or(true, |(_, synced)| synced != fee_multiplier);
+			Ok(if is_changed { Pays::No } else { Pays::Yes }.into())
+		}
+
+		/// Change the lane that XCM messages are exported over to the network, or stop exporting
+		/// messages to the network if the lane is `None`.
+		///
+		/// The lane must be registered.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn set_xcm_export_lane(
+			origin: OriginFor<T>,
+			network: NetworkId,
+			lane: Option<LaneId>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+			if let Some(lane) = lane {
+				ensure!(Lanes::<T>::contains_key(lane), Error::<T>::LaneNotRegistered);
+			}
+
+			XcmExportLanes::<T>::set(network.clone(), lane);
+
+			Self::deposit_event(Event::XcmExportLaneChanged(network, lane));
+			Ok(())
+		}
+
+		/// Restrict the size of the call of outbound messages, e.g. during congestion of the
+		/// bridge, or lift the restriction if the size is `None`.
+		///
+		/// The size can't be larger than the `MaxOutboundPayloadSize`.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_maximal_outbound_payload_size(
+			origin: OriginFor<T>,
+			size: Option<u32>,
+		) -> DispatchResult {
+			T::ConfigOrigin::ensure_origin(origin)?;
+			if let Some(size) = size {
+				ensure!(
+					size <= T::MaxOutboundPayloadSize::get(),
+					Error::<T>::OutboundPayloadSizeAboveLimit,
+				);
+			}
+
+			MaximalOutboundPayloadSize::<T>::set(size);
+
+			Self::deposit_event(Event::MaximalOutboundPayloadSizeChanged(size));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Contains<SpecVersion> for Pallet<T> {
+	fn contains(spec_version: &SpecVersion) -> bool {
+		Self::is_compatible_spec_version(*spec_version)
+	}
+}
+
+impl<T: Config> LaneConfig<T> {
+	fn new(call_filter: CallFilterId) -> Self {
+		LaneConfig {
+			enabled: true,
+			allowed_senders: Default::default(),
+			call_filter,
+			dispatch_mode: DispatchMode::Call,
+			fee_payment: LaneFeePayment::Submitter,
+			dispatch_delay: None,
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns true if new outbound messages are accepted by the bridge.
+	pub fn is_sending_messages() -> bool {
+		BridgeOperatingMode::<T>::get() == OperatingMode::Normal
+	}
+
+	/// Returns true if inbound messages are received by the bridge.
+	pub fn is_receiving_messages() -> bool {
+		BridgeOperatingMode::<T>::get() != OperatingMode::Halted
+	}
+
+	/// Returns true if the lane is registered and enabled.
+	pub fn is_lane_enabled(lane: &LaneId) -> bool {
+		Lanes::<T>::get(lane).map_or(false, |config| config.enabled)
+	}
+
+	/// Returns true if the account is allowed to send messages over the lane and the lane is
+	/// enabled.
+	pub fn is_allowed_message_sender(lane: &LaneId, account: &T::AccountId) -> bool {
+		Lanes::<T>::get(lane)
+			.map_or(false, |config| config.enabled && config.allowed_senders.contains(account))
+	}
+
+	/// Returns the call filter of the lane, if the lane is registered and enabled.
+	pub fn lane_call_filter(lane: &LaneId) -> Option<CallFilterId> {
+		Lanes::<T>::get(lane)
+			.filter(|config| config.enabled)
+			.map(|config| config.call_filter)
+	}
+
+	/// Returns the dispatch mode of the lane, if the lane is registered and enabled.
+	pub fn lane_dispatch_mode(lane: &LaneId) -> Option<DispatchMode> {
+		Lanes::<T>::get(lane)
+			.filter(|config| config.enabled)
+			.map(|config| config.dispatch_mode)
+	}
+
+	/// Returns the dispatch delay of the lane, if the lane is registered, enabled and has one.
+	pub fn lane_dispatch_delay(lane: &LaneId) -> Option<T::BlockNumber> {
+		Lanes::<T>::get(lane)
+			.filter(|config| config.enabled)
+			.and_then(|config| config.dispatch_delay)
+	}
+
+	/// Returns the maximal size of the call of outbound messages.
+	pub fn maximal_outbound_payload_size() -> u32 {
+		let max_size = T::MaxOutboundPayloadSize::get();
+		MaximalOutboundPayloadSize::<T>::get().map_or(max_size, |size| size.min(max_size))
+	}
+
+	/// Returns true if inbound messages, sent with the spec version, are accepted.
+	pub fn is_compatible_spec_version(spec_version: SpecVersion) -> bool {
+		if spec_version == T::Version::get().spec_version {
+			return true
+		}
+
+		OldestCompatibleSpecVersion::<T>::get().map_or(false, |oldest_spec_version| {
+			spec_version >= oldest_spec_version &&
+				Self::is_within_spec_version_window(oldest_spec_version) &&
+				Self::is_within_spec_version_window(spec_version)
+		})
+	}
+
+	/// Returns true if the lane is registered, enabled and subsidized.
+	pub fn is_lane_subsidized(lane: &LaneId) -> bool {
+		Lanes::<T>::get(lane).map_or(false, |config| {
+			config.enabled && config.fee_payment == LaneFeePayment::Subsidized
+		})
+	}
+
+	/// Returns true if the spec version is not newer than the current spec version and not older
+	/// than `MaxSpecVersionWindow` allows.
+	fn is_within_spec_version_window(spec_version: SpecVersion) -> bool {
+		let current_spec_version = T::Version::get().spec_version;
+		spec_version <= current_spec_version &&
+			current_spec_version - spec_version <= T::MaxSpecVersionWindow::get()
+	}
+
+	/// Returns true if the call filter allows dispatching the encoded call.
+	///
+	/// Calls that dispatch other calls are checked according to the nested calls filter, unless
+	/// the call filter allows all calls.
+	pub fn is_call_allowed(call_filter: CallFilterId, encoded_call: &[u8]) -> bool {
+		if call_filter == CALL_FILTER_ALL {
+			return true
+		}
+
+		let max_depth = match NestedCalls::<T>::get() {
+			NestedCallsFilter::Reject => 0,
+			NestedCallsFilter::Inspect { max_depth } => max_depth,
+		};
+		Self::is_nested_call_allowed(&CallFilters::<T>::get(call_filter), encoded_call, max_depth)
+	}
+
+	/// Returns true if the allowed calls include the encoded call and, unless the call is allowed
+	/// by its hash, all calls that it dispatches, nested at most `max_depth` times.
+	fn is_nested_call_allowed(
+		allowed_calls: &[AllowedCall],
+		encoded_call: &[u8],
+		max_depth: u8,
+	) -> bool {
+		let call_hash = H256(sp_io::hashing::blake2_256(encoded_call));
+		if allowed_calls.contains(&AllowedCall::Hash(call_hash)) {
+			return true
+		}
+		if !allowed_calls.iter().any(|allowed| allowed.matches(encoded_call, &call_hash)) {
+			return false
+		}
+
+		match T::InnerCalls::inner_calls(encoded_call) {
+			None => true,
+			Some(_) if max_depth == 0 => false,
+			Some(inner_calls) => inner_calls.iter().all(|inner_call| {
+				Self::is_nested_call_allowed(allowed_calls, inner_call, max_depth - 1)
+			}),
+		}
+	}
+
+	/// Returns true if the source root call filter allows dispatching the encoded call.
+	pub fn is_source_root_call_allowed(encoded_call: &[u8]) -> bool {
+		SourceRootCallFilter::<T>::get()
+			.map_or(false, |call_filter| Self::is_call_allowed(call_filter, encoded_call))
+	}
+
+	/// Returns the maximal dispatch weight that messages may declare for the encoded call, if
+	/// governance has limited it.
+	pub fn dispatch_weight_limit(encoded_call: &[u8]) -> Option<Weight> {
+		match encoded_call {
+			[pallet, call, ..] => CallWeightLimits::<T>::get((pallet, call)),
+			_ => None,
+		}
+	}
+
+	/// Returns true if the outbound lane to the bridged chain is congested.
+	pub fn is_lane_congested(chain: ChainId, lane: &LaneId) -> bool {
+		CongestedLanes::<T>::contains_key(chain, lane)
+	}
+
+	/// Marks the outbound lane as congested, if its pending messages have reached the congestion
+	/// threshold of `max_pending_messages`. Returns the consumed weight.
+	pub fn on_pending_messages_increased(
+		chain: ChainId,
+		lane: &LaneId,
+		pending_messages: MessageNonce,
+		max_pending_messages: impl FnOnce() -> MessageNonce,
+	) -> Weight {
+		let mut weight = T::DbWeight::get().reads(1);
+		if Self::is_lane_congested(chain, lane) {
+			return weight
+		}
+
+		let max_pending_messages = max_pending_messages();
+		if pending_messages >= T::LaneCongestedThreshold::get().mul_ceil(max_pending_messages) {
+			let uncongested_at = T::LaneUncongestedThreshold::get() * max_pending_messages;
+			CongestedLanes::<T>::insert(chain, lane, uncongested_at);
+			Self::deposit_event(Event::LaneCongested(chain, *lane));
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		}
+
+		weight
+	}
+
+	/// Marks the congested outbound lane as uncongested, if its pending messages have dropped to
+	/// the uncongestion threshold. Returns the consumed weight.
+	pub fn on_pending_messages_decreased(
+		chain: ChainId,
+		lane: &LaneId,
+		pending_messages: MessageNonce,
+	) -> Weight {
+		let mut weight = T::DbWeight::get().reads(1);
+		let is_uncongested = CongestedLanes::<T>::get(chain, lane)
+			.map_or(false, |uncongested_at| pending_messages <= uncongested_at);
+		if is_uncongested {
+			CongestedLanes::<T>::remove(chain, lane);
+			Self::deposit_event(Event::LaneUncongested(chain, *lane));
+			weight = weight.saturating_add(T::DbWeight::get().writes(1));
+		}
+
+		weight
+	}
+
+	/// Returns the identifiers of all registered lanes.
+	pub fn registered_lanes() -> Vec<LaneId> {
+		Lanes::<T>::iter_keys().collect()
+	}
+
+	/// Disable the registered lane, e.g. because a relayer has misbehaved at it. Governance may
+	/// enable it again with `set_lane_enabled`.
+	pub fn disable_lane(lane: LaneId) {
+		if Self::mutate_lane(lane, |config| {
+			config.enabled = false;
+			Ok(())
+		})
+		.is_ok()
+		{
+			Self::deposit_event(Event::LaneDisabled(lane));
+		}
+	}
+
+	fn mutate_lane(
+		lane: LaneId,
+		f: impl FnOnce(&mut LaneConfig<T>) -> Result<(), Error<T>>,
+	) -> Result<(), Error<T>> {
+		Lanes::<T>::try_mutate(lane, |config| {
+			f(config.as_mut().ok_or(Error::<T>::LaneNotRegistered)?)
+		})
+	}
+}
+
+/// Returns the total size of the nodes of the storage proof.
+fn storage_proof_size(storage_proof: &[Vec<u8>]) -> u32 {
+	storage_proof
+		.iter()
+		.fold(0usize, |size, node| size.saturating_add(node.len()))
+		.try_into()
+		.unwrap_or(u32::MAX)
+}
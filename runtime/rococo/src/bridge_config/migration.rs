@@ -0,0 +1,121 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A module that is responsible for migration of storage.
+
+use super::*;
+use frame_support::{storage::unhashed, traits::StorageVersion, weights::Weight};
+
+/// The current storage version.
+///
+/// v0-v1: conversion rate parameters moved from the runtime `parameter_types!` storage.
+pub const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+/// Migrates the pallet storage to the most recent version, checking and setting the
+/// `StorageVersion`.
+pub fn migrate_to_latest<T: Config>() -> Weight {
+	let mut weight = T::DbWeight::get().reads(1);
+	if StorageVersion::get::<Pallet<T>>() < 1 {
+		weight = weight.saturating_add(v1::migrate::<T>());
+		StorageVersion::new(1).put::<Pallet<T>>();
+		weight = weight.saturating_add(T::DbWeight::get().writes(1));
+	}
+	weight
+}
+
+/// Checks that the pallet storage has been migrated to the most recent version.
+#[cfg(feature = "try-runtime")]
+pub fn check_latest<T: Config>() -> Result<(), &'static str> {
+	ensure!(StorageVersion::get::<Pallet<T>>() == STORAGE_VERSION, "Storage version is outdated");
+	ensure!(
+		v1::PARAMETERS.iter().all(|name| !unhashed::exists(&v1::parameter_key(name))),
+		"Conversion rate parameters are left at the runtime storage keys",
+	);
+	Ok(())
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Names of the `parameter_types!` storage items, that are moved into the pallet storage.
+	pub const PARAMETERS: [&str; 6] = [
+		"BridgedToThisConversionRate",
+		"ConversionRateUpdatedAt",
+		"MaxConversionRateStaleness",
+		"ConversionRateChangeLimit",
+		"ConversionRateChangePeriodStart",
+		"FeeAssetConversionRate",
+	];
+
+	/// Storage key of the `parameter_types!` storage item with the given name.
+	pub fn parameter_key(name: &str) -> Vec<u8> {
+		bp_runtime::storage_parameter_key(name).0
+	}
+
+	/// Takes the value of the `parameter_types!` storage item with the given name.
+	///
+	/// Items that have never been set are left at the defaults of the pallet storage, which are
+	/// the same as the defaults of the parameters. Undecodable values are dropped.
+	fn take<V: Decode>(name: &str, writes: &mut u64) -> Option<V> {
+		let key = parameter_key(name);
+		let raw_value = unhashed::get_raw(&key)?;
+		unhashed::kill(&key);
+		*writes += 2;
+
+		let value = V::decode(&mut &raw_value[..]).ok();
+		if value.is_none() {
+			log::warn!(
+				target: "runtime::bridge",
+				"Dropping undecodable value of the {} parameter",
+				name,
+			);
+		}
+		value
+	}
+
+	/// Moves the conversion rate parameters from the runtime storage into the pallet storage.
+	pub fn migrate<T: Config>() -> Weight {
+		let mut writes = 0;
+		if let Some(rate) = take::<FixedU128>(PARAMETERS[0], &mut writes) {
+			BridgedToThisConversionRate::<T>::put(rate);
+		}
+		if let Some(updated_at) = take::<T::BlockNumber>(PARAMETERS[1], &mut writes) {
+			ConversionRateUpdatedAt::<T>::put(updated_at);
+		}
+		if let Some(max_staleness) = take::<Option<T::BlockNumber>>(PARAMETERS[2], &mut writes) {
+			MaxConversionRateStaleness::<T>::set(max_staleness);
+		}
+		if let Some(change_limit) =
+			take::<Option<(Perbill, T::BlockNumber)>>(PARAMETERS[3], &mut writes)
+		{
+			ConversionRateChangeLimit::<T>::set(change_limit);
+		}
+		if let Some(period_start) = take::<(T::BlockNumber, FixedU128)>(PARAMETERS[4], &mut writes)
+		{
+			ConversionRateChangePeriodStart::<T>::put(period_start);
+		}
+		if let Some(rate) = take::<FixedU128>(PARAMETERS[5], &mut writes) {
+			FeeAssetConversionRate::<T>::put(rate);
+		}
+
+		log::info!(
+			target: "runtime::bridge",
+			"Moved conversion rate parameters to the pallet storage",
+		);
+
+		T::DbWeight::get().reads_writes(PARAMETERS.len() as u64, writes)
+	}
+}
@@ -0,0 +1,212 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet that executes calls of the governance of this chain at the bridged chain.
+//!
+//! The `execute` call sends the message, that dispatches the call with the Root origin of this
+//! chain at the bridged chain, and remembers the nonce of the message by the id of the proposal
+//! that has executed the call. Once the delivery of the message is confirmed, the outcome of the
+//! call is recorded: it either has been dispatched at the bridged chain, or not. Every change of
+//! the outcome is announced with an event, so that referendum UIs may track remote calls without
+//! watching the bridged chain.
+
+use bp_messages::{source_chain::OnDeliveryConfirmed, DeliveredMessages, LaneId, MessageNonce};
+use frame_support::{pallet_prelude::*, traits::Get, weights::Weight};
+use scale_info::TypeInfo;
+use sp_runtime::{traits::AtLeast32BitUnsigned, DispatchError};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+/// Sends the messages that execute calls of the governance at the bridged chain.
+pub trait RemoteCallMessages<Balance> {
+	/// Weight of sending the message with the encoded call of given size, including the payment
+	/// of the message fee.
+	fn send_weight(call_size: u32) -> Weight;
+
+	/// Sends the message over the lane, that dispatches the encoded call with the Root origin of
+	/// this chain at the bridged chain. The call is dispatched with given weight, and the fee of
+	/// the message is paid by the account that is linked to the Root origin. Returns the nonce of
+	/// the message.
+	///
+	/// Calls that don't fit into a single message may be split into several messages, each paying
+	/// the fee. Then the nonce of the last message, that dispatches the call, is returned.
+	fn send(
+		lane: LaneId,
+		call: Vec<u8>,
+		call_weight: Weight,
+		fee: Balance,
+	) -> Result<MessageNonce, DispatchError>;
+}
+
+/// Outcome of the call, executed at the bridged chain.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum RemoteCallState {
+	/// The delivery of the message hasn't been confirmed yet.
+	Pending,
+	/// The call has been dispatched at the bridged chain.
+	Dispatched,
+	/// The call hasn't been dispatched at the bridged chain.
+	Failed,
+}
+
+/// Call of the governance, sent to the bridged chain.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct RemoteCall<ProposalId> {
+	/// Id of the proposal that has executed the call.
+	pub proposal: ProposalId,
+	/// Outcome of the call.
+	pub state: RemoteCallState,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	/// Configuration for the bridge governance pallet.
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Balance that message fees are paid in.
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Default + Copy + MaxEncodedLen;
+
+		/// Id of the governance proposal, e.g. the hash of the council motion.
+		type ProposalId: Parameter + Member + Copy + MaxEncodedLen;
+
+		/// Origin of the governance, that may execute calls at the bridged chain.
+		type ExecuteOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Lane that the messages with calls are sent over.
+		type Lane: Get<LaneId>;
+
+		/// Sends the messages with calls.
+		type RemoteCallMessages: RemoteCallMessages<Self::Balance>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// The call of the proposal has been sent to the bridged chain in the message with given
+		/// nonce.
+		RemoteCallSent(T::ProposalId, MessageNonce),
+		/// The delivery of the call of the proposal has been confirmed with given outcome.
+		RemoteCallConfirmed(T::ProposalId, RemoteCallState),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The call of the proposal has already been sent to the bridged chain.
+		DuplicateProposal,
+	}
+
+	/// Nonces of the messages with calls, by the id of the proposal that has executed the call.
+	#[pallet::storage]
+	#[pallet::getter(fn proposal_nonce)]
+	pub(crate) type ProposalNonces<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::ProposalId, MessageNonce>;
+
+	/// Calls, sent to the bridged chain, by the nonce of their message.
+	#[pallet::storage]
+	#[pallet::getter(fn remote_call)]
+	pub(crate) type RemoteCalls<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, MessageNonce, RemoteCall<T::ProposalId>>;
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Execute the encoded call of the bridged chain with the Root origin of this chain.
+		///
+		/// The call is dispatched with given `weight` at the bridged chain. The
+		/// `delivery_and_dispatch_fee` of every message, that the call is sent in, is paid by the
+		/// account that is linked to the Root origin.
+		#[pallet::weight(
+			T::RemoteCallMessages::send_weight(call.len() as u32)
+				.saturating_add(T::DbWeight::get().reads_writes(1, 2))
+		)]
+		pub fn execute(
+			origin: OriginFor<T>,
+			proposal: T::ProposalId,
+			call: Vec<u8>,
+			weight: Weight,
+			delivery_and_dispatch_fee: T::Balance,
+		) -> DispatchResult {
+			T::ExecuteOrigin::ensure_origin(origin)?;
+			ensure!(
+				!ProposalNonces::<T, I>::contains_key(proposal),
+				Error::<T, I>::DuplicateProposal,
+			);
+
+			let nonce = T::RemoteCallMessages::send(
+				T::Lane::get(),
+				call,
+				weight,
+				delivery_and_dispatch_fee,
+			)?;
+			ProposalNonces::<T, I>::insert(proposal, nonce);
+			RemoteCalls::<T, I>::insert(
+				nonce,
+				RemoteCall { proposal, state: RemoteCallState::Pending },
+			);
+
+			Self::deposit_event(Event::RemoteCallSent(proposal, nonce));
+			Ok(())
+		}
+	}
+
+	impl<T: Config<I>, I: 'static> OnDeliveryConfirmed for Pallet<T, I> {
+		fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+			if *lane != T::Lane::get() {
+				return 0
+			}
+
+			// the lane may be shared with other senders, so not every message is a remote call
+			let mut writes = 0;
+			for nonce in messages.begin..=messages.end {
+				let state = if messages.message_dispatch_result(nonce) {
+					RemoteCallState::Dispatched
+				} else {
+					RemoteCallState::Failed
+				};
+				RemoteCalls::<T, I>::mutate(nonce, |remote_call| {
+					if let Some(remote_call) = remote_call {
+						remote_call.state = state;
+						writes += 1;
+						Self::deposit_event(Event::RemoteCallConfirmed(
+							remote_call.proposal,
+							state,
+						));
+					}
+				});
+			}
+
+			T::DbWeight::get().reads_writes(messages.total_messages(), writes)
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns the outcome of the call of the proposal, if it has been sent to the bridged chain.
+	pub fn remote_call_state(proposal: T::ProposalId) -> Option<RemoteCallState> {
+		ProposalNonces::<T, I>::get(proposal)
+			.and_then(RemoteCalls::<T, I>::get)
+			.map(|remote_call| remote_call.state)
+	}
+}
@@ -16,30 +16,99 @@
 
 //! Over-bridge messaging support for Rococo <> Wococo bridge.
 
-pub use self::{at_rococo::*, at_wococo::*};
+pub use self::{
+	at_rococo::*, at_wococo::*, chunks::*, decode::*, fee::*, governance::*, scheduling::*,
+	xcm_export::*,
+};
+
+mod chunks;
+mod decode;
+mod fee;
+mod governance;
+mod scheduling;
+mod xcm_export;
 
-use crate::{Balances, Runtime};
+#[cfg(test)]
+mod tests;
+
+use crate::{
+	bridge_assets::UnlockMessages,
+	bridge_config::{
+		BridgedAuthoritySets, BridgedFeeMultiplierProof, CallFilterId, DispatchMode, InnerCalls,
+		LaneLimits, LargeMessageFee, LinkedOrigin,
+	},
+	bridge_governance::RemoteCallMessages,
+	bridge_relayers::{BridgedInboundLanes, HaltLane},
+	xcm_config::XcmConfig,
+	Balances, Runtime,
+};
 
+use bp_header_chain::FinalityBackend;
+use bp_message_dispatch::{CallOrigin, MessagePayload};
 use bp_messages::{
-	source_chain::{SenderOrigin, TargetHeaderChain},
-	target_chain::{ProvedMessages, SourceHeaderChain},
-	InboundLaneData, LaneId, Message, MessageNonce,
+	source_chain::{
+		ForwardDispatchResults, MessageExpiration, MessagesBridge, OnDeliveryConfirmed,
+		OnMessageAccepted, SenderOrigin, TargetHeaderChain,
+	},
+	storage_keys,
+	target_chain::{
+		DispatchMessage, DispatchMessageData, InboundLaneLimits, MessageDispatch, ProvedMessages,
+		SourceHeaderChain,
+	},
+	DeliveredMessages, InboundLaneData, LaneId, Message, MessageDispatchDryRun, MessageKey,
+	MessageNonce, MessagePayloadVerdict, Parameter as MessagesParameter, VerificationError,
 };
 use bp_rococo::{Balance, Rococo, EXTRA_STORAGE_PROOF_SIZE, MAXIMAL_ENCODED_ACCOUNT_ID_SIZE};
-use bp_runtime::{Chain, ChainId, ROCOCO_CHAIN_ID, WOCOCO_CHAIN_ID};
+use bp_runtime::{
+	derive_account_id,
+	messages::{DispatchFeePayment, MessageDispatchResult},
+	storage_value_key, Chain, ChainId, HashOf, SourceAccount, ROCOCO_CHAIN_ID, WESTEND_CHAIN_ID,
+	WOCOCO_CHAIN_ID,
+};
 use bridge_runtime_common::messages::{
 	source as messages_source, target as messages_target, transaction_payment,
 	BridgedChainWithMessages, ChainWithMessages, MessageBridge, MessageTransaction,
 	ThisChainWithMessages,
 };
 use frame_support::{
-	traits::Get,
-	weights::{Weight, WeightToFeePolynomial},
+	ensure,
+	traits::{
+		schedule::{self, v2::Anon as ScheduleAnon, DispatchTime, MaybeHashed},
+		Contains, Currency, ExistenceRequirement, Get, SortedMembers,
+	},
+	weights::{GetDispatchInfo, Weight, WeightToFeePolynomial},
 	RuntimeDebug,
 };
+use pallet_bridge_parachains::ParachainFinality;
+use parity_scale_codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
 use rococo_runtime_constants::fee::WeightToFee;
-use sp_runtime::FixedU128;
-use sp_std::{marker::PhantomData, ops::RangeInclusive};
+use runtime_common::{reserved_space::ClassifyCall, sanity::SanityCheck};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_io::hashing::blake2_256;
+use sp_runtime::{
+	traits::{DispatchInfoOf, Header as HeaderT, PostDispatchInfoOf, SignedExtension},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+		ValidTransaction,
+	},
+	DispatchError, DispatchResult, FixedPointNumber, FixedU128, Perbill, TransactionOutcome,
+};
+use sp_std::{borrow::Borrow, marker::PhantomData, ops::RangeInclusive, prelude::*};
+use sp_trie::StorageProof;
+use xcm::{
+	latest::{
+		ExecuteXcm, Instruction,
+		Junction::{AccountId32, GeneralKey},
+		Junctions::{X1, X2},
+		MultiLocation, NetworkId, Outcome, SendError, SendResult, SendXcm, Xcm,
+	},
+	VersionedXcm, MAX_XCM_DECODE_DEPTH,
+};
+use xcm_executor::{
+	traits::{Convert, WeightBounds},
+	XcmExecutor,
+};
 
 /// Maximal number of pending outbound messages.
 const MAXIMAL_PENDING_MESSAGES_AT_OUTBOUND_LANE: MessageNonce =
@@ -74,29 +143,53 @@ const PAY_INBOUND_DISPATCH_FEE_WEIGHT: Weight = 600_000_000;
 const TX_EXTRA_BYTES: u32 = 130;
 
 /// Rococo chain as it is seen at Rococo.
-pub type RococoAtRococo =
-	RococoLikeChain<AtRococoWithWococoMessageBridge, crate::RococoGrandpaInstance>;
+pub type RococoAtRococo = RococoLikeChain<AtRococoWithWococoMessageBridge, crate::RococoFinality>;
 
 /// Rococo chain as it is seen at Wococo.
-pub type RococoAtWococo =
-	RococoLikeChain<AtWococoWithRococoMessageBridge, crate::RococoGrandpaInstance>;
+pub type RococoAtWococo = RococoLikeChain<AtWococoWithRococoMessageBridge, crate::RococoFinality>;
 
 /// Wococo chain as it is seen at Wococo.
-pub type WococoAtWococo =
-	RococoLikeChain<AtWococoWithRococoMessageBridge, crate::WococoGrandpaInstance>;
+pub type WococoAtWococo = RococoLikeChain<AtWococoWithRococoMessageBridge, crate::WococoFinality>;
 
 /// Wococo chain as it is seen at Rococo.
-pub type WococoAtRococo =
-	RococoLikeChain<AtRococoWithWococoMessageBridge, crate::WococoGrandpaInstance>;
+pub type WococoAtRococo = RococoLikeChain<AtRococoWithWococoMessageBridge, crate::WococoFinality>;
+
+/// Finality of the headers of the Rococo parachain `P`, tracked by the `BridgeRococoParachains`
+/// pallet.
+///
+/// The parachain is expected to use the same header, hash and block number types as Rococo.
+pub type RococoParachainFinality<P> =
+	ParachainFinality<Runtime, crate::RococoParachainsInstance, P, Rococo>;
+
+/// Finality of the headers of the Wococo parachain `P`, tracked by the `BridgeWococoParachains`
+/// pallet.
+///
+/// The parachain is expected to use the same header, hash and block number types as Wococo.
+pub type WococoParachainFinality<P> =
+	ParachainFinality<Runtime, crate::WococoParachainsInstance, P, Rococo>;
+
+/// Rococo parachain `P` as it is seen at Wococo.
+///
+/// Proofs of messages, sent by the parachain, and proofs of messages delivery to the parachain
+/// are verified against the heads of the parachain at finalized Rococo headers.
+pub type RococoParachainAtWococo<P> =
+	RococoLikeChain<AtWococoWithRococoMessageBridge, RococoParachainFinality<P>>;
+
+/// Wococo parachain `P` as it is seen at Rococo.
+///
+/// Proofs of messages, sent by the parachain, and proofs of messages delivery to the parachain
+/// are verified against the heads of the parachain at finalized Wococo headers.
+pub type WococoParachainAtRococo<P> =
+	RococoLikeChain<AtRococoWithWococoMessageBridge, WococoParachainFinality<P>>;
 
 /// Rococo/Wococo chain from message lane point of view.
 #[derive(RuntimeDebug, Clone, Copy)]
-pub struct RococoLikeChain<B, GI> {
+pub struct RococoLikeChain<B, F> {
 	_bridge_definition: PhantomData<B>,
-	_at_this_chain_grandpa_pallet_instance: PhantomData<GI>,
+	_at_this_chain_finality_backend: PhantomData<F>,
 }
 
-impl<B, GI> ChainWithMessages for RococoLikeChain<B, GI> {
+impl<B, F> ChainWithMessages for RococoLikeChain<B, F> {
 	type Hash = crate::Hash;
 	type AccountId = crate::AccountId;
 	type Signer = primitives::v2::AccountPublic;
@@ -105,16 +198,18 @@ impl<B, GI> ChainWithMessages for RococoLikeChain<B, GI> {
 	type Balance = Balance;
 }
 
-impl<B, GI> ThisChainWithMessages for RococoLikeChain<B, GI> {
+impl<B, F> ThisChainWithMessages for RococoLikeChain<B, F> {
 	type Origin = crate::Origin;
 	type Call = crate::Call;
 
-	fn is_message_accepted(_submitter: &crate::Origin, lane: &LaneId) -> bool {
-		*lane == [0, 0, 0, 0]
+	fn is_message_accepted(submitter: &crate::Origin, lane: &LaneId) -> bool {
+		submitter
+			.linked_account()
+			.map_or(false, |account| crate::BridgeConfig::is_allowed_message_sender(lane, &account))
 	}
 
-	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
-		MAXIMAL_PENDING_MESSAGES_AT_OUTBOUND_LANE
+	fn maximal_pending_messages_at_outbound_lane(lane: &LaneId) -> MessageNonce {
+		lane_limits(lane).max_pending_messages
 	}
 
 	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
@@ -130,6 +225,7 @@ impl<B, GI> ThisChainWithMessages for RococoLikeChain<B, GI> {
 			size: inbound_data_size
 				.saturating_add(EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(TX_EXTRA_BYTES),
+			proof_size: inbound_data_size.saturating_add(EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
@@ -142,21 +238,26 @@ impl<B, GI> ThisChainWithMessages for RococoLikeChain<B, GI> {
 			crate::TransactionByteFee::get(),
 			pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier(),
 			|weight| WeightToFee::calc(&weight),
+			proof_size_to_fee,
 			transaction,
 		)
 	}
 }
 
-impl<B, GI> BridgedChainWithMessages for RococoLikeChain<B, GI> {
+impl<B: MessageBridge, F> BridgedChainWithMessages for RococoLikeChain<B, F> {
 	fn maximal_extrinsic_size() -> u32 {
 		Rococo::max_extrinsic_size()
 	}
 
-	fn message_weight_limits(_message_payload: &[u8]) -> RangeInclusive<Weight> {
+	fn message_weight_limits(message_payload: &[u8]) -> RangeInclusive<Weight> {
 		// we don't want to relay too large messages + keep reserve for future upgrades
 		let upper_limit = messages_target::maximal_incoming_message_dispatch_weight(
 			Rococo::max_extrinsic_weight(),
 		);
+		// both chains are running the same runtime, so the bridged chain limits the weight of the
+		// call the same way
+		let upper_limit = crate::BridgeConfig::dispatch_weight_limit(message_payload)
+			.map_or(upper_limit, |weight_limit| weight_limit.min(upper_limit));
 
 		// we're charging for payload bytes in `With(Wococo | Rococo)MessageBridge::transaction_payment` function
 		//
@@ -172,12 +273,12 @@ impl<B, GI> BridgedChainWithMessages for RococoLikeChain<B, GI> {
 		message_dispatch_weight: Weight,
 	) -> MessageTransaction<Weight> {
 		let message_payload_len = u32::try_from(message_payload.len()).unwrap_or(u32::MAX);
-		let extra_bytes_in_payload = Weight::from(message_payload_len)
-			.saturating_sub(pallet_bridge_messages::EXPECTED_DEFAULT_MESSAGE_LENGTH.into());
+		let large_message_weight = crate::BridgeConfig::large_message_fee()
+			.map_or(0, |large_message_fee| large_message_fee.delivery_weight(message_payload_len));
 
+		// payload bytes are charged for by `proof_size_to_fee`, because they are part of the proof
 		MessageTransaction {
-			dispatch_weight: extra_bytes_in_payload
-				.saturating_mul(ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT)
+			dispatch_weight: large_message_weight
 				.saturating_add(DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT)
 				.saturating_sub(if include_pay_dispatch_fee_cost {
 					0
@@ -188,95 +289,569 @@ impl<B, GI> BridgedChainWithMessages for RococoLikeChain<B, GI> {
 			size: message_payload_len
 				.saturating_add(EXTRA_STORAGE_PROOF_SIZE)
 				.saturating_add(TX_EXTRA_BYTES),
+			proof_size: message_payload_len.saturating_add(EXTRA_STORAGE_PROOF_SIZE),
 		}
 	}
 
 	fn transaction_payment(transaction: MessageTransaction<Weight>) -> Balance {
-		// current fee multiplier is used here
+		// fee multiplier of the bridged chain, synced by relayers, is used here. Until it is
+		// synced, the current fee multiplier of this chain is used
+		let fee_multiplier = crate::BridgeConfig::bridged_fee_multiplier(B::BRIDGED_CHAIN_ID)
+			.map(|(_, fee_multiplier)| fee_multiplier)
+			.unwrap_or_else(pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier);
 		bridge_runtime_common::messages::transaction_payment(
 			crate::BlockWeights::get()
 				.get(frame_support::weights::DispatchClass::Normal)
 				.base_extrinsic,
 			crate::TransactionByteFee::get(),
-			pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier(),
+			fee_multiplier,
 			|weight| WeightToFee::calc(&weight),
+			proof_size_to_fee,
 			transaction,
 		)
 	}
 }
 
-impl<B, GI> TargetHeaderChain<messages_source::FromThisChainMessagePayload<B>, crate::AccountId>
-	for RococoLikeChain<B, GI>
+impl<B, F> TargetHeaderChain<messages_source::FromThisChainMessagePayload<B>, crate::AccountId>
+	for RococoLikeChain<B, F>
 where
 	B: MessageBridge,
 	B::ThisChain: ChainWithMessages<AccountId = crate::AccountId>,
 	B::BridgedChain: ChainWithMessages<Hash = crate::Hash>,
-	GI: 'static,
-	Runtime: pallet_bridge_grandpa::Config<GI>,
-	<<Runtime as pallet_bridge_grandpa::Config<GI>>::BridgedChain as bp_runtime::Chain>::Hash:
-		From<crate::Hash>,
+	F: FinalityBackend,
+	HashOf<F::Chain>: From<crate::Hash>,
 {
-	type Error = &'static str;
+	type Error = BridgeError;
 	type MessagesDeliveryProof =
 		messages_source::FromBridgedChainMessagesDeliveryProof<crate::Hash>;
 
 	fn verify_message(
 		payload: &messages_source::FromThisChainMessagePayload<B>,
 	) -> Result<(), Self::Error> {
+		if !crate::BridgeConfig::is_sending_messages() {
+			return Err(BridgeError::OutboundMessagesRejected)
+		}
+		if is_conversion_rate_stale() {
+			return Err(BridgeError::StaleConversionRate)
+		}
 		messages_source::verify_chain_message::<B>(payload)
+			.map_err(|err| BridgeError::InvalidMessage.log(err))?;
+		if payload.call.len() > crate::BridgeConfig::maximal_outbound_payload_size() as usize {
+			return Err(BridgeError::InvalidMessage
+				.log("The message is larger than the maximal outbound payload size"))
+		}
+
+		Ok(())
 	}
 
 	fn verify_messages_delivery_proof(
 		proof: Self::MessagesDeliveryProof,
 	) -> Result<(LaneId, InboundLaneData<crate::AccountId>), Self::Error> {
-		messages_source::verify_messages_delivery_proof::<B, Runtime, GI>(proof)
+		messages_source::verify_messages_delivery_proof::<B, F>(proof)
+			.map_err(|err| BridgeError::InvalidMessagesDeliveryProof.log(err))
 	}
 }
 
-impl<B, GI> SourceHeaderChain<Balance> for RococoLikeChain<B, GI>
+impl<B, F> SourceHeaderChain<Balance> for RococoLikeChain<B, F>
 where
 	B: MessageBridge,
 	B::BridgedChain: ChainWithMessages<Balance = Balance, Hash = crate::Hash>,
-	GI: 'static,
-	Runtime: pallet_bridge_grandpa::Config<GI>,
-	<<Runtime as pallet_bridge_grandpa::Config<GI>>::BridgedChain as bp_runtime::Chain>::Hash:
-		From<crate::Hash>,
+	F: FinalityBackend,
+	HashOf<F::Chain>: From<crate::Hash>,
 {
-	type Error = &'static str;
+	type Error = BridgeError;
 	type MessagesProof = messages_target::FromBridgedChainMessagesProof<crate::Hash>;
 
 	fn verify_messages_proof(
 		proof: Self::MessagesProof,
 		messages_count: u32,
 	) -> Result<ProvedMessages<Message<Balance>>, Self::Error> {
-		messages_target::verify_messages_proof::<B, Runtime, GI>(proof, messages_count)
+		if !crate::BridgeConfig::is_receiving_messages() {
+			return Err(BridgeError::BridgeHalted)
+		}
+		messages_target::verify_messages_proof::<B, F>(proof, messages_count)
+			.map_err(|err| BridgeError::InvalidMessagesProof.log(err))
 			.and_then(verify_inbound_messages_lane)
 	}
 }
 
-/// Error that happens when we are receiving incoming message via unexpected lane.
-const INBOUND_LANE_DISABLED: &str = "The inbound message lane is disabled.";
+/// Reasons of the Rococo <> Wococo bridge to reject messages and proofs.
+///
+/// Rejections by the verification that is common to all bridges are reported by the coarse
+/// `Invalid*` variants. The detailed reason is logged. The messages pallet reports other reasons
+/// to the transaction submitter as its specific errors.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum BridgeError {
+	/// We are sending a message while the bridge rejects outbound messages.
+	OutboundMessagesRejected,
+	/// We are sending a message while the conversion rate is stale.
+	StaleConversionRate,
+	/// We are receiving incoming messages while the bridge is halted.
+	BridgeHalted,
+	/// We are receiving incoming message via unexpected lane.
+	InboundLaneDisabled,
+	/// The outbound message can't be delivered to the bridged chain.
+	InvalidMessage,
+	/// The proof of inbound messages is invalid.
+	InvalidMessagesProof,
+	/// The proof of delivered outbound messages is invalid.
+	InvalidMessagesDeliveryProof,
+}
+
+impl BridgeError {
+	/// Logs the detailed reason of the error and returns the error.
+	fn log(self, reason: &'static str) -> Self {
+		log::trace!(target: "runtime::bridge", "{:?}: {}", self, reason);
+		self
+	}
+}
+
+impl From<BridgeError> for &'static str {
+	fn from(err: BridgeError) -> &'static str {
+		match err {
+			BridgeError::OutboundMessagesRejected => "The bridge rejects outbound messages.",
+			BridgeError::StaleConversionRate =>
+				"The conversion rate of the bridged tokens is stale.",
+			BridgeError::BridgeHalted => "The bridge is halted.",
+			BridgeError::InboundLaneDisabled => "The inbound message lane is disabled.",
+			BridgeError::InvalidMessage => "The message can't be delivered to the bridged chain.",
+			BridgeError::InvalidMessagesProof => "The messages proof is invalid.",
+			BridgeError::InvalidMessagesDeliveryProof => "The messages delivery proof is invalid.",
+		}
+	}
+}
+
+impl From<BridgeError> for VerificationError {
+	fn from(err: BridgeError) -> VerificationError {
+		match err {
+			BridgeError::OutboundMessagesRejected => VerificationError::OutboundMessagesRejected,
+			BridgeError::StaleConversionRate => VerificationError::StaleConversionRate,
+			BridgeError::BridgeHalted => VerificationError::InboundMessagesRejected,
+			BridgeError::InboundLaneDisabled => VerificationError::InboundLaneDisabled,
+			BridgeError::InvalidMessage |
+			BridgeError::InvalidMessagesProof |
+			BridgeError::InvalidMessagesDeliveryProof => VerificationError::Unspecified,
+		}
+	}
+}
 
-/// Verify that lanes of inbound messages are enabled.
+/// Verify that lanes of inbound messages are registered and enabled.
 fn verify_inbound_messages_lane(
 	messages: ProvedMessages<Message<Balance>>,
-) -> Result<ProvedMessages<Message<Balance>>, &'static str> {
-	let allowed_incoming_lanes = [[0, 0, 0, 0]];
-	if messages.keys().any(|lane_id| !allowed_incoming_lanes.contains(lane_id)) {
-		return Err(INBOUND_LANE_DISABLED)
+) -> Result<ProvedMessages<Message<Balance>>, BridgeError> {
+	if messages.keys().any(|lane_id| !crate::BridgeConfig::is_lane_enabled(lane_id)) {
+		return Err(BridgeError::InboundLaneDisabled)
 	}
 	Ok(messages)
 }
 
-/// The cost of delivery confirmation transaction.
-pub struct GetDeliveryConfirmationTransactionFee;
+/// Tracks the GRANDPA authority sets of the bridged chains, imported by the GRANDPA pallets.
+pub struct BridgedGrandpaAuthoritySets;
 
-impl Get<Balance> for GetDeliveryConfirmationTransactionFee {
-	fn get() -> Balance {
-		<RococoAtRococo as ThisChainWithMessages>::transaction_payment(
-			RococoAtRococo::estimate_delivery_confirmation_transaction(),
-		)
+impl BridgedAuthoritySets<crate::BlockNumber> for BridgedGrandpaAuthoritySets {
+	fn imported_at() -> Vec<(ChainId, crate::BlockNumber)> {
+		[
+			(ROCOCO_CHAIN_ID, crate::BridgeRococoGrandpa::authority_set_imported_at()),
+			(WOCOCO_CHAIN_ID, crate::BridgeWococoGrandpa::authority_set_imported_at()),
+			(WESTEND_CHAIN_ID, crate::BridgeWestendGrandpa::authority_set_imported_at()),
+		]
+		.into_iter()
+		.filter_map(|(chain, imported_at)| Some((chain, imported_at?)))
+		.collect()
+	}
+}
+
+/// Reads the inbound lanes state of Rococo, Wococo or Westend from storage proofs, verified against
+/// finalized headers imported by the finality pallets of the chains.
+pub struct BridgedInboundLanesFromProof;
+
+impl BridgedInboundLanes<crate::BlockNumber> for BridgedInboundLanesFromProof {
+	fn best_finalized_number(chain: ChainId) -> Option<crate::BlockNumber> {
+		match chain {
+			ROCOCO_CHAIN_ID => Some(crate::RococoFinality::best_finalized_number()),
+			WOCOCO_CHAIN_ID => Some(crate::WococoFinality::best_finalized_number()),
+			WESTEND_CHAIN_ID => Some(crate::WestendFinality::best_finalized_number()),
+			_ => None,
+		}
+	}
+
+	fn latest_received_nonce(
+		chain: ChainId,
+		at: crate::Hash,
+		storage_proof: Vec<Vec<u8>>,
+		lane: LaneId,
+	) -> Result<(crate::BlockNumber, MessageNonce), DispatchError> {
+		match chain {
+			ROCOCO_CHAIN_ID => read_bridged_inbound_lane::<
+				AtWococoWithRococoMessageBridge,
+				crate::RococoFinality,
+			>(at, storage_proof, lane),
+			WOCOCO_CHAIN_ID => read_bridged_inbound_lane::<
+				AtRococoWithWococoMessageBridge,
+				crate::WococoFinality,
+			>(at, storage_proof, lane),
+			WESTEND_CHAIN_ID => read_bridged_inbound_lane::<
+				crate::westend_messages::AtRococoWithWestendMessageBridge,
+				crate::WestendFinality,
+			>(at, storage_proof, lane),
+			_ => Err(crate::bridge_relayers::Error::<Runtime>::UnknownBridgedChain.into()),
+		}
+	}
+}
+
+/// Halts lanes with invalid delivery confirmations by disabling them. Governance may enable them
+/// again after reviewing the misbehavior.
+pub struct DisableMisbehavingLanes;
+
+impl HaltLane for DisableMisbehavingLanes {
+	fn halt_lane(lane: LaneId) {
+		crate::BridgeConfig::disable_lane(lane);
+	}
+}
+
+/// Returns number of the best finalized header of the bridged chain, imported by the finality
+/// pallet of the chain.
+fn best_finalized_block_number(chain: ChainId) -> Option<u64> {
+	match chain {
+		ROCOCO_CHAIN_ID => Some(crate::RococoFinality::best_finalized_number().into()),
+		WOCOCO_CHAIN_ID => Some(crate::WococoFinality::best_finalized_number().into()),
+		WESTEND_CHAIN_ID => Some(crate::WestendFinality::best_finalized_number().into()),
+		_ => None,
+	}
+}
+
+impl<T: frame_system::Config> pallet_bridge_messages::WeightInfoExt
+	for crate::weights::pallet_bridge_messages::WeightInfo<T>
+{
+	fn expected_extra_storage_proof_size() -> u32 {
+		EXTRA_STORAGE_PROOF_SIZE
+	}
+}
+
+/// Reads the block number and the nonce of the latest message, received by the inbound lane of
+/// the bridge `B`, from the storage proof of the bridged chain state at the finalized header.
+///
+/// The lane, that is missing from the proved storage, hasn't received any messages.
+fn read_bridged_inbound_lane<B: MessageBridge, F: FinalityBackend<Chain = Rococo>>(
+	at: crate::Hash,
+	storage_proof: Vec<Vec<u8>>,
+	lane: LaneId,
+) -> Result<(crate::BlockNumber, MessageNonce), DispatchError> {
+	F::parse_finalized_storage_proof(at, StorageProof::new(storage_proof), |checker| {
+		let block_number =
+			checker.read_value(&storage_value_key("System", "Number").0).ok().flatten()?;
+		let inbound_lane_key =
+			storage_keys::inbound_lane_data_key(B::BRIDGED_MESSAGES_PALLET_NAME, &lane);
+		let inbound_lane_data: InboundLaneData<crate::AccountId> =
+			match checker.read_value(&inbound_lane_key.0).ok()? {
+				Some(inbound_lane_data) => Decode::decode(&mut &inbound_lane_data[..]).ok()?,
+				None => Default::default(),
+			};
+		Some((
+			Decode::decode(&mut &block_number[..]).ok()?,
+			inbound_lane_data.last_delivered_nonce(),
+		))
+	})?
+	.ok_or_else(|| crate::bridge_relayers::Error::<Runtime>::InvalidInboundLaneProof.into())
+}
+
+/// Message dispatch that dispatches inbound messages according to the dispatch mode of their
+/// lane.
+///
+/// Calls which are not allowed by the call filter of the lane are refused. Calls of messages, sent
+/// by the bridged chain Root, must also be allowed by the source root call filter. XCM messages are
+/// executed by the XCM executor with the bridged chain as origin (see [`bridged_origin`]).
+/// Messages that have expired at the bridged chain, as far as its finalized headers tell, are
+/// refused as well. Refused and failed messages are still delivered, but their dispatch fails.
+///
+/// Allowed calls of lanes with a dispatch delay aren't dispatched in the delivery transaction.
+/// They are scheduled for dispatch at a future block with the origin that the inner dispatch
+/// (backed by the given dispatch pallet instance) would use.
+pub struct LaneFilteredMessageDispatch<B, Dispatch, DispatchInstance>(
+	PhantomData<(B, Dispatch, DispatchInstance)>,
+);
+
+impl<B, Dispatch, DispatchInstance> MessageDispatch<crate::AccountId, Balance>
+	for LaneFilteredMessageDispatch<B, Dispatch, DispatchInstance>
+where
+	B: MessageBridge,
+	Dispatch: MessageDispatch<
+		crate::AccountId,
+		Balance,
+		DispatchPayload = messages_target::FromBridgedChainMessagePayload<B>,
+	>,
+	B::BridgedChain: ChainWithMessages<AccountId = crate::AccountId>,
+	Runtime:
+		pallet_bridge_dispatch::Config<DispatchInstance, SourceChainAccountId = crate::AccountId>,
+	DispatchInstance: 'static,
+{
+	type DispatchPayload = Dispatch::DispatchPayload;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Balance>) -> Weight {
+		Dispatch::dispatch_weight(message)
+	}
+
+	fn dispatch(
+		relayer_account: &crate::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Balance>,
+	) -> MessageDispatchResult {
+		let payload = match message.data.payload {
+			Ok(ref payload) => payload,
+			// the inner dispatch rejects undecodable payloads on its own
+			Err(_) => return Dispatch::dispatch(relayer_account, message),
+		};
+		let best_bridged_block = best_finalized_block_number(B::BRIDGED_CHAIN_ID);
+		let is_expired = matches!(
+			(payload.expires_at, best_bridged_block),
+			(Some(expires_at), Some(best_bridged_block)) if best_bridged_block >= expires_at
+		);
+		if is_expired {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Message {:?}/{} has expired at the bridged block {:?}",
+				message.key.lane_id,
+				message.key.nonce,
+				payload.expires_at,
+			);
+			return refused_dispatch(payload.weight)
+		}
+
+		// the encoded call is private to the payload, so we decode a copy of it
+		let encoded_call = Vec::<u8>::decode(&mut &payload.call.encode()[..]);
+
+		let lane_id = &message.key.lane_id;
+		match crate::BridgeConfig::lane_dispatch_mode(lane_id) {
+			Some(DispatchMode::Call) => {
+				let call = match encoded_call
+					.map_err(|_| InboundCallError::Undecodable)
+					.and_then(|call| decode_inbound_call(&call))
+				{
+					Err(error @ (InboundCallError::TooLarge | InboundCallError::TooDeep)) => {
+						log::trace!(
+							target: "runtime::bridge-messages",
+							"Call of message {:?}/{} is refused: {:?}",
+							message.key.lane_id,
+							message.key.nonce,
+							error,
+						);
+						return refused_dispatch(payload.weight)
+					},
+					call => call,
+				};
+
+				let call_filter = crate::BridgeConfig::lane_call_filter(lane_id);
+				let is_source_root = matches!(payload.origin, CallOrigin::SourceRoot);
+				let is_allowed = match (&call, call_filter) {
+					(Ok(call), Some(call_filter)) =>
+						check_inbound_call(call_filter, is_source_root, payload.weight, call) ==
+							MessagePayloadVerdict::Accepted,
+					// the inner dispatch rejects undecodable calls on its own
+					(Err(_), Some(_)) => true,
+					(_, None) => false,
+				};
+				if is_allowed {
+					return match (call, crate::BridgeConfig::lane_dispatch_delay(lane_id)) {
+						(Ok(call), Some(dispatch_delay)) =>
+							Self::schedule_dispatch(&message.key, payload, call, dispatch_delay),
+						_ => Dispatch::dispatch(relayer_account, message),
+					}
+				}
+
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Call of message {:?}/{} is not allowed at the lane",
+					message.key.lane_id,
+					message.key.nonce,
+				);
+				refused_dispatch(payload.weight)
+			},
+			Some(DispatchMode::Xcm) => {
+				let origin = bridged_origin(B::BRIDGED_CHAIN_ID, &payload.origin);
+				let xcm = encoded_call
+					.ok()
+					.and_then(|xcm| {
+						VersionedXcm::<crate::Call>::decode_with_depth_limit(
+							MAX_XCM_DECODE_DEPTH,
+							&mut &xcm[..],
+						)
+						.ok()
+					})
+					.and_then(|xcm| Xcm::try_from(xcm).ok());
+				let (origin, xcm) = match (origin, xcm) {
+					(Some(origin), Some(xcm)) => (origin, xcm),
+					_ => {
+						log::trace!(
+							target: "runtime::bridge-messages",
+							"Message {:?}/{} can't be executed as XCM message",
+							message.key.lane_id,
+							message.key.nonce,
+						);
+						return refused_dispatch(payload.weight)
+					},
+				};
+
+				let outcome = XcmExecutor::<XcmConfig>::execute_xcm(origin, xcm, payload.weight);
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"XCM message {:?}/{} has been executed: {:?}",
+					message.key.lane_id,
+					message.key.nonce,
+					outcome,
+				);
+				MessageDispatchResult {
+					dispatch_result: matches!(outcome, Outcome::Complete(_)),
+					unspent_weight: payload.weight.saturating_sub(outcome.weight_used()),
+					dispatch_fee_paid_during_dispatch: false,
+				}
+			},
+			None => refused_dispatch(payload.weight),
+		}
+	}
+}
+
+/// Result of a message, which has been refused without being dispatched.
+fn refused_dispatch(weight: Weight) -> MessageDispatchResult {
+	MessageDispatchResult {
+		dispatch_result: false,
+		unspent_weight: weight,
+		dispatch_fee_paid_during_dispatch: false,
+	}
+}
+
+/// Storage keys of the bridge items audited by third parties.
+///
+/// Covers the operating mode of all messages pallet instances, and the configuration and the
+/// states of all registered lanes.
+pub fn audit_keys() -> Vec<Vec<u8>> {
+	use crate::{
+		AtRococoWithWestendMessagesInstance, AtRococoWithWococoMessagesInstance,
+		AtWococoWithRococoMessagesInstance,
+	};
+	use pallet_bridge_messages::{InboundLanes, OutboundLanes, PalletOperatingMode};
+
+	let mut keys = vec![
+		PalletOperatingMode::<Runtime, AtRococoWithWococoMessagesInstance>::hashed_key().to_vec(),
+		PalletOperatingMode::<Runtime, AtWococoWithRococoMessagesInstance>::hashed_key().to_vec(),
+		PalletOperatingMode::<Runtime, AtRococoWithWestendMessagesInstance>::hashed_key().to_vec(),
+	];
+	for lane in crate::BridgeConfig::registered_lanes() {
+		keys.extend([
+			crate::bridge_config::Lanes::<Runtime>::hashed_key_for(lane),
+			OutboundLanes::<Runtime, AtRococoWithWococoMessagesInstance>::hashed_key_for(lane),
+			InboundLanes::<Runtime, AtRococoWithWococoMessagesInstance>::hashed_key_for(lane),
+			OutboundLanes::<Runtime, AtWococoWithRococoMessagesInstance>::hashed_key_for(lane),
+			InboundLanes::<Runtime, AtWococoWithRococoMessagesInstance>::hashed_key_for(lane),
+			OutboundLanes::<Runtime, AtRococoWithWestendMessagesInstance>::hashed_key_for(lane),
+			InboundLanes::<Runtime, AtRococoWithWestendMessagesInstance>::hashed_key_for(lane),
+		]);
 	}
+	keys
+}
+
+/// Sanity check that the relayer fund account holds the fees of all unconfirmed outbound messages
+/// and the unclaimed rewards of relayers.
+///
+/// All messages pallet instances share the relayer fund account. It receives the fee of every
+/// sent message, which becomes the reward of relayers once the delivery of the message is
+/// confirmed. Relayers claim their rewards from the account.
+pub struct RelayerFundCoversUnpaidFees;
+
+impl SanityCheck<crate::BlockNumber> for RelayerFundCoversUnpaidFees {
+	fn check(_now: crate::BlockNumber) -> Result<(), &'static str> {
+		let unpaid = unpaid_fees::<crate::AtRococoWithWococoMessagesInstance>()
+			.saturating_add(unpaid_fees::<crate::AtWococoWithRococoMessagesInstance>())
+			.saturating_add(unpaid_fees::<crate::AtRococoWithWestendMessagesInstance>())
+			.saturating_add(crate::BridgeRelayers::unclaimed_rewards());
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		ensure!(
+			Balances::free_balance(&relayer_fund) >= unpaid,
+			"Relayer fund balance is lower than the fees of unconfirmed messages and unclaimed rewards",
+		);
+		Ok(())
+	}
+}
+
+/// Checks that the wrapped tokens of the bridged chains match the burns confirmed by them.
+pub struct WrappedTokensReconcile;
+
+impl SanityCheck<crate::BlockNumber> for WrappedTokensReconcile {
+	fn check(_now: crate::BlockNumber) -> Result<(), &'static str> {
+		crate::BridgeRococoAssets::reconcile()?;
+		crate::BridgeWococoAssets::reconcile()
+	}
+}
+
+/// Sanity check that the states of all message lanes of all messages pallet instances are
+/// consistent.
+///
+/// The bridged chain can't have received more messages than have been sent over a lane, and only
+/// received messages may be pruned. Unrewarded relayer entries must cover contiguous and
+/// non-overlapping ranges of messages, which haven't been confirmed yet.
+///
+/// The relayer fund balance is checked separately, by [`RelayerFundCoversUnpaidFees`].
+pub struct MessageLanesAreConsistent;
+
+impl SanityCheck<crate::BlockNumber> for MessageLanesAreConsistent {
+	fn check(_now: crate::BlockNumber) -> Result<(), &'static str> {
+		check_message_lanes::<crate::AtRococoWithWococoMessagesInstance>()?;
+		check_message_lanes::<crate::AtWococoWithRococoMessagesInstance>()?;
+		check_message_lanes::<crate::AtRococoWithWestendMessagesInstance>()
+	}
+}
+
+/// Check the states of all lanes of the given messages pallet instance.
+fn check_message_lanes<I: 'static>() -> Result<(), &'static str>
+where
+	Runtime: pallet_bridge_messages::Config<I>,
+{
+	use pallet_bridge_messages::{InboundLanes, OutboundLanes};
+
+	for (_, lane) in OutboundLanes::<Runtime, I>::iter() {
+		ensure!(
+			lane.latest_received_nonce <= lane.latest_generated_nonce,
+			"Bridged chain has received more messages than have been sent over the lane",
+		);
+		ensure!(
+			lane.oldest_unpruned_nonce <= lane.latest_received_nonce.saturating_add(1),
+			"Messages that haven't been received by the bridged chain have been pruned",
+		);
+	}
+
+	for (_, lane) in InboundLanes::<Runtime, I>::iter() {
+		let mut next_begin = None;
+		for entry in &lane.relayers {
+			let messages = &entry.messages;
+			ensure!(messages.begin <= messages.end, "Unrewarded relayer entry covers no messages");
+			ensure!(
+				messages.end > lane.last_confirmed_nonce,
+				"Unrewarded relayer entry covers only confirmed messages",
+			);
+			ensure!(
+				next_begin.map_or(true, |next_begin| messages.begin == next_begin),
+				"Unrewarded relayer entries are not contiguous",
+			);
+			next_begin = Some(messages.end.saturating_add(1));
+		}
+	}
+
+	Ok(())
+}
+
+/// Sum of the fees of outbound messages whose delivery hasn't been confirmed yet.
+fn unpaid_fees<I: 'static>() -> Balance
+where
+	Runtime: pallet_bridge_messages::Config<I, OutboundMessageFee = Balance>,
+{
+	use pallet_bridge_messages::{OutboundLanes, OutboundMessages};
+
+	OutboundMessages::<Runtime, I>::iter()
+		.filter(|(key, _)| {
+			key.nonce > OutboundLanes::<Runtime, I>::get(&key.lane_id).latest_received_nonce
+		})
+		.fold(0, |unpaid, (_, message)| unpaid.saturating_add(message.fee))
 }
 
 impl SenderOrigin<crate::AccountId> for crate::Origin {
@@ -286,242 +861,822 @@ impl SenderOrigin<crate::AccountId> for crate::Origin {
 				Some(submitter.clone()),
 			crate::OriginCaller::system(frame_system::RawOrigin::Root) |
 			crate::OriginCaller::system(frame_system::RawOrigin::None) =>
-				crate::RootAccountForPayments::get(),
+				crate::BridgeConfig::linked_origin_account(LinkedOrigin::Root)
+					.or_else(crate::RootAccountForPayments::get),
+			crate::OriginCaller::Collective(pallet_collective::RawOrigin::Members(yes, total))
+				if yes * 2 > total =>
+				crate::BridgeConfig::linked_origin_account(LinkedOrigin::Collective),
 			_ => None,
 		}
 	}
 }
 
-/// This module contains definitions that are used by the messages pallet instance, "deployed" at Rococo.
-mod at_rococo {
-	use super::*;
+/// Parameters of the Rococo <> Wococo messages pallets, updated by the bridge admin.
+///
+/// Every update deposits an event of the bridge config pallet, with the old and the new value.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum BridgeMessagesParameter {
+	/// Relayer interest (in percents) for messages sent over the lane, replacing the
+	/// `RELAYER_FEE_PERCENT` of the bridge for that lane.
+	RelayerFeePercent(LaneId, u32),
+	/// Limits of the lane, replacing the maximal limits of the bridge for that lane. Limits above
+	/// the maximal limits are lowered to them.
+	LaneLimits(LaneId, LaneLimits),
+	/// The conversion formula we use is: `ThisTokens = BridgedTokens * conversion_rate`.
+	BridgedToThisConversionRate(FixedU128),
+	/// Number of blocks the conversion rate stays valid after an update, or `None` if it never
+	/// goes stale.
+	MaxConversionRateStaleness(Option<crate::BlockNumber>),
+	/// Maximal change of the conversion rate within a period of the given number of blocks, or
+	/// `None` if changes are not limited.
+	ConversionRateChangeLimit(Option<(Perbill, crate::BlockNumber)>),
+	/// The conversion formula we use is: `AssetTokens = NativeTokens * conversion_rate`.
+	FeeAssetConversionRate(FixedU128),
+	/// Super-linear component of the delivery fee of large outbound messages, or `None` if the
+	/// fee grows linearly.
+	LargeMessageFee(Option<LargeMessageFee>),
+}
 
-	/// Message bridge that is "deployed" at Rococo chain and connecting it to Wococo chain.
-	#[derive(RuntimeDebug, Clone, Copy)]
-	pub struct AtRococoWithWococoMessageBridge;
-
-	impl MessageBridge for AtRococoWithWococoMessageBridge {
-		const THIS_CHAIN_ID: ChainId = ROCOCO_CHAIN_ID;
-		const BRIDGED_CHAIN_ID: ChainId = WOCOCO_CHAIN_ID;
-		const RELAYER_FEE_PERCENT: u32 = 10;
-		const BRIDGED_MESSAGES_PALLET_NAME: &'static str =
-			bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
-
-		type ThisChain = RococoAtRococo;
-		type BridgedChain = WococoAtRococo;
-
-		fn bridged_balance_to_this_balance(
-			bridged_balance: bp_wococo::Balance,
-			_bridged_to_this_conversion_rate_override: Option<FixedU128>,
-		) -> bp_rococo::Balance {
-			bridged_balance
-		}
+impl MessagesParameter for BridgeMessagesParameter {
+	fn save(&self) {
+		use crate::bridge_config::{
+			BridgedToThisConversionRate, ConversionRateChangeLimit, ConversionRateUpdatedAt,
+			CustomLaneLimits, Event, FeeAssetConversionRate, LargeMessageFeeCurve,
+			MaxConversionRateStaleness, RelayerFeePercents,
+		};
+
+		let event = match *self {
+			BridgeMessagesParameter::RelayerFeePercent(lane, percent) => {
+				let old = RelayerFeePercents::<Runtime>::get(lane);
+				RelayerFeePercents::<Runtime>::insert(lane, percent);
+				Event::RelayerFeePercentUpdated { lane, old, new: percent }
+			},
+			BridgeMessagesParameter::LaneLimits(lane, limits) => {
+				let max_limits = max_lane_limits();
+				let limits = LaneLimits {
+					max_pending_messages: limits
+						.max_pending_messages
+						.min(max_limits.max_pending_messages),
+					max_unrewarded_relayer_entries: limits
+						.max_unrewarded_relayer_entries
+						.min(max_limits.max_unrewarded_relayer_entries),
+					max_unconfirmed_messages: limits
+						.max_unconfirmed_messages
+						.min(max_limits.max_unconfirmed_messages),
+				};
+				let old = CustomLaneLimits::<Runtime>::get(lane);
+				CustomLaneLimits::<Runtime>::insert(lane, limits);
+				Event::LaneLimitsUpdated { lane, old, new: limits }
+			},
+			BridgeMessagesParameter::BridgedToThisConversionRate(conversion_rate) => {
+				let now = frame_system::Pallet::<Runtime>::block_number();
+				let old = BridgedToThisConversionRate::<Runtime>::get();
+				let conversion_rate = limit_conversion_rate_change(conversion_rate, now);
+				BridgedToThisConversionRate::<Runtime>::put(conversion_rate);
+				ConversionRateUpdatedAt::<Runtime>::put(now);
+				Event::ConversionRateUpdated { old, new: conversion_rate }
+			},
+			BridgeMessagesParameter::MaxConversionRateStaleness(max_staleness) => {
+				let old = MaxConversionRateStaleness::<Runtime>::get();
+				MaxConversionRateStaleness::<Runtime>::set(max_staleness);
+				Event::MaxConversionRateStalenessUpdated { old, new: max_staleness }
+			},
+			BridgeMessagesParameter::ConversionRateChangeLimit(change_limit) => {
+				let old = ConversionRateChangeLimit::<Runtime>::get();
+				ConversionRateChangeLimit::<Runtime>::set(change_limit);
+				Event::ConversionRateChangeLimitUpdated { old, new: change_limit }
+			},
+			BridgeMessagesParameter::FeeAssetConversionRate(conversion_rate) => {
+				let old = FeeAssetConversionRate::<Runtime>::get();
+				FeeAssetConversionRate::<Runtime>::put(conversion_rate);
+				Event::FeeAssetConversionRateUpdated { old, new: conversion_rate }
+			},
+			BridgeMessagesParameter::LargeMessageFee(large_message_fee) => {
+				let old = LargeMessageFeeCurve::<Runtime>::get();
+				LargeMessageFeeCurve::<Runtime>::set(large_message_fee);
+				Event::LargeMessageFeeUpdated { old, new: large_message_fee }
+			},
+		};
+		crate::BridgeConfig::deposit_event(event);
 	}
+}
 
-	/// Message payload for Rococo -> Wococo messages as it is seen at the Rococo.
-	pub type ToWococoMessagePayload =
-		messages_source::FromThisChainMessagePayload<AtRococoWithWococoMessageBridge>;
+/// Maximal limits of the messages of all lanes.
+fn max_lane_limits() -> LaneLimits {
+	LaneLimits {
+		max_pending_messages: MAXIMAL_PENDING_MESSAGES_AT_OUTBOUND_LANE,
+		max_unrewarded_relayer_entries: crate::MaxUnrewardedRelayerEntriesAtInboundLane::get(),
+		max_unconfirmed_messages: crate::MaxUnconfirmedMessagesAtInboundLane::get(),
+	}
+}
 
-	/// Message verifier for Rococo -> Wococo messages at Rococo.
-	pub type ToWococoMessageVerifier =
-		messages_source::FromThisChainMessageVerifier<AtRococoWithWococoMessageBridge>;
+/// Limits of the messages of the lane, set by the `LaneLimits` parameter or the maximal limits.
+fn lane_limits(lane: &LaneId) -> LaneLimits {
+	crate::BridgeConfig::lane_limits(lane).unwrap_or_else(max_lane_limits)
+}
 
-	/// Message payload for Wococo -> Rococo messages as it is seen at Rococo.
-	pub type FromWococoMessagePayload =
-		messages_target::FromBridgedChainMessagePayload<AtRococoWithWococoMessageBridge>;
+/// Inbound lane limits, set by the `LaneLimits` parameter of the messages pallets.
+pub struct ConfiguredInboundLaneLimits;
 
-	/// Encoded Rococo Call as it comes from Wococo.
-	pub type FromWococoEncodedCall =
-		messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+impl InboundLaneLimits for ConfiguredInboundLaneLimits {
+	fn max_unrewarded_relayer_entries(lane: &LaneId) -> Option<MessageNonce> {
+		crate::BridgeConfig::lane_limits(lane).map(|limits| limits.max_unrewarded_relayer_entries)
+	}
 
-	/// Call-dispatch based message dispatch for Wococo -> Rococo messages.
-	pub type FromWococoMessageDispatch = messages_target::FromBridgedChainMessageDispatch<
-		AtRococoWithWococoMessageBridge,
-		Runtime,
-		Balances,
-		crate::AtRococoFromWococoMessagesDispatch,
-	>;
+	fn max_unconfirmed_messages(lane: &LaneId) -> Option<MessageNonce> {
+		crate::BridgeConfig::lane_limits(lane).map(|limits| limits.max_unconfirmed_messages)
+	}
 }
 
-/// This module contains definitions that are used by the messages pallet instance, "deployed" at Wococo.
-mod at_wococo {
-	use super::*;
+/// Clamps the new conversion rate to `ConversionRateChangeLimit`, which protects the bridge from
+/// mistyped or malicious updates of the rate.
+///
+/// The rate may only change by the limit within a period, relative to the rate at the start of the
+/// period, so it can't be moved further by a series of smaller updates.
+fn limit_conversion_rate_change(conversion_rate: FixedU128, now: crate::BlockNumber) -> FixedU128 {
+	use crate::bridge_config::ConversionRateChangePeriodStart;
+
+	let (max_change, period) = match crate::BridgeConfig::conversion_rate_change_limit() {
+		Some(change_limit) => change_limit,
+		None => return conversion_rate,
+	};
+
+	let (mut period_start, mut period_start_rate) =
+		crate::BridgeConfig::conversion_rate_change_period_start();
+	if now.saturating_sub(period_start) >= period {
+		period_start = now;
+		period_start_rate = crate::BridgeConfig::bridged_to_this_conversion_rate();
+		ConversionRateChangePeriodStart::<Runtime>::put((period_start, period_start_rate));
+	}
 
-	/// Message bridge that is "deployed" at Wococo chain and connecting it to Rococo chain.
-	#[derive(RuntimeDebug, Clone, Copy)]
-	pub struct AtWococoWithRococoMessageBridge;
-
-	impl MessageBridge for AtWococoWithRococoMessageBridge {
-		const THIS_CHAIN_ID: ChainId = WOCOCO_CHAIN_ID;
-		const BRIDGED_CHAIN_ID: ChainId = ROCOCO_CHAIN_ID;
-		const RELAYER_FEE_PERCENT: u32 = 10;
-		const BRIDGED_MESSAGES_PALLET_NAME: &'static str =
-			bp_wococo::WITH_WOCOCO_MESSAGES_PALLET_NAME;
-
-		type ThisChain = WococoAtWococo;
-		type BridgedChain = RococoAtWococo;
-
-		fn bridged_balance_to_this_balance(
-			bridged_balance: bp_rococo::Balance,
-			_bridged_to_this_conversion_rate_override: Option<FixedU128>,
-		) -> bp_wococo::Balance {
-			bridged_balance
-		}
+	let max_change = period_start_rate.saturating_mul(max_change.into());
+	conversion_rate.clamp(
+		period_start_rate.saturating_sub(max_change),
+		period_start_rate.saturating_add(max_change),
+	)
+}
+
+/// Returns true if the conversion rate hasn't been updated for longer than
+/// `MaxConversionRateStaleness` blocks.
+///
+/// Fees of outbound messages computed with a stale rate may be far from the actual costs of
+/// delivering the messages, so messages are rejected until the rate is updated.
+fn is_conversion_rate_stale() -> bool {
+	crate::BridgeConfig::max_conversion_rate_staleness().map_or(false, |max_staleness| {
+		let now = frame_system::Pallet::<Runtime>::block_number();
+		now.saturating_sub(crate::BridgeConfig::conversion_rate_updated_at()) > max_staleness
+	})
+}
+
+/// Priority boost of messages delivery transactions, submitted by relayers that are registered
+/// for the lane of the delivered messages.
+pub const REGISTERED_RELAYER_PRIORITY_BOOST: TransactionPriority = 1_000_000;
+
+/// Signed extension that prioritizes messages delivery transactions of registered relayers.
+///
+/// Registered relayers have reserved a bond for the lane (see `crate::bridge_relayers`), which
+/// is slashed if their transaction is rejected because of an invalid messages proof. Proofs that
+/// are rejected while the bridge is halted or the lane is disabled are not the relayer's fault,
+/// so they are never slashed.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct PrioritizeRegisteredRelayers;
+
+impl PrioritizeRegisteredRelayers {
+	/// If `call` delivers messages of a lane that `who` is registered for, returns the lane and
+	/// the error of the messages pallet that is returned for invalid messages proofs.
+	fn registered_lane(
+		who: &crate::AccountId,
+		call: &crate::Call,
+	) -> Option<(LaneId, DispatchError)> {
+		use pallet_bridge_messages::{Call as MessagesCall, Error as MessagesError};
+
+		let (lane, invalid_proof) = match call {
+			crate::Call::BridgeRococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof.lane,
+				MessagesError::<Runtime, crate::AtWococoWithRococoMessagesInstance>::InvalidMessagesProof
+					.into(),
+			),
+			crate::Call::BridgeWococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof.lane,
+				MessagesError::<Runtime, crate::AtRococoWithWococoMessagesInstance>::InvalidMessagesProof
+					.into(),
+			),
+			crate::Call::BridgeWestendMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof.lane,
+				MessagesError::<Runtime, crate::AtRococoWithWestendMessagesInstance>::InvalidMessagesProof
+					.into(),
+			),
+			_ => return None,
+		};
+		crate::BridgeRelayers::is_registered(who, lane).then(|| (lane, invalid_proof))
 	}
+}
 
-	/// Message payload for Wococo -> Rococo messages as it is seen at the Wococo.
-	pub type ToRococoMessagePayload =
-		messages_source::FromThisChainMessagePayload<AtWococoWithRococoMessageBridge>;
+impl SignedExtension for PrioritizeRegisteredRelayers {
+	const IDENTIFIER: &'static str = "PrioritizeRegisteredRelayers";
+	type AccountId = crate::AccountId;
+	type Call = crate::Call;
+	type AdditionalSigned = ();
+	type Pre = Option<(crate::AccountId, LaneId, DispatchError)>;
 
-	/// Message verifier for Wococo -> Rococo messages at Wococo.
-	pub type ToRococoMessageVerifier =
-		messages_source::FromThisChainMessageVerifier<AtWococoWithRococoMessageBridge>;
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
 
-	/// Message payload for Rococo -> Wococo messages as it is seen at Wococo.
-	pub type FromRococoMessagePayload =
-		messages_target::FromBridgedChainMessagePayload<AtWococoWithRococoMessageBridge>;
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		let priority = match Self::registered_lane(who, call) {
+			Some(_) => REGISTERED_RELAYER_PRIORITY_BOOST,
+			None => 0,
+		};
+		Ok(ValidTransaction { priority, ..Default::default() })
+	}
 
-	/// Encoded Wococo Call as it comes from Rococo.
-	pub type FromRococoEncodedCall =
-		messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(Self::registered_lane(who, call)
+			.map(|(lane, invalid_proof)| (who.clone(), lane, invalid_proof)))
+	}
 
-	/// Call-dispatch based message dispatch for Rococo -> Wococo messages.
-	pub type FromRococoMessageDispatch = messages_target::FromBridgedChainMessageDispatch<
-		AtWococoWithRococoMessageBridge,
-		Runtime,
-		Balances,
-		crate::AtWococoFromRococoMessagesDispatch,
-	>;
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		_info: &DispatchInfoOf<Self::Call>,
+		_post_info: &PostDispatchInfoOf<Self::Call>,
+		_len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		if let Some(Some((relayer, lane, invalid_proof))) = pre {
+			let is_lane_open = crate::BridgeConfig::is_receiving_messages() &&
+				crate::BridgeConfig::is_lane_enabled(&lane);
+			if *result == Err(invalid_proof) && is_lane_open {
+				crate::BridgeRelayers::slash_relayer(&relayer, lane);
+			}
+		}
+		Ok(())
+	}
 }
 
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use bp_messages::{target_chain::ProvedLaneMessages, MessageData, MessageKey};
-	use bridge_runtime_common::messages;
-	use parity_scale_codec::{Decode, Encode};
-	use sp_runtime::traits::TrailingZeroInput;
-
-	#[test]
-	fn ensure_rococo_messages_weights_are_correct() {
-		// **NOTE**: the main purpose of this test is to be sure that any message that is sumbitted
-		// to (any) inbound lane in Rococo<>Wococo bridge can be delivered to the bridged chain.
-		// Since we deal with testnets here, in case of failure + urgency:
-		//
-		// 1) ping bridges team about this failure (see the CODEOWNERS file if you're unsure who to ping);
-		// 2) comment/#[ignore] the test.
-
-		// we don't have any knowledge of messages-at-Rococo weights, so we'll be using
-		// weights of one of our testnets, which should be accurate enough
-		type Weights = pallet_bridge_messages::weights::MillauWeight<Runtime>;
-
-		pallet_bridge_messages::ensure_weights_are_correct::<Weights>(
-			DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT,
-			ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT,
-			MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT,
-			PAY_INBOUND_DISPATCH_FEE_WEIGHT,
-			crate::RocksDbWeight::get(),
-		);
+/// Priority boost of messages delivery transactions for every message that they deliver and that
+/// hasn't been delivered yet.
+pub const PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE: TransactionPriority = 10_000;
 
-		let max_incoming_message_proof_size = bp_rococo::EXTRA_STORAGE_PROOF_SIZE.saturating_add(
-			messages::target::maximal_incoming_message_size(Rococo::max_extrinsic_size()),
-		);
-		pallet_bridge_messages::ensure_able_to_receive_message::<Weights>(
-			Rococo::max_extrinsic_size(),
-			Rococo::max_extrinsic_weight(),
-			max_incoming_message_proof_size,
-			messages::target::maximal_incoming_message_dispatch_weight(
-				Rococo::max_extrinsic_weight(),
+/// Signed extension that rejects bridge transactions that wouldn't change anything: imports of
+/// GRANDPA finality proofs for already finalized headers and deliveries of already delivered
+/// messages.
+///
+/// Relayers are racing to submit the same headers and messages, so without this extension all
+/// but the first of the racing transactions would be included and paid for. Remaining messages
+/// deliveries are prioritized by the number of undelivered messages they bring, so that larger
+/// batches aren't delayed by the regular traffic and lanes don't stall.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct BridgeRejectObsoleteHeadersAndMessages;
+
+impl BridgeRejectObsoleteHeadersAndMessages {
+	/// Returns true if `call` imports a header that is already finalized.
+	fn is_obsolete_header(call: &crate::Call) -> bool {
+		use pallet_bridge_grandpa::Call as GrandpaCall;
+
+		match call {
+			crate::Call::BridgeRococoGrandpa(GrandpaCall::submit_finality_proof {
+				finality_target,
+				..
+			}) => *finality_target.number() <= crate::BridgeRococoGrandpa::best_finalized_number(),
+			crate::Call::BridgeWococoGrandpa(GrandpaCall::submit_finality_proof {
+				finality_target,
+				..
+			}) => *finality_target.number() <= crate::BridgeWococoGrandpa::best_finalized_number(),
+			crate::Call::BridgeWestendGrandpa(GrandpaCall::submit_finality_proof {
+				finality_target,
+				..
+			}) => *finality_target.number() <= crate::BridgeWestendGrandpa::best_finalized_number(),
+			_ => false,
+		}
+	}
+
+	/// If `call` delivers messages, returns the number of delivered messages that haven't been
+	/// delivered yet.
+	fn undelivered_messages(call: &crate::Call) -> Option<MessageNonce> {
+		use pallet_bridge_messages::{Call as MessagesCall, InboundLanes};
+
+		let (proof, last_delivered_nonce) = match call {
+			crate::Call::BridgeRococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof,
+				InboundLanes::<Runtime, crate::AtWococoWithRococoMessagesInstance>::get(
+					&proof.lane,
+				)
+				.last_delivered_nonce(),
 			),
-		);
+			crate::Call::BridgeWococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof,
+				InboundLanes::<Runtime, crate::AtRococoWithWococoMessagesInstance>::get(
+					&proof.lane,
+				)
+				.last_delivered_nonce(),
+			),
+			crate::Call::BridgeWestendMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => (
+				proof,
+				InboundLanes::<Runtime, crate::AtRococoWithWestendMessagesInstance>::get(
+					&proof.lane,
+				)
+				.last_delivered_nonce(),
+			),
+			_ => return None,
+		};
+		let first_undelivered_nonce =
+			proof.nonces_start.max(last_delivered_nonce.saturating_add(1));
+		Some(proof.nonces_end.saturating_add(1).saturating_sub(first_undelivered_nonce))
+	}
+}
 
-		let max_incoming_inbound_lane_data_proof_size =
-			bp_messages::InboundLaneData::<()>::encoded_size_hint(
-				bp_rococo::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
-				bp_rococo::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX as _,
-				bp_rococo::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX as _,
-			)
-			.unwrap_or(u32::MAX);
-		pallet_bridge_messages::ensure_able_to_receive_confirmation::<Weights>(
-			Rococo::max_extrinsic_size(),
-			Rococo::max_extrinsic_weight(),
-			max_incoming_inbound_lane_data_proof_size,
-			bp_rococo::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
-			bp_rococo::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
-			crate::RocksDbWeight::get(),
-		);
+impl SignedExtension for BridgeRejectObsoleteHeadersAndMessages {
+	const IDENTIFIER: &'static str = "BridgeRejectObsoleteHeadersAndMessages";
+	type AccountId = crate::AccountId;
+	type Call = crate::Call;
+	type AdditionalSigned = ();
+	type Pre = ();
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
 	}
 
-	#[test]
-	fn ensure_rococo_tx_extra_bytes_constant_is_correct() {
-		// **NOTE**: this test checks that we're computing transaction fee (for bridged chain, which, in
-		// case of Rococo<>Wococo, means any chain) on-chain properly. If this assert fails:
-		//
-		// 1) just fix the `TX_EXTRA_BYTES` constant to actual (or sightly rounded up) value;
-		// 2) (only if it has changed significantly (> x2 times)) ping the bridges team (see the CODEOWNERS
-		//    file if you're unsure who to ping)
-
-		let signed_extra: crate::SignedExtra = (
-			frame_system::CheckNonZeroSender::new(),
-			frame_system::CheckSpecVersion::new(),
-			frame_system::CheckTxVersion::new(),
-			frame_system::CheckGenesis::new(),
-			frame_system::CheckMortality::from(sp_runtime::generic::Era::mortal(
-				u64::MAX,
-				u64::MAX,
-			)),
-			frame_system::CheckNonce::from(primitives::v2::Nonce::MAX),
-			frame_system::CheckWeight::new(),
-			pallet_transaction_payment::ChargeTransactionPayment::from(
-				primitives::v2::Balance::MAX,
-			),
-		);
-		let mut zeroes = TrailingZeroInput::zeroes();
-		let extra_bytes_in_transaction = signed_extra.encoded_size() +
-			crate::Address::decode(&mut zeroes).unwrap().encoded_size() +
-			crate::Signature::decode(&mut zeroes).unwrap().encoded_size();
-		assert!(
-			TX_EXTRA_BYTES as usize >= extra_bytes_in_transaction,
-			"Hardcoded number of extra bytes in Rococo transaction {} is lower than actual value: {}",
-			TX_EXTRA_BYTES,
-			extra_bytes_in_transaction,
-		);
+	fn validate(
+		&self,
+		_who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> TransactionValidity {
+		if Self::is_obsolete_header(call) {
+			return InvalidTransaction::Stale.into()
+		}
+		let priority = match Self::undelivered_messages(call) {
+			Some(0) => return InvalidTransaction::Stale.into(),
+			Some(undelivered_messages) =>
+				PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE.saturating_mul(undelivered_messages),
+			None => 0,
+		};
+		Ok(ValidTransaction { priority, ..Default::default() })
 	}
+}
 
-	fn proved_messages(lane_id: LaneId) -> ProvedMessages<Message<Balance>> {
-		vec![(
-			lane_id,
-			ProvedLaneMessages {
-				lane_state: None,
-				messages: vec![Message {
-					key: MessageKey { lane_id, nonce: 0 },
-					data: MessageData { payload: vec![], fee: 0 },
-				}],
+/// Signed extension that refunds the transaction fee of relayers that deliver new messages.
+///
+/// Relayers may deliver messages together with the bridged header that the messages proof is
+/// verified against, by submitting the `submit_finality_proof` and the `receive_messages_proof`
+/// calls in a single atomic `Utility::batch_all`. The fee of such batches and of standalone
+/// `receive_messages_proof` transactions is refunded (without the tip) from the
+/// `RelayerRefundsAccount`, if they have delivered new messages.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub struct RefundRelayerForMessagesDelivery;
+
+impl RefundRelayerForMessagesDelivery {
+	/// If `call` delivers messages, returns the bridged chain and the lane of the messages.
+	fn delivery_lane(call: &crate::Call) -> Option<(ChainId, LaneId)> {
+		use pallet_bridge_messages::Call as MessagesCall;
+
+		match call {
+			crate::Call::Utility(pallet_utility::Call::batch_all { calls }) => match &calls[..] {
+				[header_call, messages_call] =>
+					Self::delivery_lane(messages_call).filter(|(bridged_chain, _)| {
+						Self::is_header_import(header_call, *bridged_chain)
+					}),
+				_ => None,
 			},
-		)]
-		.into_iter()
-		.collect()
+			crate::Call::BridgeRococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => Some((ROCOCO_CHAIN_ID, proof.lane)),
+			crate::Call::BridgeWococoMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => Some((WOCOCO_CHAIN_ID, proof.lane)),
+			crate::Call::BridgeWestendMessages(MessagesCall::receive_messages_proof {
+				proof, ..
+			}) => Some((WESTEND_CHAIN_ID, proof.lane)),
+			_ => None,
+		}
 	}
 
-	#[test]
-	fn verify_inbound_messages_lane_succeeds() {
-		assert_eq!(
-			verify_inbound_messages_lane(proved_messages([0, 0, 0, 0])),
-			Ok(proved_messages([0, 0, 0, 0])),
-		);
+	/// Returns true if `call` imports a finalized header of the bridged chain.
+	fn is_header_import(call: &crate::Call, bridged_chain: ChainId) -> bool {
+		use pallet_bridge_grandpa::Call as GrandpaCall;
+
+		match call {
+			crate::Call::BridgeRococoGrandpa(GrandpaCall::submit_finality_proof { .. }) =>
+				bridged_chain == ROCOCO_CHAIN_ID,
+			crate::Call::BridgeWococoGrandpa(GrandpaCall::submit_finality_proof { .. }) =>
+				bridged_chain == WOCOCO_CHAIN_ID,
+			crate::Call::BridgeWestendGrandpa(GrandpaCall::submit_finality_proof { .. }) =>
+				bridged_chain == WESTEND_CHAIN_ID,
+			_ => false,
+		}
 	}
 
-	#[test]
-	fn verify_inbound_messages_lane_fails() {
-		assert_eq!(
-			verify_inbound_messages_lane(proved_messages([0, 0, 0, 1])),
-			Err(INBOUND_LANE_DISABLED),
-		);
+	/// Returns the nonce of the last message from the bridged chain, delivered to the lane.
+	fn last_delivered_nonce(bridged_chain: ChainId, lane: LaneId) -> MessageNonce {
+		use pallet_bridge_messages::InboundLanes;
+
+		match bridged_chain {
+			ROCOCO_CHAIN_ID =>
+				InboundLanes::<Runtime, crate::AtWococoWithRococoMessagesInstance>::get(&lane)
+					.last_delivered_nonce(),
+			WESTEND_CHAIN_ID =>
+				InboundLanes::<Runtime, crate::AtRococoWithWestendMessagesInstance>::get(&lane)
+					.last_delivered_nonce(),
+			_ => InboundLanes::<Runtime, crate::AtRococoWithWococoMessagesInstance>::get(&lane)
+				.last_delivered_nonce(),
+		}
+	}
+}
+
+impl SignedExtension for RefundRelayerForMessagesDelivery {
+	const IDENTIFIER: &'static str = "RefundRelayerForMessagesDelivery";
+	type AccountId = crate::AccountId;
+	type Call = crate::Call;
+	type AdditionalSigned = ();
+	type Pre = Option<(crate::AccountId, ChainId, LaneId, MessageNonce)>;
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		_info: &DispatchInfoOf<Self::Call>,
+		_len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		Ok(Self::delivery_lane(call).map(|(bridged_chain, lane)| {
+			(who.clone(), bridged_chain, lane, Self::last_delivered_nonce(bridged_chain, lane))
+		}))
+	}
+
+	fn post_dispatch(
+		pre: Option<Self::Pre>,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		if let Some(Some((relayer, bridged_chain, lane, last_delivered_nonce))) = pre {
+			let has_delivered_messages =
+				Self::last_delivered_nonce(bridged_chain, lane) > last_delivered_nonce;
+			if result.is_ok() && has_delivered_messages {
+				let fee = pallet_transaction_payment::Pallet::<Runtime>::compute_actual_fee(
+					len as u32, info, post_info, 0,
+				);
+				crate::BridgeRelayers::refund_transaction_fee(&relayer, lane, fee);
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Classes of transactions that block space may be reserved for at Rococo/Wococo.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, MaxEncodedLen, RuntimeDebug, TypeInfo)]
+pub enum ReservedSpaceClass {
+	/// Deliveries of messages and of their confirmations over the bridge.
+	MessageDelivery,
+}
+
+/// Puts deliveries of messages and of their confirmations in [`ReservedSpaceClass::MessageDelivery`].
+pub struct ClassifyMessageDeliveries;
+
+impl ClassifyCall<crate::Call, ReservedSpaceClass> for ClassifyMessageDeliveries {
+	fn reserved_class(call: &crate::Call) -> Option<ReservedSpaceClass> {
+		use pallet_bridge_messages::Call as MessagesCall;
+
+		match call {
+			crate::Call::BridgeRococoMessages(MessagesCall::receive_messages_proof { .. }) |
+			crate::Call::BridgeRococoMessages(MessagesCall::receive_messages_delivery_proof {
+				..
+			}) |
+			crate::Call::BridgeWococoMessages(MessagesCall::receive_messages_proof { .. }) |
+			crate::Call::BridgeWococoMessages(MessagesCall::receive_messages_delivery_proof {
+				..
+			}) |
+			crate::Call::BridgeWestendMessages(MessagesCall::receive_messages_proof { .. }) |
+			crate::Call::BridgeWestendMessages(MessagesCall::receive_messages_delivery_proof {
+				..
+			}) => Some(ReservedSpaceClass::MessageDelivery),
+			_ => None,
+		}
+	}
+}
+
+/// Expires outbound messages once this chain reaches their `expires_at` block.
+pub struct ExpireMessagesAtThisChain;
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+	MessageExpiration<
+		MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
+	> for ExpireMessagesAtThisChain
+{
+	fn is_expired(
+		payload: &MessagePayload<
+			SourceChainAccountId,
+			TargetChainAccountPublic,
+			TargetChainSignature,
+			Call,
+		>,
+	) -> bool {
+		let now = frame_system::Pallet::<Runtime>::block_number();
+		payload.expires_at.map_or(false, |expires_at| u64::from(now) >= expires_at)
+	}
+}
+
+/// Pallets that react to the delivery of their outbound messages, confirmed by the bridged chain.
+///
+/// Pallets which send messages over their own lane are added wrapped in [`OnLaneDeliveryConfirmed`],
+/// so they only see confirmations of that lane.
+pub type OnBridgeMessagesDelivered = ForwardDispatchResults<OnBridgeDispatchResult>;
+
+/// Pallets that react to the dispatch results of outbound messages at the bridged chain, which
+/// are acknowledged together with the delivery confirmations.
+pub type OnBridgeDispatchResult = ();
+
+/// Forwards confirmations of messages delivered over the `Lane` to `Hook`.
+pub struct OnLaneDeliveryConfirmed<Lane, Hook>(PhantomData<(Lane, Hook)>);
+
+impl<Lane, Hook> OnDeliveryConfirmed for OnLaneDeliveryConfirmed<Lane, Hook>
+where
+	Lane: Get<LaneId>,
+	Hook: OnDeliveryConfirmed,
+{
+	fn on_messages_delivered(lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+		if *lane != Lane::get() {
+			return 0
+		}
+		Hook::on_messages_delivered(lane, messages)
+	}
+}
+
+/// Pallets that react to their outbound messages being accepted by the messages pallet.
+///
+/// Pallets which send messages over their own lane are added wrapped in [`OnLaneMessageAccepted`],
+/// so they only see nonces of messages sent over that lane.
+pub type OnBridgeMessageAccepted = ();
+
+/// Forwards nonces of messages accepted to the `Lane` to `Hook`.
+pub struct OnLaneMessageAccepted<Lane, Hook>(PhantomData<(Lane, Hook)>);
 
-		let proved_messages = proved_messages([0, 0, 0, 0])
-			.into_iter()
-			.chain(proved_messages([0, 0, 0, 1]))
-			.collect();
-		assert_eq!(verify_inbound_messages_lane(proved_messages), Err(INBOUND_LANE_DISABLED),);
+impl<Lane, Hook> OnMessageAccepted for OnLaneMessageAccepted<Lane, Hook>
+where
+	Lane: Get<LaneId>,
+	Hook: OnMessageAccepted,
+{
+	fn on_messages_accepted(lane: &LaneId, message: &MessageNonce) -> Weight {
+		if *lane != Lane::get() {
+			return 0
+		}
+		Hook::on_messages_accepted(lane, message)
 	}
 }
+
+/// Marks outbound lanes of the messages pallet instance `I` as congested, or uncongested, when
+/// messages are accepted to, or delivered over, the lane.
+///
+/// The outbound lane has just been updated by the messages pallet and the lane limits have been
+/// read by the message verifier, so the hook only reads and writes the congestion state.
+pub struct TrackLaneCongestion<I>(PhantomData<I>);
+
+impl<I: 'static> TrackLaneCongestion<I>
+where
+	Runtime: pallet_bridge_messages::Config<I>,
+{
+	fn bridged_chain() -> ChainId {
+		<Runtime as pallet_bridge_messages::Config<I>>::BridgedChainId::get()
+	}
+
+	fn pending_messages(lane: &LaneId) -> MessageNonce {
+		let data = pallet_bridge_messages::OutboundLanes::<Runtime, I>::get(lane);
+		data.latest_generated_nonce.saturating_sub(data.latest_received_nonce)
+	}
+}
+
+impl<I: 'static> OnMessageAccepted for TrackLaneCongestion<I>
+where
+	Runtime: pallet_bridge_messages::Config<I>,
+{
+	fn on_messages_accepted(lane: &LaneId, _message: &MessageNonce) -> Weight {
+		crate::BridgeConfig::on_pending_messages_increased(
+			Self::bridged_chain(),
+			lane,
+			Self::pending_messages(lane),
+			|| lane_limits(lane).max_pending_messages,
+		)
+	}
+}
+
+impl<I: 'static> OnDeliveryConfirmed for TrackLaneCongestion<I>
+where
+	Runtime: pallet_bridge_messages::Config<I>,
+{
+	fn on_messages_delivered(lane: &LaneId, _messages: &DeliveredMessages) -> Weight {
+		crate::BridgeConfig::on_pending_messages_decreased(
+			Self::bridged_chain(),
+			lane,
+			Self::pending_messages(lane),
+		)
+	}
+}
+
+/// Payload of the messages, sent by the pallets of this chain to the bridged chain.
+type ToBridgedChainMessagePayload =
+	MessagePayload<crate::AccountId, primitives::v2::AccountPublic, crate::Signature, Vec<u8>>;
+
+/// The messages pallet instance `I`.
+type MessagesPallet<I> = pallet_bridge_messages::Pallet<Runtime, I>;
+
+/// Sends the messages of the assets bridge over the messages pallet instance `I`.
+///
+/// The bridged chain runs the same runtime, so the tokens are unlocked there by the balance
+/// transfer from the account of the assets bridge. The account must be an allowed sender of the
+/// lane.
+pub struct UnlockBridgedTokens<I>(PhantomData<I>);
+
+impl<I: 'static> UnlockBridgedTokens<I> {
+	fn payload(
+		sender: crate::AccountId,
+		recipient: crate::AccountId,
+		amount: Balance,
+	) -> ToBridgedChainMessagePayload {
+		let call = crate::Call::Balances(pallet_balances::Call::transfer_keep_alive {
+			dest: recipient.into(),
+			value: amount,
+		});
+		MessagePayload {
+			spec_version: crate::VERSION.spec_version,
+			weight: call.get_dispatch_info().weight,
+			origin: CallOrigin::SourceAccount(sender),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
+			call: call.encode(),
+		}
+	}
+}
+
+impl<I: 'static> UnlockMessages<crate::AccountId, Balance> for UnlockBridgedTokens<I>
+where
+	Runtime: pallet_bridge_messages::Config<
+		I,
+		OutboundPayload = ToBridgedChainMessagePayload,
+		OutboundMessageFee = Balance,
+	>,
+{
+	fn send_weight() -> Weight {
+		use pallet_balances::WeightInfo;
+		use pallet_bridge_messages::WeightInfoExt;
+
+		let payload = Self::payload([0; 32].into(), [0; 32].into(), 0);
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		<Runtime as pallet_bridge_messages::Config<I>>::WeightInfo::send_message_weight(
+			&payload, db_weight,
+		)
+		.saturating_add(<Runtime as pallet_balances::Config>::WeightInfo::transfer())
+	}
+
+	fn send(
+		lane: LaneId,
+		sender: crate::AccountId,
+		payer: &crate::AccountId,
+		recipient: crate::AccountId,
+		amount: Balance,
+		fee: Balance,
+	) -> Result<MessageNonce, DispatchError> {
+		let payload = Self::payload(sender.clone(), recipient, amount);
+		frame_support::storage::with_transaction(|| {
+			// the sender pays the fee to the relayer fund, so it gets the fee from the payer first
+			let sent = <Balances as Currency<_>>::transfer(
+				payer,
+				&sender,
+				fee,
+				ExistenceRequirement::KeepAlive,
+			)
+			.and_then(|_| {
+				<MessagesPallet<I> as MessagesBridge<_, _, _, _>>::send_message(
+					crate::Origin::signed(sender),
+					lane,
+					payload,
+					fee,
+				)
+				.map_err(|err| err.error)
+			});
+			match sent {
+				Ok(artifacts) => TransactionOutcome::Commit(Ok(artifacts.nonce)),
+				Err(err) => TransactionOutcome::Rollback(Err(err)),
+			}
+		})
+	}
+}
+
+/// This module contains definitions that are used by the messages pallet instance, "deployed" at Rococo.
+mod at_rococo {
+	use super::*;
+
+	bridge_runtime_common::impl_message_bridge! {
+		/// Message bridge that is "deployed" at Rococo chain and connecting it to Wococo chain.
+		#[derive(RuntimeDebug, Clone, Copy)]
+		pub struct AtRococoWithWococoMessageBridge {
+			this_chain_id: ROCOCO_CHAIN_ID,
+			bridged_chain_id: WOCOCO_CHAIN_ID,
+			bridged_messages_pallet_name: bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME,
+			this_chain: RococoAtRococo,
+			bridged_chain: WococoAtRococo,
+			relayer_fee_percent: 10,
+			bridged_to_this_conversion_rate: crate::BridgeConfig::bridged_to_this_conversion_rate,
+			lane_relayer_fee_percent: crate::BridgeConfig::relayer_fee_percent,
+			lowest_relayer_bid: |lane: &LaneId| crate::BridgeRelayers::lowest_bid(*lane),
+			is_lane_subsidized: crate::BridgeConfig::is_lane_subsidized,
+		}
+
+		/// Message payload for Rococo -> Wococo messages as it is seen at the Rococo.
+		pub type ToWococoMessagePayload = outbound_payload;
+		/// Message verifier for Rococo -> Wococo messages at Rococo.
+		pub type ToWococoMessageVerifier = message_verifier;
+		/// Message payload for Wococo -> Rococo messages as it is seen at Rococo.
+		pub type FromWococoMessagePayload = inbound_payload;
+	}
+
+	/// Encoded Rococo Call as it comes from Wococo.
+	pub type FromWococoEncodedCall =
+		messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+
+	/// Call-dispatch based message dispatch for Wococo -> Rococo messages.
+	pub type FromWococoMessageDispatch = LaneFilteredMessageDispatch<
+		AtRococoWithWococoMessageBridge,
+		messages_target::FromBridgedChainMessageDispatch<
+			AtRococoWithWococoMessageBridge,
+			Runtime,
+			Balances,
+			crate::AtRococoFromWococoMessagesDispatch,
+		>,
+		crate::AtRococoFromWococoMessagesDispatch,
+	>;
+}
+
+/// This module contains definitions that are used by the messages pallet instance, "deployed" at Wococo.
+mod at_wococo {
+	use super::*;
+
+	bridge_runtime_common::impl_message_bridge! {
+		/// Message bridge that is "deployed" at Wococo chain and connecting it to Rococo chain.
+		#[derive(RuntimeDebug, Clone, Copy)]
+		pub struct AtWococoWithRococoMessageBridge {
+			this_chain_id: WOCOCO_CHAIN_ID,
+			bridged_chain_id: ROCOCO_CHAIN_ID,
+			bridged_messages_pallet_name: bp_wococo::WITH_WOCOCO_MESSAGES_PALLET_NAME,
+			this_chain: WococoAtWococo,
+			bridged_chain: RococoAtWococo,
+			relayer_fee_percent: 10,
+			bridged_to_this_conversion_rate: crate::BridgeConfig::bridged_to_this_conversion_rate,
+			lane_relayer_fee_percent: crate::BridgeConfig::relayer_fee_percent,
+			lowest_relayer_bid: |lane: &LaneId| crate::BridgeRelayers::lowest_bid(*lane),
+			is_lane_subsidized: crate::BridgeConfig::is_lane_subsidized,
+		}
+
+		/// Message payload for Wococo -> Rococo messages as it is seen at the Wococo.
+		pub type ToRococoMessagePayload = outbound_payload;
+		/// Message verifier for Wococo -> Rococo messages at Wococo.
+		pub type ToRococoMessageVerifier = message_verifier;
+		/// Message payload for Rococo -> Wococo messages as it is seen at Wococo.
+		pub type FromRococoMessagePayload = inbound_payload;
+	}
+
+	/// Encoded Wococo Call as it comes from Rococo.
+	pub type FromRococoEncodedCall =
+		messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+
+	/// Call-dispatch based message dispatch for Rococo -> Wococo messages.
+	pub type FromRococoMessageDispatch = LaneFilteredMessageDispatch<
+		AtWococoWithRococoMessageBridge,
+		messages_target::FromBridgedChainMessageDispatch<
+			AtWococoWithRococoMessageBridge,
+			Runtime,
+			Balances,
+			crate::AtWococoFromRococoMessagesDispatch,
+		>,
+		crate::AtWococoFromRococoMessagesDispatch,
+	>;
+}
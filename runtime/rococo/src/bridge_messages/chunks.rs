@@ -0,0 +1,81 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Chunks of the governance calls that don't fit into a single message.
+
+use super::*;
+
+impl<I: 'static> SendRemoteGovernanceCalls<I> {
+	/// Call of the bridged chain, that receives the chunk of the encoded call.
+	fn chunk_call(
+		call_hash: H256,
+		index: u32,
+		total: u32,
+		call_weight: Weight,
+		chunk: Vec<u8>,
+	) -> crate::Call {
+		crate::Call::BridgeChunks(crate::bridge_chunks::Call::receive_chunk {
+			call_hash,
+			index,
+			total,
+			call_weight,
+			chunk,
+		})
+	}
+
+	/// Maximal size of the chunk that is sent in a single message.
+	pub(super) fn max_chunk_size() -> usize {
+		// the chunk of the empty call is encoded with single byte length, which takes up to four
+		// bytes for larger chunks
+		let overhead = Self::chunk_call(Default::default(), 0, 0, 0, Vec::new()).encoded_size() + 3;
+		Self::max_call_size().saturating_sub(overhead).max(1)
+	}
+
+	/// Sends the call in chunks with the given `send_message`, all or none of them.
+	///
+	/// Returns the nonce of the message with the last chunk.
+	pub(super) fn send_chunks(
+		call: Vec<u8>,
+		call_weight: Weight,
+		send_message: impl Fn(ToBridgedChainMessagePayload) -> Result<MessageNonce, DispatchError>,
+	) -> Result<MessageNonce, DispatchError> {
+		let call_hash = H256(blake2_256(&call));
+		let total = call.chunks(Self::max_chunk_size()).count() as u32;
+		frame_support::storage::with_transaction(|| {
+			let mut nonce = 0;
+			for (index, chunk) in call.chunks(Self::max_chunk_size()).enumerate() {
+				let chunk_call =
+					Self::chunk_call(call_hash, index as u32, total, call_weight, chunk.to_vec());
+				let chunk_call_weight = chunk_call.get_dispatch_info().weight;
+				match send_message(Self::payload(chunk_call.encode(), chunk_call_weight)) {
+					Ok(chunk_nonce) => nonce = chunk_nonce,
+					Err(err) => return TransactionOutcome::Rollback(Err(err)),
+				}
+			}
+			TransactionOutcome::Commit(Ok(nonce))
+		})
+	}
+}
+
+/// Checks calls, reassembled from chunks, against the call filter of the Root origin of bridged
+/// chains.
+pub struct SourceRootCallFilter;
+
+impl Contains<crate::Call> for SourceRootCallFilter {
+	fn contains(call: &crate::Call) -> bool {
+		crate::BridgeConfig::is_source_root_call_allowed(&call.encode())
+	}
+}
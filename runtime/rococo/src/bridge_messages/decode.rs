@@ -0,0 +1,226 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Decoding and checking of the calls of inbound messages.
+
+use super::*;
+
+/// Maximal size of the encoded call of inbound message that may be dispatched at this chain.
+pub const MAX_INBOUND_CALL_SIZE: usize = 64 * 1024;
+/// Maximal nesting depth of the call of inbound message that may be dispatched at this chain.
+pub const MAX_INBOUND_CALL_DEPTH: u32 = 16;
+
+/// Inspects the batch, proxy, multisig, sudo and collective calls of this runtime, so that call
+/// filters of lanes may check calls that they dispatch.
+pub struct WrapperCalls;
+
+impl InnerCalls for WrapperCalls {
+	fn inner_calls(encoded_call: &[u8]) -> Option<Vec<Vec<u8>>> {
+		let call = crate::Call::decode_with_depth_limit(
+			sp_api::MAX_EXTRINSIC_DEPTH,
+			&mut &encoded_call[..],
+		)
+		.ok()?;
+		let inner_calls = match call {
+			crate::Call::Utility(pallet_utility::Call::batch { calls }) |
+			crate::Call::Utility(pallet_utility::Call::batch_all { calls }) => calls,
+			crate::Call::Utility(pallet_utility::Call::as_derivative { call, .. }) |
+			crate::Call::Utility(pallet_utility::Call::dispatch_as { call, .. }) |
+			crate::Call::Proxy(pallet_proxy::Call::proxy { call, .. }) |
+			crate::Call::Proxy(pallet_proxy::Call::proxy_announced { call, .. }) |
+			crate::Call::Multisig(pallet_multisig::Call::as_multi_threshold_1 { call, .. }) |
+			crate::Call::Sudo(pallet_sudo::Call::sudo { call }) |
+			crate::Call::Sudo(pallet_sudo::Call::sudo_unchecked_weight { call, .. }) |
+			crate::Call::Sudo(pallet_sudo::Call::sudo_as { call, .. }) => vec![*call],
+			crate::Call::Collective(pallet_collective::Call::execute { proposal, .. }) |
+			crate::Call::Collective(pallet_collective::Call::propose { proposal, .. }) =>
+				vec![*proposal],
+			_ => return None,
+		};
+		Some(inner_calls.iter().map(Encode::encode).collect())
+	}
+}
+
+/// Error of decoding the call of inbound message.
+#[derive(RuntimeDebug, PartialEq, Eq)]
+pub enum InboundCallError {
+	/// The encoded call is larger than `MAX_INBOUND_CALL_SIZE`.
+	TooLarge,
+	/// Calls are nested deeper than `MAX_INBOUND_CALL_DEPTH`.
+	TooDeep,
+	/// The call can't be decoded.
+	Undecodable,
+}
+
+/// Decode the call of inbound message, respecting `MAX_INBOUND_CALL_SIZE` and
+/// `MAX_INBOUND_CALL_DEPTH` limits.
+pub fn decode_inbound_call(encoded_call: &[u8]) -> Result<crate::Call, InboundCallError> {
+	if encoded_call.len() > MAX_INBOUND_CALL_SIZE {
+		return Err(InboundCallError::TooLarge)
+	}
+
+	crate::Call::decode_with_depth_limit(MAX_INBOUND_CALL_DEPTH, &mut &encoded_call[..]).map_err(
+		|_| {
+			// the size is limited, so it is fine to decode the call again to tell why it has failed
+			let is_too_deep = crate::Call::decode_with_depth_limit(
+				sp_api::MAX_EXTRINSIC_DEPTH,
+				&mut &encoded_call[..],
+			)
+			.is_ok();
+			if is_too_deep {
+				InboundCallError::TooDeep
+			} else {
+				InboundCallError::Undecodable
+			}
+		},
+	)
+}
+
+/// Check the decoded call of inbound message against the call filter of its lane and the weight
+/// limit of the call.
+pub(super) fn check_inbound_call(
+	call_filter: CallFilterId,
+	is_source_root: bool,
+	dispatch_weight: Weight,
+	call: &crate::Call,
+) -> MessagePayloadVerdict {
+	let encoded_call = call.encode();
+	let is_allowed = crate::BridgeConfig::is_call_allowed(call_filter, &encoded_call) &&
+		(!is_source_root || crate::BridgeConfig::is_source_root_call_allowed(&encoded_call));
+	if !is_allowed {
+		return MessagePayloadVerdict::CallNotAllowed
+	}
+
+	let is_within_weight_limit = crate::BridgeConfig::dispatch_weight_limit(&encoded_call)
+		.map_or(true, |weight_limit| dispatch_weight <= weight_limit);
+	if !is_within_weight_limit {
+		return MessagePayloadVerdict::DispatchWeightLimitExceeded
+	}
+
+	MessagePayloadVerdict::Accepted
+}
+
+/// Check the encoded payload of inbound message against the current configuration of its lane.
+///
+/// The payload is checked the same way as it is checked when the message is dispatched, so the
+/// verdict tells whether the message would be dispatched if it was delivered now. The expiration
+/// of the message isn't checked, because it depends on the bridged chain headers that are known
+/// at the time of delivery.
+pub fn check_inbound_message_payload<B: MessageBridge>(
+	lane_id: &LaneId,
+	encoded_payload: &[u8],
+) -> MessagePayloadVerdict
+where
+	messages_target::FromBridgedChainMessagePayload<B>: Decode,
+{
+	let payload = match messages_target::FromBridgedChainMessagePayload::<B>::decode(
+		&mut &encoded_payload[..],
+	) {
+		Ok(payload) => payload,
+		Err(_) => return MessagePayloadVerdict::UndecodablePayload,
+	};
+	let encoded_call = match Vec::<u8>::decode(&mut &payload.call.encode()[..]) {
+		Ok(encoded_call) => encoded_call,
+		Err(_) => return MessagePayloadVerdict::UndecodableCall,
+	};
+
+	match crate::BridgeConfig::lane_dispatch_mode(lane_id) {
+		Some(DispatchMode::Call) => {
+			if !crate::BridgeConfig::is_compatible_spec_version(payload.spec_version) {
+				return MessagePayloadVerdict::IncompatibleSpecVersion
+			}
+
+			let call = match decode_inbound_call(&encoded_call) {
+				Ok(call) => call,
+				Err(InboundCallError::TooLarge) => return MessagePayloadVerdict::CallTooLarge,
+				Err(InboundCallError::TooDeep) => return MessagePayloadVerdict::CallTooDeep,
+				Err(InboundCallError::Undecodable) => return MessagePayloadVerdict::UndecodableCall,
+			};
+			if call.get_dispatch_info().weight > payload.weight {
+				return MessagePayloadVerdict::InsufficientDispatchWeight
+			}
+
+			match crate::BridgeConfig::lane_call_filter(lane_id) {
+				Some(call_filter) => check_inbound_call(
+					call_filter,
+					matches!(payload.origin, CallOrigin::SourceRoot),
+					payload.weight,
+					&call,
+				),
+				None => MessagePayloadVerdict::LaneNotOpen,
+			}
+		},
+		Some(DispatchMode::Xcm) => {
+			let is_decodable = VersionedXcm::<crate::Call>::decode_with_depth_limit(
+				MAX_XCM_DECODE_DEPTH,
+				&mut &encoded_call[..],
+			)
+			.ok()
+			.and_then(|xcm| Xcm::try_from(xcm).ok())
+			.is_some();
+			if is_decodable {
+				MessagePayloadVerdict::Accepted
+			} else {
+				MessagePayloadVerdict::UndecodableCall
+			}
+		},
+		None => MessagePayloadVerdict::LaneNotOpen,
+	}
+}
+
+/// Dispatch the inbound message without committing any changes to the storage.
+///
+/// The message is dispatched by the given message dispatch the same way as if it was delivered by
+/// the given relayer now. All changes made by the dispatch, including events and fee payments,
+/// are rolled back afterwards, so relayers may skip messages that would fail to dispatch and
+/// refunds may be checked off-chain.
+pub fn dry_run_inbound_message_dispatch<B, Dispatch>(
+	relayer: &crate::AccountId,
+	lane_id: LaneId,
+	nonce: MessageNonce,
+	encoded_payload: &[u8],
+) -> MessageDispatchDryRun
+where
+	B: MessageBridge,
+	Dispatch: MessageDispatch<
+		crate::AccountId,
+		Balance,
+		DispatchPayload = messages_target::FromBridgedChainMessagePayload<B>,
+	>,
+	messages_target::FromBridgedChainMessagePayload<B>: Decode,
+{
+	let verdict = check_inbound_message_payload::<B>(&lane_id, encoded_payload);
+	let message = DispatchMessage {
+		key: MessageKey { lane_id, nonce },
+		data: DispatchMessageData {
+			payload: Decode::decode(&mut &encoded_payload[..]),
+			// the fee is paid at the source chain and isn't used by the dispatch
+			fee: 0,
+		},
+	};
+	let declared_weight = Dispatch::dispatch_weight(&message);
+	let result = frame_support::storage::with_transaction(|| {
+		TransactionOutcome::Rollback(Dispatch::dispatch(relayer, message))
+	});
+
+	MessageDispatchDryRun {
+		verdict,
+		dispatch_result: result.dispatch_result,
+		declared_weight,
+		actual_weight: declared_weight.saturating_sub(result.unspent_weight),
+		dispatch_fee_paid_during_dispatch: result.dispatch_fee_paid_during_dispatch,
+	}
+}
@@ -0,0 +1,128 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fees of messages and bridge transactions.
+
+use super::*;
+
+/// Converts proof size of the bridge transaction to fee.
+///
+/// Proofs of bridge transactions are mostly storage proofs, so they are priced by the benchmarked
+/// weight of verifying storage proofs of the given size. Both chains of the bridge are running the
+/// same runtime, so the same weight is used for the transactions at the bridged chain.
+pub(super) fn proof_size_to_fee(proof_size: u32) -> Balance {
+	use pallet_bridge_messages::WeightInfoExt;
+	type Weights = crate::weights::pallet_bridge_messages::WeightInfo<Runtime>;
+
+	WeightToFee::calc(&Weights::storage_proof_size_overhead(proof_size))
+}
+
+/// Reads the block number and `NextFeeMultiplier` from the storage proof of the bridged chain
+/// state at the finalized header.
+fn read_bridged_fee_multiplier<F: FinalityBackend<Chain = Rococo>>(
+	at: crate::Hash,
+	storage_proof: Vec<Vec<u8>>,
+) -> Result<(crate::BlockNumber, FixedU128), DispatchError> {
+	F::parse_finalized_storage_proof(at, StorageProof::new(storage_proof), |checker| {
+		let read_value = |pallet_prefix, value_name| {
+			let key = storage_value_key(pallet_prefix, value_name);
+			checker.read_value(&key.0).ok().flatten()
+		};
+		let block_number = read_value("System", "Number")?;
+		let fee_multiplier = read_value("TransactionPayment", "NextFeeMultiplier")?;
+		Some((
+			Decode::decode(&mut &block_number[..]).ok()?,
+			Decode::decode(&mut &fee_multiplier[..]).ok()?,
+		))
+	})?
+	.ok_or_else(|| crate::bridge_config::Error::<Runtime>::InvalidFeeMultiplierProof.into())
+}
+
+/// Reads the fee multiplier of Rococo or Wococo from a storage proof, verified against a finalized
+/// header imported by the finality pallet of the chain.
+pub struct BridgedFeeMultiplierFromProof;
+
+impl BridgedFeeMultiplierProof<crate::BlockNumber> for BridgedFeeMultiplierFromProof {
+	fn verify(
+		chain: ChainId,
+		at: crate::Hash,
+		storage_proof: Vec<Vec<u8>>,
+	) -> Result<(crate::BlockNumber, FixedU128), DispatchError> {
+		match chain {
+			ROCOCO_CHAIN_ID =>
+				read_bridged_fee_multiplier::<crate::RococoFinality>(at, storage_proof),
+			WOCOCO_CHAIN_ID =>
+				read_bridged_fee_multiplier::<crate::WococoFinality>(at, storage_proof),
+			WESTEND_CHAIN_ID =>
+				read_bridged_fee_multiplier::<crate::WestendFinality>(at, storage_proof),
+			_ => Err(crate::bridge_config::Error::<Runtime>::UnknownBridgedChain.into()),
+		}
+	}
+
+	fn proof_weight(proof_size: u32) -> Weight {
+		use pallet_bridge_messages::WeightInfoExt;
+
+		// verification of the message storage proofs is benchmarked by the messages pallet
+		crate::weights::pallet_bridge_messages::WeightInfo::<Runtime>::storage_proof_size_overhead(
+			proof_size,
+		)
+	}
+}
+
+/// The cost of delivery confirmation transaction.
+pub struct GetDeliveryConfirmationTransactionFee;
+
+impl Get<Balance> for GetDeliveryConfirmationTransactionFee {
+	fn get() -> Balance {
+		<RococoAtRococo as ThisChainWithMessages>::transaction_payment(
+			RococoAtRococo::estimate_delivery_confirmation_transaction(),
+		)
+	}
+}
+
+/// Subsidy of messages of subsidized lanes (see `crate::bridge_config::LaneFeePayment`).
+///
+/// Relayers are rewarded with the minimal fee of the message, as if it has been paid by its
+/// submitter.
+pub struct SubsidizedMessageFee<B>(PhantomData<B>);
+
+impl<B> crate::bridge_relayers::MessageSubsidy<Balance> for SubsidizedMessageFee<B>
+where
+	B: MessageBridge,
+	B::ThisChain: ChainWithMessages<Balance = Balance>,
+{
+	fn message_subsidy(lane: LaneId, payload: &[u8]) -> Balance {
+		if !crate::BridgeConfig::is_lane_subsidized(&lane) {
+			return 0
+		}
+
+		messages_source::FromThisChainMessagePayload::<B>::decode(&mut &payload[..])
+			.ok()
+			.and_then(|payload| {
+				messages_source::minimal_message_fee::<B>(&lane, &payload, None).ok()
+			})
+			.unwrap_or(0)
+	}
+}
+
+/// Converts message fees from the native tokens to the fee asset, using `FeeAssetConversionRate`.
+pub struct NativeToFeeAsset;
+
+impl sp_runtime::traits::Convert<Balance, Option<Balance>> for NativeToFeeAsset {
+	fn convert(fee: Balance) -> Option<Balance> {
+		crate::BridgeConfig::fee_asset_conversion_rate().checked_mul_int(fee)
+	}
+}
@@ -0,0 +1,109 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Messages of the governance of this chain to the bridged chains.
+
+use super::*;
+
+/// Sends the messages of the governance of this chain over the messages pallet instance `I`.
+///
+/// Messages are sent by the Root origin, so their fees are paid by the account that is linked to
+/// the Root origin. The account must be an allowed sender of the lane.
+///
+/// Calls that don't fit into a single message are split into chunks, which are reassembled by the
+/// `BridgeChunks` pallet of the bridged chain. Every chunk is sent in a separate message with the
+/// same fee, and the nonce of the last message, that dispatches the call, is returned.
+pub struct SendRemoteGovernanceCalls<I>(PhantomData<I>);
+
+impl<I: 'static> SendRemoteGovernanceCalls<I> {
+	pub(super) fn payload(call: Vec<u8>, call_weight: Weight) -> ToBridgedChainMessagePayload {
+		MessagePayload {
+			spec_version: crate::VERSION.spec_version,
+			weight: call_weight,
+			origin: CallOrigin::SourceRoot,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
+			call,
+		}
+	}
+
+	/// Maximal size of the call that is sent in a single message.
+	pub(super) fn max_call_size() -> usize {
+		(crate::BridgeConfig::maximal_outbound_payload_size() as usize).min(MAX_INBOUND_CALL_SIZE)
+	}
+}
+
+impl<I: 'static> RemoteCallMessages<Balance> for SendRemoteGovernanceCalls<I>
+where
+	Runtime: pallet_bridge_messages::Config<
+		I,
+		OutboundPayload = ToBridgedChainMessagePayload,
+		OutboundMessageFee = Balance,
+	>,
+{
+	fn send_weight(call_size: u32) -> Weight {
+		use pallet_bridge_messages::WeightInfoExt;
+
+		let call_size = call_size as usize;
+		let (messages, message_call_size) = if call_size <= Self::max_call_size() {
+			(1, call_size)
+		} else {
+			let max_chunk_size = Self::max_chunk_size();
+			((call_size + max_chunk_size - 1) / max_chunk_size, Self::max_call_size())
+		};
+		let payload = Self::payload(vec![0; message_call_size], 0);
+		let db_weight = <Runtime as frame_system::Config>::DbWeight::get();
+		<Runtime as pallet_bridge_messages::Config<I>>::WeightInfo::send_message_weight(
+			&payload, db_weight,
+		)
+		.saturating_mul(messages as Weight)
+	}
+
+	fn send(
+		lane: LaneId,
+		call: Vec<u8>,
+		call_weight: Weight,
+		fee: Balance,
+	) -> Result<MessageNonce, DispatchError> {
+		let send_message = |payload: ToBridgedChainMessagePayload| {
+			<MessagesPallet<I> as MessagesBridge<_, _, _, _>>::send_message(
+				crate::Origin::root(),
+				lane,
+				payload,
+				fee,
+			)
+			.map(|artifacts| artifacts.nonce)
+			.map_err(|err| err.error)
+		};
+		if call.len() <= Self::max_call_size() {
+			return send_message(Self::payload(call, call_weight))
+		}
+
+		Self::send_chunks(call, call_weight, send_message)
+	}
+}
+
+/// Accounts that messages of the governance of the bridged chains are dispatched with.
+pub struct BridgedRootAccounts;
+
+impl SortedMembers<crate::AccountId> for BridgedRootAccounts {
+	fn sorted_members() -> Vec<crate::AccountId> {
+		let mut accounts =
+			vec![crate::BridgedRococoRootAccount::get(), crate::BridgedWococoRootAccount::get()];
+		accounts.sort();
+		accounts
+	}
+}
@@ -0,0 +1,153 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Scheduled dispatch of inbound messages of lanes with a dispatch delay.
+
+use super::*;
+
+/// Maximal number of blocks that the scheduled dispatch of inbound message may be postponed by,
+/// when the agenda of the scheduler is full at the block after the dispatch delay of its lane.
+pub const MAX_DISPATCH_POSTPONEMENT: crate::BlockNumber = 8;
+
+impl<B, Dispatch, DispatchInstance> LaneFilteredMessageDispatch<B, Dispatch, DispatchInstance>
+where
+	B: MessageBridge,
+	B::BridgedChain: ChainWithMessages<AccountId = crate::AccountId>,
+	Runtime:
+		pallet_bridge_dispatch::Config<DispatchInstance, SourceChainAccountId = crate::AccountId>,
+	DispatchInstance: 'static,
+{
+	/// Schedules dispatch of the call of the message after the given number of blocks.
+	///
+	/// The message is verified the same way as by the inner dispatch, except that messages proving
+	/// control over an account of this chain and messages, whose dispatch fee is paid at this
+	/// chain, are refused. Only the weight of scheduling is spent by the delivery transaction.
+	///
+	/// If the agenda of the scheduler is full at the block after the delay, the call is scheduled
+	/// at the first of the next `MAX_DISPATCH_POSTPONEMENT` blocks with a free slot. If there is
+	/// none, the message is refused.
+	pub(super) fn schedule_dispatch(
+		key: &MessageKey,
+		payload: &messages_target::FromBridgedChainMessagePayload<B>,
+		call: crate::Call,
+		dispatch_delay: crate::BlockNumber,
+	) -> MessageDispatchResult {
+		use pallet_scheduler::WeightInfo;
+
+		let origin_account = bridged_dispatch_account::<DispatchInstance, _, _>(
+			B::BRIDGED_CHAIN_ID,
+			&payload.origin,
+		);
+		let is_schedulable =
+			crate::BridgeConfig::is_compatible_spec_version(payload.spec_version) &&
+				payload.dispatch_fee_payment == DispatchFeePayment::AtSourceChain &&
+				payload.weight >= call.get_dispatch_info().weight;
+		let origin_account = match origin_account {
+			Some(origin_account) if is_schedulable => origin_account,
+			_ => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Call of message {:?}/{} can't be scheduled",
+					key.lane_id,
+					key.nonce,
+				);
+				return refused_dispatch(payload.weight)
+			},
+		};
+
+		let first_block = frame_system::Pallet::<Runtime>::block_number()
+			.saturating_add(dispatch_delay)
+			.saturating_add(1);
+		let mut agenda_reads = 0;
+		let dispatch_block = (0..=MAX_DISPATCH_POSTPONEMENT)
+			.map(|postponement| first_block.saturating_add(postponement))
+			.find(|block| {
+				agenda_reads += 1;
+				let scheduled = pallet_scheduler::Agenda::<Runtime>::decode_len(block).unwrap_or(0);
+				scheduled < crate::MaxScheduledPerBlock::get() as usize
+			});
+		let agenda_weight = crate::RocksDbWeight::get().reads(agenda_reads);
+		let dispatch_block = match dispatch_block {
+			Some(dispatch_block) => dispatch_block,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Call of message {:?}/{} can't be scheduled: the agenda is full",
+					key.lane_id,
+					key.nonce,
+				);
+				return refused_dispatch(payload.weight.saturating_sub(agenda_weight))
+			},
+		};
+
+		let scheduled = <crate::Scheduler as ScheduleAnon<_, _, _>>::schedule(
+			DispatchTime::At(dispatch_block),
+			None,
+			schedule::LOWEST_PRIORITY,
+			crate::OriginCaller::system(frame_system::RawOrigin::Signed(origin_account)),
+			MaybeHashed::Value(call),
+		);
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Scheduling call of message {:?}/{} at block {}: {:?}",
+			key.lane_id,
+			key.nonce,
+			dispatch_block,
+			scheduled,
+		);
+		if scheduled.is_err() {
+			return refused_dispatch(payload.weight.saturating_sub(agenda_weight))
+		}
+
+		let schedule_weight = <Runtime as pallet_scheduler::Config>::WeightInfo::schedule(
+			crate::MaxScheduledPerBlock::get(),
+		)
+		.saturating_add(agenda_weight);
+		MessageDispatchResult {
+			dispatch_result: true,
+			unspent_weight: payload.weight.saturating_sub(schedule_weight),
+			dispatch_fee_paid_during_dispatch: false,
+		}
+	}
+}
+
+/// Account that the given dispatch pallet instance dispatches calls of messages, sent from the
+/// bridged chain with the given origin, with.
+///
+/// Messages proving control over an account of this chain are not supported, because the proof is
+/// verified by the dispatch pallet itself.
+fn bridged_dispatch_account<I: 'static, Signer, Signature>(
+	chain_id: ChainId,
+	origin: &CallOrigin<crate::AccountId, Signer, Signature>,
+) -> Option<crate::AccountId>
+where
+	Runtime: pallet_bridge_dispatch::Config<I, SourceChainAccountId = crate::AccountId>,
+{
+	use sp_runtime::traits::Convert;
+
+	match origin {
+		CallOrigin::SourceRoot =>
+			Some(<Runtime as pallet_bridge_dispatch::Config<I>>::AccountIdConverter::convert(
+				derive_account_id::<crate::AccountId>(chain_id, SourceAccount::Root),
+			)),
+		CallOrigin::SourceAccount(account) =>
+			Some(pallet_bridge_dispatch::Pallet::<Runtime, I>::bridged_account_id(
+				chain_id,
+				account.clone(),
+			)),
+		CallOrigin::TargetAccount(..) => None,
+	}
+}
@@ -0,0 +1,3996 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::bridge_config::{
+	AllowedCall, LaneFeePayment, NestedCallsFilter, OperatingMode, CALL_FILTER_ALL,
+	INITIAL_BRIDGED_TO_THIS_CONVERSION_RATE,
+};
+use bp_messages::{
+	source_chain::{LaneMessageVerifier, MessageDeliveryAndDispatchPayment},
+	target_chain::{DispatchMessageData, ProvedLaneMessages},
+	DeliveredMessages, MessageData, MessageDetails, MessageKey,
+	OperatingMode as MessagesOperatingMode, OutboundLaneData, OutboundLaneStats, UnrewardedRelayer,
+};
+use bp_runtime::messages::DispatchFeePayment;
+use bridge_runtime_common::{messages, mock_bridged_chain::MockBridgedChain};
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::GenesisBuild, weights::Pays};
+use pallet_bridge_messages::{
+	OutboundLanes, OutboundMessageSubmitters, OutboundMessages, PalletOperatingMode, PalletOwner,
+};
+use parity_scale_codec::{Decode, Encode};
+use sp_runtime::traits::TrailingZeroInput;
+use xcm::latest::prelude::*;
+
+#[test]
+fn ensure_rococo_messages_weights_are_correct() {
+	// **NOTE**: the main purpose of this test is to be sure that any message that is sumbitted
+	// to (any) inbound lane in Rococo<>Wococo bridge can be delivered to the bridged chain.
+	// Since we deal with testnets here, in case of failure + urgency:
+	//
+	// 1) ping bridges team about this failure (see the CODEOWNERS file if you're unsure who to ping);
+	// 2) comment/#[ignore] the test.
+
+	type Weights = crate::weights::pallet_bridge_messages::WeightInfo<Runtime>;
+
+	pallet_bridge_messages::ensure_weights_are_correct::<Weights>(
+		DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT,
+		ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT,
+		MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT,
+		PAY_INBOUND_DISPATCH_FEE_WEIGHT,
+		crate::RocksDbWeight::get(),
+	);
+
+	let max_incoming_message_proof_size = bp_rococo::EXTRA_STORAGE_PROOF_SIZE.saturating_add(
+		messages::target::maximal_incoming_message_size(Rococo::max_extrinsic_size()),
+	);
+	pallet_bridge_messages::ensure_able_to_receive_message::<Weights>(
+		Rococo::max_extrinsic_size(),
+		Rococo::max_extrinsic_weight(),
+		max_incoming_message_proof_size,
+		messages::target::maximal_incoming_message_dispatch_weight(Rococo::max_extrinsic_weight()),
+	);
+
+	let max_incoming_inbound_lane_data_proof_size =
+		bp_messages::InboundLaneData::<()>::encoded_size_hint(
+			bp_rococo::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
+			bp_rococo::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX as _,
+			bp_rococo::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX as _,
+		)
+		.unwrap_or(u32::MAX);
+	pallet_bridge_messages::ensure_able_to_receive_confirmation::<Weights>(
+		Rococo::max_extrinsic_size(),
+		Rococo::max_extrinsic_weight(),
+		max_incoming_inbound_lane_data_proof_size,
+		bp_rococo::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX,
+		bp_rococo::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX,
+		crate::RocksDbWeight::get(),
+	);
+}
+
+#[test]
+fn ensure_rococo_tx_extra_bytes_constant_is_correct() {
+	// **NOTE**: this test checks that we're computing transaction fee (for bridged chain, which, in
+	// case of Rococo<>Wococo, means any chain) on-chain properly. If this assert fails:
+	//
+	// 1) just fix the `TX_EXTRA_BYTES` constant to actual (or sightly rounded up) value;
+	// 2) (only if it has changed significantly (> x2 times)) ping the bridges team (see the CODEOWNERS
+	//    file if you're unsure who to ping)
+
+	let signed_extra: crate::SignedExtra = (
+		frame_system::CheckNonZeroSender::new(),
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckMortality::from(sp_runtime::generic::Era::mortal(u64::MAX, u64::MAX)),
+		frame_system::CheckNonce::from(primitives::v2::Nonce::MAX),
+		frame_system::CheckWeight::new(),
+		pallet_transaction_payment::ChargeTransactionPayment::from(primitives::v2::Balance::MAX),
+		PrioritizeRegisteredRelayers,
+		BridgeRejectObsoleteHeadersAndMessages,
+		RefundRelayerForMessagesDelivery,
+		runtime_common::reserved_space::ReserveBlockSpace::new(),
+	);
+	let mut zeroes = TrailingZeroInput::zeroes();
+	let extra_bytes_in_transaction = signed_extra.encoded_size() +
+		crate::Address::decode(&mut zeroes).unwrap().encoded_size() +
+		crate::Signature::decode(&mut zeroes).unwrap().encoded_size();
+	assert!(
+		TX_EXTRA_BYTES as usize >= extra_bytes_in_transaction,
+		"Hardcoded number of extra bytes in Rococo transaction {} is lower than actual value: {}",
+		TX_EXTRA_BYTES,
+		extra_bytes_in_transaction,
+	);
+}
+
+fn proved_messages(lane_id: LaneId) -> ProvedMessages<Message<Balance>> {
+	vec![(
+		lane_id,
+		ProvedLaneMessages {
+			lane_state: None,
+			messages: vec![Message {
+				key: MessageKey { lane_id, nonce: 0 },
+				data: MessageData { payload: vec![], fee: 0 },
+			}],
+		},
+	)]
+	.into_iter()
+	.collect()
+}
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+	crate::bridge_config::GenesisConfig {
+		lanes: vec![([0, 0, 0, 0], CALL_FILTER_ALL)],
+		source_root_call_filter: Some(CALL_FILTER_ALL),
+	}
+	.assimilate_storage::<Runtime>(&mut t)
+	.unwrap();
+	t.into()
+}
+
+#[test]
+fn message_is_accepted_only_from_allowed_senders() {
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let is_accepted = |who: &crate::AccountId, lane: LaneId| {
+			RococoAtRococo::is_message_accepted(&crate::Origin::signed(who.clone()), &lane)
+		};
+
+		assert!(!is_accepted(&alice, [0, 0, 0, 0]));
+
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			alice.clone()
+		));
+		assert!(is_accepted(&alice, [0, 0, 0, 0]));
+		assert!(!is_accepted(&alice, [0, 0, 0, 1]));
+		assert!(!is_accepted(&bob, [0, 0, 0, 0]));
+		assert!(!RococoAtRococo::is_message_accepted(&crate::Origin::root(), &[0, 0, 0, 0]));
+
+		assert_ok!(crate::BridgeConfig::set_lane_enabled(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			false
+		));
+		assert!(!is_accepted(&alice, [0, 0, 0, 0]));
+
+		assert_ok!(crate::BridgeConfig::set_lane_enabled(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			true
+		));
+		assert_ok!(crate::BridgeConfig::remove_message_sender(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			alice.clone()
+		));
+		assert!(!is_accepted(&alice, [0, 0, 0, 0]));
+	});
+}
+
+#[test]
+fn privileged_origins_send_messages_on_behalf_of_linked_accounts() {
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let collective = |yes, total| -> crate::Origin {
+			pallet_collective::RawOrigin::<crate::AccountId, ()>::Members(yes, total).into()
+		};
+		for who in [alice.clone(), bob.clone()] {
+			assert_ok!(crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				[0, 0, 0, 0],
+				who
+			));
+		}
+
+		assert_eq!(crate::Origin::root().linked_account(), None);
+		assert_eq!(collective(2, 3).linked_account(), None);
+
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Root,
+			Some(alice.clone()),
+		));
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Collective,
+			Some(bob.clone()),
+		));
+		assert_noop!(
+			crate::BridgeConfig::set_origin_account(
+				crate::Origin::signed(alice.clone()),
+				LinkedOrigin::Collective,
+				Some(alice.clone()),
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_eq!(crate::Origin::root().linked_account(), Some(alice));
+		assert_eq!(collective(2, 3).linked_account(), Some(bob.clone()));
+		assert_eq!(collective(1, 2).linked_account(), None);
+		assert!(RococoAtRococo::is_message_accepted(&collective(2, 3), &[0, 0, 0, 0]));
+		assert!(!RococoAtRococo::is_message_accepted(&collective(2, 3), &[0, 0, 0, 1]));
+
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Collective,
+			None,
+		));
+		assert_eq!(collective(2, 3).linked_account(), None);
+		assert!(!RococoAtRococo::is_message_accepted(&collective(2, 3), &[0, 0, 0, 0]));
+	});
+}
+
+#[test]
+fn message_senders_are_configured_per_lane() {
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let is_accepted = |lane: LaneId| {
+			RococoAtRococo::is_message_accepted(&crate::Origin::signed(alice.clone()), &lane)
+		};
+
+		assert_noop!(
+			crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				[0, 0, 0, 1],
+				alice.clone()
+			),
+			crate::bridge_config::Error::<Runtime>::LaneNotRegistered,
+		);
+
+		assert_ok!(crate::BridgeConfig::register_lane(crate::Origin::root(), [0, 0, 0, 1], 1));
+		assert_noop!(
+			crate::BridgeConfig::register_lane(
+				crate::Origin::root(),
+				[0, 0, 0, 1],
+				CALL_FILTER_ALL,
+			),
+			crate::bridge_config::Error::<Runtime>::LaneAlreadyRegistered,
+		);
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			[0, 0, 0, 1],
+			alice.clone()
+		));
+
+		assert!(is_accepted([0, 0, 0, 1]));
+		assert!(!is_accepted([0, 0, 0, 0]));
+		assert_eq!(crate::BridgeConfig::lane_call_filter(&[0, 0, 0, 1]), Some(1));
+	});
+}
+
+#[test]
+fn allowed_message_senders_are_bounded() {
+	new_test_ext().execute_with(|| {
+		for i in 0..crate::MaxAllowedMessageSenders::get() {
+			assert_ok!(crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				[0, 0, 0, 0],
+				crate::AccountId::from([i as u8; 32]),
+			));
+		}
+
+		assert_noop!(
+			crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				[0, 0, 0, 0],
+				crate::AccountId::from([u8::MAX; 32]),
+			),
+			crate::bridge_config::Error::<Runtime>::TooManyMessageSenders,
+		);
+		assert_noop!(
+			crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				[0, 0, 0, 0],
+				crate::AccountId::from([0; 32]),
+			),
+			crate::bridge_config::Error::<Runtime>::AlreadyAllowed,
+		);
+		assert_noop!(
+			crate::BridgeConfig::remove_message_sender(
+				crate::Origin::signed(crate::AccountId::from([0; 32])),
+				[0, 0, 0, 0],
+				crate::AccountId::from([0; 32]),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn call_filters_are_configured_by_governance() {
+	new_test_ext().execute_with(|| {
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] }).encode();
+		let other_remark =
+			crate::Call::System(frame_system::Call::remark { remark: vec![1] }).encode();
+		let transfer = crate::Call::Balances(pallet_balances::Call::transfer {
+			dest: crate::AccountId::from([1; 32]).into(),
+			value: 1,
+		})
+		.encode();
+		let is_allowed =
+			|call_filter, call: &Vec<u8>| crate::BridgeConfig::is_call_allowed(call_filter, call);
+
+		assert!(is_allowed(CALL_FILTER_ALL, &transfer));
+		assert!(!is_allowed(1, &remark));
+
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			1,
+			vec![AllowedCall::Index { pallet: remark[0], call: remark[1] }],
+		));
+		assert!(is_allowed(1, &remark));
+		assert!(is_allowed(1, &other_remark));
+		assert!(!is_allowed(1, &transfer));
+
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			1,
+			vec![AllowedCall::Hash(sp_io::hashing::blake2_256(&remark).into())],
+		));
+		assert!(is_allowed(1, &remark));
+		assert!(!is_allowed(1, &other_remark));
+
+		assert_ok!(crate::BridgeConfig::set_call_filter(crate::Origin::root(), 1, vec![]));
+		assert!(!is_allowed(1, &remark));
+
+		assert_noop!(
+			crate::BridgeConfig::set_call_filter(crate::Origin::root(), CALL_FILTER_ALL, vec![]),
+			crate::bridge_config::Error::<Runtime>::ReservedCallFilter,
+		);
+		assert_noop!(
+			crate::BridgeConfig::set_call_filter(
+				crate::Origin::root(),
+				1,
+				vec![
+					AllowedCall::Hash(Default::default());
+					crate::MaxAllowedCalls::get() as usize + 1
+				],
+			),
+			crate::bridge_config::Error::<Runtime>::TooManyAllowedCalls,
+		);
+		assert_noop!(
+			crate::BridgeConfig::set_call_filter(
+				crate::Origin::signed(crate::AccountId::from([1; 32])),
+				1,
+				vec![],
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+/// Returns Wococo -> Rococo message, signed by the Rococo account, that is remarking `[42]`.
+fn signed_message(
+	key: MessageKey,
+	spec_version: bp_message_dispatch::SpecVersion,
+	signer: primitives::v2::AccountPublic,
+	signature: crate::Signature,
+) -> DispatchMessage<FromWococoMessagePayload, Balance> {
+	let call = crate::Call::System(frame_system::Call::remark { remark: vec![42] });
+	DispatchMessage {
+		key,
+		data: DispatchMessageData {
+			payload: Ok(FromWococoMessagePayload {
+				spec_version,
+				weight: call.get_dispatch_info().weight,
+				origin: CallOrigin::TargetAccount(
+					crate::AccountId::from([1; 32]),
+					signer,
+					signature,
+				),
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: FromWococoEncodedCall::new(call.encode()),
+			}),
+			fee: 0,
+		},
+	}
+}
+
+/// Returns digest that is signed by the Rococo account to dispatch the [`signed_message`].
+fn signed_message_digest(
+	key: &MessageKey,
+	spec_version: bp_message_dispatch::SpecVersion,
+	source_chain_id: ChainId,
+) -> Vec<u8> {
+	pallet_bridge_dispatch::message_account_ownership_digest(
+		bp_runtime::ACCOUNT_OWNERSHIP_DOMAIN,
+		&(key.lane_id, key.nonce),
+		&crate::Call::System(frame_system::Call::remark { remark: vec![42] }),
+		crate::AccountId::from([1; 32]),
+		spec_version,
+		source_chain_id,
+		ROCOCO_CHAIN_ID,
+	)
+}
+
+#[test]
+fn target_account_messages_are_bound_to_lane_and_nonce() {
+	new_test_ext().execute_with(|| {
+		use sp_core::{ecdsa, ed25519, sr25519, Pair};
+
+		let relayer = crate::AccountId::from([3; 32]);
+		let spec_version = crate::VERSION.spec_version;
+		let key = |lane_id, nonce| MessageKey { lane_id, nonce };
+		let dispatch = |key, signer, signature| {
+			let message = signed_message(key, spec_version, signer, signature);
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+		let lane_id = [0, 0, 0, 0];
+		let other_lane_id = [0, 0, 0, 1];
+		assert_ok!(crate::BridgeConfig::register_lane(
+			crate::Origin::root(),
+			other_lane_id,
+			CALL_FILTER_ALL,
+		));
+
+		let sr25519 = sr25519::Pair::from_seed(&[1; 32]);
+		let ed25519 = ed25519::Pair::from_seed(&[2; 32]);
+		let ecdsa = ecdsa::Pair::from_seed(&[3; 32]);
+		let digest = signed_message_digest(&key(lane_id, 1), spec_version, WOCOCO_CHAIN_ID);
+		let signers: Vec<(primitives::v2::AccountPublic, crate::Signature)> = vec![
+			(sr25519.public().into(), sr25519.sign(&digest).into()),
+			(ed25519.public().into(), ed25519.sign(&digest).into()),
+			(ecdsa.public().into(), ecdsa.sign(&digest).into()),
+		];
+		for (signer, signature) in signers {
+			assert!(dispatch(key(lane_id, 1), signer.clone(), signature.clone()));
+			// the signature can't be replayed at other lanes or with other nonces
+			assert!(!dispatch(key(lane_id, 2), signer.clone(), signature.clone()));
+			assert!(!dispatch(key(other_lane_id, 1), signer, signature));
+		}
+
+		// signatures of the digest without the domain and message id are rejected
+		let legacy_digest = pallet_bridge_dispatch::account_ownership_digest(
+			&crate::Call::System(frame_system::Call::remark { remark: vec![42] }),
+			crate::AccountId::from([1; 32]),
+			spec_version,
+			WOCOCO_CHAIN_ID,
+			ROCOCO_CHAIN_ID,
+		);
+		assert!(!dispatch(
+			key(lane_id, 1),
+			sr25519.public().into(),
+			sr25519.sign(&legacy_digest).into(),
+		));
+	});
+}
+
+#[test]
+fn target_account_messages_are_bound_to_bridge_and_spec_version() {
+	new_test_ext().execute_with(|| {
+		use sp_core::{sr25519, Pair};
+
+		let relayer = crate::AccountId::from([3; 32]);
+		let spec_version = crate::VERSION.spec_version;
+		let key = MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 };
+		let signer = sr25519::Pair::from_seed(&[1; 32]);
+		let dispatch = |spec_version, source_chain_id| {
+			let digest = signed_message_digest(&key, spec_version, source_chain_id);
+			let message = signed_message(
+				key.clone(),
+				spec_version,
+				signer.public().into(),
+				signer.sign(&digest).into(),
+			);
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+
+		// signatures of messages of other bridges are rejected
+		assert!(!dispatch(spec_version, WESTEND_CHAIN_ID));
+
+		// calls of messages with the previous spec version may be dispatched, but their
+		// signatures may have been captured before the bridge has been redeployed
+		assert_ok!(crate::BridgeConfig::set_oldest_compatible_spec_version(
+			crate::Origin::root(),
+			Some(spec_version - 1),
+		));
+		assert!(!dispatch(spec_version - 1, WOCOCO_CHAIN_ID));
+		assert!(dispatch(spec_version, WOCOCO_CHAIN_ID));
+	});
+}
+
+#[test]
+fn call_weight_limits_are_enforced() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let encoded_remark = remark.encode();
+		let weight_limit = remark.get_dispatch_info().weight * 2;
+		let dispatch = |weight| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(encoded_remark.clone()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+		let max_weight = *WococoAtRococo::message_weight_limits(&encoded_remark).end();
+
+		assert!(dispatch(weight_limit + 1));
+
+		assert_ok!(crate::BridgeConfig::set_call_weight_limit(
+			crate::Origin::root(),
+			encoded_remark[0],
+			encoded_remark[1],
+			Some(weight_limit),
+		));
+		assert_eq!(WococoAtRococo::message_weight_limits(&encoded_remark), 0..=weight_limit);
+		assert!(dispatch(weight_limit));
+		assert!(!dispatch(weight_limit + 1));
+
+		assert_ok!(crate::BridgeConfig::set_call_weight_limit(
+			crate::Origin::root(),
+			encoded_remark[0],
+			encoded_remark[1],
+			None,
+		));
+		assert_eq!(WococoAtRococo::message_weight_limits(&encoded_remark), 0..=max_weight);
+		assert!(dispatch(weight_limit + 1));
+
+		assert_noop!(
+			crate::BridgeConfig::set_call_weight_limit(
+				crate::Origin::signed(relayer.clone()),
+				encoded_remark[0],
+				encoded_remark[1],
+				None,
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn nested_calls_are_filtered() {
+	new_test_ext().execute_with(|| {
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let transfer = crate::Call::Balances(pallet_balances::Call::transfer {
+			dest: crate::AccountId::from([1; 32]).into(),
+			value: 1,
+		});
+		let batch = |calls| crate::Call::Utility(pallet_utility::Call::batch { calls }).encode();
+		let is_allowed =
+			|call_filter, call: &Vec<u8>| crate::BridgeConfig::is_call_allowed(call_filter, call);
+		let encoded_remark = remark.encode();
+		let batch_of_remarks = batch(vec![remark.clone(), remark.clone()]);
+		let batch_of_transfers = batch(vec![transfer]);
+		let batch_of_batches = batch(vec![crate::Call::Utility(pallet_utility::Call::batch {
+			calls: vec![remark.clone()],
+		})]);
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			1,
+			vec![
+				AllowedCall::Index { pallet: encoded_remark[0], call: encoded_remark[1] },
+				AllowedCall::Index { pallet: batch_of_remarks[0], call: batch_of_remarks[1] },
+			],
+		));
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			2,
+			vec![AllowedCall::Hash(sp_io::hashing::blake2_256(&batch_of_transfers).into())],
+		));
+
+		// by default, calls that dispatch other calls are only allowed by their hash
+		assert!(is_allowed(1, &encoded_remark));
+		assert!(!is_allowed(1, &batch_of_remarks));
+		assert!(is_allowed(2, &batch_of_transfers));
+		assert!(is_allowed(CALL_FILTER_ALL, &batch_of_batches));
+
+		// inner calls must be allowed by the same call filter
+		assert_ok!(crate::BridgeConfig::set_nested_calls_filter(
+			crate::Origin::root(),
+			NestedCallsFilter::Inspect { max_depth: 1 },
+		));
+		assert!(is_allowed(1, &batch_of_remarks));
+		assert!(!is_allowed(1, &batch_of_transfers));
+		assert!(!is_allowed(1, &batch_of_batches));
+
+		assert_ok!(crate::BridgeConfig::set_nested_calls_filter(
+			crate::Origin::root(),
+			NestedCallsFilter::Inspect { max_depth: 2 },
+		));
+		assert!(is_allowed(1, &batch_of_batches));
+
+		assert_noop!(
+			crate::BridgeConfig::set_nested_calls_filter(
+				crate::Origin::signed(crate::AccountId::from([1; 32])),
+				NestedCallsFilter::Reject,
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn inbound_calls_are_filtered_per_lane() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let remark_with_event =
+			crate::Call::System(frame_system::Call::remark_with_event { remark: vec![] });
+		let dispatch = |lane_id, call: &crate::Call| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id, nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: 1_000_000_000,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(call.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+
+		// the call filter of lane 1 only allows remarks without events
+		let remark_encoded = remark.encode();
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			1,
+			vec![AllowedCall::Index { pallet: remark_encoded[0], call: remark_encoded[1] }],
+		));
+		assert_ok!(crate::BridgeConfig::register_lane(crate::Origin::root(), [0, 0, 0, 1], 1));
+
+		assert!(dispatch([0, 0, 0, 0], &remark));
+		assert!(dispatch([0, 0, 0, 0], &remark_with_event));
+		assert!(dispatch([0, 0, 0, 1], &remark));
+		assert!(!dispatch([0, 0, 0, 1], &remark_with_event));
+
+		// lane 0 may be restricted independently
+		assert_ok!(crate::BridgeConfig::set_lane_call_filter(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			2,
+		));
+		assert!(!dispatch([0, 0, 0, 0], &remark));
+		assert!(dispatch([0, 0, 0, 1], &remark));
+
+		assert_noop!(
+			crate::BridgeConfig::set_lane_call_filter(
+				crate::Origin::root(),
+				[0, 0, 0, 2],
+				CALL_FILTER_ALL,
+			),
+			crate::bridge_config::Error::<Runtime>::LaneNotRegistered,
+		);
+	});
+}
+
+#[test]
+fn source_root_calls_are_filtered() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let alice = crate::AccountId::from([1; 32]);
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let remark_with_event =
+			crate::Call::System(frame_system::Call::remark_with_event { remark: vec![] });
+		let dispatch = |origin, call: &crate::Call| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: 1_000_000_000,
+						origin,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(call.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+
+		// without the source root call filter, messages of the bridged chain Root are rejected
+		assert_ok!(crate::BridgeConfig::set_source_root_call_filter(crate::Origin::root(), None));
+		assert!(!dispatch(CallOrigin::SourceRoot, &remark));
+		assert!(dispatch(CallOrigin::SourceAccount(alice.clone()), &remark));
+
+		// the source root call filter only allows remarks without events
+		let remark_encoded = remark.encode();
+		assert_ok!(crate::BridgeConfig::set_call_filter(
+			crate::Origin::root(),
+			1,
+			vec![AllowedCall::Index { pallet: remark_encoded[0], call: remark_encoded[1] }],
+		));
+		assert_ok!(crate::BridgeConfig::set_source_root_call_filter(
+			crate::Origin::root(),
+			Some(1)
+		));
+		assert!(dispatch(CallOrigin::SourceRoot, &remark));
+		assert!(!dispatch(CallOrigin::SourceRoot, &remark_with_event));
+		assert!(dispatch(CallOrigin::SourceAccount(alice), &remark_with_event));
+
+		// the call filter of the lane still applies
+		assert_ok!(crate::BridgeConfig::set_lane_call_filter(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			2,
+		));
+		assert!(!dispatch(CallOrigin::SourceRoot, &remark));
+
+		assert_noop!(
+			crate::BridgeConfig::set_source_root_call_filter(
+				crate::Origin::signed(relayer.clone()),
+				Some(CALL_FILTER_ALL),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn unspent_dispatch_weight_is_reported() {
+	new_test_ext().execute_with(|| {
+		use frame_support::weights::GetDispatchInfo;
+
+		let relayer = crate::AccountId::from([3; 32]);
+		let declared_weight = 1_000_000_000;
+		let dispatch = |call: crate::Call| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: declared_weight,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(call.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message)
+		};
+
+		// the declared weight that the call hasn't used is refunded to the delivery transaction
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let remark_weight = remark.get_dispatch_info().weight;
+		let result = dispatch(remark);
+		assert!(result.dispatch_result);
+		assert_eq!(result.unspent_weight, declared_weight - remark_weight);
+
+		// refused calls don't use any weight
+		assert_ok!(crate::BridgeConfig::set_lane_call_filter(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			2,
+		));
+		let result = dispatch(crate::Call::System(frame_system::Call::remark { remark: vec![] }));
+		assert!(!result.dispatch_result);
+		assert_eq!(result.unspent_weight, declared_weight);
+	});
+}
+
+#[test]
+fn expired_messages_are_refused() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let dispatch = |expires_at| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: 1_000_000_000,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at,
+						call: FromWococoEncodedCall::new(remark.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+
+		// Wococo headers are not imported, so the best finalized Wococo block is 0
+		assert!(dispatch(None));
+		assert!(dispatch(Some(1)));
+		assert!(!dispatch(Some(0)));
+
+		// outbound messages expire once Rococo reaches their `expires_at` block
+		frame_system::Pallet::<Runtime>::set_block_number(10);
+		let payload = |expires_at| ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 1_000,
+			origin: CallOrigin::SourceRoot,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at,
+			call: vec![42],
+		};
+		assert!(!ExpireMessagesAtThisChain::is_expired(&payload(None)));
+		assert!(!ExpireMessagesAtThisChain::is_expired(&payload(Some(11))));
+		assert!(ExpireMessagesAtThisChain::is_expired(&payload(Some(10))));
+	});
+}
+
+#[test]
+fn messages_encoded_without_expiration_never_expire() {
+	new_test_ext().execute_with(|| {
+		// payloads have been encoded without the marker, the version and `expires_at` before
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let legacy_payload = |call: Vec<u8>| {
+			(
+				crate::VERSION.spec_version,
+				1_000_000_000 as Weight,
+				CallOrigin::<crate::AccountId, (), ()>::SourceRoot,
+				DispatchFeePayment::AtSourceChain,
+				call,
+			)
+				.encode()
+		};
+
+		// inbound messages are dispatched even if the bridged chain is far ahead
+		let relayer = crate::AccountId::from([3; 32]);
+		let payload =
+			FromWococoMessagePayload::decode(&mut &legacy_payload(remark.encode())[..]).unwrap();
+		assert_eq!(payload.expires_at, None);
+		let message = DispatchMessage {
+			key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+			data: DispatchMessageData { payload: Ok(payload), fee: 0 },
+		};
+		assert!(FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result);
+
+		// outbound messages, stored before the upgrade, are never expired
+		frame_system::Pallet::<Runtime>::set_block_number(u32::MAX);
+		let payload = ToWococoMessagePayload::decode(&mut &legacy_payload(vec![42])[..]).unwrap();
+		assert_eq!(payload.expires_at, None);
+		assert!(!ExpireMessagesAtThisChain::is_expired(&payload));
+	});
+}
+
+#[test]
+fn messages_with_compatible_spec_versions_are_dispatched() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let dispatch = |spec_version| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version,
+						weight: 1_000_000_000,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(remark.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message).dispatch_result
+		};
+		let set_oldest_compatible_spec_version = |spec_version| {
+			crate::BridgeConfig::set_oldest_compatible_spec_version(
+				crate::Origin::root(),
+				spec_version,
+			)
+		};
+		let current = crate::VERSION.spec_version;
+		let window = crate::MaxSpecVersionWindow::get();
+
+		assert!(dispatch(current));
+		assert!(!dispatch(current - 10));
+
+		assert_ok!(set_oldest_compatible_spec_version(Some(current - 10)));
+		assert!(dispatch(current - 10));
+		assert!(!dispatch(current - 11));
+		assert!(!dispatch(current + 1));
+
+		assert_noop!(
+			set_oldest_compatible_spec_version(Some(current - window - 1)),
+			crate::bridge_config::Error::<Runtime>::SpecVersionOutsideWindow,
+		);
+		assert_noop!(
+			set_oldest_compatible_spec_version(Some(current + 1)),
+			crate::bridge_config::Error::<Runtime>::SpecVersionOutsideWindow,
+		);
+		assert_noop!(
+			crate::BridgeConfig::set_oldest_compatible_spec_version(
+				crate::Origin::signed(relayer.clone()),
+				None,
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+
+		assert_ok!(set_oldest_compatible_spec_version(None));
+		assert!(!dispatch(current - 10));
+	});
+}
+
+#[test]
+fn oversized_and_deeply_nested_calls_are_refused() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([3; 32]);
+		let declared_weight = 1_000_000_000;
+		let dispatch = |call: Vec<u8>| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: declared_weight,
+						origin: CallOrigin::SourceRoot,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(call),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message)
+		};
+
+		let remark = |len| crate::Call::System(frame_system::Call::remark { remark: vec![0; len] });
+		let nested_batch = |depth| {
+			(0..depth).fold(remark(0), |call, _| {
+				crate::Call::Utility(pallet_utility::Call::batch { calls: vec![call] })
+			})
+		};
+		let oversized_call = remark(MAX_INBOUND_CALL_SIZE).encode();
+		let deep_call = nested_batch(MAX_INBOUND_CALL_DEPTH).encode();
+
+		assert_eq!(decode_inbound_call(&remark(0).encode()), Ok(remark(0)));
+		assert_eq!(decode_inbound_call(&nested_batch(1).encode()), Ok(nested_batch(1)));
+		assert_eq!(decode_inbound_call(&oversized_call), Err(InboundCallError::TooLarge));
+		assert_eq!(decode_inbound_call(&deep_call), Err(InboundCallError::TooDeep));
+		assert_eq!(decode_inbound_call(&[0xFF]), Err(InboundCallError::Undecodable));
+
+		for call in [oversized_call, deep_call] {
+			let result = dispatch(call);
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, declared_weight);
+		}
+	});
+}
+
+#[test]
+fn inbound_message_payloads_are_checked() {
+	new_test_ext().execute_with(|| {
+		let remark = crate::Call::System(frame_system::Call::remark { remark: vec![] });
+		let encoded_remark = remark.encode();
+		let remark_weight = remark.get_dispatch_info().weight;
+		let spec_version = crate::VERSION.spec_version;
+		let payload = |spec_version, weight, call: &[u8]| {
+			FromWococoMessagePayload {
+				spec_version,
+				weight,
+				origin: CallOrigin::SourceRoot,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: FromWococoEncodedCall::new(call.to_vec()),
+			}
+			.encode()
+		};
+		let check = |lane_id, payload: Vec<u8>| {
+			check_inbound_message_payload::<AtRococoWithWococoMessageBridge>(&lane_id, &payload)
+		};
+		let lane = [0, 0, 0, 0];
+
+		assert_eq!(
+			check(lane, payload(spec_version, remark_weight, &encoded_remark)),
+			MessagePayloadVerdict::Accepted,
+		);
+		assert_eq!(check(lane, vec![0xFF]), MessagePayloadVerdict::UndecodablePayload);
+		assert_eq!(
+			check([0, 0, 0, 1], payload(spec_version, remark_weight, &encoded_remark)),
+			MessagePayloadVerdict::LaneNotOpen,
+		);
+		assert_eq!(
+			check(lane, payload(spec_version + 1, remark_weight, &encoded_remark)),
+			MessagePayloadVerdict::IncompatibleSpecVersion,
+		);
+		assert_eq!(
+			check(lane, payload(spec_version, remark_weight, &[0xFF])),
+			MessagePayloadVerdict::UndecodableCall,
+		);
+		assert_eq!(
+			check(lane, payload(spec_version, remark_weight - 1, &encoded_remark)),
+			MessagePayloadVerdict::InsufficientDispatchWeight,
+		);
+
+		assert_ok!(crate::BridgeConfig::set_call_weight_limit(
+			crate::Origin::root(),
+			encoded_remark[0],
+			encoded_remark[1],
+			Some(remark_weight),
+		));
+		assert_eq!(
+			check(lane, payload(spec_version, remark_weight + 1, &encoded_remark)),
+			MessagePayloadVerdict::DispatchWeightLimitExceeded,
+		);
+
+		assert_ok!(crate::BridgeConfig::set_source_root_call_filter(crate::Origin::root(), None));
+		assert_eq!(
+			check(lane, payload(spec_version, remark_weight, &encoded_remark)),
+			MessagePayloadVerdict::CallNotAllowed,
+		);
+	});
+}
+
+#[test]
+fn inbound_message_dispatch_is_dry_run() {
+	new_test_ext().execute_with(|| {
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+
+		let relayer = crate::AccountId::from([3; 32]);
+		let remark =
+			crate::Call::System(frame_system::Call::remark_with_event { remark: vec![42] });
+		let declared_weight = remark.get_dispatch_info().weight * 2;
+		let payload = |spec_version| {
+			FromWococoMessagePayload {
+				spec_version,
+				weight: declared_weight,
+				origin: CallOrigin::SourceRoot,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: FromWococoEncodedCall::new(remark.encode()),
+			}
+			.encode()
+		};
+		let dry_run = |payload: Vec<u8>| {
+			dry_run_inbound_message_dispatch::<
+				AtRococoWithWococoMessageBridge,
+				FromWococoMessageDispatch,
+			>(&relayer, [0, 0, 0, 0], 1, &payload)
+		};
+
+		let result = dry_run(payload(crate::VERSION.spec_version));
+		assert_eq!(result.verdict, MessagePayloadVerdict::Accepted);
+		assert!(result.dispatch_result);
+		assert_eq!(result.declared_weight, declared_weight);
+		assert!(result.actual_weight > 0 && result.actual_weight <= declared_weight);
+		assert!(!result.dispatch_fee_paid_during_dispatch);
+		// nothing is committed, including events of the dispatched call
+		assert!(frame_system::Pallet::<Runtime>::events().is_empty());
+
+		let result = dry_run(payload(crate::VERSION.spec_version + 1));
+		assert_eq!(result.verdict, MessagePayloadVerdict::IncompatibleSpecVersion);
+		assert!(!result.dispatch_result);
+		assert_eq!(result.actual_weight, 0);
+		assert!(frame_system::Pallet::<Runtime>::events().is_empty());
+	});
+}
+
+#[test]
+fn calls_of_lanes_with_dispatch_delay_are_scheduled() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::OnInitialize;
+
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer = crate::AccountId::from([3; 32]);
+		let amount = 10 * rococo_runtime_constants::currency::UNITS;
+		let declared_weight = 1_000_000_000;
+		let transfer_to_bob = crate::Call::Balances(pallet_balances::Call::transfer {
+			dest: bob.clone().into(),
+			value: amount,
+		});
+		let dispatch = |dispatch_fee_payment| {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: declared_weight,
+						origin: CallOrigin::SourceAccount(alice.clone()),
+						dispatch_fee_payment,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(transfer_to_bob.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message)
+		};
+		let run_to_block = |number| {
+			while frame_system::Pallet::<Runtime>::block_number() < number {
+				let next = frame_system::Pallet::<Runtime>::block_number() + 1;
+				frame_system::Pallet::<Runtime>::set_block_number(next);
+				crate::Scheduler::on_initialize(next);
+			}
+		};
+
+		let alice_account =
+			crate::BridgeWococoMessagesDispatch::bridged_account_id(WOCOCO_CHAIN_ID, alice.clone());
+		Balances::make_free_balance_be(&alice_account, 3 * amount);
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		assert_ok!(crate::BridgeConfig::set_lane_dispatch_delay(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			Some(5),
+		));
+
+		// the call is scheduled, so most of the declared weight is unspent
+		let result = dispatch(DispatchFeePayment::AtSourceChain);
+		assert!(result.dispatch_result);
+		assert!(result.unspent_weight > 0 && result.unspent_weight < declared_weight);
+		assert_eq!(Balances::free_balance(&bob), 0);
+
+		// the dispatch fee can't be paid at this chain when the call is dispatched later
+		let result = dispatch(DispatchFeePayment::AtTargetChain);
+		assert!(!result.dispatch_result);
+		assert_eq!(result.unspent_weight, declared_weight);
+
+		// the call is dispatched by the scheduler with the origin of the message
+		run_to_block(5);
+		assert_eq!(Balances::free_balance(&bob), 0);
+		run_to_block(7);
+		assert_eq!(Balances::free_balance(&bob), amount);
+		assert_eq!(Balances::free_balance(&alice_account), 2 * amount);
+
+		// without the delay, calls are dispatched in the delivery transaction
+		assert_ok!(crate::BridgeConfig::set_lane_dispatch_delay(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			None,
+		));
+		assert!(dispatch(DispatchFeePayment::AtSourceChain).dispatch_result);
+		assert_eq!(Balances::free_balance(&bob), 2 * amount);
+	});
+}
+
+#[test]
+fn scheduled_dispatch_is_postponed_while_the_agenda_is_full() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::OnInitialize;
+
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer = crate::AccountId::from([3; 32]);
+		let amount = 10 * rococo_runtime_constants::currency::UNITS;
+		let declared_weight = 1_000_000_000;
+		let transfer_to_bob = crate::Call::Balances(pallet_balances::Call::transfer {
+			dest: bob.clone().into(),
+			value: amount,
+		});
+		let dispatch = || {
+			let message = DispatchMessage {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+				data: DispatchMessageData {
+					payload: Ok(FromWococoMessagePayload {
+						spec_version: crate::VERSION.spec_version,
+						weight: declared_weight,
+						origin: CallOrigin::SourceAccount(alice.clone()),
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						expires_at: None,
+						call: FromWococoEncodedCall::new(transfer_to_bob.encode()),
+					}),
+					fee: 0,
+				},
+			};
+			FromWococoMessageDispatch::dispatch(&relayer, message)
+		};
+		let run_to_block = |number| {
+			while frame_system::Pallet::<Runtime>::block_number() < number {
+				let next = frame_system::Pallet::<Runtime>::block_number() + 1;
+				frame_system::Pallet::<Runtime>::set_block_number(next);
+				crate::Scheduler::on_initialize(next);
+			}
+		};
+		let fill_agenda = |block| {
+			pallet_scheduler::Agenda::<Runtime>::insert(
+				block,
+				vec![None; crate::MaxScheduledPerBlock::get() as usize],
+			);
+		};
+
+		let alice_account =
+			crate::BridgeWococoMessagesDispatch::bridged_account_id(WOCOCO_CHAIN_ID, alice.clone());
+		Balances::make_free_balance_be(&alice_account, 3 * amount);
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		assert_ok!(crate::BridgeConfig::set_lane_dispatch_delay(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			Some(5),
+		));
+
+		// the message is refused if the agenda is full at all blocks it may be scheduled at
+		for block in 7..=7 + MAX_DISPATCH_POSTPONEMENT {
+			fill_agenda(block);
+		}
+		let result = dispatch();
+		assert!(!result.dispatch_result);
+		assert!(result.unspent_weight < declared_weight);
+
+		// otherwise it is scheduled at the first block with a free slot
+		pallet_scheduler::Agenda::<Runtime>::remove(9);
+		let result = dispatch();
+		assert!(result.dispatch_result);
+		assert_eq!(pallet_scheduler::Agenda::<Runtime>::decode_len(9), Some(1));
+		assert!(pallet_scheduler::Agenda::<Runtime>::get(7).iter().all(Option::is_none));
+		assert!(pallet_scheduler::Agenda::<Runtime>::get(8).iter().all(Option::is_none));
+
+		run_to_block(8);
+		assert_eq!(Balances::free_balance(&bob), 0);
+		run_to_block(9);
+		assert_eq!(Balances::free_balance(&bob), amount);
+		assert_eq!(Balances::free_balance(&alice_account), 2 * amount);
+	});
+}
+
+fn xcm_message(
+	origin: CallOrigin<crate::AccountId, primitives::v2::AccountPublic, crate::Signature>,
+	weight: Weight,
+	xcm: Xcm<crate::Call>,
+) -> DispatchMessage<FromWococoMessagePayload, Balance> {
+	DispatchMessage {
+		key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 0 },
+		data: DispatchMessageData {
+			payload: Ok(FromWococoMessagePayload {
+				spec_version: 0,
+				weight,
+				origin,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: FromWococoEncodedCall::new(VersionedXcm::from(xcm).encode()),
+			}),
+			fee: 0,
+		},
+	}
+}
+
+#[test]
+fn xcm_messages_are_executed_with_bridged_origin() {
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer = crate::AccountId::from([3; 32]);
+		let amount = 10 * rococo_runtime_constants::currency::UNITS;
+		let weight = 3 * crate::xcm_config::BaseXcmWeight::get();
+		let transfer_to_bob = Xcm(vec![
+			WithdrawAsset((Here, amount).into()),
+			BuyExecution { fees: (Here, amount).into(), weight_limit: Limited(weight) },
+			DepositAsset {
+				assets: Wild(All),
+				max_assets: 1,
+				beneficiary: Junction::AccountId32 {
+					network: NetworkId::Any,
+					id: bob.clone().into(),
+				}
+				.into(),
+			},
+		]);
+
+		let alice_location =
+			bridged_origin(WOCOCO_CHAIN_ID, &CallOrigin::SourceAccount(alice.clone())).unwrap();
+		let alice_sovereign = BridgedLocationConvertsVia::convert_ref(&alice_location).unwrap();
+		assert_ne!(
+			BridgedLocationConvertsVia::convert_ref(bridged_chain_location(WOCOCO_CHAIN_ID)),
+			Ok(alice_sovereign.clone()),
+		);
+		assert_eq!(BridgedLocationConvertsVia::convert_ref(MultiLocation::parent()), Err(()));
+		Balances::make_free_balance_be(&alice_sovereign, 2 * amount);
+
+		// in call dispatch mode the payload isn't executed as XCM message
+		let result = FromWococoMessageDispatch::dispatch(
+			&relayer,
+			xcm_message(CallOrigin::SourceAccount(alice.clone()), weight, transfer_to_bob.clone()),
+		);
+		assert!(!result.dispatch_result);
+		assert_eq!(Balances::free_balance(&bob), 0);
+
+		assert_ok!(crate::BridgeConfig::set_lane_dispatch_mode(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			DispatchMode::Xcm,
+		));
+
+		// the XCM barrier rejects unpaid execution
+		let result = FromWococoMessageDispatch::dispatch(
+			&relayer,
+			xcm_message(CallOrigin::SourceRoot, weight, Xcm(vec![ClearOrigin])),
+		);
+		assert!(!result.dispatch_result);
+		assert_eq!(result.unspent_weight, weight);
+
+		let result = FromWococoMessageDispatch::dispatch(
+			&relayer,
+			xcm_message(CallOrigin::SourceAccount(alice.clone()), weight, transfer_to_bob),
+		);
+		assert!(result.dispatch_result);
+		assert_eq!(result.unspent_weight, 0);
+		assert_eq!(Balances::free_balance(&alice_sovereign), amount);
+		assert!(Balances::free_balance(&bob) > 0);
+	});
+}
+
+#[test]
+fn xcm_messages_are_exported_over_the_bridge() {
+	type Router = crate::xcm_config::XcmRouter;
+
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let root_account = crate::AccountId::from([2; 32]);
+		let initial_balance = 1_000 * rococo_runtime_constants::currency::UNITS;
+		let lane = [0, 0, 0, 1];
+		let sender = X1(AccountId32 { network: NetworkId::Any, id: alice.clone().into() });
+		let message = Xcm::<()>(vec![DescendOrigin(sender), ClearOrigin]);
+		let wococo = bridged_chain_location(WOCOCO_CHAIN_ID);
+
+		// messages to unknown chains are not exported
+		assert!(matches!(
+			Router::send_xcm(bridged_chain_location(*b"test"), message.clone()),
+			Err(SendError::CannotReachDestination(..)),
+		));
+
+		// messages are not exported until governance selects the lane of the network
+		assert_eq!(Router::send_xcm(wococo.clone(), message.clone()), Err(SendError::Unroutable));
+		assert_noop!(
+			crate::BridgeConfig::set_xcm_export_lane(
+				crate::Origin::root(),
+				bridged_network(WOCOCO_CHAIN_ID),
+				Some(lane),
+			),
+			crate::bridge_config::Error::<Runtime>::LaneNotRegistered,
+		);
+		assert_ok!(crate::BridgeConfig::register_lane(
+			crate::Origin::root(),
+			lane,
+			CALL_FILTER_ALL,
+		));
+		assert_ok!(crate::BridgeConfig::set_xcm_export_lane(
+			crate::Origin::root(),
+			bridged_network(WOCOCO_CHAIN_ID),
+			Some(lane),
+		));
+
+		// messages without the sender are not exported
+		assert_eq!(
+			Router::send_xcm(wococo.clone(), Xcm(vec![ClearOrigin])),
+			Err(SendError::Transport("Unknown message sender")),
+		);
+
+		// messages are sent by the account of the sender, so it must be allowed to send them
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Root,
+			Some(root_account.clone()),
+		));
+		Balances::make_free_balance_be(&alice, initial_balance);
+		Balances::make_free_balance_be(&root_account, initial_balance);
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane,
+			root_account.clone(),
+		));
+		assert_eq!(
+			Router::send_xcm(wococo.clone(), message.clone()),
+			Err(SendError::Transport("Failed to send bridge message")),
+		);
+
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane,
+			alice.clone(),
+		));
+		assert_ok!(Router::send_xcm(wococo, message));
+
+		// the message is executed by the sender at the bridged chain and it pays the fee
+		let sent = OutboundMessages::<Runtime, crate::AtRococoWithWococoMessagesInstance>::get(
+			MessageKey { lane_id: lane, nonce: 1 },
+		)
+		.unwrap();
+		let payload = ToWococoMessagePayload::decode(&mut &sent.payload[..]).unwrap();
+		assert_eq!(payload.origin, CallOrigin::SourceAccount(alice.clone()));
+		assert_eq!(
+			VersionedXcm::<crate::Call>::decode(&mut &payload.call[..]),
+			Ok(VersionedXcm::from(Xcm::<crate::Call>(vec![ClearOrigin]))),
+		);
+		assert!(sent.fee > 0);
+		assert_eq!(Balances::free_balance(&alice), initial_balance - sent.fee);
+		assert_eq!(Balances::free_balance(&root_account), initial_balance);
+	});
+}
+
+#[test]
+fn xcm_messages_are_rerouted_to_fresh_lane() {
+	type Router = crate::xcm_config::XcmRouter;
+	type Instance = crate::AtRococoWithWococoMessagesInstance;
+
+	new_test_ext().execute_with(|| {
+		let alice = crate::AccountId::from([1; 32]);
+		let old_lane = [0, 0, 0, 1];
+		let new_lane = [0, 0, 0, 2];
+		let network = bridged_network(WOCOCO_CHAIN_ID);
+		let sender = X1(AccountId32 { network: NetworkId::Any, id: alice.clone().into() });
+		let message = Xcm::<()>(vec![DescendOrigin(sender), ClearOrigin]);
+		let wococo = bridged_chain_location(WOCOCO_CHAIN_ID);
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		Balances::make_free_balance_be(&alice, 1_000 * rococo_runtime_constants::currency::UNITS);
+		for lane in [old_lane, new_lane] {
+			assert_ok!(crate::BridgeConfig::register_lane(
+				crate::Origin::root(),
+				lane,
+				CALL_FILTER_ALL,
+			));
+			assert_ok!(crate::BridgeConfig::add_message_sender(
+				crate::Origin::root(),
+				lane,
+				alice.clone(),
+			));
+		}
+
+		// only governance may change the lane of the network
+		assert_noop!(
+			crate::BridgeConfig::set_xcm_export_lane(
+				crate::Origin::signed(alice.clone()),
+				network.clone(),
+				Some(old_lane),
+			),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(crate::BridgeConfig::set_xcm_export_lane(
+			crate::Origin::root(),
+			network.clone(),
+			Some(old_lane),
+		));
+		assert_ok!(Router::send_xcm(wococo.clone(), message.clone()));
+
+		assert_ok!(crate::BridgeConfig::set_xcm_export_lane(
+			crate::Origin::root(),
+			network.clone(),
+			Some(new_lane),
+		));
+		assert!(frame_system::Pallet::<Runtime>::events().iter().any(|record| record.event ==
+			crate::Event::BridgeConfig(crate::bridge_config::Event::XcmExportLaneChanged(
+				network.clone(),
+				Some(new_lane),
+			))));
+		assert_ok!(Router::send_xcm(wococo.clone(), message.clone()));
+
+		for lane_id in [old_lane, new_lane] {
+			assert!(OutboundMessages::<Runtime, Instance>::contains_key(MessageKey {
+				lane_id,
+				nonce: 1
+			}));
+			assert!(!OutboundMessages::<Runtime, Instance>::contains_key(MessageKey {
+				lane_id,
+				nonce: 2
+			}));
+		}
+
+		// messages are not exported after the lane is removed
+		assert_ok!(crate::BridgeConfig::set_xcm_export_lane(crate::Origin::root(), network, None));
+		assert_eq!(Router::send_xcm(wococo, message), Err(SendError::Unroutable));
+	});
+}
+
+#[test]
+fn outbound_message_details_only_include_undelivered_messages() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+
+		let lane_id = [0, 0, 0, 0];
+		let payload = ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 1_000,
+			origin: CallOrigin::SourceRoot,
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			expires_at: None,
+			call: vec![42; 10],
+		};
+		for nonce in 1..=3 {
+			OutboundMessages::<Runtime, Instance>::insert(
+				MessageKey { lane_id, nonce },
+				MessageData { payload: payload.encode(), fee: nonce as Balance },
+			);
+		}
+		OutboundLanes::<Runtime, Instance>::insert(
+			lane_id,
+			OutboundLaneData {
+				oldest_unpruned_nonce: 1,
+				latest_received_nonce: 1,
+				latest_generated_nonce: 3,
+			},
+		);
+
+		let details = bridge_runtime_common::messages_api::outbound_message_details::<
+			Runtime,
+			Instance,
+			AtRococoWithWococoMessageBridge,
+		>(lane_id, 0, MessageNonce::MAX);
+		assert_eq!(
+			details,
+			(2..=3)
+				.map(|nonce| MessageDetails {
+					nonce,
+					dispatch_weight: 1_000,
+					size: payload.encode().len() as _,
+					delivery_and_dispatch_fee: nonce as Balance,
+					dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				})
+				.collect::<Vec<_>>(),
+		);
+	});
+}
+
+#[test]
+fn relayer_fund_covers_unpaid_fees_check_works() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+
+		let lane_id = [0, 0, 0, 0];
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		OutboundMessages::<Runtime, Instance>::insert(
+			MessageKey { lane_id, nonce: 1 },
+			MessageData { payload: vec![], fee: 100 },
+		);
+		OutboundLanes::<Runtime, Instance>::insert(
+			lane_id,
+			OutboundLaneData {
+				oldest_unpruned_nonce: 1,
+				latest_received_nonce: 0,
+				latest_generated_nonce: 1,
+			},
+		);
+		assert!(RelayerFundCoversUnpaidFees::check(1).is_err());
+
+		Balances::make_free_balance_be(&relayer_fund, 100);
+		assert_eq!(RelayerFundCoversUnpaidFees::check(1), Ok(()));
+
+		// fees of confirmed messages have already been paid to relayers
+		Balances::make_free_balance_be(&relayer_fund, 1);
+		OutboundLanes::<Runtime, Instance>::mutate(lane_id, |lane| lane.latest_received_nonce = 1);
+		assert_eq!(RelayerFundCoversUnpaidFees::check(1), Ok(()));
+
+		// unless they haven't been claimed yet
+		crate::bridge_relayers::RelayerRewards::<Runtime>::insert(
+			crate::AccountId::from([1; 32]),
+			lane_id,
+			100,
+		);
+		assert!(RelayerFundCoversUnpaidFees::check(1).is_err());
+
+		Balances::make_free_balance_be(&relayer_fund, 100);
+		assert_eq!(RelayerFundCoversUnpaidFees::check(1), Ok(()));
+	});
+}
+
+#[test]
+fn message_lanes_are_consistent_check_works() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtWococoWithRococoMessagesInstance;
+
+		let lane_id = [0, 0, 0, 0];
+		let outbound_lane = |oldest_unpruned_nonce, latest_received_nonce| {
+			OutboundLanes::<Runtime, Instance>::insert(
+				lane_id,
+				OutboundLaneData {
+					oldest_unpruned_nonce,
+					latest_received_nonce,
+					latest_generated_nonce: 10,
+				},
+			)
+		};
+		let inbound_lane = |ranges: &[(MessageNonce, MessageNonce)]| {
+			let relayers = ranges
+				.iter()
+				.map(|(begin, end)| {
+					let mut messages = DeliveredMessages::new(*begin, true);
+					(*begin..*end).for_each(|_| messages.note_dispatched_message(true));
+					UnrewardedRelayer { relayer: crate::AccountId::from([1; 32]), messages }
+				})
+				.collect();
+			pallet_bridge_messages::InboundLanes::<Runtime, Instance>::insert(
+				lane_id,
+				InboundLaneData { relayers, last_confirmed_nonce: 5 },
+			)
+		};
+
+		outbound_lane(6, 5);
+		inbound_lane(&[(6, 7), (8, 8), (9, 12)]);
+		assert_eq!(MessageLanesAreConsistent::check(1), Ok(()));
+
+		// messages can't be received before they're sent
+		outbound_lane(6, 11);
+		assert!(MessageLanesAreConsistent::check(1).is_err());
+
+		// and can't be pruned before they're received
+		outbound_lane(7, 5);
+		assert!(MessageLanesAreConsistent::check(1).is_err());
+
+		// unrewarded relayer entries can't overlap, have gaps or cover confirmed messages
+		outbound_lane(6, 5);
+		inbound_lane(&[(6, 8), (8, 8)]);
+		assert!(MessageLanesAreConsistent::check(1).is_err());
+		inbound_lane(&[(6, 7), (9, 9)]);
+		assert!(MessageLanesAreConsistent::check(1).is_err());
+		inbound_lane(&[(4, 5), (6, 6)]);
+		assert!(MessageLanesAreConsistent::check(1).is_err());
+	});
+}
+
+#[test]
+fn relayer_rewards_are_accumulated_and_claimed() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+		>;
+
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		let confirmation_fee = GetDeliveryConfirmationTransactionFee::get();
+		for nonce in 1..=3 {
+			OutboundMessages::<Runtime, Instance>::insert(
+				MessageKey { lane_id, nonce },
+				MessageData { payload: vec![], fee: 3 * confirmation_fee },
+			);
+		}
+
+		// alice has delivered two messages and bob has delivered and confirmed one message
+		let delivered =
+			|begin, end| DeliveredMessages { begin, end, dispatch_results: Default::default() };
+		Payments::pay_relayers_rewards(
+			lane_id,
+			vec![
+				UnrewardedRelayer { relayer: alice.clone(), messages: delivered(1, 2) },
+				UnrewardedRelayer { relayer: bob.clone(), messages: delivered(3, 3) },
+			]
+			.into(),
+			&bob,
+			&(1..=3),
+			&relayer_fund,
+		);
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&alice, lane_id), 4 * confirmation_fee);
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&bob, lane_id), 5 * confirmation_fee);
+		assert_eq!(crate::BridgeRelayers::unclaimed_rewards(), 9 * confirmation_fee);
+
+		// the relayer fund account must stay alive
+		Balances::make_free_balance_be(&relayer_fund, 5 * confirmation_fee);
+		assert_noop!(
+			crate::BridgeRelayers::claim_rewards(crate::Origin::signed(bob.clone()), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::FailedToPayReward,
+		);
+
+		Balances::make_free_balance_be(
+			&relayer_fund,
+			crate::ExistentialDeposit::get() + 9 * confirmation_fee,
+		);
+		Balances::make_free_balance_be(&bob, crate::ExistentialDeposit::get());
+		assert_ok!(crate::BridgeRelayers::claim_rewards(
+			crate::Origin::signed(bob.clone()),
+			lane_id,
+		));
+		assert_eq!(
+			Balances::free_balance(&bob),
+			crate::ExistentialDeposit::get() + 5 * confirmation_fee,
+		);
+		assert_eq!(crate::BridgeRelayers::unclaimed_rewards(), 4 * confirmation_fee);
+		assert_noop!(
+			crate::BridgeRelayers::claim_rewards(crate::Origin::signed(bob), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::NoRewardForRelayer,
+		);
+	});
+}
+
+#[test]
+fn fees_of_undispatched_messages_are_partially_refunded() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+		>;
+
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		for nonce in 1..=2 {
+			let key = MessageKey { lane_id, nonce };
+			OutboundMessages::<Runtime, Instance>::insert(
+				&key,
+				MessageData { payload: vec![], fee: 1_000 },
+			);
+			OutboundMessageSubmitters::<Runtime, Instance>::insert(&key, alice.clone());
+		}
+
+		// the first message has been dispatched and the second one has been refused
+		let mut messages = DeliveredMessages::new(1, true);
+		messages.note_dispatched_message(false);
+		Payments::pay_relayers_rewards(
+			lane_id,
+			vec![UnrewardedRelayer { relayer: bob.clone(), messages }].into(),
+			&bob,
+			&(1..=2),
+			&relayer_fund,
+		);
+
+		let refund = crate::UndispatchedMessageRefund::get() * 1_000;
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&alice, lane_id), refund);
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&bob, lane_id), 2_000 - refund);
+	});
+}
+
+#[test]
+fn outbound_lane_stats_are_updated_by_delivery_confirmations() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+		>;
+
+		let lane_id = [0, 0, 0, 0];
+		let bob = crate::AccountId::from([2; 32]);
+		let charlie = crate::AccountId::from([3; 32]);
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		for nonce in 1..=3 {
+			OutboundMessages::<Runtime, Instance>::insert(
+				MessageKey { lane_id, nonce },
+				MessageData { payload: vec![], fee: 1_000 },
+			);
+		}
+		pallet_bridge_messages::OutboundLanes::<Runtime, Instance>::mutate(lane_id, |data| {
+			data.latest_generated_nonce = 3
+		});
+
+		// the message of bob has been dispatched and the message of charlie has been refused
+		Payments::pay_relayers_rewards(
+			lane_id,
+			vec![
+				UnrewardedRelayer {
+					relayer: bob.clone(),
+					messages: DeliveredMessages::new(1, true),
+				},
+				UnrewardedRelayer {
+					relayer: charlie.clone(),
+					messages: DeliveredMessages::new(2, false),
+				},
+			]
+			.into(),
+			&bob,
+			&(1..=2),
+			&relayer_fund,
+		);
+
+		assert_eq!(
+			crate::BridgeRelayers::outbound_lane_stats::<Instance>(lane_id),
+			OutboundLaneStats {
+				messages_sent: 3,
+				messages_delivered: 2,
+				failed_dispatches: 1,
+				fees_collected: 2_000,
+				last_relayer: Some(charlie),
+			},
+		);
+	});
+}
+
+#[test]
+fn relayers_are_rewarded_with_subsidies_for_messages_of_subsidized_lanes() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+			SubsidizedMessageFee<AtRococoWithWococoMessageBridge>,
+		>;
+
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer_fund = crate::RelayerFundAccount::get();
+		let subsidy_account = crate::RelayerSubsidyAccount::get();
+		let payload = ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 1_000,
+			origin: CallOrigin::SourceAccount(alice.clone()),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			expires_at: None,
+			call: vec![42; 10],
+		};
+		let subsidy = messages::source::minimal_message_fee::<AtRococoWithWococoMessageBridge>(
+			&lane_id, &payload, None,
+		)
+		.unwrap();
+		let verify_message = || {
+			ToWococoMessageVerifier::verify_message(
+				&crate::Origin::signed(alice.clone()),
+				&0,
+				&lane_id,
+				&OutboundLaneData::default(),
+				&payload,
+			)
+		};
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane_id,
+			alice.clone()
+		));
+		assert_eq!(verify_message(), Err(messages::source::TOO_LOW_FEE));
+
+		assert_noop!(
+			crate::BridgeConfig::set_lane_fee_payment(
+				crate::Origin::signed(alice.clone()),
+				lane_id,
+				LaneFeePayment::Subsidized,
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(crate::BridgeConfig::set_lane_fee_payment(
+			crate::Origin::root(),
+			lane_id,
+			LaneFeePayment::Subsidized,
+		));
+		assert!(crate::BridgeConfig::is_lane_subsidized(&lane_id));
+		assert_ok!(verify_message());
+
+		for nonce in 1..=2 {
+			OutboundMessages::<Runtime, Instance>::insert(
+				MessageKey { lane_id, nonce },
+				MessageData { payload: payload.encode(), fee: 0 },
+			);
+		}
+		let pay_rewards = |nonce| {
+			Payments::pay_relayers_rewards(
+				lane_id,
+				vec![UnrewardedRelayer {
+					relayer: bob.clone(),
+					messages: DeliveredMessages::new(nonce, true),
+				}]
+				.into(),
+				&bob,
+				&(nonce..=nonce),
+				&relayer_fund,
+			);
+		};
+
+		// the subsidy account can't afford the subsidy
+		Balances::make_free_balance_be(&relayer_fund, crate::ExistentialDeposit::get());
+		Balances::make_free_balance_be(&subsidy_account, crate::ExistentialDeposit::get());
+		pay_rewards(1);
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&bob, lane_id), 0);
+
+		Balances::make_free_balance_be(
+			&subsidy_account,
+			crate::ExistentialDeposit::get() + subsidy,
+		);
+		pay_rewards(2);
+		assert_eq!(crate::BridgeRelayers::relayer_reward(&bob, lane_id), subsidy);
+		assert_eq!(Balances::free_balance(&subsidy_account), crate::ExistentialDeposit::get());
+		assert_eq!(
+			Balances::free_balance(&relayer_fund),
+			crate::ExistentialDeposit::get() + subsidy,
+		);
+	});
+}
+
+#[test]
+fn message_fees_are_paid_in_the_fee_asset() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+		>;
+
+		let alice = crate::AccountId::from([1; 32]);
+		let asset_id = crate::BridgeFeeAssetId::get();
+		let relayer_fund = crate::RelayerFundAccount::get();
+		let exchange_account = crate::RelayerFeeExchangeAccount::get();
+		let existential_deposit = crate::ExistentialDeposit::get();
+		let fee: Balance = 1_000;
+		let pay_fee = || {
+			Payments::pay_delivery_and_dispatch_fee(
+				&crate::Origin::signed(alice.clone()),
+				&fee,
+				&relayer_fund,
+			)
+		};
+		assert_ok!(crate::Assets::force_create(
+			crate::Origin::root(),
+			asset_id,
+			alice.clone().into(),
+			true,
+			1,
+		));
+		assert_ok!(crate::Assets::mint(
+			crate::Origin::signed(alice.clone()),
+			asset_id,
+			alice.clone().into(),
+			10 * fee,
+		));
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			crate::Origin::root(),
+			BridgeMessagesParameter::FeeAssetConversionRate(FixedU128::from(2)),
+		));
+		Balances::make_free_balance_be(&relayer_fund, existential_deposit);
+		Balances::make_free_balance_be(&alice, existential_deposit + fee);
+
+		assert_ok!(crate::BridgeRelayers::set_fee_currency(
+			crate::Origin::signed(alice.clone()),
+			crate::bridge_relayers::FeeCurrency::Asset,
+		));
+		// the fee exchange account can't pay the fee, so the asset transfer is reverted
+		assert!(pay_fee().is_err());
+		assert_eq!(crate::Assets::balance(asset_id, &alice), 10 * fee);
+
+		Balances::make_free_balance_be(&exchange_account, existential_deposit + fee);
+		assert_ok!(pay_fee());
+		assert_eq!(crate::Assets::balance(asset_id, &alice), 8 * fee);
+		assert_eq!(crate::Assets::balance(asset_id, &exchange_account), 2 * fee);
+		assert_eq!(Balances::free_balance(&exchange_account), existential_deposit);
+		assert_eq!(Balances::free_balance(&relayer_fund), existential_deposit + fee);
+		assert_eq!(Balances::free_balance(&alice), existential_deposit + fee);
+
+		assert_ok!(crate::BridgeRelayers::set_fee_currency(
+			crate::Origin::signed(alice.clone()),
+			crate::bridge_relayers::FeeCurrency::Native,
+		));
+		assert_ok!(pay_fee());
+		assert_eq!(crate::Assets::balance(asset_id, &alice), 8 * fee);
+		assert_eq!(Balances::free_balance(&alice), existential_deposit);
+		assert_eq!(Balances::free_balance(&relayer_fund), existential_deposit + 2 * fee);
+	});
+}
+
+#[test]
+fn relayers_register_and_deregister_with_bond() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bond = crate::RelayerBond::get();
+		let origin = || crate::Origin::signed(alice.clone());
+
+		Balances::make_free_balance_be(&alice, bond - 1);
+		assert_noop!(
+			crate::BridgeRelayers::register(origin(), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::FailedToReserveBond,
+		);
+
+		Balances::make_free_balance_be(&alice, crate::ExistentialDeposit::get() + bond);
+		assert_ok!(crate::BridgeRelayers::register(origin(), lane_id));
+		assert!(crate::BridgeRelayers::is_registered(&alice, lane_id));
+		assert_eq!(Balances::reserved_balance(&alice), bond);
+		assert_noop!(
+			crate::BridgeRelayers::register(origin(), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::AlreadyRegistered,
+		);
+
+		assert_ok!(crate::BridgeRelayers::deregister(origin(), lane_id));
+		assert!(!crate::BridgeRelayers::is_registered(&alice, lane_id));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_noop!(
+			crate::BridgeRelayers::deregister(origin(), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::NotRegistered,
+		);
+	});
+}
+
+#[test]
+fn lowest_relayer_bid_is_the_minimal_message_fee() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let bond = crate::RelayerBond::get();
+		let payload = ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 1_000,
+			origin: CallOrigin::SourceAccount(alice.clone()),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			expires_at: None,
+			call: vec![42; 10],
+		};
+		let estimated_fee =
+			messages::source::estimate_message_dispatch_and_delivery_fee::<
+				AtRococoWithWococoMessageBridge,
+			>(&payload, AtRococoWithWococoMessageBridge::RELAYER_FEE_PERCENT, None)
+			.unwrap();
+		let minimal_fee = || {
+			messages::source::minimal_message_fee::<AtRococoWithWococoMessageBridge>(
+				&lane_id, &payload, None,
+			)
+			.unwrap()
+		};
+		assert_eq!(minimal_fee(), estimated_fee);
+
+		assert_noop!(
+			crate::BridgeRelayers::submit_bid(crate::Origin::signed(alice.clone()), lane_id, 1),
+			crate::bridge_relayers::Error::<Runtime>::NotRegistered,
+		);
+		for relayer in [&alice, &bob] {
+			Balances::make_free_balance_be(relayer, crate::ExistentialDeposit::get() + bond);
+			assert_ok!(crate::BridgeRelayers::register(
+				crate::Origin::signed(relayer.clone()),
+				lane_id,
+			));
+		}
+
+		let cheap_fee = estimated_fee / 2;
+		assert_ok!(crate::BridgeRelayers::submit_bid(
+			crate::Origin::signed(alice.clone()),
+			lane_id,
+			estimated_fee * 2,
+		));
+		assert_ok!(crate::BridgeRelayers::submit_bid(
+			crate::Origin::signed(bob.clone()),
+			lane_id,
+			estimated_fee,
+		));
+		assert_eq!(minimal_fee(), estimated_fee);
+		assert_ok!(crate::BridgeRelayers::submit_bid(
+			crate::Origin::signed(alice.clone()),
+			lane_id,
+			cheap_fee,
+		));
+		assert_eq!(
+			crate::BridgeRelayers::relayer_bids(lane_id).into_inner(),
+			vec![(alice.clone(), cheap_fee), (bob.clone(), estimated_fee)],
+		);
+		assert_eq!(minimal_fee(), cheap_fee);
+		assert_eq!(
+			messages::source::minimal_message_fee::<AtRococoWithWococoMessageBridge>(
+				&[0, 0, 0, 1],
+				&payload,
+				None,
+			),
+			Ok(estimated_fee),
+		);
+
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane_id,
+			alice.clone()
+		));
+		assert_eq!(
+			ToWococoMessageVerifier::verify_message(
+				&crate::Origin::signed(alice.clone()),
+				&(cheap_fee - 1),
+				&lane_id,
+				&OutboundLaneData::default(),
+				&payload,
+			),
+			Err(messages::source::TOO_LOW_FEE),
+		);
+		assert_ok!(ToWococoMessageVerifier::verify_message(
+			&crate::Origin::signed(alice.clone()),
+			&cheap_fee,
+			&lane_id,
+			&OutboundLaneData::default(),
+			&payload,
+		));
+
+		assert_ok!(crate::BridgeRelayers::deregister(
+			crate::Origin::signed(alice.clone()),
+			lane_id,
+		));
+		assert_eq!(minimal_fee(), estimated_fee);
+		assert_ok!(crate::BridgeRelayers::cancel_bid(crate::Origin::signed(bob.clone()), lane_id));
+		assert_noop!(
+			crate::BridgeRelayers::cancel_bid(crate::Origin::signed(bob), lane_id),
+			crate::bridge_relayers::Error::<Runtime>::NoBid,
+		);
+		assert_eq!(crate::BridgeRelayers::lowest_bid(lane_id), None);
+	});
+}
+
+#[test]
+fn registered_relayers_are_prioritized_and_slashed_for_invalid_proofs() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let bond = crate::RelayerBond::get();
+		Balances::make_free_balance_be(&alice, crate::ExistentialDeposit::get() + bond);
+		assert_ok!(crate::BridgeRelayers::register(crate::Origin::signed(alice.clone()), lane_id));
+
+		let call = crate::Call::BridgeWococoMessages(
+			pallet_bridge_messages::Call::receive_messages_proof {
+				relayer_id_at_bridged_chain: alice.clone(),
+				proof: messages_target::FromBridgedChainMessagesProof {
+					bridged_header_hash: Default::default(),
+					storage_proof: vec![],
+					storage_proof_version: Default::default(),
+					lane: lane_id,
+					nonces_start: 1,
+					nonces_end: 1,
+				},
+				messages_count: 1,
+				dispatch_weight: 0,
+			},
+		);
+		let priority = |who: &crate::AccountId| {
+			PrioritizeRegisteredRelayers
+				.validate(who, &call, &Default::default(), 0)
+				.unwrap()
+				.priority
+		};
+		assert_eq!(priority(&alice), REGISTERED_RELAYER_PRIORITY_BOOST);
+		assert_eq!(priority(&bob), 0);
+
+		let dispatch = |who: &crate::AccountId, result: DispatchResult| {
+			let pre = PrioritizeRegisteredRelayers
+				.pre_dispatch(who, &call, &Default::default(), 0)
+				.unwrap();
+			assert_ok!(PrioritizeRegisteredRelayers::post_dispatch(
+				Some(pre),
+				&Default::default(),
+				&Default::default(),
+				0,
+				&result,
+			));
+		};
+		let invalid_proof = || {
+			Err(pallet_bridge_messages::Error::<
+				Runtime,
+				crate::AtRococoWithWococoMessagesInstance,
+			>::InvalidMessagesProof
+				.into())
+		};
+
+		// proofs are not verified while the bridge is halted
+		assert_ok!(crate::BridgeConfig::set_operating_mode(
+			crate::Origin::root(),
+			OperatingMode::Halted
+		));
+		dispatch(&alice, invalid_proof());
+		assert!(crate::BridgeRelayers::is_registered(&alice, lane_id));
+		assert_ok!(crate::BridgeConfig::set_operating_mode(
+			crate::Origin::root(),
+			OperatingMode::Normal
+		));
+
+		// other failures are not the relayer's fault
+		dispatch(&alice, Err(DispatchError::BadOrigin));
+		assert!(crate::BridgeRelayers::is_registered(&alice, lane_id));
+
+		dispatch(&alice, invalid_proof());
+		assert!(!crate::BridgeRelayers::is_registered(&alice, lane_id));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::free_balance(&alice), crate::ExistentialDeposit::get());
+	});
+}
+
+#[test]
+fn invalid_delivery_confirmations_are_reported() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_relayers::{Error as RelayersError, LaneConfirmation};
+
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Payments = crate::bridge_relayers::RewardsPot<
+			Runtime,
+			Instance,
+			GetDeliveryConfirmationTransactionFee,
+		>;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let pallet_name = bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+		let bond = crate::RelayerBond::get();
+		Balances::make_free_balance_be(&alice, crate::ExistentialDeposit::get() + bond);
+		assert_ok!(crate::BridgeRelayers::register(crate::Origin::signed(alice.clone()), lane_id));
+
+		// wococo has received two messages of the lane by the first header
+		let mut wococo = MockBridgedChain::new();
+		assert_ok!(crate::BridgeWococoGrandpa::initialize(
+			crate::Origin::root(),
+			wococo.initialization_data(),
+		));
+		wococo.insert_inbound_lane_data(
+			pallet_name,
+			&lane_id,
+			&InboundLaneData {
+				relayers: vec![UnrewardedRelayer {
+					relayer: bob.clone(),
+					messages: DeliveredMessages {
+						begin: 1,
+						end: 2,
+						dispatch_results: Default::default(),
+					},
+				}]
+				.into_iter()
+				.collect(),
+				last_confirmed_nonce: 0,
+			},
+		);
+		let number_key = storage_value_key("System", "Number").0;
+		let lane_key = storage_keys::inbound_lane_data_key(pallet_name, &lane_id).0;
+		let mut finalize_header = |number: crate::BlockNumber| {
+			wococo.insert_raw(number_key.clone(), number.encode());
+			let (header, justification) = wococo.finalize_header();
+			let at = header.hash();
+			assert_ok!(crate::BridgeWococoGrandpa::submit_finality_proof(
+				crate::Origin::signed(bob.clone()),
+				Box::new(header),
+				justification,
+			));
+			(at, wococo.prove_storage(&[number_key.clone(), lane_key.clone()]))
+		};
+		let report = |(at, storage_proof): (crate::Hash, Vec<Vec<u8>>)| {
+			crate::BridgeRelayers::report_invalid_confirmation(
+				crate::Origin::signed(bob.clone()),
+				WOCOCO_CHAIN_ID,
+				lane_id,
+				at,
+				storage_proof,
+			)
+		};
+		let first_header_proof = finalize_header(1);
+		let second_header_proof = finalize_header(2);
+
+		// there's nothing to report until the delivery is confirmed
+		assert_noop!(report(second_header_proof.clone()), RelayersError::<Runtime>::NoConfirmation);
+
+		// the confirmation of received messages is valid
+		let confirm = |relayer: &crate::AccountId, received_range| {
+			Payments::pay_relayers_rewards(
+				lane_id,
+				Default::default(),
+				relayer,
+				&received_range,
+				&relayer_fund,
+			)
+		};
+		confirm(&bob, 1..=2);
+		assert_noop!(
+			report(second_header_proof.clone()),
+			RelayersError::<Runtime>::ValidConfirmation,
+		);
+
+		// alice confirms a message that wococo has never received
+		confirm(&alice, 1..=3);
+		assert_eq!(
+			crate::BridgeRelayers::latest_confirmation(WOCOCO_CHAIN_ID, lane_id),
+			Some(LaneConfirmation { relayer: alice.clone(), nonce: 3, bridged_block: 2 }),
+		);
+		assert_noop!(report(first_header_proof), RelayersError::<Runtime>::OutdatedLaneProof);
+		assert_noop!(
+			report((second_header_proof.0, wococo.prove_storage(&[lane_key]))),
+			RelayersError::<Runtime>::InvalidInboundLaneProof,
+		);
+
+		assert_ok!(report(second_header_proof.clone()));
+		assert!(!crate::BridgeRelayers::is_registered(&alice, lane_id));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert!(!crate::BridgeConfig::is_lane_enabled(&lane_id));
+		assert!(frame_system::Pallet::<Runtime>::events().iter().any(|record| record.event ==
+			crate::Event::BridgeRelayers(
+				crate::bridge_relayers::Event::InvalidConfirmationReported(
+					alice.clone(),
+					WOCOCO_CHAIN_ID,
+					lane_id,
+					3,
+					2,
+				)
+			)));
+
+		// the confirmation is only reported once
+		assert_noop!(report(second_header_proof), RelayersError::<Runtime>::NoConfirmation);
+	});
+}
+
+#[test]
+fn bridge_parameters_are_updated_by_bridge_admin() {
+	new_test_ext().execute_with(|| {
+		let owner = crate::AccountId::from([1; 32]);
+		let collective = |yes, total| -> crate::Origin {
+			pallet_collective::RawOrigin::<crate::AccountId, ()>::Members(yes, total).into()
+		};
+		let parameter = BridgeMessagesParameter::RelayerFeePercent([0, 0, 0, 0], 25);
+		PalletOwner::<Runtime, crate::AtRococoWithWococoMessagesInstance>::put(owner.clone());
+
+		// the pallet owner is not the bridge admin
+		for origin in [crate::Origin::signed(owner), collective(1, 2)] {
+			assert_noop!(
+				crate::BridgeWococoMessages::update_pallet_parameter(
+					origin.clone(),
+					parameter.clone(),
+				),
+				sp_runtime::DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				crate::BridgeWococoMessages::set_operating_mode(
+					origin,
+					MessagesOperatingMode::Halted,
+				),
+				sp_runtime::DispatchError::BadOrigin,
+			);
+		}
+
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			collective(2, 3),
+			parameter,
+		));
+		assert_eq!(AtRococoWithWococoMessageBridge::relayer_fee_percent(&[0, 0, 0, 0]), 25);
+		assert_ok!(crate::BridgeWococoMessages::set_operating_mode(
+			collective(2, 3),
+			MessagesOperatingMode::Halted,
+		));
+		assert_eq!(
+			PalletOperatingMode::<Runtime, crate::AtRococoWithWococoMessagesInstance>::get(),
+			MessagesOperatingMode::Halted,
+		);
+	});
+}
+
+#[test]
+fn bridge_parameter_updates_deposit_events() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_config::Event as BridgeConfigEvent;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let update = |parameter| {
+			assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+				crate::Origin::root(),
+				parameter,
+			));
+		};
+		let last_event = || {
+			frame_system::Pallet::<Runtime>::events().into_iter().rev().find_map(|record| {
+				match record.event {
+					crate::Event::BridgeConfig(event) => Some(event),
+					_ => None,
+				}
+			})
+		};
+
+		update(BridgeMessagesParameter::RelayerFeePercent([0, 0, 0, 0], 25));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::RelayerFeePercentUpdated {
+				lane: [0, 0, 0, 0],
+				old: None,
+				new: 25
+			}),
+		);
+		update(BridgeMessagesParameter::RelayerFeePercent([0, 0, 0, 0], 30));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::RelayerFeePercentUpdated {
+				lane: [0, 0, 0, 0],
+				old: Some(25),
+				new: 30
+			}),
+		);
+
+		let new_rate = FixedU128::saturating_from_integer(2);
+		update(BridgeMessagesParameter::BridgedToThisConversionRate(new_rate));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::ConversionRateUpdated {
+				old: INITIAL_BRIDGED_TO_THIS_CONVERSION_RATE,
+				new: new_rate,
+			}),
+		);
+
+		update(BridgeMessagesParameter::MaxConversionRateStaleness(Some(10)));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::MaxConversionRateStalenessUpdated { old: None, new: Some(10) }),
+		);
+
+		let change_limit = Some((Perbill::from_percent(10), 100));
+		update(BridgeMessagesParameter::ConversionRateChangeLimit(change_limit));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::ConversionRateChangeLimitUpdated {
+				old: None,
+				new: change_limit,
+			}),
+		);
+
+		let large_message_fee = Some(LargeMessageFee { threshold: 1024, byte_weight_per_byte: 1 });
+		update(BridgeMessagesParameter::LargeMessageFee(large_message_fee));
+		assert_eq!(
+			last_event(),
+			Some(BridgeConfigEvent::LargeMessageFeeUpdated { old: None, new: large_message_fee }),
+		);
+	});
+}
+
+#[test]
+fn relayer_fee_percent_is_updated_per_lane() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let default_percent = AtRococoWithWococoMessageBridge::RELAYER_FEE_PERCENT;
+		let parameter = BridgeMessagesParameter::RelayerFeePercent(lane_id, 25);
+		assert_eq!(AtRococoWithWococoMessageBridge::relayer_fee_percent(&lane_id), default_percent);
+
+		assert_noop!(
+			crate::BridgeWococoMessages::update_pallet_parameter(
+				crate::Origin::signed(crate::AccountId::from([1; 32])),
+				parameter.clone(),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			crate::Origin::root(),
+			parameter,
+		));
+		assert_eq!(AtRococoWithWococoMessageBridge::relayer_fee_percent(&lane_id), 25);
+		assert_eq!(
+			AtRococoWithWococoMessageBridge::relayer_fee_percent(&[0, 0, 0, 1]),
+			default_percent,
+		);
+	});
+}
+
+#[test]
+fn lane_limits_are_updated_per_lane() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let max_limits = max_lane_limits();
+		let limits = LaneLimits {
+			max_pending_messages: 1,
+			max_unrewarded_relayer_entries: 2,
+			max_unconfirmed_messages: max_limits.max_unconfirmed_messages + 1,
+		};
+		assert_eq!(
+			RococoAtRococo::maximal_pending_messages_at_outbound_lane(&lane_id),
+			max_limits.max_pending_messages,
+		);
+		assert_eq!(ConfiguredInboundLaneLimits::max_unrewarded_relayer_entries(&lane_id), None);
+
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			crate::Origin::root(),
+			BridgeMessagesParameter::LaneLimits(lane_id, limits),
+		));
+		assert_eq!(RococoAtRococo::maximal_pending_messages_at_outbound_lane(&lane_id), 1);
+		assert_eq!(ConfiguredInboundLaneLimits::max_unrewarded_relayer_entries(&lane_id), Some(2));
+		// limits can't exceed the maximal limits
+		assert_eq!(
+			ConfiguredInboundLaneLimits::max_unconfirmed_messages(&lane_id),
+			Some(max_limits.max_unconfirmed_messages),
+		);
+		assert_eq!(
+			RococoAtRococo::maximal_pending_messages_at_outbound_lane(&[0, 0, 0, 1]),
+			max_limits.max_pending_messages,
+		);
+	});
+}
+
+#[test]
+fn outbound_lane_congestion_is_tracked() {
+	new_test_ext().execute_with(|| {
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+		type Hook = TrackLaneCongestion<Instance>;
+
+		let lane_id = [0, 0, 0, 0];
+		let set_pending_messages = |generated, received| {
+			OutboundLanes::<Runtime, Instance>::mutate(lane_id, |lane| {
+				lane.latest_generated_nonce = generated;
+				lane.latest_received_nonce = received;
+			})
+		};
+		let is_lane_congested =
+			|| crate::BridgeConfig::is_lane_congested(WOCOCO_CHAIN_ID, &lane_id);
+		let delivered = DeliveredMessages::new(1, true);
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			crate::Origin::root(),
+			BridgeMessagesParameter::LaneLimits(
+				lane_id,
+				LaneLimits { max_pending_messages: 10, ..max_lane_limits() },
+			),
+		));
+
+		set_pending_messages(7, 0);
+		Hook::on_messages_accepted(&lane_id, &7);
+		assert!(!is_lane_congested());
+
+		// the lane is congested when 80% of the limit is pending
+		set_pending_messages(8, 0);
+		Hook::on_messages_accepted(&lane_id, &8);
+		assert!(is_lane_congested());
+		assert!(!crate::BridgeConfig::is_lane_congested(ROCOCO_CHAIN_ID, &lane_id));
+
+		// and stays congested until half of the limit is pending
+		set_pending_messages(8, 2);
+		Hook::on_messages_delivered(&lane_id, &delivered);
+		assert!(is_lane_congested());
+
+		set_pending_messages(8, 3);
+		Hook::on_messages_delivered(&lane_id, &delivered);
+		assert!(!is_lane_congested());
+	});
+}
+
+#[test]
+fn wrapped_tokens_are_minted_and_burned() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_assets::{Balances as AssetBalances, BurnState, Error as AssetsError};
+		type Assets = crate::BridgeWococoAssets;
+		type Instance = crate::WrappedWococoTokensInstance;
+
+		let alice = crate::AccountId::from([1; 32]);
+		let bob = crate::AccountId::from([2; 32]);
+		let signed_alice = || crate::Origin::signed(alice.clone());
+		let minter = || crate::Origin::signed(crate::BridgedWococoAssetsAccount::get());
+		let lane = crate::BridgeAssetsLane::get();
+		let ed = crate::ExistentialDeposit::get();
+		let fee = 1_000_000 * rococo_runtime_constants::currency::DOLLARS;
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+
+		assert_noop!(
+			Assets::mint(signed_alice(), alice.clone(), 100 * ed),
+			AssetsError::<Runtime, Instance>::NotBridgedAssetsAccount,
+		);
+		assert_ok!(Assets::mint(minter(), alice.clone(), 100 * ed));
+		assert_eq!(Assets::balance(&alice), 100 * ed);
+
+		// accounts are not left with balances below the minimal balance
+		assert_noop!(
+			Assets::mint(minter(), bob.clone(), ed - 1),
+			AssetsError::<Runtime, Instance>::BelowMinimumBalance,
+		);
+		assert_noop!(
+			Assets::transfer(signed_alice(), bob.clone(), ed - 1),
+			AssetsError::<Runtime, Instance>::BelowMinimumBalance,
+		);
+		assert_noop!(
+			Assets::transfer(signed_alice(), bob.clone(), 100 * ed - 1),
+			AssetsError::<Runtime, Instance>::BelowMinimumBalance,
+		);
+		assert_ok!(Assets::transfer(signed_alice(), bob.clone(), ed));
+		assert_ok!(Assets::transfer(crate::Origin::signed(bob.clone()), alice.clone(), ed));
+		assert_eq!(Assets::balance(&bob), 0);
+		assert!(!AssetBalances::<Runtime, Instance>::contains_key(&bob));
+
+		// the account of the assets bridge sends the unlock messages, holders pay their fees
+		assert_ok!(crate::BridgeConfig::register_lane(
+			crate::Origin::root(),
+			lane,
+			CALL_FILTER_ALL,
+		));
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane,
+			crate::BridgeAssetsAccount::get(),
+		));
+		Balances::make_free_balance_be(&alice, 3 * fee);
+		Balances::make_free_balance_be(&relayer_fund, crate::ExistentialDeposit::get());
+		assert_noop!(
+			Assets::burn(signed_alice(), alice.clone(), 100 * ed - 1, fee),
+			AssetsError::<Runtime, Instance>::BelowMinimumBalance,
+		);
+		assert_ok!(Assets::burn(signed_alice(), alice.clone(), 60 * ed, fee));
+		assert_ok!(Assets::burn(signed_alice(), alice.clone(), 30 * ed, fee));
+		assert_eq!(Assets::balance(&alice), 10 * ed);
+		assert_eq!(Assets::pending_burn(1).map(|burn| burn.state), Some(BurnState::Pending));
+		assert_noop!(
+			Assets::settle_burn(signed_alice(), 1),
+			AssetsError::<Runtime, Instance>::BurnIsPending,
+		);
+		assert_eq!(Assets::reconcile(), Ok(()));
+
+		// the first unlock is dispatched at the bridged chain, the second one isn't
+		let mut delivered = DeliveredMessages::new(1, true);
+		delivered.note_dispatched_message(false);
+		Assets::on_messages_delivered(&lane, &delivered);
+		assert_ok!(Assets::settle_burn(signed_alice(), 1));
+		assert_ok!(Assets::settle_burn(signed_alice(), 2));
+		assert_eq!(Assets::balance(&alice), 40 * ed);
+		assert_eq!(Assets::total_issuance(), 40 * ed);
+		assert_eq!(Assets::total_unlocked(), 60 * ed);
+		assert_eq!(Assets::reconcile(), Ok(()));
+	});
+}
+
+#[test]
+fn governance_calls_are_executed_at_bridged_chain() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_governance::{Error as GovernanceError, RemoteCallState};
+		type Governance = crate::BridgeWococoGovernance;
+		type Instance = crate::RemoteWococoGovernanceInstance;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let alice = crate::AccountId::from([1; 32]);
+		let lane = crate::BridgeGovernanceLane::get();
+		let fee = 1_000_000 * rococo_runtime_constants::currency::DOLLARS;
+		let call = crate::Call::System(frame_system::Call::remark { remark: vec![42] });
+		let execute = |proposal: u8| {
+			Governance::execute(
+				crate::Origin::root(),
+				[proposal; 32].into(),
+				call.encode(),
+				call.get_dispatch_info().weight,
+				fee,
+			)
+		};
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+
+		// the governance pays message fees from the account that is linked to the Root origin
+		assert_ok!(crate::BridgeConfig::register_lane(
+			crate::Origin::root(),
+			lane,
+			CALL_FILTER_ALL,
+		));
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Root,
+			Some(alice.clone()),
+		));
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane,
+			alice.clone(),
+		));
+		Balances::make_free_balance_be(&alice, 3 * fee);
+		Balances::make_free_balance_be(&relayer_fund, crate::ExistentialDeposit::get());
+
+		assert_noop!(
+			Governance::execute(
+				crate::Origin::signed(alice.clone()),
+				[1; 32].into(),
+				call.encode(),
+				0,
+				fee,
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_ok!(execute(1));
+		assert_ok!(execute(2));
+		assert_noop!(execute(1), GovernanceError::<Runtime, Instance>::DuplicateProposal);
+		assert_eq!(Balances::free_balance(&alice), fee);
+		assert_eq!(Governance::proposal_nonce(crate::Hash::from([2; 32])), Some(2));
+		assert_eq!(Governance::remote_call_state([1; 32].into()), Some(RemoteCallState::Pending));
+
+		// the first call is dispatched at the bridged chain, the second one isn't
+		let mut delivered = DeliveredMessages::new(1, true);
+		delivered.note_dispatched_message(false);
+		Governance::on_messages_delivered(&lane, &delivered);
+		assert_eq!(
+			Governance::remote_call_state([1; 32].into()),
+			Some(RemoteCallState::Dispatched),
+		);
+		assert_eq!(Governance::remote_call_state([2; 32].into()), Some(RemoteCallState::Failed));
+		assert!(frame_system::Pallet::<Runtime>::events().iter().any(|record| record.event ==
+			crate::Event::BridgeWococoGovernance(
+				crate::bridge_governance::Event::RemoteCallConfirmed(
+					[2; 32].into(),
+					RemoteCallState::Failed,
+				)
+			)));
+	});
+}
+
+#[test]
+fn oversized_governance_calls_are_sent_in_chunks() {
+	new_test_ext().execute_with(|| {
+		use sp_runtime::traits::Dispatchable;
+		type Governance = crate::BridgeWococoGovernance;
+		type Instance = crate::AtRococoWithWococoMessagesInstance;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let alice = crate::AccountId::from([1; 32]);
+		let lane = crate::BridgeGovernanceLane::get();
+		let fee = 1_000_000 * rococo_runtime_constants::currency::DOLLARS;
+		let call =
+			crate::Call::System(frame_system::Call::remark_with_event { remark: vec![42; 4_000] });
+		let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+			crate::AccountId,
+			bp_rococo::AccountIdConverter,
+		>();
+
+		assert_ok!(crate::BridgeConfig::register_lane(
+			crate::Origin::root(),
+			lane,
+			CALL_FILTER_ALL,
+		));
+		assert_ok!(crate::BridgeConfig::set_origin_account(
+			crate::Origin::root(),
+			LinkedOrigin::Root,
+			Some(alice.clone()),
+		));
+		assert_ok!(crate::BridgeConfig::add_message_sender(
+			crate::Origin::root(),
+			lane,
+			alice.clone(),
+		));
+		assert_ok!(crate::BridgeConfig::set_maximal_outbound_payload_size(
+			crate::Origin::root(),
+			Some(1_024),
+		));
+		Balances::make_free_balance_be(&alice, 6 * fee);
+		Balances::make_free_balance_be(&relayer_fund, crate::ExistentialDeposit::get());
+
+		// the call is split into five chunks, every chunk is sent in a separate message and the
+		// proposal is tracked by the last message
+		assert_ok!(Governance::execute(
+			crate::Origin::root(),
+			[1; 32].into(),
+			call.encode(),
+			call.get_dispatch_info().weight,
+			fee,
+		));
+		assert_eq!(Governance::proposal_nonce(crate::Hash::from([1; 32])), Some(5));
+		assert_eq!(Balances::free_balance(&alice), fee);
+		assert!(!OutboundMessages::<Runtime, Instance>::contains_key(MessageKey {
+			lane_id: lane,
+			nonce: 6,
+		}));
+
+		// the bridged chain reassembles the call and dispatches it with its governance account
+		let bridged_root = crate::BridgedRococoRootAccount::get();
+		for nonce in 1..=5 {
+			let sent =
+				OutboundMessages::<Runtime, Instance>::get(MessageKey { lane_id: lane, nonce })
+					.unwrap();
+			let payload = ToWococoMessagePayload::decode(&mut &sent.payload[..]).unwrap();
+			assert_eq!(payload.origin, CallOrigin::SourceRoot);
+			assert!(payload.call.len() <= 1_024);
+
+			let chunk_call = decode_inbound_call(&payload.call).unwrap();
+			assert_eq!(chunk_call.get_dispatch_info().weight, payload.weight);
+			assert_ok!(chunk_call.dispatch(crate::Origin::signed(bridged_root.clone())));
+		}
+		let call_hash = H256(blake2_256(&call.encode()));
+		assert_eq!(crate::BridgeChunks::staged_call((bridged_root.clone(), call_hash)), None);
+		assert!(frame_system::Pallet::<Runtime>::events().iter().any(|record| record.event ==
+			crate::Event::BridgeChunks(crate::bridge_chunks::Event::CallDispatched(
+				bridged_root.clone(),
+				call_hash,
+				Ok(()),
+			))));
+	});
+}
+
+#[test]
+fn chunked_calls_are_staged_until_complete_or_expired() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_chunks::{Error as ChunksError, StagedCall};
+		use frame_support::{assert_err, traits::OnInitialize};
+		type Chunks = crate::BridgeChunks;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let bridged_root = crate::BridgedWococoRootAccount::get();
+		let bridged_root_origin = || crate::Origin::signed(bridged_root.clone());
+		let call =
+			crate::Call::System(frame_system::Call::remark_with_event { remark: vec![42; 100] })
+				.encode();
+		let call_hash = H256(blake2_256(&call));
+		let (first_chunk, second_chunk) = call.split_at(50);
+		let receive_chunk = |origin: crate::Origin, index: u32, chunk: &[u8]| {
+			Chunks::receive_chunk(origin, call_hash, index, 2, 1_000_000_000, chunk.to_vec())
+		};
+		let is_deposited = |event| {
+			frame_system::Pallet::<Runtime>::events()
+				.iter()
+				.any(|record| record.event == crate::Event::BridgeChunks(event))
+		};
+
+		// chunks are only accepted from governance accounts of bridged chains, in order
+		assert_noop!(
+			receive_chunk(crate::Origin::signed([1; 32].into()), 0, first_chunk),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			receive_chunk(bridged_root_origin(), 1, second_chunk),
+			ChunksError::<Runtime>::UnknownCall,
+		);
+		assert_ok!(receive_chunk(bridged_root_origin(), 0, first_chunk));
+		assert_noop!(
+			receive_chunk(bridged_root_origin(), 2, second_chunk),
+			ChunksError::<Runtime>::InvalidChunk,
+		);
+
+		// not all chunks have been received in time, so the call is discarded
+		let expires_at = 1 + crate::BridgeStagedCallLifetime::get();
+		assert_eq!(
+			Chunks::staged_call((bridged_root.clone(), call_hash)),
+			Some(StagedCall { total: 2, received: 1, expires_at }),
+		);
+		Chunks::on_initialize(expires_at - 1);
+		assert!(Chunks::staged_call((bridged_root.clone(), call_hash)).is_some());
+		Chunks::on_initialize(expires_at);
+		assert_eq!(Chunks::staged_call((bridged_root.clone(), call_hash)), None);
+		assert!(is_deposited(crate::bridge_chunks::Event::CallDiscarded(
+			bridged_root.clone(),
+			call_hash,
+		)));
+		assert_noop!(
+			receive_chunk(bridged_root_origin(), 1, second_chunk),
+			ChunksError::<Runtime>::UnknownCall,
+		);
+
+		// the reassembled call must match its hash
+		assert_ok!(receive_chunk(bridged_root_origin(), 0, first_chunk));
+		assert_err!(
+			receive_chunk(bridged_root_origin(), 1, &[0; 53]),
+			ChunksError::<Runtime>::CallHashMismatch,
+		);
+
+		// the complete call is dispatched with the governance account of the bridged chain
+		assert_ok!(receive_chunk(bridged_root_origin(), 0, first_chunk));
+		assert_ok!(receive_chunk(bridged_root_origin(), 1, second_chunk));
+		assert_eq!(Chunks::staged_call((bridged_root.clone(), call_hash)), None);
+		assert!(is_deposited(crate::bridge_chunks::Event::CallDispatched(
+			bridged_root.clone(),
+			call_hash,
+			Ok(()),
+		)));
+	});
+}
+
+#[test]
+fn calls_are_dispatched_by_preimages() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_chunks::{Error as ChunksError, PendingCall};
+		use frame_support::traits::{OnInitialize, PreimageProvider};
+		type Chunks = crate::BridgeChunks;
+		type Preimage = crate::Preimage;
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		let alice = crate::AccountId::from([1; 32]);
+		let bridged_root = crate::BridgedWococoRootAccount::get();
+		let bridged_root_origin = || crate::Origin::signed(bridged_root.clone());
+		let remark = |byte| {
+			let call = crate::Call::System(frame_system::Call::remark_with_event {
+				remark: vec![byte; 100_000],
+			});
+			(call.encode(), H256(blake2_256(&call.encode())), call.get_dispatch_info().weight)
+		};
+		let is_deposited = |event| {
+			frame_system::Pallet::<Runtime>::events()
+				.iter()
+				.any(|record| record.event == crate::Event::BridgeChunks(event))
+		};
+		Balances::make_free_balance_be(&alice, 1_000 * rococo_runtime_constants::currency::DOLLARS);
+
+		// the preimage is noted before the hash is received, so the call is dispatched at once
+		let (call, call_hash, call_weight) = remark(42);
+		assert_ok!(Preimage::note_preimage(crate::Origin::signed(alice.clone()), call));
+		assert_ok!(Chunks::dispatch_preimage(bridged_root_origin(), call_hash, call_weight));
+		assert!(is_deposited(crate::bridge_chunks::Event::CallDispatched(
+			bridged_root.clone(),
+			call_hash,
+			Ok(()),
+		)));
+
+		// otherwise the preimage is requested and anyone dispatches the call once it is noted
+		let (call, call_hash, call_weight) = remark(43);
+		let dispatch_pending_call = |call_weight_limit| {
+			Chunks::dispatch_pending_call(
+				crate::Origin::signed(alice.clone()),
+				bridged_root.clone(),
+				call_hash,
+				call_weight_limit,
+			)
+		};
+		let expires_at = 1 + crate::BridgeStagedCallLifetime::get();
+		assert_ok!(Chunks::dispatch_preimage(bridged_root_origin(), call_hash, call_weight));
+		assert_eq!(
+			Chunks::pending_call((bridged_root.clone(), call_hash)),
+			Some(PendingCall { call_weight, expires_at }),
+		);
+		assert!(Preimage::preimage_requested(&call_hash));
+		assert_noop!(dispatch_pending_call(call_weight), ChunksError::<Runtime>::PreimageMissing);
+
+		assert_ok!(Preimage::note_preimage(crate::Origin::signed(alice.clone()), call));
+		assert_noop!(
+			dispatch_pending_call(call_weight - 1),
+			ChunksError::<Runtime>::InsufficientCallWeight,
+		);
+		assert_ok!(dispatch_pending_call(call_weight));
+		assert_eq!(Chunks::pending_call((bridged_root.clone(), call_hash)), None);
+		assert!(!Preimage::preimage_requested(&call_hash));
+		assert!(is_deposited(crate::bridge_chunks::Event::CallDispatched(
+			bridged_root.clone(),
+			call_hash,
+			Ok(()),
+		)));
+
+		// the pending call is discarded if its preimage isn't noted in time
+		let (_, call_hash, call_weight) = remark(44);
+		assert_ok!(Chunks::dispatch_preimage(bridged_root_origin(), call_hash, call_weight));
+		Chunks::on_initialize(expires_at);
+		assert_eq!(Chunks::pending_call((bridged_root.clone(), call_hash)), None);
+		assert!(!Preimage::preimage_requested(&call_hash));
+		assert!(is_deposited(crate::bridge_chunks::Event::CallDiscarded(
+			bridged_root.clone(),
+			call_hash,
+		)));
+	});
+}
+
+#[test]
+fn outbound_messages_are_rejected_while_conversion_rate_is_stale() {
+	new_test_ext().execute_with(|| {
+		let payload = ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 0,
+			origin: CallOrigin::SourceRoot,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
+			call: vec![],
+		};
+		let update_parameter = |parameter| {
+			assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+				crate::Origin::root(),
+				parameter,
+			));
+		};
+		let set_block_number = frame_system::Pallet::<Runtime>::set_block_number;
+
+		// the conversion rate never goes stale by default
+		set_block_number(1_000);
+		assert_ne!(WococoAtRococo::verify_message(&payload), Err(BridgeError::StaleConversionRate));
+
+		update_parameter(BridgeMessagesParameter::MaxConversionRateStaleness(Some(100)));
+		assert_eq!(WococoAtRococo::verify_message(&payload), Err(BridgeError::StaleConversionRate));
+
+		update_parameter(BridgeMessagesParameter::BridgedToThisConversionRate(
+			FixedU128::saturating_from_integer(2),
+		));
+		assert_eq!(crate::BridgeConfig::conversion_rate_updated_at(), 1_000);
+		assert_eq!(AtRococoWithWococoMessageBridge::bridged_balance_to_this_balance(10, None), 20);
+		assert_ne!(WococoAtRococo::verify_message(&payload), Err(BridgeError::StaleConversionRate));
+
+		set_block_number(1_100);
+		assert_ne!(WococoAtRococo::verify_message(&payload), Err(BridgeError::StaleConversionRate));
+		set_block_number(1_101);
+		assert_eq!(WococoAtRococo::verify_message(&payload), Err(BridgeError::StaleConversionRate));
+	});
+}
+
+#[test]
+fn conversion_rate_changes_are_limited() {
+	new_test_ext().execute_with(|| {
+		let update_rate = |conversion_rate| {
+			assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+				crate::Origin::root(),
+				BridgeMessagesParameter::BridgedToThisConversionRate(conversion_rate),
+			));
+			crate::BridgeConfig::bridged_to_this_conversion_rate()
+		};
+		let rate = |parts_per_hundred| FixedU128::saturating_from_rational(parts_per_hundred, 100);
+		let set_block_number = frame_system::Pallet::<Runtime>::set_block_number;
+
+		// changes are not limited by default
+		set_block_number(1_000);
+		assert_eq!(update_rate(rate(300)), rate(300));
+		assert_eq!(update_rate(rate(100)), rate(100));
+
+		assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+			crate::Origin::root(),
+			BridgeMessagesParameter::ConversionRateChangeLimit(Some((
+				Perbill::from_percent(10),
+				100,
+			))),
+		));
+		assert_eq!(update_rate(rate(200)), rate(110));
+		// the limit is relative to the rate at the start of the period
+		assert_eq!(update_rate(rate(50)), rate(90));
+		set_block_number(1_099);
+		assert_eq!(update_rate(rate(80)), rate(90));
+
+		// the new period starts with the current rate
+		set_block_number(1_100);
+		assert_eq!(update_rate(rate(80)), rate(81));
+		assert_eq!(crate::BridgeConfig::conversion_rate_updated_at(), 1_100);
+	});
+}
+
+#[test]
+fn conversion_rate_parameters_are_migrated() {
+	new_test_ext().execute_with(|| {
+		use crate::bridge_config::migration::{self, v1::parameter_key};
+		use frame_support::{storage::unhashed, traits::StorageVersion};
+
+		let rate = FixedU128::saturating_from_integer(2);
+		StorageVersion::new(0).put::<crate::BridgeConfig>();
+		unhashed::put(&parameter_key("BridgedToThisConversionRate"), &rate);
+		unhashed::put(&parameter_key("ConversionRateUpdatedAt"), &10u32);
+		unhashed::put(&parameter_key("MaxConversionRateStaleness"), &Some(100u32));
+		unhashed::put(&parameter_key("ConversionRateChangePeriodStart"), &(5u32, rate));
+		unhashed::put_raw(&parameter_key("FeeAssetConversionRate"), &[0xFF]);
+
+		migration::migrate_to_latest::<Runtime>();
+		assert_eq!(crate::BridgeConfig::bridged_to_this_conversion_rate(), rate);
+		assert_eq!(crate::BridgeConfig::conversion_rate_updated_at(), 10);
+		assert_eq!(crate::BridgeConfig::max_conversion_rate_staleness(), Some(100));
+		assert_eq!(crate::BridgeConfig::conversion_rate_change_limit(), None);
+		assert_eq!(crate::BridgeConfig::conversion_rate_change_period_start(), (5, rate));
+		// undecodable values are dropped
+		assert_eq!(
+			crate::BridgeConfig::fee_asset_conversion_rate(),
+			FixedU128::from_inner(FixedU128::DIV),
+		);
+		assert!(migration::v1::PARAMETERS
+			.iter()
+			.all(|name| !unhashed::exists(&parameter_key(name))));
+		assert_eq!(StorageVersion::get::<crate::BridgeConfig>(), migration::STORAGE_VERSION);
+
+		// values set after the migration are not overwritten
+		unhashed::put(&parameter_key("BridgedToThisConversionRate"), &FixedU128::default());
+		migration::migrate_to_latest::<Runtime>();
+		assert_eq!(crate::BridgeConfig::bridged_to_this_conversion_rate(), rate);
+	});
+}
+
+#[test]
+fn oracle_conversion_rates_are_parsed() {
+	use crate::bridge_rate_oracle::parse_conversion_rate;
+
+	let rate = |parts_per_hundred| FixedU128::saturating_from_rational(parts_per_hundred, 100);
+	assert_eq!(parse_conversion_rate(b"1.25"), Some(rate(125)));
+	assert_eq!(parse_conversion_rate(b" 3\n"), Some(rate(300)));
+	assert_eq!(parse_conversion_rate(b"0.050"), Some(rate(5)));
+	assert_eq!(parse_conversion_rate(b"2."), Some(rate(200)));
+	assert_eq!(parse_conversion_rate(b"0.000000000000000001"), Some(FixedU128::from_inner(1)));
+
+	assert_eq!(parse_conversion_rate(b""), None);
+	assert_eq!(parse_conversion_rate(b".5"), None);
+	assert_eq!(parse_conversion_rate(b"-1"), None);
+	assert_eq!(parse_conversion_rate(b"1.2.3"), None);
+	assert_eq!(parse_conversion_rate(b"1e3"), None);
+	assert_eq!(parse_conversion_rate(b"0.0000000000000000001"), None);
+}
+
+#[test]
+fn oracle_conversion_rates_are_accepted_only_from_oracles() {
+	use crate::bridge_rate_oracle::{Call as OracleCall, ConversionRatePayload};
+	use frame_support::unsigned::ValidateUnsigned;
+	use sp_core::{sr25519, Pair};
+	use sp_runtime::{
+		transaction_validity::{InvalidTransaction, TransactionSource},
+		MultiSignature, MultiSigner,
+	};
+
+	new_test_ext().execute_with(|| {
+		let oracle = sr25519::Pair::from_seed(&[1; 32]);
+		let stranger = sr25519::Pair::from_seed(&[2; 32]);
+		let conversion_rate = FixedU128::saturating_from_rational(150, 100);
+		let submit_call = |signer: &sr25519::Pair, block_number| {
+			let payload = ConversionRatePayload {
+				block_number,
+				conversion_rate,
+				public: MultiSigner::from(signer.public()),
+			};
+			let signature = MultiSignature::from(signer.sign(&payload.encode()));
+			OracleCall::<Runtime>::submit_conversion_rate { payload, signature }
+		};
+		let validate = |call: &OracleCall<Runtime>| {
+			crate::BridgeRateOracle::validate_unsigned(TransactionSource::External, call)
+		};
+		let update_interval = crate::ConversionRateUpdateInterval::get();
+		frame_system::Pallet::<Runtime>::set_block_number(update_interval);
+
+		assert_ok!(crate::BridgeRateOracle::set_oracles(
+			crate::Origin::root(),
+			vec![MultiSigner::from(oracle.public())],
+		));
+		assert_eq!(
+			validate(&submit_call(&stranger, update_interval)),
+			Err(InvalidTransaction::BadSigner.into()),
+		);
+		assert_eq!(
+			validate(&submit_call(&oracle, update_interval + 1)),
+			Err(InvalidTransaction::Future.into()),
+		);
+		let mut forged_call = submit_call(&oracle, update_interval);
+		if let OracleCall::submit_conversion_rate { ref mut payload, .. } = forged_call {
+			payload.conversion_rate = FixedU128::saturating_from_rational(300, 100);
+		}
+		assert_eq!(validate(&forged_call), Err(InvalidTransaction::BadProof.into()));
+
+		let call = submit_call(&oracle, update_interval);
+		assert!(validate(&call).is_ok());
+		if let OracleCall::submit_conversion_rate { payload, signature } = call {
+			assert_ok!(crate::BridgeRateOracle::submit_conversion_rate(
+				crate::Origin::none(),
+				payload,
+				signature,
+			));
+		}
+		assert_eq!(crate::BridgeConfig::bridged_to_this_conversion_rate(), conversion_rate);
+		assert_eq!(crate::BridgeRateOracle::last_update_at(), update_interval);
+
+		// the next rate may only be submitted after the update interval
+		frame_system::Pallet::<Runtime>::set_block_number(2 * update_interval - 1);
+		assert_eq!(
+			validate(&submit_call(&oracle, 2 * update_interval - 1)),
+			Err(InvalidTransaction::Stale.into()),
+		);
+	});
+}
+
+#[test]
+fn bridged_fee_multiplier_is_used_to_estimate_delivery_cost() {
+	new_test_ext().execute_with(|| {
+		let transaction =
+			|| MessageTransaction { dispatch_weight: 1_000_000_000, size: 100, proof_size: 100 };
+		let wococo_payment = <WococoAtRococo as BridgedChainWithMessages>::transaction_payment;
+		let rococo_payment = <RococoAtWococo as BridgedChainWithMessages>::transaction_payment;
+		let delivery_cost = wococo_payment(transaction());
+		assert_eq!(rococo_payment(transaction()), delivery_cost);
+
+		let fee_multiplier = pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier() *
+			FixedU128::saturating_from_integer(2);
+		crate::bridge_config::BridgedFeeMultipliers::<Runtime>::insert(
+			WOCOCO_CHAIN_ID,
+			(100, fee_multiplier),
+		);
+		assert!(wococo_payment(transaction()) > delivery_cost);
+		assert_eq!(rococo_payment(transaction()), delivery_cost);
+	});
+}
+
+#[test]
+fn delivery_and_confirmation_fees_grow_with_proof_size() {
+	new_test_ext().execute_with(|| {
+		let transaction = |proof_size| MessageTransaction {
+			dispatch_weight: 1_000_000_000,
+			size: 100,
+			proof_size,
+		};
+		let delivery_payment = <WococoAtRococo as BridgedChainWithMessages>::transaction_payment;
+		let confirmation_payment = <RococoAtRococo as ThisChainWithMessages>::transaction_payment;
+		assert!(proof_size_to_fee(1_024) > 0);
+		assert!(proof_size_to_fee(16 * 1024) > proof_size_to_fee(1_024));
+		assert!(delivery_payment(transaction(16 * 1024)) > delivery_payment(transaction(1_024)));
+		assert!(
+			confirmation_payment(transaction(16 * 1024)) > confirmation_payment(transaction(1_024))
+		);
+
+		// payload bytes are charged for by the proof size
+		let delivery_cost = |payload_len| {
+			delivery_payment(WococoAtRococo::estimate_delivery_transaction(
+				&vec![0u8; payload_len],
+				false,
+				0,
+			))
+		};
+		assert!(delivery_cost(16 * 1024) > delivery_cost(1_024));
+	});
+}
+
+#[test]
+fn large_messages_pay_super_linear_delivery_fee() {
+	new_test_ext().execute_with(|| {
+		let set_large_message_fee = |large_message_fee| {
+			assert_ok!(crate::BridgeWococoMessages::update_pallet_parameter(
+				crate::Origin::root(),
+				BridgeMessagesParameter::LargeMessageFee(large_message_fee),
+			));
+		};
+		let delivery_weight = |payload_len| {
+			WococoAtRococo::estimate_delivery_transaction(&vec![0u8; payload_len], false, 0)
+				.dispatch_weight
+		};
+		let linear_weights = [1_024, 16 * 1024, 32 * 1024, 64 * 1024].map(delivery_weight);
+
+		set_large_message_fee(Some(LargeMessageFee {
+			threshold: 16 * 1024,
+			byte_weight_per_byte: 10,
+		}));
+
+		// messages up to the threshold pay the linear fee
+		assert_eq!(delivery_weight(1_024), linear_weights[0]);
+		assert_eq!(delivery_weight(16 * 1024), linear_weights[1]);
+
+		// larger messages pay for the square of bytes above the threshold
+		assert_eq!(delivery_weight(32 * 1024), linear_weights[2] + 10 * (16 * 1024) * (16 * 1024));
+		assert_eq!(delivery_weight(64 * 1024), linear_weights[3] + 10 * (48 * 1024) * (48 * 1024));
+
+		set_large_message_fee(None);
+		assert_eq!(delivery_weight(64 * 1024), linear_weights[3]);
+	});
+}
+
+proptest::proptest! {
+	#![proptest_config(proptest::prelude::ProptestConfig::with_cases(64))]
+
+	#[test]
+	fn delivery_cost_is_monotonic_and_never_overflows(
+		payload_len in 0usize..2 * 1024 * 1024,
+		dispatch_weight in proptest::prelude::any::<Weight>(),
+		include_pay_dispatch_fee_cost in proptest::prelude::any::<bool>(),
+		fee_multiplier in proptest::prelude::any::<u128>(),
+		extra_payload_len in 0usize..1024 * 1024,
+		extra_dispatch_weight in proptest::prelude::any::<Weight>(),
+	) {
+		new_test_ext().execute_with(|| {
+			crate::bridge_config::BridgedFeeMultipliers::<Runtime>::insert(
+				WOCOCO_CHAIN_ID,
+				(100, FixedU128::from_inner(fee_multiplier)),
+			);
+			crate::bridge_config::LargeMessageFeeCurve::<Runtime>::put(LargeMessageFee {
+				threshold: 64 * 1024,
+				byte_weight_per_byte: 1,
+			});
+
+			let estimate = |payload_len, dispatch_weight| {
+				WococoAtRococo::estimate_delivery_transaction(
+					&vec![0u8; payload_len],
+					include_pay_dispatch_fee_cost,
+					dispatch_weight,
+				)
+			};
+			let transaction = estimate(payload_len, dispatch_weight);
+			let larger_payload_transaction =
+				estimate(payload_len + extra_payload_len, dispatch_weight);
+			let larger_weight_transaction = estimate(
+				payload_len,
+				dispatch_weight.saturating_add(extra_dispatch_weight),
+			);
+			proptest::prop_assert!(
+				transaction.dispatch_weight <= larger_payload_transaction.dispatch_weight
+			);
+			proptest::prop_assert!(transaction.size <= larger_payload_transaction.size);
+			proptest::prop_assert!(
+				transaction.proof_size <= larger_payload_transaction.proof_size
+			);
+			proptest::prop_assert!(
+				transaction.dispatch_weight <= larger_weight_transaction.dispatch_weight
+			);
+
+			let payment = <WococoAtRococo as BridgedChainWithMessages>::transaction_payment;
+			let delivery_cost = payment(transaction);
+			proptest::prop_assert!(delivery_cost <= payment(larger_payload_transaction));
+			proptest::prop_assert!(delivery_cost <= payment(larger_weight_transaction));
+			Ok(())
+		})?;
+	}
+}
+
+#[test]
+fn bridged_fee_multiplier_is_synced_only_from_finalized_headers() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::Origin::signed(crate::AccountId::from([1; 32]));
+
+		assert_noop!(
+			crate::BridgeConfig::sync_bridged_fee_multiplier(
+				relayer.clone(),
+				*b"unkn",
+				Default::default(),
+				vec![],
+			),
+			crate::bridge_config::Error::<Runtime>::UnknownBridgedChain,
+		);
+		assert_noop!(
+			crate::BridgeConfig::sync_bridged_fee_multiplier(
+				relayer,
+				WOCOCO_CHAIN_ID,
+				Default::default(),
+				vec![],
+			),
+			pallet_bridge_grandpa::Error::<Runtime, crate::WococoGrandpaInstance>::UnknownHeader,
+		);
+	});
+}
+
+#[test]
+fn bridged_fee_multiplier_proof_is_bounded_and_weighed_by_size() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::Origin::signed(crate::AccountId::from([1; 32]));
+		let weight = |storage_proof| {
+			crate::bridge_config::Call::<Runtime>::sync_bridged_fee_multiplier {
+				chain: WOCOCO_CHAIN_ID,
+				at: Default::default(),
+				storage_proof,
+			}
+			.get_dispatch_info()
+			.weight
+		};
+
+		assert!(weight(vec![vec![0; 1024]]) < weight(vec![vec![0; 8 * 1024]]));
+
+		for storage_proof in [vec![vec![0; 16 * 1024 + 1]], vec![vec![0]; 65]] {
+			assert_noop!(
+				crate::BridgeConfig::sync_bridged_fee_multiplier(
+					relayer.clone(),
+					WOCOCO_CHAIN_ID,
+					Default::default(),
+					storage_proof,
+				),
+				crate::bridge_config::Error::<Runtime>::StorageProofTooLarge,
+			);
+		}
+	});
+}
+
+#[test]
+fn bridged_fee_multiplier_sync_is_free_only_if_it_changes_the_multiplier() {
+	new_test_ext().execute_with(|| {
+		let relayer = crate::AccountId::from([1; 32]);
+		let number_key = storage_value_key("System", "Number").0;
+		let fee_multiplier_key = storage_value_key("TransactionPayment", "NextFeeMultiplier").0;
+		let mut wococo = MockBridgedChain::new();
+		let sync = |wococo: &mut MockBridgedChain, fee_multiplier: FixedU128| {
+			let block_number = *wococo.best_header().number() + 1;
+			wococo.insert_raw(number_key.clone(), block_number.encode());
+			wococo.insert_raw(fee_multiplier_key.clone(), fee_multiplier.encode());
+			let (header, justification) = wococo.finalize_header();
+			assert_ok!(crate::BridgeWococoGrandpa::submit_finality_proof(
+				crate::Origin::signed(relayer.clone()),
+				Box::new(header),
+				justification,
+			));
+
+			crate::BridgeConfig::sync_bridged_fee_multiplier(
+				crate::Origin::signed(relayer.clone()),
+				WOCOCO_CHAIN_ID,
+				wococo.best_header_hash(),
+				wococo.prove_storage(&[number_key.clone(), fee_multiplier_key.clone()]),
+			)
+			.map(|post_info| post_info.pays_fee)
+		};
+
+		assert_ok!(crate::BridgeWococoGrandpa::initialize(
+			crate::Origin::root(),
+			wococo.initialization_data(),
+		));
+		assert_eq!(sync(&mut wococo, FixedU128::from(2)), Ok(Pays::No));
+		assert_eq!(sync(&mut wococo, FixedU128::from(2)), Ok(Pays::Yes));
+		assert_eq!(sync(&mut wococo, FixedU128::from(3)), Ok(Pays::No));
+		assert_eq!(
+			crate::BridgeConfig::bridged_fee_multiplier(WOCOCO_CHAIN_ID),
+			Some((3, FixedU128::from(3))),
+		);
+	});
+}
+
+#[test]
+fn delivery_confirmations_are_forwarded_only_for_the_lane() {
+	parameter_types! {
+		const HookLane: LaneId = [0, 0, 0, 1];
+	}
+
+	struct WeightPerMessage;
+	impl OnDeliveryConfirmed for WeightPerMessage {
+		fn on_messages_delivered(_lane: &LaneId, messages: &DeliveredMessages) -> Weight {
+			messages.total_messages()
+		}
+	}
+
+	type Hook = OnLaneDeliveryConfirmed<HookLane, WeightPerMessage>;
+	let mut messages = DeliveredMessages::new(1, true);
+	messages.note_dispatched_message(false);
+	assert_eq!(Hook::on_messages_delivered(&[0, 0, 0, 1], &messages), 2);
+	assert_eq!(Hook::on_messages_delivered(&[0, 0, 0, 0], &messages), 0);
+}
+
+#[test]
+fn accepted_messages_are_forwarded_only_for_the_lane() {
+	parameter_types! {
+		const HookLane: LaneId = [0, 0, 0, 1];
+	}
+
+	struct WeightOfNonce;
+	impl OnMessageAccepted for WeightOfNonce {
+		fn on_messages_accepted(_lane: &LaneId, message: &MessageNonce) -> Weight {
+			*message
+		}
+	}
+
+	type Hook = OnLaneMessageAccepted<HookLane, WeightOfNonce>;
+	assert_eq!(Hook::on_messages_accepted(&[0, 0, 0, 1], &7), 7);
+	assert_eq!(Hook::on_messages_accepted(&[0, 0, 0, 0], &7), 0);
+	assert_eq!(<(Hook, WeightOfNonce)>::on_messages_accepted(&[0, 0, 0, 1], &7), 14);
+}
+
+#[test]
+fn verify_inbound_messages_lane_succeeds() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			verify_inbound_messages_lane(proved_messages([0, 0, 0, 0])),
+			Ok(proved_messages([0, 0, 0, 0])),
+		);
+	});
+}
+
+#[test]
+fn verify_inbound_messages_lane_fails() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(
+			verify_inbound_messages_lane(proved_messages([0, 0, 0, 1])),
+			Err(BridgeError::InboundLaneDisabled),
+		);
+
+		let proved_messages = proved_messages([0, 0, 0, 0])
+			.into_iter()
+			.chain(proved_messages([0, 0, 0, 1]))
+			.collect();
+		assert_eq!(
+			verify_inbound_messages_lane(proved_messages),
+			Err(BridgeError::InboundLaneDisabled),
+		);
+
+		assert_ok!(crate::BridgeConfig::set_lane_enabled(
+			crate::Origin::root(),
+			[0, 0, 0, 0],
+			false
+		));
+		assert_eq!(
+			verify_inbound_messages_lane(proved_messages([0, 0, 0, 0])),
+			Err(BridgeError::InboundLaneDisabled),
+		);
+	});
+}
+
+#[test]
+fn maximal_outbound_payload_size_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let payload = |size| {
+			let call = vec![0; size as usize];
+			ToWococoMessagePayload {
+				spec_version: 0,
+				weight: *WococoAtRococo::message_weight_limits(&call).start(),
+				origin: CallOrigin::SourceRoot,
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call,
+			}
+		};
+		let set_maximal_outbound_payload_size = |size| {
+			crate::BridgeConfig::set_maximal_outbound_payload_size(crate::Origin::root(), size)
+		};
+		let max_size = crate::MaxOutboundPayloadSize::get();
+
+		assert_eq!(crate::BridgeConfig::maximal_outbound_payload_size(), max_size);
+		assert_ok!(WococoAtRococo::verify_message(&payload(max_size)));
+		assert_eq!(
+			WococoAtRococo::verify_message(&payload(max_size + 1)),
+			Err(BridgeError::InvalidMessage),
+		);
+
+		// governance restricts the size during incident
+		assert_ok!(set_maximal_outbound_payload_size(Some(100)));
+		assert_eq!(crate::BridgeConfig::maximal_outbound_payload_size(), 100);
+		assert_ok!(WococoAtRococo::verify_message(&payload(100)));
+		assert_eq!(WococoAtRococo::verify_message(&payload(101)), Err(BridgeError::InvalidMessage));
+
+		// and lifts the restriction after the incident
+		assert_ok!(set_maximal_outbound_payload_size(None));
+		assert_ok!(WococoAtRococo::verify_message(&payload(max_size)));
+
+		assert_noop!(
+			set_maximal_outbound_payload_size(Some(max_size + 1)),
+			crate::bridge_config::Error::<Runtime>::OutboundPayloadSizeAboveLimit,
+		);
+		assert_noop!(
+			crate::BridgeConfig::set_maximal_outbound_payload_size(
+				crate::Origin::signed(crate::AccountId::from([1; 32])),
+				Some(100),
+			),
+			DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn operating_mode_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let payload = ToWococoMessagePayload {
+			spec_version: 0,
+			weight: 0,
+			origin: CallOrigin::SourceRoot,
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
+			call: vec![],
+		};
+		let proof = || messages_target::FromBridgedChainMessagesProof {
+			bridged_header_hash: Default::default(),
+			storage_proof: vec![],
+			storage_proof_version: Default::default(),
+			lane: [0, 0, 0, 0],
+			nonces_start: 1,
+			nonces_end: 1,
+		};
+		let set_operating_mode = |operating_mode| {
+			assert_ok!(crate::BridgeConfig::set_operating_mode(
+				crate::Origin::root(),
+				operating_mode
+			));
+		};
+
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Normal);
+		assert_ne!(
+			WococoAtRococo::verify_message(&payload),
+			Err(BridgeError::OutboundMessagesRejected),
+		);
+		assert_ne!(
+			WococoAtRococo::verify_messages_proof(proof(), 1),
+			Err(BridgeError::BridgeHalted),
+		);
+
+		set_operating_mode(OperatingMode::RejectingOutboundMessages);
+		assert_eq!(
+			WococoAtRococo::verify_message(&payload),
+			Err(BridgeError::OutboundMessagesRejected),
+		);
+		assert_ne!(
+			WococoAtRococo::verify_messages_proof(proof(), 1),
+			Err(BridgeError::BridgeHalted),
+		);
+
+		set_operating_mode(OperatingMode::Halted);
+		assert_eq!(
+			WococoAtRococo::verify_message(&payload),
+			Err(BridgeError::OutboundMessagesRejected),
+		);
+		assert_eq!(
+			WococoAtRococo::verify_messages_proof(proof(), 1),
+			Err(BridgeError::BridgeHalted),
+		);
+
+		assert_noop!(
+			crate::BridgeConfig::set_operating_mode(
+				crate::Origin::signed(crate::AccountId::from([1; 32])),
+				OperatingMode::Normal
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn bridge_errors_are_reported_by_messages_pallet() {
+	new_test_ext().execute_with(|| {
+		type MessagesError =
+			pallet_bridge_messages::Error<Runtime, crate::AtRococoWithWococoMessagesInstance>;
+
+		let relayer = crate::AccountId::from([1; 32]);
+		let proof = || messages_target::FromBridgedChainMessagesProof {
+			bridged_header_hash: Default::default(),
+			storage_proof: vec![],
+			storage_proof_version: Default::default(),
+			lane: [0, 0, 0, 0],
+			nonces_start: 1,
+			nonces_end: 1,
+		};
+		let receive_messages_proof = || {
+			crate::BridgeWococoMessages::receive_messages_proof(
+				crate::Origin::signed(relayer.clone()),
+				relayer.clone(),
+				proof(),
+				1,
+				0,
+			)
+		};
+
+		// the common verification has failed, so the reason is only logged
+		assert_noop!(receive_messages_proof(), MessagesError::InvalidMessagesProof);
+
+		// the bridge-specific reason is reported to the relayer
+		assert_ok!(crate::BridgeConfig::set_operating_mode(
+			crate::Origin::root(),
+			OperatingMode::Halted
+		));
+		assert_noop!(receive_messages_proof(), MessagesError::InboundMessagesRejected);
+	});
+}
+
+#[test]
+fn messages_and_delivery_proofs_are_verified_against_finalized_headers() {
+	new_test_ext().execute_with(|| {
+		let pallet_name = bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
+		let relayer = crate::AccountId::from([1; 32]);
+		let message = MessageData { payload: vec![42], fee: 1 };
+		let inbound_lane_data = InboundLaneData {
+			relayers: vec![UnrewardedRelayer {
+				relayer: relayer.clone(),
+				messages: DeliveredMessages::new(1, true),
+			}]
+			.into_iter()
+			.collect(),
+			last_confirmed_nonce: 0,
+		};
+		let mut wococo = MockBridgedChain::new();
+		let finalize_header = |wococo: &mut MockBridgedChain| {
+			let (header, justification) = wococo.finalize_header();
+			assert_ok!(crate::BridgeWococoGrandpa::submit_finality_proof(
+				crate::Origin::signed(relayer.clone()),
+				Box::new(header),
+				justification,
+			));
+		};
+		let verify_messages_proof =
+			|wococo: &MockBridgedChain, lane, nonces: RangeInclusive<MessageNonce>| {
+				let messages_count = (nonces.end() - nonces.start() + 1) as u32;
+				WococoAtRococo::verify_messages_proof(
+					wococo.messages_proof(pallet_name, lane, nonces, false),
+					messages_count,
+				)
+			};
+
+		assert_ok!(crate::BridgeWococoGrandpa::initialize(
+			crate::Origin::root(),
+			wococo.initialization_data(),
+		));
+		wococo.insert_message(pallet_name, &[0, 0, 0, 0], 1, &message);
+		wococo.insert_message(pallet_name, &[0, 0, 0, 1], 1, &message);
+		wococo.insert_inbound_lane_data(pallet_name, &[0, 0, 0, 0], &inbound_lane_data);
+
+		// proofs are rejected until the header is finalized by the GRANDPA pallet
+		wococo.finalize_header();
+		assert_eq!(
+			verify_messages_proof(&wococo, [0, 0, 0, 0], 1..=1),
+			Err(BridgeError::InvalidMessagesProof),
+		);
+		assert_eq!(
+			WococoAtRococo::verify_messages_delivery_proof(
+				wococo.messages_delivery_proof(pallet_name, [0, 0, 0, 0]),
+			),
+			Err(BridgeError::InvalidMessagesDeliveryProof),
+		);
+
+		finalize_header(&mut wococo);
+		let proved_messages = verify_messages_proof(&wococo, [0, 0, 0, 0], 1..=1).unwrap();
+		assert_eq!(
+			proved_messages.get(&[0, 0, 0, 0]).map(|lane| lane.messages.clone()),
+			Some(vec![Message {
+				key: MessageKey { lane_id: [0, 0, 0, 0], nonce: 1 },
+				data: message.clone(),
+			}]),
+		);
+		assert_eq!(
+			WococoAtRococo::verify_messages_delivery_proof(
+				wococo.messages_delivery_proof(pallet_name, [0, 0, 0, 0]),
+			),
+			Ok(([0, 0, 0, 0], inbound_lane_data)),
+		);
+
+		// proofs of missing messages and of messages of disabled lanes are rejected
+		assert_eq!(
+			verify_messages_proof(&wococo, [0, 0, 0, 0], 1..=2),
+			Err(BridgeError::InvalidMessagesProof),
+		);
+		assert_eq!(
+			verify_messages_proof(&wococo, [0, 0, 0, 1], 1..=1),
+			Err(BridgeError::InboundLaneDisabled),
+		);
+
+		// proofs, generated for the storage that is not committed to by the header, are
+		// rejected
+		wococo.insert_message(pallet_name, &[0, 0, 0, 0], 2, &message);
+		assert_eq!(
+			verify_messages_proof(&wococo, [0, 0, 0, 0], 1..=2),
+			Err(BridgeError::InvalidMessagesProof),
+		);
+		finalize_header(&mut wococo);
+		assert_ok!(verify_messages_proof(&wococo, [0, 0, 0, 0], 1..=2));
+	});
+}
+
+#[test]
+fn bridge_is_halted_when_authority_set_stalls() {
+	new_test_ext().execute_with(|| {
+		use frame_support::traits::OnInitialize;
+
+		let max_age = crate::MaxAuthoritySetAge::get();
+
+		// authority sets are not tracked until the GRANDPA pallets are initialized
+		crate::BridgeConfig::on_initialize(max_age + 2);
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Normal);
+
+		frame_system::Pallet::<Runtime>::set_block_number(1);
+		assert_ok!(crate::BridgeWococoGrandpa::initialize(
+			crate::Origin::root(),
+			bp_header_chain::InitializationData {
+				header: Box::new(<bp_wococo::Header as sp_runtime::traits::Header>::new(
+					0,
+					Default::default(),
+					Default::default(),
+					Default::default(),
+					Default::default(),
+				)),
+				authority_list: vec![],
+				set_id: 0,
+				is_halted: false,
+			},
+		));
+
+		crate::BridgeConfig::on_initialize(max_age + 1);
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Normal);
+		crate::BridgeConfig::on_initialize(max_age + 2);
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Halted);
+
+		// governance resumes the bridge for another `MaxAuthoritySetAge` blocks
+		frame_system::Pallet::<Runtime>::set_block_number(max_age + 2);
+		assert_noop!(
+			crate::BridgeConfig::resume_after_authority_set_stall(crate::Origin::signed(
+				crate::AccountId::from([1; 32])
+			)),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(crate::BridgeConfig::resume_after_authority_set_stall(crate::Origin::root()));
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Normal);
+
+		crate::BridgeConfig::on_initialize(2 * max_age + 2);
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Normal);
+		crate::BridgeConfig::on_initialize(2 * max_age + 3);
+		assert_eq!(crate::BridgeConfig::operating_mode(), OperatingMode::Halted);
+	});
+}
+
+#[test]
+fn obsolete_headers_and_messages_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let is_rejected = |call: crate::Call| {
+			BridgeRejectObsoleteHeadersAndMessages
+				.validate(&crate::AccountId::from([1; 32]), &call, &Default::default(), 0)
+				.is_err()
+		};
+		let header = |number| {
+			<bp_wococo::Header as sp_runtime::traits::Header>::new(
+				number,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			)
+		};
+		let header_import = |number| {
+			crate::Call::BridgeWococoGrandpa(pallet_bridge_grandpa::Call::submit_finality_proof {
+				finality_target: Box::new(header(number)),
+				justification: Decode::decode(&mut TrailingZeroInput::zeroes()).unwrap(),
+			})
+		};
+		let messages_delivery = |nonces_end| {
+			crate::Call::BridgeWococoMessages(
+				pallet_bridge_messages::Call::receive_messages_proof {
+					relayer_id_at_bridged_chain: crate::AccountId::from([1; 32]),
+					proof: messages_target::FromBridgedChainMessagesProof {
+						bridged_header_hash: Default::default(),
+						storage_proof: vec![],
+						storage_proof_version: Default::default(),
+						lane: [0, 0, 0, 0],
+						nonces_start: 1,
+						nonces_end,
+					},
+					messages_count: nonces_end as u32,
+					dispatch_weight: 0,
+				},
+			)
+		};
+
+		assert_ok!(crate::BridgeWococoGrandpa::initialize(
+			crate::Origin::root(),
+			bp_header_chain::InitializationData {
+				header: Box::new(header(10)),
+				authority_list: vec![],
+				set_id: 0,
+				is_halted: false,
+			},
+		));
+		assert!(is_rejected(header_import(9)));
+		assert!(is_rejected(header_import(10)));
+		assert!(!is_rejected(header_import(11)));
+
+		pallet_bridge_messages::InboundLanes::<
+			Runtime,
+			crate::AtRococoWithWococoMessagesInstance,
+		>::insert(
+			[0, 0, 0, 0],
+			InboundLaneData { relayers: Default::default(), last_confirmed_nonce: 5 },
+		);
+		assert!(is_rejected(messages_delivery(5)));
+		assert!(!is_rejected(messages_delivery(6)));
+
+		// calls that are not bridge headers or messages are never rejected
+		assert!(!is_rejected(crate::Call::System(frame_system::Call::remark { remark: vec![] })));
+	});
+}
+
+#[test]
+fn messages_deliveries_are_prioritized_by_undelivered_messages() {
+	new_test_ext().execute_with(|| {
+		let priority = |nonces_start, nonces_end| {
+			let call = crate::Call::BridgeWococoMessages(
+				pallet_bridge_messages::Call::receive_messages_proof {
+					relayer_id_at_bridged_chain: crate::AccountId::from([1; 32]),
+					proof: messages_target::FromBridgedChainMessagesProof {
+						bridged_header_hash: Default::default(),
+						storage_proof: vec![],
+						storage_proof_version: Default::default(),
+						lane: [0, 0, 0, 0],
+						nonces_start,
+						nonces_end,
+					},
+					messages_count: (nonces_end - nonces_start + 1) as u32,
+					dispatch_weight: 0,
+				},
+			);
+			BridgeRejectObsoleteHeadersAndMessages
+				.validate(&crate::AccountId::from([1; 32]), &call, &Default::default(), 0)
+				.map(|validity| validity.priority)
+		};
+
+		assert_eq!(priority(1, 1), Ok(PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE));
+		assert_eq!(priority(1, 10), Ok(10 * PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE));
+
+		// already delivered messages don't count
+		pallet_bridge_messages::InboundLanes::<
+			Runtime,
+			crate::AtRococoWithWococoMessagesInstance,
+		>::insert(
+			[0, 0, 0, 0],
+			InboundLaneData { relayers: Default::default(), last_confirmed_nonce: 5 },
+		);
+		assert_eq!(priority(1, 10), Ok(5 * PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE));
+		assert_eq!(priority(6, 10), Ok(5 * PRIORITY_BOOST_PER_UNDELIVERED_MESSAGE));
+		assert_eq!(priority(1, 5), Err(InvalidTransaction::Stale.into()));
+	});
+}
+
+#[test]
+fn relayer_is_refunded_for_delivering_new_messages() {
+	new_test_ext().execute_with(|| {
+		let lane_id = [0, 0, 0, 0];
+		let alice = crate::AccountId::from([1; 32]);
+		let header_import = |bridged_chain| {
+			let header = Box::new(<bp_wococo::Header as sp_runtime::traits::Header>::new(
+				1,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			));
+			let justification = Decode::decode(&mut TrailingZeroInput::zeroes()).unwrap();
+			match bridged_chain {
+				ROCOCO_CHAIN_ID => crate::Call::BridgeRococoGrandpa(
+					pallet_bridge_grandpa::Call::submit_finality_proof {
+						finality_target: header,
+						justification,
+					},
+				),
+				_ => crate::Call::BridgeWococoGrandpa(
+					pallet_bridge_grandpa::Call::submit_finality_proof {
+						finality_target: header,
+						justification,
+					},
+				),
+			}
+		};
+		let messages_delivery = crate::Call::BridgeWococoMessages(
+			pallet_bridge_messages::Call::receive_messages_proof {
+				relayer_id_at_bridged_chain: alice.clone(),
+				proof: messages_target::FromBridgedChainMessagesProof {
+					bridged_header_hash: Default::default(),
+					storage_proof: vec![],
+					storage_proof_version: Default::default(),
+					lane: lane_id,
+					nonces_start: 1,
+					nonces_end: 1,
+				},
+				messages_count: 1,
+				dispatch_weight: 0,
+			},
+		);
+		let batch = |calls| crate::Call::Utility(pallet_utility::Call::batch_all { calls });
+		let pre_dispatch = |call: &crate::Call| {
+			RefundRelayerForMessagesDelivery
+				.pre_dispatch(&alice, call, &Default::default(), 0)
+				.unwrap()
+		};
+
+		// only deliveries of messages, optionally batched with the import of the bridged header
+		// are refunded
+		let expected_pre = Some((alice.clone(), WOCOCO_CHAIN_ID, lane_id, 0));
+		assert_eq!(pre_dispatch(&messages_delivery), expected_pre);
+		assert_eq!(
+			pre_dispatch(&batch(vec![header_import(WOCOCO_CHAIN_ID), messages_delivery.clone()])),
+			expected_pre,
+		);
+		assert_eq!(
+			pre_dispatch(&batch(vec![header_import(ROCOCO_CHAIN_ID), messages_delivery.clone()])),
+			None,
+		);
+		assert_eq!(
+			pre_dispatch(&batch(vec![messages_delivery.clone(), header_import(WOCOCO_CHAIN_ID)])),
+			None,
+		);
+		assert_eq!(pre_dispatch(&header_import(WOCOCO_CHAIN_ID)), None);
+
+		let len = 100;
+		let fee = pallet_transaction_payment::Pallet::<Runtime>::compute_actual_fee(
+			len as u32,
+			&Default::default(),
+			&Default::default(),
+			0,
+		);
+		let existential_deposit = crate::ExistentialDeposit::get();
+		Balances::make_free_balance_be(&alice, existential_deposit);
+		Balances::make_free_balance_be(
+			&crate::RelayerRefundsAccount::get(),
+			existential_deposit + fee,
+		);
+		let dispatch = |result: DispatchResult| {
+			let pre = pre_dispatch(&messages_delivery);
+			if result.is_ok() {
+				let mut lane = pallet_bridge_messages::InboundLanes::<
+					Runtime,
+					crate::AtRococoWithWococoMessagesInstance,
+				>::get(lane_id);
+				lane.last_confirmed_nonce += 1;
+				pallet_bridge_messages::InboundLanes::<
+					Runtime,
+					crate::AtRococoWithWococoMessagesInstance,
+				>::insert(lane_id, lane);
+			}
+			assert_ok!(RefundRelayerForMessagesDelivery::post_dispatch(
+				Some(pre),
+				&Default::default(),
+				&Default::default(),
+				len,
+				&result,
+			));
+		};
+
+		// failed deliveries are not refunded
+		dispatch(Err(DispatchError::BadOrigin));
+		assert_eq!(Balances::free_balance(&alice), existential_deposit);
+
+		// successful deliveries are refunded while the refunds account can afford it
+		dispatch(Ok(()));
+		assert_eq!(Balances::free_balance(&alice), existential_deposit + fee);
+		dispatch(Ok(()));
+		assert_eq!(Balances::free_balance(&alice), existential_deposit + fee);
+	});
+}
@@ -0,0 +1,161 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export of XCM messages to the bridged chains.
+
+use super::*;
+
+/// Location of the bridged chain with the given id, as it is seen from this chain.
+pub fn bridged_chain_location(chain_id: ChainId) -> MultiLocation {
+	MultiLocation::new(1, X1(GeneralKey(chain_id.to_vec())))
+}
+
+/// Network of the bridged chain with the given id.
+pub fn bridged_network(chain_id: ChainId) -> NetworkId {
+	NetworkId::Named(chain_id.to_vec())
+}
+
+/// Origin of XCM messages that are sent from the bridged chain with the given id.
+///
+/// Messages sent by the root of the bridged chain originate from the chain itself and messages
+/// sent by an account originate from the account at the bridged chain. Messages proving control
+/// over an account of this chain can't be executed as XCM messages.
+pub fn bridged_origin<Signer, Signature>(
+	chain_id: ChainId,
+	origin: &CallOrigin<crate::AccountId, Signer, Signature>,
+) -> Option<MultiLocation> {
+	let mut location = bridged_chain_location(chain_id);
+	match origin {
+		CallOrigin::SourceRoot => (),
+		CallOrigin::SourceAccount(account) => location
+			.push_interior(AccountId32 { network: NetworkId::Any, id: account.clone().into() })
+			.ok()?,
+		CallOrigin::TargetAccount(..) => return None,
+	}
+	Some(location)
+}
+
+/// Converts locations at the bridged chains into accounts of this chain.
+///
+/// The accounts are derived from the hash of the location, so they don't overlap with any
+/// account of this chain.
+pub struct BridgedLocationConvertsVia;
+
+impl Convert<MultiLocation, crate::AccountId> for BridgedLocationConvertsVia {
+	fn convert_ref(location: impl Borrow<MultiLocation>) -> Result<crate::AccountId, ()> {
+		let location = location.borrow();
+		let chain_id = match location {
+			MultiLocation { parents: 1, interior: X1(GeneralKey(chain_id)) } => chain_id,
+			MultiLocation {
+				parents: 1,
+				interior: X2(GeneralKey(chain_id), AccountId32 { .. }),
+			} => chain_id,
+			_ => return Err(()),
+		};
+		if ![ROCOCO_CHAIN_ID, WOCOCO_CHAIN_ID, WESTEND_CHAIN_ID]
+			.iter()
+			.any(|known_chain_id| chain_id[..] == known_chain_id[..])
+		{
+			return Err(())
+		}
+		Ok((b"bridged", location).using_encoded(blake2_256).into())
+	}
+
+	fn reverse_ref(_: impl Borrow<crate::AccountId>) -> Result<MultiLocation, ()> {
+		Err(())
+	}
+}
+
+/// Exports XCM messages to the bridged chain of the message bridge `B`, over the messages pallet
+/// instance `I`.
+///
+/// Messages, sent to the [`bridged_chain_location`], are encoded into the payload of the bridge
+/// message and sent over the lane that governance has selected for the [`bridged_network`]. The
+/// lane must have the `Xcm` dispatch mode at the bridged chain, which executes the message with
+/// the location of this chain as origin.
+///
+/// The sender of the message is the location that `pallet_xcm` prepends to the message with the
+/// `DescendOrigin` instruction. The bridge message is sent by the account of the sender, so the
+/// export fee - the minimal fee of the message - is paid by the sender, and the message is executed
+/// with the sender location at the bridged chain. The account must be an allowed sender of the
+/// lane. Messages without the sender are not exported.
+pub struct BridgeXcmExporter<B, I>(PhantomData<(B, I)>);
+
+impl<B, I> SendXcm for BridgeXcmExporter<B, I>
+where
+	B: MessageBridge,
+	B::ThisChain: ChainWithMessages<Balance = Balance>,
+	I: 'static,
+	Runtime: pallet_bridge_messages::Config<
+		I,
+		OutboundPayload = messages_source::FromThisChainMessagePayload<B>,
+		OutboundMessageFee = Balance,
+	>,
+{
+	fn send_xcm(destination: impl Into<MultiLocation>, message: Xcm<()>) -> SendResult {
+		let destination = destination.into();
+		if destination != bridged_chain_location(B::BRIDGED_CHAIN_ID) {
+			return Err(SendError::CannotReachDestination(destination, message))
+		}
+		let lane = crate::BridgeConfig::xcm_export_lane(bridged_network(B::BRIDGED_CHAIN_ID))
+			.ok_or(SendError::Unroutable)?;
+
+		let mut instructions = message.0;
+		let sender = match instructions.first() {
+			Some(Instruction::DescendOrigin(interior)) =>
+				crate::xcm_config::SovereignAccountOf::convert_ref(MultiLocation {
+					parents: 0,
+					interior: interior.clone(),
+				})
+				.map_err(|()| SendError::Transport("Unknown message sender"))?,
+			_ => return Err(SendError::Transport("Unknown message sender")),
+		};
+		instructions.remove(0);
+		let message = Xcm(instructions);
+
+		// the bridged chain is running the same runtime, so it weighs the message the same way
+		let mut xcm = Xcm::<crate::Call>::from(message.clone());
+		let weight = <XcmConfig as xcm_executor::Config>::Weigher::weight(&mut xcm)
+			.map_err(|()| SendError::DestinationUnsupported)?;
+		let payload = MessagePayload {
+			spec_version: crate::VERSION.spec_version,
+			weight,
+			origin: CallOrigin::SourceAccount(sender.clone()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			expires_at: None,
+			call: VersionedXcm::from(message).encode(),
+		};
+		let fee = messages_source::minimal_message_fee::<B>(&lane, &payload, None)
+			.map_err(SendError::Transport)?;
+
+		<MessagesPallet<I> as MessagesBridge<_, _, _, _>>::send_message(
+			crate::Origin::signed(sender),
+			lane,
+			payload,
+			fee,
+		)
+		.map(drop)
+		.map_err(|err| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to export XCM message over the lane {:?}: {:?}",
+				lane,
+				err,
+			);
+			SendError::Transport("Failed to send bridge message")
+		})
+	}
+}
@@ -0,0 +1,313 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet that updates the conversion rate of the Rococo <> Wococo message bridge from a price
+//! oracle.
+//!
+//! The offchain worker of the pallet is optional. It only runs on nodes that have configured the
+//! oracle URL in the persistent offchain storage (under [`ORACLE_URL_KEY`]) and that have the key
+//! of an oracle account, authorized by governance, in their keystore. Every `UpdateInterval`
+//! blocks, it fetches the conversion rate from the oracle and submits it in an unsigned
+//! transaction with a payload signed by the oracle account.
+//!
+//! The oracle is expected to respond with the conversion rate in decimal notation, e.g. `1.25`.
+//! Submitted rates are saved like the `BridgedToThisConversionRate` parameter of the messages
+//! pallets, so they are limited by the `ConversionRateChangeLimit` parameter as well.
+
+use crate::bridge_messages::BridgeMessagesParameter;
+use bp_messages::Parameter as MessagesParameter;
+use frame_support::traits::Get;
+use frame_system::offchain::{
+	AppCrypto, SendTransactionTypes, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes,
+};
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::{
+	offchain::{http, storage::StorageValueRef, Duration},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+		ValidTransaction,
+	},
+	FixedPointNumber, FixedU128, RuntimeDebug,
+};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+/// Key type of the oracle accounts.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"brco");
+
+/// Key of the oracle URL in the persistent offchain storage.
+pub const ORACLE_URL_KEY: &[u8] = b"bridge-rate-oracle::url";
+
+/// Time the offchain worker waits for the oracle response.
+const ORACLE_REQUEST_TIMEOUT_MS: u64 = 2_000;
+
+/// Crypto of the oracle accounts.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// Identifies the oracle accounts in the keystore.
+	pub struct OracleId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for OracleId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// Conversion rate fetched from the oracle, signed by an oracle account.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ConversionRatePayload<Public, BlockNumber> {
+	/// Block at which the conversion rate has been fetched.
+	pub block_number: BlockNumber,
+	/// The conversion rate: `ThisTokens = BridgedTokens * conversion_rate`.
+	pub conversion_rate: FixedU128,
+	/// The oracle account that has signed the payload.
+	pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for ConversionRatePayload<T::Public, T::BlockNumber> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Configuration for the bridge rate oracle pallet.
+	#[pallet::config]
+	pub trait Config: SendTransactionTypes<Call<Self>> + SigningTypes {
+		/// The overreaching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Identifies the oracle accounts in the keystore.
+		type OracleId: AppCrypto<Self::Public, Self::Signature>;
+
+		/// The origin that may authorize oracle accounts.
+		type OracleOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Minimal number of blocks between two conversion rate updates.
+		#[pallet::constant]
+		type UpdateInterval: Get<Self::BlockNumber>;
+
+		/// Maximal number of oracle accounts.
+		#[pallet::constant]
+		type MaxOracles: Get<u32>;
+
+		/// Priority of the transactions submitting conversion rates.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The oracle accounts have been replaced.
+		OraclesChanged,
+		/// The oracle account has submitted the conversion rate.
+		ConversionRateSubmitted(T::Public, FixedU128),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// There are more than `MaxOracles` oracle accounts.
+		TooManyOracles,
+	}
+
+	/// Accounts that may sign conversion rates.
+	#[pallet::storage]
+	#[pallet::getter(fn oracles)]
+	pub(crate) type Oracles<T: Config> =
+		StorageValue<_, BoundedVec<T::Public, T::MaxOracles>, ValueQuery>;
+
+	/// Block at which the last submitted conversion rate has been fetched.
+	#[pallet::storage]
+	#[pallet::getter(fn last_update_at)]
+	pub(crate) type LastUpdateAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn offchain_worker(now: T::BlockNumber) {
+			if now < Self::next_update_at() {
+				return
+			}
+
+			if let Err(e) = Self::submit_oracle_conversion_rate(now) {
+				log::debug!(
+					target: "runtime::bridge",
+					"Conversion rate from the oracle has not been submitted at {:?}: {}",
+					now,
+					e,
+				);
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Replace the accounts that may sign conversion rates.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_oracles(origin: OriginFor<T>, oracles: Vec<T::Public>) -> DispatchResult {
+			T::OracleOrigin::ensure_origin(origin)?;
+			let oracles: BoundedVec<_, _> =
+				oracles.try_into().map_err(|_| Error::<T>::TooManyOracles)?;
+
+			Oracles::<T>::put(oracles);
+			Self::deposit_event(Event::OraclesChanged);
+			Ok(())
+		}
+
+		/// Update the conversion rate of the bridge with the rate signed by an oracle account.
+		///
+		/// The payload is verified in `validate_unsigned`.
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 4))]
+		pub fn submit_conversion_rate(
+			origin: OriginFor<T>,
+			payload: ConversionRatePayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			LastUpdateAt::<T>::put(payload.block_number);
+			BridgeMessagesParameter::BridgedToThisConversionRate(payload.conversion_rate).save();
+			Self::deposit_event(Event::ConversionRateSubmitted(
+				payload.public,
+				payload.conversion_rate,
+			));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let (payload, signature) = match call {
+				Call::submit_conversion_rate { payload, signature } => (payload, signature),
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if !Oracles::<T>::get().contains(&payload.public) {
+				return InvalidTransaction::BadSigner.into()
+			}
+			if !SignedPayload::<T>::verify::<T::OracleId>(payload, signature.clone()) {
+				return InvalidTransaction::BadProof.into()
+			}
+			if payload.block_number < Self::next_update_at() {
+				return InvalidTransaction::Stale.into()
+			}
+			if payload.block_number > frame_system::Pallet::<T>::block_number() {
+				return InvalidTransaction::Future.into()
+			}
+
+			ValidTransaction::with_tag_prefix("BridgeRateOracle")
+				.priority(T::UnsignedPriority::get())
+				.and_provides(payload.block_number)
+				.longevity(5)
+				.propagate(true)
+				.build()
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the first block whose conversion rate may be submitted.
+	fn next_update_at() -> T::BlockNumber {
+		LastUpdateAt::<T>::get() + T::UpdateInterval::get()
+	}
+
+	/// Fetch the conversion rate from the oracle and submit it, signed by an oracle account from
+	/// the keystore.
+	fn submit_oracle_conversion_rate(now: T::BlockNumber) -> Result<(), &'static str> {
+		let url = StorageValueRef::persistent(ORACLE_URL_KEY)
+			.get::<Vec<u8>>()
+			.map_err(|_| "The oracle URL can't be decoded")?
+			.ok_or("The oracle URL is not configured")?;
+		let conversion_rate = fetch_conversion_rate(&url)?;
+
+		let (_, result) = Signer::<T, T::OracleId>::any_account()
+			.with_filter(Oracles::<T>::get().into_inner())
+			.send_unsigned_transaction(
+				|account| ConversionRatePayload {
+					block_number: now,
+					conversion_rate,
+					public: account.public.clone(),
+				},
+				|payload, signature| Call::submit_conversion_rate { payload, signature },
+			)
+			.ok_or("There is no oracle account in the keystore")?;
+		result.map_err(|()| "The transaction can't be submitted")
+	}
+}
+
+/// Fetch the conversion rate from the oracle.
+fn fetch_conversion_rate(url: &[u8]) -> Result<FixedU128, &'static str> {
+	let url = sp_std::str::from_utf8(url).map_err(|_| "The oracle URL is not valid UTF-8")?;
+	let deadline =
+		sp_io::offchain::timestamp().add(Duration::from_millis(ORACLE_REQUEST_TIMEOUT_MS));
+	let response = http::Request::get(url)
+		.deadline(deadline)
+		.send()
+		.map_err(|_| "The oracle request can't be sent")?
+		.try_wait(deadline)
+		.map_err(|_| "The oracle request has timed out")?
+		.map_err(|_| "The oracle request has failed")?;
+	if response.code != 200 {
+		return Err("The oracle has responded with an error")
+	}
+
+	parse_conversion_rate(&response.body().collect::<Vec<u8>>())
+		.ok_or("The oracle response is not a conversion rate")
+}
+
+/// Parses a conversion rate in decimal notation, e.g. `1.25`.
+pub(crate) fn parse_conversion_rate(rate: &[u8]) -> Option<FixedU128> {
+	let rate = sp_std::str::from_utf8(rate).ok()?.trim();
+	let (integer, fraction) = rate.split_once('.').unwrap_or((rate, ""));
+	let is_number = |digits: &str| digits.bytes().all(|digit| digit.is_ascii_digit());
+	if integer.is_empty() || !is_number(integer) || !is_number(fraction) {
+		return None
+	}
+
+	// `FixedU128` has 18 decimal places
+	let fraction_digits = fraction.len() as u32;
+	if fraction_digits > 18 {
+		return None
+	}
+	let fraction = match fraction_digits {
+		0 => 0,
+		_ => fraction.parse::<u128>().ok()? * 10u128.pow(18 - fraction_digits),
+	};
+	let integer = integer.parse::<u128>().ok()?.checked_mul(FixedU128::DIV)?;
+	Some(FixedU128::from_inner(integer.checked_add(fraction)?))
+}
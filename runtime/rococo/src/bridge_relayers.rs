@@ -0,0 +1,811 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pallet that keeps the rewards of the relayers of the Rococo <> Wococo message bridge.
+//!
+//! Instead of transferring the rewards to the relayers when the delivery of messages is
+//! confirmed, [`RewardsPot`] registers them per relayer and lane. The rewards stay in the relayer
+//! fund account until the relayers claim them.
+//!
+//! Relayers may also register for a lane by reserving a bond. Messages proofs of registered
+//! relayers are prioritized over proofs of other relayers, but their bond is slashed if they
+//! submit an invalid proof.
+//!
+//! The delivery confirmations also bring the dispatch results of the messages. A share of the fee
+//! of messages that haven't been dispatched at the bridged chain (e.g. because they have been
+//! refused by the call filter, or couldn't be decoded) is refunded to their submitters. Refunds
+//! are registered and claimed the same way as the rewards.
+//!
+//! Transaction fees of relayers that deliver new messages may be refunded from a separate refunds
+//! account (see `crate::bridge_messages::RefundRelayerForMessagesDelivery`).
+//!
+//! Accounts may choose to pay fees of their messages in the fee asset, instead of the native
+//! currency. The fee is converted to the fee asset by the `FeeAssetConversion` hook and is paid to
+//! the fee exchange account, which pays the fee to the relayer fund account in the native
+//! currency. Rewards and refunds are always paid in the native currency.
+//!
+//! Messages of subsidized lanes are sent without a fee. Relayers that deliver them are rewarded
+//! with a subsidy (see [`MessageSubsidy`]), which is transferred from the pre-funded subsidy
+//! account to the relayer fund account when the delivery is confirmed.
+//!
+//! Registered relayers may bid the minimal fee they're ready to deliver a message over the lane
+//! for. The lowest bid of the lane is then used as the minimal fee of new messages, instead of
+//! the estimated fee.
+//!
+//! The pallet also keeps statistics of outbound lanes, updated when the delivery of messages is
+//! confirmed: the number of delivered and undispatched messages, their fees and the latest
+//! relayer.
+//!
+//! The latest delivery confirmation of every outbound lane is remembered as well. Anyone may
+//! prove that it has been invalid, i.e. that the bridged chain hasn't received the confirmed
+//! messages, with a storage proof of the inbound lane at a later finalized header of the bridged
+//! chain. Then the bond of the confirming relayer is slashed and the lane is halted until
+//! governance reviews the misbehavior and enables the lane again.
+
+use bp_messages::{
+	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, SenderOrigin},
+	DeliveredMessages, LaneId, MessageKey, MessageNonce, OutboundLaneStats, UnrewardedRelayer,
+};
+use bp_runtime::ChainId;
+use frame_support::{
+	storage::with_transaction,
+	traits::{tokens::fungibles, Currency, ExistenceRequirement, Get, ReservableCurrency},
+	RuntimeDebug,
+};
+use pallet_bridge_messages::{
+	instant_payments::InstantCurrencyPayments, CancelledOutboundMessages,
+	OutboundLanes, OutboundMessageSubmitters, OutboundMessages,
+};
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_core::H256;
+use sp_runtime::{
+	traits::{Convert, Saturating, Zero},
+	DispatchError, Perbill, TransactionOutcome,
+};
+use sp_std::{
+	collections::vec_deque::VecDeque, marker::PhantomData, ops::RangeInclusive, prelude::*,
+};
+
+pub use pallet::*;
+
+/// Balance of the currency the rewards are paid in.
+pub type BalanceOf<T> =
+	<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Identifier of the asset that message fees may be paid in.
+pub type AssetIdOf<T> = <<T as Config>::FeeAssets as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::AssetId;
+
+/// Balance of the asset that message fees may be paid in.
+pub type AssetBalanceOf<T> = <<T as Config>::FeeAssets as fungibles::Inspect<
+	<T as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Currency that the account pays fees of its messages in.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub enum FeeCurrency {
+	/// The native currency.
+	Native,
+	/// The fee asset. The fee is converted from the native currency by `FeeAssetConversion`.
+	Asset,
+}
+
+impl Default for FeeCurrency {
+	fn default() -> Self {
+		FeeCurrency::Native
+	}
+}
+
+/// The latest delivery confirmation of an outbound lane.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct LaneConfirmation<AccountId, BlockNumber> {
+	/// Relayer that has submitted the confirmation.
+	pub relayer: AccountId,
+	/// Nonce of the latest message that has been confirmed as received by the bridged chain.
+	pub nonce: MessageNonce,
+	/// Number of the best finalized header of the bridged chain when the confirmation has been
+	/// accepted. The confirmation has been proved at this header or at one of its ancestors.
+	pub bridged_block: BlockNumber,
+}
+
+/// Verifies proofs of the state of inbound lanes at the bridged chains.
+pub trait BridgedInboundLanes<BlockNumber> {
+	/// Returns the number of the best finalized header of the bridged chain, or `None` if the
+	/// chain is not bridged.
+	fn best_finalized_number(chain: ChainId) -> Option<BlockNumber>;
+
+	/// Verifies the storage proof against the finalized header of the bridged chain and returns
+	/// the number of the header and the nonce of the latest message, that the inbound lane has
+	/// received by that header.
+	fn latest_received_nonce(
+		chain: ChainId,
+		at: H256,
+		storage_proof: Vec<Vec<u8>>,
+		lane: LaneId,
+	) -> Result<(BlockNumber, MessageNonce), DispatchError>;
+}
+
+/// Halts lanes, whose delivery confirmations have been proved invalid.
+pub trait HaltLane {
+	/// Stop sending and receiving messages over the lane until governance resumes it.
+	fn halt_lane(lane: LaneId);
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	/// Configuration for the bridge relayers pallet.
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overreaching event type.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// Currency the rewards are paid and the bonds are reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+
+		/// Account that holds the rewards until they are claimed.
+		type RelayerFundAccount: Get<Self::AccountId>;
+
+		/// Bond reserved from relayers registering for a lane.
+		#[pallet::constant]
+		type RelayerBond: Get<BalanceOf<Self>>;
+
+		/// Share of the fee that is refunded to submitters of messages that haven't been
+		/// dispatched at the bridged chain.
+		#[pallet::constant]
+		type UndispatchedMessageRefund: Get<Perbill>;
+
+		/// Account that transaction fees of messages deliveries are refunded from.
+		type RefundsAccount: Get<Self::AccountId>;
+
+		/// Account that relayers are paid the subsidies of messages of subsidized lanes from.
+		type SubsidyAccount: Get<Self::AccountId>;
+
+		/// Maximal number of relayer bids per lane.
+		#[pallet::constant]
+		type MaxBidsPerLane: Get<u32>;
+
+		/// Assets that message fees may be paid in, instead of `Currency`.
+		type FeeAssets: fungibles::Transfer<Self::AccountId>;
+
+		/// Asset of `FeeAssets` that message fees may be paid in.
+		type FeeAssetId: Get<AssetIdOf<Self>>;
+
+		/// Converts message fees from `Currency` to the fee asset. Returns `None` if the fee can't
+		/// be converted.
+		type FeeAssetConversion: Convert<BalanceOf<Self>, Option<AssetBalanceOf<Self>>>;
+
+		/// Account that receives message fees paid in the fee asset, and pays them to the relayer
+		/// fund account in `Currency`.
+		type FeeExchangeAccount: Get<Self::AccountId>;
+
+		/// Verifies proofs of the inbound lanes state, that invalid delivery confirmations are
+		/// reported with.
+		type BridgedInboundLanes: BridgedInboundLanes<Self::BlockNumber>;
+
+		/// Halts lanes with invalid delivery confirmations.
+		type LaneHalt: HaltLane;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The relayer has claimed its reward for the lane.
+		RewardClaimed(T::AccountId, LaneId, BalanceOf<T>),
+		/// The relayer has registered for the lane, reserving the bond.
+		RelayerRegistered(T::AccountId, LaneId, BalanceOf<T>),
+		/// The relayer has deregistered from the lane and got its bond back.
+		RelayerDeregistered(T::AccountId, LaneId),
+		/// The bond of the relayer has been slashed for submitting an invalid messages proof.
+		RelayerSlashed(T::AccountId, LaneId, BalanceOf<T>),
+		/// A share of the fee of the message that hasn't been dispatched at the bridged chain has
+		/// been refunded to its submitter.
+		MessageFeeRefunded(T::AccountId, LaneId, MessageNonce, BalanceOf<T>),
+		/// The transaction fee of the relayer that has delivered new messages to the lane has been
+		/// refunded.
+		TransactionFeeRefunded(T::AccountId, LaneId, BalanceOf<T>),
+		/// Relayers have been rewarded with subsidies for delivering messages of the subsidized
+		/// lane.
+		MessagesSubsidized(LaneId, BalanceOf<T>),
+		/// The relayer has bid the minimal fee of messages sent over the lane.
+		BidSubmitted(T::AccountId, LaneId, BalanceOf<T>),
+		/// The bid of the relayer for the lane has been cancelled.
+		BidCancelled(T::AccountId, LaneId),
+		/// The account now pays fees of its messages in the given currency.
+		FeeCurrencyChanged(T::AccountId, FeeCurrency),
+		/// The account has paid the message fee with the given amount of the fee asset.
+		FeePaidInAsset(T::AccountId, BalanceOf<T>, AssetBalanceOf<T>),
+		/// The delivery confirmation of the relayer has been proved invalid: the bridged chain
+		/// has only received messages of the lane up to the second nonce, instead of the first
+		/// one. The lane has been halted.
+		InvalidConfirmationReported(T::AccountId, ChainId, LaneId, MessageNonce, MessageNonce),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The relayer has no reward for the lane.
+		NoRewardForRelayer,
+		/// The reward couldn't be transferred from the relayer fund account.
+		FailedToPayReward,
+		/// The relayer is already registered for the lane.
+		AlreadyRegistered,
+		/// The relayer is not registered for the lane.
+		NotRegistered,
+		/// The relayer can't afford the bond.
+		FailedToReserveBond,
+		/// There are already `MaxBidsPerLane` bids for the lane.
+		TooManyBids,
+		/// The relayer has no bid for the lane.
+		NoBid,
+		/// There's no delivery confirmation of the lane to report.
+		NoConfirmation,
+		/// The lane state is proved at a header of the bridged chain, that has been finalized
+		/// before the confirmation.
+		OutdatedLaneProof,
+		/// The bridged chain has received all confirmed messages.
+		ValidConfirmation,
+		/// The chain is not bridged.
+		UnknownBridgedChain,
+		/// The storage proof has no valid inbound lane state of the bridged chain.
+		InvalidInboundLaneProof,
+	}
+
+	/// Unclaimed rewards of the relayers, per lane.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_reward)]
+	pub(crate) type RelayerRewards<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		LaneId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Bonds of the relayers registered for a lane.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_bond)]
+	pub(crate) type RegisteredRelayers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		LaneId,
+		BalanceOf<T>,
+		OptionQuery,
+	>;
+
+	/// Currencies that accounts pay fees of their messages in.
+	#[pallet::storage]
+	#[pallet::getter(fn fee_currency)]
+	pub(crate) type FeeCurrencies<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, FeeCurrency, ValueQuery>;
+
+	/// Bids of the relayers registered for a lane, ordered by the fee.
+	#[pallet::storage]
+	#[pallet::getter(fn relayer_bids)]
+	pub(crate) type RelayerBids<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		LaneId,
+		BoundedVec<(T::AccountId, BalanceOf<T>), T::MaxBidsPerLane>,
+		ValueQuery,
+	>;
+
+	/// Statistics of the outbound lanes to the bridged chains.
+	///
+	/// The number of sent messages isn't stored, because the messages pallet already tracks it
+	/// (see [`Pallet::outbound_lane_stats`]).
+	#[pallet::storage]
+	pub(crate) type OutboundLanesStats<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ChainId,
+		Blake2_128Concat,
+		LaneId,
+		OutboundLaneStats<T::AccountId, BalanceOf<T>>,
+		ValueQuery,
+	>;
+
+	/// The latest delivery confirmations of the outbound lanes to the bridged chains.
+	#[pallet::storage]
+	#[pallet::getter(fn latest_confirmation)]
+	pub(crate) type LatestConfirmations<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		ChainId,
+		Blake2_128Concat,
+		LaneId,
+		LaneConfirmation<T::AccountId, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Transfer the reward of the relayer for the lane from the relayer fund account.
+		///
+		/// Fee refunds of the message submitters are claimed with this call as well.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+		pub fn claim_rewards(origin: OriginFor<T>, lane: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			let reward = RelayerRewards::<T>::get(&relayer, lane);
+			ensure!(!reward.is_zero(), Error::<T>::NoRewardForRelayer);
+
+			T::Currency::transfer(
+				&T::RelayerFundAccount::get(),
+				&relayer,
+				reward,
+				// the relayer fund account must stay alive to receive message fees
+				ExistenceRequirement::KeepAlive,
+			)
+			.map_err(|_| Error::<T>::FailedToPayReward)?;
+			RelayerRewards::<T>::remove(&relayer, lane);
+
+			Self::deposit_event(Event::RewardClaimed(relayer, lane, reward));
+			Ok(())
+		}
+
+		/// Register the relayer for the lane, reserving the bond.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn register(origin: OriginFor<T>, lane: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			ensure!(
+				!RegisteredRelayers::<T>::contains_key(&relayer, lane),
+				Error::<T>::AlreadyRegistered,
+			);
+
+			let bond = T::RelayerBond::get();
+			T::Currency::reserve(&relayer, bond).map_err(|_| Error::<T>::FailedToReserveBond)?;
+			RegisteredRelayers::<T>::insert(&relayer, lane, bond);
+
+			Self::deposit_event(Event::RelayerRegistered(relayer, lane, bond));
+			Ok(())
+		}
+
+		/// Deregister the relayer from the lane, unreserving the bond.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn deregister(origin: OriginFor<T>, lane: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			let bond =
+				RegisteredRelayers::<T>::take(&relayer, lane).ok_or(Error::<T>::NotRegistered)?;
+			T::Currency::unreserve(&relayer, bond);
+			Self::remove_bid(&relayer, lane);
+
+			Self::deposit_event(Event::RelayerDeregistered(relayer, lane));
+			Ok(())
+		}
+
+		/// Bid the minimal fee the relayer is ready to deliver a message over the lane for.
+		///
+		/// The previous bid of the relayer for the lane is replaced. Only registered relayers may
+		/// bid.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 1))]
+		pub fn submit_bid(origin: OriginFor<T>, lane: LaneId, fee: BalanceOf<T>) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			ensure!(Self::is_registered(&relayer, lane), Error::<T>::NotRegistered);
+
+			let mut bids = RelayerBids::<T>::get(lane).into_inner();
+			bids.retain(|(bidder, _)| *bidder != relayer);
+			let position = bids.partition_point(|(_, bid)| *bid <= fee);
+			bids.insert(position, (relayer.clone(), fee));
+			let bids: BoundedVec<_, _> = bids.try_into().map_err(|_| Error::<T>::TooManyBids)?;
+			RelayerBids::<T>::insert(lane, bids);
+
+			Self::deposit_event(Event::BidSubmitted(relayer, lane, fee));
+			Ok(())
+		}
+
+		/// Choose the currency that fees of messages sent by the account are paid in.
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn set_fee_currency(origin: OriginFor<T>, fee_currency: FeeCurrency) -> DispatchResult {
+			let account = ensure_signed(origin)?;
+			if fee_currency == FeeCurrency::default() {
+				FeeCurrencies::<T>::remove(&account);
+			} else {
+				FeeCurrencies::<T>::insert(&account, fee_currency);
+			}
+
+			Self::deposit_event(Event::FeeCurrencyChanged(account, fee_currency));
+			Ok(())
+		}
+
+		/// Cancel the bid of the relayer for the lane.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn cancel_bid(origin: OriginFor<T>, lane: LaneId) -> DispatchResult {
+			let relayer = ensure_signed(origin)?;
+			ensure!(Self::remove_bid(&relayer, lane), Error::<T>::NoBid);
+
+			Self::deposit_event(Event::BidCancelled(relayer, lane));
+			Ok(())
+		}
+
+		/// Report that the latest delivery confirmation of the outbound lane to the bridged chain
+		/// has been invalid.
+		///
+		/// The storage proof of the inbound lane must be verified against a finalized header of
+		/// the bridged chain, that hasn't been finalized before the confirmation. If the inbound
+		/// lane has received less messages than confirmed, the bond of the confirming relayer is
+		/// slashed and the lane is halted. The transaction is free if the report is valid.
+		#[pallet::weight(T::DbWeight::get().reads_writes(6, 6))]
+		pub fn report_invalid_confirmation(
+			origin: OriginFor<T>,
+			chain: ChainId,
+			lane: LaneId,
+			at: H256,
+			storage_proof: Vec<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let confirmation =
+				LatestConfirmations::<T>::get(chain, lane).ok_or(Error::<T>::NoConfirmation)?;
+			let (block_number, received_nonce) =
+				T::BridgedInboundLanes::latest_received_nonce(chain, at, storage_proof, lane)?;
+			ensure!(block_number >= confirmation.bridged_block, Error::<T>::OutdatedLaneProof);
+			ensure!(received_nonce < confirmation.nonce, Error::<T>::ValidConfirmation);
+
+			LatestConfirmations::<T>::remove(chain, lane);
+			Self::slash_relayer(&confirmation.relayer, lane);
+			T::LaneHalt::halt_lane(lane);
+
+			Self::deposit_event(Event::InvalidConfirmationReported(
+				confirmation.relayer,
+				chain,
+				lane,
+				confirmation.nonce,
+				received_nonce,
+			));
+			Ok(Pays::No.into())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the sum of all unclaimed rewards.
+	///
+	/// Iterates over all rewards, so it must not be used in extrinsics.
+	pub fn unclaimed_rewards() -> BalanceOf<T> {
+		RelayerRewards::<T>::iter_values()
+			.fold(Zero::zero(), |total: BalanceOf<T>, reward| total.saturating_add(reward))
+	}
+
+	/// Returns true if the relayer is registered for the lane.
+	pub fn is_registered(relayer: &T::AccountId, lane: LaneId) -> bool {
+		RegisteredRelayers::<T>::contains_key(relayer, lane)
+	}
+
+	/// Returns the lowest relayer bid for the lane.
+	pub fn lowest_bid(lane: LaneId) -> Option<BalanceOf<T>> {
+		RelayerBids::<T>::get(lane).first().map(|(_, bid)| *bid)
+	}
+
+	/// Pay the message fee in the fee asset to the fee exchange account, which pays the fee to the
+	/// relayer fund account in `Currency`.
+	fn pay_fee_in_asset(
+		submitter: &T::AccountId,
+		fee: BalanceOf<T>,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), &'static str> {
+		let asset_fee = T::FeeAssetConversion::convert(fee)
+			.ok_or("The message fee can't be converted to the fee asset")?;
+		let exchange_account = T::FeeExchangeAccount::get();
+		with_transaction(|| {
+			let result = T::FeeAssets::transfer(
+				T::FeeAssetId::get(),
+				submitter,
+				&exchange_account,
+				asset_fee,
+				false,
+			)
+			.map_err(|_| "The submitter can't pay the message fee in the fee asset")
+			.and_then(|_| {
+				T::Currency::transfer(
+					&exchange_account,
+					relayer_fund_account,
+					fee,
+					ExistenceRequirement::KeepAlive,
+				)
+				.map_err(|_| "The fee exchange account can't pay the message fee")
+			});
+			match result {
+				Ok(()) => TransactionOutcome::Commit(Ok(())),
+				Err(err) => TransactionOutcome::Rollback(Err(err)),
+			}
+		})?;
+
+		Self::deposit_event(Event::FeePaidInAsset(submitter.clone(), fee, asset_fee));
+		Ok(())
+	}
+
+	/// Transfer the subsidy of the message from the subsidy account to the relayer fund account.
+	///
+	/// Returns false if nothing has been transferred, e.g. because the subsidy account can't afford
+	/// the subsidy.
+	fn pay_subsidy(subsidy: BalanceOf<T>) -> bool {
+		!subsidy.is_zero() &&
+			T::Currency::transfer(
+				&T::SubsidyAccount::get(),
+				&T::RelayerFundAccount::get(),
+				subsidy,
+				ExistenceRequirement::KeepAlive,
+			)
+			.is_ok()
+	}
+
+	/// Remove the bid of the relayer for the lane. Returns true if there has been a bid.
+	fn remove_bid(relayer: &T::AccountId, lane: LaneId) -> bool {
+		RelayerBids::<T>::mutate(lane, |bids| {
+			let bids_count = bids.len();
+			bids.retain(|(bidder, _)| bidder != relayer);
+			bids.len() != bids_count
+		})
+	}
+
+	/// Returns statistics of the outbound lane of the messages pallet instance `I`.
+	pub fn outbound_lane_stats<I: 'static>(
+		lane: LaneId,
+	) -> OutboundLaneStats<T::AccountId, BalanceOf<T>>
+	where
+		T: pallet_bridge_messages::Config<I>,
+	{
+		let chain = <T as pallet_bridge_messages::Config<I>>::BridgedChainId::get();
+		OutboundLaneStats {
+			messages_sent: OutboundLanes::<T, I>::get(lane).latest_generated_nonce,
+			..OutboundLanesStats::<T>::get(chain, lane)
+		}
+	}
+
+	/// Register the refund of the message fee to its submitter.
+	fn register_refund(
+		submitter: &T::AccountId,
+		lane: LaneId,
+		nonce: MessageNonce,
+		refund: BalanceOf<T>,
+	) {
+		if !refund.is_zero() {
+			Self::register_reward(submitter, lane, refund);
+			Self::deposit_event(Event::MessageFeeRefunded(submitter.clone(), lane, nonce, refund));
+		}
+	}
+
+	/// Refund the transaction fee of the messages delivery to the relayer.
+	///
+	/// Nothing is refunded if the refunds account can't afford the fee.
+	pub fn refund_transaction_fee(relayer: &T::AccountId, lane: LaneId, fee: BalanceOf<T>) {
+		if fee.is_zero() {
+			return
+		}
+
+		let refund = T::Currency::transfer(
+			&T::RefundsAccount::get(),
+			relayer,
+			fee,
+			ExistenceRequirement::KeepAlive,
+		);
+		if refund.is_ok() {
+			Self::deposit_event(Event::TransactionFeeRefunded(relayer.clone(), lane, fee));
+		}
+	}
+
+	/// Slash the bond of the relayer registered for the lane and deregister it.
+	///
+	/// The slashed bond is burned.
+	pub fn slash_relayer(relayer: &T::AccountId, lane: LaneId) {
+		if let Some(bond) = RegisteredRelayers::<T>::take(relayer, lane) {
+			Self::remove_bid(relayer, lane);
+			let (_, not_slashed) = T::Currency::slash_reserved(relayer, bond);
+			let slashed = bond.saturating_sub(not_slashed);
+			Self::deposit_event(Event::RelayerSlashed(relayer.clone(), lane, slashed));
+		}
+	}
+
+	fn register_reward(relayer: &T::AccountId, lane: LaneId, reward: BalanceOf<T>) {
+		if !reward.is_zero() {
+			RelayerRewards::<T>::mutate(relayer, lane, |total| {
+				*total = total.saturating_add(reward)
+			});
+		}
+	}
+}
+
+/// Subsidy that relayers are rewarded with for delivering messages of subsidized lanes.
+pub trait MessageSubsidy<Balance> {
+	/// Returns the subsidy for delivering the message with the given encoded payload over the
+	/// lane. It is zero if the lane isn't subsidized.
+	fn message_subsidy(lane: LaneId, payload: &[u8]) -> Balance;
+}
+
+impl<Balance: Zero> MessageSubsidy<Balance> for () {
+	fn message_subsidy(_lane: LaneId, _payload: &[u8]) -> Balance {
+		Zero::zero()
+	}
+}
+
+/// Message payments that register the rewards of relayers in [`Pallet`], instead of paying them.
+///
+/// Message fees are transferred to the relayer fund account like `InstantCurrencyPayments` does,
+/// unless the submitter pays them in the fee asset.
+/// The rewards are split between the relayers the same way as well: the relayer that confirms the
+/// delivery of messages gets `GetConfirmationFee` for every message delivered by other relayers.
+/// Relayers are only rewarded with the part of the fee of undispatched messages, that isn't
+/// refunded to the submitter. The `Subsidy` of delivered messages is added to their fee, if the
+/// subsidy account can afford it.
+pub struct RewardsPot<T, I, GetConfirmationFee, Subsidy = ()>(
+	PhantomData<(T, I, GetConfirmationFee, Subsidy)>,
+);
+
+/// Transfers the message fees to the relayer fund account.
+type FeePayments<T, I, GetConfirmationFee> =
+	InstantCurrencyPayments<T, I, <T as Config>::Currency, GetConfirmationFee>;
+
+impl<T, I, GetConfirmationFee, Subsidy>
+	MessageDeliveryAndDispatchPayment<T::Origin, T::AccountId, BalanceOf<T>>
+	for RewardsPot<T, I, GetConfirmationFee, Subsidy>
+where
+	T: Config + pallet_bridge_messages::Config<I, OutboundMessageFee = BalanceOf<T>>,
+	I: 'static,
+	T::Origin: SenderOrigin<T::AccountId>,
+	BalanceOf<T>: From<MessageNonce>,
+	GetConfirmationFee: Get<BalanceOf<T>>,
+	Subsidy: MessageSubsidy<BalanceOf<T>>,
+{
+	type Error = &'static str;
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &T::Origin,
+		fee: &BalanceOf<T>,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		if let Some(submitter_account) = submitter.linked_account() {
+			if !fee.is_zero() && Pallet::<T>::fee_currency(&submitter_account) == FeeCurrency::Asset
+			{
+				return Pallet::<T>::pay_fee_in_asset(
+					&submitter_account,
+					*fee,
+					relayer_fund_account,
+				)
+			}
+		}
+
+		FeePayments::<T, I, GetConfirmationFee>::pay_delivery_and_dispatch_fee(
+			submitter,
+			fee,
+			relayer_fund_account,
+		)
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		submitter: &T::AccountId,
+		fee: &BalanceOf<T>,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		FeePayments::<T, I, GetConfirmationFee>::refund_delivery_and_dispatch_fee(
+			submitter,
+			fee,
+			relayer_fund_account,
+		)
+	}
+
+	fn pay_relayers_rewards(
+		lane_id: LaneId,
+		messages_relayers: VecDeque<UnrewardedRelayer<T::AccountId>>,
+		confirmation_relayer: &T::AccountId,
+		received_range: &RangeInclusive<MessageNonce>,
+		_relayer_fund_account: &T::AccountId,
+	) {
+		// this loop is bounded by `MaxUnrewardedRelayerEntriesAtInboundLane` and
+		// `MaxUnconfirmedMessagesAtInboundLane` of the bridged chain
+		let refund_share = T::UndispatchedMessageRefund::get();
+		let mut subsidies = BalanceOf::<T>::zero();
+		let mut relayers_rewards: RelayersRewards<_, BalanceOf<T>> = RelayersRewards::new();
+		let chain = T::BridgedChainId::get();
+		let mut lane_stats = OutboundLanesStats::<T>::get(chain, lane_id);
+		for entry in messages_relayers {
+			let nonce_begin = sp_std::cmp::max(entry.messages.begin, *received_range.start());
+			let nonce_end = sp_std::cmp::min(entry.messages.end, *received_range.end());
+
+			let delivered_before = lane_stats.messages_delivered;
+			let relayer_reward = relayers_rewards.entry(entry.relayer.clone()).or_default();
+			for nonce in nonce_begin..=nonce_end {
+				let key = MessageKey { lane_id, nonce };
+				if CancelledOutboundMessages::<T, I>::contains_key(&key) {
+					continue
+				}
+				let message = match OutboundMessages::<T, I>::get(&key) {
+					Some(message) => message,
+					None => continue,
+				};
+				let mut fee = message.fee;
+				lane_stats.messages_delivered = lane_stats.messages_delivered.saturating_add(1);
+				lane_stats.fees_collected = lane_stats.fees_collected.saturating_add(fee);
+				if !is_dispatched(&entry.messages, nonce) {
+					lane_stats.failed_dispatches = lane_stats.failed_dispatches.saturating_add(1);
+					if let Some(submitter) = OutboundMessageSubmitters::<T, I>::get(&key) {
+						let refund = refund_share * fee;
+						fee = fee.saturating_sub(refund);
+						Pallet::<T>::register_refund(&submitter, lane_id, nonce, refund);
+					}
+				}
+				let subsidy = Subsidy::message_subsidy(lane_id, &message.payload);
+				if Pallet::<T>::pay_subsidy(subsidy) {
+					subsidies = subsidies.saturating_add(subsidy);
+					fee = fee.saturating_add(subsidy);
+				}
+				relayer_reward.reward = relayer_reward.reward.saturating_add(fee);
+				relayer_reward.messages += 1;
+			}
+			if lane_stats.messages_delivered != delivered_before {
+				lane_stats.last_relayer = Some(entry.relayer);
+			}
+		}
+
+		let confirmation_fee = GetConfirmationFee::get();
+		let mut confirmation_relayer_reward = BalanceOf::<T>::zero();
+		for (relayer, reward) in relayers_rewards {
+			if relayer == *confirmation_relayer {
+				confirmation_relayer_reward =
+					confirmation_relayer_reward.saturating_add(reward.reward);
+				continue
+			}
+
+			let confirmation_reward =
+				confirmation_fee.saturating_mul(reward.messages.into()).min(reward.reward);
+			confirmation_relayer_reward =
+				confirmation_relayer_reward.saturating_add(confirmation_reward);
+			Pallet::<T>::register_reward(
+				&relayer,
+				lane_id,
+				reward.reward.saturating_sub(confirmation_reward),
+			);
+		}
+		Pallet::<T>::register_reward(confirmation_relayer, lane_id, confirmation_relayer_reward);
+
+		OutboundLanesStats::<T>::insert(chain, lane_id, lane_stats);
+		if let Some(bridged_block) = T::BridgedInboundLanes::best_finalized_number(chain) {
+			LatestConfirmations::<T>::insert(
+				chain,
+				lane_id,
+				LaneConfirmation {
+					relayer: confirmation_relayer.clone(),
+					nonce: *received_range.end(),
+					bridged_block,
+				},
+			);
+		}
+
+		if !subsidies.is_zero() {
+			Pallet::<T>::deposit_event(Event::MessagesSubsidized(lane_id, subsidies));
+		}
+	}
+}
+
+/// Returns true if the delivered message has been dispatched at the bridged chain.
+///
+/// Messages without a dispatch result are considered dispatched.
+fn is_dispatched(messages: &DeliveredMessages, nonce: MessageNonce) -> bool {
+	let index = nonce.saturating_sub(messages.begin) as usize;
+	messages
+		.dispatch_results
+		.get(index)
+		.map_or(true, |dispatch_result| *dispatch_result)
+}
@@ -27,7 +27,7 @@ use beefy_primitives::{
 };
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{Contains, InstanceFilter, KeyOwnerProofSystem},
+	traits::{Contains, EnsureOneOf, EqualPrivilegeOnly, InstanceFilter, KeyOwnerProofSystem},
 	PalletId,
 };
 use frame_system::EnsureRoot;
@@ -39,14 +39,20 @@ use pallet_transaction_payment::{CurrencyAdapter, FeeDetails, RuntimeDispatchInf
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::v2::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
-	CoreState, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
-	Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement,
-	ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode, ValidationCodeHash, ValidatorId,
-	ValidatorIndex, ValidatorSignature,
+	CoreState, FeatureBits, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
+	InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData,
+	PvfCheckStatement, ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 use runtime_common::{
-	assigned_slots, auctions, crowdloan, impl_runtime_weights, impls::ToAuthor, paras_registrar,
-	paras_sudo_wrapper, slots, BlockHashCount, BlockLength, SlowAdjustingFeeUpdate,
+	assigned_slots, auctions,
+	class_fees::{self, ClassFeeAdapter},
+	crowdloan, impl_runtime_weights,
+	impls::ToAuthor,
+	paras_registrar, paras_sudo_wrapper,
+	reserved_space::{self, ReserveBlockSpace},
+	sanity, slots, AdjustmentVariable, BlockHashCount, BlockLength, MinimumMultiplier,
+	OperationalTargetBlockFullness, SlowAdjustingFeeUpdate,
 };
 use runtime_parachains::{self, runtime_api_impl::v2 as runtime_api_impl};
 use scale_info::TypeInfo;
@@ -54,11 +60,11 @@ use sp_core::{OpaqueMetadata, RuntimeDebug, H256};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{
-		AccountIdLookup, BlakeTwo256, Block as BlockT, Extrinsic as ExtrinsicT, Keccak256,
-		OpaqueKeys, SaturatedConversion, Verify,
+		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, Extrinsic as ExtrinsicT,
+		Keccak256, OpaqueKeys, SaturatedConversion, Verify,
 	},
 	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, FixedU128, KeyTypeId,
+	ApplyExtrinsicResult, FixedU128, KeyTypeId, Perbill,
 };
 use sp_staking::SessionIndex;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
@@ -74,18 +80,29 @@ use runtime_parachains::{
 	session_info as parachains_session_info, shared as parachains_shared, ump as parachains_ump,
 };
 
-use bridge_runtime_common::messages::{
-	source::estimate_message_dispatch_and_delivery_fee, MessageBridge,
-};
+use bridge_runtime_common::messages::source::minimal_message_fee;
 
+pub use bridge_config::CALL_FILTER_ALL as BRIDGE_CALL_FILTER_ALL;
+pub use bridge_messages::{
+	decode_inbound_call as decode_inbound_bridge_call, BridgeRejectObsoleteHeadersAndMessages,
+	FromRococoMessagePayload, FromWococoMessagePayload, PrioritizeRegisteredRelayers,
+	RefundRelayerForMessagesDelivery,
+};
 pub use frame_system::Call as SystemCall;
 
 /// Constant values used within the runtime.
 use rococo_runtime_constants::{currency::*, fee::*, time::*};
 
+mod bridge_assets;
+mod bridge_chunks;
+mod bridge_config;
+mod bridge_governance;
 mod bridge_messages;
+mod bridge_rate_oracle;
+mod bridge_relayers;
 mod validator_manager;
 mod weights;
+mod westend_messages;
 pub mod xcm_config;
 
 impl_runtime_weights!(rococo_runtime_constants);
@@ -142,6 +159,10 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	PrioritizeRegisteredRelayers,
+	BridgeRejectObsoleteHeadersAndMessages,
+	RefundRelayerForMessagesDelivery,
+	ReserveBlockSpace<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -233,8 +254,8 @@ construct_runtime! {
 		// It might seem strange that we add both sides of the bridge to the same runtime. We do this because this
 		// runtime as shared by both the Rococo and Wococo chains. When running as Rococo we only use
 		// `BridgeWococoGrandpa`, and vice versa.
-		BridgeRococoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Config<T>} = 40,
-		BridgeWococoGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Storage, Config<T>} = 41,
+		BridgeRococoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>, Config<T>} = 40,
+		BridgeWococoGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 41,
 
 		// Bridge messages support. The same story as with the bridge grandpa pallet above ^^^ - when we're
 		// running as Rococo we only use `BridgeWococoMessages`/`BridgeWococoMessagesDispatch`, and vice versa.
@@ -242,6 +263,61 @@ construct_runtime! {
 		BridgeWococoMessages: pallet_bridge_messages::<Instance1>::{Pallet, Call, Storage, Event<T>, Config<T>} = 44,
 		BridgeRococoMessagesDispatch: pallet_bridge_dispatch::{Pallet, Event<T>} = 45,
 		BridgeWococoMessagesDispatch: pallet_bridge_dispatch::<Instance1>::{Pallet, Event<T>} = 46,
+		BridgeConfig: bridge_config::{Pallet, Call, Storage, Event<T>, Config} = 47,
+
+		// Per-block sanity checks, only active in runtimes built with debug assertions.
+		Sanity: sanity::{Pallet, Event<T>} = 48,
+
+		// Fee multiplier of operational transactions, next to the one of `TransactionPayment`.
+		ClassFees: class_fees::{Pallet, Storage} = 49,
+
+		// Rewards of the bridge relayers, kept until they are claimed.
+		BridgeRelayers: bridge_relayers::{Pallet, Call, Storage, Event<T>} = 50,
+
+		// Block space reserved for bridge message deliveries.
+		ReservedSpace: reserved_space::{Pallet, Call, Storage, Event<T>} = 51,
+
+		// Conversion rate of the bridge, submitted by the oracle accounts.
+		BridgeRateOracle: bridge_rate_oracle::{Pallet, Call, Storage, Event<T>, ValidateUnsigned} = 52,
+
+		// BEEFY light clients of the bridged chains - an alternative to the bridge grandpa pallets
+		// above. The same story as with the bridge grandpa pallets - when we're running as Rococo we
+		// only use `BridgeWococoBeefy`, and vice versa.
+		BridgeRococoBeefy: pallet_bridge_beefy::{Pallet, Call, Storage} = 53,
+		BridgeWococoBeefy: pallet_bridge_beefy::<Instance1>::{Pallet, Call, Storage} = 54,
+
+		// Heads of the parachains of the bridged chains, verified against the finalized headers of the
+		// bridged chains. The same story as with the bridge grandpa pallets above.
+		BridgeRococoParachains: pallet_bridge_parachains::{Pallet, Call, Storage} = 55,
+		BridgeWococoParachains: pallet_bridge_parachains::<Instance1>::{Pallet, Call, Storage} = 56,
+
+		// Wrapped native tokens of the bridged chains. The same story as with the bridge grandpa
+		// pallets above.
+		BridgeRococoAssets: bridge_assets::{Pallet, Call, Storage, Event<T>} = 57,
+		BridgeWococoAssets: bridge_assets::<Instance1>::{Pallet, Call, Storage, Event<T>} = 58,
+
+		// Fungible assets, e.g. the asset that bridge message fees may be paid in.
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 59,
+
+		// Delayed dispatch of calls, e.g. of inbound bridge messages.
+		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>} = 60,
+
+		// Second bridge of Rococo, connecting it to Westend. It reuses the same building blocks as the
+		// Rococo <> Wococo bridge and is only used when running as Rococo.
+		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance2>::{Pallet, Call, Storage, Event<T>, Config<T>} = 61,
+		BridgeWestendMessages: pallet_bridge_messages::<Instance2>::{Pallet, Call, Storage, Event<T>, Config<T>} = 62,
+		BridgeWestendMessagesDispatch: pallet_bridge_dispatch::<Instance2>::{Pallet, Event<T>} = 63,
+
+		// Calls of the governance, executed at the bridged chains. The same story as with the
+		// bridge grandpa pallets above.
+		BridgeRococoGovernance: bridge_governance::{Pallet, Call, Storage, Event<T>} = 64,
+		BridgeWococoGovernance: bridge_governance::<Instance1>::{Pallet, Call, Storage, Event<T>} = 65,
+
+		// Calls of the governance of the bridged chains, that are too large for a single message.
+		BridgeChunks: bridge_chunks::{Pallet, Call, Storage, Event<T>} = 66,
+
+		// Preimages of calls, e.g. of the large calls that are dispatched by bridge messages.
+		Preimage: pallet_preimage::{Pallet, Call, Storage, Event<T>} = 67,
 
 		// A "council"
 		Collective: pallet_collective = 80,
@@ -297,9 +373,9 @@ impl frame_system::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ValidationUpgradeFrequency: BlockNumber = 2 * DAYS;
-	pub const ValidationUpgradeDelay: BlockNumber = 8 * HOURS;
-	pub const SlashPeriod: BlockNumber = 7 * DAYS;
+	pub ValidationUpgradeFrequency: BlockNumber = 2 * days();
+	pub ValidationUpgradeDelay: BlockNumber = 8 * hours();
+	pub SlashPeriod: BlockNumber = 7 * days();
 }
 
 /// Submits a transaction with the node's public and signature type. Adheres to the signed extension
@@ -337,6 +413,10 @@ where
 			frame_system::CheckNonce::<Runtime>::from(nonce),
 			frame_system::CheckWeight::<Runtime>::new(),
 			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+			PrioritizeRegisteredRelayers,
+			BridgeRejectObsoleteHeadersAndMessages,
+			RefundRelayerForMessagesDelivery,
+			ReserveBlockSpace::<Runtime>::new(),
 		);
 		let raw_payload = SignedPayload::new(call, extra)
 			.map_err(|e| {
@@ -372,6 +452,7 @@ impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
 	type PunishValidators = ();
+	type AmnestyOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -441,7 +522,7 @@ impl pallet_authority_discovery::Config for Runtime {
 }
 
 parameter_types! {
-	pub const MinimumPeriod: u64 = SLOT_DURATION / 2;
+	pub MinimumPeriod: u64 = SlotDuration::get() / 2;
 }
 impl pallet_timestamp::Config for Runtime {
 	type Moment = u64;
@@ -458,13 +539,36 @@ parameter_types! {
 }
 
 impl pallet_transaction_payment::Config for Runtime {
-	type OnChargeTransaction = CurrencyAdapter<Balances, ToAuthor<Runtime>>;
+	type OnChargeTransaction =
+		ClassFeeAdapter<Runtime, CurrencyAdapter<Balances, ToAuthor<Runtime>>>;
 	type OperationalFeeMultiplier = OperationalFeeMultiplier;
 	type WeightToFee = WeightToFee;
 	type LengthToFee = frame_support::weights::ConstantMultiplier<Balance, TransactionByteFee>;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
 }
 
+impl class_fees::Config for Runtime {
+	type TargetBlockFullness = OperationalTargetBlockFullness;
+	type AdjustmentVariable = AdjustmentVariable;
+	type MinimumMultiplier = MinimumMultiplier;
+}
+
+parameter_types! {
+	pub const ReservationEraLength: BlockNumber = 1 * DAYS;
+	pub const MaxReservedShare: Perbill = Perbill::from_percent(25);
+	pub const ReservedSpacePriorityBoost: TransactionPriority = TransactionPriority::MAX / 2;
+}
+
+impl reserved_space::Config for Runtime {
+	type Event = Event;
+	type Class = bridge_messages::ReservedSpaceClass;
+	type ClassifyCall = bridge_messages::ClassifyMessageDeliveries;
+	type ReserveOrigin = EnsureRoot<AccountId>;
+	type EraLength = ReservationEraLength;
+	type MaxReservedShare = MaxReservedShare;
+	type PriorityBoost = ReservedSpacePriorityBoost;
+}
+
 /// Special `ValidatorIdOf` implementation that is just returning the input as result.
 pub struct ValidatorIdOf;
 impl sp_runtime::traits::Convert<AccountId, Option<AccountId>> for ValidatorIdOf {
@@ -486,7 +590,7 @@ impl pallet_session::Config for Runtime {
 }
 
 parameter_types! {
-	pub const ExpectedBlockTime: Moment = MILLISECS_PER_BLOCK;
+	pub ExpectedBlockTime: Moment = SlotDuration::get();
 	pub ReportLongevity: u64 = EpochDurationInBlocks::get() as u64 * 10;
 }
 
@@ -733,31 +837,106 @@ parameter_types! {
 	///
 	/// Note that once this is hit the pallet will essentially throttle incoming requests down to one
 	/// call per block.
-	pub const MaxRequests: u32 = 4 * HOURS as u32;
+	pub MaxRequests: u32 = 4 * hours() as u32;
 
-	/// Number of headers to keep.
+	/// Maximal number of headers to keep.
 	///
 	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
-	/// week.
-	pub const HeadersToKeep: u32 = 7 * DAYS as u32;
+	/// week. The governance may shrink this window with the `set_headers_to_keep` call of the
+	/// bridge GRANDPA pallet.
+	pub HeadersToKeep: u32 = 7 * days() as u32;
 }
 
 pub type RococoGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_rococo::Rococo;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
 
-	type WeightInfo = pallet_bridge_grandpa::weights::MillauWeight<Runtime>;
+	type WeightInfo = weights::pallet_bridge_grandpa::WeightInfo<Runtime>;
 }
 
 pub type WococoGrandpaInstance = pallet_bridge_grandpa::Instance1;
 impl pallet_bridge_grandpa::Config<WococoGrandpaInstance> for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_wococo::Wococo;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
 
-	type WeightInfo = pallet_bridge_grandpa::weights::MillauWeight<Runtime>;
+	type WeightInfo = weights::pallet_bridge_grandpa::WeightInfo<Runtime>;
+}
+
+pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance2;
+impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+	type Event = Event;
+	type BridgedChain = bp_westend::Westend;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+
+	type WeightInfo = weights::pallet_bridge_grandpa::WeightInfo<Runtime>;
+}
+
+pub type RococoBeefyInstance = ();
+impl pallet_bridge_beefy::Config for Runtime {
+	type BridgedChain = bp_rococo::Rococo;
+	type LeafExtra = H256;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+}
+
+pub type WococoBeefyInstance = pallet_bridge_beefy::Instance1;
+impl pallet_bridge_beefy::Config<WococoBeefyInstance> for Runtime {
+	type BridgedChain = bp_wococo::Wococo;
+	type LeafExtra = H256;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+}
+
+/// Finality of Rococo headers, used by the Rococo <> Wococo bridge.
+///
+/// Headers are finalized by the GRANDPA light client. It may be replaced with the BEEFY light
+/// client (`BridgeRococoBeefy`), which is cheaper to keep in sync.
+pub type RococoFinality = BridgeRococoGrandpa;
+
+/// Finality of Wococo headers, used by the Rococo <> Wococo bridge.
+///
+/// Headers are finalized by the GRANDPA light client. It may be replaced with the BEEFY light
+/// client (`BridgeWococoBeefy`), which is cheaper to keep in sync.
+pub type WococoFinality = BridgeWococoGrandpa;
+
+/// Finality of Westend headers, used by the Rococo <> Westend bridge.
+pub type WestendFinality = BridgeWestendGrandpa;
+
+parameter_types! {
+	/// Name of the `paras` pallet in the `construct_runtime!` of Rococo and Wococo.
+	pub const ParasPalletName: &'static str = "Paras";
+}
+
+pub type RococoParachainsInstance = ();
+impl pallet_bridge_parachains::Config for Runtime {
+	type RelayFinality = RococoFinality;
+	type ParasPalletName = ParasPalletName;
+	type HeadsToKeep = HeadersToKeep;
+}
+
+pub type WococoParachainsInstance = pallet_bridge_parachains::Instance1;
+impl pallet_bridge_parachains::Config<WococoParachainsInstance> for Runtime {
+	type RelayFinality = WococoFinality;
+	type ParasPalletName = ParasPalletName;
+	type HeadsToKeep = HeadersToKeep;
+}
+
+parameter_types! {
+	/// Prefix of accounts that are dispatching bridged messages on behalf of source chain accounts.
+	///
+	/// `bp_rococo::derive_account_from_wococo_id` and `bp_wococo::derive_account_from_rococo_id`
+	/// are using the default prefix, so it must not be changed without updating them.
+	pub const BridgedAccountDerivationPrefix: &'static [u8] = bp_runtime::ACCOUNT_DERIVATION_PREFIX;
+	/// Signatures of messages, that are dispatched with accounts of this chain, are bound to the
+	/// lane and nonce of the message, so they can't be replayed.
+	pub const BridgedAccountOwnershipDomain: Option<&'static [u8]> =
+		Some(bp_runtime::ACCOUNT_OWNERSHIP_DOMAIN);
 }
 
 // Instance that is "deployed" at Wococo chain. Responsible for dispatching Rococo -> Wococo messages.
@@ -768,10 +947,13 @@ impl pallet_bridge_dispatch::Config<AtWococoFromRococoMessagesDispatch> for Runt
 	type Call = Call;
 	type CallFilter = frame_support::traits::Everything;
 	type EncodedCall = bridge_messages::FromRococoEncodedCall;
+	type CompatibleSpecVersions = BridgeConfig;
 	type SourceChainAccountId = bp_wococo::AccountId;
 	type TargetChainAccountPublic = sp_runtime::MultiSigner;
 	type TargetChainSignature = sp_runtime::MultiSignature;
 	type AccountIdConverter = bp_rococo::AccountIdConverter;
+	type AccountDerivationPrefix = BridgedAccountDerivationPrefix;
+	type AccountOwnershipDomain = BridgedAccountOwnershipDomain;
 }
 
 // Instance that is "deployed" at Rococo chain. Responsible for dispatching Wococo -> Rococo messages.
@@ -782,10 +964,31 @@ impl pallet_bridge_dispatch::Config<AtRococoFromWococoMessagesDispatch> for Runt
 	type Call = Call;
 	type CallFilter = frame_support::traits::Everything;
 	type EncodedCall = bridge_messages::FromWococoEncodedCall;
+	type CompatibleSpecVersions = BridgeConfig;
 	type SourceChainAccountId = bp_rococo::AccountId;
 	type TargetChainAccountPublic = sp_runtime::MultiSigner;
 	type TargetChainSignature = sp_runtime::MultiSignature;
 	type AccountIdConverter = bp_wococo::AccountIdConverter;
+	type AccountDerivationPrefix = BridgedAccountDerivationPrefix;
+	type AccountOwnershipDomain = BridgedAccountOwnershipDomain;
+}
+
+// Instance that is "deployed" at Rococo chain. Responsible for dispatching Westend -> Rococo
+// messages.
+pub type AtRococoFromWestendMessagesDispatch = pallet_bridge_dispatch::Instance2;
+impl pallet_bridge_dispatch::Config<AtRococoFromWestendMessagesDispatch> for Runtime {
+	type Event = Event;
+	type BridgeMessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
+	type Call = Call;
+	type CallFilter = frame_support::traits::Everything;
+	type EncodedCall = westend_messages::FromWestendEncodedCall;
+	type CompatibleSpecVersions = BridgeConfig;
+	type SourceChainAccountId = bp_westend::AccountId;
+	type TargetChainAccountPublic = sp_runtime::MultiSigner;
+	type TargetChainSignature = sp_runtime::MultiSignature;
+	type AccountIdConverter = bp_rococo::AccountIdConverter;
+	type AccountDerivationPrefix = BridgedAccountDerivationPrefix;
+	type AccountOwnershipDomain = BridgedAccountOwnershipDomain;
 }
 
 parameter_types! {
@@ -797,19 +1000,29 @@ parameter_types! {
 	pub const RootAccountForPayments: Option<AccountId> = None;
 	pub const RococoChainId: bp_runtime::ChainId = bp_runtime::ROCOCO_CHAIN_ID;
 	pub const WococoChainId: bp_runtime::ChainId = bp_runtime::WOCOCO_CHAIN_ID;
+	pub const WestendChainId: bp_runtime::ChainId = bp_runtime::WESTEND_CHAIN_ID;
 }
 
+/// Governance origin that administers the bridge: it is the only origin, besides root, that
+/// updates the parameters and the operating mode of the messages pallets.
+pub type BridgeAdmin = EnsureOneOf<
+	EnsureRoot<AccountId>,
+	pallet_collective::EnsureProportionAtLeast<AccountId, (), 2, 3>,
+>;
+
 // Instance that is "deployed" at Wococo chain. Responsible for sending Wococo -> Rococo messages
 // and receiving Rococo -> Wococo messages.
 pub type AtWococoWithRococoMessagesInstance = ();
 impl pallet_bridge_messages::Config<AtWococoWithRococoMessagesInstance> for Runtime {
 	type Event = Event;
 	type BridgedChainId = RococoChainId;
-	type WeightInfo = pallet_bridge_messages::weights::MillauWeight<Runtime>;
-	type Parameter = ();
+	type WeightInfo = weights::pallet_bridge_messages::WeightInfo<Runtime>;
+	type Parameter = crate::bridge_messages::BridgeMessagesParameter;
+	type AdminOrigin = BridgeAdmin;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type InboundLaneLimits = crate::bridge_messages::ConfiguredInboundLaneLimits;
 
 	type OutboundPayload = crate::bridge_messages::ToRococoMessagePayload;
 	type OutboundMessageFee = bp_wococo::Balance;
@@ -822,15 +1035,25 @@ impl pallet_bridge_messages::Config<AtWococoWithRococoMessagesInstance> for Runt
 
 	type TargetHeaderChain = crate::bridge_messages::RococoAtWococo;
 	type LaneMessageVerifier = crate::bridge_messages::ToRococoMessageVerifier;
-	type MessageDeliveryAndDispatchPayment =
-		pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
-			Runtime,
-			AtWococoWithRococoMessagesInstance,
-			pallet_balances::Pallet<Runtime>,
-			crate::bridge_messages::GetDeliveryConfirmationTransactionFee,
-		>;
-	type OnDeliveryConfirmed = ();
-	type OnMessageAccepted = ();
+	type MessageDeliveryAndDispatchPayment = crate::bridge_relayers::RewardsPot<
+		Runtime,
+		AtWococoWithRococoMessagesInstance,
+		crate::bridge_messages::GetDeliveryConfirmationTransactionFee,
+		crate::bridge_messages::SubsidizedMessageFee<
+			crate::bridge_messages::AtWococoWithRococoMessageBridge,
+		>,
+	>;
+	type MessageExpiration = crate::bridge_messages::ExpireMessagesAtThisChain;
+	type OnDeliveryConfirmed = (
+		crate::bridge_messages::OnBridgeMessagesDelivered,
+		crate::bridge_messages::TrackLaneCongestion<AtWococoWithRococoMessagesInstance>,
+		BridgeRococoAssets,
+		BridgeRococoGovernance,
+	);
+	type OnMessageAccepted = (
+		crate::bridge_messages::OnBridgeMessageAccepted,
+		crate::bridge_messages::TrackLaneCongestion<AtWococoWithRococoMessagesInstance>,
+	);
 
 	type SourceHeaderChain = crate::bridge_messages::RococoAtWococo;
 	type MessageDispatch = crate::bridge_messages::FromRococoMessageDispatch;
@@ -842,11 +1065,13 @@ pub type AtRococoWithWococoMessagesInstance = pallet_bridge_messages::Instance1;
 impl pallet_bridge_messages::Config<AtRococoWithWococoMessagesInstance> for Runtime {
 	type Event = Event;
 	type BridgedChainId = WococoChainId;
-	type WeightInfo = pallet_bridge_messages::weights::MillauWeight<Runtime>;
-	type Parameter = ();
+	type WeightInfo = weights::pallet_bridge_messages::WeightInfo<Runtime>;
+	type Parameter = crate::bridge_messages::BridgeMessagesParameter;
+	type AdminOrigin = BridgeAdmin;
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type InboundLaneLimits = crate::bridge_messages::ConfiguredInboundLaneLimits;
 
 	type OutboundPayload = crate::bridge_messages::ToWococoMessagePayload;
 	type OutboundMessageFee = bp_rococo::Balance;
@@ -859,22 +1084,344 @@ impl pallet_bridge_messages::Config<AtRococoWithWococoMessagesInstance> for Runt
 
 	type TargetHeaderChain = crate::bridge_messages::WococoAtRococo;
 	type LaneMessageVerifier = crate::bridge_messages::ToWococoMessageVerifier;
-	type MessageDeliveryAndDispatchPayment =
-		pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
-			Runtime,
-			AtRococoWithWococoMessagesInstance,
-			pallet_balances::Pallet<Runtime>,
-			crate::bridge_messages::GetDeliveryConfirmationTransactionFee,
-		>;
-	type OnDeliveryConfirmed = ();
-	type OnMessageAccepted = ();
+	type MessageDeliveryAndDispatchPayment = crate::bridge_relayers::RewardsPot<
+		Runtime,
+		AtRococoWithWococoMessagesInstance,
+		crate::bridge_messages::GetDeliveryConfirmationTransactionFee,
+		crate::bridge_messages::SubsidizedMessageFee<
+			crate::bridge_messages::AtRococoWithWococoMessageBridge,
+		>,
+	>;
+	type MessageExpiration = crate::bridge_messages::ExpireMessagesAtThisChain;
+	type OnDeliveryConfirmed = (
+		crate::bridge_messages::OnBridgeMessagesDelivered,
+		crate::bridge_messages::TrackLaneCongestion<AtRococoWithWococoMessagesInstance>,
+		BridgeWococoAssets,
+		BridgeWococoGovernance,
+	);
+	type OnMessageAccepted = (
+		crate::bridge_messages::OnBridgeMessageAccepted,
+		crate::bridge_messages::TrackLaneCongestion<AtRococoWithWococoMessagesInstance>,
+	);
 
 	type SourceHeaderChain = crate::bridge_messages::WococoAtRococo;
 	type MessageDispatch = crate::bridge_messages::FromWococoMessageDispatch;
 }
 
+// Instance that is "deployed" at Rococo chain. Responsible for sending Rococo -> Westend messages
+// and receiving Westend -> Rococo messages.
+pub type AtRococoWithWestendMessagesInstance = pallet_bridge_messages::Instance2;
+impl pallet_bridge_messages::Config<AtRococoWithWestendMessagesInstance> for Runtime {
+	type Event = Event;
+	type BridgedChainId = WestendChainId;
+	type WeightInfo = weights::pallet_bridge_messages::WeightInfo<Runtime>;
+	type Parameter = crate::bridge_messages::BridgeMessagesParameter;
+	type AdminOrigin = BridgeAdmin;
+	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
+	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type InboundLaneLimits = crate::bridge_messages::ConfiguredInboundLaneLimits;
+
+	type OutboundPayload = crate::westend_messages::ToWestendMessagePayload;
+	type OutboundMessageFee = bp_rococo::Balance;
+
+	type InboundPayload = crate::westend_messages::FromWestendMessagePayload;
+	type InboundMessageFee = bp_westend::Balance;
+	type InboundRelayer = bp_westend::AccountId;
+
+	type AccountIdConverter = bp_rococo::AccountIdConverter;
+
+	type TargetHeaderChain = crate::westend_messages::WestendAtRococo;
+	type LaneMessageVerifier = crate::westend_messages::ToWestendMessageVerifier;
+	type MessageDeliveryAndDispatchPayment = crate::bridge_relayers::RewardsPot<
+		Runtime,
+		AtRococoWithWestendMessagesInstance,
+		crate::bridge_messages::GetDeliveryConfirmationTransactionFee,
+		crate::bridge_messages::SubsidizedMessageFee<
+			crate::westend_messages::AtRococoWithWestendMessageBridge,
+		>,
+	>;
+	type MessageExpiration = crate::bridge_messages::ExpireMessagesAtThisChain;
+	type OnDeliveryConfirmed = (
+		crate::bridge_messages::OnBridgeMessagesDelivered,
+		crate::bridge_messages::TrackLaneCongestion<AtRococoWithWestendMessagesInstance>,
+	);
+	type OnMessageAccepted = (
+		crate::bridge_messages::OnBridgeMessageAccepted,
+		crate::bridge_messages::TrackLaneCongestion<AtRococoWithWestendMessagesInstance>,
+	);
+
+	type SourceHeaderChain = crate::westend_messages::WestendAtRococo;
+	type MessageDispatch = crate::westend_messages::FromWestendMessageDispatch;
+}
+
+parameter_types! {
+	pub const MaxAllowedMessageSenders: u32 = 16;
+	pub const MaxAllowedCalls: u32 = 64;
+	/// Messages may be accepted with spec versions of the last two releases.
+	pub const MaxSpecVersionWindow: bp_message_dispatch::SpecVersion = 20;
+	/// The bridge is halted if the GRANDPA authority set of the bridged chain hasn't been changed
+	/// for a week.
+	pub MaxAuthoritySetAge: BlockNumber = 7 * days();
+	/// Storage proofs of the fee multiplier of the bridged chains only prove two values, so they
+	/// are far below these limits.
+	pub const MaxBridgedStateProofSize: u32 = 16 * 1024;
+	pub const MaxBridgedStateProofNodes: u32 = 64;
+	/// Outbound lanes are congested when 80% of their pending messages limit is reached, and
+	/// stay congested until their pending messages drop to half of the limit.
+	pub const LaneCongestedThreshold: Perbill = Perbill::from_percent(80);
+	pub const LaneUncongestedThreshold: Perbill = Perbill::from_percent(50);
+	/// All bridged chains are Rococo-like chains, so they have the same maximal message size.
+	pub MaxOutboundPayloadSize: u32 =
+		bridge_runtime_common::messages::target::maximal_incoming_message_size(
+			<bp_rococo::Rococo as bp_runtime::Chain>::max_extrinsic_size(),
+		);
+}
+
+impl bridge_config::Config for Runtime {
+	type Event = Event;
+	type ConfigOrigin = EnsureRoot<AccountId>;
+	type CallFilterOrigin = EnsureOneOf<
+		EnsureRoot<AccountId>,
+		pallet_collective::EnsureProportionMoreThan<AccountId, (), 1, 2>,
+	>;
+	type MaxAllowedMessageSenders = MaxAllowedMessageSenders;
+	type MaxAllowedCalls = MaxAllowedCalls;
+	type InnerCalls = bridge_messages::WrapperCalls;
+	type MaxSpecVersionWindow = MaxSpecVersionWindow;
+	type BridgedFeeMultiplierProof = bridge_messages::BridgedFeeMultiplierFromProof;
+	type MaxBridgedStateProofSize = MaxBridgedStateProofSize;
+	type MaxBridgedStateProofNodes = MaxBridgedStateProofNodes;
+	type BridgedAuthoritySets = bridge_messages::BridgedGrandpaAuthoritySets;
+	type MaxAuthoritySetAge = MaxAuthoritySetAge;
+	type LaneCongestedThreshold = LaneCongestedThreshold;
+	type LaneUncongestedThreshold = LaneUncongestedThreshold;
+	type MaxOutboundPayloadSize = MaxOutboundPayloadSize;
+}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * DOLLARS;
+	pub const ApprovalDeposit: Balance = 1 * DOLLARS;
+	pub const AssetsStringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = deposit(1, 68);
+	pub const MetadataDepositPerByte: Balance = deposit(0, 1);
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) *
+		BlockWeights::get().max_block;
+	pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Config for Runtime {
+	type Event = Event;
+	type Origin = Origin;
+	type PalletsOrigin = OriginCaller;
+	type Call = Call;
+	type MaximumWeight = MaximumSchedulerWeight;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type MaxScheduledPerBlock = MaxScheduledPerBlock;
+	type WeightInfo = pallet_scheduler::weights::SubstrateWeight<Runtime>;
+	type OriginPrivilegeCmp = EqualPrivilegeOnly;
+	type PreimageProvider = ();
+	type NoPreimagePostponement = ();
+}
+
 parameter_types! {
-	pub const EndingPeriod: BlockNumber = 1 * HOURS;
+	pub const PreimageMaxSize: u32 = 4096 * 1024;
+	pub const PreimageBaseDeposit: Balance = deposit(2, 64);
+	pub const PreimageByteDeposit: Balance = deposit(0, 1);
+}
+
+impl pallet_preimage::Config for Runtime {
+	type WeightInfo = pallet_preimage::weights::SubstrateWeight<Runtime>;
+	type Event = Event;
+	type Currency = Balances;
+	type ManagerOrigin = EnsureRoot<AccountId>;
+	type MaxSize = PreimageMaxSize;
+	type BaseDeposit = PreimageBaseDeposit;
+	type ByteDeposit = PreimageByteDeposit;
+}
+
+parameter_types! {
+	pub RelayerFundAccount: AccountId = pallet_bridge_messages::relayer_fund_account_id::<
+		AccountId,
+		bp_rococo::AccountIdConverter,
+	>();
+	pub const RelayerBond: Balance = 100 * DOLLARS;
+	pub const UndispatchedMessageRefund: Perbill = Perbill::from_percent(50);
+	pub const RelayerRefundsPalletId: PalletId = PalletId(*b"py/brref");
+	pub RelayerRefundsAccount: AccountId = RelayerRefundsPalletId::get().into_account();
+	/// Pre-funded account that relayers are paid the subsidies of messages of subsidized lanes
+	/// from.
+	pub const RelayerSubsidyPalletId: PalletId = PalletId(*b"py/brsub");
+	pub RelayerSubsidyAccount: AccountId = RelayerSubsidyPalletId::get().into_account();
+	pub const MaxRelayerBidsPerLane: u32 = 16;
+	/// Asset of the `Assets` pallet that message fees may be paid in.
+	pub const BridgeFeeAssetId: u32 = 1;
+	/// Pre-funded account that exchanges the fee asset, paid by message submitters, for the
+	/// native tokens paid to the relayer fund.
+	pub const RelayerFeeExchangePalletId: PalletId = PalletId(*b"py/brfex");
+	pub RelayerFeeExchangeAccount: AccountId = RelayerFeeExchangePalletId::get().into_account();
+}
+
+impl bridge_relayers::Config for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type RelayerFundAccount = RelayerFundAccount;
+	type RelayerBond = RelayerBond;
+	type UndispatchedMessageRefund = UndispatchedMessageRefund;
+	type RefundsAccount = RelayerRefundsAccount;
+	type SubsidyAccount = RelayerSubsidyAccount;
+	type MaxBidsPerLane = MaxRelayerBidsPerLane;
+	type FeeAssets = Assets;
+	type FeeAssetId = BridgeFeeAssetId;
+	type FeeAssetConversion = bridge_messages::NativeToFeeAsset;
+	type FeeExchangeAccount = RelayerFeeExchangeAccount;
+	type BridgedInboundLanes = bridge_messages::BridgedInboundLanesFromProof;
+	type LaneHalt = bridge_messages::DisableMisbehavingLanes;
+}
+
+parameter_types! {
+	/// Lane that the messages of the assets bridge are sent over.
+	pub const BridgeAssetsLane: bp_messages::LaneId = *b"asst";
+	/// The assets bridge uses the same pallet account at both bridged chains.
+	pub const BridgeAssetsPalletId: PalletId = PalletId(*b"py/brast");
+	pub BridgeAssetsAccount: AccountId = BridgeAssetsPalletId::get().into_account();
+	pub BridgedRococoAssetsAccount: AccountId = bp_wococo::derive_account_from_rococo_id(
+		bp_runtime::SourceAccount::Account(BridgeAssetsAccount::get()),
+	);
+	pub BridgedWococoAssetsAccount: AccountId = bp_rococo::derive_account_from_wococo_id(
+		bp_runtime::SourceAccount::Account(BridgeAssetsAccount::get()),
+	);
+}
+
+// Instance that is "deployed" at Wococo chain. Keeps the wrapped Rococo tokens.
+pub type WrappedRococoTokensInstance = ();
+impl bridge_assets::Config<WrappedRococoTokensInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type BridgedAssetsAccount = BridgedRococoAssetsAccount;
+	type UnlockAccount = BridgeAssetsAccount;
+	type UnlockLane = BridgeAssetsLane;
+	type UnlockMessages = bridge_messages::UnlockBridgedTokens<AtWococoWithRococoMessagesInstance>;
+	type MinimumBalance = ExistentialDeposit;
+}
+
+// Instance that is "deployed" at Rococo chain. Keeps the wrapped Wococo tokens.
+pub type WrappedWococoTokensInstance = bridge_assets::Instance1;
+impl bridge_assets::Config<WrappedWococoTokensInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type BridgedAssetsAccount = BridgedWococoAssetsAccount;
+	type UnlockAccount = BridgeAssetsAccount;
+	type UnlockLane = BridgeAssetsLane;
+	type UnlockMessages = bridge_messages::UnlockBridgedTokens<AtRococoWithWococoMessagesInstance>;
+	type MinimumBalance = ExistentialDeposit;
+}
+
+parameter_types! {
+	pub const BridgeGovernanceLane: bp_messages::LaneId = *b"govn";
+}
+
+// Instance that is "deployed" at Wococo chain. Executes calls of the Wococo governance at Rococo.
+pub type RemoteRococoGovernanceInstance = ();
+impl bridge_governance::Config<RemoteRococoGovernanceInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type ProposalId = Hash;
+	type ExecuteOrigin = BridgeAdmin;
+	type Lane = BridgeGovernanceLane;
+	type RemoteCallMessages =
+		bridge_messages::SendRemoteGovernanceCalls<AtWococoWithRococoMessagesInstance>;
+}
+
+// Instance that is "deployed" at Rococo chain. Executes calls of the Rococo governance at Wococo.
+pub type RemoteWococoGovernanceInstance = bridge_governance::Instance1;
+impl bridge_governance::Config<RemoteWococoGovernanceInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type ProposalId = Hash;
+	type ExecuteOrigin = BridgeAdmin;
+	type Lane = BridgeGovernanceLane;
+	type RemoteCallMessages =
+		bridge_messages::SendRemoteGovernanceCalls<AtRococoWithWococoMessagesInstance>;
+}
+
+parameter_types! {
+	/// Messages of the governance of the bridged chains are dispatched with these accounts.
+	pub BridgedRococoRootAccount: AccountId =
+		bp_wococo::derive_account_from_rococo_id(bp_runtime::SourceAccount::Root);
+	pub BridgedWococoRootAccount: AccountId =
+		bp_rococo::derive_account_from_wococo_id(bp_runtime::SourceAccount::Root);
+	pub const MaxBridgeCallChunks: u32 = 64;
+	pub const BridgeStagedCallLifetime: BlockNumber = 1 * HOURS;
+	pub const MaxExpiringBridgeCalls: u32 = 16;
+}
+
+impl bridge_chunks::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type ChunksOrigin =
+		frame_system::EnsureSignedBy<bridge_messages::BridgedRootAccounts, AccountId>;
+	type CallFilter = bridge_messages::SourceRootCallFilter;
+	type Preimages = Preimage;
+	type MaxChunkSize =
+		frame_support::traits::ConstU32<{ bridge_messages::MAX_INBOUND_CALL_SIZE as u32 }>;
+	type MaxChunks = MaxBridgeCallChunks;
+	type MaxCallDepth =
+		frame_support::traits::ConstU32<{ bridge_messages::MAX_INBOUND_CALL_DEPTH }>;
+	type StagedCallLifetime = BridgeStagedCallLifetime;
+	type MaxExpiringCalls = MaxExpiringBridgeCalls;
+}
+
+parameter_types! {
+	pub const ConversionRateUpdateInterval: BlockNumber = 10 * MINUTES;
+	pub const MaxConversionRateOracles: u32 = 16;
+	pub const ConversionRateUnsignedPriority: TransactionPriority = TransactionPriority::max_value();
+}
+
+impl bridge_rate_oracle::Config for Runtime {
+	type Event = Event;
+	type OracleId = bridge_rate_oracle::crypto::OracleId;
+	type OracleOrigin = EnsureRoot<AccountId>;
+	type UpdateInterval = ConversionRateUpdateInterval;
+	type MaxOracles = MaxConversionRateOracles;
+	type UnsignedPriority = ConversionRateUnsignedPriority;
+}
+
+parameter_types! {
+	pub const SanityCheckSample: u32 = 64;
+}
+
+impl sanity::Config for Runtime {
+	type Event = Event;
+	type Checks = (
+		sanity::TotalIssuanceCoversSample<Runtime, SanityCheckSample>,
+		bridge_messages::RelayerFundCoversUnpaidFees,
+		bridge_messages::WrappedTokensReconcile,
+		bridge_messages::MessageLanesAreConsistent,
+	);
+}
+
+parameter_types! {
+	pub EndingPeriod: BlockNumber = 1 * hours();
 	pub const SampleLength: BlockNumber = 1;
 }
 
@@ -890,7 +1437,7 @@ impl auctions::Config for Runtime {
 }
 
 parameter_types! {
-	pub const LeasePeriod: BlockNumber = 1 * DAYS;
+	pub LeasePeriod: BlockNumber = 1 * days();
 }
 
 impl slots::Config for Runtime {
@@ -1252,6 +1799,10 @@ sp_api::impl_runtime_apis! {
 		{
 			runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn feature_bits() -> FeatureBits {
+			runtime_api_impl::feature_bits::<Runtime>()
+		}
 	}
 
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
@@ -1361,6 +1912,13 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl primitives::v2::AuditApi<Block> for Runtime {
+		fn era_audit_keys(_era: u32) -> Vec<Vec<u8>> {
+			// Rococo has no staking, only the bridge items are audited.
+			bridge_messages::audit_keys()
+		}
+	}
+
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
 		fn validator_set() -> Option<beefy_primitives::ValidatorSet<BeefyId>> {
 			Beefy::validator_set()
@@ -1405,6 +1963,11 @@ sp_api::impl_runtime_apis! {
 			let header = BridgeRococoGrandpa::best_finalized();
 			(header.number, header.hash())
 		}
+
+		fn best_finalized_with_set_id() -> (bp_rococo::BlockNumber, bp_rococo::Hash, u64) {
+			let header = BridgeRococoGrandpa::best_finalized();
+			(header.number, header.hash(), BridgeRococoGrandpa::current_authority_set_id())
+		}
 	}
 
 	impl bp_wococo::WococoFinalityApi<Block> for Runtime {
@@ -1412,17 +1975,32 @@ sp_api::impl_runtime_apis! {
 			let header = BridgeWococoGrandpa::best_finalized();
 			(header.number, header.hash())
 		}
+
+		fn best_finalized_with_set_id() -> (bp_wococo::BlockNumber, bp_wococo::Hash, u64) {
+			let header = BridgeWococoGrandpa::best_finalized();
+			(header.number, header.hash(), BridgeWococoGrandpa::current_authority_set_id())
+		}
+	}
+
+	impl bp_westend::WestendFinalityApi<Block> for Runtime {
+		fn best_finalized() -> (bp_westend::BlockNumber, bp_westend::Hash) {
+			let header = BridgeWestendGrandpa::best_finalized();
+			(header.number, header.hash())
+		}
 	}
 
 	impl bp_rococo::ToRococoOutboundLaneApi<Block, Balance, bridge_messages::ToRococoMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: bridge_messages::ToWococoMessagePayload,
 			rococo_to_wococo_conversion_rate: Option<FixedU128>,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<bridge_messages::AtWococoWithRococoMessageBridge>(
+			if BridgeConfig::is_lane_subsidized(&lane_id) {
+				return Some(0)
+			}
+			minimal_message_fee::<bridge_messages::AtWococoWithRococoMessageBridge>(
+				&lane_id,
 				&payload,
-				bridge_messages::AtWococoWithRococoMessageBridge::RELAYER_FEE_PERCENT,
 				rococo_to_wococo_conversion_rate,
 			).ok()
 		}
@@ -1432,32 +2010,40 @@ sp_api::impl_runtime_apis! {
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
 		) -> Vec<bp_messages::MessageDetails<Balance>> {
-			(begin..=end).filter_map(|nonce| {
-				let message_data = BridgeRococoMessages::outbound_message_data(lane, nonce)?;
-				let decoded_payload = bridge_messages::ToRococoMessagePayload::decode(
-					&mut &message_data.payload[..]
-				).ok()?;
-				Some(bp_messages::MessageDetails {
-					nonce,
-					dispatch_weight: decoded_payload.weight,
-					size: message_data.payload.len() as _,
-					delivery_and_dispatch_fee: message_data.fee,
-					dispatch_fee_payment: decoded_payload.dispatch_fee_payment,
-				})
-			})
-			.collect()
+			bridge_runtime_common::messages_api::outbound_message_details::<
+				Runtime,
+				AtWococoWithRococoMessagesInstance,
+				bridge_messages::AtWococoWithRococoMessageBridge,
+			>(lane, begin, end)
+		}
+
+		fn is_lane_congested(lane: bp_messages::LaneId) -> bool {
+			BridgeConfig::is_lane_congested(bp_runtime::ROCOCO_CHAIN_ID, &lane)
+		}
+
+		fn outbound_lane_stats(
+			lane: bp_messages::LaneId,
+		) -> bp_messages::OutboundLaneStats<AccountId, Balance> {
+			BridgeRelayers::outbound_lane_stats::<AtWococoWithRococoMessagesInstance>(lane)
+		}
+
+		fn lanes() -> Vec<bp_messages::LaneId> {
+			BridgeConfig::registered_lanes()
 		}
 	}
 
 	impl bp_wococo::ToWococoOutboundLaneApi<Block, Balance, bridge_messages::ToWococoMessagePayload> for Runtime {
 		fn estimate_message_delivery_and_dispatch_fee(
-			_lane_id: bp_messages::LaneId,
+			lane_id: bp_messages::LaneId,
 			payload: bridge_messages::ToWococoMessagePayload,
 			wococo_to_rococo_conversion_rate: Option<FixedU128>,
 		) -> Option<Balance> {
-			estimate_message_dispatch_and_delivery_fee::<bridge_messages::AtRococoWithWococoMessageBridge>(
+			if BridgeConfig::is_lane_subsidized(&lane_id) {
+				return Some(0)
+			}
+			minimal_message_fee::<bridge_messages::AtRococoWithWococoMessageBridge>(
+				&lane_id,
 				&payload,
-				bridge_messages::AtRococoWithWococoMessageBridge::RELAYER_FEE_PERCENT,
 				wococo_to_rococo_conversion_rate,
 			).ok()
 		}
@@ -1467,20 +2053,99 @@ sp_api::impl_runtime_apis! {
 			begin: bp_messages::MessageNonce,
 			end: bp_messages::MessageNonce,
 		) -> Vec<bp_messages::MessageDetails<Balance>> {
-			(begin..=end).filter_map(|nonce| {
-				let message_data = BridgeWococoMessages::outbound_message_data(lane, nonce)?;
-				let decoded_payload = bridge_messages::ToWococoMessagePayload::decode(
-					&mut &message_data.payload[..]
-				).ok()?;
-				Some(bp_messages::MessageDetails {
-					nonce,
-					dispatch_weight: decoded_payload.weight,
-					size: message_data.payload.len() as _,
-					delivery_and_dispatch_fee: message_data.fee,
-					dispatch_fee_payment: decoded_payload.dispatch_fee_payment,
-				})
-			})
-			.collect()
+			bridge_runtime_common::messages_api::outbound_message_details::<
+				Runtime,
+				AtRococoWithWococoMessagesInstance,
+				bridge_messages::AtRococoWithWococoMessageBridge,
+			>(lane, begin, end)
+		}
+
+		fn is_lane_congested(lane: bp_messages::LaneId) -> bool {
+			BridgeConfig::is_lane_congested(bp_runtime::WOCOCO_CHAIN_ID, &lane)
+		}
+
+		fn outbound_lane_stats(
+			lane: bp_messages::LaneId,
+		) -> bp_messages::OutboundLaneStats<AccountId, Balance> {
+			BridgeRelayers::outbound_lane_stats::<AtRococoWithWococoMessagesInstance>(lane)
+		}
+
+		fn lanes() -> Vec<bp_messages::LaneId> {
+			BridgeConfig::registered_lanes()
+		}
+	}
+
+	impl bp_rococo::FromRococoInboundLaneApi<Block> for Runtime {
+		fn inbound_lane_data(lane: bp_messages::LaneId) -> bp_messages::InboundLaneData<AccountId> {
+			bridge_runtime_common::messages_api::inbound_lane_data::<
+				Runtime,
+				AtWococoWithRococoMessagesInstance,
+			>(lane)
+		}
+
+		fn bridged_account_id(source_account: AccountId) -> AccountId {
+			BridgeRococoMessagesDispatch::bridged_account_id(
+				bp_runtime::ROCOCO_CHAIN_ID,
+				source_account,
+			)
+		}
+
+		fn check_message_payload(
+			lane: bp_messages::LaneId,
+			payload: Vec<u8>,
+		) -> bp_messages::MessagePayloadVerdict {
+			bridge_messages::check_inbound_message_payload::<
+				bridge_messages::AtWococoWithRococoMessageBridge,
+			>(&lane, &payload)
+		}
+
+		fn dry_run_message_dispatch(
+			relayer: AccountId,
+			lane: bp_messages::LaneId,
+			nonce: bp_messages::MessageNonce,
+			payload: Vec<u8>,
+		) -> bp_messages::MessageDispatchDryRun {
+			bridge_messages::dry_run_inbound_message_dispatch::<
+				bridge_messages::AtWococoWithRococoMessageBridge,
+				bridge_messages::FromRococoMessageDispatch,
+			>(&relayer, lane, nonce, &payload)
+		}
+	}
+
+	impl bp_wococo::FromWococoInboundLaneApi<Block> for Runtime {
+		fn inbound_lane_data(lane: bp_messages::LaneId) -> bp_messages::InboundLaneData<AccountId> {
+			bridge_runtime_common::messages_api::inbound_lane_data::<
+				Runtime,
+				AtRococoWithWococoMessagesInstance,
+			>(lane)
+		}
+
+		fn bridged_account_id(source_account: AccountId) -> AccountId {
+			BridgeWococoMessagesDispatch::bridged_account_id(
+				bp_runtime::WOCOCO_CHAIN_ID,
+				source_account,
+			)
+		}
+
+		fn check_message_payload(
+			lane: bp_messages::LaneId,
+			payload: Vec<u8>,
+		) -> bp_messages::MessagePayloadVerdict {
+			bridge_messages::check_inbound_message_payload::<
+				bridge_messages::AtRococoWithWococoMessageBridge,
+			>(&lane, &payload)
+		}
+
+		fn dry_run_message_dispatch(
+			relayer: AccountId,
+			lane: bp_messages::LaneId,
+			nonce: bp_messages::MessageNonce,
+			payload: Vec<u8>,
+		) -> bp_messages::MessageDispatchDryRun {
+			bridge_messages::dry_run_inbound_message_dispatch::<
+				bridge_messages::AtRococoWithWococoMessageBridge,
+				bridge_messages::FromWococoMessageDispatch,
+			>(&relayer, lane, nonce, &payload)
 		}
 	}
 
@@ -1495,10 +2160,22 @@ sp_api::impl_runtime_apis! {
 		Balance,
 	> for Runtime {
 		fn query_info(uxt: <Block as BlockT>::Extrinsic, len: u32) -> RuntimeDispatchInfo<Balance> {
-			TransactionPayment::query_info(uxt, len)
+			ClassFees::query_info(uxt, len)
 		}
 		fn query_fee_details(uxt: <Block as BlockT>::Extrinsic, len: u32) -> FeeDetails<Balance> {
-			TransactionPayment::query_fee_details(uxt, len)
+			ClassFees::query_fee_details(uxt, len)
+		}
+	}
+
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade() -> (Weight, Weight) {
+			log::info!("try-runtime::on_runtime_upgrade rococo.");
+			let weight = Executive::try_runtime_upgrade().unwrap();
+			(weight, BlockWeights::get().max_block)
+		}
+		fn execute_block_no_check(block: Block) -> Weight {
+			Executive::execute_block_no_check(block)
 		}
 	}
 
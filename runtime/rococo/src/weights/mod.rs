@@ -17,6 +17,8 @@
 
 pub mod frame_system;
 pub mod pallet_balances;
+pub mod pallet_bridge_grandpa;
+pub mod pallet_bridge_messages;
 pub mod pallet_collective;
 pub mod pallet_im_online;
 pub mod pallet_indices;
@@ -0,0 +1,41 @@
+// Copyright 2017-2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+//! Placeholder weights for `pallet_bridge_grandpa`
+//!
+//! THIS FILE WAS NOT GENERATED FOR THE ROCOCO RUNTIME. THE WEIGHTS ARE COPIED FROM THE MILLAU
+//! WEIGHTS OF THE PALLET (`pallet_bridge_grandpa::weights::MillauWeight`), BECAUSE THE PALLET
+//! BENCHMARKS ARE NOT YET RUNNING FOR THE ROCOCO RUNTIME. THE FILE MUST BE REPLACED WITH THE
+//! OUTPUT OF THE `benchmark` COMMAND FOR THE `rococo-dev` CHAIN ON THE REFERENCE HARDWARE, BEFORE
+//! THE BRIDGE IS USED WITH REAL VALUE.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Placeholder weight functions for `pallet_bridge_grandpa`, copied from Millau.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_bridge_grandpa::weights::WeightInfo for WeightInfo<T> {
+	fn submit_finality_proof(p: u32, v: u32) -> Weight {
+		(115_651_000 as Weight)
+			.saturating_add((61_465_000 as Weight).saturating_mul(p as Weight))
+			.saturating_add((3_438_000 as Weight).saturating_mul(v as Weight))
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+}
@@ -0,0 +1,105 @@
+// Copyright 2017-2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+//! Placeholder weights for `pallet_bridge_messages`
+//!
+//! THIS FILE WAS NOT GENERATED FOR THE ROCOCO RUNTIME. THE WEIGHTS ARE COPIED FROM THE MILLAU
+//! WEIGHTS OF THE PALLET (`pallet_bridge_messages::weights::MillauWeight`), BECAUSE THE PALLET
+//! BENCHMARKS ARE NOT YET RUNNING FOR THE ROCOCO RUNTIME. THE FILE MUST BE REPLACED WITH THE
+//! OUTPUT OF THE `benchmark` COMMAND FOR THE `rococo-dev` CHAIN ON THE REFERENCE HARDWARE, BEFORE
+//! THE BRIDGE IS USED WITH REAL VALUE.
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::marker::PhantomData;
+
+/// Placeholder weight functions for `pallet_bridge_messages`, copied from Millau.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_bridge_messages::weights::WeightInfo for WeightInfo<T> {
+	fn send_minimal_message_worst_case() -> Weight {
+		(117_480_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	fn send_1_kb_message_worst_case() -> Weight {
+		(128_391_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	fn send_16_kb_message_worst_case() -> Weight {
+		(149_149_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(12 as Weight))
+	}
+	fn maximal_increase_message_fee() -> Weight {
+		(6_015_058_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn increase_message_fee(i: u32) -> Weight {
+		(0 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_single_message_proof() -> Weight {
+		(179_892_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_two_messages_proof() -> Weight {
+		(291_793_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_single_message_proof_with_outbound_lane_state() -> Weight {
+		(192_191_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_single_message_proof_1_kb() -> Weight {
+		(202_104_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_single_message_proof_16_kb() -> Weight {
+		(357_144_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_single_prepaid_message_proof() -> Weight {
+		(122_648_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn receive_delivery_proof_for_single_message() -> Weight {
+		(107_631_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_delivery_proof_for_two_messages_by_single_relayer() -> Weight {
+		(113_885_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn receive_delivery_proof_for_two_messages_by_two_relayers() -> Weight {
+		(155_151_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+}
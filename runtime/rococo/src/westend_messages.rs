@@ -0,0 +1,165 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Over-bridge messaging support for Rococo <> Westend bridge.
+//!
+//! The bridge is "deployed" at Rococo only. It is built from the same blocks as the Rococo <> Wococo
+//! bridge (see the `bridge_messages` module): the lanes, the relayer rewards and the call filters
+//! are shared by both bridges.
+
+use crate::{
+	bridge_messages::{LaneFilteredMessageDispatch, RococoLikeChain},
+	Balances, Runtime,
+};
+
+use bp_messages::LaneId;
+use bp_runtime::{ROCOCO_CHAIN_ID, WESTEND_CHAIN_ID};
+use bridge_runtime_common::messages::target as messages_target;
+use frame_support::RuntimeDebug;
+
+/// Rococo chain as it is seen at Rococo by the Rococo <> Westend bridge.
+pub type RococoAtRococo = RococoLikeChain<AtRococoWithWestendMessageBridge, crate::RococoFinality>;
+
+/// Westend chain as it is seen at Rococo.
+pub type WestendAtRococo =
+	RococoLikeChain<AtRococoWithWestendMessageBridge, crate::WestendFinality>;
+
+bridge_runtime_common::impl_message_bridge! {
+	/// Message bridge that is "deployed" at Rococo chain and connecting it to Westend chain.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	pub struct AtRococoWithWestendMessageBridge {
+		this_chain_id: ROCOCO_CHAIN_ID,
+		bridged_chain_id: WESTEND_CHAIN_ID,
+		bridged_messages_pallet_name: bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME,
+		this_chain: RococoAtRococo,
+		bridged_chain: WestendAtRococo,
+		relayer_fee_percent: 10,
+		bridged_to_this_conversion_rate: crate::BridgeConfig::bridged_to_this_conversion_rate,
+		lane_relayer_fee_percent: crate::BridgeConfig::relayer_fee_percent,
+		lowest_relayer_bid: |lane: &LaneId| crate::BridgeRelayers::lowest_bid(*lane),
+		is_lane_subsidized: crate::BridgeConfig::is_lane_subsidized,
+	}
+
+	/// Message payload for Rococo -> Westend messages as it is seen at the Rococo.
+	pub type ToWestendMessagePayload = outbound_payload;
+	/// Message verifier for Rococo -> Westend messages at Rococo.
+	pub type ToWestendMessageVerifier = message_verifier;
+	/// Message payload for Westend -> Rococo messages as it is seen at Rococo.
+	pub type FromWestendMessagePayload = inbound_payload;
+}
+
+/// Encoded Rococo Call as it comes from Westend.
+pub type FromWestendEncodedCall = messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+
+/// Call-dispatch based message dispatch for Westend -> Rococo messages.
+pub type FromWestendMessageDispatch = LaneFilteredMessageDispatch<
+	AtRococoWithWestendMessageBridge,
+	messages_target::FromBridgedChainMessageDispatch<
+		AtRococoWithWestendMessageBridge,
+		Runtime,
+		Balances,
+		crate::AtRococoFromWestendMessagesDispatch,
+	>,
+	crate::AtRococoFromWestendMessagesDispatch,
+>;
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BridgeWestendGrandpa, BridgeWestendMessages, Call, Event, Origin, System};
+	use bp_message_dispatch::{CallOrigin, MessagePayload};
+	use bp_messages::{MessageData, OutboundLaneData};
+	use bp_runtime::messages::DispatchFeePayment;
+	use bridge_runtime_common::mock_bridged_chain::MockBridgedChain;
+	use frame_support::{assert_ok, traits::GenesisBuild, weights::GetDispatchInfo};
+	use parity_scale_codec::Encode;
+
+	const LANE_ID: LaneId = [0, 0, 0, 0];
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		crate::bridge_config::GenesisConfig {
+			lanes: vec![(LANE_ID, crate::BRIDGE_CALL_FILTER_ALL)],
+			source_root_call_filter: Some(crate::BRIDGE_CALL_FILTER_ALL),
+		}
+		.assimilate_storage::<Runtime>(&mut t)
+		.unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn message_from_westend_is_delivered_and_dispatched() {
+		new_test_ext().execute_with(|| {
+			let alice = bp_westend::AccountId::from([1; 32]);
+			let relayer = crate::AccountId::from([2; 32]);
+			let pallet_name = bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
+
+			let call = Call::System(frame_system::Call::remark_with_event {
+				remark: b"Hi, Rococo!".to_vec(),
+			});
+			let dispatch_weight = call.get_dispatch_info().weight;
+			let payload: FromWestendMessagePayload = MessagePayload {
+				spec_version: crate::VERSION.spec_version,
+				weight: dispatch_weight,
+				origin: CallOrigin::SourceAccount(alice),
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: call.encode(),
+			};
+
+			let mut westend = MockBridgedChain::new();
+			assert_ok!(BridgeWestendGrandpa::initialize(
+				Origin::root(),
+				westend.initialization_data(),
+			));
+			westend.insert_message(
+				pallet_name,
+				&LANE_ID,
+				1,
+				&MessageData { payload: payload.encode(), fee: 0u128 },
+			);
+			westend.insert_outbound_lane_data(
+				pallet_name,
+				&LANE_ID,
+				&OutboundLaneData { latest_generated_nonce: 1, ..Default::default() },
+			);
+			let (header, justification) = westend.finalize_header();
+			assert_ok!(BridgeWestendGrandpa::submit_finality_proof(
+				Origin::signed(relayer.clone()),
+				Box::new(header),
+				justification,
+			));
+
+			assert_ok!(BridgeWestendMessages::receive_messages_proof(
+				Origin::signed(relayer.clone()),
+				relayer.clone(),
+				westend.messages_proof(pallet_name, LANE_ID, 1..=1, true),
+				1,
+				dispatch_weight,
+			));
+			assert!(System::events().iter().any(|record| record.event ==
+				Event::BridgeWestendMessagesDispatch(
+					pallet_bridge_dispatch::Event::MessageDispatched(
+						WESTEND_CHAIN_ID,
+						(LANE_ID, 1),
+						Ok(()),
+					)
+				)));
+		});
+	}
+}
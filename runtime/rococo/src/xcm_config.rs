@@ -17,8 +17,15 @@
 //! XCM configuration for Rococo.
 
 use super::{
-	parachains_origin, AccountId, Balances, Call, Event, Origin, ParaId, Runtime, WeightToFee,
-	XcmPallet,
+	bridge_messages::{
+		AtRococoWithWococoMessageBridge, AtWococoWithRococoMessageBridge, BridgeXcmExporter,
+		BridgedLocationConvertsVia,
+	},
+	parachains_origin,
+	westend_messages::AtRococoWithWestendMessageBridge,
+	AccountId, AtRococoWithWestendMessagesInstance, AtRococoWithWococoMessagesInstance,
+	AtWococoWithRococoMessagesInstance, Balances, Call, Event, Origin, ParaId, Runtime,
+	WeightToFee, XcmPallet,
 };
 use frame_support::{
 	parameter_types,
@@ -42,8 +49,12 @@ parameter_types! {
 	pub CheckAccount: AccountId = XcmPallet::check_account();
 }
 
-pub type SovereignAccountOf =
-	(ChildParachainConvertsVia<ParaId, AccountId>, AccountId32Aliases<RococoNetwork, AccountId>);
+pub type SovereignAccountOf = (
+	ChildParachainConvertsVia<ParaId, AccountId>,
+	AccountId32Aliases<RococoNetwork, AccountId>,
+	// Messages of the bridged chain which are executed as XCM messages.
+	BridgedLocationConvertsVia,
+);
 
 pub type LocalAssetTransactor = XcmCurrencyAdapter<
 	// Use this currency:
@@ -72,8 +83,14 @@ parameter_types! {
 /// The XCM router. When we want to send an XCM message, we use this type. It amalgamates all of our
 /// individual routers.
 pub type XcmRouter = (
-	// Only one router so far - use DMP to communicate with child parachains.
+	// Use DMP to communicate with child parachains.
 	xcm_sender::ChildParachainRouter<Runtime, XcmPallet>,
+	// Export messages to the bridged chains over the lanes, selected by the `BridgeConfig` pallet.
+	// When running as Rococo we only use the exporters to Wococo and Westend, and when running as
+	// Wococo - the exporter to Rococo.
+	BridgeXcmExporter<AtWococoWithRococoMessageBridge, AtWococoWithRococoMessagesInstance>,
+	BridgeXcmExporter<AtRococoWithWococoMessageBridge, AtRococoWithWococoMessagesInstance>,
+	BridgeXcmExporter<AtRococoWithWestendMessageBridge, AtRococoWithWestendMessagesInstance>,
 );
 
 parameter_types! {
@@ -0,0 +1,201 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! End-to-end tests of the Rococo <> Wococo bridge.
+//!
+//! Both chains are running the Rococo runtime, so every test instantiates it twice, in separate
+//! test externalities. Headers, justifications and storage proofs are relayed between chains the
+//! same way the relayer does it: the proofs are generated from the real storage of the source
+//! chain and verified against the header that is finalized at the target chain.
+
+use bp_header_chain::justification::GrandpaJustification;
+use bp_message_dispatch::{CallOrigin, MessagePayload};
+use bp_messages::{DeliveredMessages, LaneId, UnrewardedRelayersState};
+use bp_rococo::{AccountId, Balance, Header};
+use bp_runtime::{messages::DispatchFeePayment, ROCOCO_CHAIN_ID};
+use bridge_runtime_common::mock_bridged_chain::MockBridgedChain;
+use frame_support::{assert_ok, weights::GetDispatchInfo};
+use parity_scale_codec::Encode;
+use rococo_runtime::{
+	Balances, BridgeConfig, BridgeConfigConfig, BridgeRelayers, BridgeRococoGrandpa,
+	BridgeRococoMessages, BridgeWococoGrandpa, BridgeWococoMessages, Call, Event, Origin, Runtime,
+	System, BRIDGE_CALL_FILTER_ALL, VERSION,
+};
+use rococo_runtime_constants::currency::UNITS;
+use sp_state_machine::Backend;
+
+const LANE_ID: LaneId = [0, 0, 0, 0];
+
+/// Chain that is running the Rococo runtime.
+struct TestChain {
+	/// Externalities with the storage of the chain.
+	ext: sp_io::TestExternalities,
+	/// Headers, justifications and storage proofs of the chain, as they're seen by the other chain.
+	bridged: MockBridgedChain,
+}
+
+impl TestChain {
+	/// Create chain with given endowed accounts and the enabled `LANE_ID` lane.
+	fn new(balances: Vec<(AccountId, Balance)>) -> Self {
+		let mut t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		pallet_balances::GenesisConfig::<Runtime> { balances }
+			.assimilate_storage(&mut t)
+			.unwrap();
+		BridgeConfigConfig {
+			lanes: vec![(LANE_ID, BRIDGE_CALL_FILTER_ALL)],
+			source_root_call_filter: Some(BRIDGE_CALL_FILTER_ALL),
+		}
+		.assimilate_storage::<Runtime>(&mut t)
+		.unwrap();
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+
+		TestChain { ext, bridged: MockBridgedChain::new() }
+	}
+
+	/// Execute closure in the externalities of the chain.
+	fn execute_with<R>(&mut self, execute: impl FnOnce() -> R) -> R {
+		self.ext.execute_with(execute)
+	}
+
+	/// Produce child of the best header, that commits to the current storage of the chain, and
+	/// its GRANDPA justification.
+	fn finalize_header(&mut self) -> (Header, GrandpaJustification<Header>) {
+		self.ext.commit_all().unwrap();
+		self.bridged.set_storage(self.ext.as_backend().pairs());
+		self.bridged.finalize_header()
+	}
+}
+
+#[test]
+fn message_is_delivered_dispatched_and_confirmed() {
+	// message sender at Rococo
+	let alice = AccountId::from([1; 32]);
+	// relayer that delivers the message to Wococo
+	let bob = AccountId::from([2; 32]);
+	// relayer that confirms delivery at Rococo
+	let charlie = AccountId::from([3; 32]);
+	let initial_balance = 1_000 * UNITS;
+	let fee = 100 * UNITS;
+
+	let mut rococo = TestChain::new(vec![(alice.clone(), initial_balance)]);
+	let mut wococo = TestChain::new(vec![]);
+	let rococo_initialization_data = rococo.bridged.initialization_data();
+	let wococo_initialization_data = wococo.bridged.initialization_data();
+	rococo.execute_with(|| {
+		assert_ok!(BridgeWococoGrandpa::initialize(Origin::root(), wococo_initialization_data));
+	});
+	wococo.execute_with(|| {
+		assert_ok!(BridgeRococoGrandpa::initialize(Origin::root(), rococo_initialization_data));
+	});
+
+	// alice sends the message at Rococo
+	let call =
+		Call::System(frame_system::Call::remark_with_event { remark: b"Hi, Wococo!".to_vec() });
+	let dispatch_weight = call.get_dispatch_info().weight;
+	let payload = MessagePayload {
+		spec_version: VERSION.spec_version,
+		weight: dispatch_weight,
+		origin: CallOrigin::SourceAccount(alice.clone()),
+		dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+		expires_at: None,
+		call: call.encode(),
+	};
+	let relayer_fund = pallet_bridge_messages::relayer_fund_account_id::<
+		AccountId,
+		bp_rococo::AccountIdConverter,
+	>();
+	rococo.execute_with(|| {
+		assert_ok!(BridgeConfig::add_message_sender(Origin::root(), LANE_ID, alice.clone()));
+		assert_ok!(BridgeWococoMessages::send_message(
+			Origin::signed(alice.clone()),
+			LANE_ID,
+			payload,
+			fee,
+		));
+		assert_eq!(Balances::free_balance(&alice), initial_balance - fee);
+		assert_eq!(Balances::free_balance(&relayer_fund), fee);
+	});
+
+	// bob relays Rococo header and the message to Wococo
+	let (rococo_header, rococo_justification) = rococo.finalize_header();
+	let messages_proof = rococo.bridged.messages_proof(
+		bp_wococo::WITH_WOCOCO_MESSAGES_PALLET_NAME,
+		LANE_ID,
+		1..=1,
+		true,
+	);
+	wococo.execute_with(|| {
+		assert_ok!(BridgeRococoGrandpa::submit_finality_proof(
+			Origin::signed(bob.clone()),
+			Box::new(rococo_header),
+			rococo_justification,
+		));
+		assert_ok!(BridgeRococoMessages::receive_messages_proof(
+			Origin::signed(bob.clone()),
+			bob.clone(),
+			messages_proof,
+			1,
+			dispatch_weight,
+		));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::BridgeRococoMessagesDispatch(
+				pallet_bridge_dispatch::Event::MessageDispatched(
+					ROCOCO_CHAIN_ID,
+					(LANE_ID, 1),
+					Ok(()),
+				)
+			)));
+	});
+
+	// charlie relays Wococo header and the delivery confirmation to Rococo
+	let (wococo_header, wococo_justification) = wococo.finalize_header();
+	let delivery_proof = wococo
+		.bridged
+		.messages_delivery_proof(bp_rococo::WITH_ROCOCO_MESSAGES_PALLET_NAME, LANE_ID);
+	rococo.execute_with(|| {
+		assert_ok!(BridgeWococoGrandpa::submit_finality_proof(
+			Origin::signed(charlie.clone()),
+			Box::new(wococo_header),
+			wococo_justification,
+		));
+		assert_ok!(BridgeWococoMessages::receive_messages_delivery_proof(
+			Origin::signed(charlie.clone()),
+			delivery_proof,
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: 1,
+				messages_in_oldest_entry: 1,
+				total_messages: 1,
+			},
+		));
+		assert!(System::events().iter().any(|record| record.event ==
+			Event::BridgeWococoMessages(pallet_bridge_messages::Event::MessagesDelivered(
+				LANE_ID,
+				DeliveredMessages::new(1, true),
+			))));
+
+		// the fee is split between the delivery and confirmation relayers, and kept in the
+		// relayer fund until the rewards are claimed
+		let bob_reward = BridgeRelayers::relayer_reward(&bob, LANE_ID);
+		let charlie_reward = BridgeRelayers::relayer_reward(&charlie, LANE_ID);
+		assert!(bob_reward > 0);
+		assert!(charlie_reward > 0);
+		assert_eq!(bob_reward + charlie_reward, fee);
+		assert_eq!(BridgeRelayers::unclaimed_rewards(), fee);
+		assert_eq!(Balances::free_balance(&relayer_fund), fee);
+	});
+}
@@ -46,7 +46,7 @@ use pallet_transaction_payment::{FeeDetails, RuntimeDispatchInfo};
 use polkadot_runtime_parachains::reward_points::RewardValidatorsWithEraPoints;
 use primitives::v2::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
-	CoreState, GroupRotationInfo, Hash as HashT, Id as ParaId, InboundDownwardMessage,
+	CoreState, FeatureBits, GroupRotationInfo, Hash as HashT, Id as ParaId, InboundDownwardMessage,
 	InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData,
 	ScrapedOnChainVotes, SessionInfo as SessionInfoData, Signature, ValidationCode,
 	ValidationCodeHash, ValidatorId, ValidatorIndex,
@@ -477,6 +477,7 @@ impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
 	type PunishValidators = ();
+	type AmnestyOrigin = frame_system::EnsureRoot<AccountId>;
 	type WeightInfo = parachains_disputes::TestWeightInfo;
 }
 
@@ -893,6 +894,16 @@ sp_api::impl_runtime_apis! {
 		{
 			runtime_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn feature_bits() -> FeatureBits {
+			runtime_impl::feature_bits::<Runtime>()
+		}
+	}
+
+	impl primitives::v2::AuditApi<Block> for Runtime {
+		fn era_audit_keys(era: u32) -> Vec<Vec<u8>> {
+			runtime_common::audit::staking_era_keys::<Runtime>(era)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
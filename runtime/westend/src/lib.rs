@@ -38,15 +38,15 @@ use pallet_transaction_payment::{CurrencyAdapter, FeeDetails, RuntimeDispatchInf
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use primitives::v2::{
 	AccountId, AccountIndex, Balance, BlockNumber, CandidateEvent, CommittedCandidateReceipt,
-	CoreState, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage, InboundHrmpMessage,
-	Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement,
-	ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode, ValidationCodeHash, ValidatorId,
-	ValidatorIndex, ValidatorSignature,
+	CoreState, FeatureBits, GroupRotationInfo, Hash, Id as ParaId, InboundDownwardMessage,
+	InboundHrmpMessage, Moment, Nonce, OccupiedCoreAssumption, PersistedValidationData,
+	PvfCheckStatement, ScrapedOnChainVotes, SessionInfo, Signature, ValidationCode,
+	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 use runtime_common::{
 	assigned_slots, auctions, crowdloan, elections::OnChainSeqPhragmen, impl_runtime_weights,
-	impls::ToAuthor, paras_registrar, paras_sudo_wrapper, slots, BlockHashCount, BlockLength,
-	CurrencyToVote, SlowAdjustingFeeUpdate,
+	impls::ToAuthor, paras_registrar, paras_sudo_wrapper, sanity, slots, BlockHashCount,
+	BlockLength, CurrencyToVote, SlowAdjustingFeeUpdate,
 };
 use runtime_parachains::{
 	configuration as parachains_configuration, disputes as parachains_disputes,
@@ -87,6 +87,7 @@ pub use sp_runtime::BuildStorage;
 use westend_runtime_constants::{currency::*, fee::*, time::*};
 
 mod bag_thresholds;
+mod rococo_messages;
 mod weights;
 pub mod xcm_config;
 
@@ -870,6 +871,18 @@ impl parachains_initializer::Config for Runtime {
 
 impl paras_sudo_wrapper::Config for Runtime {}
 
+parameter_types! {
+	pub const SanityCheckSample: u32 = 64;
+}
+
+impl sanity::Config for Runtime {
+	type Event = Event;
+	type Checks = (
+		sanity::TotalIssuanceCoversSample<Runtime, SanityCheckSample>,
+		sanity::NoStaleSchedulerAgendas<Runtime>,
+	);
+}
+
 parameter_types! {
 	pub const PermanentSlotLeasePeriodLength: u32 = 26;
 	pub const TemporarySlotLeasePeriodLength: u32 = 1;
@@ -893,6 +906,7 @@ impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
 	type PunishValidators = ();
+	type AmnestyOrigin = EnsureRoot<AccountId>;
 	type WeightInfo = weights::runtime_parachains_disputes::WeightInfo<Runtime>;
 }
 
@@ -965,6 +979,107 @@ impl auctions::Config for Runtime {
 	type WeightInfo = weights::runtime_common_auctions::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+	/// This is a pretty unscientific cap.
+	///
+	/// Note that once this is hit the pallet will essentially throttle incoming requests down to one
+	/// call per block.
+	pub const MaxRequests: u32 = 4 * HOURS as u32;
+
+	/// Maximal number of headers to keep.
+	///
+	/// Assuming the worst case of every header being finalized, we will keep headers at least for a
+	/// week.
+	pub const HeadersToKeep: u32 = 7 * DAYS as u32;
+}
+
+pub type RococoGrandpaInstance = ();
+impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
+	type BridgedChain = bp_rococo::Rococo;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+
+	type WeightInfo = pallet_bridge_grandpa::weights::MillauWeight<Runtime>;
+}
+
+parameter_types! {
+	/// Prefix of accounts that are dispatching bridged messages on behalf of source chain accounts.
+	pub const BridgedAccountDerivationPrefix: &'static [u8] = bp_runtime::ACCOUNT_DERIVATION_PREFIX;
+	/// Signatures of messages, that are dispatched with accounts of this chain, are bound to the
+	/// lane and nonce of the message, so they can't be replayed. Must match the Rococo end.
+	pub const BridgedAccountOwnershipDomain: Option<&'static [u8]> =
+		Some(bp_runtime::ACCOUNT_OWNERSHIP_DOMAIN);
+}
+
+// Instance that is "deployed" at Westend chain. Responsible for dispatching Rococo -> Westend
+// messages.
+pub type AtWestendFromRococoMessagesDispatch = ();
+impl pallet_bridge_dispatch::Config for Runtime {
+	type Event = Event;
+	type BridgeMessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
+	type Call = Call;
+	type CallFilter = frame_support::traits::Everything;
+	type EncodedCall = rococo_messages::FromRococoEncodedCall;
+	type CompatibleSpecVersions = frame_support::traits::Nothing;
+	type SourceChainAccountId = bp_rococo::AccountId;
+	type TargetChainAccountPublic = sp_runtime::MultiSigner;
+	type TargetChainSignature = sp_runtime::MultiSignature;
+	type AccountIdConverter = bp_westend::AccountIdConverter;
+	type AccountDerivationPrefix = BridgedAccountDerivationPrefix;
+	type AccountOwnershipDomain = BridgedAccountOwnershipDomain;
+}
+
+parameter_types! {
+	pub const MaxMessagesToPruneAtOnce: bp_messages::MessageNonce = 8;
+	pub const MaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
+		bp_westend::MAX_UNREWARDED_RELAYERS_IN_CONFIRMATION_TX;
+	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
+		bp_westend::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX;
+	pub const RococoChainId: bp_runtime::ChainId = bp_runtime::ROCOCO_CHAIN_ID;
+}
+
+// Instance that is "deployed" at Westend chain. Responsible for sending Westend -> Rococo messages
+// and receiving Rococo -> Westend messages.
+pub type AtWestendWithRococoMessagesInstance = ();
+impl pallet_bridge_messages::Config for Runtime {
+	type Event = Event;
+	type BridgedChainId = RococoChainId;
+	type WeightInfo = pallet_bridge_messages::weights::MillauWeight<Runtime>;
+	type Parameter = rococo_messages::WestendToRococoMessagesParameter;
+	type AdminOrigin =
+		pallet_bridge_messages::EnsureOwnerOrRoot<Runtime, AtWestendWithRococoMessagesInstance>;
+	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
+	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
+	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type InboundLaneLimits = ();
+
+	type OutboundPayload = rococo_messages::ToRococoMessagePayload;
+	type OutboundMessageFee = Balance;
+
+	type InboundPayload = rococo_messages::FromRococoMessagePayload;
+	type InboundMessageFee = bp_rococo::Balance;
+	type InboundRelayer = bp_rococo::AccountId;
+
+	type AccountIdConverter = bp_westend::AccountIdConverter;
+
+	type TargetHeaderChain = rococo_messages::Rococo;
+	type LaneMessageVerifier = rococo_messages::ToRococoMessageVerifier;
+	type MessageDeliveryAndDispatchPayment =
+		pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+			Runtime,
+			AtWestendWithRococoMessagesInstance,
+			Balances,
+			rococo_messages::GetDeliveryConfirmationTransactionFee,
+		>;
+	type MessageExpiration = ();
+	type OnMessageAccepted = ();
+	type OnDeliveryConfirmed = ();
+
+	type SourceHeaderChain = rococo_messages::Rococo;
+	type MessageDispatch = rococo_messages::FromRococoMessageDispatch;
+}
+
 construct_runtime! {
 	pub enum Runtime where
 		Block = Block,
@@ -1027,6 +1142,9 @@ construct_runtime! {
 		// Provides a semi-sorted list of nominators for staking.
 		BagsList: pallet_bags_list::{Pallet, Call, Storage, Event<T>} = 25,
 
+		// Per-block sanity checks, only active in runtimes built with debug assertions.
+		Sanity: sanity::{Pallet, Event<T>} = 29,
+
 		// Parachains pallets. Start indices at 40 to leave room.
 		ParachainsOrigin: parachains_origin::{Pallet, Origin} = 41,
 		Configuration: parachains_configuration::{Pallet, Call, Storage, Config<T>} = 42,
@@ -1050,6 +1168,11 @@ construct_runtime! {
 		Crowdloan: crowdloan::{Pallet, Call, Storage, Event<T>} = 64,
 		AssignedSlots: assigned_slots::{Pallet, Call, Storage, Event<T>} = 65,
 
+		// Staging bridge with Rococo. The Rococo end is the Rococo <> Westend bridge of Rococo.
+		BridgeRococoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>, Config<T>} = 70,
+		BridgeRococoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, Config<T>} = 71,
+		BridgeRococoMessagesDispatch: pallet_bridge_dispatch::{Pallet, Event<T>} = 72,
+
 		// Pallet for sending XCM.
 		XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 99,
 	}
@@ -1313,6 +1436,16 @@ sp_api::impl_runtime_apis! {
 		{
 			parachains_runtime_api_impl::validation_code_hash::<Runtime>(para_id, assumption)
 		}
+
+		fn feature_bits() -> FeatureBits {
+			parachains_runtime_api_impl::feature_bits::<Runtime>()
+		}
+	}
+
+	impl primitives::v2::AuditApi<Block> for Runtime {
+		fn era_audit_keys(era: u32) -> Vec<Vec<u8>> {
+			runtime_common::audit::staking_era_keys::<Runtime>(era)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
@@ -1347,6 +1480,18 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl bp_rococo::RococoFinalityApi<Block> for Runtime {
+		fn best_finalized() -> (bp_rococo::BlockNumber, bp_rococo::Hash) {
+			let header = BridgeRococoGrandpa::best_finalized();
+			(header.number, header.hash())
+		}
+
+		fn best_finalized_with_set_id() -> (bp_rococo::BlockNumber, bp_rococo::Hash, u64) {
+			let header = BridgeRococoGrandpa::best_finalized();
+			(header.number, header.hash(), BridgeRococoGrandpa::current_authority_set_id())
+		}
+	}
+
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
 		fn grandpa_authorities() -> Vec<(GrandpaId, u64)> {
 			Grandpa::grandpa_authorities()
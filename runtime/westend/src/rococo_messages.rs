@@ -0,0 +1,429 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Over-bridge messaging support for Westend <> Rococo bridge.
+//!
+//! This is the Westend end of the staging bridge. The Rococo end is configured by the Rococo
+//! runtime (see its `westend_messages` module). Unlike Rococo, Westend doesn't have the bridge
+//! configuration and relayers pallets, so the bridge is kept as simple as possible: relayers are
+//! paid instantly and messages are only accepted from signed origins.
+
+use crate::{Balances, Runtime};
+
+use bp_messages::{
+	source_chain::{SenderOrigin, TargetHeaderChain},
+	target_chain::{ProvedMessages, SourceHeaderChain},
+	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
+};
+use bp_runtime::{Chain, ROCOCO_CHAIN_ID, WESTEND_CHAIN_ID};
+use bridge_runtime_common::messages::{
+	self, source as messages_source, target as messages_target, MessageTransaction,
+	ThisChainWithMessages,
+};
+use frame_support::{
+	parameter_types,
+	traits::Get,
+	weights::{DispatchClass, Weight, WeightToFeePolynomial},
+	RuntimeDebug,
+};
+use pallet_bridge_messages::WeightInfoExt;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::{convert::TryFrom, ops::RangeInclusive};
+
+/// Lane that is used by the Westend <> Rococo bridge.
+pub const ROCOCO_LANE: LaneId = [0, 0, 0, 0];
+
+/// Initial value of `RococoToWestendConversionRate` parameter.
+pub const INITIAL_ROCOCO_TO_WESTEND_CONVERSION_RATE: FixedU128 =
+	FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `RococoFeeMultiplier` parameter.
+pub const INITIAL_ROCOCO_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+
+parameter_types! {
+	/// Rococo to Westend conversion rate. Initially we treat both tokens as equal.
+	pub storage RococoToWestendConversionRate: FixedU128 =
+		INITIAL_ROCOCO_TO_WESTEND_CONVERSION_RATE;
+	/// Fee multiplier value at Rococo chain.
+	pub storage RococoFeeMultiplier: FixedU128 = INITIAL_ROCOCO_FEE_MULTIPLIER;
+}
+
+bridge_runtime_common::impl_message_bridge! {
+	/// Message bridge that is "deployed" at Westend chain and connecting it to Rococo chain.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	pub struct AtWestendWithRococoMessageBridge {
+		this_chain_id: WESTEND_CHAIN_ID,
+		bridged_chain_id: ROCOCO_CHAIN_ID,
+		bridged_messages_pallet_name: bp_westend::WITH_WESTEND_MESSAGES_PALLET_NAME,
+		this_chain: Westend,
+		bridged_chain: Rococo,
+		relayer_fee_percent: 10,
+		bridged_to_this_conversion_rate: RococoToWestendConversionRate::get,
+		lane_relayer_fee_percent: |_: &LaneId| None,
+		lowest_relayer_bid: |_: &LaneId| None,
+		is_lane_subsidized: |_: &LaneId| false,
+	}
+
+	/// Message payload for Westend -> Rococo messages as it is seen at Westend.
+	pub type ToRococoMessagePayload = outbound_payload;
+	/// Message verifier for Westend -> Rococo messages at Westend.
+	pub type ToRococoMessageVerifier = message_verifier;
+	/// Message payload for Rococo -> Westend messages as it is seen at Westend.
+	pub type FromRococoMessagePayload = inbound_payload;
+}
+
+/// Encoded Westend Call as it comes from Rococo.
+pub type FromRococoEncodedCall = messages_target::FromBridgedChainEncodedMessageCall<crate::Call>;
+
+/// Messages proof for Rococo -> Westend messages.
+pub type FromRococoMessagesProof = messages_target::FromBridgedChainMessagesProof<bp_rococo::Hash>;
+
+/// Messages delivery proof for Westend -> Rococo messages.
+pub type ToRococoMessagesDeliveryProof =
+	messages_source::FromBridgedChainMessagesDeliveryProof<bp_rococo::Hash>;
+
+/// Call-dispatch based message dispatch for Rococo -> Westend messages.
+pub type FromRococoMessageDispatch = messages_target::FromBridgedChainMessageDispatch<
+	AtWestendWithRococoMessageBridge,
+	Runtime,
+	Balances,
+	crate::AtWestendFromRococoMessagesDispatch,
+>;
+
+/// Westend chain from message lane point of view.
+#[derive(RuntimeDebug, Clone, Copy)]
+pub struct Westend;
+
+impl messages::ChainWithMessages for Westend {
+	type Hash = crate::Hash;
+	type AccountId = crate::AccountId;
+	type Signer = primitives::v2::AccountPublic;
+	type Signature = crate::Signature;
+	type Weight = Weight;
+	type Balance = crate::Balance;
+}
+
+impl messages::ThisChainWithMessages for Westend {
+	type Origin = crate::Origin;
+	type Call = crate::Call;
+
+	fn is_message_accepted(send_origin: &Self::Origin, lane: &LaneId) -> bool {
+		*lane == ROCOCO_LANE && send_origin.linked_account().is_some()
+	}
+
+	fn maximal_pending_messages_at_outbound_lane(_lane: &LaneId) -> MessageNonce {
+		bp_westend::MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX
+	}
+
+	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
+		let inbound_data_size = InboundLaneData::<crate::AccountId>::encoded_size_hint(
+			bp_westend::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE,
+			1,
+			1,
+		)
+		.unwrap_or(u32::MAX);
+
+		MessageTransaction {
+			dispatch_weight: bp_westend::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT,
+			size: inbound_data_size
+				.saturating_add(bp_rococo::EXTRA_STORAGE_PROOF_SIZE)
+				.saturating_add(bp_westend::TX_EXTRA_BYTES),
+			proof_size: inbound_data_size.saturating_add(bp_rococo::EXTRA_STORAGE_PROOF_SIZE),
+		}
+	}
+
+	fn transaction_payment(transaction: MessageTransaction<Weight>) -> crate::Balance {
+		// current fee multiplier is used here
+		messages::transaction_payment(
+			crate::BlockWeights::get().get(DispatchClass::Normal).base_extrinsic,
+			crate::TransactionByteFee::get(),
+			pallet_transaction_payment::Pallet::<Runtime>::next_fee_multiplier(),
+			|weight| crate::WeightToFee::calc(&weight),
+			|proof_size| {
+				type Weights = <Runtime as pallet_bridge_messages::Config>::WeightInfo;
+				crate::WeightToFee::calc(&Weights::storage_proof_size_overhead(proof_size))
+			},
+			transaction,
+		)
+	}
+}
+
+/// Rococo chain from message lane point of view.
+#[derive(RuntimeDebug, Clone, Copy)]
+pub struct Rococo;
+
+impl messages::ChainWithMessages for Rococo {
+	type Hash = bp_rococo::Hash;
+	type AccountId = bp_rococo::AccountId;
+	type Signer = bp_rococo::AccountPublic;
+	type Signature = bp_rococo::Signature;
+	type Weight = Weight;
+	type Balance = bp_rococo::Balance;
+}
+
+impl messages::BridgedChainWithMessages for Rococo {
+	fn maximal_extrinsic_size() -> u32 {
+		bp_rococo::Rococo::max_extrinsic_size()
+	}
+
+	fn message_weight_limits(_message_payload: &[u8]) -> RangeInclusive<Weight> {
+		// we don't want to relay too large messages + keep reserve for future upgrades
+		let upper_limit = messages_target::maximal_incoming_message_dispatch_weight(
+			bp_rococo::Rococo::max_extrinsic_weight(),
+		);
+
+		// we're charging for payload bytes in `Rococo::transaction_payment` function
+		//
+		// this bridge may be used to deliver all kind of messages, so we're not making any
+		// assumptions about minimal dispatch weight here
+
+		0..=upper_limit
+	}
+
+	fn estimate_delivery_transaction(
+		message_payload: &[u8],
+		include_pay_dispatch_fee_cost: bool,
+		message_dispatch_weight: Weight,
+	) -> MessageTransaction<Weight> {
+		let message_payload_len = u32::try_from(message_payload.len()).unwrap_or(u32::MAX);
+
+		// payload bytes are charged for in `Rococo::transaction_payment`, because they are part of
+		// the proof
+		MessageTransaction {
+			dispatch_weight: bp_rococo::DEFAULT_MESSAGE_DELIVERY_TX_WEIGHT
+				.saturating_sub(if include_pay_dispatch_fee_cost {
+					0
+				} else {
+					bp_rococo::PAY_INBOUND_DISPATCH_FEE_WEIGHT
+				})
+				.saturating_add(message_dispatch_weight),
+			size: message_payload_len
+				.saturating_add(bp_westend::EXTRA_STORAGE_PROOF_SIZE)
+				.saturating_add(bp_rococo::TX_EXTRA_BYTES),
+			proof_size: message_payload_len.saturating_add(bp_westend::EXTRA_STORAGE_PROOF_SIZE),
+		}
+	}
+
+	fn transaction_payment(transaction: MessageTransaction<Weight>) -> bp_rococo::Balance {
+		// we don't have a direct access to the value of multiplier at Rococo chain
+		// => it is a messages module parameter
+		messages::transaction_payment(
+			bp_rococo::BlockWeights::get().get(DispatchClass::Normal).base_extrinsic,
+			bp_rococo::TRANSACTION_BYTE_FEE,
+			RococoFeeMultiplier::get(),
+			|weight| bp_rococo::WeightToFee::calc(&weight),
+			|proof_size| {
+				bp_rococo::WeightToFee::calc(
+					&bp_rococo::ADDITIONAL_MESSAGE_BYTE_DELIVERY_WEIGHT
+						.saturating_mul(proof_size.into()),
+				)
+			},
+			transaction,
+		)
+	}
+}
+
+impl TargetHeaderChain<ToRococoMessagePayload, crate::AccountId> for Rococo {
+	type Error = &'static str;
+	// The proof is:
+	// - hash of the header this proof has been created with;
+	// - the storage proof or one or several keys;
+	// - id of the lane we prove state of.
+	type MessagesDeliveryProof = ToRococoMessagesDeliveryProof;
+
+	fn verify_message(payload: &ToRococoMessagePayload) -> Result<(), Self::Error> {
+		messages_source::verify_chain_message::<AtWestendWithRococoMessageBridge>(payload)
+	}
+
+	fn verify_messages_delivery_proof(
+		proof: Self::MessagesDeliveryProof,
+	) -> Result<(LaneId, InboundLaneData<crate::AccountId>), Self::Error> {
+		messages_source::verify_messages_delivery_proof::<
+			AtWestendWithRococoMessageBridge,
+			crate::BridgeRococoGrandpa,
+		>(proof)
+	}
+}
+
+impl SourceHeaderChain<bp_rococo::Balance> for Rococo {
+	type Error = &'static str;
+	// The proof is:
+	// - hash of the header this proof has been created with;
+	// - the storage proof or one or several keys;
+	// - id of the lane we prove messages for;
+	// - inclusive range of messages nonces that are proved.
+	type MessagesProof = FromRococoMessagesProof;
+
+	fn verify_messages_proof(
+		proof: Self::MessagesProof,
+		messages_count: u32,
+	) -> Result<ProvedMessages<Message<bp_rococo::Balance>>, Self::Error> {
+		messages_target::verify_messages_proof::<
+			AtWestendWithRococoMessageBridge,
+			crate::BridgeRococoGrandpa,
+		>(proof, messages_count)
+	}
+}
+
+impl SenderOrigin<crate::AccountId> for crate::Origin {
+	fn linked_account(&self) -> Option<crate::AccountId> {
+		match self.caller {
+			crate::OriginCaller::system(frame_system::RawOrigin::Signed(ref submitter)) =>
+				Some(submitter.clone()),
+			_ => None,
+		}
+	}
+}
+
+/// The cost of delivery confirmation transaction.
+pub struct GetDeliveryConfirmationTransactionFee;
+
+impl Get<crate::Balance> for GetDeliveryConfirmationTransactionFee {
+	fn get() -> crate::Balance {
+		<Westend as ThisChainWithMessages>::transaction_payment(
+			Westend::estimate_delivery_confirmation_transaction(),
+		)
+	}
+}
+
+/// Westend -> Rococo messages pallet parameters.
+#[derive(RuntimeDebug, Clone, Encode, Decode, PartialEq, Eq, TypeInfo)]
+pub enum WestendToRococoMessagesParameter {
+	/// The conversion formula we use is: `WestendTokens = RococoTokens * conversion_rate`.
+	RococoToWestendConversionRate(FixedU128),
+	/// Fee multiplier at the Rococo chain.
+	RococoFeeMultiplier(FixedU128),
+}
+
+impl MessagesParameter for WestendToRococoMessagesParameter {
+	fn save(&self) {
+		match *self {
+			WestendToRococoMessagesParameter::RococoToWestendConversionRate(
+				ref conversion_rate,
+			) => RococoToWestendConversionRate::set(conversion_rate),
+			WestendToRococoMessagesParameter::RococoFeeMultiplier(ref fee_multiplier) =>
+				RococoFeeMultiplier::set(fee_multiplier),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{BridgeRococoGrandpa, BridgeRococoMessages, Call, Event, Origin, System};
+	use bp_message_dispatch::{CallOrigin, MessagePayload};
+	use bp_messages::{MessageData, OutboundLaneData};
+	use bp_runtime::messages::DispatchFeePayment;
+	use bridge_runtime_common::mock_bridged_chain::MockBridgedChain;
+	use frame_support::{assert_ok, weights::GetDispatchInfo};
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default().build_storage::<Runtime>().unwrap();
+		let mut ext: sp_io::TestExternalities = t.into();
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	#[test]
+	fn message_from_rococo_is_delivered_and_dispatched() {
+		new_test_ext().execute_with(|| {
+			let alice = bp_rococo::AccountId::from([1; 32]);
+			let relayer = crate::AccountId::from([2; 32]);
+			let pallet_name = bp_westend::WITH_WESTEND_MESSAGES_PALLET_NAME;
+
+			let call = Call::System(frame_system::Call::remark_with_event {
+				remark: b"Hi, Westend!".to_vec(),
+			});
+			let dispatch_weight = call.get_dispatch_info().weight;
+			let payload: FromRococoMessagePayload = MessagePayload {
+				spec_version: crate::VERSION.spec_version,
+				weight: dispatch_weight,
+				origin: CallOrigin::SourceAccount(alice),
+				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				expires_at: None,
+				call: call.encode(),
+			};
+
+			let mut rococo = MockBridgedChain::new();
+			assert_ok!(BridgeRococoGrandpa::initialize(
+				Origin::root(),
+				rococo.initialization_data(),
+			));
+			rococo.insert_message(
+				pallet_name,
+				&ROCOCO_LANE,
+				1,
+				&MessageData { payload: payload.encode(), fee: 0u128 },
+			);
+			rococo.insert_outbound_lane_data(
+				pallet_name,
+				&ROCOCO_LANE,
+				&OutboundLaneData { latest_generated_nonce: 1, ..Default::default() },
+			);
+			let (header, justification) = rococo.finalize_header();
+			assert_ok!(BridgeRococoGrandpa::submit_finality_proof(
+				Origin::signed(relayer.clone()),
+				Box::new(header),
+				justification,
+			));
+
+			assert_ok!(BridgeRococoMessages::receive_messages_proof(
+				Origin::signed(relayer.clone()),
+				relayer.clone(),
+				rococo.messages_proof(pallet_name, ROCOCO_LANE, 1..=1, true),
+				1,
+				dispatch_weight,
+			));
+			assert!(System::events().iter().any(|record| record.event ==
+				Event::BridgeRococoMessagesDispatch(
+					pallet_bridge_dispatch::Event::MessageDispatched(
+						ROCOCO_CHAIN_ID,
+						(ROCOCO_LANE, 1),
+						Ok(()),
+					)
+				)));
+		});
+	}
+
+	#[test]
+	fn delivery_and_confirmation_fees_grow_with_proof_size() {
+		new_test_ext().execute_with(|| {
+			let transaction = |proof_size| MessageTransaction {
+				dispatch_weight: 1_000_000_000,
+				size: 100,
+				proof_size,
+			};
+			assert!(
+				Westend::transaction_payment(transaction(16 * 1024)) >
+					Westend::transaction_payment(transaction(1_024))
+			);
+			let delivery_payment =
+				<Rococo as messages::BridgedChainWithMessages>::transaction_payment;
+			assert!(
+				delivery_payment(transaction(16 * 1024)) > delivery_payment(transaction(1_024))
+			);
+		});
+	}
+
+	#[test]
+	fn messages_are_only_accepted_from_signed_origins_at_rococo_lane() {
+		let alice = crate::AccountId::from([1; 32]);
+		assert!(Westend::is_message_accepted(&Origin::signed(alice.clone()), &ROCOCO_LANE));
+		assert!(!Westend::is_message_accepted(&Origin::signed(alice), &[0, 0, 0, 1]));
+		assert!(!Westend::is_message_accepted(&Origin::root(), &ROCOCO_LANE));
+	}
+}